@@ -1,5 +1,12 @@
 use ::criterion::{Criterion, criterion_group, criterion_main};
-use ::weather_providers::{Provider, create_provider};
+use ::weather_providers::{
+    FailKind, MockFaults, Provider, ProviderOptions, Result, WeatherInfo, WeatherProvider,
+    create_provider, create_provider_with,
+};
+use ::wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
 
 fn bench_create_provider(c: &mut Criterion) {
     c.bench_function("create_provider_mock", |b| {
@@ -17,9 +24,100 @@ fn bench_get_weather_mock(c: &mut Criterion) {
 
     c.bench_function("get_weather_mock", |b| {
         b.to_async(&rt)
-            .iter(|| async { provider.get_weather(Some("mock-key"), "London", None).await })
+            .iter(|| async { provider.get_weather(Some("mock-key"), "London").await })
     });
 }
 
-criterion_group!(benches, bench_create_provider, bench_get_weather_mock);
+/// Compares requests through one pooled client, as the providers hold, with a new
+/// connection per request, as `reqwest::get` makes.
+fn bench_http_pooling(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/current.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"location": {"name": "London", "country": "UK"},
+                    "current": {"temp_f": 50.0, "humidity": 80}}"#,
+            ))
+            .mount(&server)
+            .await;
+        server
+    });
+    let url = format!("{}/v1/current.json", server.uri());
+
+    let pooled = reqwest::Client::builder()
+        .pool_max_idle_per_host(5)
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("http_get");
+    group.bench_function("pooled", |b| {
+        b.to_async(&rt)
+            .iter(|| async { pooled.get(&url).send().await.unwrap().bytes().await })
+    });
+    group.bench_function("unpooled", |b| {
+        b.to_async(&rt)
+            .iter(|| async { reqwest::get(&url).await.unwrap().bytes().await })
+    });
+    group.finish();
+}
+
+/// Retries a request up to `attempts` times, as long as its error is retryable.
+async fn with_retry(provider: &dyn WeatherProvider, attempts: usize) -> Result<WeatherInfo> {
+    let mut result = provider.get_weather(None, "London").await;
+    for _ in 1..attempts {
+        match &result {
+            Err(e) if e.is_retryable() => {}
+            _ => break,
+        }
+        result = provider.get_weather(None, "London").await;
+    }
+    result
+}
+
+/// Measures the cost of recovering from failures, with 20% of the mock requests failing
+/// (seeded, so every run fails the same requests).
+fn bench_fault_injection(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let options = ProviderOptions {
+        mock_faults: Some(MockFaults {
+            fail_rate: 0.2,
+            fail_kind: FailKind::ServerError,
+            seed: 42,
+            ..MockFaults::default()
+        }),
+        ..ProviderOptions::default()
+    };
+    let healthy = create_provider(Provider::Mock);
+    let flaky = create_provider_with(Provider::Mock, &options);
+
+    let mut group = c.benchmark_group("fault_injection");
+    group.bench_function("baseline", |b| {
+        b.to_async(&rt)
+            .iter(|| async { with_retry(healthy.as_ref(), 1).await })
+    });
+    group.bench_function("retry_20_percent", |b| {
+        b.to_async(&rt)
+            .iter(|| async { with_retry(flaky.as_ref(), 3).await })
+    });
+    group.bench_function("failover_20_percent", |b| {
+        b.to_async(&rt).iter(|| async {
+            match flaky.get_weather(None, "London").await {
+                Ok(info) => Ok(info),
+                Err(e) if e.is_retryable() => healthy.get_weather(None, "London").await,
+                Err(e) => Err(e),
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_create_provider,
+    bench_get_weather_mock,
+    bench_http_pooling,
+    bench_fault_injection
+);
 criterion_main!(benches);
@@ -0,0 +1,1008 @@
+//! # World Cities
+//!
+//! A bundled list of about a thousand major world cities, used as a source of location
+//! completions when no alias or history entry matches.
+
+/// Major world cities as "City, CC" (ISO 3166-1 alpha-2 country code), sorted by name.
+pub static WORLD_CITIES: &[&str] = &[
+    "Aalborg, DK",
+    "Aarhus, DK",
+    "Aba, NG",
+    "Abha, SA",
+    "Abidjan, CI",
+    "Abu Dhabi, AE",
+    "Abuja, NG",
+    "Accra, GH",
+    "Adana, TR",
+    "Addis Ababa, ET",
+    "Adelaide, AU",
+    "Aden, YE",
+    "Agadez, NE",
+    "Agadir, MA",
+    "Aguascalientes, MX",
+    "Ahmedabad, IN",
+    "Ahvaz, IR",
+    "Ajman, AE",
+    "Aktau, KZ",
+    "Aktobe, KZ",
+    "Akureyri, IS",
+    "Al Ain, AE",
+    "Aleppo, SY",
+    "Alesund, NO",
+    "Alexandria, EG",
+    "Algiers, DZ",
+    "Alicante, ES",
+    "Almaty, KZ",
+    "Almere, NL",
+    "Ambato, EC",
+    "Amman, JO",
+    "Amsterdam, NL",
+    "Andijan, UZ",
+    "Andorra la Vella, AD",
+    "Ankara, TR",
+    "Annaba, DZ",
+    "Antalya, TR",
+    "Antananarivo, MG",
+    "Antigua Guatemala, GT",
+    "Antipolo, PH",
+    "Antofagasta, CL",
+    "Antwerp, BE",
+    "Apia, WS",
+    "Aqaba, JO",
+    "Arad, RO",
+    "Arequipa, PE",
+    "Arica, CL",
+    "Arnhem, NL",
+    "Arusha, TZ",
+    "Ashdod, IL",
+    "Ashgabat, TM",
+    "Asmara, ER",
+    "Astana, KZ",
+    "Asuncion, PY",
+    "Asyut, EG",
+    "Athens, GR",
+    "Atyrau, KZ",
+    "Auckland, NZ",
+    "Austin, US",
+    "Aveiro, PT",
+    "Ayacucho, PE",
+    "Ayutthaya, TH",
+    "Bacolod, PH",
+    "Baghdad, IQ",
+    "Bago, MM",
+    "Bahawalpur, PK",
+    "Bahir Dar, ET",
+    "Baku, AZ",
+    "Balti, MD",
+    "Bamako, ML",
+    "Bamenda, CM",
+    "Bandar Seri Begawan, BN",
+    "Bandung, ID",
+    "Bangalore, IN",
+    "Bangkok, TH",
+    "Bangui, CF",
+    "Banja Luka, BA",
+    "Banjul, GM",
+    "Barcelona, ES",
+    "Barcelona, VE",
+    "Bari, IT",
+    "Barisal, BD",
+    "Barquisimeto, VE",
+    "Barranquilla, CO",
+    "Basel, CH",
+    "Basra, IQ",
+    "Batam, ID",
+    "Batna, DZ",
+    "Battambang, KH",
+    "Batumi, GE",
+    "Beersheba, IL",
+    "Beijing, CN",
+    "Beira, MZ",
+    "Beirut, LB",
+    "Bekasi, ID",
+    "Belem, BR",
+    "Belgrade, RS",
+    "Belize City, BZ",
+    "Belmopan, BZ",
+    "Belo Horizonte, BR",
+    "Benghazi, LY",
+    "Benguela, AO",
+    "Benin City, NG",
+    "Bergen, NO",
+    "Berlin, DE",
+    "Bern, CH",
+    "Bialystok, PL",
+    "Bien Hoa, VN",
+    "Bilbao, ES",
+    "Biratnagar, NP",
+    "Birmingham, GB",
+    "Bishkek, KG",
+    "Bissau, GW",
+    "Bitola, MK",
+    "Bizerte, TN",
+    "Blantyre, MW",
+    "Blida, DZ",
+    "Bloemfontein, ZA",
+    "Bobo-Dioulasso, BF",
+    "Bodo, NO",
+    "Bogor, ID",
+    "Bogota, CO",
+    "Bologna, IT",
+    "Bordeaux, FR",
+    "Bouake, CI",
+    "Bradford, GB",
+    "Braga, PT",
+    "Brasilia, BR",
+    "Brasov, RO",
+    "Bratislava, SK",
+    "Brazzaville, CG",
+    "Breda, NL",
+    "Bregenz, AT",
+    "Bremen, DE",
+    "Brest, BY",
+    "Bridgetown, BB",
+    "Brisbane, AU",
+    "Bristol, GB",
+    "Brno, CZ",
+    "Bruges, BE",
+    "Brussels, BE",
+    "Bucaramanga, CO",
+    "Bucharest, RO",
+    "Budapest, HU",
+    "Budva, ME",
+    "Buenos Aires, AR",
+    "Bujumbura, BI",
+    "Bukavu, CD",
+    "Bukhara, UZ",
+    "Bulawayo, ZW",
+    "Buon Ma Thuot, VN",
+    "Buraidah, SA",
+    "Burgas, BG",
+    "Bursa, TR",
+    "Busan, KR",
+    "Bydgoszcz, PL",
+    "Cagayan de Oro, PH",
+    "Cairo, EG",
+    "Calgary, CA",
+    "Cali, CO",
+    "Caloocan, PH",
+    "Camaguey, CU",
+    "Can Tho, VN",
+    "Canberra, AU",
+    "Cap-Haitien, HT",
+    "Cape Coast, GH",
+    "Cape Town, ZA",
+    "Caracas, VE",
+    "Cartagena, CO",
+    "Casablanca, MA",
+    "Castries, LC",
+    "Catania, IT",
+    "Cayenne, GF",
+    "Cebu City, PH",
+    "Ceske Budejovice, CZ",
+    "Changwon, KR",
+    "Chania, GR",
+    "Charleroi, BE",
+    "Chelyabinsk, RU",
+    "Chengdu, CN",
+    "Chennai, IN",
+    "Chiang Mai, TH",
+    "Chiang Rai, TH",
+    "Chicago, US",
+    "Chiclayo, PE",
+    "Chillan, CL",
+    "Chisinau, MD",
+    "Chittagong, BD",
+    "Chongqing, CN",
+    "Christchurch, NZ",
+    "Ciudad del Este, PY",
+    "Ciudad Guayana, VE",
+    "Cluj-Napoca, RO",
+    "Cochabamba, BO",
+    "Coimbra, PT",
+    "Cologne, DE",
+    "Colombo, LK",
+    "Colon, PA",
+    "Colonia del Sacramento, UY",
+    "Comilla, BD",
+    "Conakry, GN",
+    "Concepcion, CL",
+    "Constanta, RO",
+    "Constantine, DZ",
+    "Copenhagen, DK",
+    "Cordoba, AR",
+    "Cordoba, ES",
+    "Corfu, GR",
+    "Cork, IE",
+    "Cotonou, BJ",
+    "Coventry, GB",
+    "Craiova, RO",
+    "Cucuta, CO",
+    "Cuenca, EC",
+    "Curitiba, BR",
+    "Cusco, PE",
+    "Da Lat, VN",
+    "Da Nang, VN",
+    "Daegu, KR",
+    "Daejeon, KR",
+    "Dakar, SN",
+    "Dallas, US",
+    "Damascus, SY",
+    "Dammam, SA",
+    "Dar es Salaam, TZ",
+    "Daugavpils, LV",
+    "Davao City, PH",
+    "David, PA",
+    "Davos, CH",
+    "Debrecen, HU",
+    "Delhi, IN",
+    "Depok, ID",
+    "Dhaka, BD",
+    "Dili, TL",
+    "Dire Dawa, ET",
+    "Diyarbakir, TR",
+    "Djerba, TN",
+    "Djibouti, DJ",
+    "Dnipro, UA",
+    "Dodoma, TZ",
+    "Doha, QA",
+    "Donetsk, UA",
+    "Dongguan, CN",
+    "Dortmund, DE",
+    "Douala, CM",
+    "Douglas, IM",
+    "Drammen, NO",
+    "Dresden, DE",
+    "Dubai, AE",
+    "Dublin, IE",
+    "Dubrovnik, HR",
+    "Dunedin, NZ",
+    "Durban, ZA",
+    "Durres, AL",
+    "Dushanbe, TJ",
+    "Dusseldorf, DE",
+    "East London, ZA",
+    "Edinburgh, GB",
+    "Edmonton, CA",
+    "Eilat, IL",
+    "Eindhoven, NL",
+    "El Alto, BO",
+    "Eldoret, KE",
+    "Encarnacion, PY",
+    "Entebbe, UG",
+    "Erbil, IQ",
+    "Esbjerg, DK",
+    "Eskisehir, TR",
+    "Espoo, FI",
+    "Essaouira, MA",
+    "Essen, DE",
+    "Evora, PT",
+    "Faisalabad, PK",
+    "Faiyum, EG",
+    "Faro, PT",
+    "Fergana, UZ",
+    "Fez, MA",
+    "Florence, IT",
+    "Fort-de-France, MQ",
+    "Fortaleza, BR",
+    "Foshan, CN",
+    "Francistown, BW",
+    "Frankfurt, DE",
+    "Freetown, SL",
+    "Fujairah, AE",
+    "Fukuoka, JP",
+    "Funafuti, TV",
+    "Funchal, PT",
+    "Gaborone, BW",
+    "Galapagos, EC",
+    "Galati, RO",
+    "Galle, LK",
+    "Galway, IE",
+    "Ganja, AZ",
+    "Garoua, CM",
+    "Gaza, PS",
+    "Gaziantep, TR",
+    "Gdansk, PL",
+    "Gdynia, PL",
+    "Geelong, AU",
+    "Geneva, CH",
+    "Genoa, IT",
+    "George Town, KY",
+    "George Town, MY",
+    "Georgetown, GY",
+    "Ghent, BE",
+    "Gibraltar, GI",
+    "Gitega, BI",
+    "Giza, EG",
+    "Glasgow, GB",
+    "Goiania, BR",
+    "Gold Coast, AU",
+    "Goma, CD",
+    "Gomel, BY",
+    "Gondar, ET",
+    "Gothenburg, SE",
+    "Goyang, KR",
+    "Granada, NI",
+    "Graz, AT",
+    "Grodno, BY",
+    "Groningen, NL",
+    "Guadalajara, MX",
+    "Guangzhou, CN",
+    "Guatemala City, GT",
+    "Guayaquil, EC",
+    "Gujranwala, PK",
+    "Gulu, UG",
+    "Gwangju, KR",
+    "Gyor, HU",
+    "Gyumri, AM",
+    "Haarlem, NL",
+    "Hagatna, GU",
+    "Haifa, IL",
+    "Hail, SA",
+    "Haiphong, VN",
+    "Hama, SY",
+    "Hamburg, DE",
+    "Hamilton, BM",
+    "Hamilton, CA",
+    "Hamilton, NZ",
+    "Hangzhou, CN",
+    "Hanoi, VN",
+    "Harare, ZW",
+    "Hargeisa, SO",
+    "Hat Yai, TH",
+    "Havana, CU",
+    "Hawassa, ET",
+    "Hebron, PS",
+    "Helsingborg, SE",
+    "Helsinki, FI",
+    "Heraklion, GR",
+    "Herat, AF",
+    "Hermosillo, MX",
+    "Hiroshima, JP",
+    "Ho Chi Minh City, VN",
+    "Hobart, AU",
+    "Hodeidah, YE",
+    "Holguin, CU",
+    "Homs, SY",
+    "Hong Kong, HK",
+    "Honiara, SB",
+    "Houston, US",
+    "Hradec Kralove, CZ",
+    "Hsinchu, TW",
+    "Huambo, AO",
+    "Huancayo, PE",
+    "Hue, VN",
+    "Hyderabad, IN",
+    "Hyderabad, PK",
+    "Iasi, RO",
+    "Ibadan, NG",
+    "Ibague, CO",
+    "Ilorin, NG",
+    "Incheon, KR",
+    "Innsbruck, AT",
+    "Interlaken, CH",
+    "Ioannina, GR",
+    "Ipoh, MY",
+    "Iquique, CL",
+    "Iquitos, PE",
+    "Irbid, JO",
+    "Isfahan, IR",
+    "Islamabad, PK",
+    "Ismailia, EG",
+    "Istanbul, TR",
+    "Izmir, TR",
+    "Jacksonville, US",
+    "Jaffna, LK",
+    "Jaipur, IN",
+    "Jakarta, ID",
+    "Jalalabad, AF",
+    "Jeddah, SA",
+    "Jeonju, KR",
+    "Jerusalem, IL",
+    "Jinja, UG",
+    "Johannesburg, ZA",
+    "Johor Bahru, MY",
+    "Jonkoping, SE",
+    "Jos, NG",
+    "Juarez, MX",
+    "Juba, SS",
+    "Jubail, SA",
+    "Jurmala, LV",
+    "Jyvaskyla, FI",
+    "Kabul, AF",
+    "Kaduna, NG",
+    "Kairouan, TN",
+    "Kampala, UG",
+    "Kananga, CD",
+    "Kandahar, AF",
+    "Kandy, LK",
+    "Kano, NG",
+    "Kanpur, IN",
+    "Kaohsiung, TW",
+    "Karachi, PK",
+    "Karaganda, KZ",
+    "Karaj, IR",
+    "Karbala, IQ",
+    "Karlovy Vary, CZ",
+    "Kassala, SD",
+    "Kathmandu, NP",
+    "Katowice, PL",
+    "Kaunas, LT",
+    "Kavala, GR",
+    "Kawasaki, JP",
+    "Kayseri, TR",
+    "Kazan, RU",
+    "Keelung, TW",
+    "Kenitra, MA",
+    "Kermanshah, IR",
+    "Kharkiv, UA",
+    "Khartoum, SD",
+    "Khobar, SA",
+    "Khon Kaen, TH",
+    "Khujand, TJ",
+    "Khulna, BD",
+    "Kigali, RW",
+    "Kilkenny, IE",
+    "Kimberley, ZA",
+    "Kingston, JM",
+    "Kinshasa, CD",
+    "Kirkuk, IQ",
+    "Kisangani, CD",
+    "Kismayo, SO",
+    "Kisumu, KE",
+    "Kitchener, CA",
+    "Kitwe, ZM",
+    "Klagenfurt, AT",
+    "Klaipeda, LT",
+    "Kobe, JP",
+    "Kolkata, IN",
+    "Konya, TR",
+    "Kosice, SK",
+    "Kota Bharu, MY",
+    "Kota Kinabalu, MY",
+    "Kotor, ME",
+    "Kragujevac, RS",
+    "Krakow, PL",
+    "Krasnoyarsk, RU",
+    "Kristiansand, NO",
+    "Kryvyi Rih, UA",
+    "Kuala Lumpur, MY",
+    "Kuantan, MY",
+    "Kuching, MY",
+    "Kumasi, GH",
+    "Kuopio, FI",
+    "Kutaisi, GE",
+    "Kuwait City, KW",
+    "Kyiv, UA",
+    "Kyoto, JP",
+    "La Paz, BO",
+    "La Plata, AR",
+    "La Serena, CL",
+    "Lae, PG",
+    "Lagos, NG",
+    "Lahore, PK",
+    "Lahti, FI",
+    "Lalitpur, NP",
+    "Larissa, GR",
+    "Larnaca, CY",
+    "Las Palmas, ES",
+    "Latakia, SY",
+    "Lausanne, CH",
+    "Leeds, GB",
+    "Leicester, GB",
+    "Leipzig, DE",
+    "Leon, MX",
+    "Leon, NI",
+    "Leuven, BE",
+    "Liberec, CZ",
+    "Liberia, CR",
+    "Libreville, GA",
+    "Liege, BE",
+    "Liepaja, LV",
+    "Lille, FR",
+    "Lilongwe, MW",
+    "Lima, PE",
+    "Limassol, CY",
+    "Limerick, IE",
+    "Limon, CR",
+    "Linkoping, SE",
+    "Linz, AT",
+    "Lisbon, PT",
+    "Liverpool, GB",
+    "Livingstone, ZM",
+    "Ljubljana, SI",
+    "Lobito, AO",
+    "Lodz, PL",
+    "Loja, EC",
+    "Lome, TG",
+    "London, CA",
+    "London, GB",
+    "Los Angeles, US",
+    "Luanda, AO",
+    "Luang Prabang, LA",
+    "Lublin, PL",
+    "Lubumbashi, CD",
+    "Lucerne, CH",
+    "Lucknow, IN",
+    "Lugano, CH",
+    "Luhansk, UA",
+    "Lund, SE",
+    "Lusaka, ZM",
+    "Luxembourg, LU",
+    "Luxor, EG",
+    "Lviv, UA",
+    "Lyon, FR",
+    "Macau, MO",
+    "Machala, EC",
+    "Madrid, ES",
+    "Maiduguri, NG",
+    "Majuro, MH",
+    "Makassar, ID",
+    "Makati, PH",
+    "Malacca, MY",
+    "Malaga, ES",
+    "Male, MV",
+    "Malindi, KE",
+    "Malmo, SE",
+    "Managua, NI",
+    "Manama, BH",
+    "Manaus, BR",
+    "Manchester, GB",
+    "Mandalay, MM",
+    "Manila, PH",
+    "Manizales, CO",
+    "Mansoura, EG",
+    "Manta, EC",
+    "Maputo, MZ",
+    "Mar del Plata, AR",
+    "Maracaibo, VE",
+    "Maracay, VE",
+    "Maribor, SI",
+    "Mariupol, UA",
+    "Marrakesh, MA",
+    "Marseille, FR",
+    "Maseru, LS",
+    "Mashhad, IR",
+    "Maturin, VE",
+    "Maun, BW",
+    "Mawlamyine, MM",
+    "Mayaguez, PR",
+    "Mazar-i-Sharif, AF",
+    "Mbabane, SZ",
+    "Mbeya, TZ",
+    "Mbuji-Mayi, CD",
+    "McMurdo Station, AQ",
+    "Mecca, SA",
+    "Medan, ID",
+    "Medellin, CO",
+    "Medina, SA",
+    "Mekelle, ET",
+    "Meknes, MA",
+    "Melbourne, AU",
+    "Mendoza, AR",
+    "Merida, MX",
+    "Merida, VE",
+    "Mersin, TR",
+    "Mexico City, MX",
+    "Milan, IT",
+    "Minsk, BY",
+    "Miskolc, HU",
+    "Misrata, LY",
+    "Mogadishu, SO",
+    "Mogilev, BY",
+    "Mombasa, KE",
+    "Monaco, MC",
+    "Monrovia, LR",
+    "Mons, BE",
+    "Montego Bay, JM",
+    "Monterrey, MX",
+    "Montevideo, UY",
+    "Montpellier, FR",
+    "Montreal, CA",
+    "Moroni, KM",
+    "Moscow, RU",
+    "Moshi, TZ",
+    "Mostar, BA",
+    "Mosul, IQ",
+    "Mukalla, YE",
+    "Multan, PK",
+    "Mumbai, IN",
+    "Munich, DE",
+    "Murcia, ES",
+    "Muscat, OM",
+    "Mutare, ZW",
+    "Mwanza, TZ",
+    "Mykolaiv, UA",
+    "Mymensingh, BD",
+    "N'Djamena, TD",
+    "Nablus, PS",
+    "Nadi, FJ",
+    "Nagoya, JP",
+    "Nagpur, IN",
+    "Nairobi, KE",
+    "Najaf, IQ",
+    "Nakhon Ratchasima, TH",
+    "Nakuru, KE",
+    "Namangan, UZ",
+    "Nampula, MZ",
+    "Namur, BE",
+    "Nanjing, CN",
+    "Nantes, FR",
+    "Napier, NZ",
+    "Naples, IT",
+    "Nasiriyah, IQ",
+    "Nassau, BS",
+    "Naypyidaw, MM",
+    "Ndola, ZM",
+    "Negombo, LK",
+    "Nelson, NZ",
+    "Nelspruit, ZA",
+    "Netanya, IL",
+    "Neuquen, AR",
+    "New Plymouth, NZ",
+    "New York, US",
+    "Newcastle, AU",
+    "Ngerulmud, PW",
+    "Nha Trang, VN",
+    "Niamey, NE",
+    "Nice, FR",
+    "Nicosia, CY",
+    "Nijmegen, NL",
+    "Nis, RS",
+    "Nitra, SK",
+    "Nizhny Novgorod, RU",
+    "Nizwa, OM",
+    "Nonthaburi, TH",
+    "Norrkoping, SE",
+    "Nouakchott, MR",
+    "Noumea, NC",
+    "Novi Sad, RS",
+    "Novosibirsk, RU",
+    "Nuku'alofa, TO",
+    "Nukus, UZ",
+    "Nuuk, GL",
+    "Odense, DK",
+    "Odesa, UA",
+    "Ohrid, MK",
+    "Olomouc, CZ",
+    "Omdurman, SD",
+    "Omsk, RU",
+    "Oradea, RO",
+    "Oran, DZ",
+    "Oranjestad, AW",
+    "Orebro, SE",
+    "Oruro, BO",
+    "Osaka, JP",
+    "Osh, KG",
+    "Osijek, HR",
+    "Oskemen, KZ",
+    "Oslo, NO",
+    "Ostrava, CZ",
+    "Ottawa, CA",
+    "Ouagadougou, BF",
+    "Oujda, MA",
+    "Oulu, FI",
+    "Palembang, ID",
+    "Palermo, IT",
+    "Palikir, FM",
+    "Palma, ES",
+    "Palmerston North, NZ",
+    "Panama City, PA",
+    "Papeete, PF",
+    "Paphos, CY",
+    "Paramaribo, SR",
+    "Paris, FR",
+    "Parnu, EE",
+    "Pasig, PH",
+    "Patras, GR",
+    "Pattaya, TH",
+    "Pavlodar, KZ",
+    "Pecs, HU",
+    "Pereira, CO",
+    "Perth, AU",
+    "Peshawar, PK",
+    "Petah Tikva, IL",
+    "Petaling Jaya, MY",
+    "Philadelphia, US",
+    "Phnom Penh, KH",
+    "Phoenix, US",
+    "Phuket, TH",
+    "Pietermaritzburg, ZA",
+    "Piura, PE",
+    "Ploiesti, RO",
+    "Plovdiv, BG",
+    "Plzen, CZ",
+    "Podgorica, ME",
+    "Pointe-a-Pitre, GP",
+    "Pointe-Noire, CG",
+    "Pokhara, NP",
+    "Polokwane, ZA",
+    "Ponce, PR",
+    "Ponta Delgada, PT",
+    "Pori, FI",
+    "Port Elizabeth, ZA",
+    "Port Harcourt, NG",
+    "Port Louis, MU",
+    "Port Moresby, PG",
+    "Port of Spain, TT",
+    "Port Said, EG",
+    "Port Sudan, SD",
+    "Port Vila, VU",
+    "Port-au-Prince, HT",
+    "Porto Alegre, BR",
+    "Porto, PT",
+    "Porto-Novo, BJ",
+    "Potosi, BO",
+    "Poznan, PL",
+    "Prague, CZ",
+    "Praia, CV",
+    "Presov, SK",
+    "Pretoria, ZA",
+    "Pristina, XK",
+    "Prizren, XK",
+    "Pucallpa, PE",
+    "Puebla, MX",
+    "Puerto La Cruz, VE",
+    "Puerto Montt, CL",
+    "Puerto Plata, DO",
+    "Pune, IN",
+    "Puno, PE",
+    "Punta Cana, DO",
+    "Punta del Este, UY",
+    "Pyongyang, KP",
+    "Qom, IR",
+    "Quebec City, CA",
+    "Quetta, PK",
+    "Quetzaltenango, GT",
+    "Quezon City, PH",
+    "Quito, EC",
+    "Quy Nhon, VN",
+    "Rabat, MA",
+    "Rajshahi, BD",
+    "Ramallah, PS",
+    "Rancagua, CL",
+    "Rangpur, BD",
+    "Ras Al Khaimah, AE",
+    "Rasht, IR",
+    "Rawalpindi, PK",
+    "Recife, BR",
+    "Reims, FR",
+    "Rennes, FR",
+    "Resistencia, AR",
+    "Reykjavik, IS",
+    "Rhodes, GR",
+    "Riga, LV",
+    "Rijeka, HR",
+    "Rio de Janeiro, BR",
+    "Rishon LeZion, IL",
+    "Riyadh, SA",
+    "Rome, IT",
+    "Rosario, AR",
+    "Roskilde, DK",
+    "Rostov-on-Don, RU",
+    "Rotorua, NZ",
+    "Rotterdam, NL",
+    "Rovaniemi, FI",
+    "Ruse, BG",
+    "Saint George's, GD",
+    "Saint Helier, JE",
+    "Saint John's, AG",
+    "Saint Petersburg, RU",
+    "Saint-Louis, SN",
+    "Saitama, JP",
+    "Salalah, OM",
+    "Salta, AR",
+    "Salto, UY",
+    "Salvador, BR",
+    "Salzburg, AT",
+    "Samara, RU",
+    "Samarkand, UZ",
+    "San Antonio, US",
+    "San Diego, US",
+    "San Fernando, TT",
+    "San Jose, CR",
+    "San Jose, US",
+    "San Juan, AR",
+    "San Juan, PR",
+    "San Luis Potosi, MX",
+    "San Marino, SM",
+    "San Miguel de Tucuman, AR",
+    "San Pedro Sula, HN",
+    "San Salvador, SV",
+    "San-Pedro, CI",
+    "Sanaa, YE",
+    "Santa Ana, SV",
+    "Santa Cruz de la Sierra, BO",
+    "Santa Fe, AR",
+    "Santa Marta, CO",
+    "Santiago de Cuba, CU",
+    "Santiago de los Caballeros, DO",
+    "Santiago, CL",
+    "Santo Domingo, DO",
+    "Santo Domingo, EC",
+    "Sao Paulo, BR",
+    "Sapporo, JP",
+    "Sarajevo, BA",
+    "Sargodha, PK",
+    "Semarang, ID",
+    "Semey, KZ",
+    "Sendai, JP",
+    "Seongnam, KR",
+    "Seoul, KR",
+    "Seremban, MY",
+    "Setif, DZ",
+    "Setubal, PT",
+    "Seville, ES",
+    "Sfax, TN",
+    "Shah Alam, MY",
+    "Shanghai, CN",
+    "Sharjah, AE",
+    "Sheffield, GB",
+    "Shenzhen, CN",
+    "Shiraz, IR",
+    "Shkoder, AL",
+    "Shubra El Kheima, EG",
+    "Shymkent, KZ",
+    "Siauliai, LT",
+    "Sibiu, RO",
+    "Sidon, LB",
+    "Siem Reap, KH",
+    "Sihanoukville, KH",
+    "Singapore, SG",
+    "Skopje, MK",
+    "Sofia, BG",
+    "Sohar, OM",
+    "Sousse, TN",
+    "Soweto, ZA",
+    "Split, HR",
+    "St. Gallen, CH",
+    "Stanley, FK",
+    "Stara Zagora, BG",
+    "Stavanger, NO",
+    "Stockholm, SE",
+    "Strasbourg, FR",
+    "Stuttgart, DE",
+    "Subotica, RS",
+    "Sucre, BO",
+    "Suez, EG",
+    "Sulaymaniyah, IQ",
+    "Sumqayit, AZ",
+    "Sunshine Coast, AU",
+    "Surabaya, ID",
+    "Surat Thani, TH",
+    "Surat, IN",
+    "Suva, FJ",
+    "Suwon, KR",
+    "Swakopmund, NA",
+    "Sydney, AU",
+    "Sylhet, BD",
+    "Szczecin, PL",
+    "Szeged, HU",
+    "Tabriz, IR",
+    "Tabuk, SA",
+    "Tacna, PE",
+    "Taguig, PH",
+    "Taichung, TW",
+    "Taif, SA",
+    "Tainan, TW",
+    "Taipei, TW",
+    "Taiz, YE",
+    "Takoradi, GH",
+    "Talca, CL",
+    "Tallinn, EE",
+    "Tamale, GH",
+    "Tampere, FI",
+    "Tangerang, ID",
+    "Tangier, MA",
+    "Tanta, EG",
+    "Taoyuan, TW",
+    "Tarawa, KI",
+    "Taraz, KZ",
+    "Tarija, BO",
+    "Tartu, EE",
+    "Tashkent, UZ",
+    "Tauranga, NZ",
+    "Tbilisi, GE",
+    "Tegucigalpa, HN",
+    "Tehran, IR",
+    "Tel Aviv, IL",
+    "Temuco, CL",
+    "Tetouan, MA",
+    "The Hague, NL",
+    "Thessaloniki, GR",
+    "Thies, SN",
+    "Thimphu, BT",
+    "Tianjin, CN",
+    "Tijuana, MX",
+    "Tilburg, NL",
+    "Timbuktu, ML",
+    "Timisoara, RO",
+    "Tirana, AL",
+    "Tiraspol, MD",
+    "Tlemcen, DZ",
+    "Toamasina, MG",
+    "Tokyo, JP",
+    "Toronto, CA",
+    "Torshavn, FO",
+    "Touba, SN",
+    "Toulouse, FR",
+    "Tripoli, LB",
+    "Tripoli, LY",
+    "Tromso, NO",
+    "Trondheim, NO",
+    "Trujillo, PE",
+    "Tunis, TN",
+    "Turin, IT",
+    "Turkmenabat, TM",
+    "Turku, FI",
+    "Tuzla, BA",
+    "Udon Thani, TH",
+    "Ufa, RU",
+    "Ulaanbaatar, MN",
+    "Ulsan, KR",
+    "Umea, SE",
+    "Uppsala, SE",
+    "Urmia, IR",
+    "Utrecht, NL",
+    "Vaduz, LI",
+    "Valencia, ES",
+    "Valencia, VE",
+    "Valletta, MT",
+    "Valparaiso, CL",
+    "Vancouver, CA",
+    "Vantaa, FI",
+    "Varadero, CU",
+    "Varna, BG",
+    "Vasteras, SE",
+    "Vatican City, VA",
+    "Venice, IT",
+    "Verona, IT",
+    "Victoria Falls, ZW",
+    "Victoria, CA",
+    "Victoria, SC",
+    "Vienna, AT",
+    "Vientiane, LA",
+    "Villach, AT",
+    "Villavicencio, CO",
+    "Vilnius, LT",
+    "Vinnytsia, UA",
+    "Vitebsk, BY",
+    "Vlore, AL",
+    "Volos, GR",
+    "Vung Tau, VN",
+    "Walvis Bay, NA",
+    "Warsaw, PL",
+    "Waterford, IE",
+    "Wellington, NZ",
+    "Wels, AT",
+    "Whangarei, NZ",
+    "Willemstad, CW",
+    "Windhoek, NA",
+    "Winnipeg, CA",
+    "Winterthur, CH",
+    "Wollongong, AU",
+    "Wroclaw, PL",
+    "Wuhan, CN",
+    "Xi'an, CN",
+    "Yamoussoukro, CI",
+    "Yangon, MM",
+    "Yaounde, CM",
+    "Yaren, NR",
+    "Yekaterinburg, RU",
+    "Yerevan, AM",
+    "Yokohama, JP",
+    "Zadar, HR",
+    "Zagreb, HR",
+    "Zahedan, IR",
+    "Zamboanga City, PH",
+    "Zanzibar City, TZ",
+    "Zapopan, MX",
+    "Zaporizhzhia, UA",
+    "Zaragoza, ES",
+    "Zaria, NG",
+    "Zarqa, JO",
+    "Zermatt, CH",
+    "Zilina, SK",
+    "Zinder, NE",
+    "Zurich, CH",
+];
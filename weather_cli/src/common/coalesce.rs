@@ -0,0 +1,249 @@
+//! # Request Coalescing
+//!
+//! Deduplicates weather requests when several locations of one batch resolve to the same
+//! address. Requests are keyed by `(provider, normalized address, date)`; duplicates share a
+//! single upstream call and receive clones of its result, marked as cache hits.
+
+use crate::common::{fetch::fetch_weather, history::location_key, *};
+use ::futures::{
+    FutureExt,
+    future::{BoxFuture, Shared},
+};
+use ::std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+use ::weather_providers::{Provider, WeatherInfo, WeatherProvider};
+
+/// The result of a coalesced request. Errors are shared between all consumers.
+pub type SharedWeather = std::result::Result<WeatherInfo, Arc<Error>>;
+
+/// A request that may be awaited by several consumers.
+pub type SharedRequest = Shared<BoxFuture<'static, SharedWeather>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RequestKey {
+    provider: &'static str,
+    address: String,
+    date: Option<String>,
+}
+
+/// Fetches weather through a single provider, coalescing duplicate requests.
+///
+/// The fetcher is meant to live for one batch: every request issued through it is remembered,
+/// so a duplicate receives the result of the first request even after it has completed.
+pub struct CoalescingFetcher {
+    provider: Provider,
+    weather_provider: Arc<dyn WeatherProvider>,
+    api_key: Option<Arc<str>>,
+    requests: Mutex<HashMap<RequestKey, SharedRequest>>,
+}
+
+impl CoalescingFetcher {
+    /// Creates a fetcher for the given provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The provider, whose ID is part of the coalescing key.
+    /// * `weather_provider` - The provider performing the upstream requests.
+    /// * `api_key` - The API key passed to every request.
+    pub fn new(
+        provider: Provider,
+        weather_provider: Arc<dyn WeatherProvider>,
+        api_key: Option<String>,
+    ) -> Self {
+        Self {
+            provider,
+            weather_provider,
+            api_key: api_key.map(Arc::from),
+            requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the request for `address` and `date`, starting it unless an identical one exists.
+    ///
+    /// The returned future is lazy: the upstream call happens when it is first polled. The
+    /// result of an identical earlier request is marked as a cache hit in its metadata.
+    pub fn fetch(&self, address: &str, date: Option<&str>) -> BoxFuture<'static, SharedWeather> {
+        let date = date.map(str::trim).filter(|d| !d.is_empty());
+        let key = RequestKey {
+            provider: self.provider.id(),
+            address: location_key(address),
+            date: date.map(str::to_string),
+        };
+
+        let mut requests = self.requests.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(request) = requests.get(&key) {
+            return request
+                .clone()
+                .map(|result| result.map(WeatherInfo::with_cache_hit))
+                .boxed();
+        }
+
+        let weather_provider = self.weather_provider.clone();
+        let provider = self.provider.clone();
+        let api_key = self.api_key.clone();
+        let address = address.to_string();
+        let date = date.map(str::to_string);
+
+        let request = async move {
+            fetch_weather(
+                weather_provider.as_ref(),
+                &provider,
+                api_key.as_deref(),
+                &address,
+                date.as_deref(),
+            )
+            .await
+            .map_err(|e| Arc::new(Error::from(e)))
+        }
+        .boxed()
+        .shared();
+        requests.insert(key, request.clone());
+
+        request.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixtures;
+    use ::async_trait::async_trait;
+    use ::futures::future::join_all;
+    use ::std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+    use ::weather_providers::{
+        CacheStatus, DataSource, HistoricalProvider, ProviderInfo, WeatherMeta,
+    };
+
+    /// Counts the upstream calls and fails for the address "fail".
+    #[derive(Default)]
+    struct CountingProvider {
+        calls: AtomicUsize,
+    }
+
+    impl CountingProvider {
+        async fn weather_on(
+            &self,
+            address: &str,
+            date: Option<&str>,
+        ) -> weather_providers::Result<WeatherInfo> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+
+            if address == "fail" {
+                return Err("upstream failure".into());
+            }
+
+            Ok(WeatherInfo {
+                city: address.to_string(),
+                date: date.unwrap_or("today").to_string(),
+                meta: Some(WeatherMeta::new(
+                    Provider::Mock,
+                    "mock",
+                    DataSource::Observed,
+                )),
+                ..fixtures::weather()
+            })
+        }
+    }
+
+    #[async_trait]
+    impl WeatherProvider for CountingProvider {
+        async fn get_weather(
+            &self,
+            _provider_key: Option<&str>,
+            address: &str,
+        ) -> weather_providers::Result<WeatherInfo> {
+            self.weather_on(address, None).await
+        }
+
+        async fn health_check(
+            &self,
+            _provider_key: Option<&str>,
+        ) -> weather_providers::Result<Duration> {
+            Ok(Duration::ZERO)
+        }
+
+        fn info(&self) -> ProviderInfo {
+            ProviderInfo {
+                signup_url: "https://example.com",
+                key_format: "Not required",
+                free_tier: "Unlimited",
+                capabilities: &["current", "historical"],
+                example: "weather get London",
+            }
+        }
+
+        fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+            Some(self)
+        }
+    }
+
+    #[async_trait]
+    impl HistoricalProvider for CountingProvider {
+        async fn get_history(
+            &self,
+            _provider_key: Option<&str>,
+            address: &str,
+            date: &str,
+        ) -> weather_providers::Result<WeatherInfo> {
+            self.weather_on(address, Some(date)).await
+        }
+    }
+
+    fn fetcher() -> (Arc<CountingProvider>, CoalescingFetcher) {
+        let provider = Arc::new(CountingProvider::default());
+        let fetcher = CoalescingFetcher::new(Provider::Mock, provider.clone(), None);
+        (provider, fetcher)
+    }
+
+    #[tokio::test]
+    async fn test_duplicates_share_one_call() {
+        let (provider, fetcher) = fetcher();
+        let addresses = ["London", "london", " London ", "LONDON", "London"];
+
+        let results = join_all(addresses.iter().map(|a| fetcher.fetch(a, None))).await;
+
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+        assert!(results.iter().all(|r| r.as_ref().unwrap().city == "London"));
+
+        // The first request went upstream, the duplicates were served from it
+        let cache = |i: usize| results[i].as_ref().unwrap().meta.as_ref().unwrap().cache;
+        assert_eq!(cache(0), CacheStatus::Miss);
+        assert!((1..addresses.len()).all(|i| cache(i) == CacheStatus::Hit));
+    }
+
+    #[tokio::test]
+    async fn test_distinct_dates_are_not_coalesced() {
+        let (provider, fetcher) = fetcher();
+
+        let results = join_all([
+            fetcher.fetch("London", Some("2024-01-01")),
+            fetcher.fetch("London", Some("2024-01-02")),
+            fetcher.fetch("London", None),
+            fetcher.fetch("London", Some("2024-01-01")),
+        ])
+        .await;
+
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(results[0].as_ref().unwrap().date, "2024-01-01");
+        assert_eq!(results[1].as_ref().unwrap().date, "2024-01-02");
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_shared() {
+        let (provider, fetcher) = fetcher();
+
+        let results = join_all([fetcher.fetch("fail", None), fetcher.fetch("fail", None)]).await;
+
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+        for result in results {
+            assert_eq!(result.unwrap_err().to_string(), "upstream failure");
+        }
+    }
+}
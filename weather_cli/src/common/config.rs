@@ -1,12 +1,28 @@
-use crate::{common::*, models::config::Settings};
+use crate::{
+    common::{
+        fs::{FileLock, write_atomic},
+        *,
+    },
+    models::config::{Settings, UnknownKey},
+};
+use ::chrono::NaiveDateTime;
 use ::std::{
+    collections::HashMap,
     fs,
-    io::{self, BufReader, BufWriter, Write},
+    io::{self, BufReader},
     path::{Path, PathBuf},
-    sync::{Arc, RwLock, RwLockReadGuard},
+    sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard},
+    time::Duration,
 };
+use ::tokio::sync::watch;
 use ::tracing::debug;
 
+/// How long acquiring the configuration lock may take before the command fails.
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Notification channels for provider API key changes, keyed by provider ID.
+type KeyWatchers = HashMap<String, watch::Sender<Option<String>>>;
+
 /// Application configuration manager.
 ///
 /// Provides thread-safe access to `Settings`, handling automatic loading on startup
@@ -15,8 +31,14 @@ use ::tracing::debug;
 pub struct AppConfig {
     /// Path to the configuration file.
     settings_file: Arc<PathBuf>,
-    /// Current settings protected by a read-write lock.
+    /// Current settings protected by a read-write lock, only write-locked to replace them.
     settings: Arc<RwLock<Settings>>,
+    /// Subscribers to provider API key changes.
+    key_watchers: Arc<Mutex<KeyWatchers>>,
+    /// How long acquiring the lock of the file may take.
+    lock_timeout: Duration,
+    /// Whether the file is neither read nor written (`--no-config`).
+    read_only: bool,
 }
 
 impl AppConfig {
@@ -48,31 +70,54 @@ impl AppConfig {
             }
         };
 
+        Self::with_settings(path, settings, false)
+    }
+
+    /// Creates an `AppConfig` instance with default settings that never reads or writes the
+    /// file at `path` (`--no-config`). Modifying the settings fails.
+    pub fn read_only<P: Into<PathBuf>>(path: P) -> Self {
+        Self::with_settings(path.into(), Settings::default(), true)
+    }
+
+    fn with_settings(path: PathBuf, settings: Settings, read_only: bool) -> Self {
         Self {
             settings_file: Arc::new(path),
             settings: Arc::new(RwLock::new(settings)),
+            key_watchers: Arc::new(Mutex::new(HashMap::new())),
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
+            read_only,
         }
     }
 
+    /// Sets how long acquiring the lock of the file while saving the settings may take before
+    /// failing with `Error::ConfigLocked`.
+    pub fn with_lock_timeout(mut self, lock_timeout: Duration) -> Self {
+        self.lock_timeout = lock_timeout;
+        self
+    }
+
     /// Acquires a read lock for the settings.
     ///
     /// Returns an `RwLockReadGuard` allowing read access to the settings fields.
-    /// Blocks the current thread if the settings are currently being updated.
+    /// Updates only hold the lock while replacing the settings, so it is never held for long.
     ///
     /// # Errors
     ///
     /// Returns an error if the lock is poisoned due to a panic in another thread.
     pub fn get(&self) -> Result<RwLockReadGuard<'_, Settings>> {
-        self.settings
+        Ok(self
+            .settings
             .read()
-            .map_err(|e| format!("Config read lock poisoned: {e:?}").into())
+            .map_err(|e| format!("Config read lock poisoned: {e:?}"))?)
     }
 
     /// Modifies settings and atomically saves them to disk.
     ///
-    /// Provides mutable access to `Settings` within the given closure.
-    /// After the closure executes, the settings are automatically serialized and saved
-    /// to the file using an atomic writing strategy (write to tmp + rename).
+    /// Provides mutable access to a copy of `Settings` within the given closure.
+    /// After the closure executes, the copy is automatically serialized and saved
+    /// to the file using an atomic writing strategy (write to tmp + rename), and then replaces
+    /// the current settings. Updates are serialized, in the process and across processes, by
+    /// the lock of the file; waiting for it yields to the runtime.
     ///
     /// # Arguments
     ///
@@ -81,23 +126,187 @@ impl AppConfig {
     /// # Errors
     ///
     /// Returns an error if:
-    /// * The write lock could not be acquired.
+    /// * The configuration is read-only (`--no-config`).
+    /// * The lock of the file could not be acquired within the lock timeout.
     /// * An I/O error occurred while saving the file.
     /// * A JSON serialization error occurred.
-    pub fn with_mut<F, R>(&self, f: F) -> Result<R>
+    pub async fn with_mut<F, R>(&self, f: F) -> Result<R>
     where
         F: FnOnce(&mut Settings) -> R,
     {
-        let mut settings_guard = self
+        if self.read_only {
+            Err("The configuration cannot be changed with --no-config.")?;
+        }
+
+        let _file_lock = FileLock::acquire(&self.settings_file, self.lock_timeout).await?;
+
+        let mut settings = self.get()?.clone();
+        let result = f(&mut settings);
+
+        save_file_atomic(&settings, &self.settings_file)?;
+
+        self.notify_key_changes(&settings);
+
+        *self
             .settings
             .write()
-            .map_err(|e| format!("Config write lock poisoned: {e:?}"))?;
+            .map_err(|e| format!("Config write lock poisoned: {e:?}"))? = settings;
 
-        let result = f(&mut settings_guard);
+        Ok(result)
+    }
 
-        save_file_atomic(&settings_guard, &self.settings_file)?;
+    /// Serializes the current settings as TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read lock is poisoned or the settings cannot be serialized.
+    pub fn export_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(&*self.get()?)?)
+    }
 
-        Ok(result)
+    /// Replaces the settings with the ones of a TOML document and saves them to disk.
+    ///
+    /// The document is parsed and validated before anything is changed, so an invalid one
+    /// leaves the current settings intact.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The TOML document, in the format produced by `export_toml`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be parsed, the settings are invalid, or
+    /// saving them fails.
+    pub async fn import_toml(&self, s: &str) -> Result<()> {
+        let settings = toml::from_str::<Settings>(s)?;
+        settings.validate()?;
+
+        self.with_mut(|current| *current = settings).await
+    }
+
+    /// Reads the configuration file again and returns the keys it contains that no setting
+    /// reads. A missing file has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, or its settings are invalid.
+    pub fn check(&self) -> Result<Vec<UnknownKey>> {
+        let json = match fs::read_to_string(&*self.settings_file) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => Err(e)?,
+        };
+
+        let (settings, unknown) = Settings::from_json_checked(&json)?;
+        settings.validate()?;
+
+        Ok(unknown)
+    }
+
+    /// Returns the path of the configuration file.
+    pub fn path(&self) -> &Path {
+        &self.settings_file
+    }
+
+    /// Copies the configuration file to `target`, creating its parent directories.
+    ///
+    /// If no configuration file was saved yet, the current (default) settings are written to
+    /// `target` instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `target` is the configuration file itself, or the file cannot be
+    /// copied or the settings cannot be written.
+    pub fn backup(&self, target: &Path) -> Result<()> {
+        if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Copying a file onto itself truncates it
+        if let (Ok(source), Ok(target)) = (
+            fs::canonicalize(&*self.settings_file),
+            fs::canonicalize(target),
+        ) && source == target
+        {
+            Err(format!(
+                "Cannot back up the configuration onto itself: {target:?}"
+            ))?;
+        }
+
+        match fs::copy(&*self.settings_file, target) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                save_file_atomic(&*self.get()?, target)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Returns the default target of `backup`: a timestamped file next to the configuration
+    /// file, e.g. `config.backup-20240115-083000.json`.
+    pub fn backup_path(&self, now: NaiveDateTime) -> PathBuf {
+        self.settings_file.with_file_name(format!(
+            "config.backup-{}.json",
+            now.format("%Y%m%d-%H%M%S")
+        ))
+    }
+
+    /// Subscribes to changes of a provider's API key.
+    ///
+    /// The receiver starts with the currently configured key and is notified whenever a
+    /// `with_mut` call changes it (including removal, reported as `None`). Keys stored in the
+    /// OS keychain are not read; only the plain-text `key` of the configuration is tracked.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider_id` - The provider ID (e.g., "ow").
+    // Intended for long-running embedders; the CLI itself reads keys once per command.
+    #[allow(dead_code)]
+    pub fn subscribe_to_key_changes(&self, provider_id: &str) -> watch::Receiver<Option<String>> {
+        let mut watchers = self
+            .key_watchers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(sender) = watchers.get(provider_id) {
+            return sender.subscribe();
+        }
+
+        let current = self
+            .settings
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .providers
+            .get(provider_id)
+            .and_then(|p| p.key.clone());
+
+        let (sender, receiver) = watch::channel(current);
+        watchers.insert(provider_id.to_string(), sender);
+
+        receiver
+    }
+
+    /// Notifies the subscribers of every provider whose API key differs from the last sent value.
+    fn notify_key_changes(&self, settings: &Settings) {
+        let watchers = self
+            .key_watchers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        for (provider_id, sender) in watchers.iter() {
+            let key = settings
+                .providers
+                .get(provider_id)
+                .and_then(|p| p.key.clone());
+
+            sender.send_if_modified(|current| {
+                if *current == key {
+                    return false;
+                }
+                *current = key;
+                true
+            });
+        }
     }
 }
 
@@ -109,25 +318,240 @@ fn load_file(path: &Path) -> Result<Settings> {
 }
 
 fn save_file_atomic(settings: &Settings, path: &Path) -> Result<()> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+    write_atomic(path, |writer| {
+        Ok(serde_json::to_writer_pretty(writer, settings)?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::config::{LoggingSettings, ProviderConfig, Units};
+    use ::std::collections::BTreeMap;
+    use ::std::time::Duration;
+    use ::weather_providers::DisplayOptions;
+
+    fn config(name: &str) -> (AppConfig, PathBuf) {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "weather-cli-test-config-{name}-{}",
+            std::process::id()
+        ));
+        (AppConfig::new(temp_dir.join("config.json")), temp_dir)
     }
 
-    let tmp_path = path.with_extension("tmp");
+    async fn set_key(config: &AppConfig, provider_id: &str, key: Option<&str>) {
+        config
+            .with_mut(|s| {
+                s.providers.insert(
+                    provider_id.to_string(),
+                    ProviderConfig {
+                        key: key.map(str::to_string),
+                        ..Default::default()
+                    },
+                );
+            })
+            .await
+            .unwrap();
+    }
 
-    {
-        let file = fs::File::create(&tmp_path)?;
-        let mut writer = BufWriter::new(file);
-        serde_json::to_writer_pretty(&mut writer, settings)?;
-        writer.flush()?;
-        writer.get_ref().sync_all()?;
+    #[tokio::test]
+    async fn test_toml_round_trip() {
+        let (config, temp_dir) = config("toml");
+        let settings = Settings {
+            addresses: BTreeMap::from([("home".to_string(), "London, UK".to_string())]),
+            default_alias: Some("home".to_string()),
+            default_provider: Some("ow".to_string()),
+            display_options: Some(DisplayOptions {
+                show_date: true,
+                locale: "de-DE".to_string(),
+                ..Default::default()
+            }),
+            units: Some(Units::Metric),
+            lang: Some("de-DE".to_string()),
+            logging: Some(LoggingSettings {
+                max_files: Some(3),
+                ..Default::default()
+            }),
+            ..Settings::default()
+        };
+        config.with_mut(|s| *s = settings.clone()).await.unwrap();
+
+        let toml = config.export_toml().unwrap();
+        assert!(toml.contains("[addresses]"), "{toml}");
+        assert!(toml.contains("home = \"London, UK\""), "{toml}");
+
+        config.with_mut(|s| *s = Settings::default()).await.unwrap();
+        config.import_toml(&toml).await.unwrap();
+        assert_eq!(*config.get().unwrap(), settings);
+
+        // The imported settings were saved
+        assert_eq!(load_file(&config.settings_file).unwrap(), settings);
+
+        let _ = fs::remove_dir_all(temp_dir);
     }
 
-    fs::rename(&tmp_path, path).inspect_err(|_| {
-        if let Err(e) = fs::remove_file(&tmp_path) {
-            debug!("Failed to remove temporary file: {e:?}")
+    #[tokio::test]
+    async fn test_backup() {
+        let (config, temp_dir) = config("backup");
+        let target = temp_dir.join("backups").join("config.backup.json");
+
+        // Without a configuration file, the default settings are backed up
+        config.backup(&target).unwrap();
+        assert_eq!(load_file(&target).unwrap(), Settings::default());
+
+        set_key(&config, "ow", Some("12345")).await;
+        config.backup(&target).unwrap();
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            fs::read_to_string(&*config.settings_file).unwrap()
+        );
+
+        // The configuration file itself is refused, also through another path
+        let live = fs::read_to_string(&*config.settings_file).unwrap();
+        for target in [
+            config.settings_file.to_path_buf(),
+            temp_dir.join("backups").join("..").join("config.json"),
+        ] {
+            let error = config.backup(&target).unwrap_err();
+            assert!(error.to_string().contains("onto itself"), "{error}");
         }
-    })?;
+        assert_eq!(fs::read_to_string(&*config.settings_file).unwrap(), live);
+
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(8, 30, 0)
+            .unwrap();
+        assert_eq!(
+            config.backup_path(now),
+            temp_dir.join("config.backup-20240115-083000.json")
+        );
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_toml_is_rejected() {
+        let (config, temp_dir) = config("invalid-toml");
+
+        let error = config.import_toml("addresses = 1").await.unwrap_err();
+        assert!(matches!(error, Error::TomlDeserialization(_)), "{error}");
 
-    Ok(())
+        let error = config
+            .import_toml("default_provider = \"nope\"")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("nope"), "{error}");
+
+        assert_eq!(*config.get().unwrap(), Settings::default());
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_key_change_notifies_subscribers() {
+        let (config, temp_dir) = config("watch");
+        let mut receiver = config.subscribe_to_key_changes("ow");
+        assert_eq!(*receiver.borrow(), None);
+
+        set_key(&config, "ow", Some("first")).await;
+        tokio::time::timeout(Duration::from_secs(1), receiver.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(receiver.borrow_and_update().as_deref(), Some("first"));
+
+        set_key(&config, "ow", None).await;
+        receiver.changed().await.unwrap();
+        assert_eq!(*receiver.borrow_and_update(), None);
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_unrelated_changes_do_not_notify() {
+        let (config, temp_dir) = config("unrelated");
+        set_key(&config, "ow", Some("same")).await;
+
+        let receiver = config.subscribe_to_key_changes("ow");
+        assert_eq!(receiver.borrow().as_deref(), Some("same"));
+
+        set_key(&config, "wa", Some("other")).await;
+        set_key(&config, "ow", Some("same")).await;
+        config
+            .with_mut(|s| s.default_alias = Some("home".to_string()))
+            .await
+            .unwrap();
+
+        assert!(!receiver.has_changed().unwrap());
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_lock_timeout() {
+        let (config, temp_dir) = config("lock-timeout");
+        let config = config.with_lock_timeout(Duration::from_millis(100));
+
+        // The file is locked by another running process
+        fs::create_dir_all(&temp_dir).unwrap();
+        let pid = std::process::id();
+        fs::write(temp_dir.join("config.json.lock"), pid.to_string()).unwrap();
+
+        let started = std::time::Instant::now();
+        assert!(matches!(
+            config.with_mut(|s| s.default_alias = Some("home".to_string())).await,
+            Err(Error::ConfigLocked(Some(locked))) if locked == pid
+        ));
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        // Reading is not blocked, and the failed update changed nothing
+        assert_eq!(config.get().unwrap().default_alias, None);
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_updates() {
+        let (config, temp_dir) = config("concurrent");
+
+        let updates = (0..8).map(|i| {
+            let config = config.clone();
+            tokio::spawn(async move {
+                config
+                    .with_mut(|s| s.addresses.insert(i.to_string(), "London".to_string()))
+                    .await
+            })
+        });
+        for update in futures::future::join_all(updates).await {
+            update.unwrap().unwrap();
+        }
+
+        // No update was lost, in memory or on disk
+        assert_eq!(config.get().unwrap().addresses.len(), 8);
+        assert_eq!(load_file(&config.settings_file).unwrap().addresses.len(), 8);
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_read_only() {
+        let (config, temp_dir) = config("read-only");
+        config
+            .with_mut(|s| s.default_alias = Some("home".to_string()))
+            .await
+            .unwrap();
+
+        let read_only = AppConfig::read_only(temp_dir.join("config.json"));
+        assert_eq!(read_only.get().unwrap().default_alias, None);
+        assert!(
+            read_only
+                .with_mut(|s| s.default_alias = None)
+                .await
+                .unwrap_err()
+                .to_string()
+                .contains("--no-config")
+        );
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
 }
@@ -20,9 +20,67 @@ pub enum Error {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// Represents errors occurring during TOML serialization.
+    #[error("TOML serialization error: {0}")]
+    TomlSerialization(#[from] toml::ser::Error),
+
+    /// Represents errors occurring during TOML parsing, with the location of the problem.
+    #[error("Invalid TOML: {0}")]
+    TomlDeserialization(#[from] toml::de::Error),
+
     /// Represents errors from the weather providers.
     #[error("{0}")]
     Providers(#[from] weather_providers::Error),
+
+    /// The configuration could not be locked in time, with the ID of the process holding the
+    /// lock if it is known.
+    #[error(
+        "The configuration is locked by another weather process{}",
+        .0.map(|pid| format!(" (pid {pid})")).unwrap_or_default()
+    )]
+    ConfigLocked(Option<u32>),
+
+    /// The weather exceeds the alert thresholds checked by `get --alert`.
+    #[error("{0}")]
+    Alert(String),
+
+    /// Some, but not all, locations of a multi-location request failed. Holds the summary of
+    /// the outcome, e.g. "3 succeeded, 1 failed (Paris: ...)".
+    #[error("{0}")]
+    PartialFailure(String),
+
+    /// Every location of a multi-location request failed. Holds the summary of the outcome.
+    #[error("{0}")]
+    AllFailed(String),
+}
+
+impl Error {
+    /// Returns the exit code of the process: 2 for an alert, so scripts can tell it apart
+    /// from a failure, 6 when some locations of a multi-location request failed, 4 when all of
+    /// them failed, and 1 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Alert(_) => 2,
+            Self::AllFailed(_) => 4,
+            Self::PartialFailure(_) => 6,
+            _ => 1,
+        }
+    }
+
+    /// Returns advice on how to resolve the error, printed below the error message.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Providers(weather_providers::Error::OfflineMode(_)) => Some(
+                "Network access is disabled by WEATHER_PROVIDERS_OFFLINE=1. \
+                 Unset it to reach real providers, or use '--provider mock'.",
+            ),
+            Self::ConfigLocked(_) => Some(
+                "Wait for the other process to finish, or use '--no-config' to run without \
+                 reading or saving the configuration (e.g., 'weather --no-config get London').",
+            ),
+            _ => None,
+        }
+    }
 }
 
 impl From<String> for Error {
@@ -55,3 +113,40 @@ impl From<&'static str> for Error {
 /// }
 /// ```
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_hint() {
+        let offline = Error::from(weather_providers::Error::OfflineMode("OpenWeather".into()));
+        assert!(
+            offline
+                .hint()
+                .unwrap()
+                .contains("WEATHER_PROVIDERS_OFFLINE")
+        );
+
+        assert_eq!(Error::from("other").hint(), None);
+
+        let locked = Error::ConfigLocked(Some(4242));
+        assert_eq!(
+            locked.to_string(),
+            "The configuration is locked by another weather process (pid 4242)"
+        );
+        assert!(locked.hint().unwrap().contains("--no-config"));
+        assert_eq!(
+            Error::ConfigLocked(None).to_string(),
+            "The configuration is locked by another weather process"
+        );
+    }
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(Error::Alert("Too hot".to_string()).exit_code(), 2);
+        assert_eq!(Error::AllFailed("0 succeeded".to_string()).exit_code(), 4);
+        assert_eq!(Error::PartialFailure("1 failed".to_string()).exit_code(), 6);
+        assert_eq!(Error::from("other").exit_code(), 1);
+    }
+}
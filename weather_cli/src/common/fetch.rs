@@ -0,0 +1,495 @@
+//! # Concurrent Fetching
+//!
+//! Runs many weather requests with bounded concurrency, per-request timeouts and cooperative
+//! cancellation. Every multi-location or multi-day feature fetches through `fetch_many`, so
+//! they share the same limits and return results in the order of their requests. A single
+//! dated request goes through `fetch_weather`, which picks the capability of the provider
+//! covering the date.
+
+use crate::common::*;
+use ::futures::{StreamExt, stream};
+use ::std::{cmp::Ordering, fmt::Display, time::Duration};
+use ::tokio_util::sync::CancellationToken;
+use ::weather_providers::{Provider, WeatherInfo, WeatherProvider, normalize_date, parse_date};
+
+/// The maximum number of requests in flight at once.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// The maximum time a single request may take.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Limits applied by `fetch_many`.
+#[derive(Debug, Clone, Copy)]
+pub struct FetchLimits {
+    /// The maximum number of requests in flight at once (at least 1).
+    pub concurrency: usize,
+    /// The maximum time a single request may take.
+    pub timeout: Duration,
+}
+
+impl Default for FetchLimits {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_CONCURRENCY,
+            timeout: REQUEST_TIMEOUT,
+        }
+    }
+}
+
+/// Fetches the weather for every request.
+///
+/// At most `limits.concurrency` requests are in flight at once. A request that exceeds
+/// `limits.timeout` fails with a timeout error. Once `cancel` is triggered, running requests
+/// are abandoned and pending ones are not started; both fail with a cancellation error.
+///
+/// # Arguments
+///
+/// * `requests` - The requests to perform.
+/// * `limits` - The concurrency and timeout limits.
+/// * `cancel` - A token that cancels the remaining requests when triggered.
+/// * `fetch` - Performs a single request.
+///
+/// # Returns
+///
+/// Each request paired with its result, in the order of `requests` regardless of the order
+/// in which the requests completed.
+pub async fn fetch_many<R, F>(
+    requests: Vec<R>,
+    limits: FetchLimits,
+    cancel: &CancellationToken,
+    fetch: F,
+) -> Vec<(R, Result<WeatherInfo>)>
+where
+    F: AsyncFn(&R) -> Result<WeatherInfo>,
+{
+    fetch_each(requests, limits, cancel, fetch, |_, _| {}).await
+}
+
+/// Fetches the weather for every request, like `fetch_many`, and passes each result to
+/// `on_result` as soon as its request completes.
+///
+/// `on_result` sees the results in the order of completion, which lets a caller stream them;
+/// the returned results are still in the order of `requests`.
+pub async fn fetch_each<R, F, C>(
+    requests: Vec<R>,
+    limits: FetchLimits,
+    cancel: &CancellationToken,
+    fetch: F,
+    mut on_result: C,
+) -> Vec<(R, Result<WeatherInfo>)>
+where
+    F: AsyncFn(&R) -> Result<WeatherInfo>,
+    C: FnMut(&R, &Result<WeatherInfo>),
+{
+    let fetch = &fetch;
+
+    let mut results = stream::iter(requests.into_iter().enumerate())
+        .map(|(index, request)| async move {
+            let result = if cancel.is_cancelled() {
+                Err(cancelled())
+            } else {
+                tokio::select! {
+                    _ = cancel.cancelled() => Err(cancelled()),
+                    result = tokio::time::timeout(limits.timeout, fetch(&request)) => {
+                        result.unwrap_or_else(|_| {
+                            Err(format!(
+                                "Request timed out after {} seconds.",
+                                limits.timeout.as_secs_f32()
+                            )
+                            .into())
+                        })
+                    }
+                }
+            };
+            (index, request, result)
+        })
+        .buffer_unordered(limits.concurrency.max(1))
+        .inspect(|(_, request, result)| on_result(request, result))
+        .collect::<Vec<_>>()
+        .await;
+
+    results.sort_by_key(|(index, ..)| *index);
+    results
+        .into_iter()
+        .map(|(_, request, result)| (request, result))
+        .collect()
+}
+
+/// Returns a token that is cancelled when the user presses Ctrl+C.
+pub fn cancel_on_ctrl_c() -> CancellationToken {
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            token.cancel();
+        }
+    });
+
+    cancel
+}
+
+/// Returns a token that is cancelled when the process is asked to stop: on Ctrl+C, or when a
+/// service manager sends `SIGTERM` on Unix.
+pub fn cancel_on_shutdown() -> CancellationToken {
+    let cancel = cancel_on_ctrl_c();
+
+    #[cfg(unix)]
+    {
+        use ::tokio::signal::unix::{SignalKind, signal};
+
+        let token = cancel.clone();
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::spawn(async move {
+                    if terminate.recv().await.is_some() {
+                        token.cancel();
+                    }
+                });
+            }
+            Err(e) => tracing::debug!("Failed to listen for SIGTERM: {e}"),
+        }
+    }
+
+    cancel
+}
+
+/// Fetches the weather of `address` on `date` (today if `None`).
+///
+/// Today is requested with `get_weather`, a past date from the provider as a
+/// `HistoricalProvider` and a future date as a `ForecastProvider`. Commands validate the dates
+/// given by the user beforehand (see `validate_date`).
+///
+/// # Errors
+///
+/// Returns an error if `date` cannot be parsed, or `Error::UnsupportedDate` if the provider has
+/// no capability for the date.
+pub async fn fetch_weather(
+    weather_provider: &dyn WeatherProvider,
+    provider: &Provider,
+    api_key: Option<&str>,
+    address: &str,
+    date: Option<&str>,
+) -> weather_providers::Result<WeatherInfo> {
+    let Some(date) = date.map(str::trim).filter(|d| !d.is_empty()) else {
+        return weather_provider.get_weather(api_key, address).await;
+    };
+
+    // Dates in the `YYYY-MM-DD` format sort as strings
+    let day = parse_date(date)?.format("%Y-%m-%d").to_string();
+    let today = normalize_date(None::<&str>);
+    let unsupported = |reason: &'static str| weather_providers::Error::UnsupportedDate {
+        provider: provider.name().into(),
+        date: day.clone(),
+        reason: reason.into(),
+    };
+
+    match day.cmp(&today) {
+        Ordering::Less => match weather_provider.as_historical() {
+            Some(historical) => historical.get_history(api_key, address, &day).await,
+            None => Err(unsupported("it does not report past dates")),
+        },
+        Ordering::Greater => match weather_provider.as_forecast() {
+            Some(forecast) => forecast.get_forecast(api_key, address, &day).await,
+            None => Err(unsupported("it does not forecast")),
+        },
+        Ordering::Equal => weather_provider.get_weather(api_key, address).await,
+    }
+}
+
+fn cancelled() -> Error {
+    "Request cancelled.".into()
+}
+
+/// The outcome of a multi-location command: how many locations succeeded, and why the others
+/// failed.
+///
+/// Every multi-location command ends with `finish`, so they share the same summary and exit
+/// codes: 0 when all locations succeeded, 6 when some failed and 4 when all failed.
+#[derive(Debug, Default)]
+pub struct FetchOutcome {
+    succeeded: usize,
+    /// The failed locations, each with its error, e.g. "Paris: Location not found".
+    failures: Vec<String>,
+}
+
+impl FetchOutcome {
+    /// Tallies the results of the locations, each paired with its label (an address or alias).
+    pub fn of<'a, T: 'a, E: Display + 'a>(
+        results: impl IntoIterator<Item = (&'a str, &'a std::result::Result<T, E>)>,
+    ) -> Self {
+        let mut outcome = Self::default();
+        for (label, result) in results {
+            match result {
+                Ok(_) => outcome.succeeded += 1,
+                Err(e) => outcome.failures.push(format!("{label}: {e}")),
+            }
+        }
+        outcome
+    }
+
+    /// Returns the summary line, e.g. "3 succeeded, 1 failed (Paris: Location not found)".
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} succeeded, {} failed",
+            self.succeeded,
+            self.failures.len()
+        );
+        if !self.failures.is_empty() {
+            summary.push_str(&format!(" ({})", self.failures.join("; ")));
+        }
+        summary
+    }
+
+    /// Prints the summary to `stderr` if every location succeeded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PartialFailure` if some locations failed, or `Error::AllFailed` if all
+    /// of them did, with the summary as the message.
+    pub fn finish(self) -> Result<()> {
+        match (self.succeeded, self.failures.len()) {
+            (_, 0) => {
+                eprintln!("{}", self.summary());
+                Ok(())
+            }
+            (0, _) => Err(Error::AllFailed(self.summary())),
+            _ => Err(Error::PartialFailure(self.summary())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixtures;
+    use ::async_trait::async_trait;
+    use ::std::sync::atomic::{AtomicUsize, Ordering};
+    use ::weather_providers::{HistoricalProvider, Provider, ProviderInfo, create_provider};
+
+    /// Records the number of requests in flight.
+    #[derive(Default)]
+    struct InFlight {
+        current: AtomicUsize,
+        max: AtomicUsize,
+    }
+
+    impl InFlight {
+        async fn fetch(&self, city: &str, delay_ms: u64) -> Result<WeatherInfo> {
+            let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max.fetch_max(current, Ordering::SeqCst);
+
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            self.current.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(weather(city))
+        }
+    }
+
+    fn weather(city: &str) -> WeatherInfo {
+        WeatherInfo {
+            city: city.to_string(),
+            ..fixtures::weather()
+        }
+    }
+
+    /// Reports history under the city "history" and everything else under "current".
+    struct Historical;
+
+    #[async_trait]
+    impl WeatherProvider for Historical {
+        async fn get_weather(
+            &self,
+            _provider_key: Option<&str>,
+            _address: &str,
+        ) -> weather_providers::Result<WeatherInfo> {
+            Ok(weather("current"))
+        }
+
+        async fn health_check(
+            &self,
+            _provider_key: Option<&str>,
+        ) -> weather_providers::Result<Duration> {
+            Ok(Duration::ZERO)
+        }
+
+        fn info(&self) -> ProviderInfo {
+            create_provider(Provider::Mock).info()
+        }
+
+        fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+            Some(self)
+        }
+    }
+
+    #[async_trait]
+    impl HistoricalProvider for Historical {
+        async fn get_history(
+            &self,
+            _provider_key: Option<&str>,
+            _address: &str,
+            _date: &str,
+        ) -> weather_providers::Result<WeatherInfo> {
+            Ok(weather("history"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_weather_by_capability() {
+        let fetch =
+            async |date| fetch_weather(&Historical, &Provider::Mock, None, "London", date).await;
+        let city = async |date| fetch(date).await.unwrap().city;
+
+        assert_eq!(city(Some("2000-01-01")).await, "history");
+        let today = normalize_date(None::<&str>);
+        assert_eq!(city(Some(&today)).await, "current");
+        assert_eq!(city(None).await, "current");
+        assert_eq!(city(Some(" ")).await, "current");
+
+        // Without the forecast capability, future dates are refused
+        let error = fetch(Some("2999-01-01")).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "'MockWeather' has no data for 2999-01-01: it does not forecast"
+        );
+    }
+
+    fn limits(concurrency: usize) -> FetchLimits {
+        FetchLimits {
+            concurrency,
+            ..FetchLimits::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bound_and_order() {
+        let in_flight = InFlight::default();
+        // Earlier requests take longer, so they complete last
+        let requests = (0..10u64)
+            .map(|i| (format!("City {i}"), 50 - i * 5))
+            .collect();
+
+        let results = fetch_many(
+            requests,
+            limits(3),
+            &CancellationToken::new(),
+            async |(city, ms)| in_flight.fetch(city, *ms).await,
+        )
+        .await;
+
+        assert_eq!(in_flight.max.load(Ordering::SeqCst), 3);
+        assert_eq!(results.len(), 10);
+        for (i, ((city, _), result)) in results.iter().enumerate() {
+            assert_eq!(city, &format!("City {i}"));
+            assert_eq!(&result.as_ref().unwrap().city, city);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_each_in_completion_order() {
+        let in_flight = InFlight::default();
+        let mut completed = Vec::new();
+
+        let results = fetch_each(
+            vec![("Slow", 60), ("Fast", 1), ("Medium", 30)],
+            limits(3),
+            &CancellationToken::new(),
+            async |(city, ms)| in_flight.fetch(city, *ms).await,
+            |(city, _), result| {
+                assert!(result.is_ok());
+                completed.push(*city);
+            },
+        )
+        .await;
+
+        assert_eq!(completed, ["Fast", "Medium", "Slow"]);
+        let cities = results
+            .iter()
+            .map(|((city, _), _)| *city)
+            .collect::<Vec<_>>();
+        assert_eq!(cities, ["Slow", "Fast", "Medium"]);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let in_flight = InFlight::default();
+        let limits = FetchLimits {
+            concurrency: 2,
+            timeout: Duration::from_millis(20),
+        };
+
+        let results = fetch_many(
+            vec![("Fast", 1), ("Slow", 500)],
+            limits,
+            &CancellationToken::new(),
+            async |(city, ms)| in_flight.fetch(city, *ms).await,
+        )
+        .await;
+
+        assert!(results[0].1.is_ok());
+        assert!(
+            results[1]
+                .1
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .starts_with("Request timed out")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancellation() {
+        let in_flight = InFlight::default();
+        let cancel = CancellationToken::new();
+
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+
+        let results = fetch_many(
+            vec![("A", 1), ("B", 500), ("C", 500)],
+            limits(1),
+            &cancel,
+            async |(city, ms)| in_flight.fetch(city, *ms).await,
+        )
+        .await;
+
+        assert!(results[0].1.is_ok());
+        for (_, result) in &results[1..] {
+            assert_eq!(
+                result.as_ref().unwrap_err().to_string(),
+                "Request cancelled."
+            );
+        }
+    }
+
+    #[test]
+    fn test_outcome() {
+        let results: [(&str, std::result::Result<(), &str>); 4] = [
+            ("London", Ok(())),
+            ("Paris", Err("Location not found")),
+            ("Tokyo", Ok(())),
+            ("Rome", Ok(())),
+        ];
+        let outcome = FetchOutcome::of(results.iter().map(|(label, result)| (*label, result)));
+        assert_eq!(
+            outcome.summary(),
+            "3 succeeded, 1 failed (Paris: Location not found)"
+        );
+        assert!(matches!(outcome.finish(), Err(Error::PartialFailure(_))));
+
+        let failed = [("Paris", Err::<(), _>("timeout")), ("Rome", Err("timeout"))];
+        let outcome = FetchOutcome::of(failed.iter().map(|(label, result)| (*label, result)));
+        assert_eq!(
+            outcome.summary(),
+            "0 succeeded, 2 failed (Paris: timeout; Rome: timeout)"
+        );
+        assert!(matches!(outcome.finish(), Err(Error::AllFailed(_))));
+
+        let outcome = FetchOutcome::of(results[..1].iter().map(|(label, result)| (*label, result)));
+        assert_eq!(outcome.summary(), "1 succeeded, 0 failed");
+        assert!(outcome.finish().is_ok());
+    }
+}
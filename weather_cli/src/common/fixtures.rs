@@ -0,0 +1,8 @@
+//! Test data shared by the unit tests.
+
+use ::weather_providers::WeatherInfo;
+
+/// A reading in London, adjusted with the struct update syntax.
+pub fn weather() -> WeatherInfo {
+    WeatherInfo::new("UK", "London", "2024-01-01", 50.0, 80)
+}
@@ -0,0 +1,257 @@
+use crate::common::*;
+use ::std::{
+    fs,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+use ::tracing::debug;
+
+/// How long a lock file may stay without the ID of its process before it is taken over, as it
+/// was left by a process that exited right after creating it.
+const UNWRITTEN_LOCK_AGE: Duration = Duration::from_secs(1);
+
+/// The time between two attempts to acquire a lock.
+pub const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A lock shared by the processes using the same file, held until dropped.
+///
+/// The lock is a `.lock` file next to the locked file, created exclusively and containing
+/// the ID of the process holding it. A lock whose process is no longer running is taken over.
+#[derive(Debug)]
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Acquires the lock of `path`, retrying until `timeout` elapses.
+    ///
+    /// Waiting for a contended lock yields to the runtime instead of blocking its thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigLocked` with the ID of the holding process if the lock is still
+    /// held after `timeout`, or an I/O error if the lock file cannot be created.
+    pub async fn acquire(path: &Path, timeout: Duration) -> Result<Self> {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        let lock_path = path.with_file_name(name);
+
+        if let Some(parent) = lock_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock_path)
+            {
+                Ok(mut file) => {
+                    let lock = Self { path: lock_path };
+                    write!(file, "{}", std::process::id())?;
+                    return Ok(lock);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            let holder = fs::read_to_string(&lock_path).ok();
+            if holder
+                .as_deref()
+                .is_some_and(|pid| is_stale(&lock_path, pid))
+            {
+                // Another process may have taken the stale lock over in the meantime, so only
+                // the lock that was found stale is removed
+                if fs::read_to_string(&lock_path).ok() == holder {
+                    debug!("Removing stale lock file {lock_path:?}");
+                    let _ = fs::remove_file(&lock_path);
+                }
+                continue;
+            }
+
+            if Instant::now() >= deadline {
+                let pid = holder.and_then(|pid| pid.trim().parse().ok());
+                return Err(Error::ConfigLocked(pid));
+            }
+
+            tokio::time::sleep(LOCK_RETRY_INTERVAL).await;
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            debug!("Failed to remove lock file: {e:?}")
+        }
+    }
+}
+
+/// Checks whether a lock file with the content `pid` was left by a process that is no longer
+/// running.
+fn is_stale(lock_path: &Path, pid: &str) -> bool {
+    match pid.trim().parse() {
+        Ok(pid) => !is_running(pid),
+        // The holder has not written its ID yet, unless it exited right after creating the lock
+        Err(_) => fs::metadata(lock_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > UNWRITTEN_LOCK_AGE),
+    }
+}
+
+/// Checks whether the process with the ID `pid` is running. A process whose state cannot be
+/// determined is considered running.
+#[cfg(target_os = "linux")]
+fn is_running(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+/// Checks whether the process with the ID `pid` is running. A process whose state cannot be
+/// determined is considered running.
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_running(pid: u32) -> bool {
+    use ::std::process::{Command, Stdio};
+
+    // Signal 0 only checks that the process exists; a process of another user is reported
+    // as not permitted, which still means it is running
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .map_or(true, |output| {
+            output.status.success()
+                || String::from_utf8_lossy(&output.stderr).contains("not permitted")
+        })
+}
+
+/// Checks whether the process with the ID `pid` is running. A process whose state cannot be
+/// determined is considered running.
+#[cfg(windows)]
+fn is_running(pid: u32) -> bool {
+    use ::std::process::Command;
+
+    Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+        .output()
+        .map_or(true, |output| {
+            String::from_utf8_lossy(&output.stdout).contains(&format!("\"{pid}\""))
+        })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_running(_pid: u32) -> bool {
+    true
+}
+
+/// Writes a file atomically.
+///
+/// The content is written to a temporary file next to the target (same directory, `.tmp`
+/// extension), flushed and synced to disk, and then renamed over the target. Readers therefore
+/// observe either the old file or the complete new one, never a partially written file.
+///
+/// # Arguments
+///
+/// * `path` - The destination file path. Missing parent directories are created.
+/// * `write` - A closure that writes the content into the provided buffered writer.
+///
+/// # Errors
+///
+/// Returns an error if any I/O operation fails or if the `write` closure returns an error.
+/// The temporary file is removed when the final rename fails.
+pub fn write_atomic<F>(path: &Path, write: F) -> Result<()>
+where
+    F: FnOnce(&mut BufWriter<fs::File>) -> Result<()>,
+{
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp_path = path.with_extension("tmp");
+
+    {
+        let file = fs::File::create(&tmp_path)?;
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+    }
+
+    fs::rename(&tmp_path, path).inspect_err(|_| {
+        if let Err(e) = fs::remove_file(&tmp_path) {
+            debug!("Failed to remove temporary file: {e:?}")
+        }
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic_replaces_content() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("weather-cli-test-fs-{}", std::process::id()));
+        let path = temp_dir.join("nested").join("weather.prom");
+
+        write_atomic(&path, |w| Ok(w.write_all(b"first")?)).unwrap();
+        write_atomic(&path, |w| Ok(w.write_all(b"second")?)).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert!(!path.with_extension("tmp").exists());
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_file_lock() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("weather-cli-test-lock-{}", std::process::id()));
+        let path = temp_dir.join("config.json");
+        let lock_path = temp_dir.join("config.json.lock");
+
+        {
+            let _lock = FileLock::acquire(&path, Duration::ZERO).await.unwrap();
+            assert_eq!(
+                fs::read_to_string(&lock_path).unwrap(),
+                std::process::id().to_string()
+            );
+
+            let error = FileLock::acquire(&path, Duration::from_millis(50))
+                .await
+                .unwrap_err();
+            assert!(matches!(error, Error::ConfigLocked(Some(pid)) if pid == std::process::id()));
+        }
+        assert!(!lock_path.exists());
+
+        // A lock of a running process is kept, however old it is
+        fs::write(&lock_path, std::process::id().to_string()).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&lock_path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+        let error = FileLock::acquire(&path, Duration::ZERO).await.unwrap_err();
+        assert!(matches!(error, Error::ConfigLocked(Some(_))));
+
+        // A lock left over by a process that is no longer running is taken over
+        fs::write(&lock_path, u32::MAX.to_string()).unwrap();
+        drop(FileLock::acquire(&path, Duration::ZERO).await.unwrap());
+
+        // So is a lock that never received the ID of its process
+        let file = fs::File::create(&lock_path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+        drop(file);
+        FileLock::acquire(&path, Duration::ZERO).await.unwrap();
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+}
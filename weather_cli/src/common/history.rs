@@ -0,0 +1,449 @@
+//! # Observation History
+//!
+//! Keeps a local, append-only log of fetched weather observations (one JSON object per line),
+//! so later requests can be compared against earlier ones.
+//!
+//! The `privacy` settings limit the location data of the entries: coordinates are rounded and,
+//! if addresses are not stored, the location key is hashed and the place names are removed.
+
+use crate::{common::*, models::config::PrivacySettings};
+use ::chrono::{DateTime, Utc};
+use ::serde::{Deserialize, Serialize};
+use ::sha2::{Digest, Sha256};
+use ::std::{
+    fs::{self, OpenOptions},
+    io::{BufRead, BufReader, ErrorKind, Write},
+    path::PathBuf,
+};
+use ::weather_providers::WeatherInfo;
+
+/// File name of the history log.
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// Setting this environment variable to a path overrides the location of the history file.
+pub const HISTORY_ENV: &str = "WEATHER_HISTORY";
+
+/// A single recorded observation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Normalized location key (see `location_key`).
+    pub location_key: String,
+    /// The ID of the provider that returned the observation (e.g., "ow").
+    pub provider: String,
+    /// When the observation was fetched.
+    pub fetched_at: DateTime<Utc>,
+    /// The observation itself.
+    pub weather: WeatherInfo,
+}
+
+impl HistoryEntry {
+    /// Creates an entry for the observation of `address`, keeping only the location data
+    /// allowed by the privacy settings.
+    pub fn new(
+        address: &str,
+        provider: &str,
+        fetched_at: DateTime<Utc>,
+        weather: &WeatherInfo,
+        privacy: &PrivacySettings,
+    ) -> Self {
+        let mut weather = weather.clone();
+        if !privacy.store_addresses {
+            weather.city.clear();
+            weather.country.clear();
+        }
+
+        Self {
+            location_key: persisted_key(address, privacy),
+            provider: provider.to_string(),
+            fetched_at,
+            weather,
+        }
+    }
+}
+
+/// The history store backed by a JSON Lines file.
+#[derive(Clone)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    /// Creates a history store for the given file path. The file is created on the first append.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Appends an observation to the history.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file cannot be created or written.
+    pub fn append(&self, entry: &HistoryEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{line}")?;
+
+        Ok(())
+    }
+
+    /// Returns the most recent entry for `location_key` fetched strictly before `before`.
+    ///
+    /// Lines that cannot be parsed are skipped, so a damaged history never breaks a request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file exists but cannot be read.
+    pub fn latest_before(
+        &self,
+        location_key: &str,
+        before: DateTime<Utc>,
+    ) -> Result<Option<HistoryEntry>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => Err(e)?,
+        };
+
+        let mut latest: Option<HistoryEntry> = None;
+
+        for line in BufReader::new(file).lines() {
+            let Ok(entry) = serde_json::from_str::<HistoryEntry>(&line?) else {
+                continue;
+            };
+
+            if entry.location_key == location_key
+                && entry.fetched_at < before
+                && latest
+                    .as_ref()
+                    .is_none_or(|l| l.fetched_at <= entry.fetched_at)
+            {
+                latest = Some(entry);
+            }
+        }
+
+        Ok(latest)
+    }
+
+    /// Returns the entries for `location_key` fetched at or after `since`, oldest first.
+    ///
+    /// Lines that cannot be parsed are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file exists but cannot be read.
+    pub fn entries_since(
+        &self,
+        location_key: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<HistoryEntry>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => Err(e)?,
+        };
+
+        let mut entries = Vec::new();
+
+        for line in BufReader::new(file).lines() {
+            let Ok(entry) = serde_json::from_str::<HistoryEntry>(&line?) else {
+                continue;
+            };
+
+            if entry.location_key == location_key && entry.fetched_at >= since {
+                entries.push(entry);
+            }
+        }
+
+        entries.sort_by_key(|entry| entry.fetched_at);
+        Ok(entries)
+    }
+
+    /// Returns the distinct locations of the history as "City, Country", most recent first.
+    ///
+    /// Lines that cannot be parsed are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file exists but cannot be read.
+    pub fn locations(&self) -> Result<Vec<String>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => Err(e)?,
+        };
+
+        let mut locations = Vec::<String>::new();
+
+        for line in BufReader::new(file).lines() {
+            let Ok(entry) = serde_json::from_str::<HistoryEntry>(&line?) else {
+                continue;
+            };
+            // Entries recorded without place names
+            if entry.weather.city.is_empty() {
+                continue;
+            }
+
+            let location = format!("{}, {}", entry.weather.city, entry.weather.country);
+            locations.retain(|l| !l.eq_ignore_ascii_case(&location));
+            locations.push(location);
+        }
+
+        locations.reverse();
+        Ok(locations)
+    }
+}
+
+/// Normalizes an address into a key identifying the location in the history.
+///
+/// The key is case-insensitive and ignores redundant whitespace, so "London,  UK" and
+/// "london, uk" refer to the same location.
+pub fn location_key(address: &str) -> String {
+    address
+        .split(',')
+        .map(|part| part.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join(",")
+        .to_lowercase()
+}
+
+/// Returns the key under which the observations of `address` are persisted.
+///
+/// The key is the `location_key` of the address, with coordinates rounded to
+/// `round_coordinates_to` decimal places, so nearby coordinates share a key. If addresses are
+/// not stored, the key is replaced by its SHA-256 hash (e.g., "sha256:9f86d0...").
+pub fn persisted_key(address: &str, privacy: &PrivacySettings) -> String {
+    let mut key = location_key(address);
+
+    if let Some(places) = privacy.round_coordinates_to
+        && let Some((lat, lon)) = key.split_once(',')
+        && let (Ok(lat), Ok(lon)) = (lat.trim().parse::<f64>(), lon.trim().parse::<f64>())
+    {
+        let factor = 10f64.powi(i32::from(places));
+        // Adding zero turns a rounded -0.0 into 0.0, so both sides of zero share a key
+        let round = |value: f64| (value * factor).round() / factor + 0.0;
+        let places = usize::from(places);
+        key = format!("{:.places$},{:.places$}", round(lat), round(lon));
+    }
+
+    if !privacy.store_addresses {
+        key = format!("sha256:{:x}", Sha256::digest(key.as_bytes()));
+    }
+
+    key
+}
+
+/// Resolves the history file path: the `WEATHER_HISTORY` path if set, or a file next to the
+/// logs of the application.
+pub fn resolve_history_file() -> PathBuf {
+    if let Some(path) = std::env::var_os(HISTORY_ENV).filter(|path| !path.is_empty()) {
+        PathBuf::from(path)
+    } else if cfg!(debug_assertions) {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        if let Some(parent) = path.parent() {
+            path = parent.to_path_buf();
+        }
+        path.join(".dev").join(HISTORY_FILE)
+    } else {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(env!("CARGO_PKG_NAME"))
+            .join(HISTORY_FILE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixtures;
+    use ::chrono::{TimeZone, Timelike};
+
+    fn entry(key: &str, hour: u32, temperature: f32) -> HistoryEntry {
+        HistoryEntry {
+            location_key: key.to_string(),
+            provider: "mock".to_string(),
+            fetched_at: Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap(),
+            weather: WeatherInfo {
+                temperature,
+                ..fixtures::weather()
+            },
+        }
+    }
+
+    #[test]
+    fn test_latest_before() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("weather-cli-test-history-{}", std::process::id()));
+        let history = History::new(temp_dir.join(HISTORY_FILE));
+        let noon = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        assert!(history.latest_before("london", noon).unwrap().is_none());
+
+        history.append(&entry("london", 8, 50.0)).unwrap();
+        history.append(&entry("london", 10, 52.0)).unwrap();
+        history.append(&entry("paris", 11, 60.0)).unwrap();
+        history.append(&entry("london", 13, 55.0)).unwrap();
+
+        let latest = history.latest_before("london", noon).unwrap().unwrap();
+        assert_eq!(latest.weather.temperature, 52.0);
+        assert_eq!(latest.fetched_at.hour(), 10);
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn test_entries_without_temperature() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "weather-cli-test-history-nan-{}",
+            std::process::id()
+        ));
+        let history = History::new(temp_dir.join(HISTORY_FILE));
+        let noon = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        // Sun times and air quality providers report no temperature, written as null
+        history.append(&entry("london", 10, f32::NAN)).unwrap();
+
+        let latest = history.latest_before("london", noon).unwrap().unwrap();
+        assert!(latest.weather.temperature.is_nan());
+        assert_eq!(latest.weather.humidity, Some(80));
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn test_entries_since() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "weather-cli-test-history-entries-{}",
+            std::process::id()
+        ));
+        let history = History::new(temp_dir.join(HISTORY_FILE));
+        let nine = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+
+        assert!(history.entries_since("london", nine).unwrap().is_empty());
+
+        history.append(&entry("london", 13, 55.0)).unwrap();
+        history.append(&entry("london", 8, 50.0)).unwrap();
+        history.append(&entry("paris", 11, 60.0)).unwrap();
+        history.append(&entry("london", 9, 52.0)).unwrap();
+
+        let temperatures = history
+            .entries_since("london", nine)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.weather.temperature)
+            .collect::<Vec<_>>();
+        assert_eq!(temperatures, [52.0, 55.0]);
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn test_locations() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "weather-cli-test-history-locations-{}",
+            std::process::id()
+        ));
+        let history = History::new(temp_dir.join(HISTORY_FILE));
+
+        assert!(history.locations().unwrap().is_empty());
+
+        let mut paris = entry("paris", 9, 60.0);
+        paris.weather.city = "Paris".to_string();
+        paris.weather.country = "FR".to_string();
+
+        history.append(&entry("london", 8, 50.0)).unwrap();
+        history.append(&paris).unwrap();
+        history.append(&entry("london", 10, 52.0)).unwrap();
+
+        assert_eq!(history.locations().unwrap(), ["London, UK", "Paris, FR"]);
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn test_persisted_key() {
+        let rounded = PrivacySettings {
+            round_coordinates_to: Some(2),
+            ..PrivacySettings::default()
+        };
+        assert_eq!(
+            persisted_key("51.507351, -0.127758", &rounded),
+            "51.51,-0.13"
+        );
+        assert_eq!(persisted_key("-0.001,0.004", &rounded), "0.00,0.00");
+        // Other addresses are not affected by rounding
+        assert_eq!(persisted_key("London, UK", &rounded), "london,uk");
+        assert_eq!(
+            persisted_key("London, UK", &PrivacySettings::default()),
+            "london,uk"
+        );
+
+        let hashed = PrivacySettings {
+            store_addresses: false,
+            ..rounded
+        };
+        let key = persisted_key("51.507351,-0.127758", &hashed);
+        assert!(key.starts_with("sha256:") && key.len() == 71, "{key}");
+        assert_eq!(persisted_key("51.5074,-0.1276", &hashed), key);
+        assert_ne!(persisted_key("48.8566,2.3522", &hashed), key);
+    }
+
+    #[test]
+    fn test_privacy_of_persisted_entries() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "weather-cli-test-history-privacy-{}",
+            std::process::id()
+        ));
+        let history = History::new(temp_dir.join(HISTORY_FILE));
+        let privacy = PrivacySettings {
+            round_coordinates_to: Some(3),
+            store_addresses: false,
+        };
+        let weather = entry("", 8, 50.0).weather;
+        let at = |hour| Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap();
+
+        let first = HistoryEntry::new("51.507351,-0.127758", "mock", at(8), &weather, &privacy);
+        history.append(&first).unwrap();
+
+        let contents = fs::read_to_string(temp_dir.join(HISTORY_FILE)).unwrap();
+        for private in ["51.507", "-0.127", "London", "UK"] {
+            assert!(!contents.contains(private), "{private} in {contents}");
+        }
+        assert!(contents.contains("sha256:"), "{contents}");
+
+        // A later request for nearby coordinates finds the observation
+        let key = persisted_key("51.50738,-0.12779", &privacy);
+        let latest = history.latest_before(&key, at(12)).unwrap().unwrap();
+        assert_eq!(latest.weather.temperature, 50.0);
+
+        // Entries without place names are not offered for completion
+        assert!(history.locations().unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn test_rounded_entries_keep_place_names() {
+        let privacy = PrivacySettings {
+            round_coordinates_to: Some(1),
+            ..PrivacySettings::default()
+        };
+        let weather = entry("", 8, 50.0).weather;
+        let entry = HistoryEntry::new("51.5074,-0.1276", "ow", Utc::now(), &weather, &privacy);
+
+        assert_eq!(entry.location_key, "51.5,-0.1");
+        assert_eq!(entry.weather.city, "London");
+    }
+
+    #[test]
+    fn test_location_key() {
+        assert_eq!(location_key("London,  UK"), "london,uk");
+        assert_eq!(location_key(" new   York , US"), "new york,us");
+    }
+}
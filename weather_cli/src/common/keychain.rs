@@ -0,0 +1,179 @@
+//! # Keychain Storage
+//!
+//! Stores provider API keys and request signing secrets in the OS keychain (macOS Keychain,
+//! Windows Credential Manager, Linux kernel keyring) instead of the plain-text configuration
+//! file.
+//!
+//! Keychain support is optional and requires the `keyring` Cargo feature. Without it, every
+//! operation fails with an error explaining how to enable it.
+
+use crate::{
+    common::*,
+    models::config::{ProviderConfig, SigningConfig},
+};
+
+/// The service name under which API keys are stored in the keychain.
+#[cfg(feature = "keyring")]
+const KEYCHAIN_SERVICE: &str = "weather-cli";
+
+/// Resolves the API key of a provider from its configuration.
+///
+/// Keys marked with `key_ref: "keychain"` are loaded from the OS keychain; otherwise the
+/// plain-text key (or the combined client credentials) is returned if it is not empty.
+///
+/// # Errors
+///
+/// Returns an error if the key is stored in the keychain and cannot be loaded.
+pub fn resolve_key(provider_id: &str, config: &ProviderConfig) -> Result<Option<String>> {
+    if config.is_keychain() {
+        return load_key(provider_id).map(Some);
+    }
+
+    Ok(config.plain_key())
+}
+
+/// Resolves the request signing secret of a provider from its signing configuration.
+///
+/// Secrets marked with `secret_ref: "keychain"` are loaded from the OS keychain entry
+/// `<PROVIDER_ID>:signing`; otherwise the plain-text secret is returned.
+///
+/// # Errors
+///
+/// Returns an error if neither is configured, or the secret cannot be loaded from the keychain.
+pub fn resolve_signing_secret(provider_id: &str, config: &SigningConfig) -> Result<String> {
+    if config.is_keychain() {
+        return load_key(&signing_entry_name(provider_id));
+    }
+
+    config
+        .secret
+        .clone()
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| {
+            format!(
+                "No request signing secret for '{provider_id}'. Set \"secret\" in its \"signing\" \
+             configuration, or set \"secret_ref\" to \"keychain\" and store the secret in the \
+             OS keychain as '{}'.",
+                signing_entry_name(provider_id)
+            )
+            .into()
+        })
+}
+
+/// Returns the keychain entry name of the request signing secret of a provider.
+fn signing_entry_name(provider_id: &str) -> String {
+    format!("{provider_id}:signing")
+}
+
+/// Stores the API key of a provider in the OS keychain.
+///
+/// # Errors
+///
+/// Returns an error if the keychain is locked, not accessible, or the build lacks keychain support.
+#[cfg(feature = "keyring")]
+pub fn store_key(provider_id: &str, secret: &str) -> Result<()> {
+    store(&entry(provider_id)?, provider_id, secret)
+}
+
+/// Loads the API key of a provider from the OS keychain.
+///
+/// # Errors
+///
+/// Returns an error if the entry is missing, the keychain is locked or not accessible,
+/// or the build lacks keychain support.
+#[cfg(feature = "keyring")]
+pub fn load_key(provider_id: &str) -> Result<String> {
+    load(&entry(provider_id)?, provider_id)
+}
+
+#[cfg(feature = "keyring")]
+fn entry(provider_id: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, provider_id).map_err(|e| map_error(e, provider_id))
+}
+
+#[cfg(feature = "keyring")]
+fn store(entry: &keyring::Entry, provider_id: &str, secret: &str) -> Result<()> {
+    entry
+        .set_password(secret)
+        .map_err(|e| map_error(e, provider_id))
+}
+
+#[cfg(feature = "keyring")]
+fn load(entry: &keyring::Entry, provider_id: &str) -> Result<String> {
+    entry.get_password().map_err(|e| map_error(e, provider_id))
+}
+
+#[cfg(feature = "keyring")]
+fn map_error(error: keyring::Error, provider_id: &str) -> Error {
+    match error {
+        keyring::Error::NoEntry => format!(
+            "API key for '{provider_id}' not found in the OS keychain. \
+             Set it again using: 'weather provider {provider_id} --key <API_KEY> --keychain'"
+        )
+        .into(),
+        keyring::Error::NoStorageAccess(e) | keyring::Error::PlatformFailure(e) => format!(
+            "The OS keychain is locked or not accessible: {e}. \
+             Unlock it and try again."
+        )
+        .into(),
+        e => format!("Keychain error for '{provider_id}': {e}").into(),
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn store_key(_provider_id: &str, _secret: &str) -> Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn load_key(_provider_id: &str) -> Result<String> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "keyring"))]
+fn unsupported() -> Error {
+    "This build of weather has no keychain support. \
+     Rebuild it with '--features keyring' to store API keys in the OS keychain."
+        .into()
+}
+
+#[cfg(all(test, feature = "keyring"))]
+mod tests {
+    use super::*;
+    use ::keyring::{mock, mock::MockCredential};
+
+    fn mock_entry() -> keyring::Entry {
+        keyring::set_default_credential_builder(mock::default_credential_builder());
+        keyring::Entry::new(KEYCHAIN_SERVICE, "ow").unwrap()
+    }
+
+    #[test]
+    fn test_store_and_load_roundtrip() {
+        let entry = mock_entry();
+
+        store(&entry, "ow", "secret-key").unwrap();
+
+        assert_eq!(load(&entry, "ow").unwrap(), "secret-key");
+    }
+
+    #[test]
+    fn test_missing_entry_error() {
+        let entry = mock_entry();
+
+        let error = load(&entry, "ow").unwrap_err().to_string();
+
+        assert!(error.contains("not found in the OS keychain"));
+        assert!(error.contains("weather provider ow --key <API_KEY> --keychain"));
+    }
+
+    #[test]
+    fn test_locked_keychain_error() {
+        let entry = mock_entry();
+        let mock: &MockCredential = entry.get_credential().downcast_ref().unwrap();
+        mock.set_error(keyring::Error::NoStorageAccess("locked".into()));
+
+        let error = load(&entry, "ow").unwrap_err().to_string();
+
+        assert!(error.contains("locked or not accessible"));
+    }
+}
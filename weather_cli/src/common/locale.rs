@@ -0,0 +1,129 @@
+//! # Locale Detection
+//!
+//! Derives default units and language from the system locale, so first-time users outside
+//! the US get metric units without any configuration.
+
+use crate::models::config::Units;
+
+/// Environment variables consulted for the locale, in order of precedence.
+const LOCALE_VARS: &[&str] = &["LC_ALL", "LANG"];
+
+/// Regions that use imperial units for everyday temperatures.
+const IMPERIAL_REGIONS: &[&str] = &["US", "LR", "MM"];
+
+/// Detects the default units and language from the system locale.
+///
+/// On Unix-like systems the locale is read from `LC_ALL` and `LANG`; on Windows the
+/// user's UI locale is used when those variables are not set.
+///
+/// # Returns
+///
+/// A tuple of the default `Units` and the language tag (e.g., "de-DE"), if a locale is set.
+pub fn detect_locale_defaults() -> (Units, Option<String>) {
+    let defaults = detect_locale_defaults_from(|var| std::env::var(var).ok());
+
+    #[cfg(windows)]
+    if defaults.1.is_none()
+        && let Some(locale) = sys_locale::get_locale()
+    {
+        return locale_defaults(&locale);
+    }
+
+    defaults
+}
+
+/// Detects the default units and language using the given environment lookup.
+fn detect_locale_defaults_from<F>(env: F) -> (Units, Option<String>)
+where
+    F: Fn(&str) -> Option<String>,
+{
+    LOCALE_VARS
+        .iter()
+        .filter_map(|var| env(var))
+        .find(|value| !value.trim().is_empty())
+        .map(|locale| locale_defaults(&locale))
+        .unwrap_or((Units::Imperial, None))
+}
+
+/// Maps a locale string such as `de_DE.UTF-8` or `en-GB` to units and a language tag.
+///
+/// The `C` and `POSIX` locales carry no regional information and keep the imperial defaults.
+fn locale_defaults(locale: &str) -> (Units, Option<String>) {
+    let name = locale.split(['.', '@']).next().unwrap_or_default();
+
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return (Units::Imperial, None);
+    }
+
+    let mut parts = name.split(['_', '-']);
+    let language = parts.next().unwrap_or_default().to_lowercase();
+    let region = parts.next().map(str::to_uppercase);
+
+    let units = match region.as_deref() {
+        Some(region) if IMPERIAL_REGIONS.contains(&region) => Units::Imperial,
+        Some(_) => Units::Metric,
+        None if language == "en" => Units::Imperial,
+        None => Units::Metric,
+    };
+
+    let lang = match region {
+        Some(region) => format!("{language}-{region}"),
+        None => language,
+    };
+
+    (units, Some(lang))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::std::collections::HashMap;
+
+    fn detect(vars: &[(&str, &str)]) -> (Units, Option<String>) {
+        let env = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>();
+        detect_locale_defaults_from(|var| env.get(var).cloned())
+    }
+
+    #[test]
+    fn test_en_us_is_imperial() {
+        assert_eq!(
+            detect(&[("LANG", "en_US.UTF-8")]),
+            (Units::Imperial, Some("en-US".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_de_de_is_metric() {
+        assert_eq!(
+            detect(&[("LANG", "de_DE.UTF-8")]),
+            (Units::Metric, Some("de-DE".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_en_gb_is_metric() {
+        assert_eq!(
+            detect(&[("LANG", "en_GB.UTF-8")]),
+            (Units::Metric, Some("en-GB".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_unset_locale_keeps_imperial() {
+        assert_eq!(detect(&[]), (Units::Imperial, None));
+        assert_eq!(detect(&[("LANG", "")]), (Units::Imperial, None));
+        assert_eq!(detect(&[("LANG", "C.UTF-8")]), (Units::Imperial, None));
+        assert_eq!(detect(&[("LC_ALL", "POSIX")]), (Units::Imperial, None));
+    }
+
+    #[test]
+    fn test_lc_all_takes_precedence() {
+        assert_eq!(
+            detect(&[("LANG", "en_US.UTF-8"), ("LC_ALL", "fr_FR.UTF-8")]),
+            (Units::Metric, Some("fr-FR".to_string()))
+        );
+    }
+}
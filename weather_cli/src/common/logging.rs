@@ -1,11 +1,15 @@
-use crate::common::*;
+use crate::{
+    common::*,
+    models::config::{LogLevel, LoggingSettings},
+};
+use ::clap::ValueEnum;
 use ::std::{
     fs::create_dir_all,
     path::{Path, PathBuf},
 };
 use ::tracing::level_filters::LevelFilter;
 use ::tracing_appender::{
-    non_blocking::WorkerGuard,
+    non_blocking::{NonBlocking, WorkerGuard},
     rolling::{RollingFileAppender, Rotation},
 };
 use ::tracing_subscriber::{
@@ -17,22 +21,125 @@ use ::tracing_subscriber::{
 
 /// Suffix for log files.
 const LOG_FILE_SUFFIX: &str = "log";
-/// Maximum number of log files to keep during rotation.
+/// Default maximum number of log files to keep during rotation.
 const MAX_LOG_FILES: usize = 10;
 
+/// File logging options, resolved from the configuration and CLI flags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogOptions {
+    /// Whether logs are written to files.
+    pub file_enabled: bool,
+    /// The minimum level of messages written to the log files.
+    pub level: LevelFilter,
+    /// The maximum number of daily log files to keep.
+    pub max_files: usize,
+    /// The directory for the log files.
+    pub directory: PathBuf,
+}
+
+impl Default for LogOptions {
+    /// Creates options matching the built-in behavior: `DEBUG` to 10 daily files.
+    fn default() -> Self {
+        Self {
+            file_enabled: true,
+            level: LevelFilter::DEBUG,
+            max_files: MAX_LOG_FILES,
+            directory: resolve_log_path(),
+        }
+    }
+}
+
+impl LogOptions {
+    /// Resolves the logging options.
+    ///
+    /// CLI flags take precedence over the `logging` configuration section, which takes
+    /// precedence over the defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - The `logging` configuration section, if present.
+    /// * `level` - The level given with `--log-level`.
+    /// * `no_log_file` - If `true` (`--no-log-file`), file logging is disabled.
+    ///
+    /// An invalid configured level falls back to the default level with a warning on stderr,
+    /// so that it does not fail every command, including the `config set` that would fix it.
+    pub fn resolve(
+        settings: Option<&LoggingSettings>,
+        level: Option<LogLevel>,
+        no_log_file: bool,
+    ) -> Self {
+        let mut options = Self::default();
+
+        if let Some(settings) = settings {
+            options.file_enabled = settings.enabled;
+            if let Some(level) = &settings.level {
+                match parse_level(level) {
+                    Ok(level) => options.level = level.into(),
+                    Err(e) => eprintln!("Warning: {e}. Using the default level."),
+                }
+            }
+            if let Some(max_files) = settings.max_files {
+                options.max_files = max_files;
+            }
+            if let Some(directory) = &settings.directory {
+                options.directory = directory.clone();
+            }
+        }
+
+        if let Some(level) = level {
+            options.level = level.into();
+        }
+        if no_log_file {
+            options.file_enabled = false;
+        }
+
+        options
+    }
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// Parses the `logging.level` of the configuration, ignoring the case.
+///
+/// # Errors
+///
+/// Returns an error listing the valid options if `level` is not a log level.
+pub fn parse_level(level: &str) -> Result<LogLevel> {
+    LogLevel::from_str(level, true).map_err(|_| {
+        let options = LogLevel::value_variants()
+            .iter()
+            .filter_map(|l| l.to_possible_value())
+            .map(|v| v.get_name().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Invalid log level '{level}' in the configuration. Valid options: {options}").into()
+    })
+}
+
 /// Initializes the logging system for the application.
 ///
-/// Configures `tracing` to output logs to both stdout (only in debug builds)
-/// and a rotating file appender.
+/// Configures `tracing` to output logs to stdout and, unless disabled, to a rotating
+/// file appender.
 ///
 /// # Arguments
 ///
-/// * `debug_mode` - If `true`, sets the log level to `DEBUG`. Otherwise, defaults to `INFO`.
+/// * `debug_mode` - If `true`, sets the stdout log level to `DEBUG`. Otherwise, defaults to `INFO`.
+/// * `options` - File logging options (see `LogOptions::resolve`).
 ///
 /// # Returns
 ///
-/// Returns a `Result` containing a `WorkerGuard`. This guard **must** be held
-/// by the main function (e.g., assigned to a variable like `_guard`) to ensure
+/// Returns a `Result` containing a `WorkerGuard` when file logging is enabled. This guard
+/// **must** be held by the main function (e.g., assigned to a variable like `_guard`) to ensure
 /// that logs are flushed to the file before the application exits.
 ///
 /// # Errors
@@ -41,27 +148,26 @@ const MAX_LOG_FILES: usize = 10;
 /// * The log directory cannot be created.
 /// * The file appender cannot be initialized.
 /// * The global subscriber cannot be set (e.g., if logging was already initialized).
-pub fn init(debug_mode: bool) -> Result<WorkerGuard> {
-    let logs_path = resolve_log_path();
-
-    create_dir_all(&logs_path).map_err(|e| format!("Failed to create log directory: {e}"))?;
-
+pub fn init(debug_mode: bool, options: &LogOptions) -> Result<Option<WorkerGuard>> {
     let stdout_filter = if debug_mode {
         LevelFilter::DEBUG
     } else {
         LevelFilter::INFO
     };
-    let file_filter = LevelFilter::DEBUG;
 
     let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = Vec::new();
 
     let stdout_layer = create_stdout_layer().with_filter(stdout_filter);
     layers.push(Box::new(stdout_layer));
 
-    let file_appender = create_file_appender(logs_path)?;
-    let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
-    let file_layer = create_file_layer(non_blocking).with_filter(file_filter);
-    layers.push(Box::new(file_layer));
+    let guard = match create_file_writer(options)? {
+        Some((non_blocking, guard)) => {
+            let file_layer = create_file_layer(non_blocking).with_filter(options.level);
+            layers.push(Box::new(file_layer));
+            Some(guard)
+        }
+        None => None,
+    };
 
     tracing_subscriber::registry()
         .with(layers)
@@ -71,6 +177,22 @@ pub fn init(debug_mode: bool) -> Result<WorkerGuard> {
     Ok(guard)
 }
 
+/// Creates the non-blocking log file writer, or `None` if file logging is disabled.
+///
+/// The log directory is only created when file logging is enabled.
+fn create_file_writer(options: &LogOptions) -> Result<Option<(NonBlocking, WorkerGuard)>> {
+    if !options.file_enabled {
+        return Ok(None);
+    }
+
+    create_dir_all(&options.directory)
+        .map_err(|e| format!("Failed to create log directory: {e}"))?;
+
+    let file_appender = create_file_appender(&options.directory, options.max_files)?;
+
+    Ok(Some(tracing_appender::non_blocking(file_appender)))
+}
+
 fn create_stdout_layer() -> impl Layer<Registry> {
     layer().compact().with_target(false).without_time()
 }
@@ -86,12 +208,12 @@ where
         .with_writer(writer)
 }
 
-fn create_file_appender<P: AsRef<Path>>(path: P) -> Result<RollingFileAppender> {
+fn create_file_appender<P: AsRef<Path>>(path: P, max_files: usize) -> Result<RollingFileAppender> {
     RollingFileAppender::builder()
         .rotation(Rotation::DAILY)
         .filename_prefix(env!("CARGO_PKG_NAME"))
         .filename_suffix(LOG_FILE_SUFFIX)
-        .max_log_files(MAX_LOG_FILES)
+        .max_log_files(max_files)
         .build(path)
         .map_err(|e| format!("Failed to create file appender: {e}").into())
 }
@@ -127,7 +249,7 @@ mod tests {
             std::env::temp_dir().join(format!("weather-cli-test-logs-{}", std::process::id()));
         create_dir_all(&temp_dir).expect("Failed to create temp dir");
 
-        let appender = create_file_appender(&temp_dir);
+        let appender = create_file_appender(&temp_dir, MAX_LOG_FILES);
         assert!(
             appender.is_ok(),
             "Should successfully create file appender in a valid directory"
@@ -135,4 +257,71 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(temp_dir);
     }
+
+    #[test]
+    fn test_file_logging_disabled() {
+        let directory =
+            std::env::temp_dir().join(format!("weather-cli-test-nologs-{}", std::process::id()));
+        let settings = LoggingSettings {
+            directory: Some(directory.clone()),
+            ..Default::default()
+        };
+
+        let options = LogOptions::resolve(Some(&settings), None, true);
+        assert!(!options.file_enabled);
+
+        // Disabled file logging must not touch the file system
+        assert!(create_file_writer(&options).unwrap().is_none());
+        assert!(!directory.exists());
+
+        let settings = LoggingSettings {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(!LogOptions::resolve(Some(&settings), None, false).file_enabled);
+    }
+
+    #[test]
+    fn test_custom_directory_and_level() {
+        let directory = std::env::temp_dir().join(format!(
+            "weather-cli-test-custom-logs-{}",
+            std::process::id()
+        ));
+        let settings = LoggingSettings {
+            level: Some("WARN".to_string()),
+            max_files: Some(3),
+            directory: Some(directory.clone()),
+            ..Default::default()
+        };
+
+        let options = LogOptions::resolve(Some(&settings), None, false);
+        assert_eq!(options.level, LevelFilter::WARN);
+        assert_eq!(options.max_files, 3);
+
+        assert!(create_file_writer(&options).unwrap().is_some());
+        assert!(directory.is_dir());
+
+        // The CLI flag overrides the configured level
+        let options = LogOptions::resolve(Some(&settings), Some(LogLevel::Trace), false);
+        assert_eq!(options.level, LevelFilter::TRACE);
+
+        let _ = std::fs::remove_dir_all(directory);
+    }
+
+    #[test]
+    fn test_invalid_level_falls_back() {
+        let settings = LoggingSettings {
+            level: Some("loud".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            LogOptions::resolve(Some(&settings), None, false).level,
+            LevelFilter::DEBUG
+        );
+
+        let error = parse_level("loud").unwrap_err().to_string();
+        assert!(error.contains("Invalid log level 'loud'"));
+        assert!(error.contains("error, warn, info, debug, trace"));
+    }
 }
@@ -1,9 +1,18 @@
+pub mod cities;
+pub mod coalesce;
 mod config;
 mod error;
+pub mod fetch;
+#[cfg(test)]
+pub mod fixtures;
+pub mod fs;
+pub mod history;
+pub mod keychain;
+pub mod locale;
 pub mod logging;
 mod state;
 
 pub use self::{
     error::{Error, Result},
-    state::APP_STATE,
+    state::{APP_STATE, disable_config},
 };
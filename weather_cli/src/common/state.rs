@@ -1,10 +1,36 @@
-use super::config::AppConfig;
-use ::std::{path::PathBuf, sync::LazyLock};
+use super::{
+    config::AppConfig,
+    history::{History, resolve_history_file},
+};
+use ::std::{
+    path::{Path, PathBuf},
+    sync::{
+        LazyLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
 pub static APP_STATE: LazyLock<AppState> = LazyLock::new(AppState::new);
 
+/// Setting this environment variable to a path overrides the location of the configuration file.
+pub const CONFIG_ENV: &str = "WEATHER_CONFIG";
+
+/// Setting this environment variable to a number of seconds overrides how long waiting for
+/// the configuration lock may take (2 seconds by default).
+pub const CONFIG_LOCK_TIMEOUT_ENV: &str = "WEATHER_CONFIG_LOCK_TIMEOUT";
+
+static NO_CONFIG: AtomicBool = AtomicBool::new(false);
+
+/// Makes `APP_STATE` use default settings without reading or writing the configuration file
+/// (`--no-config`). Only effective before `APP_STATE` is first accessed.
+pub fn disable_config() {
+    NO_CONFIG.store(true, Ordering::Relaxed);
+}
+
 pub struct AppState {
     pub config: AppConfig,
+    pub history: History,
 }
 
 // pub type SharedState = Arc<RwLock<AppState>>;
@@ -13,14 +39,43 @@ impl AppState {
     pub fn new() -> Self {
         let config_file_file = resolve_config_file();
 
-        let config = AppConfig::new(config_file_file);
+        let config = if NO_CONFIG.load(Ordering::Relaxed) {
+            AppConfig::read_only(config_file_file)
+        } else {
+            AppConfig::new(config_file_file)
+        };
+        let config = match lock_timeout() {
+            Some(timeout) => config.with_lock_timeout(timeout),
+            None => config,
+        };
 
-        Self { config }
+        let history = History::new(resolve_history_file());
+
+        Self { config, history }
+    }
+
+    /// Returns the path of the configuration file, whether or not it exists yet.
+    pub fn config_path(&self) -> &Path {
+        self.config.path()
     }
 }
 
+fn lock_timeout() -> Option<Duration> {
+    let seconds = std::env::var(CONFIG_LOCK_TIMEOUT_ENV).ok()?;
+    Duration::try_from_secs_f64(seconds.trim().parse().ok()?).ok()
+}
+
 fn resolve_config_file() -> PathBuf {
-    if cfg!(debug_assertions) {
+    match std::env::var_os(CONFIG_ENV).filter(|path| !path.is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => default_config_file(cfg!(debug_assertions)),
+    }
+}
+
+/// Returns the configuration file of debug builds (in the `.dev` directory of the workspace)
+/// or of release builds (in the configuration directory of the OS).
+fn default_config_file(debug: bool) -> PathBuf {
+    if debug {
         let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         if let Some(parent) = path.parent() {
             path = parent.to_path_buf();
@@ -33,3 +88,14 @@ fn resolve_config_file() -> PathBuf {
             .join("config.json")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_file() {
+        assert!(default_config_file(true).ends_with(".dev/config.json"));
+        assert!(default_config_file(false).ends_with("weather_cli/config.json"));
+    }
+}
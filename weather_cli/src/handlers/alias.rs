@@ -4,7 +4,12 @@
 //! Aliases allow users to assign short names to frequently used addresses
 //! (e.g., "home" -> "London, UK").
 
-use crate::common::*;
+use super::config::BACKUP_TIP;
+use crate::{common::*, output::table::Table};
+
+/// The widest alias and address shown by `list_aliases`, in terminal cells.
+const MAX_ALIAS_WIDTH: usize = 20;
+const MAX_ADDRESS_WIDTH: usize = 50;
 
 /// Lists all configured location aliases.
 ///
@@ -23,15 +28,13 @@ pub fn list_aliases() -> Result<()> {
     }
 
     println!("Aliases:\n");
-    println!(
-        "{:<10} | {:<30}\n-----------+--------------",
-        "ALIAS", "ADDRESS"
-    );
-
+    let mut table = Table::new(&["ALIAS", "ADDRESS"])
+        .max_width(0, MAX_ALIAS_WIDTH)
+        .max_width(1, MAX_ADDRESS_WIDTH);
     for (alias, address) in &config.addresses {
-        println!("{:<10} | {:<30}", alias, address);
+        table.row([alias, address]);
     }
-    println!();
+    println!("{}\n", table.render());
 
     match &config.default_alias {
         Some(alias) => println!("Default alias: {alias}"),
@@ -59,15 +62,74 @@ pub fn list_aliases() -> Result<()> {
 ///
 /// Returns `Ok(())` if the operation completes (even if validation fails),
 /// or an `Error` if saving the configuration fails.
-pub fn set_alias(alias: &str, address: Option<&str>) -> Result<()> {
+pub async fn set_alias(alias: &str, address: Option<&str>) -> Result<()> {
     if address.is_none() {
-        return set_default_alias(alias);
+        return set_default_alias(alias).await;
     }
 
     let Some(address) = address.filter(|a| !a.trim().is_empty()) else {
         Err("Address cannot be empty. Use --address <ADDRESS>")?
     };
 
+    let alias = validate_alias(alias)?;
+    if insert_alias(alias, address).await? {
+        println!("Alias '{alias}' set as default.");
+    }
+
+    println!("Alias '{alias}' set to '{address}'");
+
+    Ok(())
+}
+
+/// Checks that an alias for `get --save-as` is valid and, unless `force` is set, not taken.
+///
+/// Runs before the request, so an invalid alias fails without contacting the provider.
+///
+/// # Returns
+///
+/// The alias with surrounding whitespace removed.
+///
+/// # Errors
+///
+/// Returns an error if the alias is not between 1 and 5 characters long, or already exists
+/// and `force` is not set.
+pub(super) fn check_new_alias(alias: &str, force: bool) -> Result<String> {
+    let alias = validate_alias(alias)?;
+
+    if !force && let Some(address) = APP_STATE.config.get()?.addresses.get(alias) {
+        Err(format!(
+            "Alias '{alias}' already exists ('{address}'). Use --force to overwrite it."
+        ))?
+    }
+
+    Ok(alias.to_string())
+}
+
+/// Stores an alias, making it the default if no default alias is set.
+///
+/// # Returns
+///
+/// `true` if the alias became the default alias.
+///
+/// # Errors
+///
+/// Returns an error if saving the configuration fails.
+pub(super) async fn insert_alias(alias: &str, address: &str) -> Result<bool> {
+    APP_STATE
+        .config
+        .with_mut(|s| {
+            s.addresses.insert(alias.to_string(), address.to_string());
+            let is_first = s.default_alias.is_none();
+            if is_first {
+                s.default_alias = Some(alias.to_string());
+            }
+            is_first
+        })
+        .await
+}
+
+/// Trims an alias and checks that it is between 1 and 5 characters long.
+fn validate_alias(alias: &str) -> Result<&str> {
     let alias = alias.trim();
     let char_count = alias.chars().count();
 
@@ -75,17 +137,7 @@ pub fn set_alias(alias: &str, address: Option<&str>) -> Result<()> {
         Err("Alias must be between 1 and 5 characters long.")?
     }
 
-    APP_STATE.config.with_mut(|s| {
-        s.addresses.insert(alias.to_string(), address.to_string());
-        if s.default_alias.is_none() {
-            s.default_alias = Some(alias.to_string());
-            println!("Alias '{alias}' set as default.");
-        }
-    })?;
-
-    println!("Alias '{alias}' set to '{address}'");
-
-    Ok(())
+    Ok(alias)
 }
 
 /// Removes an existing location alias.
@@ -97,24 +149,28 @@ pub fn set_alias(alias: &str, address: Option<&str>) -> Result<()> {
 /// # Returns
 ///
 /// Returns `Ok(())` if the operation completes, or an `Error` if saving the configuration fails.
-pub fn remove_alias(alias: &str) -> Result<()> {
+pub async fn remove_alias(alias: &str) -> Result<()> {
     let mut was_default = false;
     let mut existed = false;
 
-    APP_STATE.config.with_mut(|s| {
-        existed = s.addresses.remove(alias).is_some();
+    APP_STATE
+        .config
+        .with_mut(|s| {
+            existed = s.addresses.remove(alias).is_some();
 
-        if s.default_alias.as_deref() == Some(alias) {
-            s.default_alias = None;
-            was_default = true;
-        }
-    })?;
+            if s.default_alias.as_deref() == Some(alias) {
+                s.default_alias = None;
+                was_default = true;
+            }
+        })
+        .await?;
 
     if existed {
         println!("Alias '{alias}' removed.");
         if was_default {
             println!("Note: '{alias}' was the default alias. Default alias is now unset.");
         }
+        println!("{BACKUP_TIP}");
     } else {
         println!("Alias '{alias}' not found.");
     }
@@ -132,7 +188,7 @@ pub fn remove_alias(alias: &str) -> Result<()> {
 ///
 /// * `Ok(())` if the alias was found and successfully set as default.
 /// * `Error` if the alias does not exist in the configuration or if saving failed.
-fn set_default_alias(alias: &str) -> Result<()> {
+async fn set_default_alias(alias: &str) -> Result<()> {
     let alias_exists = {
         let state = APP_STATE.config.get()?;
         state.addresses.contains_key(alias)
@@ -142,9 +198,12 @@ fn set_default_alias(alias: &str) -> Result<()> {
         Err(format!("Alias '{alias}' not found"))?
     }
 
-    APP_STATE.config.with_mut(|s| {
-        s.default_alias = Some(alias.to_string());
-    })?;
+    APP_STATE
+        .config
+        .with_mut(|s| {
+            s.default_alias = Some(alias.to_string());
+        })
+        .await?;
 
     println!("Alias '{alias}' set as default.");
 
@@ -0,0 +1,61 @@
+//! # Anomalies Handler
+//!
+//! Checks the recorded weather of a location, and a fresh observation of it, for values far
+//! from the usual weather of the location.
+
+use super::weather::{
+    record_history, resolve_address, resolve_display_options, resolve_provider_options,
+    resolve_routed_provider,
+};
+use crate::{
+    common::{history::persisted_key, *},
+    output::anomalies::AnomalyReport,
+};
+use ::chrono::{Duration, Utc};
+use ::weather_providers::create_provider_with;
+
+/// Prints the anomalies of the weather of a location over the last `days` days.
+///
+/// The observations of the location recorded in the history over the period give the mean
+/// and standard deviation of each metric. The days of the history, and a fresh observation
+/// fetched now, are flagged if they lie more than 2 standard deviations from the mean. The
+/// fresh observation is recorded in the history afterwards, like the ones of `get`.
+///
+/// # Arguments
+///
+/// * `address` - An optional location string or alias. If `None`, the default alias is used.
+/// * `days` - The number of past days of history to compare with.
+/// * `provider` - An optional provider identifier. If `None`, the first routing rule matching
+///   the address chooses it, or the default provider is used.
+///
+/// # Errors
+///
+/// Returns an error if the address or the provider cannot be resolved, the history cannot be
+/// read, or the fresh observation cannot be fetched. A history too short for statistics is
+/// reported, not an error.
+pub async fn anomalies(address: Option<String>, days: u32, provider: Option<String>) -> Result<()> {
+    let address = resolve_address(address)?;
+    let (provider, api_key, _) = resolve_routed_provider(provider, &address)?;
+
+    let privacy = APP_STATE.config.get()?.privacy.clone().unwrap_or_default();
+    let since = Utc::now() - Duration::days(i64::from(days));
+    let entries = APP_STATE
+        .history
+        .entries_since(&persisted_key(&address, &privacy), since)?;
+
+    println!("Fetching weather from '{provider}' for '{address}'...");
+    let weather_info =
+        create_provider_with(provider.clone(), &resolve_provider_options(&provider)?)
+            .get_weather(api_key.as_deref(), &address)
+            .await?;
+
+    let report = AnomalyReport::new(&entries, &weather_info);
+    println!(
+        "{}",
+        report.render(&address, days, &resolve_display_options()?)
+    );
+
+    record_history(&address, &provider, &weather_info);
+
+    Ok(())
+}
@@ -0,0 +1,323 @@
+//! # Batch Handler
+//!
+//! Fetches weather for every address listed in a file and writes the results as a CSV table,
+//! one row per address, or as NDJSON, one record per address.
+
+use super::weather::{
+    describe_providers, resolve_address, resolve_provider_options, resolve_routed_providers,
+};
+use crate::{
+    common::{
+        coalesce::CoalescingFetcher,
+        fetch::{FetchLimits, FetchOutcome, cancel_on_ctrl_c, fetch_each},
+        fs::write_atomic,
+        *,
+    },
+    models::args::{OutputFormat, SortMetric},
+    output::{ndjson::RecordWriter, ranking::sort_by_metric},
+};
+use ::serde::Serialize;
+use ::std::{fmt::Display, io::Write, path::PathBuf, sync::Arc};
+use ::weather_providers::{WeatherInfo, create_provider_with};
+
+/// The maximum number of requests in flight unless set with `--concurrency`.
+pub const BATCH_CONCURRENCY: usize = 5;
+
+/// The columns of the CSV table.
+const CSV_HEADER: &str = "address,country,city,date,temperature,humidity,description,\
+                          wind_speed_mph,precipitation_mm,error";
+
+/// Retrieves weather information for every address in a file and outputs it as CSV or NDJSON.
+///
+/// The file holds one address or alias per line; blank lines and lines starting with `#` are
+/// skipped. Addresses are fetched concurrently from their providers, with at most
+/// `concurrency` requests in flight, and duplicates are requested only once. A failed address
+/// gets a row with the error message instead of aborting the batch. Pressing Ctrl+C cancels the
+/// remaining requests.
+///
+/// CSV rows keep the order of the file, or are ordered by `sort_by`, and are written once every
+/// address is fetched. NDJSON records printed to the standard output are written and flushed
+/// as each address completes, so they follow the order of completion, unless `sort_by` is set:
+/// then they are written at the end, in the same order as CSV rows.
+///
+/// # Arguments
+///
+/// * `location_file` - The file listing the addresses.
+/// * `date` - An optional date string, applied to every address.
+/// * `provider` - An optional provider identifier. If `None`, the first routing rule matching
+///   each address chooses its provider, or the default provider is used.
+/// * `concurrency` - The maximum number of requests in flight at once. If `None`,
+///   `BATCH_CONCURRENCY` is used.
+/// * `sort_by` - An optional metric to order the rows by, highest first.
+/// * `output` - `OutputFormat::Json` for NDJSON; any other format writes CSV.
+/// * `export` - An optional file path. If set, the output is written to this file instead of the
+///   standard output.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or lists no addresses, the provider cannot be
+/// resolved, or the output cannot be written. If some or all addresses failed, returns the
+/// error of `FetchOutcome::finish`, after writing the output.
+pub async fn get_weather_batch(
+    location_file: PathBuf,
+    date: Option<String>,
+    provider: Option<String>,
+    concurrency: Option<u16>,
+    sort_by: Option<SortMetric>,
+    output: OutputFormat,
+    export: Option<PathBuf>,
+) -> Result<()> {
+    let content = std::fs::read_to_string(&location_file)
+        .map_err(|e| format!("Failed to read {location_file:?}: {e}"))?;
+    let addresses = parse_location_file(&content)
+        .into_iter()
+        .map(|address| resolve_address(Some(address)))
+        .collect::<Result<Vec<_>>>()?;
+
+    if addresses.is_empty() {
+        Err(format!("No addresses found in {location_file:?}."))?;
+    }
+
+    let providers = resolve_routed_providers(provider, &addresses)?;
+
+    // The standard output may carry the results, so progress goes to the standard error
+    eprintln!(
+        "Fetching weather from {} for {} locations...",
+        describe_providers(providers.values().map(|(provider, _)| provider)),
+        addresses.len()
+    );
+
+    let fetchers = providers.try_map(|(provider, api_key)| {
+        Ok(CoalescingFetcher::new(
+            provider.clone(),
+            Arc::from(create_provider_with(
+                provider.clone(),
+                &resolve_provider_options(&provider)?,
+            )),
+            api_key,
+        ))
+    })?;
+    let limits = FetchLimits {
+        concurrency: concurrency.map_or(BATCH_CONCURRENCY, usize::from),
+        ..FetchLimits::default()
+    };
+    let ndjson = output == OutputFormat::Json;
+    let mut stream = (ndjson && export.is_none() && sort_by.is_none()).then(RecordWriter::stdout);
+    let mut stream_error = None;
+
+    let mut results = fetch_each(
+        addresses,
+        limits,
+        &cancel_on_ctrl_c(),
+        async |address| {
+            fetchers
+                .get(address)
+                .fetch(address, date.as_deref())
+                .await
+                .map_err(|e| e.to_string().into())
+        },
+        |address, result| {
+            if let Some(writer) = stream.as_mut()
+                && stream_error.is_none()
+                && let Err(e) = writer.write(&BatchRecord::new(address, result))
+            {
+                stream_error = Some(e);
+            }
+        },
+    )
+    .await;
+
+    if let Some(e) = stream_error {
+        return Err(e);
+    }
+
+    if let Some(metric) = sort_by {
+        sort_by_metric(&mut results, metric);
+    }
+
+    if let Some(path) = export {
+        let rendered = if ndjson {
+            render_ndjson(&results)?
+        } else {
+            render_csv(&results)
+        };
+        write_atomic(&path, |w| Ok(w.write_all(rendered.as_bytes())?))?;
+        eprintln!("Weather exported to {path:?}");
+    } else if !ndjson {
+        print!("{}", render_csv(&results));
+    } else if sort_by.is_some() {
+        // The records were not streamed, as they follow the order of the rows
+        print!("{}", render_ndjson(&results)?);
+    }
+
+    FetchOutcome::of(
+        results
+            .iter()
+            .map(|(address, result)| (address.as_str(), result)),
+    )
+    .finish()
+}
+
+/// An NDJSON record: the weather of an address, or the error fetching it.
+#[derive(Serialize)]
+struct BatchRecord<'a> {
+    address: &'a str,
+    #[serde(flatten)]
+    weather: Option<&'a WeatherInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl<'a> BatchRecord<'a> {
+    fn new(address: &'a str, result: &'a Result<WeatherInfo>) -> Self {
+        Self {
+            address,
+            weather: result.as_ref().ok(),
+            error: result.as_ref().err().map(ToString::to_string),
+        }
+    }
+}
+
+/// Returns the addresses of a location file, skipping blank lines and `#` comments.
+fn parse_location_file(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Renders the results as a CSV table with a header row.
+fn render_csv(results: &[(String, Result<WeatherInfo>)]) -> String {
+    let mut csv = format!("{CSV_HEADER}\n");
+
+    for (address, result) in results {
+        let row = match result {
+            Ok(info) => [
+                csv_field(address),
+                csv_field(&info.country),
+                csv_field(&info.city),
+                csv_field(&info.date),
+                info.temperature.to_string(),
+                optional_field(info.humidity),
+                optional_field(info.description.as_ref()),
+                optional_field(info.wind_speed_mph),
+                optional_field(info.precipitation_mm),
+                String::new(),
+            ],
+            Err(e) => {
+                let mut row: [String; 10] = Default::default();
+                row[0] = csv_field(address);
+                row[9] = csv_field(&e.to_string());
+                row
+            }
+        };
+
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Renders the results as NDJSON, one record per line in the order of the file.
+fn render_ndjson(results: &[(String, Result<WeatherInfo>)]) -> Result<String> {
+    let mut writer = RecordWriter::new(Vec::new());
+    for (address, result) in results {
+        writer.write(&BatchRecord::new(address, result))?;
+    }
+
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+fn optional_field(value: Option<impl Display>) -> String {
+    value.map(|v| csv_field(&v.to_string())).unwrap_or_default()
+}
+
+/// Quotes a field if it contains a separator, a quote or a line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixtures;
+
+    fn weather(city: &str) -> WeatherInfo {
+        WeatherInfo {
+            city: city.to_string(),
+            temperature: 50.5,
+            description: Some("Rain, heavy".to_string()),
+            wind_speed_mph: Some(10.0),
+            ..fixtures::weather()
+        }
+    }
+
+    #[test]
+    fn test_parse_location_file() {
+        let content = "# Capitals\nLondon\n\n  Paris, FR  \n   # indented comment\nKyiv\n";
+
+        assert_eq!(
+            parse_location_file(content),
+            ["London", "Paris, FR", "Kyiv"]
+        );
+        assert!(parse_location_file("# nothing\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let results = vec![
+            ("London".to_string(), Ok(weather("London"))),
+            ("Nowhere".to_string(), Err("Location not found".into())),
+        ];
+
+        let csv = render_csv(&results);
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "London,UK,London,2024-01-01,50.5,80,\"Rain, heavy\",10,,"
+        );
+        assert_eq!(lines[2], "Nowhere,,,,,,,,,Location not found");
+        assert!(lines.iter().all(|l| !l.is_empty()));
+    }
+
+    #[test]
+    fn test_render_ndjson() {
+        let results = vec![
+            ("London".to_string(), Ok(weather("London"))),
+            ("Nowhere".to_string(), Err("Location not found".into())),
+        ];
+
+        let ndjson = render_ndjson(&results).unwrap();
+        let records = ndjson
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["address"], "London");
+        assert_eq!(records[0]["city"], "London");
+        assert_eq!(records[0]["temperature"], 50.5);
+        assert!(records[0].get("error").is_none());
+        assert_eq!(
+            records[1],
+            serde_json::json!({ "address": "Nowhere", "error": "Location not found" })
+        );
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("London"), "London");
+        assert_eq!(csv_field("Paris, FR"), "\"Paris, FR\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,124 @@
+//! # Compare Handler
+//!
+//! Fetches weather for several locations concurrently and prints one line per location.
+
+use super::weather::{
+    describe_providers, resolve_address, resolve_display_options, resolve_provider_options,
+    resolve_routed_providers,
+};
+use crate::{
+    common::{
+        coalesce::CoalescingFetcher,
+        fetch::{FetchLimits, FetchOutcome, cancel_on_ctrl_c, fetch_many},
+        *,
+    },
+    models::args::{DisplayArgs, SortMetric},
+    output::{
+        alerts::supports_color,
+        ranking::{Extremes, sort_by_metric},
+    },
+};
+use ::std::{io::IsTerminal, sync::Arc};
+use ::weather_providers::create_provider_with;
+
+/// Retrieves and displays weather information for multiple locations.
+///
+/// Locations are fetched concurrently (with a bounded number of requests in flight) from their
+/// providers. Addresses and aliases that resolve to the same location are requested only
+/// once. Pressing Ctrl+C cancels the remaining requests.
+///
+/// The locations are printed in the given order, or ordered by `sort_by`. With
+/// `highlight_extremes`, the locations with the highest and lowest value of the metric are
+/// colored on terminals that support colors.
+///
+/// # Arguments
+///
+/// * `addresses` - Location strings or aliases.
+/// * `date` - An optional date string, applied to every location.
+/// * `provider` - An optional provider identifier. If `None`, the first routing rule matching
+///   each address chooses its provider, or the default provider is used.
+/// * `sort_by` - An optional metric to order the locations by, highest first.
+/// * `highlight_extremes` - Color the highest and lowest value of `sort_by`.
+/// * `display` - Text display flags overriding the display options stored in the configuration.
+///
+/// # Errors
+///
+/// Returns an error if the provider cannot be resolved. Failures of individual locations are
+/// printed next to them; if any location failed, the error of `FetchOutcome::finish` is
+/// returned after printing them all.
+pub async fn compare(
+    addresses: Vec<String>,
+    date: Option<String>,
+    provider: Option<String>,
+    sort_by: Option<SortMetric>,
+    highlight_extremes: bool,
+    display: DisplayArgs,
+) -> Result<()> {
+    let addresses = addresses
+        .into_iter()
+        .map(|address| resolve_address(Some(address)))
+        .collect::<Result<Vec<_>>>()?;
+    let providers = resolve_routed_providers(provider, &addresses)?;
+
+    println!(
+        "Fetching weather from {} for {} locations...",
+        describe_providers(providers.values().map(|(provider, _)| provider)),
+        addresses.len()
+    );
+
+    let fetchers = providers.try_map(|(provider, api_key)| {
+        Ok(CoalescingFetcher::new(
+            provider.clone(),
+            Arc::from(create_provider_with(
+                provider.clone(),
+                &resolve_provider_options(&provider)?,
+            )),
+            api_key,
+        ))
+    })?;
+    let mut results = fetch_many(
+        addresses,
+        FetchLimits::default(),
+        &cancel_on_ctrl_c(),
+        async |address| {
+            fetchers
+                .get(address)
+                .fetch(address, date.as_deref())
+                .await
+                .map_err(|e| e.to_string().into())
+        },
+    )
+    .await;
+
+    let mut display_options = resolve_display_options()?;
+    display.apply(&mut display_options);
+
+    let mut extremes = Extremes::default();
+    if let Some(metric) = sort_by {
+        sort_by_metric(&mut results, metric);
+        if highlight_extremes
+            && supports_color(std::io::stdout().is_terminal(), |var| {
+                std::env::var(var).ok()
+            })
+        {
+            extremes = Extremes::find(&results, metric);
+        }
+    }
+
+    let outcome = FetchOutcome::of(
+        results
+            .iter()
+            .map(|(address, result)| (address.as_str(), result)),
+    );
+    for (index, (address, result)) in results.into_iter().enumerate() {
+        match result {
+            Ok(info) => println!(
+                "{}",
+                extremes.highlight(index, info.format_for_display(&display_options))
+            ),
+            Err(e) => println!("Weather in '{address}': {e}"),
+        }
+    }
+
+    outcome.finish()
+}
@@ -0,0 +1,133 @@
+//! # Completion Handlers
+//!
+//! Provides location candidates for the shell completion generated by `clap_complete`, and for
+//! other scripts through the hidden `_complete-locations` command.
+
+use crate::common::{
+    cities::WORLD_CITIES,
+    history::{History, resolve_history_file},
+    *,
+};
+use ::clap_complete::engine::CompletionCandidate;
+use ::std::ffi::OsStr;
+
+/// The maximum number of candidates printed.
+pub const MAX_COMPLETIONS: usize = 50;
+
+/// Prints the locations starting with `prefix`, one per line.
+///
+/// Candidates come from the configured aliases, then the history, then the bundled list of
+/// world cities. A source that cannot be read is skipped, so completion never fails.
+///
+/// # Arguments
+///
+/// * `prefix` - The text typed so far. If `None`, every candidate matches.
+pub fn complete_locations(prefix: Option<&str>) -> Result<()> {
+    for candidate in location_candidates(prefix.unwrap_or_default()) {
+        println!("{candidate}");
+    }
+
+    Ok(())
+}
+
+/// Completes a `LOCATION` argument with the same candidates as `complete_locations`.
+///
+/// Registered on the location arguments with `ArgValueCompleter`, so the shell completion
+/// generated from the `Cli` command offers them.
+pub fn complete_location(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(prefix) = current.to_str() else {
+        return Vec::new();
+    };
+
+    location_candidates(prefix)
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Returns the aliases, history locations and world cities starting with `prefix`.
+fn location_candidates(prefix: &str) -> Vec<String> {
+    let aliases = APP_STATE
+        .config
+        .get()
+        .map(|config| config.addresses.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let history = History::new(resolve_history_file())
+        .locations()
+        .unwrap_or_default();
+    let cities = WORLD_CITIES.iter().map(|city| city.to_string()).collect();
+
+    complete(prefix, [aliases, history, cities])
+}
+
+/// Returns the candidates of the sources, in order, that start with `prefix`.
+///
+/// Matching and deduplication are case-insensitive; the first spelling seen wins. At most
+/// `MAX_COMPLETIONS` candidates are returned.
+fn complete(prefix: &str, sources: impl IntoIterator<Item = Vec<String>>) -> Vec<String> {
+    let prefix = prefix.trim_start().to_lowercase();
+    let mut candidates = Vec::<String>::new();
+
+    for candidate in sources.into_iter().flatten() {
+        if candidates.len() == MAX_COMPLETIONS {
+            break;
+        }
+
+        if candidate.to_lowercase().starts_with(&prefix)
+            && !candidates
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(&candidate))
+        {
+            candidates.push(candidate);
+        }
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_complete_matches_prefix_case_insensitively() {
+        let cities = strings(&["London, GB", "Los Angeles, US", "Paris, FR"]);
+
+        assert_eq!(complete("lon", [cities.clone()]), ["London, GB"]);
+        assert_eq!(
+            complete("LO", [cities.clone()]),
+            ["London, GB", "Los Angeles, US"]
+        );
+        assert!(complete("Berlin", [cities]).is_empty());
+    }
+
+    #[test]
+    fn test_complete_orders_and_dedupes_sources() {
+        let aliases = strings(&["home", "lon"]);
+        let history = strings(&["London, GB", "Lviv, UA"]);
+        let cities = strings(&["Lagos, NG", "london, gb"]);
+
+        assert_eq!(
+            complete("l", [aliases, history, cities]),
+            ["lon", "London, GB", "Lviv, UA", "Lagos, NG"]
+        );
+    }
+
+    #[test]
+    fn test_complete_caps_candidates() {
+        let cities = WORLD_CITIES.iter().map(|c| c.to_string()).collect();
+
+        assert_eq!(complete("", [cities]).len(), MAX_COMPLETIONS);
+    }
+
+    #[test]
+    fn test_world_cities() {
+        assert!(WORLD_CITIES.len() >= 1000);
+        assert!(WORLD_CITIES.contains(&"London, GB"));
+        assert!(WORLD_CITIES.iter().all(|c| c.contains(", ")));
+    }
+}
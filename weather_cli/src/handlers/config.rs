@@ -0,0 +1,138 @@
+//! # Configuration Handlers
+//!
+//! Exports the configuration for inspection, imports an edited copy, backs it up, checks
+//! it for unknown keys, prints where it is stored, and reads or changes single settings.
+
+use crate::{
+    common::*,
+    models::{
+        args::{ConfigFormat, ReportFormat},
+        config_path::ConfigPath,
+    },
+};
+use ::chrono::Local;
+use ::serde_json::Value;
+use ::std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Printed after changes that are hard to undo, e.g. removing an alias.
+pub(super) const BACKUP_TIP: &str = "Tip: Run 'weather config backup' to save your settings.";
+
+/// Prints the configuration in the given format.
+///
+/// # Errors
+///
+/// Returns an error if the configuration cannot be accessed or serialized.
+pub fn export_config(format: ConfigFormat) -> Result<()> {
+    let exported = match format {
+        ConfigFormat::Toml => APP_STATE.config.export_toml()?,
+        ConfigFormat::Json => serde_json::to_string_pretty(&*APP_STATE.config.get()?)?,
+    };
+
+    println!("{}", exported.trim_end());
+
+    Ok(())
+}
+
+/// Replaces the configuration with the settings of a TOML file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, is not valid TOML, or contains invalid
+/// settings. The configuration is left unchanged in that case.
+pub async fn import_config(path: &Path) -> Result<()> {
+    let document = fs::read_to_string(path)?;
+
+    APP_STATE.config.import_toml(&document).await?;
+    println!("Configuration imported from {path:?}.");
+
+    Ok(())
+}
+
+/// Lists the keys of the configuration file that no setting reads, e.g. misspelled ones.
+///
+/// Normal loads ignore such keys, so a typo silently falls back to the default.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, is invalid, or contains unknown keys.
+pub fn check_config() -> Result<()> {
+    let path = APP_STATE.config.path();
+    let unknown = APP_STATE.config.check()?;
+
+    if unknown.is_empty() {
+        println!("No unknown keys in {path:?}.");
+        return Ok(());
+    }
+
+    println!("Unknown keys in {path:?}:");
+    for key in &unknown {
+        println!("  {key}");
+    }
+
+    Err(format!("Found {} unknown key(s), which are ignored.", unknown.len()).into())
+}
+
+/// Copies the configuration file to `path`, or to a timestamped file next to it.
+///
+/// # Errors
+///
+/// Returns an error if the backup cannot be written.
+pub fn backup_config(path: Option<PathBuf>) -> Result<()> {
+    let target = path.unwrap_or_else(|| APP_STATE.config.backup_path(Local::now().naive_local()));
+
+    APP_STATE.config.backup(&target)?;
+    println!("Configuration backed up to {target:?}.");
+
+    Ok(())
+}
+
+/// Prints the path of the configuration file, which may not exist yet.
+pub fn print_config_path() {
+    println!("{}", APP_STATE.config_path().display());
+}
+
+/// Prints the value of the setting at a dotted path, e.g. "providers.ow.key".
+///
+/// Strings are printed as they are, unless `output` is JSON, which prints the raw JSON value.
+///
+/// # Errors
+///
+/// Returns an error if the path is unknown, the setting is not set, or the configuration
+/// cannot be accessed.
+pub fn get_setting(path: &str, output: ReportFormat) -> Result<()> {
+    let path = ConfigPath::parse(path)?;
+    let value = path
+        .get(&*APP_STATE.config.get()?)?
+        .ok_or_else(|| format!("'{path}' is not set."))?;
+
+    match (output, value) {
+        (ReportFormat::Text, Value::String(text)) => println!("{text}"),
+        (_, value) => println!("{value}"),
+    }
+
+    Ok(())
+}
+
+/// Sets the setting at a dotted path and saves the configuration.
+///
+/// # Errors
+///
+/// Returns an error if the path is unknown, the value is invalid for the setting, or the
+/// configuration cannot be saved. The configuration is left unchanged in that case.
+pub async fn set_setting(path: &str, value: &str) -> Result<()> {
+    let path = ConfigPath::parse(path)?;
+
+    APP_STATE
+        .config
+        .with_mut(|settings| -> Result<()> {
+            *settings = path.set(settings, value)?;
+            Ok(())
+        })
+        .await??;
+    println!("Set '{path}'.");
+
+    Ok(())
+}
@@ -0,0 +1,528 @@
+//! # Daemon Handler
+//!
+//! Refreshes the weather of the configured locations in the foreground, for service managers
+//! like systemd or launchd. Every cycle records the observations in the history and, if asked,
+//! writes them as JSON files that a status bar can read without calling a provider.
+
+use super::weather::{
+    describe_providers, record_history_in, resolve_address, resolve_provider_options,
+    resolve_routed_providers,
+};
+use crate::{
+    common::{
+        fetch::{FetchLimits, cancel_on_shutdown, fetch_many},
+        fs::write_atomic,
+        history::History,
+        *,
+    },
+    models::{config::DaemonSettings, routing::Routed},
+};
+use ::std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use ::tokio::time::{self, MissedTickBehavior};
+use ::tokio_util::sync::CancellationToken;
+use ::tracing::{info, warn};
+use ::weather_providers::{
+    ErrorCode, Provider, WeatherInfo, WeatherProvider, create_provider_with,
+};
+
+/// The most cycles skipped in a row while a provider rejects its API key.
+const MAX_SKIPPED_CYCLES: u32 = 63;
+
+/// A location refreshed by the daemon.
+#[derive(Debug, Clone, PartialEq)]
+struct DaemonLocation {
+    /// The location as configured, an alias or an address.
+    name: String,
+    /// The resolved address.
+    address: String,
+}
+
+/// The outcome of a refresh cycle.
+#[derive(Debug, Default, PartialEq)]
+struct CycleSummary {
+    fetched: usize,
+    failed: usize,
+    /// Whether a provider rejected its API key.
+    unauthorized: bool,
+}
+
+/// Skips cycles with exponential backoff while a provider rejects its API key, so a revoked
+/// key does not hammer the provider.
+///
+/// After `n` rejected cycles in a row, the next `2^(n-1) - 1` cycles are skipped, up to
+/// `MAX_SKIPPED_CYCLES`. A cycle that is not rejected resets the backoff.
+#[derive(Debug, Default)]
+struct AuthBackoff {
+    failures: u32,
+    skip: u32,
+}
+
+impl AuthBackoff {
+    /// Returns `true` if the cycle is skipped, counting it.
+    fn skip_cycle(&mut self) -> bool {
+        if self.skip == 0 {
+            return false;
+        }
+
+        self.skip -= 1;
+        true
+    }
+
+    /// Records the outcome of a cycle that ran.
+    fn record(&mut self, unauthorized: bool) {
+        if !unauthorized {
+            *self = Self::default();
+            return;
+        }
+
+        self.failures = self.failures.saturating_add(1);
+        self.skip = 2u32
+            .saturating_pow(self.failures - 1)
+            .saturating_sub(1)
+            .min(MAX_SKIPPED_CYCLES);
+    }
+}
+
+/// A provider refreshing some of the locations of the daemon.
+struct DaemonProvider {
+    provider: Provider,
+    api_key: Option<String>,
+    weather_provider: Box<dyn WeatherProvider>,
+}
+
+/// The providers, locations and state of a running daemon.
+struct Daemon {
+    /// The provider of each location, by address.
+    providers: Routed<DaemonProvider>,
+    locations: Vec<DaemonLocation>,
+    /// The history receiving the observations.
+    history: History,
+    export_dir: Option<PathBuf>,
+    backoff: AuthBackoff,
+}
+
+/// Refreshes the weather of the locations of the `daemon` settings every `interval_secs`.
+///
+/// Each cycle fetches every location concurrently, records the observations in the history
+/// and, with `export_dir`, writes each of them to `<export_dir>/<location>.json`. Failed
+/// requests are logged and never stop the daemon. While a provider rejects its API key,
+/// cycles are skipped with exponential backoff. Ctrl+C or `SIGTERM` stop the daemon once the
+/// running cycle is finished.
+///
+/// # Arguments
+///
+/// * `provider` - An optional provider identifier. If `None`, the first routing rule matching
+///   each address chooses its provider, or the default provider is used.
+/// * `export_dir` - A directory receiving a JSON file per location, e.g. for a status bar.
+/// * `once` - Run a single cycle and return, e.g. from cron.
+///
+/// # Errors
+///
+/// Returns an error if no locations are configured, the provider or a location cannot be
+/// resolved, or the export directory cannot be created.
+pub async fn daemon(
+    provider: Option<String>,
+    export_dir: Option<PathBuf>,
+    once: bool,
+) -> Result<()> {
+    let settings = APP_STATE.config.get()?.daemon.clone().unwrap_or_default();
+    let locations = resolve_locations(&settings)?;
+    // The settings are only validated by `config --check`, and a zero interval panics
+    let interval = settings.interval()?;
+
+    let addresses = locations
+        .iter()
+        .map(|location| location.address.clone())
+        .collect::<Vec<_>>();
+    let providers =
+        resolve_routed_providers(provider, &addresses)?.try_map(|(provider, api_key)| {
+            Ok(DaemonProvider {
+                weather_provider: create_provider_with(
+                    provider.clone(),
+                    &resolve_provider_options(&provider)?,
+                ),
+                provider,
+                api_key,
+            })
+        })?;
+
+    if let Some(dir) = &export_dir {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create the export directory {dir:?}: {e}"))?;
+    }
+
+    let mut daemon = Daemon {
+        providers,
+        locations,
+        history: APP_STATE.history.clone(),
+        export_dir,
+        backoff: AuthBackoff::default(),
+    };
+
+    if once {
+        daemon.cycle(1).await;
+        return Ok(());
+    }
+
+    info!(
+        providers = %daemon.describe_providers(),
+        locations = daemon.locations.len(),
+        interval_secs = settings.interval_secs,
+        "Daemon started"
+    );
+
+    daemon.run(interval, &cancel_on_shutdown()).await;
+
+    info!("Daemon stopped");
+    Ok(())
+}
+
+impl Daemon {
+    /// Runs a cycle every `interval` until `shutdown` is cancelled. A running cycle is finished
+    /// before returning.
+    async fn run(&mut self, interval: Duration, shutdown: &CancellationToken) {
+        let mut cycles = time::interval(interval);
+        cycles.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        for cycle in 1.. {
+            tokio::select! {
+                _ = shutdown.cancelled() => return,
+                _ = cycles.tick() => {}
+            }
+
+            self.cycle(cycle).await;
+        }
+    }
+
+    /// Refreshes every location once and logs the outcome.
+    async fn cycle(&mut self, cycle: u64) -> CycleSummary {
+        if self.backoff.skip_cycle() {
+            warn!(
+                cycle,
+                providers = %self.describe_providers(),
+                "Cycle skipped: a provider rejected its API key"
+            );
+            return CycleSummary {
+                unauthorized: true,
+                ..CycleSummary::default()
+            };
+        }
+
+        let started = Instant::now();
+        // A cycle is never cancelled, so a shutdown waits for it to finish
+        let results = fetch_many(
+            self.locations.clone(),
+            FetchLimits::default(),
+            &CancellationToken::new(),
+            async |location| {
+                let provider = self.providers.get(&location.address);
+                Ok(provider
+                    .weather_provider
+                    .get_weather(provider.api_key.as_deref(), &location.address)
+                    .await?)
+            },
+        )
+        .await;
+
+        let mut summary = CycleSummary::default();
+        for (location, result) in &results {
+            match result {
+                Ok(weather_info) => {
+                    summary.fetched += 1;
+                    let provider = &self.providers.get(&location.address).provider;
+                    record_history_in(&self.history, &location.address, provider, weather_info);
+                    if let Some(dir) = &self.export_dir
+                        && let Err(e) = export(dir, &location.name, weather_info)
+                    {
+                        warn!(cycle, location = %location.name, "Failed to export: {e}");
+                    }
+                }
+                Err(e) => {
+                    summary.failed += 1;
+                    summary.unauthorized |= is_unauthorized(e);
+                    warn!(cycle, location = %location.name, "Failed to refresh: {e}");
+                }
+            }
+        }
+        self.backoff.record(summary.unauthorized);
+
+        info!(
+            cycle,
+            fetched = summary.fetched,
+            failed = summary.failed,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Cycle completed"
+        );
+
+        summary
+    }
+
+    fn describe_providers(&self) -> String {
+        describe_providers(self.providers.values().map(|p| &p.provider))
+    }
+}
+
+/// Resolves the configured locations to addresses.
+///
+/// # Errors
+///
+/// Returns an error if there are no locations or a location cannot be resolved.
+fn resolve_locations(settings: &DaemonSettings) -> Result<Vec<DaemonLocation>> {
+    if settings.locations.is_empty() {
+        Err("No locations to refresh. Add them to daemon.locations in the configuration.")?;
+    }
+
+    settings
+        .locations
+        .iter()
+        .map(|name| {
+            Ok(DaemonLocation {
+                name: name.clone(),
+                address: resolve_address(Some(name.clone()))?,
+            })
+        })
+        .collect()
+}
+
+fn is_unauthorized(error: &Error) -> bool {
+    matches!(error, Error::Providers(e) if e.code() == ErrorCode::InvalidKey)
+}
+
+/// Writes the weather of a location to `<dir>/<file_name(name)>`, replacing it atomically so a
+/// reader never sees a partial file.
+fn export(dir: &Path, name: &str, weather_info: &WeatherInfo) -> Result<()> {
+    write_atomic(&dir.join(file_name(name)), |writer| {
+        serde_json::to_writer_pretty(&mut *writer, weather_info)?;
+        Ok(())
+    })
+}
+
+/// Returns the export file name of a location: its lowercase letters and digits, with runs of
+/// other characters replaced by a dash (e.g., "Paris, FR" is "paris-fr.json").
+fn file_name(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_end_matches('-');
+    format!("{}.json", if slug.is_empty() { "location" } else { slug })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::async_trait::async_trait;
+    use ::std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use ::weather_providers::{ProviderInfo, create_provider};
+
+    fn location(name: &str) -> DaemonLocation {
+        DaemonLocation {
+            name: name.to_string(),
+            address: name.to_string(),
+        }
+    }
+
+    /// Creates a daemon recording its observations in `dir`, and exporting them there if
+    /// `export` is set.
+    fn daemon(weather_provider: Box<dyn WeatherProvider>, dir: &Path, export: bool) -> Daemon {
+        let locations = vec![location("home"), location("Paris, FR")];
+        let addresses = locations
+            .iter()
+            .map(|l| l.address.clone())
+            .collect::<Vec<_>>();
+        let mut weather_provider = Some(weather_provider);
+        let providers = Routed::resolve(
+            &addresses,
+            |_| (),
+            |_| {
+                Ok(DaemonProvider {
+                    provider: Provider::Mock,
+                    api_key: None,
+                    weather_provider: weather_provider.take().unwrap(),
+                })
+            },
+        )
+        .unwrap();
+
+        Daemon {
+            providers,
+            locations,
+            history: History::new(dir.join("history.jsonl")),
+            export_dir: export.then(|| dir.join("export")),
+            backoff: AuthBackoff::default(),
+        }
+    }
+
+    /// Creates an empty temporary directory for the test `name`.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "weather-cli-test-daemon-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// A provider rejecting every API key, counting its requests.
+    #[derive(Default)]
+    struct Unauthorized(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl WeatherProvider for Unauthorized {
+        async fn get_weather(
+            &self,
+            _provider_key: Option<&str>,
+            _address: &str,
+        ) -> ::weather_providers::Result<WeatherInfo> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Err(::weather_providers::Error::InvalidApiKey("Test".into()))
+        }
+
+        async fn health_check(
+            &self,
+            _provider_key: Option<&str>,
+        ) -> ::weather_providers::Result<Duration> {
+            Ok(Duration::ZERO)
+        }
+
+        fn info(&self) -> ProviderInfo {
+            create_provider(Provider::Mock).info()
+        }
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("home"), "home.json");
+        assert_eq!(file_name("Paris, FR"), "paris-fr.json");
+        assert_eq!(file_name("  São Paulo!"), "são-paulo.json");
+        assert_eq!(file_name("51.5,-0.12"), "51-5-0-12.json");
+        assert_eq!(file_name("..."), "location.json");
+    }
+
+    #[test]
+    fn test_auth_backoff() {
+        let mut backoff = AuthBackoff::default();
+        let mut ran = Vec::new();
+
+        // Rejected by the provider for 12 cycles, then accepted
+        for cycle in 1..=16 {
+            if backoff.skip_cycle() {
+                continue;
+            }
+            ran.push(cycle);
+            backoff.record(cycle <= 12);
+        }
+
+        // Waits 0, 1, 3 and then 7 cycles between attempts, until accepted again
+        assert_eq!(ran, [1, 2, 4, 8, 16]);
+        assert_eq!(backoff.failures, 0);
+        assert!(!backoff.skip_cycle());
+
+        backoff.failures = 40;
+        backoff.record(true);
+        assert_eq!(backoff.skip, MAX_SKIPPED_CYCLES);
+    }
+
+    #[tokio::test]
+    async fn test_cycles_export_each_location() {
+        let dir = test_dir("export");
+        let export_dir = dir.join("export");
+        let mut daemon = daemon(create_provider(Provider::Mock), &dir, true);
+
+        for cycle in 1..=2 {
+            assert_eq!(
+                daemon.cycle(cycle).await,
+                CycleSummary {
+                    fetched: 2,
+                    failed: 0,
+                    unauthorized: false
+                }
+            );
+        }
+
+        let mut files = std::fs::read_dir(&export_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, ["home.json", "paris-fr.json"]);
+
+        let exported: WeatherInfo =
+            serde_json::from_str(&std::fs::read_to_string(export_dir.join("home.json")).unwrap())
+                .unwrap();
+        assert_eq!(exported.city, "Mock City");
+
+        // Every observation of both cycles was recorded
+        let history = std::fs::read_to_string(dir.join("history.jsonl")).unwrap();
+        assert_eq!(history.lines().count(), 4);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rejected_key_backs_off() {
+        let dir = test_dir("unauthorized");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let mut daemon = daemon(Box::new(Unauthorized(requests.clone())), &dir, false);
+
+        for cycle in 1..=4 {
+            let summary = daemon.cycle(cycle).await;
+            assert!(summary.unauthorized);
+            assert_eq!(summary.fetched, 0);
+        }
+
+        // The third cycle was skipped, and nothing was recorded
+        assert_eq!(requests.load(Ordering::SeqCst), 3 * 2);
+        assert!(!dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_on_shutdown() {
+        let dir = test_dir("shutdown");
+        let mut daemon = daemon(create_provider(Provider::Mock), &dir, false);
+        let shutdown = CancellationToken::new();
+
+        let stopper = shutdown.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            stopper.cancel();
+        });
+
+        let started = Instant::now();
+        daemon.run(Duration::from_millis(50), &shutdown).await;
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(daemon.backoff.failures, 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resolve_locations() {
+        let settings = DaemonSettings {
+            locations: vec!["Paris, FR".to_string()],
+            ..DaemonSettings::default()
+        };
+        assert_eq!(
+            resolve_locations(&settings).unwrap(),
+            [DaemonLocation {
+                name: "Paris, FR".to_string(),
+                address: "Paris, FR".to_string(),
+            }]
+        );
+
+        let error = resolve_locations(&DaemonSettings::default()).unwrap_err();
+        assert!(error.to_string().contains("daemon.locations"), "{error}");
+    }
+}
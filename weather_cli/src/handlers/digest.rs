@@ -0,0 +1,258 @@
+//! # Digest Handler
+//!
+//! Prints a morning briefing for the locations configured under `digest`: today's weather and
+//! tomorrow's forecast of each location, with "today" taken in the location's own timezone.
+
+use super::weather::{
+    describe_providers, resolve_display_options, resolve_provider_options, resolve_routed_providers,
+};
+use crate::{
+    common::{
+        fetch::{FetchLimits, FetchOutcome, cancel_on_ctrl_c, fetch_many, fetch_weather},
+        *,
+    },
+    models::config::{DigestLocation, Settings},
+    output::digest::{Briefing, render_markdown, render_text, sort},
+};
+use ::weather_providers::{Clock, OpenWeatherEndpoint, SystemClock, create_provider_with};
+
+/// Printed instead of a digest when no locations are configured.
+pub const SETUP_HINT: &str = r#"The digest has no locations yet. Add aliases to it in the configuration file, e.g.:
+
+  "digest": {
+    "locations": [
+      { "alias": "home" },
+      { "alias": "tokyo", "timezone": "Asia/Tokyo" }
+    ],
+    "order": "listed"
+  }
+
+The order is one of "listed", "alias", "warmest" or "coldest". Create aliases with 'weather alias'."#;
+
+/// A digest location resolved for fetching.
+#[derive(Debug, PartialEq)]
+struct Target {
+    alias: String,
+    address: String,
+    /// Today's date at the location, in the `YYYY-MM-DD` format.
+    today: String,
+    /// Tomorrow's date at the location.
+    tomorrow: String,
+}
+
+/// Fetches and prints the morning briefing of every digest location.
+///
+/// Both days of all locations are requested concurrently (with a bounded number of requests
+/// in flight) from the provider of each location; OpenWeather always uses its One Call
+/// endpoint here, as it covers tomorrow. Failures of individual locations are printed in their
+/// blocks.
+///
+/// # Arguments
+///
+/// * `provider` - An optional provider identifier. If `None`, the first routing rule matching
+///   each address chooses its provider, or the default provider is used.
+/// * `markdown` - Render the digest as Markdown instead of plain text.
+///
+/// # Errors
+///
+/// Returns an error if the digest refers to an unknown alias or timezone, the provider cannot
+/// be resolved, or today's weather of some or all locations could not be fetched (see
+/// `FetchOutcome::finish`). No configured locations is not an error;
+/// `SETUP_HINT` is printed instead.
+pub async fn digest(provider: Option<String>, markdown: bool) -> Result<()> {
+    let (settings, targets) = {
+        let config = APP_STATE.config.get()?;
+        let settings = config.digest.clone().unwrap_or_default();
+        let targets = resolve_targets(&settings.locations, &config, &SystemClock)?;
+        (settings, targets)
+    };
+
+    if targets.is_empty() {
+        println!("{SETUP_HINT}");
+        return Ok(());
+    }
+
+    let addresses = targets
+        .iter()
+        .map(|target| target.address.clone())
+        .collect::<Vec<_>>();
+    let providers =
+        resolve_routed_providers(provider, &addresses)?.try_map(|(provider, api_key)| {
+            let mut options = resolve_provider_options(&provider)?;
+            options.open_weather_endpoint = OpenWeatherEndpoint::OneCall;
+            Ok((
+                provider.clone(),
+                api_key,
+                create_provider_with(provider, &options),
+            ))
+        })?;
+
+    // The progress goes to stderr, so the digest can be piped or redirected on its own
+    eprintln!(
+        "Fetching the digest from {} for {} locations...",
+        describe_providers(providers.values().map(|(provider, ..)| provider)),
+        targets.len()
+    );
+
+    let requests = targets
+        .iter()
+        .enumerate()
+        .flat_map(|(index, target)| {
+            [
+                (index, target.today.clone()),
+                (index, target.tomorrow.clone()),
+            ]
+        })
+        .collect();
+    let results = fetch_many(
+        requests,
+        FetchLimits::default(),
+        &cancel_on_ctrl_c(),
+        async |(index, date)| {
+            let address = &targets[*index].address;
+            let (provider, api_key, weather_provider) = providers.get(address);
+            Ok(fetch_weather(
+                weather_provider.as_ref(),
+                provider,
+                api_key.as_deref(),
+                address,
+                Some(date),
+            )
+            .await?)
+        },
+    )
+    .await;
+
+    // The results are in the order of the requests: today, then tomorrow of each location
+    let mut results = results.into_iter().map(|(_, result)| result);
+    let mut briefings = Vec::with_capacity(targets.len());
+    for target in targets {
+        let (Some(today), Some(tomorrow)) = (results.next(), results.next()) else {
+            break;
+        };
+        briefings.push(Briefing {
+            alias: target.alias,
+            today: today.map_err(|e| e.to_string()),
+            tomorrow: tomorrow.ok(),
+        });
+    }
+    sort(&mut briefings, settings.order);
+
+    let display_options = resolve_display_options()?;
+    if markdown {
+        print!("{}", render_markdown(&briefings, &display_options));
+    } else {
+        print!("{}", render_text(&briefings, &display_options));
+    }
+
+    FetchOutcome::of(briefings.iter().map(|b| (b.alias.as_str(), &b.today))).finish()
+}
+
+/// Resolves the aliases of the digest locations and their dates of today and tomorrow.
+///
+/// # Errors
+///
+/// Returns an error if an alias is not defined or a timezone is unknown.
+fn resolve_targets(
+    locations: &[DigestLocation],
+    settings: &Settings,
+    clock: &dyn Clock,
+) -> Result<Vec<Target>> {
+    locations
+        .iter()
+        .map(|location| {
+            let address = settings.addresses.get(&location.alias).ok_or_else(|| {
+                format!(
+                    "The digest alias '{alias}' is not defined. Create it with: \
+                     'weather alias {alias} --address <LOCATION>'",
+                    alias = location.alias
+                )
+            })?;
+            let today = clock.today_in(location.tz()?);
+            let tomorrow = today.succ_opt().ok_or("Date out of range.")?;
+
+            Ok(Target {
+                alias: location.alias.clone(),
+                address: address.clone(),
+                today: today.format("%Y-%m-%d").to_string(),
+                tomorrow: tomorrow.format("%Y-%m-%d").to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::chrono::{TimeZone, Utc};
+    use ::weather_providers::FixedClock;
+
+    fn location(alias: &str, timezone: Option<&str>) -> DigestLocation {
+        DigestLocation {
+            alias: alias.to_string(),
+            timezone: timezone.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_resolve_targets_per_timezone() {
+        let mut settings = Settings::default();
+        settings
+            .addresses
+            .insert("tokyo".to_string(), "Tokyo, JP".to_string());
+        settings
+            .addresses
+            .insert("la".to_string(), "Los Angeles, US".to_string());
+        // 20:00 UTC is already the next morning in Tokyo, and still noon in Los Angeles
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 1, 15, 20, 0, 0).unwrap());
+
+        let targets = resolve_targets(
+            &[
+                location("tokyo", Some("Asia/Tokyo")),
+                location("la", Some("America/Los_Angeles")),
+            ],
+            &settings,
+            &clock,
+        )
+        .unwrap();
+
+        assert_eq!(
+            targets,
+            [
+                Target {
+                    alias: "tokyo".to_string(),
+                    address: "Tokyo, JP".to_string(),
+                    today: "2024-01-16".to_string(),
+                    tomorrow: "2024-01-17".to_string(),
+                },
+                Target {
+                    alias: "la".to_string(),
+                    address: "Los Angeles, US".to_string(),
+                    today: "2024-01-15".to_string(),
+                    tomorrow: "2024-01-16".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_targets_errors() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 1, 15, 20, 0, 0).unwrap());
+        let mut settings = Settings::default();
+        settings
+            .addresses
+            .insert("home".to_string(), "London".to_string());
+
+        let error = resolve_targets(&[location("work", None)], &settings, &clock).unwrap_err();
+        assert!(
+            error.to_string().contains("'work' is not defined"),
+            "{error}"
+        );
+
+        let error =
+            resolve_targets(&[location("home", Some("Nowhere"))], &settings, &clock).unwrap_err();
+        assert!(error.to_string().contains("Nowhere"), "{error}");
+
+        assert!(resolve_targets(&[], &settings, &clock).unwrap().is_empty());
+    }
+}
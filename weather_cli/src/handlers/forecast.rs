@@ -0,0 +1,159 @@
+//! # Multi-Day Handler
+//!
+//! Fetches weather for several consecutive days of one location and prints one line per day,
+//! followed by terminal charts when the output is an interactive terminal.
+
+use super::weather::{
+    resolve_address, resolve_display_options, resolve_provider_options, resolve_routed_provider,
+};
+use crate::{
+    common::{
+        fetch::{FetchLimits, cancel_on_ctrl_c, fetch_many, fetch_weather},
+        *,
+    },
+    models::args::{DisplayArgs, OutputFormat},
+    output::chart::{DEFAULT_WIDTH, render_charts},
+};
+use ::chrono::{Days, NaiveDate};
+use ::std::io::IsTerminal;
+use ::terminal_size::{Width, terminal_size};
+use ::weather_providers::{OpenWeatherEndpoint, create_provider_with, normalize_date};
+
+/// Retrieves and displays weather information for consecutive days.
+///
+/// Today and past days are requested concurrently (with a bounded number of requests in
+/// flight) from the same provider, and the future days together as one forecast, so
+/// OpenWeather, which always uses its One Call endpoint here, is requested only once for them.
+/// In text output, a temperature bar chart and a precipitation sparkline scaled to the terminal
+/// width are printed below the daily lines; the charts are skipped if the standard output is
+/// not a terminal.
+///
+/// # Arguments
+///
+/// * `address` - An optional location string or alias. If `None`, the default alias is used.
+/// * `date` - The first day. If `None` (or not a valid date), today is used.
+/// * `days` - The number of days to retrieve.
+/// * `provider` - An optional provider identifier. If `None`, the first routing rule matching
+///   the address chooses it, or the default provider is used.
+/// * `output` - The output format, text or JSON; `validate_get_args` rejects the others.
+/// * `display` - Text display flags overriding the display options stored in the configuration.
+///
+/// # Errors
+///
+/// Returns an error if the provider or address cannot be resolved, or the weather of any of
+/// the days cannot be fetched.
+pub async fn get_weather_days(
+    address: Option<String>,
+    date: Option<String>,
+    days: u8,
+    provider: Option<String>,
+    output: OutputFormat,
+    display: DisplayArgs,
+) -> Result<()> {
+    let address = resolve_address(address)?;
+    let (provider, api_key, _) = resolve_routed_provider(provider, &address)?;
+
+    if output == OutputFormat::Text {
+        println!("Fetching {days}-day weather from '{provider}' for '{address}'...");
+    }
+
+    let dates = consecutive_dates(date.as_deref(), days)?;
+    // The full One Call endpoint covers today and the next 7 days, unlike `day_summary`
+    let mut options = resolve_provider_options(&provider)?;
+    options.open_weather_endpoint = OpenWeatherEndpoint::OneCall;
+    let weather_provider = create_provider_with(provider.clone(), &options);
+
+    // Dates in the `YYYY-MM-DD` format sort as strings
+    let today = normalize_date(None::<&str>);
+    let forecast = weather_provider.as_forecast();
+    let (days, future) = match forecast {
+        Some(_) => dates.split_at(dates.partition_point(|date| *date <= today)),
+        None => (dates.as_slice(), &[][..]),
+    };
+    let cancel = cancel_on_ctrl_c();
+    let mut weather = fetch_many(
+        days.to_vec(),
+        FetchLimits::default(),
+        &cancel,
+        async |date| {
+            Ok(fetch_weather(
+                weather_provider.as_ref(),
+                &provider,
+                api_key.as_deref(),
+                &address,
+                Some(date.as_str()),
+            )
+            .await?)
+        },
+    )
+    .await
+    .into_iter()
+    .map(|(_, result)| result)
+    .collect::<Result<Vec<_>>>()?;
+    if let Some(forecast) = forecast
+        && !future.is_empty()
+    {
+        weather.extend(tokio::select! {
+            _ = cancel.cancelled() => Err("Request cancelled.")?,
+            result = forecast.get_forecast_days(api_key.as_deref(), &address, future) => result?,
+        });
+    }
+
+    if output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&weather).map_err(|e| e.to_string())?
+        );
+        return Ok(());
+    }
+
+    let mut display_options = resolve_display_options()?;
+    display.apply(&mut display_options);
+    display_options.show_date = true;
+
+    for info in &weather {
+        println!("{}", info.format_for_display(&display_options));
+    }
+
+    if std::io::stdout().is_terminal() {
+        let width = terminal_size()
+            .map(|(Width(w), _)| w as usize)
+            .unwrap_or(DEFAULT_WIDTH);
+
+        println!();
+        for line in render_charts(&weather, width) {
+            println!("{line}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns `days` consecutive dates in the `YYYY-MM-DD` format, starting at `start`.
+fn consecutive_dates(start: Option<&str>, days: u8) -> Result<Vec<String>> {
+    let start =
+        NaiveDate::parse_from_str(&normalize_date(start), "%Y-%m-%d").map_err(|e| e.to_string())?;
+
+    (0..days as u64)
+        .map(|offset| {
+            start
+                .checked_add_days(Days::new(offset))
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .ok_or_else(|| "Date out of range.".into())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consecutive_dates() {
+        assert_eq!(
+            consecutive_dates(Some("2024-02-28"), 3).unwrap(),
+            ["2024-02-28", "2024-02-29", "2024-03-01"]
+        );
+        assert_eq!(consecutive_dates(None, 5).unwrap().len(), 5);
+    }
+}
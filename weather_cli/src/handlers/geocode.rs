@@ -0,0 +1,41 @@
+//! # Geocode Handlers
+//!
+//! This module resolves addresses into coordinates using a provider's geocoding API,
+//! without fetching any weather data.
+
+use super::weather::{resolve_address, resolve_provider_options, resolve_routed_provider};
+use crate::common::*;
+use ::weather_providers::create_geocoder_with;
+
+/// Geocodes an address and prints its normalized name and coordinates.
+///
+/// The provider is selected with the same logic as the `get` command: the explicit
+/// `provider` argument first, then the routing rules matching the address, then the
+/// configured default. Address aliases are resolved before geocoding.
+///
+/// # Arguments
+///
+/// * `address` - The location string or alias to geocode.
+/// * `provider` - An optional provider identifier (e.g., "ow", "wa").
+///
+/// # Returns
+///
+/// Returns an `Error` if the provider cannot be resolved, does not support geocoding,
+/// or fails to find the location.
+pub async fn geocode(address: String, provider: Option<String>) -> Result<()> {
+    let address = resolve_address(Some(address))?;
+    let (provider, api_key, _) = resolve_routed_provider(provider, &address)?;
+
+    println!("Geocoding '{address}' with '{provider}'...");
+
+    let options = resolve_provider_options(&provider)?;
+    let location = create_geocoder_with(provider, &options)?
+        .geocode(api_key.as_deref(), &address)
+        .await?;
+
+    println!("Location: {}, {}", location.city, location.country);
+    println!("Latitude: {:.4}", location.lat);
+    println!("Longitude: {:.4}", location.lon);
+
+    Ok(())
+}
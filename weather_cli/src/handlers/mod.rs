@@ -1,5 +1,19 @@
 mod alias;
+mod anomalies;
+mod batch;
+mod compare;
+mod complete;
+mod config;
+mod daemon;
+mod digest;
+mod forecast;
+mod geocode;
+mod prefetch;
 mod provider;
+mod watch;
 mod weather;
 
-pub use self::{alias::*, provider::*, weather::*};
+pub use self::{
+    alias::*, anomalies::*, batch::*, compare::*, complete::*, config::*, daemon::*, digest::*,
+    forecast::*, geocode::*, prefetch::*, provider::*, watch::*, weather::*,
+};
@@ -0,0 +1,349 @@
+//! # Prefetch Handler
+//!
+//! Fetches the weather of the usual locations when a shell starts, so their observations are
+//! already recorded by the time they are asked for. Prefetching never fails and never blocks
+//! the shell for long: it stops at a short deadline and only logs its errors.
+
+use super::weather::{
+    describe_providers, record_history, resolve_address, resolve_provider_options,
+    resolve_routed_providers,
+};
+use crate::{
+    common::{
+        fetch::{FetchLimits, fetch_each},
+        history::persisted_key,
+        *,
+    },
+    models::{config::Settings, routing::Routed},
+};
+use ::std::{
+    process::{Command, Stdio},
+    time::Duration,
+};
+use ::tokio_util::sync::CancellationToken;
+use ::tracing::debug;
+use ::weather_providers::{Provider, WeatherInfo, WeatherProvider, create_provider_with};
+
+/// The providers and locations of a prefetch.
+struct Prefetch {
+    /// The provider of each address, with its API key and client.
+    providers: Routed<(Provider, Option<String>, Box<dyn WeatherProvider>)>,
+    addresses: Vec<String>,
+}
+
+/// The outcome of a prefetch.
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    fetched: usize,
+    failed: usize,
+    /// The requests still running at the deadline.
+    unfinished: usize,
+}
+
+/// Fetches the weather of several locations concurrently and records it in the history.
+///
+/// Requests still running when the `deadline` expires are abandoned. Nothing is printed
+/// unless `verbose` is set; errors are only logged, so a shell profile running
+/// `weather prefetch` never shows them.
+///
+/// # Arguments
+///
+/// * `locations` - The addresses or aliases to fetch. If empty, the default alias and the
+///   aliases of the digest are fetched.
+/// * `provider` - An optional provider identifier. If `None`, the first routing rule matching
+///   each address chooses its provider, or the default provider is used.
+/// * `deadline` - The maximum time the prefetch may take.
+/// * `verbose` - Print the outcome of every location.
+///
+/// # Returns
+///
+/// Always `Ok(())`, even if every request failed.
+pub async fn prefetch(
+    locations: Vec<String>,
+    provider: Option<String>,
+    deadline: Duration,
+    verbose: bool,
+) -> Result<()> {
+    let Prefetch {
+        providers,
+        addresses,
+    } = match resolve_prefetch(locations, provider) {
+        Ok(prefetch) => prefetch,
+        Err(e) => {
+            debug!("Prefetch skipped: {e}");
+            if verbose {
+                println!("Prefetch skipped: {e}");
+            }
+            return Ok(());
+        }
+    };
+    let privacy = APP_STATE
+        .config
+        .get()
+        .ok()
+        .and_then(|config| config.privacy.clone())
+        .unwrap_or_default();
+
+    let summary = fetch_until(
+        addresses,
+        deadline,
+        async |address| {
+            let (_, api_key, weather_provider) = providers.get(address);
+            Ok(weather_provider
+                .get_weather(api_key.as_deref(), address)
+                .await?)
+        },
+        |address, result| match result {
+            Ok(weather_info) => {
+                record_history(address, &providers.get(address).0, weather_info);
+                if verbose {
+                    println!("Prefetched '{address}'.");
+                }
+            }
+            Err(e) => {
+                // The log keeps the location only as the history does
+                let key = persisted_key(address, &privacy);
+                debug!("Failed to prefetch '{key}': {e}");
+                if verbose {
+                    println!("Failed to prefetch '{address}': {e}");
+                }
+            }
+        },
+    )
+    .await;
+
+    if summary.unfinished > 0 {
+        debug!(
+            "Prefetch stopped at its deadline of {}s with {} locations unfinished.",
+            deadline.as_secs_f32(),
+            summary.unfinished
+        );
+    }
+    if verbose {
+        println!(
+            "Prefetched {} of {} locations from {}.",
+            summary.fetched,
+            summary.fetched + summary.failed + summary.unfinished,
+            describe_providers(providers.values().map(|(provider, ..)| provider))
+        );
+    }
+
+    Ok(())
+}
+
+/// Starts the prefetch as a detached process with its standard streams closed, so the shell
+/// returns immediately.
+///
+/// # Returns
+///
+/// Always `Ok(())`; a process that cannot be started is only logged.
+pub fn prefetch_in_background(
+    locations: &[String],
+    provider: Option<&str>,
+    deadline: Duration,
+) -> Result<()> {
+    let spawned = std::env::current_exe().and_then(|exe| {
+        let mut command = Command::new(exe);
+        command
+            .args(["prefetch", "--deadline", &deadline.as_secs().to_string()])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if !locations.is_empty() {
+            command.arg("--locations").args(locations);
+        }
+        if let Some(provider) = provider {
+            command.args(["--provider", provider]);
+        }
+        detach(&mut command);
+
+        command.spawn()
+    });
+
+    match spawned {
+        Ok(child) => debug!("Prefetch started in the background (pid {}).", child.id()),
+        Err(e) => debug!("Failed to start the prefetch in the background: {e}"),
+    }
+
+    Ok(())
+}
+
+/// Moves the process into its own process group, so it outlives the shell's job control.
+#[cfg(unix)]
+fn detach(command: &mut Command) {
+    use ::std::os::unix::process::CommandExt;
+
+    command.process_group(0);
+}
+
+/// Starts the process without a console, so it outlives the console of the shell.
+#[cfg(windows)]
+fn detach(command: &mut Command) {
+    use ::std::os::windows::process::CommandExt;
+
+    const DETACHED_PROCESS: u32 = 0x0000_0008;
+    command.creation_flags(DETACHED_PROCESS);
+}
+
+#[cfg(not(any(unix, windows)))]
+fn detach(_command: &mut Command) {}
+
+/// Resolves the addresses to prefetch and their providers.
+///
+/// # Errors
+///
+/// Returns an error if a provider cannot be resolved or there are no locations to fetch.
+fn resolve_prefetch(locations: Vec<String>, provider: Option<String>) -> Result<Prefetch> {
+    let addresses = match locations.is_empty() {
+        true => default_addresses(&*APP_STATE.config.get()?),
+        false => locations
+            .into_iter()
+            .map(|location| resolve_address(Some(location)))
+            .collect::<Result<_>>()?,
+    };
+
+    if addresses.is_empty() {
+        Err(
+            "No locations to prefetch. Set a default alias or digest locations, \
+             or use --locations.",
+        )?;
+    }
+
+    let providers =
+        resolve_routed_providers(provider, &addresses)?.try_map(|(provider, api_key)| {
+            let options = resolve_provider_options(&provider)?;
+            Ok((
+                provider.clone(),
+                api_key,
+                create_provider_with(provider, &options),
+            ))
+        })?;
+
+    Ok(Prefetch {
+        providers,
+        addresses,
+    })
+}
+
+/// Returns the addresses of the default alias and of the digest locations, without duplicates.
+///
+/// Aliases that are not defined are skipped.
+fn default_addresses(settings: &Settings) -> Vec<String> {
+    let digest_aliases = settings
+        .digest
+        .iter()
+        .flat_map(|digest| digest.locations.iter().map(|location| &location.alias));
+
+    let mut addresses: Vec<String> = Vec::new();
+    for alias in settings.default_alias.iter().chain(digest_aliases) {
+        match settings.addresses.get(alias) {
+            Some(address) if !addresses.contains(address) => addresses.push(address.clone()),
+            Some(_) => {}
+            None => debug!("Prefetch skips the undefined alias '{alias}'."),
+        }
+    }
+
+    addresses
+}
+
+/// Fetches every address concurrently until the `deadline`, passing each result to
+/// `on_result` as soon as it completes. Requests still running at the deadline are abandoned.
+async fn fetch_until<F, C>(
+    addresses: Vec<String>,
+    deadline: Duration,
+    fetch: F,
+    mut on_result: C,
+) -> Summary
+where
+    F: AsyncFn(&String) -> Result<WeatherInfo>,
+    C: FnMut(&str, &Result<WeatherInfo>),
+{
+    let total = addresses.len();
+    // The deadline bounds all the requests together, not each of them
+    let limits = FetchLimits {
+        concurrency: total,
+        ..FetchLimits::default()
+    };
+    let mut summary = Summary::default();
+    let cancel = CancellationToken::new();
+
+    let fetching = fetch_each(addresses, limits, &cancel, fetch, |address, result| {
+        match result {
+            Ok(_) => summary.fetched += 1,
+            Err(_) => summary.failed += 1,
+        }
+        on_result(address, result);
+    });
+    let _ = tokio::time::timeout(deadline, fetching).await;
+
+    summary.unfinished = total - summary.fetched - summary.failed;
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::config::{DigestLocation, DigestSettings};
+    use ::std::{collections::BTreeMap, time::Instant};
+    use ::weather_providers::{Provider, create_provider};
+
+    #[tokio::test]
+    async fn test_fetch_until_enforces_deadline() {
+        let addresses = vec!["fast".to_string(), "slow".to_string(), "fail".to_string()];
+        let mut completed = Vec::new();
+
+        let started = Instant::now();
+        let summary = fetch_until(
+            addresses,
+            Duration::from_millis(200),
+            async |address| match address.as_str() {
+                "slow" => {
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                    unreachable!("abandoned at the deadline")
+                }
+                "fail" => Err("Provider server error: try again later".into()),
+                _ => Ok(create_provider(Provider::Mock)
+                    .get_weather(None, address)
+                    .await?),
+            },
+            |address, _| completed.push(address.to_string()),
+        )
+        .await;
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(
+            summary,
+            Summary {
+                fetched: 1,
+                failed: 1,
+                unfinished: 1
+            }
+        );
+        completed.sort();
+        assert_eq!(completed, ["fail", "fast"]);
+    }
+
+    #[test]
+    fn test_default_addresses() {
+        let settings = Settings {
+            addresses: BTreeMap::from([
+                ("home".to_string(), "London, UK".to_string()),
+                ("work".to_string(), "Paris, FR".to_string()),
+            ]),
+            default_alias: Some("home".to_string()),
+            digest: Some(DigestSettings {
+                locations: ["work", "home", "gone"]
+                    .map(|alias| DigestLocation {
+                        alias: alias.to_string(),
+                        timezone: None,
+                    })
+                    .to_vec(),
+                ..Default::default()
+            }),
+            ..Settings::default()
+        };
+
+        assert_eq!(default_addresses(&settings), ["London, UK", "Paris, FR"]);
+        assert!(default_addresses(&Settings::default()).is_empty());
+    }
+}
@@ -2,42 +2,116 @@
 //!
 //! This module contains handler functions for managing weather service providers.
 
-use crate::common::*;
-use ::clap::ValueEnum;
-use ::weather_providers::Provider;
+use super::{config::BACKUP_TIP, weather::resolve_provider_options};
+use crate::{
+    common::*,
+    models::{
+        args::ReportFormat,
+        config::{KEYCHAIN_KEY_REF, ProviderConfig},
+    },
+    output::table::Table,
+};
+use ::serde::Serialize;
+use ::weather_providers::{
+    Provider, ProviderDescriptor, ProviderInfo, ProviderMetadata, ProviderOptions,
+    available_providers, create_provider, create_provider_with,
+};
+
+/// The widest API key shown by `list_providers`, in terminal cells; longer keys are truncated.
+const MAX_KEY_WIDTH: usize = 24;
 
 /// Lists all supported weather providers and their current configuration status.
 ///
-/// This function iterates through all available variants of `Provider` and checks
-/// the application configuration to see if an API key is set for each.
-/// It prints a formatted table to the standard output.
+/// This function iterates through the providers of `available_providers`, and checks the
+/// application configuration to see if an API key is set for each. Keys stored in the OS
+/// keychain are shown as `(keychain)`. Providers not compiled into this build are noted.
+/// It prints a formatted table to the standard output, or a JSON array of `ProviderStatus`.
+///
+/// # Arguments
+///
+/// * `ping` - If `true`, runs each configured provider's health check and adds a latency column.
+/// * `format` - Print a human-readable table or JSON.
 ///
 /// # Returns
 ///
 /// Returns `Ok(())` if the list was successfully printed.
-pub fn list_providers() -> Result<()> {
-    let config = APP_STATE.config.get()?;
+pub async fn list_providers(ping: bool, format: ReportFormat) -> Result<()> {
+    let (provider_configs, default_provider) = {
+        let config = APP_STATE.config.get()?;
+        let provider_configs = available_providers()
+            .iter()
+            .map(|descriptor| config.providers.get(descriptor.id).cloned())
+            .collect::<Vec<_>>();
+        (provider_configs, config.default_provider.clone())
+    };
+
+    if format == ReportFormat::Json {
+        let mut statuses = Vec::new();
+        for (descriptor, provider_config) in available_providers().iter().zip(provider_configs) {
+            let provider_config = provider_config.unwrap_or_default();
+            let ping = match ping {
+                true => Some(ping_configured(descriptor, &provider_config).await),
+                false => None,
+            };
+            statuses.push(ProviderStatus {
+                id: descriptor.id.to_string(),
+                name: descriptor.name.to_string(),
+                key_configured: provider_config.has_key(),
+                is_default: default_provider.as_deref() == Some(descriptor.id),
+                ping,
+            });
+        }
+        println!("{}", serde_json::to_string_pretty(&statuses)?);
+        return Ok(());
+    }
 
     println!("Weather providers:\n");
-    println!(
-        "{:<5} | {:<15} | {:<10}\n------+-----------------+---------",
-        "ID", "PROVIDER", "API KEY"
-    );
+    let mut table = Table::new(&[
+        "ID",
+        "PROVIDER",
+        "API KEY",
+        if ping { "LATENCY" } else { "WEBSITE" },
+    ])
+    .max_width(2, MAX_KEY_WIDTH);
+
+    for (descriptor, provider_config) in available_providers().iter().zip(provider_configs) {
+        let provider_config = provider_config.unwrap_or_default();
+
+        let key_display = match provider_config
+            .key
+            .as_deref()
+            .or(provider_config.client_id.as_deref())
+        {
+            _ if provider_config.is_keychain() => "(keychain)",
+            Some(key) if !key.is_empty() => key,
+            _ if descriptor.requires_key => "-",
+            _ => "not required",
+        };
+
+        let last_column = if ping {
+            ping_configured(descriptor, &provider_config).await
+        } else {
+            descriptor.provider.metadata().website.to_string()
+        };
 
-    for provider in Provider::value_variants() {
-        let provider_id = provider.id();
-        let key = config
-            .providers
-            .get(provider_id)
-            .and_then(|p| p.key.as_deref())
-            .filter(|k| !k.is_empty())
-            .unwrap_or("-");
+        table.row([
+            descriptor.id,
+            descriptor.name,
+            key_display,
+            last_column.as_str(),
+        ]);
+    }
+    println!("{}\n", table.render());
 
-        println!("{:<5} | {:<15} | {:<10}", provider_id, provider.name(), key);
+    for descriptor in available_providers() {
+        if !descriptor.compiled_in {
+            println!("Note ({}): Not compiled into this build.", descriptor.id);
+        } else if let Some(notes) = descriptor.provider.metadata().notes {
+            println!("Note ({}): {notes}", descriptor.id);
+        }
     }
-    println!();
 
-    match &config.default_provider {
+    match &default_provider {
         Some(id) => {
             let display_name = Provider::try_from(id.as_str())
                 .map(|p| p.to_string())
@@ -50,61 +124,302 @@ pub fn list_providers() -> Result<()> {
     Ok(())
 }
 
+/// The JSON representation of a provider in `weather provider --list --format json`.
+#[derive(Serialize)]
+struct ProviderStatus {
+    id: String,
+    name: String,
+    key_configured: bool,
+    is_default: bool,
+    /// The outcome of the health check, with `--ping`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ping: Option<String>,
+}
+
+/// Resolves the configured key and options of a provider and runs its health check with
+/// `ping_provider`.
+async fn ping_configured(
+    descriptor: &ProviderDescriptor,
+    provider_config: &ProviderConfig,
+) -> String {
+    let resolved = keychain::resolve_key(descriptor.id, provider_config)
+        .and_then(|key| Ok((key, resolve_provider_options(&descriptor.provider)?)));
+    match resolved {
+        Ok((key, options)) => ping_provider(descriptor, key.as_deref(), &options).await,
+        Err(e) => format!("error: {e}"),
+    }
+}
+
+/// Runs the provider's health check and formats the outcome for the provider table.
+///
+/// Providers that are not compiled in, or require an API key but have none configured, are not
+/// contacted.
+async fn ping_provider(
+    descriptor: &ProviderDescriptor,
+    key: Option<&str>,
+    options: &ProviderOptions,
+) -> String {
+    if !descriptor.compiled_in {
+        return "not compiled in".to_string();
+    }
+    if descriptor.requires_key && key.is_none() {
+        return "-".to_string();
+    }
+
+    match create_provider_with(descriptor.provider.clone(), options)
+        .health_check(key)
+        .await
+    {
+        Ok(latency) => format!("{} ms", latency.as_millis()),
+        Err(e) => format!("error: {e}"),
+    }
+}
+
+/// The JSON representation of `weather provider <id> --info`.
+#[derive(Serialize)]
+struct ProviderReport {
+    #[serde(flatten)]
+    metadata: ProviderMetadata,
+    #[serde(flatten)]
+    info: ProviderInfo,
+}
+
+/// Prints getting-started information about a provider.
+///
+/// The information comes from the provider implementation itself (see `WeatherProvider::info`).
+///
+/// # Arguments
+///
+/// * `provider` - The identifier of the provider (e.g., "ow", "wa").
+/// * `format` - Print human-readable text or JSON.
+///
+/// # Errors
+///
+/// Returns an error if the provider is unknown.
+pub fn provider_info(provider: impl AsRef<str>, format: ReportFormat) -> Result<()> {
+    let provider = Provider::try_from(provider.as_ref())?;
+    let metadata = provider.metadata();
+    let info = create_provider(provider).info();
+
+    match format {
+        ReportFormat::Json => {
+            let report = ProviderReport { metadata, info };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        ReportFormat::Text => {
+            println!("{} ({})\n", metadata.name, metadata.id);
+            println!("{:<14}{}", "Website:", metadata.website);
+            println!("{:<14}{}", "Sign up:", info.signup_url);
+            println!(
+                "{:<14}{}",
+                "API key:",
+                if metadata.requires_key {
+                    info.key_format
+                } else {
+                    "Not required"
+                }
+            );
+            println!("{:<14}{}", "Free tier:", info.free_tier);
+            println!("{:<14}{}", "Capabilities:", info.capabilities.join(", "));
+            println!("{:<14}{}", "Example:", info.example);
+            if let Some(notes) = metadata.notes {
+                println!("{:<14}{notes}", "Notes:");
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Configures a provider and optionally sets it as the default.
 ///
 /// # Actions
 /// 1. **Updates API Key**: If a `key` is provided and not empty, it updates the stored key for the provider.
+///    With `keychain`, the key is written to the OS keychain and only a `key_ref` marker is saved.
 /// 2. **Sets Default**: Attempts to make this provider the global default.
-///    - **Success**: If the provider is `Mock` OR if a valid API key exists (either newly set or previously saved).
-///    - **Warning**: If attempting to set a non-Mock provider as default without an API key, the default provider
+///    - **Success**: If the provider does not require a key OR if a valid API key exists (either newly set or previously saved).
+///    - **Warning**: If attempting to set a key-based provider as default without an API key, the default provider
 ///      will *not* be changed, and a warning will be displayed.
 ///
 /// # Arguments
 ///
 /// * `provider` - The identifier of the provider (e.g., "ow", "wa").
 /// * `key` - An optional API key.
+/// * `keychain` - Store the key in the OS keychain instead of the configuration file.
 ///
 /// # Returns
 ///
 /// Returns `Ok(())` if the configuration process is completed (even if a warning was issued).
-pub fn set_provider(provider: impl AsRef<str>, key: Option<impl AsRef<str>>) -> Result<()> {
+/// Returns an `Error` if the key cannot be written to the keychain.
+pub async fn set_provider(
+    provider: impl AsRef<str>,
+    key: Option<impl AsRef<str>>,
+    keychain: bool,
+) -> Result<()> {
     let provider = Provider::try_from(provider.as_ref())?;
 
     let key_to_set = key.as_ref().map(|k| k.as_ref()).filter(|k| !k.is_empty());
 
+    // Client credentials are stored as separate fields of the provider configuration
+    let client_credentials = match key_to_set {
+        Some(k) if provider == Provider::Foreca => Some(k.split_once(':').ok_or_else(|| {
+            format!("Invalid key for '{provider}'. Expected format: <CLIENT_ID>:<CLIENT_SECRET>")
+        })?),
+        _ => None,
+    };
+
+    if keychain && let Some(k) = key_to_set {
+        keychain::store_key(provider.id(), k)?;
+    }
+
     let mut message = String::new();
 
-    APP_STATE.config.with_mut(|state| {
-        if let Some(k) = key_to_set {
+    APP_STATE
+        .config
+        .with_mut(|state| {
+            if let Some(k) = key_to_set {
+                let provider_config = state
+                    .providers
+                    .entry(provider.id().to_string())
+                    .or_default();
+
+                provider_config.client_id = None;
+                provider_config.client_secret = None;
+
+                if keychain {
+                    provider_config.key = None;
+                    provider_config.key_ref = Some(KEYCHAIN_KEY_REF.to_string());
+                    message.push_str(&format!(
+                        "API key for '{provider}' stored in the OS keychain.\n"
+                    ));
+                } else if let Some((client_id, client_secret)) = client_credentials {
+                    provider_config.key = None;
+                    provider_config.key_ref = None;
+                    provider_config.client_id = Some(client_id.to_string());
+                    provider_config.client_secret = Some(client_secret.to_string());
+                    message.push_str(&format!("Client credentials for '{provider}' updated.\n"));
+                } else {
+                    provider_config.key = Some(k.to_string());
+                    provider_config.key_ref = None;
+                    message.push_str(&format!("API key for '{provider}' updated.\n"));
+                }
+            }
+
+            let has_key = state
+                .providers
+                .get(provider.id())
+                .is_some_and(|p| p.has_key());
+
+            if !provider.metadata().requires_key || has_key {
+                let changed = state.default_provider.as_deref() != Some(provider.id());
+                state.default_provider = Some(provider.id().to_string());
+                message.push_str(&format!("Default provider set to: '{provider}'\n"));
+                if changed {
+                    message.push_str(BACKUP_TIP);
+                    message.push('\n');
+                }
+            } else {
+                message.push_str(&format!(
+                    "WARNING: API key not found for '{provider}'. Default provider NOT changed.\n\
+                Please set the key first using --key <API_KEY>"
+                ));
+            }
+        })
+        .await?;
+
+    if !message.is_empty() {
+        println!("{message}");
+    }
+
+    Ok(())
+}
+
+/// Disables TLS certificate verification for a provider.
+///
+/// Meant for capturing provider traffic through a local HTTPS proxy with a self-signed
+/// certificate. Every request to the provider prints a warning while verification is disabled.
+///
+/// # Arguments
+///
+/// * `provider` - The provider identifier (e.g., "ow").
+///
+/// # Errors
+///
+/// Returns an `Error` if the provider is unknown or the configuration cannot be saved.
+#[cfg(feature = "danger")]
+pub async fn disable_tls_verify(provider: impl AsRef<str>) -> Result<()> {
+    let provider = Provider::try_from(provider.as_ref())?;
+
+    APP_STATE
+        .config
+        .with_mut(|state| {
             state
                 .providers
                 .entry(provider.id().to_string())
                 .or_default()
-                .key = Some(k.to_string());
-            message.push_str(&format!("API key for '{provider}' updated.\n"));
-        }
+                .tls_verify = false;
+        })
+        .await?;
 
-        let has_key = state
-            .providers
-            .get(provider.id())
-            .and_then(|p| p.key.as_deref())
-            .filter(|k| !k.is_empty())
-            .is_some();
+    eprintln!(
+        "WARNING: TLS certificate verification disabled for '{provider}'. \
+         Set \"tls_verify\" to true in the configuration file to enable it again."
+    );
 
-        if provider.is_mock() || has_key {
-            state.default_provider = Some(provider.id().to_string());
-            message.push_str(&format!("Default provider set to: '{provider}'\n"));
-        } else {
-            message.push_str(&format!(
-                "WARNING: API key not found for '{provider}'. Default provider NOT changed.\n\
-                Please set the key first using --key <API_KEY>"
-            ));
-        }
-    })?;
+    Ok(())
+}
 
-    if !message.is_empty() {
-        println!("{message}");
+/// Moves the plain-text API key of a provider into the OS keychain.
+///
+/// The key is written to the keychain first; only after that succeeds is it removed from
+/// the configuration file and replaced by a `key_ref` marker.
+///
+/// # Arguments
+///
+/// * `provider` - The identifier of the provider (e.g., "ow", "wa").
+///
+/// # Returns
+///
+/// Returns an `Error` if the provider has no plain-text key or the keychain cannot be written.
+pub async fn migrate_key_to_keychain(provider: impl AsRef<str>) -> Result<()> {
+    let provider = Provider::try_from(provider.as_ref())?;
+
+    let provider_config = APP_STATE
+        .config
+        .get()?
+        .providers
+        .get(provider.id())
+        .cloned()
+        .unwrap_or_default();
+
+    if provider_config.is_keychain() {
+        println!("API key for '{provider}' is already stored in the OS keychain.");
+        return Ok(());
     }
 
+    let Some(key) = provider_config.plain_key() else {
+        Err(format!(
+            "No plain-text API key found for '{provider}'. Nothing to migrate."
+        ))?
+    };
+
+    keychain::store_key(provider.id(), &key)?;
+
+    APP_STATE
+        .config
+        .with_mut(|state| {
+            let provider_config = state
+                .providers
+                .entry(provider.id().to_string())
+                .or_default();
+            provider_config.key = None;
+            provider_config.client_id = None;
+            provider_config.client_secret = None;
+            provider_config.key_ref = Some(KEYCHAIN_KEY_REF.to_string());
+        })
+        .await?;
+
+    println!("API key for '{provider}' moved to the OS keychain.");
+
     Ok(())
 }
@@ -0,0 +1,122 @@
+//! # Watch Handler
+//!
+//! Polls the weather of a location and prints it whenever it changes, until Ctrl+C.
+
+use super::weather::{
+    resolve_address, resolve_display_options, resolve_provider_options, resolve_routed_provider,
+};
+use crate::{
+    common::{fetch::cancel_on_ctrl_c, *},
+    models::args::ReportFormat,
+    output::ndjson::RecordWriter,
+};
+use ::std::{
+    io::{self, Write},
+    time::Duration,
+};
+use ::tokio::time::{self, Instant, Interval, MissedTickBehavior};
+use ::weather_providers::{PressureTracker, create_provider_with};
+
+/// Polls the weather of a location and prints it each time it changes.
+///
+/// The weather is fetched every `interval` and printed when it differs from the last one
+/// printed. The pressure tendency is derived from consecutive polls when the provider reports
+/// the pressure (see `PressureTracker`). With `ReportFormat::Json`, each change is written as
+/// one compact JSON record per line (NDJSON) and flushed right away, so a process reading the
+/// pipe receives it immediately. A failed poll is reported on the standard error and the watch
+/// goes on. Pressing Ctrl+C stops the watch.
+///
+/// # Arguments
+///
+/// * `address` - An optional location string or alias. If `None`, the default alias is used.
+/// * `provider` - An optional provider identifier. If `None`, the first routing rule matching
+///   the address chooses it, or the default provider is used.
+/// * `interval` - The time between polls.
+/// * `output` - The output format: a text line or an NDJSON record per change.
+/// * `heartbeat` - If set, an empty record (`{}`) is also written at this interval, even when
+///   the weather has not changed, so consumers can tell that the watch is alive. JSON only.
+///
+/// # Errors
+///
+/// Returns an error if `heartbeat` is set for text output, the provider or address cannot be
+/// resolved, or the standard output cannot be written.
+pub async fn watch(
+    address: Option<String>,
+    provider: Option<String>,
+    interval: Duration,
+    output: ReportFormat,
+    heartbeat: Option<Duration>,
+) -> Result<()> {
+    if heartbeat.is_some() && output != ReportFormat::Json {
+        Err("--heartbeat only applies to --output json. Remove --heartbeat or use --output json.")?;
+    }
+
+    let address = resolve_address(address)?;
+    let (provider, api_key, _) = resolve_routed_provider(provider, &address)?;
+    let display_options = match output {
+        ReportFormat::Text => Some(resolve_display_options()?),
+        ReportFormat::Json => None,
+    };
+
+    // The standard output may carry the records, so progress goes to the standard error
+    eprintln!(
+        "Watching the weather from '{provider}' for '{address}' every {}s. \
+         Press Ctrl+C to stop.",
+        interval.as_secs()
+    );
+
+    let weather_provider =
+        create_provider_with(provider.clone(), &resolve_provider_options(&provider)?);
+    let cancel = cancel_on_ctrl_c();
+    let mut writer = RecordWriter::stdout();
+    let pressure = PressureTracker::default();
+    let mut last = None;
+
+    let mut polls = time::interval(interval);
+    polls.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut heartbeats = heartbeat.map(|period| time::interval_at(Instant::now() + period, period));
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+            _ = polls.tick() => {
+                let result = tokio::select! {
+                    _ = cancel.cancelled() => return Ok(()),
+                    result = weather_provider.get_weather(api_key.as_deref(), &address) => result,
+                };
+
+                match result {
+                    Ok(info) => {
+                        let info = pressure.apply(&address, info);
+                        let value = serde_json::to_value(&info)?;
+                        if last.as_ref() == Some(&value) {
+                            continue;
+                        }
+
+                        match &display_options {
+                            Some(opts) => {
+                                let mut stdout = io::stdout().lock();
+                                writeln!(stdout, "{}", info.format_for_display(opts))?;
+                                stdout.flush()?;
+                            }
+                            None => writer.write(&value)?,
+                        }
+                        last = Some(value);
+                    }
+                    Err(e) => eprintln!("{e}"),
+                }
+            }
+            _ = tick(&mut heartbeats) => writer.heartbeat()?,
+        }
+    }
+}
+
+/// Waits for the next tick of the interval, or forever if there is none.
+async fn tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
@@ -4,8 +4,43 @@
 //! It acts as a bridge between the CLI input, the application configuration,
 //! and the specific weather provider services.
 
-use crate::common::*;
-use ::weather_providers::{Provider, create_provider};
+use super::alias::{check_new_alias, insert_alias};
+use crate::{
+    common::{
+        fetch::fetch_weather,
+        fs::write_atomic,
+        history::{History, HistoryEntry, persisted_key},
+        keychain,
+        locale::detect_locale_defaults,
+        *,
+    },
+    models::{
+        args::{DisplayArgs, OutputFormat},
+        config::{Settings, Units, WeightedProvider},
+        request::FollowUp,
+        routing::{Routed, RoutingRule, route},
+        window::TimeWindow,
+    },
+    output::{
+        alerts,
+        trend::{TrendAnnotations, is_comparable, trend},
+    },
+};
+use ::chrono::{NaiveDate, Utc};
+use ::rand::{Rng, distributions::WeightedIndex, prelude::Distribution};
+use ::serde::Serialize;
+use ::std::{
+    io::{IsTerminal, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+use ::tracing::{debug, warn};
+use ::weather_providers::{
+    DisplayOptions, ExpositionFormat, HmacSha256Signer, HourlyProvider, MetricsSample,
+    OpenWeatherEndpoint, Provider, ProviderOptions, RequestSigner, WeatherAlert, WeatherInfo,
+    WeatherProvider, WindUnit, available_providers, create_geocoder_with, create_provider_with,
+    render_metrics,
+};
 
 /// Retrieves and displays weather information for a specified location.
 ///
@@ -14,20 +49,33 @@ use ::weather_providers::{Provider, create_provider};
 ///
 /// # Process
 ///
-/// 1.  **Provider Resolution**: Determines which weather service (provider) to use.
-///     It checks the explicit `provider` argument first, falling back to the configuration's default.
-///     It also ensures the necessary API key is available.
-/// 2.  **Address Resolution**: Resolves the target location. If `address` matches a configured alias,
+/// 1.  **Address Resolution**: Resolves the target location. If `address` matches a configured alias,
 ///     it uses the mapped value; otherwise, it treats the input as a raw location string.
+/// 2.  **Provider Resolution**: Determines which weather service (provider) to use.
+///     It checks the explicit `provider` argument first, then the routing rules matching the
+///     address, falling back to the configuration's default.
+///     It also ensures the necessary API key is available.
 /// 3.  **Data Retrieval**: Instantiates the resolved provider and requests weather data, passing
 ///     the resolved address and optional date.
-/// 4.  **Display**: Prints the formatted weather information to the standard output.
+/// 4.  **History**: Records the observation and compares it with an earlier observation of the
+///     same location from today, if any, to show trend indicators (text and JSON output only).
+/// 5.  **Display**: Renders the weather information in the requested `output` format and prints it
+///     to the standard output, or writes it atomically to the `export` file. In text output,
+///     severe weather alerts are shown above the weather, highlighted in red on terminals.
+/// 6.  **Follow-up**: Saves the location as the `--save-as` alias (see `saved_address`), and
+///     checks the alert thresholds, in every hour of the `--window` if given. The alias is
+///     validated before the request, but only written once the weather was retrieved.
 ///
 /// # Arguments
 ///
 /// *   `address` - An optional location string or alias. If `None`, the application attempts to use the default alias from the config.
 /// *   `date` - An optional date string. The format is flexible (handled by the provider's normalization logic).
 /// *   `provider` - An optional provider identifier (e.g., "ow", "wa"). If `None`, the default provider is used.
+/// *   `output` - The output format (human-readable text, JSON, Prometheus or OpenMetrics exposition format).
+/// *   `export` - An optional file path. If set, the output is written to this file instead of the standard output.
+/// *   `display` - Text display flags overriding the display options stored in the configuration.
+/// *   `follow_up` - Save the location as an alias and/or check the weather, or the hours of a
+///     window, against the configured alert thresholds after printing.
 ///
 /// # Returns
 ///
@@ -35,47 +83,469 @@ use ::weather_providers::{Provider, create_provider};
 ///
 /// Returns an `Error` in the following cases:
 /// *   No address is specified and no default alias is found.
+/// *   The `--save-as` alias is invalid, or already exists without `--force`.
 /// *   The specified or default provider requires an API key that is missing from the configuration.
+/// *   A `--window` is given and the provider does not report hourly weather, or the sunrise or
+///     sunset the window needs.
 /// *   The weather provider encounters an error (e.g., network failure, invalid location).
+/// *   The export file cannot be written.
+/// *   `alert` is set and the weather, or an hour of the window, exceeds an alert threshold
+///     (`Error::Alert`, exit code 2).
 pub async fn get_weather(
     address: Option<String>,
     date: Option<String>,
     provider: Option<String>,
+    output: OutputFormat,
+    export: Option<PathBuf>,
+    display: DisplayArgs,
+    follow_up: FollowUp,
 ) -> Result<()> {
-    let (provider, api_key) = resolve_provider(provider)?;
     let address = resolve_address(address)?;
+    let (provider, api_key, _) = resolve_routed_provider(provider, &address)?;
+    let save_as = follow_up
+        .save_as
+        .map(|save_as| check_new_alias(&save_as.alias, save_as.force))
+        .transpose()?;
+
+    let options = window_options(
+        resolve_provider_options(&provider)?,
+        follow_up.window.as_ref(),
+    );
+    let weather_provider = create_provider_with(provider.clone(), &options);
+    let hourly = match follow_up.window {
+        Some(_) => Some(weather_provider.as_hourly().ok_or_else(|| {
+            format!(
+                "'{provider}' does not report hourly weather, which --window needs. \
+                 Use a provider with hourly data, e.g. --provider ow, or remove --window."
+            )
+        })?),
+        None => None,
+    };
+
+    if output == OutputFormat::Text {
+        println!("Fetching weather from '{provider}' for '{address}'...");
+    }
+
+    let weather_info = fetch_weather(
+        weather_provider.as_ref(),
+        &provider,
+        api_key.as_deref(),
+        &address,
+        date.as_deref(),
+    )
+    .await?;
+
+    let trend = record_history(&address, &provider, &weather_info);
+    let warnings = match output {
+        OutputFormat::Text => {
+            fetch_alerts(
+                weather_provider.as_ref(),
+                api_key.as_deref(),
+                &address,
+                &weather_info.date,
+            )
+            .await
+        }
+        _ => Vec::new(),
+    };
+
+    let rendered = match output {
+        OutputFormat::Text => {
+            let mut display_options = resolve_display_options()?;
+            display.apply(&mut display_options);
+            let annotations = trend
+                .as_ref()
+                .map(|t| t.to_display(&display_options))
+                .unwrap_or_default();
+            let color = export.is_none()
+                && alerts::supports_color(std::io::stdout().is_terminal(), |var| {
+                    std::env::var(var).ok()
+                });
+            format!(
+                "{}{}\n",
+                alerts::render(&warnings, color),
+                weather_info.format_for_display_with(&display_options, &annotations)
+            )
+        }
+        OutputFormat::Json => {
+            let report = JsonReport {
+                weather: &weather_info,
+                trend: trend.as_ref(),
+            };
+            format!(
+                "{}\n",
+                serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+            )
+        }
+        OutputFormat::Prometheus => {
+            render_sample(&weather_info, &provider, ExpositionFormat::Prometheus)
+        }
+        OutputFormat::OpenMetrics => {
+            render_sample(&weather_info, &provider, ExpositionFormat::OpenMetrics)
+        }
+    };
+
+    match export {
+        Some(path) => {
+            write_atomic(&path, |w| Ok(w.write_all(rendered.as_bytes())?))?;
+            if output == OutputFormat::Text {
+                println!("Weather exported to {path:?}");
+            }
+        }
+        None => print!("{rendered}"),
+    }
+
+    if let Some(alias) = save_as {
+        let saved = saved_address(&provider, &options, api_key.as_deref(), &address).await?;
+        let is_default = insert_alias(&alias, &saved).await?;
+        if output == OutputFormat::Text {
+            println!("Alias '{alias}' set to '{saved}'");
+            if is_default {
+                println!("Alias '{alias}' set as default.");
+            }
+        }
+    }
+
+    if follow_up.alert {
+        match (follow_up.window, hourly) {
+            (Some(window), Some(hourly)) => {
+                check_window_thresholds(
+                    hourly,
+                    api_key.as_deref(),
+                    &address,
+                    &window,
+                    &weather_info,
+                )
+                .await?
+            }
+            _ => check_alert_thresholds(&address, &weather_info)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders the weather as metrics labelled with the provider that returned it.
+fn render_sample(
+    weather_info: &WeatherInfo,
+    provider: &Provider,
+    format: ExpositionFormat,
+) -> String {
+    let sample = MetricsSample {
+        info: weather_info,
+        labels: vec![("provider", provider.id())],
+    };
+
+    render_metrics(&[sample], format)
+}
+
+/// Returns the severe weather warnings shown above the weather in the text output.
+///
+/// The warnings come from providers reporting them (see `AlertsProvider`), limited to those in
+/// effect on `date`. Failing to fetch them is logged and does not fail the request, since the
+/// weather itself was retrieved.
+async fn fetch_alerts(
+    weather_provider: &dyn WeatherProvider,
+    api_key: Option<&str>,
+    address: &str,
+    date: &str,
+) -> Vec<WeatherAlert> {
+    let Some(alerts_provider) = weather_provider.as_alerts() else {
+        return Vec::new();
+    };
+
+    match alerts_provider.get_alerts(api_key, address).await {
+        Ok(alerts) => alerts_on(alerts, date),
+        Err(e) => {
+            warn!("Failed to fetch the weather alerts: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Keeps the alerts in effect on `date` (`YYYY-MM-DD`), or all of them if it is not a date.
+fn alerts_on(alerts: Vec<WeatherAlert>, date: &str) -> Vec<WeatherAlert> {
+    let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+        return alerts;
+    };
+
+    alerts
+        .into_iter()
+        .filter(|alert| alert.start.date() <= date && date <= alert.end.date())
+        .collect()
+}
+
+/// Returns the address saved by `--save-as`.
+///
+/// Providers with a geocoder save the display name of the location it finds (e.g.,
+/// "Springfield, Missouri, US"). Station and ID providers have none, and report the name of
+/// the station, which they cannot look up again, so the typed address (e.g., a station code)
+/// is saved instead.
+///
+/// # Errors
+///
+/// Returns an error if the geocoder fails.
+async fn saved_address(
+    provider: &Provider,
+    options: &ProviderOptions,
+    api_key: Option<&str>,
+    typed: &str,
+) -> Result<String> {
+    match create_geocoder_with(provider.clone(), options) {
+        Ok(geocoder) => Ok(geocoder.geocode(api_key, typed).await?.display_name()),
+        Err(_) => Ok(typed.to_string()),
+    }
+}
+
+/// Adjusts the provider options to the needs of `window`.
+///
+/// A window around sunrise or sunset needs the OpenWeather One Call endpoint: unlike
+/// `day_summary`, it reports the sunrise and sunset of the day.
+fn window_options(mut options: ProviderOptions, window: Option<&TimeWindow>) -> ProviderOptions {
+    if window.is_some_and(TimeWindow::needs_sun) {
+        options.open_weather_endpoint = OpenWeatherEndpoint::OneCall;
+    }
+
+    options
+}
+
+/// Checks every hour of the window against the configured alert thresholds.
+///
+/// The window is resolved against the sunrise and sunset of `weather_info`, and the hours of
+/// its date are requested from the provider.
+///
+/// # Errors
+///
+/// Returns an error if the window needs a sunrise or sunset the provider did not report, or
+/// covers none of the hours, and `Error::Alert` listing every hour exceeding a threshold.
+async fn check_window_thresholds(
+    hourly: &dyn HourlyProvider,
+    api_key: Option<&str>,
+    address: &str,
+    window: &TimeWindow,
+    weather_info: &WeatherInfo,
+) -> Result<()> {
+    let resolved = window.resolve(weather_info)?;
+    let hours = hourly
+        .get_hourly(api_key, address, Some(&weather_info.date))
+        .await?
+        .into_iter()
+        .filter(|hour| resolved.contains(hour.time))
+        .collect::<Vec<_>>();
+
+    if hours.is_empty() {
+        Err(format!(
+            "No hourly weather of {} falls within the window '{window}'.",
+            weather_info.date
+        ))?;
+    }
+
+    let thresholds = APP_STATE.config.get()?.alert_thresholds.unwrap_or_default();
+    let display_options = resolve_display_options()?;
+    let mut message = String::new();
+    for hour in &hours {
+        for breach in hour.breaches(&thresholds) {
+            message.push_str(&format!(
+                "\n  - {}: {}",
+                hour.time.format("%H:%M"),
+                breach.describe(&display_options)
+            ));
+        }
+    }
+
+    if message.is_empty() {
+        return Ok(());
+    }
+
+    Err(Error::Alert(format!(
+        "Weather alert for '{address}' within {window}:{message}"
+    )))
+}
 
-    println!("Fetching weather from '{provider}' for '{address}'...");
+/// Checks the weather against the configured alert thresholds.
+///
+/// # Errors
+///
+/// Returns `Error::Alert` listing every exceeded threshold, so the process exits with code 2.
+fn check_alert_thresholds(address: &str, weather_info: &WeatherInfo) -> Result<()> {
+    let thresholds = APP_STATE.config.get()?.alert_thresholds.unwrap_or_default();
+    let breaches = weather_info.breaches(&thresholds);
+
+    if breaches.is_empty() {
+        return Ok(());
+    }
+
+    let display_options = resolve_display_options()?;
+    let mut message = format!("Weather alert for '{address}':");
+    for breach in breaches {
+        message.push_str("\n  - ");
+        message.push_str(&breach.describe(&display_options));
+    }
+
+    Err(Error::Alert(message))
+}
+
+/// The JSON representation of a `get` result.
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    #[serde(flatten)]
+    weather: &'a WeatherInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trend: Option<&'a TrendAnnotations>,
+}
+
+/// Records the observation in the history and computes its trend.
+///
+/// The trend is computed against the most recent earlier observation of the same location,
+/// if it was fetched today for the same date. The entry only keeps the location data allowed
+/// by the `privacy` settings. History failures never fail the request; they are only logged.
+pub(super) fn record_history(
+    address: &str,
+    provider: &Provider,
+    weather_info: &WeatherInfo,
+) -> Option<TrendAnnotations> {
+    record_history_in(&APP_STATE.history, address, provider, weather_info)
+}
+
+/// Records the observation in `history` and computes its trend, like `record_history`.
+pub(super) fn record_history_in(
+    history: &History,
+    address: &str,
+    provider: &Provider,
+    weather_info: &WeatherInfo,
+) -> Option<TrendAnnotations> {
+    let now = Utc::now();
+    let privacy = APP_STATE
+        .config
+        .get()
+        .ok()
+        .and_then(|config| config.privacy.clone())
+        .unwrap_or_default();
+    let key = persisted_key(address, &privacy);
+
+    let trend = history
+        .latest_before(&key, now)
+        .inspect_err(|e| debug!("Failed to read weather history: {e}"))
+        .ok()
+        .flatten()
+        .filter(|prev| is_comparable(prev, weather_info, now))
+        .map(|prev| trend(&prev, weather_info));
+
+    let entry = HistoryEntry::new(address, provider.id(), now, weather_info, &privacy);
+    if let Err(e) = history.append(&entry) {
+        debug!("Failed to record weather history: {e}");
+    }
 
-    let weather_provider = create_provider(provider);
-    let weather_info = weather_provider
-        .get_weather(api_key.as_deref(), &address, date.as_deref())
-        .await?;
+    trend
+}
+
+/// Prints the address and the provider that `get_weather` would use, and the routing rule
+/// that chose the provider, without retrieving the weather (`--dry-run`).
+///
+/// # Errors
+///
+/// Returns an error if the address or the provider cannot be resolved.
+pub fn plan_weather(address: Option<String>, provider: Option<String>) -> Result<()> {
+    let explicit = provider.is_some();
+    let address = resolve_address(address)?;
+    let (provider, _, rule) = resolve_routed_provider(provider, &address)?;
 
-    println!("{weather_info}");
+    let routing = match rule {
+        Some((i, rule)) => format!("rule {} ({})", i + 1, rule.matcher),
+        None if explicit => "not applied (--provider)".to_string(),
+        None => "no rule matched".to_string(),
+    };
+    println!("Address:  {address}");
+    println!("Provider: '{provider}' ({})", provider.id());
+    println!("Routing:  {routing}");
 
     Ok(())
 }
 
+/// A routing rule that chose the provider, with its position in `routing`.
+type FiredRule = (usize, RoutingRule);
+
+/// Resolves the provider of `address` like `resolve_provider`, except that without a
+/// `provider_input` the first routing rule matching the address chooses the provider.
+///
+/// # Returns
+///
+/// The provider with its API key, and the routing rule that chose it, if any.
+pub(super) fn resolve_routed_provider(
+    provider_input: Option<String>,
+    address: &str,
+) -> Result<(Provider, Option<String>, Option<FiredRule>)> {
+    let rule = match provider_input {
+        Some(_) => None,
+        None => {
+            let config = APP_STATE.config.get()?;
+            route(&config.routing, address).map(|(i, rule)| (i, rule.clone()))
+        }
+    };
+    let provider_input = provider_input.or_else(|| rule.as_ref().map(|(_, r)| r.provider.clone()));
+
+    let (provider, api_key) = resolve_provider(provider_input)?;
+
+    Ok((provider, api_key, rule))
+}
+
+/// Resolves the provider of each of `addresses` like `resolve_routed_provider`.
+///
+/// Addresses routed to the same provider share it, and so do the addresses no rule matches:
+/// the default provider is picked once for all of them.
+pub(super) fn resolve_routed_providers(
+    provider_input: Option<String>,
+    addresses: &[String],
+) -> Result<Routed<(Provider, Option<String>)>> {
+    let rules = match provider_input {
+        Some(_) => Vec::new(),
+        None => APP_STATE.config.get()?.routing.clone(),
+    };
+
+    Routed::resolve(
+        addresses,
+        |address| route(&rules, address).map(|(_, rule)| rule.provider.clone()),
+        |routed| resolve_provider(routed.clone().or_else(|| provider_input.clone())),
+    )
+}
+
+/// Describes the providers of a set of addresses for a message, e.g. "'WeatherApi'" or
+/// "'WeatherApi', 'OpenWeather'".
+pub(super) fn describe_providers<'a>(providers: impl Iterator<Item = &'a Provider>) -> String {
+    providers
+        .map(|provider| format!("'{provider}'"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Determines the weather provider to use and retrieves its configuration.
 ///
 /// # Logic
 ///
-/// 1. If a `provider_input` is given, it tries to parse it.
-/// 2. If not, it looks for a default provider in the configuration.
-/// 3. If neither is present, it falls back to the `Mock` provider.
+/// 1. If a `provider_input` is given, it tries to parse it. An unknown provider fails with
+///    the closest match and the providers ready to use (see `unknown_provider`).
+/// 2. If not, it picks one of the weighted default providers of the configuration.
+/// 3. If there are none, it looks for a default provider in the configuration.
+/// 4. If neither is present, it falls back to the `Mock` provider.
 ///
-/// It also retrieves the API key for the selected provider from the config.
+/// It also retrieves the API key for the selected provider from the config,
+/// or from the OS keychain if the key is stored there.
 ///
 /// # Errors
 ///
-/// Returns an error if the selected provider is NOT the Mock provider and no API key
-/// is found in the configuration.
-fn resolve_provider(provider_input: Option<String>) -> Result<(Provider, Option<String>)> {
+/// Returns an error if the selected provider requires an API key and no API key
+/// is found in the configuration, or if the keychain entry cannot be read.
+pub(super) fn resolve_provider(
+    provider_input: Option<String>,
+) -> Result<(Provider, Option<String>)> {
     let config = APP_STATE.config.get()?;
 
     let provider = match provider_input {
-        Some(p) => Provider::try_from(p.as_str())?,
+        Some(p) => Provider::try_from(p.as_str()).map_err(|_| unknown_provider(&p, &config))?,
+        None if !config.default_providers.is_empty() => Provider::try_from(pick_weighted(
+            &config.default_providers,
+            &mut rand::thread_rng(),
+        )?)?,
         None => config
             .default_provider
             .as_deref()
@@ -84,12 +554,12 @@ fn resolve_provider(provider_input: Option<String>) -> Result<(Provider, Option<
             .unwrap_or(Provider::Mock),
     };
 
-    let api_key = config
-        .providers
-        .get(provider.id())
-        .and_then(|p| p.key.clone());
+    let api_key = match config.providers.get(provider.id()) {
+        Some(provider_config) => keychain::resolve_key(provider.id(), provider_config)?,
+        None => None,
+    };
 
-    if !provider.is_mock() && api_key.is_none() {
+    if provider.metadata().requires_key && api_key.is_none() {
         Err(format!(
             "API key not found for provider '{provider}'. Please configure it first."
         ))?;
@@ -98,6 +568,197 @@ fn resolve_provider(provider_input: Option<String>) -> Result<(Provider, Option<
     Ok((provider, api_key))
 }
 
+/// Builds the error of an unknown `--provider`.
+///
+/// The closest match comes first, then every provider of this build with whether it is
+/// ready to use: its key is configured, or it needs none.
+fn unknown_provider(input: &str, config: &Settings) -> Error {
+    let lowercase = input.to_lowercase();
+    let similarity = |id: &str, name: &str| {
+        strsim::jaro(&lowercase, id).max(strsim::jaro(&lowercase, &name.to_lowercase()))
+    };
+
+    let mut message = format!("Unknown provider: '{input}'.");
+    if let Some((_, closest)) = available_providers()
+        .iter()
+        .filter(|d| d.compiled_in)
+        .map(|d| (similarity(d.id, d.name), d))
+        .filter(|(similarity, _)| *similarity > 0.7)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+    {
+        message += &format!(" Did you mean '{}' ({})?", closest.id, closest.name);
+    }
+
+    message += "\nAvailable providers:";
+    for descriptor in available_providers() {
+        let status = if !descriptor.compiled_in {
+            "✗ not compiled in"
+        } else if !descriptor.requires_key {
+            "✓ no key needed"
+        } else if config
+            .providers
+            .get(descriptor.id)
+            .is_some_and(|c| c.has_key())
+        {
+            "✓ key configured"
+        } else {
+            "✗ no key"
+        };
+        message += &format!(
+            "\n  {:<5} {:<22} {status}",
+            descriptor.id,
+            format!("({})", descriptor.name)
+        );
+    }
+
+    message.into()
+}
+
+/// Picks the ID of one of `providers` at random, in proportion to their weights.
+///
+/// # Errors
+///
+/// Returns an error if there are no providers or none has a weight above 0.
+fn pick_weighted<'a, R: Rng + ?Sized>(
+    providers: &'a [WeightedProvider],
+    rng: &mut R,
+) -> Result<&'a str> {
+    // Summed as u64, so the total of any number of u32 weights cannot overflow
+    let index = WeightedIndex::new(providers.iter().map(|p| u64::from(p.weight)))
+        .map_err(|e| format!("Invalid default_providers weights: {e}"))?;
+
+    Ok(&providers[index.sample(rng)].id)
+}
+
+/// Builds the provider-specific options stored in the configuration of `provider`.
+///
+/// Prints a warning to the standard error if TLS certificate verification is disabled for the
+/// provider (`danger` feature only).
+///
+/// # Errors
+///
+/// Returns an error if the configuration cannot be read, or the request signing settings are
+/// invalid or their secret cannot be loaded.
+pub(super) fn resolve_provider_options(provider: &Provider) -> Result<ProviderOptions> {
+    let config = APP_STATE.config.get()?;
+    let provider_config = config.providers.get(provider.id());
+
+    #[cfg(feature = "danger")]
+    let danger_accept_invalid_certs = provider_config.is_some_and(|c| !c.tls_verify);
+    #[cfg(feature = "danger")]
+    if danger_accept_invalid_certs {
+        eprintln!(
+            "WARNING: TLS certificate verification is DISABLED for '{provider}'. \
+             API keys and responses can be intercepted; use this only with a local \
+             development proxy."
+        );
+    }
+
+    let signer = match provider_config.and_then(|c| c.signing.as_ref()) {
+        Some(signing) => {
+            let secret = keychain::resolve_signing_secret(provider.id(), signing)?;
+            let signer = HmacSha256Signer::new(&signing.header, secret)?
+                .with_clock_skew(signing.clock_skew_secs);
+            Some(Arc::new(signer) as Arc<dyn RequestSigner>)
+        }
+        None => None,
+    };
+
+    Ok(ProviderOptions {
+        open_weather_endpoint: provider_config
+            .and_then(|c| c.ow_endpoint)
+            .unwrap_or_default(),
+        #[cfg(feature = "danger")]
+        danger_accept_invalid_certs,
+        signer,
+        allow_cross_host_redirects: provider_config.is_some_and(|c| c.allow_cross_host_redirects),
+        // Read from the environment by the mock provider
+        mock_faults: None,
+        // Every provider answers in JSON
+        accepted_formats: Vec::new(),
+    })
+}
+
+/// Determines the text display options before CLI flag overrides are applied.
+///
+/// Condition emoji are shown when the standard output is a terminal that supports them, in
+/// addition to the configured options.
+pub(super) fn resolve_display_options() -> Result<DisplayOptions> {
+    let mut options = configured_display_options()?;
+    options.show_emoji |= supports_emoji(std::io::stdout().is_terminal(), |var| {
+        std::env::var(var).ok()
+    });
+
+    Ok(options)
+}
+
+/// Returns `true` if the standard output is a terminal likely to display emoji: not a "dumb"
+/// terminal, and on Windows only in Windows Terminal, as the legacy console cannot.
+fn supports_emoji(is_terminal: bool, env: impl Fn(&str) -> Option<String>) -> bool {
+    is_terminal
+        && env("TERM").is_none_or(|term| term != "dumb")
+        && (!cfg!(windows) || env("WT_SESSION").is_some())
+}
+
+/// Determines the display options from the configuration.
+///
+/// # Logic
+///
+/// 1. If `display_options` are configured, they are used as is, except that a wind speed unit
+///    left at mph follows the configured `units`.
+/// 2. Otherwise the options are derived from the configured `units` and `lang`.
+/// 3. Units and language that are not configured are detected from the system locale.
+///    The detection is never saved to the configuration; when it selects metric units, a note
+///    on stderr explains how to pin the preference with `weather config set units`.
+fn configured_display_options() -> Result<DisplayOptions> {
+    let (configured, units, lang) = {
+        let config = APP_STATE.config.get()?;
+        (
+            config.display_options.clone(),
+            config.units,
+            config.lang.clone(),
+        )
+    };
+
+    if let Some(mut options) = configured {
+        // The wind speed unit follows the units unless another one is configured
+        if let Some(units) = units
+            && options.wind_unit == WindUnit::default()
+        {
+            options.wind_unit = units.wind_unit();
+        }
+        return Ok(options);
+    }
+
+    let (units, lang) = match units {
+        Some(units) => (units, lang),
+        None => {
+            let (detected_units, detected_lang) = detect_locale_defaults();
+            let lang = lang.or(detected_lang);
+
+            if detected_units == Units::Metric {
+                eprintln!(
+                    "Note: using metric units based on your system locale ({}). \
+                     Run 'weather config set units metric' (or 'imperial') to pin your \
+                     preference.",
+                    lang.as_deref().unwrap_or("unknown")
+                );
+            }
+
+            (detected_units, lang)
+        }
+    };
+
+    let defaults = DisplayOptions::default();
+
+    Ok(DisplayOptions {
+        temperature_unit: units.temperature_unit(),
+        wind_unit: units.wind_unit(),
+        locale: lang.unwrap_or(defaults.locale.clone()),
+        ..defaults
+    })
+}
+
 /// Resolves the target location string from the input.
 ///
 /// # Logic
@@ -113,7 +774,7 @@ fn resolve_provider(provider_input: Option<String>) -> Result<(Provider, Option<
 ///
 /// Returns an error if no address is provided and no default alias is configured.
 /// Logs a warning if a default alias is set but points to a non-existent entry.
-fn resolve_address(address_input: Option<String>) -> Result<String> {
+pub(super) fn resolve_address(address_input: Option<String>) -> Result<String> {
     let config = APP_STATE.config.get()?;
     let addresses = &config.addresses;
 
@@ -134,3 +795,167 @@ fn resolve_address(address_input: Option<String>) -> Result<String> {
     Err("No address specified and no default address alias found. \
          Use --address <LOCATION> or set a default alias.")?
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports_emoji() {
+        let env = |term: Option<&'static str>| {
+            move |var: &str| match var {
+                "TERM" => term.map(str::to_string),
+                "WT_SESSION" => Some("1".to_string()),
+                _ => None,
+            }
+        };
+
+        assert!(supports_emoji(true, env(Some("xterm-256color"))));
+        assert!(supports_emoji(true, env(None)));
+        assert!(!supports_emoji(true, env(Some("dumb"))));
+        assert!(!supports_emoji(false, env(Some("xterm-256color"))));
+    }
+
+    #[test]
+    fn test_alerts_on() {
+        let alert = |start: &str, end: &str| WeatherAlert {
+            event: "Wind warning".to_string(),
+            start: format!("{start}T12:00:00").parse().unwrap(),
+            end: format!("{end}T06:00:00").parse().unwrap(),
+            description: String::new(),
+            sender: "Met Office".to_string(),
+        };
+        let alerts = vec![
+            alert("2024-01-14", "2024-01-15"),
+            alert("2024-01-16", "2024-01-17"),
+        ];
+
+        assert_eq!(alerts_on(alerts.clone(), "2024-01-15").len(), 1);
+        assert_eq!(alerts_on(alerts.clone(), "2024-01-17").len(), 1);
+        assert!(alerts_on(alerts.clone(), "2024-01-18").is_empty());
+        assert_eq!(alerts_on(alerts, "today").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_saved_address() {
+        let typed = "Springfield, Missouri, US";
+
+        // The mock geocoder finds "Mock City" for any address
+        assert_eq!(
+            saved_address(&Provider::Mock, &ProviderOptions::default(), None, typed)
+                .await
+                .unwrap(),
+            "Mock City, Mock Country"
+        );
+        // A station provider keeps the station code, not the station name
+        assert_eq!(
+            saved_address(
+                &Provider::Bom,
+                &ProviderOptions::default(),
+                None,
+                "IDN60901.94768"
+            )
+            .await
+            .unwrap(),
+            "IDN60901.94768"
+        );
+    }
+
+    #[test]
+    fn test_window_options() {
+        let endpoint = |window: Option<&str>| {
+            let window = window.map(|w| TimeWindow::parse(w).unwrap());
+            window_options(ProviderOptions::default(), window.as_ref()).open_weather_endpoint
+        };
+
+        // Only the sun times need the One Call endpoint
+        assert_eq!(endpoint(Some("sunrise±2h")), OpenWeatherEndpoint::OneCall);
+        assert_eq!(endpoint(Some("sunset-1h")), OpenWeatherEndpoint::OneCall);
+        assert_eq!(
+            endpoint(Some("06:00-09:00")),
+            OpenWeatherEndpoint::DaySummary
+        );
+        assert_eq!(endpoint(None), OpenWeatherEndpoint::DaySummary);
+    }
+
+    #[test]
+    fn test_unknown_provider() {
+        use crate::models::config::ProviderConfig;
+
+        let mut config = Settings::default();
+        config.providers.insert(
+            "ow".to_string(),
+            ProviderConfig {
+                key: Some("ow-key".to_string()),
+                ..Default::default()
+            },
+        );
+        let error = unknown_provider("OpenWether", &config).to_string();
+        let lines = error.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines[0],
+            "Unknown provider: 'OpenWether'. Did you mean 'ow' (OpenWeather)?"
+        );
+        assert_eq!(lines[1], "Available providers:");
+        assert_eq!(lines.len(), 2 + available_providers().len());
+        let line = |id: &str| {
+            *lines
+                .iter()
+                .find(|line| line.split_whitespace().next() == Some(id))
+                .unwrap()
+        };
+        assert!(line("ow").contains("(OpenWeather)"), "{error}");
+        assert!(line("ow").ends_with("✓ key configured"), "{error}");
+        assert!(line("wa").ends_with("✗ no key"), "{error}");
+        assert!(line("ss").ends_with("✓ no key needed"), "{error}");
+
+        // A name matches as well as an ID, and nothing is suggested for unrelated input
+        let error = unknown_provider("wetherapi", &config).to_string();
+        assert!(
+            error.starts_with("Unknown provider: 'wetherapi'. Did you mean 'wa' (WeatherApi)?"),
+            "{error}"
+        );
+        let error = unknown_provider("xyz", &config).to_string();
+        assert!(error.starts_with("Unknown provider: 'xyz'.\n"), "{error}");
+    }
+
+    #[test]
+    fn test_pick_weighted() {
+        use ::rand::{SeedableRng, rngs::StdRng};
+        use ::std::collections::BTreeMap;
+
+        let providers = [("ow", 2), ("wa", 1), ("mock", 0)].map(|(id, weight)| WeightedProvider {
+            id: id.to_string(),
+            weight,
+        });
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut picks = BTreeMap::new();
+        for _ in 0..1000 {
+            *picks
+                .entry(pick_weighted(&providers, &mut rng).unwrap())
+                .or_insert(0) += 1;
+        }
+
+        // About 2 of 3 picks go to "ow", and none to the provider without weight
+        let ow = picks["ow"];
+        let wa = picks["wa"];
+        assert!((600..=733).contains(&ow), "{picks:?}");
+        assert_eq!(ow + wa, 1000, "{picks:?}");
+        assert!(
+            (1.6..=2.5).contains(&(f64::from(ow) / f64::from(wa))),
+            "{picks:?}"
+        );
+
+        assert!(pick_weighted(&[], &mut rng).is_err());
+        assert!(pick_weighted(&providers[2..], &mut rng).is_err());
+
+        // Weights whose total exceeds u32::MAX
+        let heavy = ["ow", "wa"].map(|id| WeightedProvider {
+            id: id.to_string(),
+            weight: u32::MAX,
+        });
+        assert!(pick_weighted(&heavy, &mut rng).is_ok());
+    }
+}
@@ -9,48 +9,95 @@
 //! - `common`: Shared utilities, configuration, error types, and logging setup.
 //! - `handlers`: Implementation of business logic for each CLI command.
 //! - `models`: Data structures representing CLI arguments and application state.
+//! - `output`: Renderers for machine-readable output formats.
 //!
 //! ## Execution Flow
 //!
-//! 1.  **Parse Arguments**: Uses `clap` to parse arguments into the `Cli` struct.
-//! 2.  **Initialize Logging**: Sets up tracing/logging based on the debug flag.
-//! 3.  **Dispatch Command**: Matches the parsed subcommand (`get`, `provider`, `alias`) and calls the corresponding handler function.
-//! 4.  **Error Handling**: Catches any errors bubbled up from handlers, prints them to `stderr`, and exits with a non-zero status code.
+//! 1.  **Parse Arguments**: Expands command aliases and shorthands (`weather London` is `weather get London`), then uses
+//!     `clap` to parse arguments into the `Cli` struct.
+//! 2.  **Initialize Logging**: Sets up tracing/logging based on the configuration and the logging flags.
+//! 3.  **Dispatch Command**: Matches the parsed subcommand (`get`, `compare`, `watch`, `digest`, `prefetch`, `daemon`, `geocode`, `provider`, `alias`, `config`) and calls the corresponding handler function.
+//! 4.  **Error Handling**: Catches any errors bubbled up from handlers, prints them to `stderr`, and exits with a non-zero status code
+//!     (2 when `get --alert` finds the weather beyond the alert thresholds, 6 or 4 when some or all locations of a
+//!     multi-location command failed, 1 otherwise).
 
 mod common;
 mod handlers;
 mod models;
+mod output;
 
-use crate::{common::*, models::args::*};
-use ::clap::Parser;
+use crate::{
+    common::*,
+    models::{args::*, request::*, shorthand::CommandLine},
+};
+use ::clap::{CommandFactory, Parser};
+use ::clap_complete::CompleteEnv;
+use ::std::time::Duration;
 use ::tracing::debug;
 
 /// The main entry point of the application.
 ///
 /// It initializes the Tokio runtime and delegates the execution to `run()`.
-/// If `run()` returns an error, it prints the error message to stderr and terminates the process with exit code 1.
+/// If `run()` returns an error, it prints the error message to stderr and terminates the process with the
+/// error's exit code: 2 for a triggered `--alert`, 6 or 4 when some or all locations of a
+/// multi-location command failed, otherwise 1.
 #[tokio::main]
 async fn main() {
+    // Answers the completion requests of the scripts printed by `COMPLETE=<SHELL> weather`
+    CompleteEnv::with_factory(Cli::command).complete();
+
     if let Err(e) = run().await {
         eprintln!("{e}");
-        std::process::exit(1);
+        if let Some(hint) = e.hint() {
+            eprintln!("{hint}");
+        }
+        std::process::exit(e.exit_code());
     }
 }
 
 /// Orchestrates the application logic.
 ///
 /// This function:
-/// 1. Parses CLI arguments.
-/// 2. Initializes the logging system.
+/// 1. Parses CLI arguments, after expanding command aliases and shorthands (see `shorthand`).
+/// 2. Loads the configuration and initializes the logging system.
 /// 3. Matches the requested subcommand and invokes the relevant handler from the `handlers` module.
 ///
 /// # Returns
 ///
 /// Returns `Ok(())` if the command executes successfully, or an `Error` if any step fails.
 async fn run() -> Result<()> {
-    let cli = Cli::parse();
+    // The configuration is loaded on first use, and the aliases below need it
+    if std::env::args_os()
+        .take_while(|arg| arg != "--")
+        .any(|arg| arg == "--no-config")
+    {
+        disable_config();
+    }
+
+    let aliases = APP_STATE
+        .config
+        .get()
+        .map(|config| config.command_aliases.clone())
+        .unwrap_or_default();
+    let command_line = CommandLine::rewrite(std::env::args_os(), &aliases);
+    let cli = Cli::try_parse_from(&command_line.args).unwrap_or_else(|e| {
+        let _ = e.print();
+        if e.use_stderr()
+            && let Some(hint) = command_line.hint()
+        {
+            eprintln!("\n{hint}");
+        }
+        std::process::exit(e.exit_code());
+    });
+
+    // The configuration is loaded first, as it holds the logging settings. If it cannot be
+    // parsed, default settings are used, so logging falls back to its defaults as well.
+    let log_options = {
+        let config = APP_STATE.config.get()?;
+        logging::LogOptions::resolve(config.logging.as_ref(), cli.log_level, cli.no_log_file)
+    };
 
-    let _logger_guard = logging::init(cli.debug)?;
+    let _logger_guard = logging::init(cli.debug, &log_options)?;
 
     if cli.debug {
         debug!("Debug output enabled.");
@@ -61,25 +108,160 @@ async fn run() -> Result<()> {
     };
 
     match command {
-        AppCommands::Get {
-            address,
+        AppCommands::Get(args) => {
+            let ValidatedRequest {
+                address,
+                date,
+                provider,
+                mode,
+            } = validate_get_args(&args)?;
+
+            match mode {
+                GetMode::Single {
+                    output,
+                    export,
+                    display,
+                    follow_up,
+                } => {
+                    handlers::get_weather(
+                        address, date, provider, output, export, display, follow_up,
+                    )
+                    .await?
+                }
+                GetMode::Days {
+                    days,
+                    output,
+                    display,
+                } => {
+                    handlers::get_weather_days(address, date, days, provider, output, display)
+                        .await?
+                }
+                GetMode::DryRun => handlers::plan_weather(address, provider)?,
+                GetMode::Batch {
+                    location_file,
+                    concurrency,
+                    sort_by,
+                    output,
+                    export,
+                } => {
+                    handlers::get_weather_batch(
+                        location_file,
+                        date,
+                        provider,
+                        concurrency,
+                        sort_by,
+                        output,
+                        export,
+                    )
+                    .await?
+                }
+            }
+        }
+
+        AppCommands::Compare {
+            addresses,
             date,
             provider,
+            sort_by,
+            highlight_extremes,
+            display,
+        } => {
+            handlers::compare(
+                addresses,
+                validate_date(date.as_deref())?,
+                provider,
+                sort_by,
+                highlight_extremes,
+                display,
+            )
+            .await?;
+        }
+
+        AppCommands::Digest { provider, markdown } => {
+            handlers::digest(provider, markdown).await?;
+        }
+
+        AppCommands::Anomalies {
+            address,
+            days,
+            provider,
+        } => {
+            handlers::anomalies(address, days, provider).await?;
+        }
+
+        AppCommands::Watch {
+            address,
+            provider,
+            interval,
+            output,
+            heartbeat,
+        } => {
+            handlers::watch(
+                address,
+                provider,
+                Duration::from_secs(interval),
+                output,
+                heartbeat.map(Duration::from_secs),
+            )
+            .await?;
+        }
+
+        AppCommands::Prefetch {
+            locations,
+            provider,
+            deadline,
+            verbose,
+            background,
         } => {
-            handlers::get_weather(address, date, provider).await?;
+            let deadline = Duration::from_secs(deadline);
+            if background {
+                handlers::prefetch_in_background(&locations, provider.as_deref(), deadline)?;
+            } else {
+                handlers::prefetch(locations, provider, deadline, verbose).await?;
+            }
+        }
+
+        AppCommands::Daemon {
+            provider,
+            export_dir,
+            once,
+        } => {
+            handlers::daemon(provider, export_dir, once).await?;
+        }
+
+        AppCommands::Geocode { address, provider } => {
+            handlers::geocode(address, provider).await?;
         }
 
         AppCommands::Provider {
             provider,
             key,
             list,
+            ping,
+            keychain,
+            migrate_key_to_keychain,
+            info,
+            output,
+            #[cfg(feature = "danger")]
+            no_tls_verify,
         } => {
             if list {
-                return handlers::list_providers();
+                return handlers::list_providers(ping, output).await;
             }
 
             if let Some(provider_str) = provider {
-                handlers::set_provider(provider_str, key)?;
+                #[cfg(feature = "danger")]
+                if no_tls_verify {
+                    return handlers::disable_tls_verify(provider_str).await;
+                }
+
+                if info {
+                    handlers::provider_info(provider_str, output)?;
+                } else if migrate_key_to_keychain {
+                    handlers::migrate_key_to_keychain(provider_str).await?;
+                } else {
+                    handlers::set_provider(provider_str, key, keychain).await?;
+                }
             }
         }
 
@@ -95,12 +277,29 @@ async fn run() -> Result<()> {
 
             if let Some(alias_name) = name {
                 if remove {
-                    handlers::remove_alias(alias_name.as_str())?;
+                    handlers::remove_alias(alias_name.as_str()).await?;
                 } else {
-                    handlers::set_alias(alias_name.as_str(), address.as_deref())?;
+                    handlers::set_alias(alias_name.as_str(), address.as_deref()).await?;
                 }
             }
         }
+
+        AppCommands::Config { command, .. } => match command {
+            Some(ConfigCommands::Export { format }) => handlers::export_config(format)?,
+            Some(ConfigCommands::Import { path }) => handlers::import_config(&path).await?,
+            Some(ConfigCommands::Backup { path }) => handlers::backup_config(path)?,
+            Some(ConfigCommands::Path) => handlers::print_config_path(),
+            Some(ConfigCommands::Get { path, output }) => handlers::get_setting(&path, output)?,
+            Some(ConfigCommands::Set { path, value }) => {
+                handlers::set_setting(&path, &value).await?
+            }
+            // Without a subcommand, `--check` is required
+            None => handlers::check_config()?,
+        },
+
+        AppCommands::CompleteLocations { prefix } => {
+            handlers::complete_locations(prefix.as_deref())?;
+        }
     }
 
     Ok(())
@@ -3,7 +3,11 @@
 //! This module defines the command-line interface (CLI) structure using the `clap` crate.
 //! It specifies the available subcommands, arguments, and flags for the application.
 
-use ::clap::{Parser, Subcommand};
+use crate::{handlers::complete_location, models::config::LogLevel};
+use ::clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use ::clap_complete::engine::ArgValueCompleter;
+use ::std::path::PathBuf;
+use ::weather_providers::{DisplayOptions, Unit, WindUnit};
 
 /// The main CLI structure parsing command-line arguments.
 #[derive(Parser)]
@@ -17,6 +21,18 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub debug: bool,
 
+    /// The minimum level of messages written to the log files.
+    #[arg(long, global = true, value_name = "LEVEL")]
+    pub log_level: Option<LogLevel>,
+
+    /// Do not write log files.
+    #[arg(long, global = true)]
+    pub no_log_file: bool,
+
+    /// Use the default settings without reading or saving the configuration file.
+    #[arg(long, global = true)]
+    pub no_config: bool,
+
     /// The main subcommand to execute.
     #[command(subcommand)]
     pub command: Option<AppCommands>,
@@ -26,10 +42,18 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum AppCommands {
     /// Retrieve weather information.
-    Get {
-        /// The address or address alias to query.
-        #[arg(value_name = "LOCATION")]
-        address: Option<String>,
+    Get(GetArgs),
+
+    /// Retrieve weather information for several locations at once.
+    Compare {
+        /// The addresses or address aliases to query.
+        #[arg(
+            value_name = "LOCATION",
+            required = true,
+            num_args = 1..,
+            add = ArgValueCompleter::new(complete_location)
+        )]
+        addresses: Vec<String>,
 
         /// The date to retrieve weather information for.
         #[arg(short, long, value_name = "DATE")]
@@ -38,10 +62,160 @@ pub enum AppCommands {
         /// Explicitly select the weather provider to use for this request.
         #[arg(short, long, value_name = "PROVIDER")]
         provider: Option<String>,
+
+        /// Order the locations by a metric, highest first. Locations without it come last.
+        #[arg(long, value_name = "METRIC")]
+        sort_by: Option<SortMetric>,
+
+        /// Color the location with the highest value of the --sort-by metric red and the one
+        /// with the lowest blue, on terminals that support colors.
+        #[arg(long, requires = "sort_by")]
+        highlight_extremes: bool,
+
+        #[command(flatten)]
+        display: DisplayArgs,
+    },
+
+    /// Poll the weather of a location and print it whenever it changes.
+    Watch {
+        /// The address or address alias to watch.
+        #[arg(value_name = "LOCATION", add = ArgValueCompleter::new(complete_location))]
+        address: Option<String>,
+
+        /// Explicitly select the weather provider to use.
+        #[arg(short, long, value_name = "PROVIDER")]
+        provider: Option<String>,
+
+        /// The number of seconds between polls.
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = 300,
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        interval: u64,
+
+        /// The output format; json writes one record per line (NDJSON).
+        #[arg(
+            short,
+            long,
+            alias = "format",
+            value_name = "FORMAT",
+            default_value = "text"
+        )]
+        output: ReportFormat,
+
+        /// Also write an empty record ({}) every N seconds, even when the weather has not
+        /// changed (--output json only).
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        heartbeat: Option<u64>,
+    },
+
+    /// Print a morning briefing for the locations configured under "digest".
+    Digest {
+        /// Explicitly select the weather provider to use.
+        #[arg(short, long, value_name = "PROVIDER")]
+        provider: Option<String>,
+
+        /// Render the digest as Markdown, e.g. for pasting into chat.
+        #[arg(long)]
+        markdown: bool,
+    },
+
+    /// Flag the days whose weather was unusual for a location: values more than 2 standard
+    /// deviations from the mean of its history, including a fresh observation.
+    Anomalies {
+        /// The address or address alias to check.
+        #[arg(value_name = "LOCATION", add = ArgValueCompleter::new(complete_location))]
+        address: Option<String>,
+
+        /// The number of past days of history to compare with, up to 100 years.
+        #[arg(
+            long,
+            value_name = "DAYS",
+            default_value_t = 30,
+            value_parser = clap::value_parser!(u32).range(1..=36500)
+        )]
+        days: u32,
+
+        /// Explicitly select the weather provider of the fresh observation.
+        #[arg(short, long, value_name = "PROVIDER")]
+        provider: Option<String>,
+    },
+
+    /// Fetch the weather of the usual locations ahead of time, e.g. from a shell profile.
+    /// Prints nothing and always succeeds; errors are only logged.
+    Prefetch {
+        /// The addresses or aliases to fetch [default: the default alias and the digest
+        /// locations].
+        #[arg(
+            long,
+            value_name = "LOCATION",
+            num_args = 1..,
+            add = ArgValueCompleter::new(complete_location)
+        )]
+        locations: Vec<String>,
+
+        /// Explicitly select the weather provider to use.
+        #[arg(short, long, value_name = "PROVIDER")]
+        provider: Option<String>,
+
+        /// The maximum number of seconds the prefetch may take; unfinished requests are
+        /// abandoned.
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = 3,
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        deadline: u64,
+
+        /// Print the outcome of every location.
+        #[arg(short, long, conflicts_with = "background")]
+        verbose: bool,
+
+        /// Prefetch in a detached background process, so the shell returns immediately.
+        #[arg(long)]
+        background: bool,
+    },
+
+    /// Refresh the weather of the locations configured under "daemon" periodically, in the
+    /// foreground until stopped, e.g. as a systemd or launchd service.
+    Daemon {
+        /// Explicitly select the weather provider to use.
+        #[arg(short, long, value_name = "PROVIDER")]
+        provider: Option<String>,
+
+        /// Also write the weather of each location to DIR/<location>.json, e.g. for a status
+        /// bar to read.
+        #[arg(long, value_name = "DIR")]
+        export_dir: Option<PathBuf>,
+
+        /// Run a single refresh cycle and exit, e.g. from cron.
+        #[arg(long)]
+        once: bool,
+    },
+
+    /// Resolve an address into coordinates without fetching weather.
+    Geocode {
+        /// The address or address alias to geocode.
+        #[arg(value_name = "LOCATION", add = ArgValueCompleter::new(complete_location))]
+        address: String,
+
+        /// Explicitly select the provider whose geocoding API is used.
+        #[arg(short, long, value_name = "PROVIDER")]
+        provider: Option<String>,
     },
 
     /// Manage weather service providers.
-    #[command(arg_required_else_help = true)]
+    #[command(
+        arg_required_else_help = true,
+        group = ArgGroup::new("report").args(["list", "info"])
+    )]
     Provider {
         /// Set the specified provider as the default.
         #[arg(value_name = "PROVIDER")]
@@ -54,6 +228,39 @@ pub enum AppCommands {
         /// List all supported providers and their configuration status.
         #[arg(short, long, conflicts_with_all = ["provider", "key"])]
         list: bool,
+
+        /// Measure the latency of each configured provider (requires --list).
+        #[arg(long, requires = "list")]
+        ping: bool,
+
+        /// Store the API key in the OS keychain instead of the configuration file.
+        #[arg(long, requires = "key")]
+        keychain: bool,
+
+        /// Move the existing plain-text API key of the provider into the OS keychain.
+        #[arg(long, requires = "provider", conflicts_with_all = ["key", "list"])]
+        migrate_key_to_keychain: bool,
+
+        /// Show how to get started with the provider: signup URL, key format, free-tier limits.
+        #[arg(long, requires = "provider", conflicts_with_all = ["key", "list", "migrate_key_to_keychain"])]
+        info: bool,
+
+        /// The output format of --list and --info.
+        #[arg(
+            short,
+            long,
+            alias = "format",
+            value_name = "FORMAT",
+            default_value = "text",
+            requires = "report"
+        )]
+        output: ReportFormat,
+
+        /// Disable TLS certificate verification for the provider, e.g. behind a local HTTPS
+        /// proxy with a self-signed certificate. For development only.
+        #[cfg(feature = "danger")]
+        #[arg(long, requires = "provider", conflicts_with_all = ["key", "list", "migrate_key_to_keychain", "info"])]
+        no_tls_verify: bool,
     },
 
     /// Manage location aliases, e.g., "home" -> "London, UK"
@@ -75,6 +282,324 @@ pub enum AppCommands {
         #[arg(short, long, conflicts_with_all = ["name", "address", "remove"])]
         list: bool,
     },
+
+    /// Export, import, back up, check or edit the configuration.
+    #[command(arg_required_else_help = true, args_conflicts_with_subcommands = true)]
+    Config {
+        /// Report keys of the configuration file that no setting reads, e.g. misspelled ones.
+        #[arg(long)]
+        check: bool,
+
+        #[command(subcommand)]
+        command: Option<ConfigCommands>,
+    },
+
+    /// Print the locations starting with a prefix, one per line, for shell completion scripts.
+    #[command(name = "_complete-locations", hide = true)]
+    CompleteLocations {
+        /// The text typed so far.
+        #[arg(value_name = "PREFIX", allow_hyphen_values = true)]
+        prefix: Option<String>,
+    },
+}
+
+/// Subcommands of the `config` command.
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Print the configuration to the standard output.
+    Export {
+        /// The format of the exported configuration.
+        #[arg(short, long, value_name = "FORMAT", default_value = "toml")]
+        format: ConfigFormat,
+    },
+
+    /// Replace the configuration with the settings of a TOML file.
+    Import {
+        /// The TOML file to import, e.g. one written by 'weather config export'.
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+
+    /// Copy the configuration file, e.g. before major changes.
+    Backup {
+        /// The backup file [default: a timestamped file next to the configuration file].
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+
+    /// Print the path of the configuration file, e.g. to edit it by hand.
+    Path,
+
+    /// Print the value of a setting, e.g. 'providers.ow.key'.
+    Get {
+        /// The dotted path of the setting.
+        #[arg(value_name = "PATH")]
+        path: String,
+
+        /// The output format; json prints the raw JSON value.
+        #[arg(
+            short,
+            long,
+            alias = "format",
+            value_name = "FORMAT",
+            default_value = "text"
+        )]
+        output: ReportFormat,
+    },
+
+    /// Change a setting, e.g. 'weather config set addresses.home "London, UK"'.
+    Set {
+        /// The dotted path of the setting.
+        #[arg(value_name = "PATH")]
+        path: String,
+
+        /// The new value: true or false, a number or a string, depending on the setting.
+        #[arg(value_name = "VALUE", allow_hyphen_values = true)]
+        value: String,
+    },
+}
+
+/// The format of an exported configuration.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ConfigFormat {
+    /// TOML, easier to read and edit by hand.
+    Toml,
+    /// JSON, the format of the configuration file.
+    Json,
+}
+
+/// Arguments of the `get` command.
+///
+/// Conflicts that depend only on which flags are present are declared here; combinations
+/// that depend on the values are checked by `validate_get_args`.
+#[derive(Args, Debug)]
+pub struct GetArgs {
+    /// The address or address alias to query.
+    #[arg(value_name = "LOCATION", add = ArgValueCompleter::new(complete_location))]
+    pub address: Option<String>,
+
+    /// The date to retrieve weather information for.
+    #[arg(short, long, value_name = "DATE")]
+    pub date: Option<String>,
+
+    /// A date relative to today: "today", "yesterday", "N days ago", "-Nd" or "+Nd".
+    #[arg(
+        long,
+        value_name = "EXPR",
+        conflicts_with = "date",
+        allow_hyphen_values = true
+    )]
+    pub date_relative: Option<String>,
+
+    /// Retrieve N consecutive days starting at the date (or today), with terminal charts.
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u8).range(1..=16),
+        conflicts_with = "export"
+    )]
+    pub days: Option<u8>,
+
+    /// Explicitly select the weather provider to use for this request.
+    #[arg(short, long, value_name = "PROVIDER")]
+    pub provider: Option<String>,
+
+    /// The output format.
+    #[arg(
+        short,
+        long,
+        alias = "format",
+        value_name = "FORMAT",
+        default_value = "text"
+    )]
+    pub output: OutputFormat,
+
+    /// Write the output to a file (atomically) instead of the standard output.
+    #[arg(long, value_name = "PATH")]
+    pub export: Option<PathBuf>,
+
+    /// Read the addresses from a file, one per line ('#' starts a comment), and output a
+    /// CSV table with one row per address, or NDJSON with --output json.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["address", "days"]
+    )]
+    pub location_file: Option<PathBuf>,
+
+    /// The maximum number of requests in flight with --location-file [default: 5].
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..),
+        requires = "location_file",
+        conflicts_with = "address"
+    )]
+    pub concurrency: Option<u16>,
+
+    /// Order the rows of --location-file by a metric, highest first. Addresses without it
+    /// come last.
+    #[arg(
+        long,
+        value_name = "METRIC",
+        requires = "location_file",
+        conflicts_with = "address"
+    )]
+    pub sort_by: Option<SortMetric>,
+
+    /// Exit with code 2 and a warning if the weather exceeds the configured alert thresholds
+    /// (by default, above 95°F or 90% humidity).
+    #[arg(long, conflicts_with_all = ["days", "location_file"])]
+    pub alert: bool,
+
+    /// With --alert, check the thresholds in every hour of a part of the day instead of in the
+    /// current weather: a time of day range like 06:00-09:00, or a time around sunrise or
+    /// sunset like sunrise±2h (+ only after, - only before).
+    #[arg(long, value_name = "WINDOW", requires = "alert")]
+    pub window: Option<String>,
+
+    /// After a successful request, save the location as an alias, with the name found by the
+    /// geocoder of the provider (e.g., "Springfield, Missouri, US"), or the address as typed
+    /// for station providers.
+    #[arg(long, value_name = "ALIAS", conflicts_with_all = ["days", "location_file"])]
+    pub save_as: Option<String>,
+
+    /// Overwrite an existing alias with --save-as.
+    #[arg(long, requires = "save_as")]
+    pub force: bool,
+
+    /// Print the address and the provider that would be used, and the routing rule that
+    /// chose it, without retrieving the weather.
+    #[arg(
+        long,
+        conflicts_with_all = ["days", "location_file", "export", "alert", "save_as"]
+    )]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    pub display: DisplayArgs,
+}
+
+/// Text display overrides for the `get` command.
+///
+/// Flags that are not set keep the value from the configured display options.
+#[derive(Args, Debug, Clone, Default, PartialEq)]
+pub struct DisplayArgs {
+    /// The temperature unit to display.
+    #[arg(short, long, value_name = "UNIT")]
+    pub unit: Option<Unit>,
+
+    /// Show the wind speed in the given unit, overriding the unit of the configured units.
+    #[arg(long, value_name = "UNIT")]
+    pub wind_unit: Option<WindUnit>,
+
+    /// The locale used for number formatting (e.g., "de-DE").
+    #[arg(long, value_name = "LOCALE")]
+    pub locale: Option<String>,
+
+    /// Show the date of the observation.
+    #[arg(long)]
+    pub show_date: bool,
+
+    /// Hide the relative humidity.
+    #[arg(long)]
+    pub hide_humidity: bool,
+
+    /// Hide the weather description.
+    #[arg(long)]
+    pub hide_description: bool,
+
+    /// Show the temperatures of the morning, afternoon, evening and night on a second line.
+    #[arg(long)]
+    pub detailed: bool,
+
+    /// Show where the data came from: the provider, endpoint, kind of data, fetch time, cache
+    /// status and the fields computed locally.
+    #[arg(long)]
+    pub verbose: bool,
+}
+
+impl DisplayArgs {
+    /// Returns the name of the first flag that was set, e.g. "--unit".
+    pub fn first_flag(&self) -> Option<&'static str> {
+        [
+            (self.unit.is_some(), "--unit"),
+            (self.wind_unit.is_some(), "--wind-unit"),
+            (self.locale.is_some(), "--locale"),
+            (self.show_date, "--show-date"),
+            (self.hide_humidity, "--hide-humidity"),
+            (self.hide_description, "--hide-description"),
+            (self.detailed, "--detailed"),
+            (self.verbose, "--verbose"),
+        ]
+        .into_iter()
+        .find_map(|(set, flag)| set.then_some(flag))
+    }
+
+    /// Applies the flags that were set on top of the given display options.
+    pub fn apply(&self, opts: &mut DisplayOptions) {
+        if let Some(unit) = self.unit {
+            opts.temperature_unit = unit;
+        }
+        if let Some(wind_unit) = self.wind_unit {
+            opts.show_wind = true;
+            opts.wind_unit = wind_unit;
+        }
+        if let Some(locale) = &self.locale {
+            opts.locale = locale.clone();
+        }
+        if self.show_date {
+            opts.show_date = true;
+        }
+        if self.hide_humidity {
+            opts.show_humidity = false;
+        }
+        if self.hide_description {
+            opts.show_description = false;
+        }
+        if self.detailed {
+            opts.show_periods = true;
+        }
+        if self.verbose {
+            opts.show_meta = true;
+        }
+    }
+}
+
+/// Output formats supported by the `get` command.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    Text,
+    /// JSON object with the weather data and, if available, its trend.
+    Json,
+    /// Prometheus text exposition format (gauges).
+    Prometheus,
+    /// OpenMetrics text exposition format.
+    #[value(name = "openmetrics")]
+    OpenMetrics,
+}
+
+/// Metrics by which the weather of several locations can be ordered.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SortMetric {
+    /// The temperature.
+    Temp,
+    /// The relative humidity.
+    Humidity,
+    /// The wind speed.
+    Wind,
+    /// The amount of precipitation.
+    Precip,
+}
+
+/// Output formats of informational commands.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ReportFormat {
+    /// Human-readable text.
+    Text,
+    /// JSON.
+    Json,
 }
 
 #[cfg(test)]
@@ -91,11 +616,12 @@ mod tests {
     fn test_parse_get_basic() {
         let args = Cli::try_parse_from(["weather", "get", "London"]).unwrap();
         match args.command {
-            Some(AppCommands::Get {
+            Some(AppCommands::Get(GetArgs {
                 address,
                 date,
                 provider,
-            }) => {
+                ..
+            })) => {
                 assert_eq!(address, Some("London".to_string()));
                 assert_eq!(date, None);
                 assert_eq!(provider, None);
@@ -118,11 +644,12 @@ mod tests {
         .unwrap();
 
         match args.command {
-            Some(AppCommands::Get {
+            Some(AppCommands::Get(GetArgs {
                 address,
                 date,
                 provider,
-            }) => {
+                ..
+            })) => {
                 assert_eq!(address, Some("Paris".to_string()));
                 assert_eq!(date, Some("2023-01-01".to_string()));
                 assert_eq!(provider, Some("ow".to_string()));
@@ -131,6 +658,190 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_get_location_file() {
+        let args = Cli::try_parse_from([
+            "weather",
+            "get",
+            "--location-file",
+            "cities.txt",
+            "--concurrency",
+            "8",
+        ])
+        .unwrap();
+        match args.command {
+            Some(AppCommands::Get(GetArgs {
+                address,
+                location_file,
+                concurrency,
+                ..
+            })) => {
+                assert_eq!(address, None);
+                assert_eq!(location_file, Some(PathBuf::from("cities.txt")));
+                assert_eq!(concurrency, Some(8));
+            }
+            _ => panic!("Expected Get command"),
+        }
+
+        // The file replaces the address, and the concurrency applies only to the file
+        assert!(
+            Cli::try_parse_from(["weather", "get", "London", "--location-file", "cities.txt"])
+                .is_err()
+        );
+        assert!(Cli::try_parse_from(["weather", "get", "London", "--concurrency", "8"]).is_err());
+        assert!(Cli::try_parse_from(["weather", "get", "--concurrency", "8"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "weather",
+                "get",
+                "--location-file",
+                "c.txt",
+                "--concurrency",
+                "0"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_get_date_relative() {
+        let args =
+            Cli::try_parse_from(["weather", "get", "Paris", "--date-relative", "-7d"]).unwrap();
+        match args.command {
+            Some(AppCommands::Get(GetArgs {
+                date,
+                date_relative,
+                ..
+            })) => {
+                assert_eq!(date, None);
+                assert_eq!(date_relative, Some("-7d".to_string()));
+            }
+            _ => panic!("Expected Get command"),
+        }
+
+        // --date and --date-relative are mutually exclusive
+        let result = Cli::try_parse_from([
+            "weather",
+            "get",
+            "--date",
+            "2023-01-01",
+            "--date-relative",
+            "yesterday",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_get_days() {
+        let args = Cli::try_parse_from(["weather", "get", "Paris", "--days", "5"]).unwrap();
+        match args.command {
+            Some(AppCommands::Get(GetArgs { days, .. })) => assert_eq!(days, Some(5)),
+            _ => panic!("Expected Get command"),
+        }
+
+        assert!(Cli::try_parse_from(["weather", "get", "--days", "0"]).is_err());
+        assert!(Cli::try_parse_from(["weather", "get", "--days", "17"]).is_err());
+        assert!(
+            Cli::try_parse_from(["weather", "get", "--days", "3", "--export", "out.txt"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_digest() {
+        let args = Cli::try_parse_from(["weather", "digest", "--markdown"]).unwrap();
+        match args.command {
+            Some(AppCommands::Digest { provider, markdown }) => {
+                assert_eq!(provider, None);
+                assert!(markdown);
+            }
+            _ => panic!("Expected Digest command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        let args =
+            Cli::try_parse_from(["weather", "compare", "London", "Paris", "-d", "2024-01-01"])
+                .unwrap();
+        match args.command {
+            Some(AppCommands::Compare {
+                addresses, date, ..
+            }) => {
+                assert_eq!(addresses, vec!["London", "Paris"]);
+                assert_eq!(date, Some("2024-01-01".to_string()));
+            }
+            _ => panic!("Expected Compare command"),
+        }
+
+        // At least one location is mandatory
+        assert!(Cli::try_parse_from(["weather", "compare"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_sort_by() {
+        let args = Cli::try_parse_from([
+            "weather",
+            "compare",
+            "London",
+            "Paris",
+            "--sort-by",
+            "temp",
+            "--highlight-extremes",
+        ])
+        .unwrap();
+        match args.command {
+            Some(AppCommands::Compare {
+                sort_by,
+                highlight_extremes,
+                ..
+            }) => {
+                assert_eq!(sort_by, Some(SortMetric::Temp));
+                assert!(highlight_extremes);
+            }
+            _ => panic!("Expected Compare command"),
+        }
+
+        // Highlighting needs a metric
+        assert!(
+            Cli::try_parse_from(["weather", "compare", "London", "--highlight-extremes"]).is_err()
+        );
+
+        let args = Cli::try_parse_from([
+            "weather",
+            "get",
+            "--location-file",
+            "cities.txt",
+            "--sort-by",
+            "wind",
+        ])
+        .unwrap();
+        match args.command {
+            Some(AppCommands::Get(GetArgs { sort_by, .. })) => {
+                assert_eq!(sort_by, Some(SortMetric::Wind))
+            }
+            _ => panic!("Expected Get command"),
+        }
+        assert!(Cli::try_parse_from(["weather", "get", "London", "--sort-by", "wind"]).is_err());
+        assert!(
+            Cli::try_parse_from(["weather", "compare", "London", "--sort-by", "pressure"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_geocode() {
+        let args = Cli::try_parse_from(["weather", "geocode", "London", "-p", "wa"]).unwrap();
+        match args.command {
+            Some(AppCommands::Geocode { address, provider }) => {
+                assert_eq!(address, "London");
+                assert_eq!(provider, Some("wa".to_string()));
+            }
+            _ => panic!("Expected Geocode command"),
+        }
+
+        // The address is mandatory
+        assert!(Cli::try_parse_from(["weather", "geocode"]).is_err());
+    }
+
     #[test]
     fn test_provider_conflicts() {
         let result = Cli::try_parse_from(["weather", "provider", "ow", "--list"]);
@@ -144,6 +855,92 @@ mod tests {
             Some(AppCommands::Provider { list, .. }) => assert!(list),
             _ => panic!("Expected Provider command"),
         }
+
+        // --keychain requires a key, migration requires a provider and conflicts with --key
+        assert!(Cli::try_parse_from(["weather", "provider", "ow", "--keychain"]).is_err());
+        assert!(Cli::try_parse_from(["weather", "provider", "--migrate-key-to-keychain"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "weather",
+                "provider",
+                "ow",
+                "--key",
+                "123",
+                "--migrate-key-to-keychain"
+            ])
+            .is_err()
+        );
+        assert!(
+            Cli::try_parse_from(["weather", "provider", "ow", "-k", "123", "--keychain"]).is_ok()
+        );
+
+        // --ping is only meaningful together with --list
+        let result = Cli::try_parse_from(["weather", "provider", "--ping"]);
+        assert!(result.is_err());
+
+        let args = Cli::try_parse_from(["weather", "provider", "--list", "--ping"]).unwrap();
+        match args.command {
+            Some(AppCommands::Provider { list, ping, .. }) => assert!(list && ping),
+            _ => panic!("Expected Provider command"),
+        }
+    }
+
+    #[test]
+    fn test_provider_info() {
+        let args = Cli::try_parse_from(["weather", "provider", "ow", "--info", "--output", "json"])
+            .unwrap();
+        match args.command {
+            Some(AppCommands::Provider {
+                provider,
+                info,
+                output,
+                ..
+            }) => {
+                assert_eq!(provider, Some("ow".to_string()));
+                assert!(info);
+                assert_eq!(output, ReportFormat::Json);
+            }
+            _ => panic!("Expected Provider command"),
+        }
+
+        // --info needs a provider and cannot be combined with setting a key
+        assert!(Cli::try_parse_from(["weather", "provider", "--info"]).is_err());
+        assert!(Cli::try_parse_from(["weather", "provider", "ow", "--info", "-k", "123"]).is_err());
+    }
+
+    #[test]
+    fn test_provider_list_format() {
+        let args =
+            Cli::try_parse_from(["weather", "provider", "--list", "--format", "json"]).unwrap();
+        match args.command {
+            Some(AppCommands::Provider { list, output, .. }) => {
+                assert!(list);
+                assert_eq!(output, ReportFormat::Json);
+            }
+            _ => panic!("Expected Provider command"),
+        }
+
+        // The format only applies to --list and --info
+        assert!(Cli::try_parse_from(["weather", "provider", "ow", "--format", "json"]).is_err());
+    }
+
+    #[cfg(feature = "danger")]
+    #[test]
+    fn test_provider_no_tls_verify() {
+        let args = Cli::try_parse_from(["weather", "provider", "ow", "--no-tls-verify"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(AppCommands::Provider {
+                no_tls_verify: true,
+                ..
+            })
+        ));
+
+        assert!(Cli::try_parse_from(["weather", "provider", "--no-tls-verify"]).is_err());
+        assert!(
+            Cli::try_parse_from(["weather", "provider", "ow", "--no-tls-verify", "-k", "1"])
+                .is_err()
+        );
     }
 
     #[test]
@@ -180,9 +977,127 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_config_check() {
+        let args = Cli::try_parse_from(["weather", "config", "--check"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(AppCommands::Config {
+                check: true,
+                command: None
+            })
+        ));
+
+        let args = Cli::try_parse_from(["weather", "config", "backup"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(AppCommands::Config {
+                check: false,
+                command: Some(ConfigCommands::Backup { .. })
+            })
+        ));
+
+        // Either a subcommand or --check is required, but not both
+        assert!(Cli::try_parse_from(["weather", "config"]).is_err());
+        assert!(Cli::try_parse_from(["weather", "config", "--check", "export"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_get_output() {
+        let args = Cli::try_parse_from(["weather", "get", "London"]).unwrap();
+        match args.command {
+            Some(AppCommands::Get(GetArgs { output, export, .. })) => {
+                assert_eq!(output, OutputFormat::Text);
+                assert_eq!(export, None);
+            }
+            _ => panic!("Expected Get command"),
+        }
+
+        let args = Cli::try_parse_from([
+            "weather",
+            "get",
+            "London",
+            "--output",
+            "prometheus",
+            "--export",
+            "weather.prom",
+        ])
+        .unwrap();
+        match args.command {
+            Some(AppCommands::Get(GetArgs { output, export, .. })) => {
+                assert_eq!(output, OutputFormat::Prometheus);
+                assert_eq!(export, Some(PathBuf::from("weather.prom")));
+            }
+            _ => panic!("Expected Get command"),
+        }
+
+        let args =
+            Cli::try_parse_from(["weather", "get", "London", "--format", "openmetrics"]).unwrap();
+        match args.command {
+            Some(AppCommands::Get(GetArgs { output, .. })) => {
+                assert_eq!(output, OutputFormat::OpenMetrics)
+            }
+            _ => panic!("Expected Get command"),
+        }
+
+        assert!(Cli::try_parse_from(["weather", "get", "--output", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_display_args_override() {
+        let args = Cli::try_parse_from([
+            "weather",
+            "get",
+            "London",
+            "--unit",
+            "celsius",
+            "--wind-unit",
+            "knots",
+            "--hide-humidity",
+            "--detailed",
+            "--verbose",
+        ])
+        .unwrap();
+
+        let Some(AppCommands::Get(GetArgs { display, .. })) = args.command else {
+            panic!("Expected Get command");
+        };
+
+        let mut opts = DisplayOptions {
+            show_date: true,
+            ..DisplayOptions::default()
+        };
+        display.apply(&mut opts);
+
+        assert_eq!(opts.temperature_unit, Unit::Celsius);
+        assert!(opts.show_wind);
+        assert_eq!(opts.wind_unit, WindUnit::Knots);
+        assert!(!opts.show_humidity);
+        assert!(opts.show_periods);
+        assert!(opts.show_meta);
+        // Options without a flag keep their configured values
+        assert!(opts.show_date);
+        assert!(opts.show_description);
+        assert_eq!(opts.locale, "en-US");
+    }
+
     #[test]
     fn test_global_debug_flag() {
         let args = Cli::try_parse_from(["weather", "--debug", "get"]).unwrap();
         assert!(args.debug);
     }
+
+    #[test]
+    fn test_global_logging_flags() {
+        let args = Cli::try_parse_from(["weather", "get", "--log-level", "warn", "--no-log-file"])
+            .unwrap();
+        assert_eq!(args.log_level, Some(LogLevel::Warn));
+        assert!(args.no_log_file);
+
+        let error = Cli::try_parse_from(["weather", "--log-level", "loud", "get"])
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("possible values: error, warn, info, debug, trace"));
+    }
 }
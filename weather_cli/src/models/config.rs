@@ -1,5 +1,18 @@
-use ::serde::{Deserialize, Serialize};
-use ::std::collections::BTreeMap;
+use crate::{
+    common::{Result, logging::parse_level},
+    models::{routing::RoutingRule, shorthand::is_subcommand},
+};
+use ::chrono_tz::Tz;
+use ::clap::ValueEnum;
+use ::serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, DeserializeOwned, Visitor},
+};
+use ::serde_ignored::Path as IgnoredPath;
+use ::std::{collections::BTreeMap, fmt::Display, path::PathBuf, time::Duration};
+use ::weather_providers::{
+    DisplayOptions, OpenWeatherEndpoint, Provider, SafetyThresholds, Unit, WindUnit,
+};
 
 /// Represents the persistent configuration of the application.
 ///
@@ -16,7 +29,11 @@ pub struct Settings {
     pub addresses: BTreeMap<String, String>,
 
     /// The alias to use when no specific location is provided in the arguments.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        alias = "defaultAlias",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub default_alias: Option<String>,
 
     /// Configuration for specific weather providers.
@@ -26,8 +43,81 @@ pub struct Settings {
     pub providers: BTreeMap<String, ProviderConfig>,
 
     /// The ID of the provider to use by default if none is specified.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        alias = "defaultProvider",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub default_provider: Option<String>,
+
+    /// Providers picked at random, in proportion to their weight, when none is specified.
+    ///
+    /// Spreads the requests, and so the quota usage, across several providers. Takes
+    /// precedence over `default_provider`.
+    #[serde(
+        default,
+        alias = "defaultProviders",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub default_providers: Vec<WeightedProvider>,
+
+    /// Rules choosing the provider by address when none is specified, evaluated in order;
+    /// the first matching rule applies. Takes precedence over the default providers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routing: Vec<RoutingRule>,
+
+    /// Default options for rendering weather information as text.
+    ///
+    /// When set, these take precedence over `units` and `lang`.
+    /// Individual options can be overridden per request with CLI flags.
+    #[serde(
+        default,
+        alias = "displayOptions",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub display_options: Option<DisplayOptions>,
+
+    /// The preferred unit system. Detected from the system locale when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub units: Option<Units>,
+
+    /// The preferred language tag (e.g., "de-DE"). Detected from the system locale when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+
+    /// Log file settings. Defaults are used when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logging: Option<LoggingSettings>,
+
+    /// The locations of the morning briefing printed by `weather digest`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<DigestSettings>,
+
+    /// The locations refreshed periodically by `weather daemon`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daemon: Option<DaemonSettings>,
+
+    /// The limits checked by `weather get --alert`. The defaults of `SafetyThresholds` are
+    /// used when not set.
+    #[serde(
+        default,
+        alias = "alertThresholds",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub alert_thresholds: Option<SafetyThresholds>,
+
+    /// How much location data is written to disk. Everything is stored in full when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<PrivacySettings>,
+
+    /// Command aliases expanded when they are the first word of the command line, e.g.
+    /// "w" -> "get --output json".
+    #[serde(
+        default,
+        alias = "commandAliases",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub command_aliases: BTreeMap<String, String>,
 }
 
 impl Default for Settings {
@@ -38,12 +128,14 @@ impl Default for Settings {
             "mock".to_string(),
             ProviderConfig {
                 key: Some("mock-key".to_string()),
+                ..Default::default()
             },
         );
         providers.insert(
             "grpc".to_string(),
             ProviderConfig {
                 key: Some("grpc-mock-key".to_string()),
+                ..Default::default()
             },
         );
 
@@ -52,16 +144,625 @@ impl Default for Settings {
             default_alias: None,
             providers,
             default_provider: None,
+            default_providers: Vec::new(),
+            routing: Vec::new(),
+            display_options: None,
+            units: None,
+            lang: None,
+            logging: None,
+            digest: None,
+            daemon: None,
+            alert_thresholds: None,
+            privacy: None,
+            command_aliases: BTreeMap::new(),
         }
     }
 }
 
-/// Configuration options for a specific weather provider.
+impl Settings {
+    /// Checks that the settings refer only to known providers and existing aliases.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first invalid reference.
+    pub fn validate(&self) -> Result<()> {
+        for provider in self
+            .providers
+            .keys()
+            .chain(&self.default_provider)
+            .chain(self.default_providers.iter().map(|p| &p.id))
+            .chain(self.routing.iter().map(|r| &r.provider))
+        {
+            Provider::try_from(provider.as_str())?;
+        }
+
+        for rule in &self.routing {
+            rule.matcher.validate()?;
+        }
+
+        if !self.default_providers.is_empty()
+            && self.default_providers.iter().all(|p| p.weight == 0)
+        {
+            Err("At least one of the default_providers must have a weight above 0.")?;
+        }
+
+        if let Some(alias) = &self.default_alias
+            && !self.addresses.contains_key(alias)
+        {
+            Err(format!(
+                "The default alias '{alias}' is not defined in [addresses]."
+            ))?;
+        }
+
+        for location in self.digest.iter().flat_map(|d| &d.locations) {
+            if !self.addresses.contains_key(&location.alias) {
+                Err(format!(
+                    "The digest alias '{}' is not defined in [addresses].",
+                    location.alias
+                ))?;
+            }
+            location.tz()?;
+        }
+
+        if let Some(daemon) = &self.daemon {
+            daemon.interval()?;
+        }
+
+        // At startup, an invalid level only falls back to the default (see `LogOptions::resolve`)
+        if let Some(level) = self.logging.as_ref().and_then(|l| l.level.as_deref()) {
+            parse_level(level)?;
+        }
+
+        for (alias, expansion) in &self.command_aliases {
+            if is_subcommand(alias) {
+                Err(format!(
+                    "The command alias '{alias}' would hide the '{alias}' command. \
+                     Rename the alias."
+                ))?;
+            }
+            if alias.is_empty() || alias.starts_with('-') || alias.contains(char::is_whitespace) {
+                Err(format!(
+                    "Invalid command alias '{alias}': use a single word not starting with '-'."
+                ))?;
+            }
+            if expansion.trim().is_empty() {
+                Err(format!("The command alias '{alias}' expands to nothing."))?;
+            }
+        }
+
+        if let Some(SafetyThresholds {
+            min_temperature_f: Some(min),
+            max_temperature_f: Some(max),
+            ..
+        }) = self.alert_thresholds
+            && min >= max
+        {
+            Err(format!(
+                "The alert threshold min_temperature_f ({min}) must be below \
+                 max_temperature_f ({max})."
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Parses a JSON configuration like a normal load, also returning the keys it ignored.
+    ///
+    /// Unknown keys never fail a load; they are only reported, e.g. by `weather config --check`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is invalid or a known key has a value of the wrong type.
+    pub fn from_json_checked(json: &str) -> Result<(Self, Vec<UnknownKey>)> {
+        let mut unknown = Vec::new();
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let settings =
+            serde_ignored::deserialize(&mut deserializer, |path| unknown.push(unknown_key(&path)))?;
+        deserializer.end()?;
+
+        Ok((settings, unknown))
+    }
+}
+
+/// A key of the configuration file that no setting reads, e.g. a misspelled one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownKey {
+    /// The path of the key, e.g. "providers.ow.endpont".
+    pub path: String,
+    /// The known key it is most likely a misspelling of.
+    pub suggestion: Option<&'static str>,
+}
+
+impl Display for UnknownKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.suggestion {
+            Some(suggestion) => write!(f, "{} (did you mean '{suggestion}'?)", self.path),
+            None => write!(f, "{}", self.path),
+        }
+    }
+}
+
+fn unknown_key(path: &IgnoredPath) -> UnknownKey {
+    let mut segments = Vec::new();
+    collect_segments(path, &mut segments);
+
+    let parent = segments[..segments.len().saturating_sub(1)]
+        .iter()
+        .map(|segment| snake_case(segment))
+        .collect::<Vec<_>>();
+    let suggestion = segments
+        .last()
+        .zip(known_keys(&parent))
+        .and_then(|(key, known)| suggest(&snake_case(key), known));
+
+    UnknownKey {
+        path: segments.join("."),
+        suggestion,
+    }
+}
+
+/// Appends the map keys and sequence indexes leading to `path`.
+fn collect_segments(path: &IgnoredPath, segments: &mut Vec<String>) {
+    match path {
+        IgnoredPath::Root => {}
+        IgnoredPath::Seq { parent, index } => {
+            collect_segments(parent, segments);
+            segments.push(index.to_string());
+        }
+        IgnoredPath::Map { parent, key } => {
+            collect_segments(parent, segments);
+            segments.push(key.clone());
+        }
+        IgnoredPath::Some { parent }
+        | IgnoredPath::NewtypeStruct { parent }
+        | IgnoredPath::NewtypeVariant { parent } => collect_segments(parent, segments),
+    }
+}
+
+/// Returns the keys read at `parent` (in snake case), or `None` for free-form maps.
+fn known_keys(parent: &[String]) -> Option<&'static [&'static str]> {
+    let parent = parent.iter().map(String::as_str).collect::<Vec<_>>();
+
+    Some(match parent.as_slice() {
+        [] => field_names::<Settings>(),
+        ["providers", _] => field_names::<ProviderConfig>(),
+        ["providers", _, "signing"] => field_names::<SigningConfig>(),
+        ["default_providers", _] => field_names::<WeightedProvider>(),
+        ["routing", _] => field_names::<RoutingRule>(),
+        ["display_options"] => field_names::<DisplayOptions>(),
+        ["logging"] => field_names::<LoggingSettings>(),
+        ["digest"] => field_names::<DigestSettings>(),
+        ["digest", "locations", _] => field_names::<DigestLocation>(),
+        ["daemon"] => field_names::<DaemonSettings>(),
+        ["alert_thresholds"] => field_names::<SafetyThresholds>(),
+        ["privacy"] => field_names::<PrivacySettings>(),
+        _ => return None,
+    })
+}
+
+/// Returns the keys a struct reads, including their aliases, as declared by its
+/// `Deserialize` implementation.
+fn field_names<T: DeserializeOwned>() -> &'static [&'static str] {
+    struct Probe<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for Probe<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(
+            self,
+            _visitor: V,
+        ) -> std::result::Result<V::Value, Self::Error> {
+            Err(de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> std::result::Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("fields collected"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+            ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(Probe(&mut fields));
+    fields
+}
+
+/// Returns the known key most similar to `key`, if any is similar enough.
+fn suggest(key: &str, known: &[&'static str]) -> Option<&'static str> {
+    known
+        .iter()
+        .copied()
+        // The camelCase aliases lead to the same settings; suggest the snake case names only
+        .filter(|name| !name.contains(char::is_uppercase))
+        .map(|name| (strsim::jaro(key, name), name))
+        .filter(|(similarity, _)| *similarity > 0.7)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, name)| name)
+}
+
+/// Converts a camelCase key to snake case, e.g. "defaultProvider" to "default_provider".
+fn snake_case(key: &str) -> String {
+    let mut snake = String::with_capacity(key.len() + 4);
+    for (i, c) in key.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// The unit system used for displaying measurements.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// Fahrenheit, miles per hour.
+    Imperial,
+    /// Celsius, kilometers per hour.
+    Metric,
+}
+
+impl Units {
+    /// Returns the temperature unit of this unit system.
+    pub fn temperature_unit(&self) -> Unit {
+        match self {
+            Units::Imperial => Unit::Fahrenheit,
+            Units::Metric => Unit::Celsius,
+        }
+    }
+
+    /// Returns the wind speed unit of this unit system.
+    pub fn wind_unit(&self) -> WindUnit {
+        match self {
+            Units::Imperial => WindUnit::MilesPerHour,
+            Units::Metric => WindUnit::KilometersPerHour,
+        }
+    }
+}
+
+/// Log file settings.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LoggingSettings {
+    /// Whether logs are written to files at all.
+    pub enabled: bool,
+
+    /// The minimum level of messages written to the log files (e.g., "info").
+    ///
+    /// Kept as a string so that an invalid value is reported with the valid options
+    /// instead of invalidating the whole configuration file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+
+    /// The maximum number of daily log files to keep.
+    #[serde(alias = "maxFiles", skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<usize>,
+
+    /// The directory for the log files, instead of the platform default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub directory: Option<PathBuf>,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            level: None,
+            max_files: None,
+            directory: None,
+        }
+    }
+}
+
+/// Limits the location data persisted in the history.
+///
+/// Requests always use the full address; these settings only apply to what is written to disk.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PrivacySettings {
+    /// Round coordinate addresses (e.g., "51.50735,-0.12776") to this many decimal places.
+    #[serde(alias = "roundCoordinatesTo", skip_serializing_if = "Option::is_none")]
+    pub round_coordinates_to: Option<u8>,
+
+    /// Whether addresses and place names are stored. If `false`, only a hash of the location
+    /// key is stored, which still matches later requests for the same location.
+    #[serde(alias = "storeAddresses")]
+    pub store_addresses: bool,
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            round_coordinates_to: None,
+            store_addresses: true,
+        }
+    }
+}
+
+/// Settings of the `weather digest` morning briefing.
 #[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct DigestSettings {
+    /// The locations of the briefing, each referring to an alias of `addresses`.
+    #[serde(default)]
+    pub locations: Vec<DigestLocation>,
+
+    /// The order in which the locations are printed.
+    #[serde(default)]
+    pub order: DigestOrder,
+}
+
+/// A location of the digest.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DigestLocation {
+    /// The alias of the location (e.g., "home").
+    pub alias: String,
+
+    /// The IANA timezone of the location (e.g., "Asia/Tokyo"), which decides its "today" and
+    /// "tomorrow". The system local timezone is used when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+impl DigestLocation {
+    /// Parses the timezone of the location.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the timezone is not a known IANA timezone name.
+    pub fn tz(&self) -> Result<Option<Tz>> {
+        self.timezone
+            .as_deref()
+            .map(|name| {
+                name.parse::<Tz>().map_err(|_| {
+                    format!(
+                        "Unknown timezone '{name}' for the digest alias '{}'.",
+                        self.alias
+                    )
+                    .into()
+                })
+            })
+            .transpose()
+    }
+}
+
+/// The order of the locations in the digest.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestOrder {
+    /// As listed in `locations`.
+    #[default]
+    Listed,
+    /// Alphabetically by alias.
+    Alias,
+    /// The warmest location first.
+    Warmest,
+    /// The coldest location first.
+    Coldest,
+}
+
+/// The default number of seconds between two refresh cycles of `weather daemon`.
+pub const DEFAULT_DAEMON_INTERVAL_SECS: u64 = 300;
+
+/// Settings of the `weather daemon` background refresh.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DaemonSettings {
+    /// The addresses or aliases refreshed in every cycle.
+    pub locations: Vec<String>,
+
+    /// The number of seconds between two refresh cycles.
+    #[serde(alias = "intervalSecs")]
+    pub interval_secs: u64,
+}
+
+impl DaemonSettings {
+    /// Returns the time between two refresh cycles.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `interval_secs` is 0.
+    pub fn interval(&self) -> Result<Duration> {
+        if self.interval_secs == 0 {
+            Err("The daemon interval_secs must be at least 1.")?;
+        }
+
+        Ok(Duration::from_secs(self.interval_secs))
+    }
+}
+
+impl Default for DaemonSettings {
+    fn default() -> Self {
+        Self {
+            locations: Vec::new(),
+            interval_secs: DEFAULT_DAEMON_INTERVAL_SECS,
+        }
+    }
+}
+
+/// A provider of `default_providers` with its selection weight.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct WeightedProvider {
+    /// The ID of the provider (e.g., "ow").
+    pub id: String,
+
+    /// How likely the provider is to be picked: a provider with a weight of 2 is picked twice
+    /// as often as one with a weight of 1, and never with a weight of 0.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+/// The minimum level of log messages to record.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+/// The `key_ref` marker for API keys stored in the OS keychain.
+pub const KEYCHAIN_KEY_REF: &str = "keychain";
+
+/// Configuration options for a specific weather provider.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(not(feature = "danger"), derive(Default))]
 pub struct ProviderConfig {
     /// The API key required to authenticate with the provider.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub key: Option<String>,
+
+    /// A reference to an API key stored outside the configuration file.
+    ///
+    /// The only supported value is `"keychain"` (see `KEYCHAIN_KEY_REF`).
+    #[serde(default, alias = "keyRef", skip_serializing_if = "Option::is_none")]
+    pub key_ref: Option<String>,
+
+    /// The OAuth2 client ID; only used by providers authorized with client credentials (`fc`).
+    #[serde(default, alias = "clientId", skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+
+    /// The OAuth2 client secret belonging to `client_id`.
+    #[serde(
+        default,
+        alias = "clientSecret",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub client_secret: Option<String>,
+
+    /// The OpenWeather endpoint (`"day_summary"` or `"onecall"`); only used by the `ow` provider.
+    #[serde(default, alias = "owEndpoint", skip_serializing_if = "Option::is_none")]
+    pub ow_endpoint: Option<OpenWeatherEndpoint>,
+
+    /// Signs every request to the provider, e.g. for a self-hosted gateway.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing: Option<SigningConfig>,
+
+    /// Whether redirects to other hosts are followed, e.g. for a provider moved behind a CDN.
+    /// By default they fail with a hint about captive portals.
+    #[serde(
+        default,
+        alias = "allowCrossHostRedirects",
+        skip_serializing_if = "is_false"
+    )]
+    pub allow_cross_host_redirects: bool,
+
+    /// Whether TLS certificates of the provider are verified. Only available with the `danger`
+    /// feature, for local HTTPS proxies with self-signed certificates.
+    #[cfg(feature = "danger")]
+    #[serde(
+        default = "tls_verify_default",
+        alias = "tlsVerify",
+        skip_serializing_if = "is_tls_verified"
+    )]
+    pub tls_verify: bool,
+}
+
+#[cfg(feature = "danger")]
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        Self {
+            key: None,
+            key_ref: None,
+            client_id: None,
+            client_secret: None,
+            ow_endpoint: None,
+            signing: None,
+            allow_cross_host_redirects: false,
+            tls_verify: true,
+        }
+    }
+}
+
+/// HMAC-SHA256 request signing settings of a provider (see `HmacSha256Signer`).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SigningConfig {
+    /// The header carrying the signature, e.g. "X-Signature".
+    pub header: String,
+
+    /// The shared secret.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+
+    /// A reference to a secret stored outside the configuration file.
+    ///
+    /// The only supported value is `"keychain"` (see `KEYCHAIN_KEY_REF`).
+    #[serde(default, alias = "secretRef", skip_serializing_if = "Option::is_none")]
+    pub secret_ref: Option<String>,
+
+    /// Seconds added to the signed timestamps, for a gateway whose clock differs from ours.
+    #[serde(default, alias = "clockSkewSecs", skip_serializing_if = "is_zero")]
+    pub clock_skew_secs: i64,
+}
+
+impl SigningConfig {
+    /// Returns `true` if the secret is stored in the OS keychain.
+    pub fn is_keychain(&self) -> bool {
+        self.secret_ref.as_deref() == Some(KEYCHAIN_KEY_REF)
+    }
+}
+
+fn is_zero(value: &i64) -> bool {
+    *value == 0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[cfg(feature = "danger")]
+fn tls_verify_default() -> bool {
+    true
+}
+
+#[cfg(feature = "danger")]
+fn is_tls_verified(tls_verify: &bool) -> bool {
+    *tls_verify
+}
+
+impl ProviderConfig {
+    /// Returns `true` if the API key is stored in the OS keychain.
+    pub fn is_keychain(&self) -> bool {
+        self.key_ref.as_deref() == Some(KEYCHAIN_KEY_REF)
+    }
+
+    /// Returns `true` if an API key is configured, either in plain text or in the keychain.
+    pub fn has_key(&self) -> bool {
+        self.is_keychain() || self.plain_key().is_some()
+    }
+
+    /// Returns the plain-text key passed to the provider.
+    ///
+    /// Client credentials are combined into a `<CLIENT_ID>:<CLIENT_SECRET>` key.
+    pub fn plain_key(&self) -> Option<String> {
+        let non_empty = |s: &Option<String>| s.clone().filter(|s| !s.is_empty());
+
+        non_empty(&self.key).or_else(|| {
+            Some(format!(
+                "{}:{}",
+                non_empty(&self.client_id)?,
+                non_empty(&self.client_secret)?
+            ))
+        })
+    }
 }
 
 #[cfg(test)]
@@ -89,6 +790,7 @@ mod tests {
             default_alias: None,
             providers: BTreeMap::new(),
             default_provider: None,
+            ..Settings::default()
         };
 
         let json_output = serde_json::to_string(&settings).unwrap();
@@ -97,6 +799,23 @@ mod tests {
         assert_eq!(json_output, r#"{"addresses":{},"providers":{}}"#);
     }
 
+    #[test]
+    fn test_signing_config() {
+        let config = serde_json::from_value::<ProviderConfig>(json!({
+            "signing": { "header": "X-Signature", "secret_ref": "keychain" }
+        }))
+        .unwrap();
+        let signing = config.signing.unwrap();
+
+        assert_eq!(signing.header, "X-Signature");
+        assert!(signing.is_keychain());
+        assert_eq!(signing.clock_skew_secs, 0);
+        assert_eq!(
+            serde_json::to_value(&signing).unwrap(),
+            json!({ "header": "X-Signature", "secret_ref": "keychain" })
+        );
+    }
+
     #[test]
     fn test_serialization_full() {
         let mut addresses = BTreeMap::new();
@@ -107,6 +826,7 @@ mod tests {
             "ow".to_string(),
             ProviderConfig {
                 key: Some("12345".to_string()),
+                ..Default::default()
             },
         );
 
@@ -115,6 +835,8 @@ mod tests {
             default_alias: Some("home".to_string()),
             providers,
             default_provider: Some("ow".to_string()),
+            units: Some(Units::Metric),
+            ..Settings::default()
         };
 
         let json_value: serde_json::Value = serde_json::to_value(&settings).unwrap();
@@ -123,6 +845,7 @@ mod tests {
         assert_eq!(json_value["default_provider"], "ow");
         assert_eq!(json_value["addresses"]["home"], "London");
         assert_eq!(json_value["providers"]["ow"]["key"], "12345");
+        assert_eq!(json_value["units"], "metric");
     }
 
     #[test]
@@ -143,6 +866,334 @@ mod tests {
         assert!(settings.providers.is_empty());
     }
 
+    #[test]
+    fn test_provider_config_key_source() {
+        let plain = ProviderConfig {
+            key: Some("12345".to_string()),
+            ..Default::default()
+        };
+        assert!(plain.has_key() && !plain.is_keychain());
+
+        let empty = ProviderConfig {
+            key: Some(String::new()),
+            ..Default::default()
+        };
+        assert!(!empty.has_key());
+
+        let keychain: ProviderConfig =
+            serde_json::from_value(json!({ "key_ref": "keychain" })).unwrap();
+        assert!(keychain.has_key() && keychain.is_keychain());
+        assert_eq!(keychain.key, None);
+    }
+
+    #[test]
+    fn test_provider_config_client_credentials() {
+        let config: ProviderConfig =
+            serde_json::from_value(json!({ "client_id": "id", "client_secret": "secret" }))
+                .unwrap();
+        assert!(config.has_key());
+        assert_eq!(config.plain_key().as_deref(), Some("id:secret"));
+
+        let incomplete = ProviderConfig {
+            client_id: Some("id".to_string()),
+            ..Default::default()
+        };
+        assert!(!incomplete.has_key());
+    }
+
+    #[test]
+    fn test_provider_config_ow_endpoint() {
+        let config: ProviderConfig =
+            serde_json::from_value(json!({ "key": "12345", "ow_endpoint": "onecall" })).unwrap();
+        assert_eq!(config.ow_endpoint, Some(OpenWeatherEndpoint::OneCall));
+
+        let json_value = serde_json::to_value(ProviderConfig::default()).unwrap();
+        assert!(json_value.get("ow_endpoint").is_none());
+    }
+
+    #[cfg(feature = "danger")]
+    #[test]
+    fn test_provider_config_tls_verify() {
+        let config: ProviderConfig = serde_json::from_value(json!({ "key": "abc" })).unwrap();
+        assert!(config.tls_verify);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            json!({ "key": "abc" })
+        );
+
+        let config: ProviderConfig =
+            serde_json::from_value(json!({ "tls_verify": false })).unwrap();
+        assert!(!config.tls_verify);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            json!({ "tls_verify": false })
+        );
+    }
+
+    #[test]
+    fn test_logging_settings_defaults() {
+        let settings: Settings =
+            serde_json::from_value(json!({ "logging": { "level": "warn" } })).unwrap();
+        let logging = settings.logging.unwrap();
+
+        assert!(logging.enabled);
+        assert_eq!(logging.level.as_deref(), Some("warn"));
+        assert_eq!(logging.max_files, None);
+        assert_eq!(logging.directory, None);
+    }
+
+    #[test]
+    fn test_digest_settings() {
+        let settings: Settings = serde_json::from_value(json!({
+            "addresses": { "home": "London", "tokyo": "Tokyo, JP" },
+            "digest": {
+                "locations": [{ "alias": "home" }, { "alias": "tokyo", "timezone": "Asia/Tokyo" }],
+                "order": "warmest"
+            }
+        }))
+        .unwrap();
+        let digest = settings.digest.as_ref().unwrap();
+
+        assert_eq!(digest.order, DigestOrder::Warmest);
+        assert_eq!(digest.locations[0].tz().unwrap(), None);
+        assert_eq!(digest.locations[1].tz().unwrap(), Some(Tz::Asia__Tokyo));
+        assert!(settings.validate().is_ok());
+
+        let mut unknown_alias = settings.clone();
+        unknown_alias.addresses.remove("tokyo");
+        assert!(unknown_alias.validate().is_err());
+
+        let mut unknown_timezone = settings;
+        unknown_timezone.digest.as_mut().unwrap().locations[1].timezone =
+            Some("Mars/Olympus".to_string());
+        let error = unknown_timezone.validate().unwrap_err();
+        assert!(error.to_string().contains("Mars/Olympus"), "{error}");
+    }
+
+    #[test]
+    fn test_daemon_settings() {
+        let settings: Settings = serde_json::from_value(json!({
+            "daemon": { "locations": ["home", "Paris, FR"], "intervalSecs": 60 }
+        }))
+        .unwrap();
+        let daemon = settings.daemon.as_ref().unwrap();
+
+        assert_eq!(daemon.locations, ["home", "Paris, FR"]);
+        assert_eq!(daemon.interval_secs, 60);
+        assert_eq!(daemon.interval().unwrap(), Duration::from_secs(60));
+        assert!(settings.validate().is_ok());
+
+        let defaults: Settings = serde_json::from_value(json!({ "daemon": {} })).unwrap();
+        assert_eq!(defaults.daemon, Some(DaemonSettings::default()));
+        assert_eq!(
+            defaults.daemon.unwrap().interval_secs,
+            DEFAULT_DAEMON_INTERVAL_SECS
+        );
+
+        let mut stopped = settings;
+        stopped.daemon.as_mut().unwrap().interval_secs = 0;
+        assert!(stopped.daemon.as_ref().unwrap().interval().is_err());
+        let error = stopped.validate().unwrap_err();
+        assert!(error.to_string().contains("interval_secs"), "{error}");
+    }
+
+    #[test]
+    fn test_default_providers() {
+        let settings: Settings = serde_json::from_value(json!({
+            "defaultProviders": [{ "id": "ow", "weight": 2 }, { "id": "wa" }]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            settings.default_providers,
+            [
+                WeightedProvider {
+                    id: "ow".to_string(),
+                    weight: 2
+                },
+                WeightedProvider {
+                    id: "wa".to_string(),
+                    weight: 1
+                }
+            ]
+        );
+        assert!(settings.validate().is_ok());
+
+        let mut unknown = settings.clone();
+        unknown.default_providers[1].id = "nope".to_string();
+        assert!(unknown.validate().is_err());
+
+        let mut unweighted = settings;
+        for provider in &mut unweighted.default_providers {
+            provider.weight = 0;
+        }
+        let error = unweighted.validate().unwrap_err();
+        assert!(error.to_string().contains("weight"), "{error}");
+    }
+
+    #[test]
+    fn test_routing() {
+        let settings: Settings = serde_json::from_value(json!({
+            "routing": [
+                { "match": { "country": "US" }, "provider": "wa" },
+                { "match": { "regex": "^iata:" }, "provider": "ow" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(settings.routing.len(), 2);
+        assert!(settings.validate().is_ok());
+
+        let mut unknown = settings.clone();
+        unknown.routing[0].provider = "noaa".to_string();
+        assert!(unknown.validate().is_err());
+
+        let invalid: Settings = serde_json::from_value(json!({
+            "routing": [{ "match": { "regex": "(" }, "provider": "wa" }]
+        }))
+        .unwrap();
+        let error = invalid.validate().unwrap_err();
+        assert!(
+            error.to_string().contains("Invalid routing regex"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_alert_thresholds() {
+        let settings: Settings = serde_json::from_value(json!({
+            "alert_thresholds": { "max_humidity": 80, "min_temperature_f": 14.0 }
+        }))
+        .unwrap();
+        let thresholds = settings.alert_thresholds.unwrap();
+
+        assert_eq!(thresholds.max_humidity, Some(80));
+        assert_eq!(thresholds.min_temperature_f, Some(14.0));
+        // Unset limits keep their defaults
+        assert_eq!(thresholds.max_temperature_f, Some(95.0));
+        assert!(settings.validate().is_ok());
+
+        let mut inverted = settings;
+        inverted.alert_thresholds = Some(SafetyThresholds {
+            min_temperature_f: Some(100.0),
+            ..thresholds
+        });
+        let error = inverted.validate().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The alert threshold min_temperature_f (100) must be below max_temperature_f (95)."
+        );
+    }
+
+    #[test]
+    fn test_command_aliases() {
+        let settings: Settings = serde_json::from_value(json!({
+            "command_aliases": { "w": "get --output json" }
+        }))
+        .unwrap();
+        assert_eq!(settings.command_aliases["w"], "get --output json");
+        assert!(settings.validate().is_ok());
+
+        for (alias, expansion, error) in [
+            ("compare", "get", "would hide"),
+            ("-w", "get", "single word"),
+            ("w", " ", "expands to nothing"),
+        ] {
+            let mut invalid = settings.clone();
+            invalid
+                .command_aliases
+                .insert(alias.to_string(), expansion.to_string());
+            let e = invalid.validate().unwrap_err();
+            assert!(e.to_string().contains(error), "{alias}: {e}");
+        }
+    }
+
+    #[test]
+    fn test_camel_case_keys() {
+        let json = json!({
+            "addresses": { "home": "London" },
+            "defaultAlias": "home",
+            "defaultProvider": "ow",
+            "providers": {
+                "ow": { "key": "12345", "owEndpoint": "onecall", "allowCrossHostRedirects": true }
+            },
+            "privacy": { "storeAddresses": false },
+            "commandAliases": { "w": "get" },
+            "displayOptions": { "showDate": true, "temperatureUnit": "celsius", "windUnit": "kmh" },
+            "alertThresholds": { "maxHumidity": 80, "minTemperatureF": 14.0 }
+        })
+        .to_string();
+
+        let (settings, unknown) = Settings::from_json_checked(&json).unwrap();
+        assert!(unknown.is_empty());
+        assert_eq!(settings.default_alias.as_deref(), Some("home"));
+        assert_eq!(settings.default_provider.as_deref(), Some("ow"));
+        assert_eq!(
+            settings.providers["ow"].ow_endpoint,
+            Some(OpenWeatherEndpoint::OneCall)
+        );
+        assert!(settings.providers["ow"].allow_cross_host_redirects);
+        assert!(!settings.privacy.as_ref().unwrap().store_addresses);
+        let display = settings.display_options.as_ref().unwrap();
+        assert!(display.show_date);
+        assert_eq!(display.temperature_unit, Unit::Celsius);
+        assert_eq!(display.wind_unit, WindUnit::KilometersPerHour);
+        let thresholds = settings.alert_thresholds.unwrap();
+        assert_eq!(thresholds.max_humidity, Some(80));
+        assert_eq!(thresholds.min_temperature_f, Some(14.0));
+
+        // Normal loads accept the same keys, and saving writes snake case
+        let loaded = serde_json::from_str::<Settings>(&json).unwrap();
+        assert_eq!(loaded, settings);
+        let saved = serde_json::to_value(&settings).unwrap();
+        assert_eq!(saved["default_provider"], "ow");
+        assert_eq!(saved["providers"]["ow"]["ow_endpoint"], "onecall");
+        assert_eq!(saved["command_aliases"]["w"], "get");
+        assert_eq!(saved["display_options"]["show_date"], true);
+        assert_eq!(saved["alert_thresholds"]["max_humidity"], 80);
+        assert!(saved.get("defaultProvider").is_none());
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let json = json!({
+            "defalt_provider": "ow",
+            "default_alias": "home",
+            "addresses": { "home": "London" },
+            "providers": { "ow": { "key": "12345", "owEndpont": "onecall" } },
+            "logging": { "maxFile": 3 },
+            "digest": { "locations": [{ "alias": "home", "timezon": "Europe/London" }] },
+            "generated_by": "templates"
+        })
+        .to_string();
+
+        let (settings, unknown) = Settings::from_json_checked(&json).unwrap();
+        assert_eq!(
+            unknown.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "defalt_provider (did you mean 'default_provider'?)",
+                "digest.locations.0.timezon (did you mean 'timezone'?)",
+                "generated_by",
+                "logging.maxFile (did you mean 'max_files'?)",
+                "providers.ow.owEndpont (did you mean 'ow_endpoint'?)",
+            ]
+        );
+
+        // The unknown keys are ignored, not fatal
+        assert_eq!(settings.default_provider, None);
+        assert_eq!(settings.default_alias.as_deref(), Some("home"));
+        assert_eq!(settings.providers["ow"].key.as_deref(), Some("12345"));
+
+        // Keys of free-form maps are never unknown
+        let (_, unknown) =
+            Settings::from_json_checked(r#"{"addresses": {"anything": "Oslo"}}"#).unwrap();
+        assert!(unknown.is_empty());
+
+        // Invalid values still fail
+        assert!(Settings::from_json_checked(r#"{"units": "kelvin"}"#).is_err());
+        assert!(Settings::from_json_checked(r#"{"addresses": {}} trailing"#).is_err());
+    }
+
     #[test]
     fn test_btreemap_ordering() {
         let mut settings = Settings::default();
@@ -0,0 +1,426 @@
+//! # Configuration Paths
+//!
+//! Resolves dotted paths such as `providers.ow.key` to the fields of `Settings`, for
+//! `weather config get` and `weather config set`. Only the fields listed in `FIELDS` can be
+//! addressed; each has a value kind that the raw command-line value is checked against.
+//!
+//! Values are read from and written to the JSON form of the settings, which is then
+//! deserialized and validated again, so every value is checked exactly like one written to
+//! the configuration file by hand. Entries of maps (aliases, providers, command aliases) and
+//! optional sections (e.g., `logging`) are created as needed.
+
+use crate::{common::*, models::config::Settings};
+use ::serde_json::{Map, Number, Value};
+use ::std::fmt::Display;
+
+/// The kind of value a setting holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// `true` or `false`.
+    Boolean,
+    /// A whole number that is not negative.
+    Integer,
+    /// A decimal number.
+    Number,
+    /// Any text, including the names of enum values such as "metric".
+    String,
+}
+
+impl Display for ValueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ValueKind::Boolean => "true or false",
+            ValueKind::Integer => "a whole number",
+            ValueKind::Number => "a number",
+            ValueKind::String => "a string",
+        })
+    }
+}
+
+/// The settable fields. A segment in angle brackets stands for any map key; at the end of a
+/// path, the key may contain dots (e.g., `addresses.st.ives`).
+const FIELDS: &[(&str, ValueKind)] = &[
+    ("addresses.<alias>", ValueKind::String),
+    ("default_alias", ValueKind::String),
+    ("default_provider", ValueKind::String),
+    ("units", ValueKind::String),
+    ("lang", ValueKind::String),
+    ("providers.<id>.key", ValueKind::String),
+    ("providers.<id>.key_ref", ValueKind::String),
+    ("providers.<id>.client_id", ValueKind::String),
+    ("providers.<id>.client_secret", ValueKind::String),
+    ("providers.<id>.ow_endpoint", ValueKind::String),
+    (
+        "providers.<id>.allow_cross_host_redirects",
+        ValueKind::Boolean,
+    ),
+    #[cfg(feature = "danger")]
+    ("providers.<id>.tls_verify", ValueKind::Boolean),
+    ("display_options.show_date", ValueKind::Boolean),
+    ("display_options.show_humidity", ValueKind::Boolean),
+    ("display_options.show_description", ValueKind::Boolean),
+    ("display_options.temperature_unit", ValueKind::String),
+    ("display_options.locale", ValueKind::String),
+    ("display_options.show_periods", ValueKind::Boolean),
+    ("display_options.show_emoji", ValueKind::Boolean),
+    ("display_options.show_wind", ValueKind::Boolean),
+    ("display_options.wind_unit", ValueKind::String),
+    ("display_options.show_meta", ValueKind::Boolean),
+    ("logging.enabled", ValueKind::Boolean),
+    ("logging.level", ValueKind::String),
+    ("logging.max_files", ValueKind::Integer),
+    ("logging.directory", ValueKind::String),
+    ("daemon.interval_secs", ValueKind::Integer),
+    ("alert_thresholds.max_temperature_f", ValueKind::Number),
+    ("alert_thresholds.min_temperature_f", ValueKind::Number),
+    ("alert_thresholds.max_humidity", ValueKind::Integer),
+    ("alert_thresholds.max_wind_speed_mph", ValueKind::Number),
+    ("privacy.round_coordinates_to", ValueKind::Integer),
+    ("privacy.store_addresses", ValueKind::Boolean),
+    ("command_aliases.<name>", ValueKind::String),
+];
+
+/// A resolved path to a settable field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigPath {
+    path: String,
+    keys: Vec<String>,
+    kind: ValueKind,
+}
+
+impl ConfigPath {
+    /// Resolves a dotted path, e.g. "providers.ow.key".
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing the settable fields if `path` matches none of them.
+    pub fn parse(path: &str) -> Result<Self> {
+        FIELDS
+            .iter()
+            .find_map(|&(pattern, kind)| {
+                let keys = match_pattern(pattern, path)?;
+                Some(Self {
+                    path: path.to_string(),
+                    keys,
+                    kind,
+                })
+            })
+            .ok_or_else(|| {
+                let fields = FIELDS
+                    .iter()
+                    .map(|(pattern, _)| format!("  {pattern}"))
+                    .collect::<Vec<_>>();
+                format!(
+                    "Unknown setting '{path}'. Settable fields:\n{}",
+                    fields.join("\n")
+                )
+                .into()
+            })
+    }
+
+    /// Returns the value of the field, or `None` if it is not set.
+    ///
+    /// Fields left at their default are usually not written to the configuration file, so
+    /// they are not set either.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the settings cannot be serialized.
+    pub fn get(&self, settings: &Settings) -> Result<Option<Value>> {
+        let json = serde_json::to_value(settings)?;
+
+        Ok(self
+            .keys
+            .iter()
+            .try_fold(&json, |node, key| node.get(key))
+            .filter(|value| !value.is_null())
+            .cloned())
+    }
+
+    /// Returns a copy of `settings` with the field set to `raw`, creating missing map entries
+    /// and sections.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `raw` is not of the kind of the field, is not a valid value of the
+    /// field (e.g., an unknown unit), or the updated settings are invalid (e.g., an unknown
+    /// provider).
+    pub fn set(&self, settings: &Settings, raw: &str) -> Result<Settings> {
+        let value = self.parse_value(raw)?;
+        let mut json = serde_json::to_value(settings)?;
+
+        let (field, parents) = self
+            .keys
+            .split_last()
+            .ok_or("A setting path cannot be empty.")?;
+        let mut node = &mut json;
+        for key in parents {
+            node = as_object(node, &self.path)?
+                .entry(key.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if node.is_null() {
+                *node = Value::Object(Map::new());
+            }
+        }
+        as_object(node, &self.path)?.insert(field.clone(), value);
+
+        let updated = serde_json::from_value::<Settings>(json)
+            .map_err(|e| format!("Invalid value for '{}': {e}", self.path))?;
+        updated.validate()?;
+
+        Ok(updated)
+    }
+
+    /// Converts a command-line value to the JSON value of the field.
+    fn parse_value(&self, raw: &str) -> Result<Value> {
+        let invalid = || format!("'{}' expects {}, got '{raw}'.", self.path, self.kind);
+
+        Ok(match self.kind {
+            ValueKind::Boolean => match raw {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => Err(invalid())?,
+            },
+            ValueKind::Integer => Value::from(raw.parse::<u64>().map_err(|_| invalid())?),
+            ValueKind::Number => raw
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number)
+                .ok_or_else(invalid)?,
+            ValueKind::String => Value::String(raw.to_string()),
+        })
+    }
+}
+
+impl Display for ConfigPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+/// Returns the keys of `path` if it matches `pattern`.
+fn match_pattern(pattern: &str, path: &str) -> Option<Vec<String>> {
+    let segments = pattern.split('.').collect::<Vec<_>>();
+    let mut rest = path;
+    let mut keys = Vec::with_capacity(segments.len());
+
+    for (index, segment) in segments.iter().enumerate() {
+        let key = if index + 1 == segments.len() {
+            rest
+        } else {
+            let (key, tail) = rest.split_once('.')?;
+            rest = tail;
+            key
+        };
+
+        if key.is_empty() || !(segment.starts_with('<') || *segment == key) {
+            return None;
+        }
+        keys.push(key.to_string());
+    }
+
+    Some(keys)
+}
+
+fn as_object<'a>(node: &'a mut Value, path: &str) -> Result<&'a mut Map<String, Value>> {
+    node.as_object_mut()
+        .ok_or_else(|| format!("Cannot set '{path}': its parent is not a section.").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::serde_json::json;
+
+    fn set(settings: &Settings, path: &str, value: &str) -> Result<Settings> {
+        ConfigPath::parse(path)?.set(settings, value)
+    }
+
+    fn get(settings: &Settings, path: &str) -> Option<Value> {
+        ConfigPath::parse(path).unwrap().get(settings).unwrap()
+    }
+
+    #[test]
+    fn test_every_field_round_trips() {
+        let mut settings = Settings::default();
+        settings
+            .addresses
+            .insert("home".into(), "London, UK".into());
+
+        let cases = [
+            ("addresses.work", "Paris, FR", json!("Paris, FR")),
+            ("default_alias", "home", json!("home")),
+            ("default_provider", "ow", json!("ow")),
+            ("units", "metric", json!("metric")),
+            ("lang", "de-DE", json!("de-DE")),
+            ("providers.ow.key", "0123abcd", json!("0123abcd")),
+            ("providers.wa.key_ref", "keychain", json!("keychain")),
+            ("providers.fc.client_id", "client", json!("client")),
+            ("providers.fc.client_secret", "secret", json!("secret")),
+            ("providers.ow.ow_endpoint", "onecall", json!("onecall")),
+            (
+                "providers.ow.allow_cross_host_redirects",
+                "true",
+                json!(true),
+            ),
+            #[cfg(feature = "danger")]
+            ("providers.ow.tls_verify", "false", json!(false)),
+            ("display_options.show_date", "true", json!(true)),
+            ("display_options.show_humidity", "false", json!(false)),
+            ("display_options.show_description", "false", json!(false)),
+            (
+                "display_options.temperature_unit",
+                "celsius",
+                json!("celsius"),
+            ),
+            ("display_options.locale", "fr-FR", json!("fr-FR")),
+            ("display_options.show_periods", "true", json!(true)),
+            ("display_options.show_emoji", "true", json!(true)),
+            ("display_options.show_wind", "true", json!(true)),
+            ("display_options.wind_unit", "kmh", json!("kmh")),
+            ("display_options.show_meta", "true", json!(true)),
+            ("logging.enabled", "false", json!(false)),
+            ("logging.level", "debug", json!("debug")),
+            ("logging.max_files", "14", json!(14)),
+            (
+                "logging.directory",
+                "/var/log/weather",
+                json!("/var/log/weather"),
+            ),
+            ("daemon.interval_secs", "600", json!(600)),
+            ("alert_thresholds.max_temperature_f", "95.5", json!(95.5)),
+            ("alert_thresholds.min_temperature_f", "-4", json!(-4.0)),
+            ("alert_thresholds.max_humidity", "90", json!(90)),
+            ("alert_thresholds.max_wind_speed_mph", "40", json!(40.0)),
+            ("privacy.round_coordinates_to", "2", json!(2)),
+            ("privacy.store_addresses", "false", json!(false)),
+            (
+                "command_aliases.w",
+                "get --output json",
+                json!("get --output json"),
+            ),
+        ];
+        assert_eq!(cases.len(), FIELDS.len());
+
+        for (path, raw, expected) in cases {
+            settings = set(&settings, path, raw).unwrap_or_else(|e| panic!("{path}: {e}"));
+            assert_eq!(get(&settings, path), Some(expected), "{path}");
+        }
+
+        // The values reach the typed settings
+        assert_eq!(settings.addresses["work"], "Paris, FR");
+        assert_eq!(settings.providers["ow"].key.as_deref(), Some("0123abcd"));
+        assert_eq!(settings.logging.unwrap().max_files, Some(14));
+        assert_eq!(settings.daemon.unwrap().interval_secs, 600);
+        assert_eq!(
+            settings.alert_thresholds.unwrap().min_temperature_f,
+            Some(-4.0)
+        );
+    }
+
+    #[test]
+    fn test_missing_entries_are_created() {
+        let settings = set(&Settings::default(), "providers.wa.key", "abc").unwrap();
+        assert_eq!(settings.providers["wa"].key.as_deref(), Some("abc"));
+        // The other fields of a new provider keep their defaults
+        assert_eq!(settings.providers["wa"].client_id, None);
+        assert!(!settings.providers["wa"].allow_cross_host_redirects);
+
+        let settings = set(&settings, "logging.level", "warn").unwrap();
+        let logging = settings.logging.as_ref().unwrap();
+        assert_eq!(logging.level.as_deref(), Some("warn"));
+        assert!(logging.enabled);
+
+        // Existing entries are updated in place
+        let settings = set(&settings, "providers.wa.client_id", "id").unwrap();
+        assert_eq!(settings.providers["wa"].key.as_deref(), Some("abc"));
+        assert_eq!(settings.providers["wa"].client_id.as_deref(), Some("id"));
+    }
+
+    #[test]
+    fn test_map_keys_may_contain_dots() {
+        let settings = set(&Settings::default(), "addresses.st.ives", "St Ives, UK").unwrap();
+
+        assert_eq!(settings.addresses["st.ives"], "St Ives, UK");
+        assert_eq!(
+            get(&settings, "addresses.st.ives"),
+            Some(json!("St Ives, UK"))
+        );
+    }
+
+    #[test]
+    fn test_unset_fields() {
+        let settings = Settings::default();
+
+        assert_eq!(get(&settings, "default_provider"), None);
+        assert_eq!(get(&settings, "addresses.home"), None);
+        assert_eq!(get(&settings, "logging.level"), None);
+        assert_eq!(
+            get(&settings, "providers.mock.key"),
+            Some(json!("mock-key"))
+        );
+    }
+
+    #[test]
+    fn test_unknown_paths() {
+        for path in [
+            "default_providr",
+            "providers",
+            "providers.ow",
+            "providers.ow.secret",
+            "addresses",
+            "addresses.",
+            "logging.level.extra",
+            "",
+        ] {
+            let Err(e) = ConfigPath::parse(path) else {
+                panic!("'{path}' should be unknown");
+            };
+            let message = e.to_string();
+            assert!(message.starts_with(&format!("Unknown setting '{path}'")));
+            assert!(message.contains("  providers.<id>.key\n"), "{message}");
+        }
+    }
+
+    #[test]
+    fn test_type_validation() {
+        let settings = Settings::default();
+        let error = |path, value| set(&settings, path, value).unwrap_err().to_string();
+
+        assert_eq!(
+            error("privacy.store_addresses", "yes"),
+            "'privacy.store_addresses' expects true or false, got 'yes'."
+        );
+        assert_eq!(
+            error("daemon.interval_secs", "-1"),
+            "'daemon.interval_secs' expects a whole number, got '-1'."
+        );
+        assert_eq!(
+            error("alert_thresholds.max_temperature_f", "hot"),
+            "'alert_thresholds.max_temperature_f' expects a number, got 'hot'."
+        );
+        assert_eq!(
+            error("alert_thresholds.max_temperature_f", "NaN"),
+            "'alert_thresholds.max_temperature_f' expects a number, got 'NaN'."
+        );
+
+        // Values of the right kind are still checked by the settings themselves
+        assert!(error("units", "kelvin").starts_with("Invalid value for 'units': unknown variant"));
+        assert!(error("alert_thresholds.max_humidity", "300").contains("expected u8"));
+        assert!(error("default_provider", "nope").contains("nope"));
+        assert!(error("providers.nope.key", "abc").contains("nope"));
+        assert_eq!(
+            error("default_alias", "home"),
+            "The default alias 'home' is not defined in [addresses]."
+        );
+        assert_eq!(
+            error("daemon.interval_secs", "0"),
+            "The daemon interval_secs must be at least 1."
+        );
+        // A level that would fail every command at startup
+        let level = error("logging.level", "verbose");
+        assert!(level.starts_with("Invalid log level 'verbose'"), "{level}");
+        assert!(set(&settings, "logging.level", "WARN").is_ok());
+    }
+}
@@ -1,2 +1,7 @@
 pub mod args;
 pub mod config;
+pub mod config_path;
+pub mod request;
+pub mod routing;
+pub mod shorthand;
+pub mod window;
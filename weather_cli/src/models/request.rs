@@ -0,0 +1,450 @@
+//! # Request Validation
+//!
+//! Turns the parsed arguments of the `get` command into a single normalized request.
+//!
+//! Conflicts that depend only on which flags are present are declared on `GetArgs` and
+//! rejected by `clap`. Combinations that depend on the values of the flags are rejected
+//! here, with a message explaining why and what to use instead.
+
+use crate::{
+    common::*,
+    models::{
+        args::{DisplayArgs, GetArgs, OutputFormat, SortMetric},
+        window::TimeWindow,
+    },
+};
+use ::clap::ValueEnum;
+use ::std::path::PathBuf;
+use ::weather_providers::{parse_date, parse_relative_date};
+
+/// A `get` request whose flags are known to be compatible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatedRequest {
+    /// The address or alias to query, if given.
+    pub address: Option<String>,
+    /// The date to query in the `YYYY-MM-DD` format, with a relative date already resolved.
+    pub date: Option<String>,
+    /// The provider to use, if given.
+    pub provider: Option<String>,
+    /// What to retrieve and how to output it.
+    pub mode: GetMode,
+}
+
+/// The kind of `get` request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GetMode {
+    /// The weather of one location for one date.
+    Single {
+        output: OutputFormat,
+        export: Option<PathBuf>,
+        display: DisplayArgs,
+        follow_up: FollowUp,
+    },
+    /// The weather of one location for consecutive days.
+    Days {
+        days: u8,
+        output: OutputFormat,
+        display: DisplayArgs,
+    },
+    /// Only resolve the address and the provider, without retrieving the weather.
+    DryRun,
+    /// The weather of every location listed in a file, as a CSV table or NDJSON.
+    Batch {
+        location_file: PathBuf,
+        concurrency: Option<u16>,
+        sort_by: Option<SortMetric>,
+        output: OutputFormat,
+        export: Option<PathBuf>,
+    },
+}
+
+/// What a single-date `get` request does with a successful result, besides printing it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FollowUp {
+    /// Fail if the weather exceeds the alert thresholds.
+    pub alert: bool,
+    /// Check the alert thresholds in every hour of the window instead of in the weather.
+    pub window: Option<TimeWindow>,
+    /// Save the location as an address alias.
+    pub save_as: Option<SaveAs>,
+}
+
+/// An address alias to save after a successful request (`--save-as`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveAs {
+    /// The name of the alias.
+    pub alias: String,
+    /// Overwrite the alias if it already exists.
+    pub force: bool,
+}
+
+/// Validates the arguments of the `get` command and normalizes them into a request.
+///
+/// # Errors
+///
+/// Returns an error explaining the conflict if:
+/// * `--days` or `--location-file` is combined with a metrics output format, which describes a
+///   single observation.
+/// * A text display flag (e.g., `--unit`) is combined with a non-text output or with
+///   `--location-file`, where it would have no effect.
+/// * `--export` points to the `--location-file` it would overwrite.
+/// * The `--date`, `--date-relative` or `--window` value cannot be parsed.
+pub fn validate_get_args(args: &GetArgs) -> Result<ValidatedRequest> {
+    let mode = match (&args.location_file, args.days) {
+        (Some(location_file), _) => {
+            if !matches!(args.output, OutputFormat::Text | OutputFormat::Json) {
+                Err(format!(
+                    "--location-file cannot be combined with --output {}, which describes a \
+                     single observation. Remove --output for a CSV table, or use --output json \
+                     for NDJSON.",
+                    output_name(args.output)
+                ))?;
+            }
+
+            if let Some(flag) = args.display.first_flag() {
+                Err(format!(
+                    "{flag} does not apply to the CSV table or NDJSON of --location-file. \
+                     Remove {flag}."
+                ))?;
+            }
+
+            if args.export.as_ref() == Some(location_file) {
+                Err(format!(
+                    "--export would overwrite the location file {location_file:?}. \
+                     Export to another file."
+                ))?;
+            }
+
+            GetMode::Batch {
+                location_file: location_file.clone(),
+                concurrency: args.concurrency,
+                sort_by: args.sort_by,
+                output: args.output,
+                export: args.export.clone(),
+            }
+        }
+        (None, Some(days)) => {
+            if !matches!(args.output, OutputFormat::Text | OutputFormat::Json) {
+                Err(format!(
+                    "--days cannot be combined with --output {}, which describes a single \
+                     observation. Use --output text or --output json, or remove --days.",
+                    output_name(args.output)
+                ))?;
+            }
+            check_display_flags(args)?;
+
+            GetMode::Days {
+                days,
+                output: args.output,
+                display: args.display.clone(),
+            }
+        }
+        (None, None) if args.dry_run => GetMode::DryRun,
+        (None, None) => {
+            check_display_flags(args)?;
+
+            GetMode::Single {
+                output: args.output,
+                export: args.export.clone(),
+                display: args.display.clone(),
+                follow_up: FollowUp {
+                    alert: args.alert,
+                    window: args.window.as_deref().map(TimeWindow::parse).transpose()?,
+                    save_as: args.save_as.as_ref().map(|alias| SaveAs {
+                        alias: alias.clone(),
+                        force: args.force,
+                    }),
+                },
+            }
+        }
+    };
+
+    let date = match &args.date_relative {
+        Some(expr) => Some(parse_relative_date(expr)?.format("%Y-%m-%d").to_string()),
+        None => validate_date(args.date.as_deref())?,
+    };
+
+    Ok(ValidatedRequest {
+        address: args.address.clone(),
+        date,
+        provider: args.provider.clone(),
+        mode,
+    })
+}
+
+/// Validates the `--date` of a command and normalizes it to the `YYYY-MM-DD` format.
+///
+/// Every command accepting `--date` validates it here, so a mistyped date fails instead of
+/// being requested as today.
+///
+/// # Errors
+///
+/// Returns an error listing the supported formats if `date` cannot be parsed.
+pub fn validate_date(date: Option<&str>) -> Result<Option<String>> {
+    date.map(|date| Ok(parse_date(date)?.format("%Y-%m-%d").to_string()))
+        .transpose()
+}
+
+/// Rejects text display flags when the output is not text.
+fn check_display_flags(args: &GetArgs) -> Result<()> {
+    if args.output != OutputFormat::Text
+        && let Some(flag) = args.display.first_flag()
+    {
+        Err(format!(
+            "{flag} only affects the text output, not --output {}. \
+             Remove {flag} or use --output text.",
+            output_name(args.output)
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the command-line name of an output format, e.g. "openmetrics".
+fn output_name(output: OutputFormat) -> String {
+    output
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::args::{AppCommands, Cli};
+    use ::clap::Parser;
+
+    /// The expected outcome of a combination of `get` flags.
+    #[derive(Debug)]
+    enum Outcome {
+        Single,
+        Days,
+        Batch,
+        DryRun,
+        /// Rejected by `clap` while parsing.
+        Conflict,
+        /// Rejected by the validator with a message containing the text.
+        Invalid(&'static str),
+    }
+
+    fn validate(flags: &[&str]) -> std::result::Result<Result<ValidatedRequest>, clap::Error> {
+        let cli = Cli::try_parse_from(["weather", "get"].iter().chain(flags))?;
+        let Some(AppCommands::Get(args)) = cli.command else {
+            panic!("Expected Get command");
+        };
+
+        Ok(validate_get_args(&args))
+    }
+
+    #[test]
+    fn test_compatibility_matrix() {
+        use Outcome::*;
+
+        let matrix: &[(&[&str], Outcome)] = &[
+            // A single date
+            (&["London"], Single),
+            (&["London", "--date", "2024-01-01"], Single),
+            (&["London", "--date", "15.01.2024"], Single),
+            (
+                &["London", "--date", "2024-13-45"],
+                Invalid("Invalid date: '2024-13-45'"),
+            ),
+            (&["London", "--date-relative", "yesterday"], Single),
+            (
+                &["London", "--date", "2024-01-01", "--date-relative", "-1d"],
+                Conflict,
+            ),
+            (
+                &["London", "--date-relative", "someday"],
+                Invalid("someday"),
+            ),
+            (&["London", "-o", "json", "--export", "w.json"], Single),
+            (
+                &["London", "-o", "prometheus", "--export", "w.prom"],
+                Single,
+            ),
+            (&["London", "-o", "openmetrics"], Single),
+            (&["London", "--unit", "celsius", "--detailed"], Single),
+            (&["London", "--alert"], Single),
+            (&["London", "--alert", "-o", "json"], Single),
+            (&["London", "--alert", "--days", "3"], Conflict),
+            (&["--location-file", "cities.txt", "--alert"], Conflict),
+            (&["London", "--alert", "--window", "06:00-09:00"], Single),
+            (&["London", "--alert", "--window", "sunrise±2h"], Single),
+            (&["London", "--alert", "--window", "sunset-1h"], Single),
+            (&["London", "--window", "06:00-09:00"], Conflict),
+            (
+                &["London", "--alert", "--window", "dawn"],
+                Invalid("Invalid window 'dawn'"),
+            ),
+            (&["London", "--save-as", "home"], Single),
+            (
+                &["London", "--save-as", "home", "--force", "-o", "json"],
+                Single,
+            ),
+            (&["London", "--force"], Conflict),
+            (&["London", "--save-as", "home", "--days", "3"], Conflict),
+            (
+                &["--location-file", "cities.txt", "--save-as", "home"],
+                Conflict,
+            ),
+            (
+                &["London", "-o", "json", "--unit", "celsius"],
+                Invalid("--unit only affects"),
+            ),
+            (
+                &["London", "-o", "prometheus", "--detailed"],
+                Invalid("--detailed only affects"),
+            ),
+            (
+                &["London", "-o", "openmetrics", "--locale", "de-DE"],
+                Invalid("--output text"),
+            ),
+            // Consecutive days
+            (&["London", "--days", "3"], Days),
+            (&["London", "--days", "3", "--date", "2024-01-01"], Days),
+            (&["London", "--days", "3", "--date-relative", "+1d"], Days),
+            (&["London", "--days", "3", "-o", "json"], Days),
+            (
+                &["London", "--days", "3", "--show-date", "--unit", "celsius"],
+                Days,
+            ),
+            (
+                &["London", "--days", "3", "-o", "prometheus"],
+                Invalid("--output prometheus"),
+            ),
+            (
+                &["London", "--days", "3", "-o", "openmetrics"],
+                Invalid("remove --days"),
+            ),
+            (
+                &["London", "--days", "3", "-o", "json", "--hide-humidity"],
+                Invalid("--hide-humidity"),
+            ),
+            (&["London", "--days", "3", "--export", "w.txt"], Conflict),
+            (&["London", "--days", "0"], Conflict),
+            (&["London", "--days", "17"], Conflict),
+            // A location file
+            (&["--location-file", "cities.txt"], Batch),
+            (
+                &["--location-file", "cities.txt", "--concurrency", "8"],
+                Batch,
+            ),
+            (
+                &["--location-file", "cities.txt", "--export", "w.csv"],
+                Batch,
+            ),
+            (
+                &["--location-file", "cities.txt", "--date-relative", "today"],
+                Batch,
+            ),
+            (
+                &["--location-file", "cities.txt", "--export", "cities.txt"],
+                Invalid("overwrite"),
+            ),
+            (
+                &["--location-file", "cities.txt", "--unit", "celsius"],
+                Invalid("CSV table"),
+            ),
+            (
+                &["--location-file", "cities.txt", "--hide-description"],
+                Invalid("--hide-description"),
+            ),
+            (&["London", "--location-file", "cities.txt"], Conflict),
+            (&["--location-file", "cities.txt", "--days", "3"], Conflict),
+            (&["--location-file", "cities.txt", "-o", "json"], Batch),
+            (
+                &[
+                    "--location-file",
+                    "cities.txt",
+                    "-o",
+                    "json",
+                    "--export",
+                    "w.ndjson",
+                ],
+                Batch,
+            ),
+            (
+                &["--location-file", "cities.txt", "-o", "prometheus"],
+                Invalid("--output prometheus"),
+            ),
+            (
+                &["--location-file", "cities.txt", "-o", "json", "--detailed"],
+                Invalid("--detailed"),
+            ),
+            (&["--concurrency", "8"], Conflict),
+            // Only the resolution
+            (&["London", "--dry-run"], DryRun),
+            (&["--dry-run", "-p", "ow"], DryRun),
+            (&["London", "--dry-run", "--days", "3"], Conflict),
+            (&["London", "--dry-run", "--save-as", "home"], Conflict),
+            (&["--location-file", "cities.txt", "--dry-run"], Conflict),
+            (&["London", "--concurrency", "8"], Conflict),
+        ];
+
+        for (flags, expected) in matrix {
+            let outcome = validate(flags);
+
+            match (expected, outcome) {
+                (Conflict, Err(_)) => {}
+                (Invalid(text), Ok(Err(e))) => {
+                    assert!(e.to_string().contains(text), "{flags:?}: {e}");
+                }
+                (Single, Ok(Ok(r))) if matches!(r.mode, GetMode::Single { .. }) => {}
+                (Days, Ok(Ok(r))) if matches!(r.mode, GetMode::Days { .. }) => {}
+                (Batch, Ok(Ok(r))) if matches!(r.mode, GetMode::Batch { .. }) => {}
+                (DryRun, Ok(Ok(r))) if r.mode == GetMode::DryRun => {}
+                (expected, outcome) => panic!("{flags:?}: expected {expected:?}, got {outcome:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_date() {
+        assert_eq!(validate_date(None).unwrap(), None);
+        assert_eq!(
+            validate_date(Some("01/15/2024")).unwrap().as_deref(),
+            Some("2024-01-15")
+        );
+        assert!(
+            validate_date(Some("garbage"))
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid date: 'garbage'")
+        );
+    }
+
+    #[test]
+    fn test_normalized_request() {
+        let request = validate(&["Paris", "--days", "5", "-p", "ow", "--unit", "celsius"])
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(request.address.as_deref(), Some("Paris"));
+        assert_eq!(request.provider.as_deref(), Some("ow"));
+        assert_eq!(request.date, None);
+        let GetMode::Days {
+            days,
+            output,
+            display,
+        } = request.mode
+        else {
+            panic!("Expected Days mode");
+        };
+        assert_eq!(days, 5);
+        assert_eq!(output, OutputFormat::Text);
+        assert_eq!(display.first_flag(), Some("--unit"));
+
+        // A date is normalized, and a relative date is resolved into an absolute one
+        let request = validate(&["Paris", "--date", "15 Jan 2024"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.date.as_deref(), Some("2024-01-15"));
+
+        let request = validate(&["Paris", "--date-relative", "today"])
+            .unwrap()
+            .unwrap();
+        let today = parse_relative_date("today").unwrap();
+        assert_eq!(request.date, Some(today.format("%Y-%m-%d").to_string()));
+    }
+}
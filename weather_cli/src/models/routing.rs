@@ -0,0 +1,330 @@
+//! # Provider Routing
+//!
+//! Rules choosing the provider of a request from its address when no `--provider` is
+//! given, e.g. one provider for US addresses and another for the rest of the world.
+
+use crate::common::Result;
+use ::regex::{Regex, RegexBuilder};
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ::std::{collections::HashMap, fmt::Display, hash::Hash};
+
+/// A rule of `routing`: the provider to use for the addresses it matches.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RoutingRule {
+    /// What the address must look like.
+    #[serde(rename = "match")]
+    pub matcher: RouteMatch,
+
+    /// The ID of the provider to use (e.g., "wa").
+    pub provider: String,
+}
+
+/// What an address must look like for a routing rule to apply.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteMatch {
+    /// The country of the address: its last comma-separated part (e.g., "US" in
+    /// "Austin, TX, US").
+    Country(String),
+    /// The beginning of the address (e.g., "iata:").
+    Prefix(String),
+    /// A regular expression matching the address, or a part of it.
+    Regex(RoutePattern),
+}
+
+/// The regular expression of a routing rule, compiled once when the rule is read.
+#[derive(Debug, Clone)]
+pub struct RoutePattern {
+    source: String,
+    /// The case-insensitive expression, or why `source` is not one.
+    regex: std::result::Result<Regex, regex::Error>,
+}
+
+impl RoutePattern {
+    /// Compiles `source`, ignoring the case. An invalid expression is kept to be reported by
+    /// `RouteMatch::validate`.
+    pub fn new(source: impl Into<String>) -> Self {
+        let source = source.into();
+        let regex = RegexBuilder::new(&source).case_insensitive(true).build();
+
+        Self { source, regex }
+    }
+}
+
+impl PartialEq for RoutePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Serialize for RoutePattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for RoutePattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::new)
+    }
+}
+
+impl RouteMatch {
+    /// Returns `true` if `address` matches, ignoring the case.
+    ///
+    /// An invalid regular expression matches nothing; `validate` rejects it beforehand.
+    pub fn matches(&self, address: &str) -> bool {
+        let address = address.trim();
+
+        match self {
+            RouteMatch::Country(country) => address
+                .rsplit(',')
+                .next()
+                .is_some_and(|last| last.trim().eq_ignore_ascii_case(country.trim())),
+            RouteMatch::Prefix(prefix) => address
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix)),
+            RouteMatch::Regex(pattern) => pattern
+                .regex
+                .as_ref()
+                .is_ok_and(|regex| regex.is_match(address)),
+        }
+    }
+
+    /// Checks that the condition can match an address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the country or prefix is empty, or the regular expression is invalid.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            RouteMatch::Country(value) | RouteMatch::Prefix(value) if value.trim().is_empty() => {
+                Err(format!("The routing rule '{self}' matches nothing."))?
+            }
+            RouteMatch::Regex(pattern) => {
+                if let Err(e) = &pattern.regex {
+                    Err(format!("Invalid routing regex '{}': {e}", pattern.source))?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for RouteMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RouteMatch::Country(country) => write!(f, "country {country:?}"),
+            RouteMatch::Prefix(prefix) => write!(f, "prefix {prefix:?}"),
+            RouteMatch::Regex(pattern) => write!(f, "regex {:?}", pattern.source),
+        }
+    }
+}
+
+/// Returns the first rule matching `address`, with its position in `rules`.
+pub fn route<'a>(rules: &'a [RoutingRule], address: &str) -> Option<(usize, &'a RoutingRule)> {
+    rules
+        .iter()
+        .enumerate()
+        .find(|(_, rule)| rule.matcher.matches(address))
+}
+
+/// A value chosen for each of a set of addresses, e.g. the provider every address is routed
+/// to. Addresses routed alike share one value.
+#[derive(Debug)]
+pub struct Routed<T> {
+    values: Vec<T>,
+    /// The index in `values` of the value of each address.
+    routes: HashMap<String, usize>,
+}
+
+impl<T> Routed<T> {
+    /// Routes `addresses`, calling `resolve` once for each distinct `key` of an address.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of `resolve`.
+    pub fn resolve<K: Eq + Hash>(
+        addresses: &[String],
+        key: impl Fn(&str) -> K,
+        mut resolve: impl FnMut(&K) -> Result<T>,
+    ) -> Result<Self> {
+        let mut indices = HashMap::new();
+        let mut values = Vec::new();
+        let mut routes = HashMap::new();
+
+        for address in addresses {
+            let key = key(address);
+            let index = match indices.get(&key) {
+                Some(&index) => index,
+                None => {
+                    values.push(resolve(&key)?);
+                    indices.insert(key, values.len() - 1);
+                    values.len() - 1
+                }
+            };
+            routes.insert(address.clone(), index);
+        }
+
+        Ok(Self { values, routes })
+    }
+
+    /// Returns the value of `address`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `address` was not routed.
+    pub fn get(&self, address: &str) -> &T {
+        &self.values[self.routes[address]]
+    }
+
+    /// Returns the distinct values, in the order of their first address.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.values.iter()
+    }
+
+    /// Converts every value with `f`, keeping the routes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of `f`.
+    pub fn try_map<U>(self, f: impl FnMut(T) -> Result<U>) -> Result<Routed<U>> {
+        Ok(Routed {
+            values: self.values.into_iter().map(f).collect::<Result<_>>()?,
+            routes: self.routes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(matcher: RouteMatch, provider: &str) -> RoutingRule {
+        RoutingRule {
+            matcher,
+            provider: provider.to_string(),
+        }
+    }
+
+    fn routed<'a>(rules: &'a [RoutingRule], address: &str) -> Option<(usize, &'a str)> {
+        route(rules, address).map(|(i, rule)| (i, rule.provider.as_str()))
+    }
+
+    #[test]
+    fn test_matches() {
+        let country = RouteMatch::Country("US".to_string());
+        assert!(country.matches("Austin, TX, US"));
+        assert!(country.matches("Boston, us "));
+        assert!(!country.matches("Paris, FR"));
+        assert!(!country.matches("Usti, CZ"));
+        assert!(country.matches("US"));
+
+        let prefix = RouteMatch::Prefix("iata:".to_string());
+        assert!(prefix.matches("iata:JFK"));
+        assert!(prefix.matches("IATA:lhr"));
+        assert!(!prefix.matches("ia"));
+        assert!(!prefix.matches("Київ"));
+
+        let regex = RouteMatch::Regex(RoutePattern::new(r"^\d{5}$"));
+        assert!(regex.matches("10001"));
+        assert!(!regex.matches("London"));
+        assert!(!RouteMatch::Regex(RoutePattern::new("(")).matches("("));
+    }
+
+    #[test]
+    fn test_route_first_match() {
+        let rules = [
+            rule(RouteMatch::Prefix("iata:".to_string()), "ow"),
+            rule(RouteMatch::Country("US".to_string()), "wa"),
+            rule(RouteMatch::Regex(RoutePattern::new(", (US|CA)$")), "sy"),
+            rule(RouteMatch::Regex(RoutePattern::new(".")), "mock"),
+        ];
+
+        // Overlapping rules: the first one listed wins
+        assert_eq!(routed(&rules, "iata:JFK, US"), Some((0, "ow")));
+        assert_eq!(routed(&rules, "Austin, TX, US"), Some((1, "wa")));
+        assert_eq!(routed(&rules, "Toronto, CA"), Some((2, "sy")));
+        assert_eq!(routed(&rules, "Paris, FR"), Some((3, "mock")));
+        assert_eq!(routed(&rules[..3], "Paris, FR"), None);
+        assert_eq!(routed(&[], "Paris, FR"), None);
+    }
+
+    #[test]
+    fn test_routed() {
+        let addresses = ["Austin, US", "Paris, FR", "Boston, US", "Paris, FR"].map(String::from);
+        let mut resolved = Vec::new();
+
+        let routed = Routed::resolve(
+            &addresses,
+            |address| address.ends_with("US"),
+            |&us| {
+                resolved.push(us);
+                Ok(if us { "wa" } else { "ow" })
+            },
+        )
+        .unwrap();
+
+        // Each distinct key is resolved once, in the order of the addresses
+        assert_eq!(resolved, [true, false]);
+        assert_eq!(routed.values().collect::<Vec<_>>(), [&"wa", &"ow"]);
+        assert_eq!(*routed.get("Boston, US"), "wa");
+        assert_eq!(*routed.get("Paris, FR"), "ow");
+
+        let routed = routed.try_map(|id| Ok(id.len())).unwrap();
+        assert_eq!(*routed.get("Austin, US"), 2);
+
+        let error = Routed::<()>::resolve(&addresses, |_| (), |_| Err("no key".into()));
+        assert_eq!(error.unwrap_err().to_string(), "no key");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(RouteMatch::Country("US".to_string()).validate().is_ok());
+        assert!(
+            RouteMatch::Regex(RoutePattern::new(r"^\d+$"))
+                .validate()
+                .is_ok()
+        );
+
+        let error = RouteMatch::Regex(RoutePattern::new("([a-z]"))
+            .validate()
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("Invalid routing regex"),
+            "{error}"
+        );
+        assert!(RouteMatch::Prefix(" ".to_string()).validate().is_err());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let rules: Vec<RoutingRule> = serde_json::from_str(
+            r#"[
+                {"match": {"country": "US"}, "provider": "wa"},
+                {"match": {"prefix": "iata:"}, "provider": "ow"},
+                {"match": {"regex": "^\\d{5}$"}, "provider": "sy"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rules,
+            [
+                rule(RouteMatch::Country("US".to_string()), "wa"),
+                rule(RouteMatch::Prefix("iata:".to_string()), "ow"),
+                rule(RouteMatch::Regex(RoutePattern::new(r"^\d{5}$")), "sy"),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&rules[2].matcher).unwrap(),
+            r#"{"regex":"^\\d{5}$"}"#
+        );
+        assert!(
+            serde_json::from_str::<RoutingRule>(r#"{"match": {"city": "x"}, "provider": "wa"}"#)
+                .is_err()
+        );
+    }
+}
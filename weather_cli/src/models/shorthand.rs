@@ -0,0 +1,224 @@
+//! # Command Shorthands
+//!
+//! Rewrites the command line before it is parsed, so common invocations need fewer words:
+//!
+//! - A user-defined command alias (`command_aliases` in the configuration, e.g. "w" ->
+//!   "get --output json") is replaced by its expansion.
+//! - A location given without a subcommand becomes `get <LOCATION>`: `weather London` is
+//!   `weather get London`.
+//!
+//! A word that is a subcommand is always parsed as the subcommand, even if a location of that
+//! name was meant; `CommandLine::hint` then explains how to query the location instead.
+
+use crate::models::args::Cli;
+use ::clap::CommandFactory;
+use ::std::{collections::BTreeMap, ffi::OsString};
+
+/// The global flags that may precede the subcommand, and whether they take a value.
+const GLOBAL_FLAGS: &[(&str, bool)] = &[
+    ("--debug", false),
+    ("--no-log-file", false),
+    ("--no-config", false),
+    ("--log-level", true),
+    ("-h", false),
+    ("--help", false),
+    ("-V", false),
+    ("--version", false),
+];
+
+/// A rewritten command line.
+#[derive(Debug, PartialEq)]
+pub struct CommandLine {
+    /// The arguments to parse, starting with the program name.
+    pub args: Vec<OsString>,
+    /// The subcommand given explicitly, other than `get`.
+    subcommand: Option<String>,
+}
+
+impl CommandLine {
+    /// Rewrites the arguments, expanding the first word if it is one of the `aliases` and
+    /// inserting `get` if the first word is not a subcommand.
+    pub fn rewrite(
+        args: impl IntoIterator<Item = OsString>,
+        aliases: &BTreeMap<String, String>,
+    ) -> Self {
+        let mut args = args.into_iter().collect::<Vec<_>>();
+        let Some(index) = first_word(&args) else {
+            return Self {
+                args,
+                subcommand: None,
+            };
+        };
+
+        let word = args[index].to_string_lossy().into_owned();
+        if is_subcommand(&word) {
+            return Self {
+                args,
+                subcommand: (word != "get").then_some(word),
+            };
+        }
+
+        if let Some(expansion) = aliases.get(&word) {
+            args.splice(
+                index..=index,
+                expansion.split_whitespace().map(OsString::from),
+            );
+            // An alias may expand to a location, e.g. "h" -> "home --unit celsius"
+            if args
+                .get(index)
+                .is_some_and(|arg| is_subcommand(&arg.to_string_lossy()))
+            {
+                return Self {
+                    args,
+                    subcommand: None,
+                };
+            }
+        }
+
+        args.insert(index, OsString::from("get"));
+        Self {
+            args,
+            subcommand: None,
+        }
+    }
+
+    /// Returns advice for a command line that failed to parse after an explicit subcommand,
+    /// in case a location of the same name was meant.
+    pub fn hint(&self) -> Option<String> {
+        self.subcommand.as_ref().map(|subcommand| {
+            format!(
+                "'{subcommand}' is a command. To get the weather of a location named \
+                 '{subcommand}', use: weather get {subcommand}"
+            )
+        })
+    }
+}
+
+/// Returns whether `name` is a subcommand (or an alias of one), including `help`.
+pub fn is_subcommand(name: &str) -> bool {
+    name == "help"
+        || Cli::command().get_subcommands().any(|command| {
+            command.get_name() == name || command.get_all_aliases().any(|a| a == name)
+        })
+}
+
+/// Returns the index of the first argument after the global flags, i.e. the subcommand.
+fn first_word(args: &[OsString]) -> Option<usize> {
+    let mut index = 1;
+
+    while let Some(arg) = args.get(index) {
+        let arg = arg.to_string_lossy();
+        if arg.starts_with("--log-level=") {
+            index += 1;
+            continue;
+        }
+
+        match GLOBAL_FLAGS.iter().find(|(flag, _)| *flag == arg) {
+            Some((_, takes_value)) => index += if *takes_value { 2 } else { 1 },
+            None if arg == "--" => return None,
+            None => return Some(index),
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite(args: &[&str], aliases: &[(&str, &str)]) -> CommandLine {
+        let aliases = aliases
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        CommandLine::rewrite(["weather"].iter().chain(args).map(OsString::from), &aliases)
+    }
+
+    fn args(command_line: &CommandLine) -> Vec<String> {
+        command_line
+            .args
+            .iter()
+            .skip(1)
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_location_becomes_get() {
+        assert_eq!(args(&rewrite(&["London"], &[])), ["get", "London"]);
+        assert_eq!(
+            args(&rewrite(&["--debug", "New York", "-p", "mock"], &[])),
+            ["--debug", "get", "New York", "-p", "mock"]
+        );
+        assert_eq!(
+            args(&rewrite(&["--log-level", "debug", "Paris"], &[])),
+            ["--log-level", "debug", "get", "Paris"]
+        );
+        assert_eq!(
+            args(&rewrite(&["--no-config", "Paris"], &[])),
+            ["--no-config", "get", "Paris"]
+        );
+        // Flags of `get` without a location use the default alias
+        assert_eq!(args(&rewrite(&["-p", "mock"], &[])), ["get", "-p", "mock"]);
+    }
+
+    #[test]
+    fn test_unchanged() {
+        for unchanged in [
+            &[][..],
+            &["--help"],
+            &["--version"],
+            &["get", "London"],
+            &["compare", "London", "Paris"],
+            &["help", "get"],
+            &["--debug", "--", "London"],
+        ] {
+            assert_eq!(args(&rewrite(unchanged, &[])), unchanged, "{unchanged:?}");
+        }
+    }
+
+    #[test]
+    fn test_alias_expansion() {
+        let aliases = [("w", "get --output json"), ("h", "home --unit celsius")];
+
+        assert_eq!(
+            args(&rewrite(&["w", "London"], &aliases)),
+            ["get", "--output", "json", "London"]
+        );
+        assert_eq!(
+            args(&rewrite(&["h", "--show-date"], &aliases)),
+            ["get", "home", "--unit", "celsius", "--show-date"]
+        );
+        // Only the first word is expanded
+        assert_eq!(
+            args(&rewrite(&["compare", "w"], &aliases)),
+            ["compare", "w"]
+        );
+    }
+
+    #[test]
+    fn test_ambiguity_hint() {
+        let subcommand = rewrite(&["provider"], &[]);
+        assert_eq!(args(&subcommand), ["provider"]);
+        assert_eq!(
+            subcommand.hint().unwrap(),
+            "'provider' is a command. To get the weather of a location named 'provider', \
+             use: weather get provider"
+        );
+
+        assert_eq!(rewrite(&["get", "provider"], &[]).hint(), None);
+        assert_eq!(rewrite(&["London"], &[]).hint(), None);
+    }
+
+    #[test]
+    fn test_rewritten_arguments_parse() {
+        let command_line = rewrite(&["London", "--unit", "celsius"], &[]);
+        let cli = <Cli as clap::Parser>::try_parse_from(&command_line.args).unwrap();
+
+        assert!(matches!(
+            cli.command,
+            Some(crate::models::args::AppCommands::Get(_))
+        ));
+    }
+}
@@ -0,0 +1,382 @@
+//! # Time Windows
+//!
+//! The `--window` expressions of `weather get --alert`, which check the alert thresholds in
+//! every hour of a part of the day instead of in the current weather:
+//!
+//! - `HH:MM-HH:MM`: a range of the time of day at the location, e.g. `06:00-09:00`. A range
+//!   ending before it starts wraps around midnight, e.g. `22:00-02:00`.
+//! - `sunrise±2h` or `sunset±90m`: the time around sunrise or sunset of the day. `+` keeps only
+//!   the time after the event and `-` only the time before it; `+-` may be typed for `±`.
+
+use crate::common::*;
+use ::chrono::{DateTime, FixedOffset, NaiveTime, TimeDelta};
+use ::std::fmt::Display;
+use ::weather_providers::WeatherInfo;
+
+/// The farthest a window may reach from sunrise or sunset.
+const MAX_OFFSET: TimeDelta = TimeDelta::hours(12);
+
+/// An astronomical event of the day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SunEvent {
+    Sunrise,
+    Sunset,
+}
+
+impl SunEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            SunEvent::Sunrise => "sunrise",
+            SunEvent::Sunset => "sunset",
+        }
+    }
+}
+
+/// A part of the day, as given to `--window`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeWindow {
+    /// The times of day from `start` (inclusive) to `end` (exclusive), wrapping around
+    /// midnight if `end` is before `start`.
+    Clock { start: NaiveTime, end: NaiveTime },
+    /// The time from `before` the event to `after` it.
+    Sun {
+        event: SunEvent,
+        before: TimeDelta,
+        after: TimeDelta,
+    },
+}
+
+/// A window resolved against the astronomy data of a day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResolvedWindow {
+    /// The times of day from `start` (inclusive) to `end` (exclusive), in the local time of
+    /// the location.
+    Clock { start: NaiveTime, end: NaiveTime },
+    /// The instants from `start` to `end`, both inclusive.
+    Between {
+        start: DateTime<FixedOffset>,
+        end: DateTime<FixedOffset>,
+    },
+}
+
+impl TimeWindow {
+    /// Parses a window expression, ignoring the case and surrounding whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error explaining the expected syntax if `expr` is not a window, or if the
+    /// window is empty or reaches more than 12 hours from sunrise or sunset.
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = expr.trim();
+        let invalid = |reason: &str| {
+            Error::from(format!(
+                "Invalid window '{expr}': {reason}. Use a time of day range like 06:00-09:00, \
+                 or a time around sunrise or sunset like sunrise±2h."
+            ))
+        };
+        let lower = expr.to_lowercase();
+
+        for event in [SunEvent::Sunrise, SunEvent::Sunset] {
+            let Some(rest) = lower.strip_prefix(event.name()) else {
+                continue;
+            };
+
+            let (sides, offset) =
+                if let Some(offset) = rest.strip_prefix('±').or_else(|| rest.strip_prefix("+-")) {
+                    ((true, true), offset)
+                } else if let Some(offset) = rest.strip_prefix('+') {
+                    ((false, true), offset)
+                } else if let Some(offset) = rest.strip_prefix('-') {
+                    ((true, false), offset)
+                } else {
+                    return Err(invalid("expected ±, + or - after the event"));
+                };
+
+            let offset = parse_offset(offset.trim())
+                .ok_or_else(|| invalid("expected an offset in hours or minutes, e.g. 2h or 90m"))?;
+            if offset.is_zero() || offset > MAX_OFFSET {
+                return Err(invalid("the offset must be between 1m and 12h"));
+            }
+
+            let side = |included: bool| if included { offset } else { TimeDelta::zero() };
+            return Ok(TimeWindow::Sun {
+                event,
+                before: side(sides.0),
+                after: side(sides.1),
+            });
+        }
+
+        let (start, end) = lower
+            .split_once('-')
+            .ok_or_else(|| invalid("expected a range of times or a sun event"))?;
+        let time = |time: &str| {
+            NaiveTime::parse_from_str(time.trim(), "%H:%M")
+                .map_err(|_| invalid("times are written HH:MM, from 00:00 to 23:59"))
+        };
+        let (start, end) = (time(start)?, time(end)?);
+
+        if start == end {
+            return Err(invalid("the range is empty"));
+        }
+
+        Ok(TimeWindow::Clock { start, end })
+    }
+
+    /// Returns `true` if the window is around sunrise or sunset.
+    pub fn needs_sun(&self) -> bool {
+        matches!(self, TimeWindow::Sun { .. })
+    }
+
+    /// Resolves the window against the sunrise and sunset of the weather.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the window is around sunrise or sunset and the provider did not
+    /// report its time.
+    pub fn resolve(&self, weather: &WeatherInfo) -> Result<ResolvedWindow> {
+        match *self {
+            TimeWindow::Clock { start, end } => Ok(ResolvedWindow::Clock { start, end }),
+            TimeWindow::Sun {
+                event,
+                before,
+                after,
+            } => {
+                let time = match event {
+                    SunEvent::Sunrise => weather.sunrise.as_deref(),
+                    SunEvent::Sunset => weather.sunset.as_deref(),
+                }
+                .ok_or_else(|| {
+                    format!(
+                        "The provider reported no {} time for {}, which the window '{self}' \
+                         needs. Use a time of day range instead, e.g. 06:00-09:00.",
+                        event.name(),
+                        weather.date
+                    )
+                })?;
+                let time = DateTime::parse_from_rfc3339(time)
+                    .map_err(|e| format!("Invalid {} time '{time}': {e}", event.name()))?;
+
+                Ok(ResolvedWindow::Between {
+                    start: time - before,
+                    end: time + after,
+                })
+            }
+        }
+    }
+}
+
+impl Display for TimeWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeWindow::Clock { start, end } => {
+                write!(f, "{}-{}", start.format("%H:%M"), end.format("%H:%M"))
+            }
+            TimeWindow::Sun {
+                event,
+                before,
+                after,
+            } => {
+                let (sign, offset) = match (before.is_zero(), after.is_zero()) {
+                    (true, _) => ('+', after),
+                    (_, true) => ('-', before),
+                    _ => ('±', before),
+                };
+                let minutes = offset.num_minutes();
+
+                if minutes % 60 == 0 {
+                    write!(f, "{}{sign}{}h", event.name(), minutes / 60)
+                } else {
+                    write!(f, "{}{sign}{minutes}m", event.name())
+                }
+            }
+        }
+    }
+}
+
+impl ResolvedWindow {
+    /// Returns `true` if the window contains the time, given in the local time of the location.
+    pub fn contains(&self, time: DateTime<FixedOffset>) -> bool {
+        match *self {
+            ResolvedWindow::Clock { start, end } => {
+                let time = time.time();
+
+                if start < end {
+                    start <= time && time < end
+                } else {
+                    // Wraps around midnight
+                    time >= start || time < end
+                }
+            }
+            ResolvedWindow::Between { start, end } => start <= time && time <= end,
+        }
+    }
+}
+
+/// Parses an offset like "2h" or "90m".
+fn parse_offset(offset: &str) -> Option<TimeDelta> {
+    if let Some(hours) = offset.strip_suffix('h') {
+        return Some(TimeDelta::hours(hours.parse::<u8>().ok()?.into()));
+    }
+
+    let minutes = offset.strip_suffix('m')?;
+    Some(TimeDelta::minutes(minutes.parse::<u16>().ok()?.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::weather_providers::{Provider, create_provider};
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn at(time: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(time).unwrap()
+    }
+
+    async fn weather(sunrise: Option<&str>) -> WeatherInfo {
+        let mut weather = create_provider(Provider::Mock)
+            .as_historical()
+            .unwrap()
+            .get_history(None, "London", "2024-06-21")
+            .await
+            .unwrap();
+        weather.sunrise = sunrise.map(str::to_string);
+        weather
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            TimeWindow::parse("06:00-09:00").unwrap(),
+            TimeWindow::Clock {
+                start: time(6, 0),
+                end: time(9, 0)
+            }
+        );
+        assert_eq!(
+            TimeWindow::parse(" Sunrise±2h ").unwrap(),
+            TimeWindow::Sun {
+                event: SunEvent::Sunrise,
+                before: TimeDelta::hours(2),
+                after: TimeDelta::hours(2)
+            }
+        );
+        assert_eq!(
+            TimeWindow::parse("sunrise+-2h").unwrap(),
+            TimeWindow::parse("sunrise±2h").unwrap()
+        );
+        assert_eq!(
+            TimeWindow::parse("sunset+90m").unwrap(),
+            TimeWindow::Sun {
+                event: SunEvent::Sunset,
+                before: TimeDelta::zero(),
+                after: TimeDelta::minutes(90)
+            }
+        );
+        assert_eq!(
+            TimeWindow::parse("sunset-1h").unwrap(),
+            TimeWindow::Sun {
+                event: SunEvent::Sunset,
+                before: TimeDelta::hours(1),
+                after: TimeDelta::zero()
+            }
+        );
+
+        for expr in [
+            "06:00-09:00",
+            "22:00-02:00",
+            "sunrise±2h",
+            "sunset+90m",
+            "sunset-1h",
+        ] {
+            assert_eq!(TimeWindow::parse(expr).unwrap().to_string(), expr);
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let cases = [
+            ("", "expected a range"),
+            ("morning", "expected a range"),
+            ("06:00", "expected a range"),
+            ("6-9", "HH:MM"),
+            ("06:00-24:00", "HH:MM"),
+            ("06:00-06:00", "empty"),
+            ("sunrise", "expected ±, + or -"),
+            ("sunrise*2h", "expected ±, + or -"),
+            ("sunrise±", "offset in hours or minutes"),
+            ("sunrise±2d", "offset in hours or minutes"),
+            ("sunrise±-2h", "offset in hours or minutes"),
+            ("sunrise±0h", "between 1m and 12h"),
+            ("sunset±13h", "between 1m and 12h"),
+        ];
+
+        for (expr, reason) in cases {
+            let error = TimeWindow::parse(expr).unwrap_err().to_string();
+            assert!(error.contains(reason), "{expr}: {error}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_midnight_wraparound() {
+        let window = TimeWindow::parse("22:00-02:00")
+            .unwrap()
+            .resolve(&weather(None).await)
+            .unwrap();
+
+        assert!(window.contains(at("2024-06-21T22:00:00+02:00")));
+        assert!(window.contains(at("2024-06-21T23:00:00+02:00")));
+        assert!(window.contains(at("2024-06-21T00:00:00+02:00")));
+        assert!(window.contains(at("2024-06-21T01:00:00+02:00")));
+        assert!(!window.contains(at("2024-06-21T02:00:00+02:00")));
+        assert!(!window.contains(at("2024-06-21T12:00:00+02:00")));
+
+        // Without wraparound, the end is exclusive
+        let window = ResolvedWindow::Clock {
+            start: time(6, 0),
+            end: time(9, 0),
+        };
+        assert!(window.contains(at("2024-06-21T06:00:00Z")));
+        assert!(!window.contains(at("2024-06-21T09:00:00Z")));
+        assert!(!window.contains(at("2024-06-21T23:00:00Z")));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_sunrise() {
+        let window = TimeWindow::parse("sunrise±2h")
+            .unwrap()
+            .resolve(&weather(Some("2024-06-21T04:43:00+01:00")).await)
+            .unwrap();
+
+        // The window covers the instants, whatever the offset of the hours
+        assert!(window.contains(at("2024-06-21T02:43:00+01:00")));
+        assert!(window.contains(at("2024-06-21T05:00:00Z")));
+        assert!(window.contains(at("2024-06-21T06:43:00+01:00")));
+        assert!(!window.contains(at("2024-06-21T07:00:00+01:00")));
+        assert!(!window.contains(at("2024-06-21T02:00:00+01:00")));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_without_sunrise() {
+        let weather = weather(None).await;
+
+        let error = TimeWindow::parse("sunrise±2h")
+            .unwrap()
+            .resolve(&weather)
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("no sunrise time for 2024-06-21"),
+            "{error}"
+        );
+
+        // A time of day range needs no astronomy data
+        assert!(
+            TimeWindow::parse("06:00-09:00")
+                .unwrap()
+                .resolve(&weather)
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! # Weather Alerts
+//!
+//! Renders the severe weather warnings of a location as a section printed above the weather,
+//! highlighted in red on terminals that support colors.
+
+use ::std::fmt::Write;
+use ::weather_providers::WeatherAlert;
+
+const RED: &str = "\x1b[1;31m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders one block per alert, followed by a blank line, or nothing if there are no alerts.
+///
+/// # Arguments
+///
+/// * `alerts` - The alerts to render.
+/// * `color` - Highlight the headings in red with ANSI escape codes.
+pub fn render(alerts: &[WeatherAlert], color: bool) -> String {
+    let mut section = String::new();
+
+    for alert in alerts {
+        let heading = format!("⚠ WEATHER ALERT: {}", alert.event);
+        let _ = match color {
+            true => writeln!(section, "{RED}{heading}{RESET}"),
+            false => writeln!(section, "{heading}"),
+        };
+        let _ = writeln!(
+            section,
+            "  {} to {} UTC, issued by {}",
+            alert.start.format("%Y-%m-%d %H:%M"),
+            alert.end.format("%Y-%m-%d %H:%M"),
+            alert.sender
+        );
+        for line in alert.description.lines().filter(|l| !l.trim().is_empty()) {
+            let _ = writeln!(section, "  {}", line.trim());
+        }
+        section.push('\n');
+    }
+
+    section
+}
+
+/// Returns `true` if the standard output is a terminal and colors are not disabled with the
+/// `NO_COLOR` environment variable (see <https://no-color.org>).
+pub fn supports_color(is_terminal: bool, env: impl Fn(&str) -> Option<String>) -> bool {
+    is_terminal
+        && env("NO_COLOR").is_none_or(|value| value.is_empty())
+        && env("TERM").is_none_or(|term| term != "dumb")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::chrono::NaiveDate;
+
+    fn alert() -> WeatherAlert {
+        let at = |hour| {
+            NaiveDate::from_ymd_opt(2024, 1, 15)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+        };
+
+        WeatherAlert {
+            event: "Yellow snow and ice warning".to_string(),
+            start: at(11),
+            end: at(20),
+            description: "Snow and ice may cause travel disruption.\n".to_string(),
+            sender: "Met Office".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(&[alert()], false),
+            "\
+⚠ WEATHER ALERT: Yellow snow and ice warning
+  2024-01-15 11:00 to 2024-01-15 20:00 UTC, issued by Met Office
+  Snow and ice may cause travel disruption.
+
+"
+        );
+        assert!(
+            render(&[alert()], true)
+                .starts_with("\x1b[1;31m⚠ WEATHER ALERT: Yellow snow and ice warning\x1b[0m\n")
+        );
+        assert_eq!(render(&[], true), "");
+    }
+
+    #[test]
+    fn test_supports_color() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+
+        assert!(supports_color(true, env(&[])));
+        assert!(supports_color(true, env(&[("NO_COLOR", "")])));
+        assert!(!supports_color(true, env(&[("NO_COLOR", "1")])));
+        assert!(!supports_color(true, env(&[("TERM", "dumb")])));
+        assert!(!supports_color(false, env(&[])));
+    }
+}
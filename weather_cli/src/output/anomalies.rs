@@ -0,0 +1,508 @@
+//! # Weather Anomalies
+//!
+//! Compares the recorded weather of a location with its own statistics and flags the values
+//! more than 2 standard deviations away from the mean (e.g., "2024-01-15 | Temperature |
+//! 71.2°F | 48.3°F | +2.6σ"). Each day of the history counts as one sample, the mean of the
+//! observations fetched that day.
+
+use super::table::Table;
+use crate::common::history::HistoryEntry;
+use ::chrono::{Local, NaiveDate};
+use ::std::collections::BTreeMap;
+use ::weather_providers::{DisplayOptions, WeatherInfo};
+
+/// The fewest days of history from which statistics are computed.
+pub const MIN_SAMPLES: usize = 5;
+
+/// How many standard deviations from the mean a value must exceed to be an anomaly.
+const THRESHOLD_SIGMA: f64 = 2.0;
+
+/// A value of the weather checked for anomalies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    Temperature,
+    Humidity,
+    WindSpeed,
+    Pressure,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 4] = [
+        Metric::Temperature,
+        Metric::Humidity,
+        Metric::WindSpeed,
+        Metric::Pressure,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Metric::Temperature => "Temperature",
+            Metric::Humidity => "Humidity",
+            Metric::WindSpeed => "Wind speed",
+            Metric::Pressure => "Pressure",
+        }
+    }
+
+    /// Returns the value of the metric in the units of `WeatherInfo`, or `None` if it was
+    /// not reported (including the `NaN` temperature of the air quality providers).
+    pub fn value(&self, weather: &WeatherInfo) -> Option<f64> {
+        let value = match self {
+            Metric::Temperature => Some(weather.temperature),
+            Metric::Humidity => weather.humidity.map(f32::from),
+            Metric::WindSpeed => weather.wind_speed_mph,
+            Metric::Pressure => weather.pressure_hpa,
+        };
+
+        value.filter(|v| !v.is_nan()).map(f64::from)
+    }
+
+    /// Formats a value in the units and locale of `opts`.
+    fn format(&self, value: f64, opts: &DisplayOptions) -> String {
+        let value = value as f32;
+        match self {
+            Metric::Temperature => {
+                let unit = opts.temperature_unit;
+                format!(
+                    "{}{}",
+                    opts.format_decimal(unit.from_fahrenheit(value)),
+                    unit.symbol()
+                )
+            }
+            Metric::Humidity => format!("{}%", opts.format_decimal(value)),
+            Metric::WindSpeed => format!(
+                "{} {}",
+                opts.format_decimal(opts.wind_unit.from_mph(value)),
+                opts.wind_unit.symbol()
+            ),
+            Metric::Pressure => format!("{} hPa", opts.format_decimal(value)),
+        }
+    }
+}
+
+/// The mean and standard deviation of a sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl Statistics {
+    /// Computes the statistics of `values`, or returns `None` for fewer than `MIN_SAMPLES`
+    /// values. The standard deviation is that of the population, as the history is all there
+    /// is to compare with.
+    pub fn of(values: &[f64]) -> Option<Self> {
+        if values.len() < MIN_SAMPLES {
+            return None;
+        }
+
+        Some(Self::population(values))
+    }
+
+    /// Computes the statistics of `values` without the one at `index`, to compare it with
+    /// the others: a value inflates the deviation of a sample it is part of, so that in a
+    /// sample of 5 no value can lie 2 standard deviations from the mean.
+    fn leave_out(values: &[f64], index: usize) -> Self {
+        let others = values
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != index)
+            .map(|(_, &value)| value)
+            .collect::<Vec<_>>();
+
+        Self::population(&others)
+    }
+
+    fn population(values: &[f64]) -> Self {
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+
+        Self {
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+
+    /// Returns how many standard deviations `value` lies from the mean (negative below it),
+    /// or `None` if the sample does not vary and `value` equals it. A value differing from a
+    /// sample that does not vary lies infinitely far from it.
+    pub fn deviation(&self, value: f64) -> Option<f64> {
+        let distance = value - self.mean;
+
+        if self.std_dev > 0.0 {
+            Some(distance / self.std_dev)
+        } else if distance != 0.0 {
+            Some(f64::INFINITY.copysign(distance))
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the daily mean of a metric over the entries, by the local day they were fetched,
+/// oldest first. Entries without the metric are skipped, so days without it are left out.
+pub fn daily_values(entries: &[HistoryEntry], metric: Metric) -> Vec<(NaiveDate, f64)> {
+    let mut days = BTreeMap::<NaiveDate, (f64, u32)>::new();
+
+    for entry in entries {
+        if let Some(value) = metric.value(&entry.weather) {
+            let day = entry.fetched_at.with_timezone(&Local).date_naive();
+            let (sum, count) = days.entry(day).or_default();
+            *sum += value;
+            *count += 1;
+        }
+    }
+
+    days.into_iter()
+        .map(|(day, (sum, count))| (day, sum / f64::from(count)))
+        .collect()
+}
+
+/// A value more than 2 standard deviations from the mean of its metric.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    /// The day of the history, or `None` for the fresh observation.
+    pub day: Option<NaiveDate>,
+    pub metric: Metric,
+    pub value: f64,
+    pub mean: f64,
+    /// The distance to the mean in standard deviations, negative below it.
+    pub deviation: f64,
+}
+
+/// The anomalies of the history of a location and of its fresh observation.
+#[derive(Debug, Default)]
+pub struct AnomalyReport {
+    /// The metrics with enough history to be checked.
+    pub checked: Vec<Metric>,
+    /// The metrics recorded on fewer than `MIN_SAMPLES` days.
+    pub insufficient: Vec<Metric>,
+    /// The anomalies ordered by day, the fresh observation last.
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl AnomalyReport {
+    /// Checks every metric of the history entries and of the `current` observation against
+    /// the statistics of the history. A day of the history is compared with the other days,
+    /// and the current observation with all of them. Metrics that were never reported are
+    /// ignored.
+    pub fn new(entries: &[HistoryEntry], current: &WeatherInfo) -> Self {
+        let mut report = Self::default();
+
+        for metric in Metric::ALL {
+            let days = daily_values(entries, metric);
+            let values = days.iter().map(|(_, value)| *value).collect::<Vec<_>>();
+            let current = metric.value(current);
+
+            let Some(statistics) = Statistics::of(&values) else {
+                if !days.is_empty() || current.is_some() {
+                    report.insufficient.push(metric);
+                }
+                continue;
+            };
+            report.checked.push(metric);
+
+            let samples = days
+                .into_iter()
+                .enumerate()
+                .map(|(i, (day, value))| (Some(day), value, Statistics::leave_out(&values, i)))
+                .chain(current.map(|value| (None, value, statistics)));
+            for (day, value, statistics) in samples {
+                if let Some(deviation) = statistics.deviation(value)
+                    && deviation.abs() > THRESHOLD_SIGMA
+                {
+                    report.anomalies.push(Anomaly {
+                        day,
+                        metric,
+                        value,
+                        mean: statistics.mean,
+                        deviation,
+                    });
+                }
+            }
+        }
+
+        // Stable, so the metrics of a day keep their order
+        report
+            .anomalies
+            .sort_by_key(|anomaly| (anomaly.day.is_none(), anomaly.day));
+        report
+    }
+
+    /// Renders the report for `address` over the last `days` days, with the values in the
+    /// units and locale of `opts`.
+    pub fn render(&self, address: &str, days: u32, opts: &DisplayOptions) -> String {
+        if self.checked.is_empty() {
+            return format!(
+                "Not enough history for '{address}': anomalies need observations on at least \
+                 {MIN_SAMPLES} of the last {days} days."
+            );
+        }
+
+        let mut lines = self
+            .insufficient
+            .iter()
+            .map(|metric| format!("{}: not enough history.", metric.name()))
+            .collect::<Vec<_>>();
+
+        if self.anomalies.is_empty() {
+            lines.push(format!(
+                "No anomalies for '{address}' in the last {days} days."
+            ));
+        } else {
+            let mut table = Table::new(&["Day", "Metric", "Value", "Mean", "Deviation"]);
+            for anomaly in &self.anomalies {
+                table.row([
+                    anomaly
+                        .day
+                        .map_or_else(|| "now".to_string(), |day| day.to_string()),
+                    anomaly.metric.name().to_string(),
+                    anomaly.metric.format(anomaly.value, opts),
+                    anomaly.metric.format(anomaly.mean, opts),
+                    format_deviation(anomaly.deviation),
+                ]);
+            }
+            lines.push(format!(
+                "Anomalies for '{address}' in the last {days} days (beyond {THRESHOLD_SIGMA}σ):"
+            ));
+            lines.push(table.render());
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Formats a deviation with its sign, e.g. "+2.5σ", or "+∞σ" from a sample that does not vary.
+fn format_deviation(deviation: f64) -> String {
+    if deviation.is_infinite() {
+        format!("{}∞σ", if deviation > 0.0 { '+' } else { '-' })
+    } else {
+        format!("{deviation:+.1}σ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixtures;
+    use ::chrono::{TimeZone, Utc};
+    use ::weather_providers::Unit;
+
+    fn weather(temperature: f32, wind_speed_mph: Option<f32>) -> WeatherInfo {
+        WeatherInfo {
+            temperature,
+            wind_speed_mph,
+            ..fixtures::weather()
+        }
+    }
+
+    /// An entry fetched at noon (UTC) of the given day of January 2024.
+    fn entry(day: u32, temperature: f32, wind_speed_mph: Option<f32>) -> HistoryEntry {
+        HistoryEntry {
+            location_key: "london".to_string(),
+            provider: "mock".to_string(),
+            fetched_at: Utc.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap(),
+            weather: weather(temperature, wind_speed_mph),
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    #[test]
+    fn test_statistics() {
+        // Mean 5, squared deviations 9+1+1+1+0+0+4+16 = 32, variance 32/8 = 4
+        let statistics = Statistics::of(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+        assert_eq!(
+            statistics,
+            Statistics {
+                mean: 5.0,
+                std_dev: 2.0
+            }
+        );
+        assert_eq!(statistics.deviation(9.0), Some(2.0));
+        assert_eq!(statistics.deviation(0.0), Some(-2.5));
+
+        // Mean 12, squared deviations 4+4+0+1+1 = 10, variance 2
+        let statistics = Statistics::of(&[10.0, 14.0, 12.0, 11.0, 13.0]).unwrap();
+        assert_eq!(statistics.mean, 12.0);
+        assert!((statistics.std_dev - 2f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_statistics_of_small_or_flat_samples() {
+        assert_eq!(Statistics::of(&[]), None);
+        assert_eq!(Statistics::of(&[1.0, 2.0, 3.0, 4.0]), None);
+
+        let flat = Statistics::of(&[3.0; 5]).unwrap();
+        assert_eq!(flat.std_dev, 0.0);
+        assert_eq!(flat.deviation(3.0), None);
+        assert_eq!(flat.deviation(10.0), Some(f64::INFINITY));
+        assert_eq!(flat.deviation(-1.0), Some(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn test_daily_values() {
+        let mut evening = entry(2, 60.0, None);
+        evening.fetched_at += chrono::Duration::minutes(30);
+        let entries = [
+            entry(1, 50.0, Some(10.0)),
+            entry(2, 40.0, None),
+            evening,
+            entry(3, f32::NAN, Some(4.0)),
+        ];
+
+        assert_eq!(
+            daily_values(&entries, Metric::Temperature),
+            [(date(1), 50.0), (date(2), 50.0)]
+        );
+        assert_eq!(
+            daily_values(&entries, Metric::WindSpeed),
+            [(date(1), 10.0), (date(3), 4.0)]
+        );
+        assert!(daily_values(&entries, Metric::Pressure).is_empty());
+    }
+
+    #[test]
+    fn test_statistics_leave_out() {
+        let values = [50.0, 51.0, 49.0, 50.0, 80.0];
+
+        // Mean 50, squared deviations 0+1+1+0 = 2, variance 0.5
+        let statistics = Statistics::leave_out(&values, 4);
+        assert_eq!(statistics.mean, 50.0);
+        assert!((statistics.std_dev - 0.5f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_report_flags_outliers() {
+        // Without 75, mean 45, squared deviations 6 × 1, variance 0.75
+        let temperatures = [44.0, 46.0, 45.0, 44.0, 46.0, 45.0, 44.0, 46.0, 75.0];
+        let entries = temperatures
+            .iter()
+            .zip(1..)
+            .map(|(&t, day)| entry(day, t, (day <= 2).then_some(5.0)))
+            .collect::<Vec<_>>();
+
+        let report = AnomalyReport::new(&entries, &weather(20.0, Some(30.0)));
+
+        assert_eq!(report.checked, [Metric::Temperature, Metric::Humidity]);
+        // Only 2 days of wind, and pressure was never reported
+        assert_eq!(report.insufficient, [Metric::WindSpeed]);
+        let flagged = report
+            .anomalies
+            .iter()
+            .map(|a| (a.day, a.metric, a.value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flagged,
+            [
+                (Some(date(9)), Metric::Temperature, 75.0),
+                (None, Metric::Temperature, 20.0),
+            ]
+        );
+        assert_eq!(report.anomalies[0].mean, 45.0);
+        assert!((report.anomalies[0].deviation - 30.0 / 0.75f64.sqrt()).abs() < 1e-9);
+        // The current observation is compared with every day
+        let all = Statistics::of(&temperatures.map(f64::from)).unwrap();
+        assert_eq!(report.anomalies[1].mean, all.mean);
+        assert_eq!(Some(report.anomalies[1].deviation), all.deviation(20.0));
+    }
+
+    #[test]
+    fn test_report_flags_outliers_of_few_days() {
+        // 80 lies 2.0σ from the mean of all 5 days, but 42σ from the other days
+        let entries = [50.0, 51.0, 49.0, 50.0, 80.0]
+            .iter()
+            .zip(1..)
+            .map(|(&t, day)| entry(day, t, None))
+            .collect::<Vec<_>>();
+
+        let report = AnomalyReport::new(&entries, &weather(52.0, None));
+
+        let flagged = report
+            .anomalies
+            .iter()
+            .map(|a| (a.day, a.metric, a.value))
+            .collect::<Vec<_>>();
+        assert_eq!(flagged, [(Some(date(5)), Metric::Temperature, 80.0)]);
+    }
+
+    #[test]
+    fn test_report_flags_outliers_of_constant_days() {
+        // The other days of 90 do not vary at all
+        let entries = [50.0, 50.0, 50.0, 50.0, 90.0]
+            .iter()
+            .zip(1..)
+            .map(|(&t, day)| entry(day, t, None))
+            .collect::<Vec<_>>();
+
+        let report = AnomalyReport::new(&entries, &weather(58.0, None));
+
+        let flagged = report
+            .anomalies
+            .iter()
+            .map(|a| (a.day, a.metric, a.value, a.deviation))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flagged,
+            [(Some(date(5)), Metric::Temperature, 90.0, f64::INFINITY)]
+        );
+        assert_eq!(format_deviation(f64::INFINITY), "+∞σ");
+        assert_eq!(format_deviation(f64::NEG_INFINITY), "-∞σ");
+    }
+
+    #[test]
+    fn test_missing_humidity_is_unknown() {
+        let mut dry = weather(50.0, None);
+        assert_eq!(Metric::Humidity.value(&dry), Some(80.0));
+
+        dry.humidity = None;
+        assert_eq!(Metric::Humidity.value(&dry), None);
+
+        let entries = (1..=5)
+            .map(|day| {
+                let mut entry = entry(day, 50.0, None);
+                entry.weather.humidity = None;
+                entry
+            })
+            .collect::<Vec<_>>();
+        let report = AnomalyReport::new(&entries, &dry);
+        assert_eq!(report.checked, [Metric::Temperature]);
+        assert!(report.insufficient.is_empty());
+    }
+
+    #[test]
+    fn test_render() {
+        let opts = DisplayOptions {
+            temperature_unit: Unit::Celsius,
+            ..DisplayOptions::default()
+        };
+        let report = AnomalyReport {
+            checked: vec![Metric::Temperature],
+            insufficient: vec![Metric::WindSpeed],
+            anomalies: vec![Anomaly {
+                day: None,
+                metric: Metric::Temperature,
+                value: 95.0,
+                mean: 50.0,
+                deviation: 2.54,
+            }],
+        };
+
+        assert_eq!(
+            report.render("London", 30, &opts),
+            "Wind speed: not enough history.\n\
+             Anomalies for 'London' in the last 30 days (beyond 2σ):\n\
+             Day | Metric      | Value  | Mean   | Deviation\n\
+             ----+-------------+--------+--------+----------\n\
+             now | Temperature | 35.0°C | 10.0°C | +2.5σ"
+        );
+
+        assert!(
+            AnomalyReport::default()
+                .render("London", 30, &opts)
+                .starts_with("Not enough history for 'London'")
+        );
+    }
+}
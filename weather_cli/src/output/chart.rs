@@ -0,0 +1,218 @@
+//! # Terminal Charts
+//!
+//! Renders multi-day weather as compact Unicode charts: a per-day temperature bar chart and a
+//! precipitation sparkline. The renderer is pure; detecting the terminal width is up to the caller.
+
+use ::weather_providers::WeatherInfo;
+
+/// The terminal width assumed when it cannot be detected.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Partial blocks for one to seven eighths of a cell.
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+/// Sparkline levels from lowest to highest.
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+const TEMPERATURE_TITLE: &str = "Temperature";
+const PRECIPITATION_LABEL: &str = "Precip ";
+/// The width of a day label, e.g. "01-15 ".
+const DAY_LABEL_WIDTH: usize = 6;
+
+/// Renders the temperature bar chart and the precipitation sparkline for `days`.
+///
+/// Temperature bars are scaled between the lowest and the highest temperature, so negative
+/// temperatures are handled like any other value; the lowest day still gets a visible sliver.
+/// The precipitation sparkline is scaled between zero and the highest amount and is omitted
+/// if no day reports precipitation.
+///
+/// # Arguments
+///
+/// * `days` - The weather of consecutive days, in order.
+/// * `width` - The maximum width of a line, in terminal columns.
+///
+/// # Returns
+///
+/// The chart lines, or no lines if `days` is empty.
+pub fn render_charts(days: &[WeatherInfo], width: usize) -> Vec<String> {
+    if days.is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![TEMPERATURE_TITLE.to_string()];
+    lines.extend(temperature_bars(days, width));
+
+    if let Some(sparkline) = precipitation_sparkline(days, width) {
+        lines.push(sparkline);
+    }
+
+    lines
+}
+
+fn temperature_bars(days: &[WeatherInfo], width: usize) -> Vec<String> {
+    let (min, max) = days
+        .iter()
+        .map(|d| d.temperature)
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), t| {
+            (min.min(t), max.max(t))
+        });
+    let range = max - min;
+    let bar_cells = width.saturating_sub(DAY_LABEL_WIDTH).max(1);
+
+    days.iter()
+        .map(|day| {
+            // All-equal temperatures have no range to scale against, so every bar is full
+            let fraction = if range > 0.0 {
+                (day.temperature - min) / range
+            } else {
+                1.0
+            };
+            let eighths = ((fraction * (bar_cells * 8) as f32).round() as usize).max(1);
+
+            format!("{}{}", day_label(&day.date), bar(eighths))
+        })
+        .collect()
+}
+
+fn precipitation_sparkline(days: &[WeatherInfo], width: usize) -> Option<String> {
+    let max = days
+        .iter()
+        .filter_map(|d| d.precipitation_mm)
+        .reduce(f32::max)?;
+
+    let sparkline = days
+        .iter()
+        .take(width.saturating_sub(PRECIPITATION_LABEL.len()).max(1))
+        .map(|day| match day.precipitation_mm {
+            Some(mm) if max > 0.0 => {
+                let level = (mm.max(0.0) / max * (SPARK_LEVELS.len() - 1) as f32).round();
+                SPARK_LEVELS[level as usize]
+            }
+            Some(_) => SPARK_LEVELS[0],
+            None => ' ',
+        })
+        .collect::<String>();
+
+    Some(format!("{PRECIPITATION_LABEL}{sparkline}"))
+}
+
+/// Returns a bar of the given length in eighths of a cell.
+fn bar(eighths: usize) -> String {
+    let mut bar = "█".repeat(eighths / 8);
+    if !eighths.is_multiple_of(8) {
+        bar.push(PARTIAL_BLOCKS[eighths % 8 - 1]);
+    }
+    bar
+}
+
+/// Shortens a `YYYY-MM-DD` date to `MM-DD` and pads it to the label width.
+fn day_label(date: &str) -> String {
+    let date = date.get(5..).filter(|_| date.len() == 10).unwrap_or(date);
+    format!("{date:<width$}", width = DAY_LABEL_WIDTH - 1) + " "
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixtures;
+
+    fn day(date: &str, temperature: f32, precipitation_mm: Option<f32>) -> WeatherInfo {
+        WeatherInfo {
+            date: date.to_string(),
+            temperature,
+            precipitation_mm,
+            ..fixtures::weather()
+        }
+    }
+
+    fn bar_len(line: &str) -> usize {
+        line.chars().skip(DAY_LABEL_WIDTH).count()
+    }
+
+    #[test]
+    fn test_scaling() {
+        let days = [
+            day("2024-01-15", 40.0, None),
+            day("2024-01-16", 50.0, None),
+            day("2024-01-17", 60.0, None),
+        ];
+
+        let lines = render_charts(&days, 26);
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "Temperature");
+        assert_eq!(lines[1], "01-15 ▏");
+        assert_eq!(lines[2], format!("01-16 {}", "█".repeat(10)));
+        assert_eq!(lines[3], format!("01-17 {}", "█".repeat(20)));
+        assert!(lines.iter().all(|l| l.chars().count() <= 26));
+    }
+
+    #[test]
+    fn test_negative_temperatures() {
+        let days = [
+            day("2024-01-15", -10.0, None),
+            day("2024-01-16", -5.0, None),
+            day("2024-01-17", 10.0, None),
+        ];
+
+        let lines = render_charts(&days, 46);
+
+        assert_eq!(bar_len(&lines[1]), 1);
+        assert_eq!(lines[2], format!("01-16 {}", "█".repeat(10)));
+        assert_eq!(bar_len(&lines[3]), 40);
+    }
+
+    #[test]
+    fn test_single_day() {
+        let lines = render_charts(&[day("2024-01-15", 20.0, Some(1.5))], 16);
+
+        assert_eq!(
+            lines,
+            [
+                "Temperature".to_string(),
+                format!("01-15 {}", "█".repeat(10)),
+                "Precip █".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_all_equal_values() {
+        let days = [
+            day("2024-01-15", 20.0, Some(0.0)),
+            day("2024-01-16", 20.0, Some(0.0)),
+        ];
+
+        let lines = render_charts(&days, 16);
+
+        assert_eq!(bar_len(&lines[1]), 10);
+        assert_eq!(bar_len(&lines[2]), 10);
+        assert_eq!(lines[3], "Precip ▁▁");
+    }
+
+    #[test]
+    fn test_precipitation_sparkline() {
+        let days = [
+            day("2024-01-15", 20.0, Some(0.0)),
+            day("2024-01-16", 20.0, Some(7.0)),
+            day("2024-01-17", 20.0, None),
+            day("2024-01-18", 20.0, Some(3.5)),
+        ];
+
+        let lines = render_charts(&days, 80);
+        assert_eq!(lines.last().unwrap(), "Precip ▁█ ▅");
+
+        let dry = render_charts(&[day("2024-01-15", 20.0, None)], 80);
+        assert_eq!(dry.len(), 2);
+    }
+
+    #[test]
+    fn test_partial_blocks_and_tiny_width() {
+        assert_eq!(bar(1), "▏");
+        assert_eq!(bar(12), "█▌");
+        assert_eq!(bar(16), "██");
+
+        let lines = render_charts(&[day("2024-01-15", 20.0, None)], 0);
+        assert_eq!(lines[1], "01-15 █");
+        assert!(render_charts(&[], 80).is_empty());
+    }
+}
@@ -0,0 +1,349 @@
+//! # Morning Digest
+//!
+//! Renders the `weather digest` briefing: one compact block per location with the current
+//! temperature, today's range and chance of precipitation, tomorrow's forecast and a single
+//! suggestion, as plain text or as Markdown for pasting into chat. The renderer is pure;
+//! fetching is up to the caller.
+
+use crate::models::config::DigestOrder;
+use ::std::fmt::Write;
+use ::weather_providers::{DisplayOptions, FREEZING_POINT_F, PrecipType, WeatherInfo};
+
+/// From this chance of precipitation (in percent), it is likely to rain.
+const LIKELY_CHANCE: u8 = 50;
+/// From this temperature (°F, 30 °C), the day is hot.
+const HOT_F: f32 = 86.0;
+/// From this wind speed (mph), the day is windy.
+const WINDY_MPH: f32 = 25.0;
+
+/// The fetched weather of one digest location.
+#[derive(Debug, Clone)]
+pub struct Briefing {
+    /// The alias of the location.
+    pub alias: String,
+    /// Today's weather, or the reason it could not be fetched.
+    pub today: Result<WeatherInfo, String>,
+    /// Tomorrow's forecast, if it could be fetched.
+    pub tomorrow: Option<WeatherInfo>,
+}
+
+/// Sorts the briefings in the configured order.
+///
+/// Locations whose weather could not be fetched come last when sorting by temperature.
+pub fn sort(briefings: &mut [Briefing], order: DigestOrder) {
+    let temperature = |b: &Briefing| b.today.as_ref().ok().map(|t| t.temperature);
+
+    match order {
+        DigestOrder::Listed => {}
+        DigestOrder::Alias => briefings.sort_by(|a, b| a.alias.cmp(&b.alias)),
+        DigestOrder::Warmest | DigestOrder::Coldest => {
+            briefings.sort_by(|a, b| match (temperature(a), temperature(b)) {
+                (Some(a), Some(b)) if order == DigestOrder::Warmest => b.total_cmp(&a),
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            })
+        }
+    }
+}
+
+/// Renders the briefings as plain text blocks separated by blank lines.
+pub fn render_text(briefings: &[Briefing], opts: &DisplayOptions) -> String {
+    let blocks = briefings
+        .iter()
+        .map(|briefing| {
+            let mut block = title(briefing);
+            for (label, text) in lines(briefing, opts) {
+                let _ = write!(block, "\n  {label:<11} {text}");
+            }
+            block
+        })
+        .collect::<Vec<_>>();
+
+    blocks.join("\n\n") + "\n"
+}
+
+/// Renders the briefings as Markdown, with a heading and a bullet list per location.
+pub fn render_markdown(briefings: &[Briefing], opts: &DisplayOptions) -> String {
+    let blocks = briefings
+        .iter()
+        .map(|briefing| {
+            let mut block = format!("### {}", title(briefing));
+            block.push('\n');
+            for (label, text) in lines(briefing, opts) {
+                let _ = write!(block, "\n- **{label}:** {text}");
+            }
+            block
+        })
+        .collect::<Vec<_>>();
+
+    blocks.join("\n\n") + "\n"
+}
+
+/// Returns the title of a block, e.g. "home: London, UK (2024-01-15)". A country that was not
+/// reported, as by sensor providers, is left out.
+fn title(briefing: &Briefing) -> String {
+    match &briefing.today {
+        Ok(today) if today.country.is_empty() => {
+            format!("{}: {} ({})", briefing.alias, today.city, today.date)
+        }
+        Ok(today) => format!(
+            "{}: {}, {} ({})",
+            briefing.alias, today.city, today.country, today.date
+        ),
+        Err(_) => briefing.alias.clone(),
+    }
+}
+
+/// Returns the labeled lines of a block.
+fn lines(briefing: &Briefing, opts: &DisplayOptions) -> Vec<(&'static str, String)> {
+    let today = match &briefing.today {
+        Ok(today) => today,
+        Err(e) => return vec![("Error", e.clone())],
+    };
+
+    let mut range = Vec::new();
+    if let Some(periods) = &today.periods {
+        for (label, value) in [("Low", periods.low), ("High", periods.high)] {
+            if let Some(value) = value {
+                range.push(format!("{label} {}", temperature(value, opts)));
+            }
+        }
+    }
+    range.extend(today.precip_probability_pct().map(chance));
+    if range.is_empty() {
+        range.push("No forecast for the rest of the day".to_string());
+    }
+
+    let tomorrow = match &briefing.tomorrow {
+        Some(tomorrow) => conditions(tomorrow, opts)
+            .into_iter()
+            .chain(tomorrow.precip_probability_pct().map(chance))
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "Not available".to_string(),
+    };
+
+    vec![
+        ("Now", conditions(today, opts).join(", ")),
+        ("Today", range.join(", ")),
+        ("Tomorrow", tomorrow),
+        (
+            "Suggestion",
+            suggestion(today, briefing.tomorrow.as_ref()).to_string(),
+        ),
+    ]
+}
+
+/// Returns the temperature and the description, if any.
+fn conditions(info: &WeatherInfo, opts: &DisplayOptions) -> Vec<String> {
+    let mut parts = vec![temperature(info.temperature, opts)];
+    parts.extend(info.description.clone());
+    parts
+}
+
+fn temperature(fahrenheit: f32, opts: &DisplayOptions) -> String {
+    format!(
+        "{}{}",
+        opts.format_decimal(opts.temperature_unit.from_fahrenheit(fahrenheit)),
+        opts.temperature_unit.symbol()
+    )
+}
+
+fn chance(percent: u8) -> String {
+    format!("{percent}% chance of precipitation")
+}
+
+/// Picks the single most useful suggestion for the day, checking today's precipitation,
+/// temperature and wind before tomorrow's precipitation.
+fn suggestion(today: &WeatherInfo, tomorrow: Option<&WeatherInfo>) -> &'static str {
+    let likely = |info: &WeatherInfo| {
+        info.precip_probability_pct()
+            .is_some_and(|c| c >= LIKELY_CHANCE)
+    };
+    let high = today
+        .periods
+        .and_then(|p| p.high)
+        .unwrap_or(today.temperature);
+
+    match today.precip_type {
+        Some(
+            PrecipType::Snow | PrecipType::Sleet | PrecipType::FreezingRain | PrecipType::Mixed,
+        ) => "Slippery roads are likely: allow extra travel time.",
+        Some(PrecipType::Rain) => "Take an umbrella.",
+        _ if likely(today) => "Take an umbrella.",
+        _ if high >= HOT_F => "Hot day ahead: stay hydrated.",
+        _ if today.temperature <= FREEZING_POINT_F => "Freezing: wrap up warm.",
+        _ if today.wind_speed_mph.is_some_and(|w| w >= WINDY_MPH) => "Windy: hold on to your hat.",
+        _ if tomorrow.is_some_and(likely) => "Enjoy the dry day; precipitation is likely tomorrow.",
+        _ => "No weather worries today.",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixtures;
+    use ::weather_providers::{DayParts, Unit};
+
+    fn weather(city: &str, date: &str, temperature: f32) -> WeatherInfo {
+        WeatherInfo {
+            city: city.to_string(),
+            date: date.to_string(),
+            temperature,
+            description: Some("light rain".to_string()),
+            precip_probability: Some(0.7),
+            periods: Some(DayParts {
+                morning: 46.0,
+                afternoon: 52.0,
+                evening: 49.0,
+                night: 44.0,
+                low: Some(41.0),
+                high: Some(53.6),
+            }),
+            ..fixtures::weather()
+        }
+    }
+
+    fn briefings() -> Vec<Briefing> {
+        vec![
+            Briefing {
+                alias: "home".to_string(),
+                today: Ok(weather("London", "2024-01-15", 50.0)),
+                tomorrow: Some(WeatherInfo {
+                    description: None,
+                    precip_probability: None,
+                    ..weather("London", "2024-01-16", 44.6)
+                }),
+            },
+            Briefing {
+                alias: "cabin".to_string(),
+                today: Err("Request timed out after 30 seconds.".to_string()),
+                tomorrow: None,
+            },
+            Briefing {
+                alias: "beach".to_string(),
+                today: Ok(WeatherInfo {
+                    description: None,
+                    precip_probability: None,
+                    periods: None,
+                    ..weather("Brighton", "2024-01-15", 59.0)
+                }),
+                tomorrow: None,
+            },
+        ]
+    }
+
+    fn celsius() -> DisplayOptions {
+        DisplayOptions {
+            temperature_unit: Unit::Celsius,
+            ..DisplayOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_render_text() {
+        assert_eq!(
+            render_text(&briefings(), &celsius()),
+            "\
+home: London, UK (2024-01-15)
+  Now         10.0°C, light rain
+  Today       Low 5.0°C, High 12.0°C, 70% chance of precipitation
+  Tomorrow    7.0°C
+  Suggestion  Take an umbrella.
+
+cabin
+  Error       Request timed out after 30 seconds.
+
+beach: Brighton, UK (2024-01-15)
+  Now         15.0°C
+  Today       No forecast for the rest of the day
+  Tomorrow    Not available
+  Suggestion  No weather worries today.
+"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        assert_eq!(
+            render_markdown(&briefings()[..1], &DisplayOptions::default()),
+            "\
+### home: London, UK (2024-01-15)
+
+- **Now:** 50.0°F, light rain
+- **Today:** Low 41.0°F, High 53.6°F, 70% chance of precipitation
+- **Tomorrow:** 44.6°F
+- **Suggestion:** Take an umbrella.
+"
+        );
+    }
+
+    #[test]
+    fn test_title_without_country() {
+        let briefing = Briefing {
+            alias: "air".to_string(),
+            today: Ok(WeatherInfo {
+                country: String::new(),
+                ..weather("Sensor 1234", "2024-01-15", 50.0)
+            }),
+            tomorrow: None,
+        };
+
+        assert_eq!(title(&briefing), "air: Sensor 1234 (2024-01-15)");
+    }
+
+    #[test]
+    fn test_sort() {
+        let aliases = |order| {
+            let mut briefings = briefings();
+            sort(&mut briefings, order);
+            briefings.into_iter().map(|b| b.alias).collect::<Vec<_>>()
+        };
+
+        assert_eq!(aliases(DigestOrder::Listed), ["home", "cabin", "beach"]);
+        assert_eq!(aliases(DigestOrder::Alias), ["beach", "cabin", "home"]);
+        // Failed locations come last either way
+        assert_eq!(aliases(DigestOrder::Warmest), ["beach", "home", "cabin"]);
+        assert_eq!(aliases(DigestOrder::Coldest), ["home", "beach", "cabin"]);
+    }
+
+    #[test]
+    fn test_suggestion() {
+        let dry = WeatherInfo {
+            precip_probability: Some(0.0),
+            periods: None,
+            ..weather("London", "2024-01-15", 60.0)
+        };
+        let with = |update: fn(&mut WeatherInfo)| {
+            let mut info = dry.clone();
+            update(&mut info);
+            info
+        };
+
+        assert_eq!(suggestion(&dry, None), "No weather worries today.");
+        assert_eq!(
+            suggestion(&with(|i| i.precip_type = Some(PrecipType::Sleet)), None),
+            "Slippery roads are likely: allow extra travel time."
+        );
+        assert_eq!(
+            suggestion(&with(|i| i.precip_probability = Some(0.5)), None),
+            "Take an umbrella."
+        );
+        assert_eq!(
+            suggestion(&with(|i| i.temperature = 90.0), None),
+            "Hot day ahead: stay hydrated."
+        );
+        assert_eq!(
+            suggestion(&with(|i| i.temperature = 28.0), None),
+            "Freezing: wrap up warm."
+        );
+        assert_eq!(
+            suggestion(&with(|i| i.wind_speed_mph = Some(30.0)), None),
+            "Windy: hold on to your hat."
+        );
+        assert_eq!(
+            suggestion(&dry, Some(&weather("London", "2024-01-16", 50.0))),
+            "Enjoy the dry day; precipitation is likely tomorrow."
+        );
+    }
+}
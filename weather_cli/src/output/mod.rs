@@ -0,0 +1,18 @@
+//! # Output Renderers
+//!
+//! This module contains renderers that turn fetched weather data into the
+//! machine-readable formats selectable via `--output`, into terminal charts, into the
+//! morning briefing of `weather digest`, and into the severe weather alerts shown by `get`.
+//! The history of a location is checked for unusual weather by `anomalies`.
+//! The results of several locations are ordered by `ranking`, and listings are aligned by
+//! `table`.
+//! Streaming commands write line-oriented records through `ndjson::RecordWriter`.
+
+pub mod alerts;
+pub mod anomalies;
+pub mod chart;
+pub mod digest;
+pub mod ndjson;
+pub mod ranking;
+pub mod table;
+pub mod trend;
@@ -0,0 +1,109 @@
+//! # NDJSON Output
+//!
+//! Writes records as newline-delimited JSON: one compact JSON object per line. Every record is
+//! flushed as soon as it is written, so a process reading a pipe receives it right away instead
+//! of when the buffer fills up or the command exits.
+
+use crate::common::*;
+use ::serde::Serialize;
+use ::std::io::{self, Stdout, Write};
+
+/// The record written by `RecordWriter::heartbeat`.
+pub const HEARTBEAT: &str = "{}";
+
+/// Writes line-oriented JSON records, flushing after each one.
+pub struct RecordWriter<W: Write> {
+    out: W,
+}
+
+impl RecordWriter<Stdout> {
+    /// Creates a writer to the standard output.
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    /// Writes the record as one line and flushes it.
+    pub fn write<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.out.write_all(&line)?;
+        self.flush()
+    }
+
+    /// Writes an empty record, telling the consumer that the producer is still alive.
+    pub fn heartbeat(&mut self) -> Result<()> {
+        writeln!(self.out, "{HEARTBEAT}")?;
+        self.flush()
+    }
+
+    /// Flushes the records written so far.
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.out.flush()?)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A writer that records what had been flushed at each flush.
+    #[derive(Default)]
+    struct FlushLog {
+        buffer: Vec<u8>,
+        flushed: Vec<String>,
+    }
+
+    impl Write for FlushLog {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.buffer.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushed
+                .push(String::from_utf8(self.buffer.clone()).unwrap());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_records_are_compact_lines() {
+        let mut writer = RecordWriter::new(Vec::new());
+        writer
+            .write(&serde_json::json!({ "city": "Kyiv", "nested": { "a": [1, 2] } }))
+            .unwrap();
+        writer.heartbeat().unwrap();
+        writer.write("multi\nline").unwrap();
+
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            [
+                r#"{"city":"Kyiv","nested":{"a":[1,2]}}"#,
+                HEARTBEAT,
+                r#""multi\nline""#
+            ]
+        );
+        assert!(output.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_every_record_is_flushed() {
+        let mut writer = RecordWriter::new(FlushLog::default());
+        writer.write(&1).unwrap();
+        writer.heartbeat().unwrap();
+
+        assert_eq!(writer.into_inner().flushed, ["1\n", "1\n{}\n"]);
+    }
+}
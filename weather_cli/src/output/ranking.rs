@@ -0,0 +1,204 @@
+//! # Ranking
+//!
+//! Orders the weather of several locations by a metric (`--sort-by`) and finds the locations
+//! with its highest and lowest value (`--highlight-extremes`).
+
+use crate::{common::Result, models::args::SortMetric};
+use ::std::cmp::Ordering;
+use ::weather_providers::WeatherInfo;
+
+const RED: &str = "\x1b[31m";
+const BLUE: &str = "\x1b[34m";
+const RESET: &str = "\x1b[0m";
+
+impl SortMetric {
+    /// Returns the value of the metric, or `None` if the provider did not report it.
+    ///
+    /// Temperatures are in °F, wind speeds in mph and precipitation in mm. Without weather
+    /// observations (e.g., daylight times only), the temperature and humidity are unknown.
+    pub fn value(&self, info: &WeatherInfo) -> Option<f32> {
+        let has_weather = !info.temperature.is_nan();
+
+        match self {
+            SortMetric::Temp => has_weather.then_some(info.temperature),
+            SortMetric::Humidity => info.humidity.filter(|_| has_weather).map(f32::from),
+            SortMetric::Wind => info.wind_speed_mph,
+            SortMetric::Precip => info.precipitation_mm,
+        }
+    }
+}
+
+/// Sorts the results by `metric`, highest first.
+///
+/// Results without the metric, including failed requests, come last. Ties are ordered by
+/// address, so the order does not depend on which request completed first.
+pub fn sort_by_metric<A: AsRef<str>>(results: &mut [(A, Result<WeatherInfo>)], metric: SortMetric) {
+    let value = |result: &Result<WeatherInfo>| result.as_ref().ok().and_then(|i| metric.value(i));
+
+    results.sort_by(|(a, a_result), (b, b_result)| {
+        match (value(a_result), value(b_result)) {
+            (Some(a), Some(b)) => b.total_cmp(&a),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+        .then_with(|| a.as_ref().cmp(b.as_ref()))
+    });
+}
+
+/// The positions of the results with the highest and lowest value of a metric.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Extremes {
+    pub max: Option<usize>,
+    pub min: Option<usize>,
+}
+
+impl Extremes {
+    /// Finds the first result with the highest and the first with the lowest value of
+    /// `metric`. Nothing stands out if fewer than two results have distinct values.
+    pub fn find<A>(results: &[(A, Result<WeatherInfo>)], metric: SortMetric) -> Self {
+        let values = results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, result))| Some((i, metric.value(result.as_ref().ok()?)?)));
+
+        let mut max: Option<(usize, f32)> = None;
+        let mut min: Option<(usize, f32)> = None;
+        for (i, value) in values {
+            if max.is_none_or(|(_, max)| value > max) {
+                max = Some((i, value));
+            }
+            if min.is_none_or(|(_, min)| value < min) {
+                min = Some((i, value));
+            }
+        }
+
+        match (max, min) {
+            (Some((max, high)), Some((min, low))) if high > low => Self {
+                max: Some(max),
+                min: Some(min),
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// Colors `line` with ANSI escape codes if it is the result at `index`: red for the
+    /// highest value and blue for the lowest.
+    pub fn highlight(&self, index: usize, line: String) -> String {
+        if self.max == Some(index) {
+            format!("{RED}{line}{RESET}")
+        } else if self.min == Some(index) {
+            format!("{BLUE}{line}{RESET}")
+        } else {
+            line
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Error;
+    use crate::common::fixtures;
+
+    fn weather(temperature: f32, wind_speed_mph: Option<f32>) -> Result<WeatherInfo> {
+        Ok(WeatherInfo {
+            temperature,
+            humidity: Some(50),
+            wind_speed_mph,
+            ..fixtures::weather()
+        })
+    }
+
+    fn addresses(results: &[(&str, Result<WeatherInfo>)]) -> Vec<String> {
+        results.iter().map(|(a, _)| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_sort_by_metric() {
+        let mut results = vec![
+            ("Oslo", weather(28.0, Some(12.0))),
+            ("Nowhere", Err(Error::from("Location not found"))),
+            ("Madrid", weather(61.0, None)),
+            ("Daylight", weather(f32::NAN, None)),
+            ("Lisbon", weather(61.0, Some(3.5))),
+            ("Berlin", weather(35.0, Some(8.0))),
+        ];
+
+        sort_by_metric(&mut results, SortMetric::Temp);
+        // Ties by address, unknown values and failures last
+        assert_eq!(
+            addresses(&results),
+            ["Lisbon", "Madrid", "Berlin", "Oslo", "Daylight", "Nowhere"]
+        );
+
+        sort_by_metric(&mut results, SortMetric::Wind);
+        assert_eq!(
+            addresses(&results),
+            ["Oslo", "Berlin", "Lisbon", "Daylight", "Madrid", "Nowhere"]
+        );
+
+        // Every value is unknown
+        sort_by_metric(&mut results, SortMetric::Precip);
+        assert_eq!(
+            addresses(&results),
+            ["Berlin", "Daylight", "Lisbon", "Madrid", "Nowhere", "Oslo"]
+        );
+    }
+
+    #[test]
+    fn test_extremes() {
+        let results = vec![
+            ("Lisbon", weather(61.0, None)),
+            ("Madrid", weather(61.0, Some(4.0))),
+            ("Nowhere", Err(Error::from("Location not found"))),
+            ("Oslo", weather(28.0, Some(12.0))),
+        ];
+
+        assert_eq!(
+            Extremes::find(&results, SortMetric::Temp),
+            Extremes {
+                max: Some(0),
+                min: Some(3)
+            }
+        );
+        assert_eq!(
+            Extremes::find(&results, SortMetric::Wind),
+            Extremes {
+                max: Some(3),
+                min: Some(1)
+            }
+        );
+        // Nothing stands out among equal or unknown values
+        assert_eq!(
+            Extremes::find(&results, SortMetric::Humidity),
+            Extremes::default()
+        );
+        assert_eq!(
+            Extremes::find(&results, SortMetric::Precip),
+            Extremes::default()
+        );
+        assert_eq!(
+            Extremes::find(&results[..1], SortMetric::Temp),
+            Extremes::default()
+        );
+    }
+
+    #[test]
+    fn test_highlight() {
+        let extremes = Extremes {
+            max: Some(0),
+            min: Some(2),
+        };
+
+        assert_eq!(
+            extremes.highlight(0, "warm".to_string()),
+            "\x1b[31mwarm\x1b[0m"
+        );
+        assert_eq!(extremes.highlight(1, "mild".to_string()), "mild");
+        assert_eq!(
+            extremes.highlight(2, "cold".to_string()),
+            "\x1b[34mcold\x1b[0m"
+        );
+    }
+}
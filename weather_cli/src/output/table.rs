@@ -0,0 +1,256 @@
+//! # Text Tables
+//!
+//! Renders the aligned tables of the alias and provider listings. Widths are measured in
+//! terminal cells rather than characters, so full-width CJK characters and emoji (two cells)
+//! and combining marks (no cell) keep the columns aligned. Overlong cells are truncated with
+//! an ellipsis, without splitting a grapheme cluster.
+
+use ::unicode_segmentation::UnicodeSegmentation;
+use ::unicode_width::UnicodeWidthStr;
+
+/// Marks a truncated cell.
+const ELLIPSIS: &str = "…";
+
+/// Separates two columns.
+const COLUMN_SEPARATOR: &str = " | ";
+
+/// A text table: a header row, a separator line and the rows, with columns as wide as their
+/// widest cell.
+#[derive(Debug, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    max_widths: Vec<Option<usize>>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Creates a table with the given column headers and no rows.
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            max_widths: vec![None; headers.len()],
+            rows: Vec::new(),
+        }
+    }
+
+    /// Limits the width of a column, in terminal cells; longer cells are truncated with an
+    /// ellipsis.
+    pub fn max_width(mut self, column: usize, width: usize) -> Self {
+        if let Some(max_width) = self.max_widths.get_mut(column) {
+            *max_width = Some(width);
+        }
+        self
+    }
+
+    /// Appends a row. Missing cells are left empty, and cells beyond the headers are ignored.
+    pub fn row<S: Into<String>>(&mut self, cells: impl IntoIterator<Item = S>) {
+        let mut row = cells
+            .into_iter()
+            .take(self.headers.len())
+            .map(Into::into)
+            .collect::<Vec<_>>();
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+    }
+
+    /// Renders the table as lines separated by `\n`, without a trailing newline. The last
+    /// column is not padded, so lines have no trailing spaces.
+    pub fn render(&self) -> String {
+        let widths = self.column_widths();
+        let separator = widths
+            .iter()
+            .enumerate()
+            .map(|(column, width)| {
+                let padding = usize::from(column > 0) + usize::from(column + 1 < widths.len());
+                "-".repeat(width + padding)
+            })
+            .collect::<Vec<_>>()
+            .join("+");
+
+        let mut lines = vec![self.render_row(&self.headers, &widths), separator];
+        lines.extend(self.rows.iter().map(|row| self.render_row(row, &widths)));
+        lines.join("\n")
+    }
+
+    /// Returns the width of each column: its widest cell, up to its maximum width.
+    fn column_widths(&self) -> Vec<usize> {
+        self.headers
+            .iter()
+            .enumerate()
+            .map(|(column, header)| {
+                let widest = self
+                    .rows
+                    .iter()
+                    .map(|row| display_width(&row[column]))
+                    .fold(display_width(header), usize::max);
+                self.max_widths[column].map_or(widest, |max| widest.min(max))
+            })
+            .collect()
+    }
+
+    fn render_row(&self, cells: &[String], widths: &[usize]) -> String {
+        let last = widths.len().saturating_sub(1);
+
+        cells
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, &width))| {
+                let cell = truncate(cell, width);
+                if column == last {
+                    cell
+                } else {
+                    pad(&cell, width)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(COLUMN_SEPARATOR)
+    }
+}
+
+/// Returns the width of `text` in terminal cells.
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Pads `text` with spaces to `width` terminal cells. Wider text is returned unchanged.
+pub fn pad(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{text}{}", " ".repeat(padding))
+}
+
+/// Truncates `text` to at most `width` terminal cells, ending with an ellipsis if it was
+/// shortened. Grapheme clusters are kept whole, so a truncated cell may be a cell narrower
+/// than `width` when a full-width character does not fit.
+pub fn truncate(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+
+    let available = width.saturating_sub(ELLIPSIS.width());
+    let mut truncated = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        if used + grapheme_width > available {
+            break;
+        }
+        truncated.push_str(grapheme);
+        used += grapheme_width;
+    }
+
+    if width >= ELLIPSIS.width() {
+        truncated.push_str(ELLIPSIS);
+    }
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(rows: &[(&str, &str)]) -> String {
+        let mut table = Table::new(&["ALIAS", "ADDRESS"])
+            .max_width(0, 12)
+            .max_width(1, 24);
+        for (alias, address) in rows {
+            table.row([*alias, *address]);
+        }
+        table.render()
+    }
+
+    #[test]
+    fn test_ascii_table() {
+        assert_eq!(
+            aliases(&[("home", "London, UK"), ("work", "Paris, FR")]),
+            "\
+ALIAS | ADDRESS
+------+-----------
+home  | London, UK
+work  | Paris, FR"
+        );
+    }
+
+    #[test]
+    fn test_japanese_city_names() {
+        // Every kanji takes two cells
+        assert_eq!(
+            aliases(&[("東京", "東京都, 日本"), ("osaka", "大阪市, 日本")]),
+            "\
+ALIAS | ADDRESS
+------+-------------
+東京  | 東京都, 日本
+osaka | 大阪市, 日本"
+        );
+    }
+
+    #[test]
+    fn test_emoji_descriptions() {
+        let mut table = Table::new(&["DAY", "SKY", "TEMP"]);
+        table.row(["Mon", "☀\u{fe0f} Sunny", "20°C"]);
+        table.row(["Tue", "🌧\u{fe0f} Rain", "14°C"]);
+        table.row(["Wed", "⛅ Cloudy", "12°C"]);
+
+        // Each emoji takes two cells: "☀" and "🌧" are made wide by their variation selectors
+        assert_eq!(
+            table.render(),
+            "\
+DAY | SKY       | TEMP
+----+-----------+-----
+Mon | ☀\u{fe0f} Sunny  | 20°C
+Tue | 🌧\u{fe0f} Rain   | 14°C
+Wed | ⛅ Cloudy | 12°C"
+        );
+    }
+
+    #[test]
+    fn test_combining_characters() {
+        // "e" followed by a combining acute accent is one cell, like the precomposed "é"
+        assert_eq!(
+            aliases(&[("cafe\u{301}", "Zu\u{308}rich"), ("café", "Zürich")]),
+            "\
+ALIAS | ADDRESS
+------+--------
+cafe\u{301}  | Zu\u{308}rich
+café  | Zürich"
+        );
+    }
+
+    #[test]
+    fn test_long_cells_are_truncated() {
+        assert_eq!(
+            aliases(&[
+                (
+                    "a-very-long-alias-name",
+                    "1600 Pennsylvania Avenue NW, Washington, DC"
+                ),
+                ("北海道の札幌市中央区", "北海道札幌市中央区北1条西2丁目"),
+            ]),
+            "\
+ALIAS        | ADDRESS
+-------------+-------------------------
+a-very-long… | 1600 Pennsylvania Avenu…
+北海道の札…  | 北海道札幌市中央区北1条…"
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("London", 6), "London");
+        assert_eq!(truncate("London", 4), "Lon…");
+        // A full-width character that does not fit is dropped whole
+        assert_eq!(truncate("東京都", 4), "東…");
+        // A combining mark stays with its base character
+        assert_eq!(truncate("Zu\u{308}rich", 3), "Zu\u{308}…");
+        assert_eq!(truncate("London", 1), "…");
+        assert_eq!(truncate("London", 0), "");
+    }
+
+    #[test]
+    fn test_pad() {
+        assert_eq!(pad("東京", 6), "東京  ");
+        assert_eq!(pad("London", 4), "London");
+        assert_eq!(display_width("☀\u{fe0f}"), 2);
+        assert_eq!(display_width("☀"), 1);
+    }
+}
@@ -0,0 +1,254 @@
+//! # Weather Trends
+//!
+//! Compares a fresh observation with an earlier one for the same location and renders
+//! trend indicators (e.g., "61.2°F ↑ (+2.4 since 08:00)").
+
+use crate::common::history::HistoryEntry;
+use ::chrono::{DateTime, Local, Utc};
+use ::serde::Serialize;
+use ::weather_providers::{DisplayAnnotations, DisplayOptions, WeatherInfo};
+
+/// Temperature changes (in °F) smaller than this are considered steady.
+const STEADY_TEMPERATURE_F: f32 = 0.5;
+/// Humidity changes (in percentage points) smaller than this are considered steady.
+const STEADY_HUMIDITY: f32 = 1.0;
+
+/// The direction in which a value changed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Rising,
+    Falling,
+    Steady,
+}
+
+impl Direction {
+    fn from_delta(delta: f32, steady: f32) -> Self {
+        if delta >= steady {
+            Direction::Rising
+        } else if delta <= -steady {
+            Direction::Falling
+        } else {
+            Direction::Steady
+        }
+    }
+
+    /// Returns the arrow shown next to the value.
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            Direction::Rising => "↑",
+            Direction::Falling => "↓",
+            Direction::Steady => "→",
+        }
+    }
+}
+
+/// The change of a single value between two observations.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueTrend {
+    pub previous: f32,
+    pub current: f32,
+    pub direction: Direction,
+}
+
+impl ValueTrend {
+    fn new(previous: f32, current: f32, steady: f32) -> Self {
+        Self {
+            previous,
+            current,
+            direction: Direction::from_delta(current - previous, steady),
+        }
+    }
+}
+
+/// Trends of the current observation compared to a previous one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrendAnnotations {
+    /// When the previous observation was fetched.
+    pub since: DateTime<Utc>,
+    /// Temperature trend in degrees Fahrenheit.
+    pub temperature: ValueTrend,
+    /// Relative humidity trend in percent, `None` unless both observations report a humidity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<ValueTrend>,
+}
+
+/// Computes the trends of `current` compared to the previous observation `prev`.
+pub fn trend(prev: &HistoryEntry, current: &WeatherInfo) -> TrendAnnotations {
+    TrendAnnotations {
+        since: prev.fetched_at,
+        temperature: ValueTrend::new(
+            prev.weather.temperature,
+            current.temperature,
+            STEADY_TEMPERATURE_F,
+        ),
+        humidity: prev
+            .weather
+            .humidity
+            .zip(current.humidity)
+            .map(|(previous, current)| {
+                ValueTrend::new(f32::from(previous), f32::from(current), STEADY_HUMIDITY)
+            }),
+    }
+}
+
+/// Returns whether `prev` can serve as the baseline for a trend of `current` fetched at `now`.
+///
+/// Only observations fetched earlier on the same local day for the same observation date
+/// are compared, and only if both have a temperature.
+pub fn is_comparable(prev: &HistoryEntry, current: &WeatherInfo, now: DateTime<Utc>) -> bool {
+    prev.weather.date == current.date
+        && !prev.weather.temperature.is_nan()
+        && !current.temperature.is_nan()
+        && prev.fetched_at.with_timezone(&Local).date_naive()
+            == now.with_timezone(&Local).date_naive()
+}
+
+impl TrendAnnotations {
+    /// Renders the trends as display annotations using the unit and locale of `opts`.
+    pub fn to_display(&self, opts: &DisplayOptions) -> DisplayAnnotations {
+        let since = self.since.with_timezone(&Local).format("%H:%M");
+        let unit = opts.temperature_unit;
+
+        let temperature_delta = unit.from_fahrenheit(self.temperature.current)
+            - unit.from_fahrenheit(self.temperature.previous);
+
+        DisplayAnnotations {
+            temperature: Some(format!(
+                " {} ({}{} since {since})",
+                self.temperature.direction.arrow(),
+                sign(temperature_delta),
+                opts.format_decimal(temperature_delta.abs()),
+            )),
+            humidity: self.humidity.as_ref().map(|humidity| {
+                let delta = humidity.current - humidity.previous;
+                format!(
+                    " {} ({}{}% since {since})",
+                    humidity.direction.arrow(),
+                    sign(delta),
+                    delta.abs(),
+                )
+            }),
+        }
+    }
+}
+
+fn sign(delta: f32) -> &'static str {
+    if delta < 0.0 { "-" } else { "+" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixtures;
+    use ::chrono::TimeZone;
+    use ::weather_providers::Unit;
+
+    fn weather(date: &str, temperature: f32, humidity: u8) -> WeatherInfo {
+        WeatherInfo {
+            date: date.to_string(),
+            temperature,
+            humidity: Some(humidity),
+            ..fixtures::weather()
+        }
+    }
+
+    fn entry(fetched_at: DateTime<Utc>, temperature: f32, humidity: u8) -> HistoryEntry {
+        HistoryEntry {
+            location_key: "london".to_string(),
+            provider: "mock".to_string(),
+            fetched_at,
+            weather: weather("2024-01-01", temperature, humidity),
+        }
+    }
+
+    fn morning() -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(2024, 1, 1, 8, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_trend_directions() {
+        let prev = entry(morning(), 58.8, 70);
+
+        let rising = trend(&prev, &weather("2024-01-01", 61.2, 70));
+        assert_eq!(rising.temperature.direction, Direction::Rising);
+        assert_eq!(
+            rising.humidity.map(|h| h.direction),
+            Some(Direction::Steady)
+        );
+
+        let falling = trend(&prev, &weather("2024-01-01", 58.5, 60));
+        assert_eq!(falling.temperature.direction, Direction::Steady);
+        assert_eq!(
+            falling.humidity.map(|h| h.direction),
+            Some(Direction::Falling)
+        );
+        assert_eq!(falling.since, morning());
+    }
+
+    #[test]
+    fn test_trend_without_humidity() {
+        let prev = entry(morning(), 58.8, 70);
+        let current = WeatherInfo {
+            humidity: None,
+            ..weather("2024-01-01", 61.2, 0)
+        };
+
+        let trend = trend(&prev, &current);
+        assert_eq!(trend.humidity, None);
+        assert_eq!(trend.to_display(&DisplayOptions::default()).humidity, None);
+    }
+
+    #[test]
+    fn test_trend_rendering() {
+        let prev = entry(morning(), 58.8, 70);
+        let trend = trend(&prev, &weather("2024-01-01", 61.2, 73));
+
+        let annotations = trend.to_display(&DisplayOptions::default());
+        assert_eq!(
+            annotations.temperature.as_deref(),
+            Some(" ↑ (+2.4 since 08:00)")
+        );
+        assert_eq!(
+            annotations.humidity.as_deref(),
+            Some(" ↑ (+3% since 08:00)")
+        );
+
+        let opts = DisplayOptions {
+            temperature_unit: Unit::Celsius,
+            locale: "de-DE".to_string(),
+            ..DisplayOptions::default()
+        };
+        assert_eq!(
+            trend.to_display(&opts).temperature.as_deref(),
+            Some(" ↑ (+1,3 since 08:00)")
+        );
+    }
+
+    #[test]
+    fn test_is_comparable() {
+        let prev = entry(morning(), 58.8, 70);
+        let noon = morning() + ::chrono::Duration::hours(4);
+        let next_day = morning() + ::chrono::Duration::days(1);
+
+        assert!(is_comparable(&prev, &weather("2024-01-01", 61.2, 70), noon));
+        assert!(!is_comparable(
+            &prev,
+            &weather("2024-01-02", 61.2, 70),
+            noon
+        ));
+        assert!(!is_comparable(
+            &prev,
+            &weather("2024-01-01", 61.2, 70),
+            next_day
+        ));
+        assert!(!is_comparable(
+            &prev,
+            &weather("2024-01-01", f32::NAN, 70),
+            noon
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 use ::assert_cmd::Command;
 use ::predicates::prelude::*;
+use ::std::path::{Path, PathBuf};
 
 /// Helper function to create a command instance for the CLI binary.
 /// Assumes the binary name matches the package name 'weather'.
@@ -7,9 +8,28 @@ fn weather_cli() -> Command {
     Command::new(env!("CARGO_BIN_EXE_weather"))
 }
 
+/// Creates a command that keeps its configuration and history in `dir`, away from the
+/// developer's own.
+fn isolated_cli(dir: &Path) -> Command {
+    let mut command = weather_cli();
+    command
+        .env("WEATHER_CONFIG", dir.join("config.json"))
+        .env("WEATHER_HISTORY", dir.join("history.jsonl"));
+    command
+}
+
+/// Creates an empty temporary directory for the test `name`.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("weather-cli-test-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
 #[test]
 fn test_help_command() {
-    let mut cmd = weather_cli();
+    let dir = test_dir("help-command");
+    let mut cmd = isolated_cli(&dir);
 
     // Run `weather --help` and assert that it runs successfully
     // and contains expected usage information.
@@ -25,7 +45,8 @@ fn test_help_command() {
 
 #[test]
 fn test_version_flag() {
-    let mut cmd = weather_cli();
+    let dir = test_dir("version-flag");
+    let mut cmd = isolated_cli(&dir);
 
     // Run `weather --version`
     cmd.arg("--version")
@@ -36,7 +57,8 @@ fn test_version_flag() {
 
 #[test]
 fn test_get_weather_mock_default() {
-    let mut cmd = weather_cli();
+    let dir = test_dir("get-weather-mock-default");
+    let mut cmd = isolated_cli(&dir);
 
     // Test the `get` command using the Mock provider.
     // We explicitly set the provider to 'mock' to avoid needing a config file with keys.
@@ -54,9 +76,96 @@ fn test_get_weather_mock_default() {
         .stdout(predicate::str::contains("Sunny (Mock)"));
 }
 
+#[test]
+fn test_get_weather_alert_within_thresholds() {
+    let dir = test_dir("get-weather-alert-within-thresholds");
+
+    // The mock weather (20°F, 50% humidity) is within the default thresholds
+    isolated_cli(&dir)
+        .args(["get", "London", "--provider", "mock", "--alert"])
+        .assert()
+        .code(0)
+        .stdout(predicate::str::contains("Mock City"))
+        .stderr(predicate::str::contains("Weather alert").not());
+}
+
+#[test]
+fn test_get_weather_alert_window() {
+    let dir = test_dir("get-weather-alert-window");
+    let weather = |args: &[&str]| {
+        let mut command = isolated_cli(&dir);
+        command.args(args);
+        command
+    };
+    weather(&["config", "set", "alert_thresholds.min_temperature_f", "12"])
+        .assert()
+        .success();
+
+    // The mock hours are at 11°F until 06:00, then at 14°F
+    let get = |window: &str| {
+        weather(&[
+            "get",
+            "London",
+            "-p",
+            "mock",
+            "-d",
+            "2024-06-21",
+            "--alert",
+            "--window",
+            window,
+        ])
+        .assert()
+    };
+    get("06:00-09:00").code(0);
+    get("04:00-07:00")
+        .code(2)
+        .stderr(predicate::str::contains("within 04:00-07:00"))
+        .stderr(predicate::str::contains("- 04:00: Temperature"))
+        .stderr(predicate::str::contains("- 05:00: Temperature"))
+        .stderr(predicate::str::contains("06:00:").not());
+
+    // The mock provider reports no sunrise
+    get("sunrise±2h")
+        .code(1)
+        .stderr(predicate::str::contains("no sunrise time for 2024-06-21"));
+    get("dawn")
+        .code(1)
+        .stderr(predicate::str::contains("Invalid window 'dawn'"));
+
+    weather(&["get", "London", "-p", "mock", "--window", "06:00-09:00"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--alert"));
+}
+
+#[test]
+fn test_location_without_get() {
+    let dir = test_dir("location-without-get");
+
+    isolated_cli(&dir)
+        .args(["London", "--provider", "mock"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Fetching weather from 'MockWeather' for 'London'",
+        ));
+}
+
+#[test]
+fn test_subcommand_name_hint() {
+    let dir = test_dir("subcommand-name-hint");
+
+    isolated_cli(&dir)
+        .arg("compare")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use: weather get compare"));
+}
+
 #[test]
 fn test_get_weather_with_date_mock() {
-    let mut cmd = weather_cli();
+    let dir = test_dir("get-weather-with-date-mock");
+    let mut cmd = isolated_cli(&dir);
 
     // Test requesting weather for a specific date using the Mock provider.
     cmd.arg("get")
@@ -75,7 +184,8 @@ fn test_get_weather_with_date_mock() {
 
 #[test]
 fn test_provider_list() {
-    let mut cmd = weather_cli();
+    let dir = test_dir("provider-list");
+    let mut cmd = isolated_cli(&dir);
 
     // Test the `provider --list` command.
     cmd.arg("provider")
@@ -88,9 +198,28 @@ fn test_provider_list() {
         .stdout(predicate::str::contains("wa"));
 }
 
+#[test]
+fn test_provider_list_json() {
+    let dir = test_dir("provider-list-json");
+    let output = isolated_cli(&dir)
+        .args(["provider", "--list", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let statuses: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let statuses = statuses.as_array().unwrap();
+    let mock = statuses.iter().find(|s| s["id"] == "mock").unwrap();
+    assert_eq!(mock["name"], "MockWeather");
+    assert!(mock["key_configured"].is_boolean());
+    assert!(mock["is_default"].is_boolean());
+    assert!(statuses.iter().any(|s| s["id"] == "ow"));
+}
+
 #[test]
 fn test_fail_unknown_provider() {
-    let mut cmd = weather_cli();
+    let dir = test_dir("fail-unknown-provider");
+    let mut cmd = isolated_cli(&dir);
 
     // Expect failure when using a non-existent provider.
     cmd.arg("get")
@@ -104,10 +233,11 @@ fn test_fail_unknown_provider() {
 
 #[test]
 fn test_fail_missing_address() {
-    let mut cmd = weather_cli();
+    let dir = test_dir("fail-missing-address");
+    let mut cmd = isolated_cli(&dir);
 
-    // Expect failure when no address and no default alias are configured.
-    // This assumes the test environment doesn't have a config file set up yet.
+    // Expect failure when no address and no default alias are configured, as in the empty
+    // test directory.
     cmd.arg("get")
         .arg("--provider")
         .arg("mock")
@@ -115,3 +245,1149 @@ fn test_fail_missing_address() {
         .failure()
         .stderr(predicate::str::contains("No address specified"));
 }
+
+#[test]
+fn test_digest_setup_hint() {
+    let dir = test_dir("digest-setup-hint");
+
+    isolated_cli(&dir)
+        .arg("digest")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("The digest has no locations yet"))
+        .stdout(predicate::str::contains("\"alias\": \"home\""));
+}
+
+#[test]
+fn test_digest_progress_on_stderr() {
+    let dir = test_dir("digest-progress");
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{
+            "addresses": { "home": "London, UK" },
+            "digest": { "locations": [{ "alias": "home" }] }
+        }"#,
+    )
+    .unwrap();
+
+    // Only the digest itself is printed to stdout, so it can be redirected to a file
+    isolated_cli(&dir)
+        .args(["digest", "--provider", "mock", "--markdown"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("home"))
+        .stdout(predicate::str::contains("Fetching").not())
+        .stderr(predicate::str::contains("Fetching the digest from"));
+}
+
+#[test]
+fn test_anomalies_without_history() {
+    let dir = test_dir("anomalies-without-history");
+
+    // A location never fetched before has no history to compare with
+    isolated_cli(&dir)
+        .args([
+            "anomalies",
+            "Anomaly Test",
+            "--provider",
+            "mock",
+            "--days",
+            "7",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Not enough history"));
+}
+
+#[test]
+fn test_anomalies_rejects_too_many_days() {
+    let dir = test_dir("anomalies-rejects-too-many-days");
+
+    isolated_cli(&dir)
+        .args(["anomalies", "London", "--provider", "mock"])
+        .args(["--days", "4294967295"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--days"));
+}
+
+#[test]
+fn test_get_weather_prometheus_export() {
+    let dir = test_dir("get-weather-prometheus-export");
+    let export_path = dir.join("weather.prom");
+
+    // Prometheus output written to a file must not contain the human-readable header.
+    isolated_cli(&dir)
+        .arg("get")
+        .arg("London")
+        .arg("--provider")
+        .arg("mock")
+        .arg("--output")
+        .arg("prometheus")
+        .arg("--export")
+        .arg(&export_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let exported = std::fs::read_to_string(&export_path).expect("Export file should exist");
+    let _ = std::fs::remove_file(&export_path);
+
+    assert!(exported.contains("# TYPE weather_temperature_fahrenheit gauge"));
+    assert!(exported.contains(
+        "weather_temperature_fahrenheit{city=\"Mock City\",country=\"Mock Country\",provider=\"mock\"} 20"
+    ));
+}
+
+#[test]
+fn test_geocode_mock() {
+    let dir = test_dir("geocode-mock");
+    let mut cmd = isolated_cli(&dir);
+
+    cmd.arg("geocode")
+        .arg("London")
+        .arg("--provider")
+        .arg("mock")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Geocoding 'London' with 'MockWeather'",
+        ))
+        .stdout(predicate::str::contains(
+            "Location: Mock City, Mock Country",
+        ))
+        .stdout(predicate::str::contains("Latitude: 51.5072"))
+        .stdout(predicate::str::contains("Longitude: -0.1276"));
+}
+
+#[test]
+fn test_get_weather_openmetrics() {
+    let dir = test_dir("get-weather-openmetrics");
+    let mut cmd = isolated_cli(&dir);
+
+    cmd.arg("get")
+        .arg("London")
+        .arg("--provider")
+        .arg("mock")
+        .arg("--format")
+        .arg("openmetrics")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "# HELP weather_temperature_fahrenheit",
+        ))
+        .stdout(predicate::str::contains(
+            "weather_humidity_percent{city=\"Mock City\",country=\"Mock Country\",\
+             provider=\"mock\"} 50\n",
+        ))
+        .stdout(predicate::str::ends_with("# EOF\n"));
+}
+
+#[test]
+fn test_get_weather_json_with_trend() {
+    let dir = test_dir("json-trend");
+
+    // The first request records the observation, so the second one has a trend baseline
+    isolated_cli(&dir)
+        .args(["get", "Trend Town", "--provider", "mock"])
+        .assert()
+        .success();
+
+    isolated_cli(&dir)
+        .args([
+            "get",
+            "Trend Town",
+            "--provider",
+            "mock",
+            "--output",
+            "json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("{"))
+        .stdout(predicate::str::contains("\"city\": \"Mock City\""))
+        .stdout(predicate::str::contains("\"direction\": \"steady\""))
+        .stdout(predicate::str::contains("\"icon\": \"clear-day\""))
+        .stdout(predicate::str::contains("\"provider_icon\": \"sunny\""))
+        .stdout(predicate::str::contains("\"endpoint\": \"mock\""))
+        .stdout(predicate::str::contains("\"cache\": \"miss\""));
+}
+
+#[test]
+fn test_compare_mock() {
+    let dir = test_dir("compare-mock");
+    let mut cmd = isolated_cli(&dir);
+
+    cmd.arg("compare")
+        .arg("London")
+        .arg("london")
+        .arg("Paris")
+        .arg("--provider")
+        .arg("mock")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Fetching weather from 'MockWeather' for 3 locations...",
+        ))
+        .stdout(predicate::str::contains("Weather in 'Mock Country, Mock City'").count(3))
+        .stderr(predicate::str::ends_with("3 succeeded, 0 failed\n"));
+}
+
+#[test]
+fn test_compare_rejects_invalid_date() {
+    let dir = test_dir("compare-invalid-date");
+
+    isolated_cli(&dir)
+        .args(["compare", "London", "Paris", "--provider", "mock"])
+        .args(["--date", "garbage"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid date: 'garbage'"));
+}
+
+#[test]
+fn test_compare_partial_failure() {
+    let dir = test_dir("compare-partial-failure");
+
+    isolated_cli(&dir)
+        .args(["compare", "London", "Paris", "Kyiv", "--provider", "mock"])
+        .env(
+            "WEATHER_PROVIDERS_MOCK_FAULTS",
+            "fail_on=Paris,fail_kind=not_found",
+        )
+        .assert()
+        .code(6)
+        .stdout(predicate::str::contains("Weather in 'Mock Country, Mock City'").count(2))
+        .stdout(predicate::str::contains(
+            "Weather in 'Paris': Location not found",
+        ))
+        .stderr(predicate::str::ends_with(
+            "2 succeeded, 1 failed (Paris: Location not found by 'MockWeather')\n",
+        ));
+}
+
+#[test]
+fn test_compare_total_failure() {
+    let dir = test_dir("compare-total-failure");
+
+    isolated_cli(&dir)
+        .args(["compare", "London", "Paris", "--provider", "mock"])
+        .env(
+            "WEATHER_PROVIDERS_MOCK_FAULTS",
+            "fail_rate=1.0,fail_kind=server_error",
+        )
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("0 succeeded, 2 failed (London: "))
+        .stderr(predicate::str::contains("; Paris: "));
+}
+
+#[test]
+fn test_compare_sorted() {
+    let dir = test_dir("compare-sorted");
+
+    // Colors are only used on terminals
+    isolated_cli(&dir)
+        .args(["compare", "London", "Paris", "--provider", "mock"])
+        .args(["--sort-by", "humidity", "--highlight-extremes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Weather in 'Mock Country, Mock City'").count(2))
+        .stdout(predicate::str::contains("\x1b[").not());
+}
+
+#[test]
+fn test_get_weather_detailed() {
+    let dir = test_dir("get-weather-detailed");
+
+    isolated_cli(&dir)
+        .args(["get", "London", "--provider", "mock", "--detailed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "  Morning 14.0°F    Afternoon 20.0°F  Evening 17.0°F    Night 11.0°F      \
+             Low 9.0°F High 22.0°F",
+        ));
+}
+
+#[test]
+fn test_get_weather_verbose() {
+    let dir = test_dir("get-weather-verbose");
+
+    isolated_cli(&dir)
+        .args(["get", "London", "--provider", "mock"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Source:").not());
+
+    isolated_cli(&dir)
+        .args(["get", "London", "--provider", "mock", "--verbose"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\n  Source: mock mock (observed), fetched ",
+        ))
+        .stdout(predicate::str::contains("cache miss"));
+
+    // Duplicate locations of a comparison are served from the first request
+    isolated_cli(&dir)
+        .args([
+            "compare",
+            "London",
+            "london",
+            "--provider",
+            "mock",
+            "--verbose",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cache miss").count(1))
+        .stdout(predicate::str::contains("cache hit").count(1));
+}
+
+#[test]
+fn test_get_weather_location_file() {
+    let dir = test_dir("get-weather-location-file");
+    let location_file = dir.join("locations.txt");
+    std::fs::write(&location_file, "# Capitals\nLondon\n\nParis, FR\nKyiv\n").unwrap();
+
+    let assert = isolated_cli(&dir)
+        .arg("get")
+        .arg("--location-file")
+        .arg(&location_file)
+        .arg("--concurrency")
+        .arg("2")
+        .arg("--provider")
+        .arg("mock")
+        .assert();
+    let _ = std::fs::remove_file(&location_file);
+
+    assert
+        .success()
+        .stderr(predicate::str::contains(
+            "Fetching weather from 'MockWeather' for 3 locations...",
+        ))
+        .stdout(predicate::str::starts_with("address,country,city,date,"))
+        .stdout(predicate::str::contains(
+            "\"Paris, FR\",Mock Country,Mock City,",
+        ))
+        .stdout(predicate::str::contains("Mock City").count(3))
+        .stderr(predicate::str::ends_with("3 succeeded, 0 failed\n"));
+}
+
+#[test]
+fn test_get_weather_location_file_partial_failure() {
+    let dir = test_dir("get-weather-location-file-partial-failure");
+    let location_file = dir.join("locations.txt");
+    std::fs::write(
+        &location_file,
+        "London
+Paris
+Kyiv
+Rome
+",
+    )
+    .unwrap();
+
+    let assert = isolated_cli(&dir)
+        .args(["get", "--location-file"])
+        .arg(&location_file)
+        .args(["--provider", "mock"])
+        .env(
+            "WEATHER_PROVIDERS_MOCK_FAULTS",
+            "fail_on=paris,fail_kind=not_found",
+        )
+        .assert();
+    let _ = std::fs::remove_file(&location_file);
+
+    // The failed address still gets its row
+    assert
+        .code(6)
+        .stdout(predicate::str::contains("Mock City").count(3))
+        .stdout(predicate::str::contains("Paris,,,,"))
+        .stderr(predicate::str::ends_with(
+            "3 succeeded, 1 failed (Paris: Location not found by 'MockWeather')\n",
+        ));
+}
+
+#[test]
+fn test_get_weather_location_file_sorted() {
+    let dir = test_dir("get-weather-location-file-sorted");
+    let location_file = dir.join("locations.txt");
+    std::fs::write(&location_file, "Paris\nKyiv\nLondon\n").unwrap();
+
+    let run = |output: &str| {
+        isolated_cli(&dir)
+            .args(["get", "--location-file"])
+            .arg(&location_file)
+            .args([
+                "--sort-by",
+                "temp",
+                "--provider",
+                "mock",
+                "--output",
+                output,
+            ])
+            .output()
+            .unwrap()
+    };
+    let csv = run("text");
+    let ndjson = run("json");
+    let _ = std::fs::remove_file(&location_file);
+
+    // The mock weather is the same everywhere, so the addresses break the ties
+    let addresses = |stdout: &[u8], prefix: &str| {
+        String::from_utf8_lossy(stdout)
+            .lines()
+            .filter_map(|line| line.strip_prefix(prefix).map(|rest| rest[..4].to_string()))
+            .collect::<Vec<_>>()
+    };
+    assert!(csv.status.success());
+    assert_eq!(addresses(&csv.stdout, ""), ["addr", "Kyiv", "Lond", "Pari"]);
+    assert!(ndjson.status.success());
+    assert_eq!(
+        addresses(&ndjson.stdout, "{\"address\":\""),
+        ["Kyiv", "Lond", "Pari"]
+    );
+}
+
+#[test]
+fn test_complete_locations() {
+    let dir = test_dir("complete-locations");
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{ "addresses": { "londres": "London, UK" } }"#,
+    )
+    .unwrap();
+
+    let output = isolated_cli(&dir)
+        .args(["_complete-locations", "lond"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+
+    // One candidate per line, each starting with the prefix regardless of case, aliases first
+    assert_eq!(lines[0], "londres");
+    assert!(lines.contains(&"London, GB"));
+    assert!(lines.iter().all(|l| l.to_lowercase().starts_with("lond")));
+
+    // Without a prefix, every location matches up to the cap
+    isolated_cli(&dir)
+        .arg("_complete-locations")
+        .assert()
+        .success()
+        .stdout(predicate::function(|out: &str| out.lines().count() == 50));
+
+    // The command is hidden from the help
+    isolated_cli(&dir)
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_complete-locations").not());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_shell_completion() {
+    let dir = test_dir("shell-completion");
+
+    // The registration script of the shell
+    isolated_cli(&dir)
+        .env("COMPLETE", "bash")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_clap_complete_weather"));
+
+    // What bash asks the registered script for `weather get lond<TAB>`
+    let complete = |words: &[&str]| {
+        let output = isolated_cli(&dir)
+            .env("COMPLETE", "bash")
+            .env("_CLAP_COMPLETE_INDEX", words.len().to_string())
+            .env("_CLAP_COMPLETE_COMP_TYPE", "9")
+            .args(["--", "weather"])
+            .args(words)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output)
+            .unwrap()
+            .split(['\u{b}', '\n'])
+            .filter(|candidate| !candidate.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+
+    let locations = complete(&["get", "lond"]);
+    assert!(locations.contains(&"London, GB".to_string()));
+    assert!(
+        locations
+            .iter()
+            .all(|l| l.to_lowercase().starts_with("lond"))
+    );
+
+    // The commands come from the clap definition, without the hidden ones
+    let commands = complete(&["co"]);
+    assert_eq!(commands, ["compare", "config"]);
+}
+
+#[test]
+fn test_get_weather_days() {
+    let dir = test_dir("get-weather-days");
+    let mut cmd = isolated_cli(&dir);
+
+    // The standard output is not a terminal here, so no charts are rendered
+    cmd.args([
+        "get",
+        "London",
+        "--provider",
+        "mock",
+        "--days",
+        "3",
+        "--date",
+        "2024-02-28",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(
+        "Fetching 3-day weather from 'MockWeather' for 'London'...",
+    ))
+    .stdout(predicate::str::contains(" on 2024-02-29"))
+    .stdout(predicate::str::contains(" on 2024-03-01"))
+    .stdout(predicate::str::contains("Temperature").not());
+}
+
+#[test]
+fn test_get_weather_date_relative() {
+    let dir = test_dir("get-weather-date-relative");
+    let mut cmd = isolated_cli(&dir);
+
+    cmd.args([
+        "get",
+        "London",
+        "--provider",
+        "mock",
+        "--date-relative",
+        "today",
+        "--show-date",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(
+        chrono::Local::now().format(" on %Y-%m-%d").to_string(),
+    ));
+
+    isolated_cli(&dir)
+        .args([
+            "get",
+            "London",
+            "--provider",
+            "mock",
+            "--date-relative",
+            "last week",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid relative date: 'last week'",
+        ));
+}
+
+#[test]
+fn test_provider_info() {
+    let dir = test_dir("provider-info");
+
+    isolated_cli(&dir)
+        .args(["provider", "wa", "--info"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("WeatherApi (wa)"))
+        .stdout(predicate::str::contains(
+            "Sign up:      https://www.weatherapi.com/signup.aspx",
+        ));
+
+    isolated_cli(&dir)
+        .args(["provider", "ow", "--info", "--output", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"id\": \"ow\""))
+        .stdout(predicate::str::contains("\"signup_url\": \"https://"));
+}
+
+#[test]
+fn test_get_save_as() {
+    let dir = test_dir("get-save-as");
+    let config = dir.join("config.json");
+    let aliases = || {
+        let settings: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&config).unwrap()).unwrap();
+        settings["addresses"].clone()
+    };
+
+    // The alias keeps the address resolved by the provider, not the raw input
+    isolated_cli(&dir)
+        .args(["get", "Springfield, Missouri, US", "--provider", "mock"])
+        .args(["--save-as", "home"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Alias 'home' set to 'Mock City, Mock Country'",
+        ));
+    assert_eq!(aliases()["home"], "Mock City, Mock Country");
+
+    // An existing alias is only overwritten with --force
+    isolated_cli(&dir)
+        .args(["get", "Paris", "--provider", "mock", "--save-as", "home"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Use --force to overwrite it"));
+    isolated_cli(&dir)
+        .args([
+            "get",
+            "Paris",
+            "--provider",
+            "mock",
+            "--save-as",
+            "home",
+            "--force",
+        ])
+        .assert()
+        .success();
+
+    // Failed requests and invalid aliases do not touch the configuration
+    isolated_cli(&dir)
+        .env("WEATHER_PROVIDERS_OFFLINE", "1")
+        .args(["get", "Oslo", "--provider", "ss", "--save-as", "work"])
+        .assert()
+        .failure();
+    isolated_cli(&dir)
+        .args(["get", "Oslo", "--provider", "mock", "--save-as", "toolong"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("between 1 and 5 characters"));
+    assert!(aliases().get("work").is_none() && aliases().get("toolong").is_none());
+}
+
+#[test]
+fn test_prefetch_is_silent() {
+    let dir = test_dir("prefetch");
+
+    isolated_cli(&dir)
+        .args([
+            "prefetch",
+            "--locations",
+            "London",
+            "Paris",
+            "--provider",
+            "mock",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+
+    // Failures are only logged, and never fail the shell startup
+    isolated_cli(&dir)
+        .env("WEATHER_PROVIDERS_OFFLINE", "1")
+        .args(["prefetch", "--locations", "Oslo", "--provider", "ss"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    isolated_cli(&dir)
+        .args(["prefetch", "--locations", "London", "--provider", "mock"])
+        .arg("--verbose")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Prefetched 1 of 1 locations"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_prefetch_deadline() {
+    let dir = test_dir("prefetch-deadline");
+    let started = std::time::Instant::now();
+
+    isolated_cli(&dir)
+        .env("WEATHER_PROVIDERS_MOCK_FAULTS", "latency_ms=30000")
+        .args(["prefetch", "--locations", "London", "--provider", "mock"])
+        .args(["--deadline", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_offline_mode() {
+    let dir = test_dir("offline-mode");
+
+    isolated_cli(&dir)
+        .env("WEATHER_PROVIDERS_OFFLINE", "1")
+        .args(["get", "Oslo", "--provider", "ss"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("offline mode"))
+        .stderr(predicate::str::contains("--provider mock"));
+
+    isolated_cli(&dir)
+        .env("WEATHER_PROVIDERS_OFFLINE", "1")
+        .args(["get", "London", "--provider", "mock"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Mock City"));
+}
+
+#[test]
+fn test_config_export() {
+    let dir = test_dir("config-export");
+
+    isolated_cli(&dir)
+        .args(["config", "export"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[providers."));
+
+    isolated_cli(&dir)
+        .args(["config", "export", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"providers\": {"));
+}
+
+#[test]
+fn test_get_dry_run_routing() {
+    let dir = test_dir("get-dry-run-routing");
+    let config = dir.join("config.json");
+    std::fs::write(
+        &config,
+        r#"{"routing": [{"match": {"country": "US"}, "provider": "ss"}]}"#,
+    )
+    .unwrap();
+
+    isolated_cli(&dir)
+        .args(["get", "Austin, TX, US", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Provider: 'SunriseSunset' (ss)"))
+        .stdout(predicate::str::contains(
+            r#"Routing:  rule 1 (country "US")"#,
+        ));
+
+    isolated_cli(&dir)
+        .args(["get", "Paris, FR", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Provider: 'MockWeather' (mock)"))
+        .stdout(predicate::str::contains("Routing:  no rule matched"));
+
+    isolated_cli(&dir)
+        .args(["get", "Austin, TX, US", "--dry-run", "-p", "mock"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("not applied (--provider)"));
+}
+
+#[test]
+fn test_get_wind_unit_follows_units() {
+    let dir = test_dir("get-wind-unit-follows-units");
+    let config = dir.join("config.json");
+    let get = |settings: &str| {
+        std::fs::write(&config, settings).unwrap();
+        isolated_cli(&dir)
+            .args(["get", "London", "-p", "mock"])
+            .assert()
+            .success()
+    };
+
+    get(r#"{"units": "metric", "display_options": {"show_wind": true}}"#)
+        .stdout(predicate::str::contains("km/h"));
+    get(r#"{"units": "metric", "display_options": {"show_wind": true, "wind_unit": "knots"}}"#)
+        .stdout(predicate::str::contains("kn").and(predicate::str::contains("km/h").not()));
+}
+
+#[test]
+fn test_compare_routing() {
+    let dir = test_dir("compare-routing");
+    let config = dir.join("config.json");
+    std::fs::write(
+        &config,
+        r#"{"routing": [{"match": {"country": "US"}, "provider": "wa"}]}"#,
+    )
+    .unwrap();
+
+    // Each address gets the provider of its rule, which needs a key here
+    isolated_cli(&dir)
+        .args(["compare", "Paris, FR", "Austin, TX, US"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "API key not found for provider 'WeatherApi'",
+        ));
+
+    isolated_cli(&dir)
+        .args(["compare", "Paris, FR", "London, UK"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Fetching weather from 'MockWeather' for 2 locations",
+        ));
+}
+
+#[test]
+fn test_config_path() {
+    let dir = test_dir("config-path");
+    let config = dir.join("config.json");
+
+    isolated_cli(&dir)
+        .args(["config", "path"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", config.display()));
+}
+
+#[test]
+fn test_config_set_get() {
+    let dir = test_dir("config-set-get");
+    let weather = |args: &[&str]| {
+        let mut command = isolated_cli(&dir);
+        command.args(args);
+        command
+    };
+
+    for (path, value) in [
+        ("addresses.home", "London, UK"),
+        ("default_alias", "home"),
+        ("providers.wa.key", "0123abcd"),
+        ("logging.max_files", "7"),
+        ("display_options.show_date", "true"),
+        ("alert_thresholds.min_temperature_f", "-4.5"),
+    ] {
+        weather(&["config", "set", path, value])
+            .assert()
+            .success()
+            .stdout(format!("Set '{path}'.\n"));
+    }
+
+    weather(&["config", "get", "addresses.home"])
+        .assert()
+        .success()
+        .stdout("London, UK\n");
+    weather(&["config", "get", "addresses.home", "--output", "json"])
+        .assert()
+        .success()
+        .stdout("\"London, UK\"\n");
+    weather(&["config", "get", "providers.wa.key"])
+        .assert()
+        .success()
+        .stdout("0123abcd\n");
+    weather(&["config", "get", "logging.max_files", "--output", "json"])
+        .assert()
+        .success()
+        .stdout("7\n");
+    weather(&["config", "get", "alert_thresholds.min_temperature_f"])
+        .assert()
+        .success()
+        .stdout("-4.5\n");
+
+    // The settings are used by other commands
+    weather(&["get", "--provider", "mock"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(" on "));
+
+    weather(&["config", "get", "default_provider"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'default_provider' is not set."));
+    weather(&["config", "set", "logging.max_files", "many"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "'logging.max_files' expects a whole number, got 'many'.",
+        ));
+    weather(&["config", "set", "providers.wa.token", "x"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Unknown setting 'providers.wa.token'",
+        ))
+        .stderr(predicate::str::contains("providers.<id>.key"));
+
+    // A rejected value leaves the configuration unchanged
+    weather(&["config", "get", "logging.max_files"])
+        .assert()
+        .success()
+        .stdout("7\n");
+}
+
+#[test]
+fn test_config_check() {
+    let dir = test_dir("config-check");
+    let config = dir.join("config.json");
+
+    std::fs::write(&config, r#"{"defaultProvider": "mock", "addresses": {}}"#).unwrap();
+    isolated_cli(&dir)
+        .args(["config", "--check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No unknown keys"));
+
+    std::fs::write(&config, r#"{"defalt_provider": "mock", "addresses": {}}"#).unwrap();
+    isolated_cli(&dir)
+        .args(["config", "--check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "defalt_provider (did you mean 'default_provider'?)",
+        ))
+        .stderr(predicate::str::contains("Found 1 unknown key(s)"));
+}
+
+#[test]
+fn test_invalid_log_level_falls_back() {
+    let dir = test_dir("invalid-log-level");
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{ "logging": { "level": "loud", "enabled": false } }"#,
+    )
+    .unwrap();
+
+    // The invalid level does not stop the command that fixes it
+    isolated_cli(&dir)
+        .args(["config", "set", "logging.level", "info"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Invalid log level 'loud'"));
+    isolated_cli(&dir)
+        .args(["config", "get", "logging.level"])
+        .assert()
+        .success()
+        .stdout("info\n")
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_config_locked() {
+    let dir = test_dir("config-locked");
+    let config = dir.join("config.json");
+    let lock = config.with_extension("json.lock");
+    // The lock is held by a running process: the test itself
+    let pid = std::process::id();
+    std::fs::write(&lock, pid.to_string()).unwrap();
+
+    // Saving the configuration gives up once the lock timeout elapses
+    isolated_cli(&dir)
+        .env("WEATHER_CONFIG_LOCK_TIMEOUT", "0.1")
+        .args(["get", "London", "--provider", "mock", "--save-as", "home"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "The configuration is locked by another weather process (pid {pid})"
+        )))
+        .stderr(predicate::str::contains("--no-config"));
+
+    // The configuration is not needed to get the weather
+    isolated_cli(&dir)
+        .args(["--no-config", "get", "--provider", "mock", "London"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Mock City"));
+
+    isolated_cli(&dir)
+        .args([
+            "--no-config",
+            "get",
+            "London",
+            "--provider",
+            "mock",
+            "--save-as",
+            "home",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "cannot be changed with --no-config",
+        ));
+
+    assert!(!config.exists());
+    let _ = std::fs::remove_file(&lock);
+}
+
+#[test]
+#[cfg(feature = "integration")]
+fn test_get_weather_real_api() {
+    let dir = test_dir("get-weather-real-api");
+
+    // Calls the keyless Sunrise-Sunset API; enabled by the `integration` feature.
+    isolated_cli(&dir)
+        .args(["get", "Oslo", "--provider", "ss", "--output", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"sunrise\": \""));
+}
+
+/// Spawns the CLI, isolated like `isolated_cli`, and returns it with a channel receiving its standard output line by line.
+fn spawn_streaming(
+    dir: &Path,
+    args: &[&str],
+) -> (std::process::Child, std::sync::mpsc::Receiver<String>) {
+    use ::std::io::BufRead;
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_weather"))
+        .env("WEATHER_CONFIG", dir.join("config.json"))
+        .env("WEATHER_HISTORY", dir.join("history.jsonl"))
+        .args(args)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let stdout = std::io::BufReader::new(child.stdout.take().unwrap());
+    let (lines, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for line in stdout.lines().map_while(|line| line.ok()) {
+            if lines.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    (child, receiver)
+}
+
+#[test]
+fn test_watch_streams_records() {
+    let dir = test_dir("watch-streams-records");
+    let timeout = std::time::Duration::from_secs(30);
+    let (mut child, lines) = spawn_streaming(
+        &dir,
+        &[
+            "watch",
+            "London",
+            "--provider",
+            "mock",
+            "--output",
+            "json",
+            "--interval",
+            "3600",
+            "--heartbeat",
+            "1",
+        ],
+    );
+
+    // Both records arrive while the watch is still running
+    let record = lines.recv_timeout(timeout);
+    let heartbeat = lines.recv_timeout(timeout);
+    let running = child.try_wait().unwrap().is_none();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let record = serde_json::from_str::<serde_json::Value>(&record.unwrap()).unwrap();
+    assert_eq!(record["city"], "Mock City");
+    assert_eq!(heartbeat.unwrap(), "{}");
+    assert!(running);
+}
+
+#[test]
+fn test_watch_heartbeat_requires_json() {
+    let dir = test_dir("watch-heartbeat-requires-json");
+
+    isolated_cli(&dir)
+        .args(["watch", "London", "--provider", "mock", "--heartbeat", "5"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--heartbeat only applies to --output json",
+        ));
+}
+
+#[test]
+fn test_get_weather_location_file_ndjson() {
+    let dir = test_dir("get-weather-location-file-ndjson");
+    let location_file = dir.join("locations.txt");
+    std::fs::write(&location_file, "London\nParis, FR\nKyiv\n").unwrap();
+
+    let output = isolated_cli(&dir)
+        .arg("get")
+        .arg("--location-file")
+        .arg(&location_file)
+        .args(["--provider", "mock", "--output", "json"])
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_file(&location_file);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut addresses = stdout
+        .lines()
+        .map(|line| {
+            let record = serde_json::from_str::<serde_json::Value>(line).unwrap();
+            assert_eq!(record["city"], "Mock City");
+            record["address"].as_str().unwrap().to_string()
+        })
+        .collect::<Vec<_>>();
+    addresses.sort();
+
+    assert_eq!(addresses, ["Kyiv", "London", "Paris, FR"]);
+}
+
+#[test]
+fn test_daemon_once() {
+    let dir = test_dir("daemon");
+    let config = dir.join("config.json");
+    let export_dir = dir.join("export");
+
+    // Without locations, there is nothing to refresh
+    isolated_cli(&dir)
+        .args(["daemon", "--once", "--provider", "mock"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("daemon.locations"));
+
+    std::fs::write(
+        &config,
+        r#"{
+            "addresses": { "home": "London, UK" },
+            "daemon": { "locations": ["home", "Paris, FR"], "interval_secs": 60 }
+        }"#,
+    )
+    .unwrap();
+
+    isolated_cli(&dir)
+        .args(["daemon", "--once", "--provider", "mock", "--no-log-file"])
+        .arg("--export-dir")
+        .arg(&export_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cycle completed"));
+
+    let exported: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(export_dir.join("paris-fr.json")).unwrap())
+            .unwrap();
+    assert_eq!(exported["city"], "Mock City");
+    assert!(export_dir.join("home.json").exists());
+
+    // A zero interval is refused rather than spinning or panicking
+    std::fs::write(
+        &config,
+        r#"{ "daemon": { "locations": ["Paris, FR"], "interval_secs": 0 } }"#,
+    )
+    .unwrap();
+    isolated_cli(&dir)
+        .args(["daemon", "--provider", "mock", "--no-log-file"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("interval_secs must be at least 1"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
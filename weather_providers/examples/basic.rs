@@ -0,0 +1,26 @@
+//! Fetches weather from the offline mock provider and prints it.
+//!
+//! Run with `cargo run -p weather_providers --example basic`.
+
+use ::weather_providers::{DisplayOptions, Provider, Result, Unit, create_provider};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let provider = create_provider(Provider::Mock);
+
+    let weather = provider.get_weather(None, "London").await?;
+
+    // The default display: Fahrenheit, US number formatting
+    println!("{weather}");
+
+    // Custom display options
+    let opts = DisplayOptions {
+        show_date: true,
+        temperature_unit: Unit::Celsius,
+        locale: "de-DE".to_string(),
+        ..DisplayOptions::default()
+    };
+    println!("{}", weather.format_for_display(&opts));
+
+    Ok(())
+}
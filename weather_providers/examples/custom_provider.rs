@@ -0,0 +1,63 @@
+//! Implements `WeatherProvider` for an application type and registers it next to the
+//! built-in providers.
+//!
+//! Run with `cargo run -p weather_providers --example custom_provider`.
+
+use ::async_trait::async_trait;
+use ::std::{sync::Arc, time::Duration};
+use ::weather_providers::{
+    ProviderInfo, ProviderRegistry, Result, WeatherInfo, WeatherProvider, normalize_date,
+};
+
+/// Reports the readings of a thermometer on the balcony.
+struct BalconyThermometer {
+    fahrenheit: f32,
+}
+
+#[async_trait]
+impl WeatherProvider for BalconyThermometer {
+    async fn get_weather(&self, _provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        Ok(WeatherInfo {
+            country: "Home".to_string(),
+            city: address.to_string(),
+            date: normalize_date(None::<&str>),
+            temperature: self.fahrenheit,
+            humidity: Some(45),
+            description: Some("Balcony".to_string()),
+            station_id: Some("balcony-1".to_string()),
+            ..Default::default()
+        })
+    }
+
+    async fn health_check(&self, _provider_key: Option<&str>) -> Result<Duration> {
+        Ok(Duration::ZERO)
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://example.com",
+            key_format: "Not required",
+            free_tier: "Unlimited",
+            capabilities: &["current"],
+            example: "weather get Balcony",
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut registry = ProviderRegistry::with_builtin();
+    registry.register("balcony", Arc::new(BalconyThermometer { fahrenheit: 68.5 }));
+
+    println!(
+        "Registered providers: {}",
+        registry.ids().collect::<Vec<_>>().join(", ")
+    );
+
+    for id in ["balcony", "mock"] {
+        let provider = registry.get(id).expect("registered above");
+        println!("{id}: {}", provider.get_weather(None, "Kyiv").await?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+//! Fetches the weather of the next days concurrently, sharing one provider between tasks.
+//!
+//! Run with `cargo run -p weather_providers --example forecast`.
+
+use ::std::sync::Arc;
+use ::weather_providers::{
+    Provider, Result, WeatherProvider, create_provider, parse_relative_date,
+};
+
+const DAYS: u32 = 3;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let provider: Arc<dyn WeatherProvider> = Arc::from(create_provider(Provider::Mock));
+
+    let tasks = (1..=DAYS)
+        .map(|day| {
+            let provider = provider.clone();
+            tokio::spawn(async move {
+                // Relative dates are resolved in the local timezone
+                let date = parse_relative_date(&format!("+{day}d"))?
+                    .format("%Y-%m-%d")
+                    .to_string();
+                let Some(forecast) = provider.as_forecast() else {
+                    return Err("The provider does not forecast.".into());
+                };
+                forecast.get_forecast(None, "London", &date).await
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for task in tasks {
+        let weather = task.await.map_err(|e| e.to_string())??;
+        println!("{}: {:.1}°F", weather.date, weather.temperature);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+//! Fetches weather from OpenWeather and shows how to handle the library errors.
+//!
+//! Run with `OPENWEATHER_API_KEY=<API_KEY> cargo run -p weather_providers --example real_provider [LOCATION]`.
+//! Without the key, the example explains how to get one and exits.
+
+use ::weather_providers::{Error, Provider, create_provider};
+
+#[tokio::main]
+async fn main() {
+    let Ok(api_key) = std::env::var("OPENWEATHER_API_KEY") else {
+        let info = create_provider(Provider::OpenWeather).info();
+        println!("OPENWEATHER_API_KEY is not set, skipping.");
+        println!("Get a key at {} ({}).", info.signup_url, info.free_tier);
+        return;
+    };
+    let location = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "London".to_string());
+
+    let provider = create_provider(Provider::OpenWeather);
+
+    match provider.get_weather(Some(&api_key), &location).await {
+        Ok(weather) => println!("{weather}"),
+        Err(Error::InvalidApiKey(provider)) => {
+            eprintln!("The key was rejected by '{provider}'. Check OPENWEATHER_API_KEY.");
+        }
+        Err(e @ Error::UnknownLocation { .. }) => eprintln!("{e}. Try a more specific name."),
+        Err(Error::RateLimited(_)) => eprintln!("Too many requests, try again in a minute."),
+        Err(e) => eprintln!("Request failed: {e}"),
+    }
+}
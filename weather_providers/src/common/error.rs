@@ -1,4 +1,5 @@
-use ::std::borrow::Cow;
+use ::serde::Serialize;
+use ::std::{borrow::Cow, ops::Deref, sync::Arc};
 
 /// The central error type for the library.
 #[derive(Debug, thiserror::Error)]
@@ -12,11 +13,234 @@ pub enum Error {
     // #[error("I/O error: {0}")]
     // Io(#[from] std::io::Error),
 
+    /// The provider requires an API key (or credentials) that was not given. The message
+    /// tells how to set it.
+    #[error("{0}")]
+    MissingApiKey(Cow<'static, str>),
+
+    /// The provider rejected the API key (HTTP 401/403).
+    #[error("Invalid API key for '{0}'")]
+    InvalidApiKey(Cow<'static, str>),
+
+    /// The provider does not know the requested location (HTTP 404).
+    #[error("Location not found by '{0}'")]
+    LocationNotFound(Cow<'static, str>),
+
+    /// The geocoding API returned no usable match for the query.
+    #[error("Location not found: '{query}' ('{provider}' geocoding returned no valid match)")]
+    UnknownLocation {
+        provider: Cow<'static, str>,
+        query: String,
+    },
+
+    /// The geocoding request itself failed (network failure, server error, invalid response).
+    #[error("Failed to geocode '{query}' with '{provider}': {source}")]
+    GeocodingFailed {
+        provider: Cow<'static, str>,
+        query: String,
+        source: Box<Error>,
+    },
+
+    /// The provider has no data for the requested date.
+    #[error("'{provider}' has no data for {date}: {reason}")]
+    UnsupportedDate {
+        provider: Cow<'static, str>,
+        date: String,
+        reason: Cow<'static, str>,
+    },
+
+    /// The provider rejected the request because of its rate limit (HTTP 429).
+    #[error("Rate limit exceeded for '{0}'. Try again later")]
+    RateLimited(Cow<'static, str>),
+
+    /// The provider failed with a server error (HTTP 5xx), holding the provider name.
+    #[error("Provider server error: try again later")]
+    ServerError(Cow<'static, str>),
+
+    /// The provider did not complete the request in time.
+    #[error("'{provider}' did not respond within {seconds} seconds. Try again later")]
+    Timeout {
+        provider: Cow<'static, str>,
+        seconds: u64,
+    },
+
+    /// Network access is disabled, so the provider was not contacted (see `set_offline`).
+    #[error("Network access is disabled: '{0}' was not contacted (offline mode)")]
+    OfflineMode(Cow<'static, str>),
+
+    /// The request was redirected to another host, e.g. to the login page of a captive portal.
+    #[error("Request was redirected to {0} — are you behind a captive portal?")]
+    Redirected(String),
+
+    /// The response body could not be parsed. Holds the status and the body as received, so
+    /// the unexpected payload can be inspected; only its beginning is displayed.
+    ///
+    /// The parse error is part of the message rather than the `source` of the error, so it is
+    /// not repeated when the error chain is printed.
+    #[error(
+        "Failed to parse the response of '{provider}' (HTTP {status}): {cause}. Body: {}",
+        excerpt(body)
+    )]
+    ResponseParseFailed {
+        provider: Cow<'static, str>,
+        status: u16,
+        body: String,
+        cause: serde_json::Error,
+    },
+
     /// Represents errors occurring during HTTP requests.
     #[error("HTTP error: {0}")]
     Reqwest(#[from] reqwest::Error),
 }
 
+/// A machine-readable category of an `Error`, e.g. for the responses of an API built on the
+/// library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The provider requires an API key that was not given.
+    MissingKey,
+    /// The provider rejected the API key.
+    InvalidKey,
+    /// The location is unknown.
+    NotFound,
+    /// The provider rate limit was exceeded.
+    RateLimited,
+    /// The provider could not be reached, or did not respond in time.
+    Network,
+    /// The provider answered with an error.
+    Upstream,
+    /// The response of the provider could not be decoded.
+    Decode,
+    /// The provider does not support the request, e.g. its date.
+    Unsupported,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorCode {
+    /// Returns the code in snake case, e.g. "rate_limited".
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::MissingKey => "missing_key",
+            ErrorCode::InvalidKey => "invalid_key",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::Network => "network",
+            ErrorCode::Upstream => "upstream",
+            ErrorCode::Decode => "decode",
+            ErrorCode::Unsupported => "unsupported",
+            ErrorCode::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Error {
+    /// Returns the category of the error.
+    ///
+    /// A failed geocoding request has the code of its cause. HTTP errors are categorized by
+    /// their status if they have one, as network failures otherwise.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Any(_) => ErrorCode::Other,
+            Error::MissingApiKey(_) => ErrorCode::MissingKey,
+            Error::InvalidApiKey(_) => ErrorCode::InvalidKey,
+            Error::LocationNotFound(_) | Error::UnknownLocation { .. } => ErrorCode::NotFound,
+            Error::GeocodingFailed { source, .. } => source.code(),
+            Error::UnsupportedDate { .. } => ErrorCode::Unsupported,
+            Error::RateLimited(_) => ErrorCode::RateLimited,
+            Error::ServerError(_) => ErrorCode::Upstream,
+            Error::ResponseParseFailed { .. } => ErrorCode::Decode,
+            Error::Timeout { .. } | Error::OfflineMode(_) | Error::Redirected(_) => {
+                ErrorCode::Network
+            }
+            Error::Reqwest(e) => match e.status().map(|status| status.as_u16()) {
+                Some(401 | 403) => ErrorCode::InvalidKey,
+                Some(404) => ErrorCode::NotFound,
+                Some(429) => ErrorCode::RateLimited,
+                Some(_) => ErrorCode::Upstream,
+                None if e.is_decode() => ErrorCode::Decode,
+                None if e.is_builder() => ErrorCode::Other,
+                None => ErrorCode::Network,
+            },
+        }
+    }
+
+    /// Returns `true` if the same request may succeed later: after a rate limit, a timeout,
+    /// a network failure or a server error.
+    ///
+    /// Network access disabled by the offline mode and redirects to a captive portal need
+    /// action from the user, so they are not retryable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::RateLimited(_) | Error::ServerError(_) | Error::Timeout { .. } => true,
+            Error::GeocodingFailed { source, .. } => source.is_retryable(),
+            Error::Reqwest(e) => match e.status() {
+                Some(status) => status.is_server_error() || status.as_u16() == 429,
+                None => {
+                    // reqwest cannot tell connection failures apart on wasm32
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if e.is_connect() {
+                        return true;
+                    }
+                    e.is_timeout() || e.is_request()
+                }
+            },
+            _ => false,
+        }
+    }
+}
+
+/// The number of characters of a response body shown by `Error::ResponseParseFailed`.
+const BODY_EXCERPT_CHARS: usize = 200;
+
+/// Returns the first `BODY_EXCERPT_CHARS` characters of `body`, ending with an ellipsis if it
+/// was shortened.
+fn excerpt(body: &str) -> Cow<'_, str> {
+    match body.char_indices().nth(BODY_EXCERPT_CHARS) {
+        Some((end, _)) => Cow::Owned(format!("{}…", &body[..end])),
+        None => Cow::Borrowed(body),
+    }
+}
+
+/// An `Error` that is cheap to clone, e.g. to cache a failure or share it between tasks.
+///
+/// It dereferences to the error, so `code()` and `is_retryable()` are available on it.
+#[derive(Debug, Clone)]
+pub struct SharedError(Arc<Error>);
+
+impl From<Error> for SharedError {
+    fn from(error: Error) -> Self {
+        Self(Arc::new(error))
+    }
+}
+
+impl Deref for SharedError {
+    type Target = Error;
+
+    fn deref(&self) -> &Error {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for SharedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for SharedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
 impl From<String> for Error {
     /// Converts an owned `String` into an `Error::Any`.
     fn from(msg: String) -> Self {
@@ -47,3 +271,191 @@ impl From<&'static str> for Error {
 /// }
 /// ```
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(error: Error) -> (ErrorCode, bool) {
+        (error.code(), error.is_retryable())
+    }
+
+    #[test]
+    fn test_codes() {
+        let provider = || Cow::Borrowed("Test");
+
+        assert_eq!(codes("failed".into()), (ErrorCode::Other, false));
+        assert_eq!(
+            codes(Error::MissingApiKey("'Test' API key not set".into())),
+            (ErrorCode::MissingKey, false)
+        );
+        assert_eq!(
+            codes(Error::InvalidApiKey(provider())),
+            (ErrorCode::InvalidKey, false)
+        );
+        assert_eq!(
+            codes(Error::LocationNotFound(provider())),
+            (ErrorCode::NotFound, false)
+        );
+        assert_eq!(
+            codes(Error::UnknownLocation {
+                provider: provider(),
+                query: "Nowhere".to_string(),
+            }),
+            (ErrorCode::NotFound, false)
+        );
+        assert_eq!(
+            codes(Error::UnsupportedDate {
+                provider: provider(),
+                date: "1900-01-01".to_string(),
+                reason: "before the records".into(),
+            }),
+            (ErrorCode::Unsupported, false)
+        );
+        assert_eq!(
+            codes(Error::RateLimited(provider())),
+            (ErrorCode::RateLimited, true)
+        );
+        assert_eq!(
+            codes(Error::ServerError(provider())),
+            (ErrorCode::Upstream, true)
+        );
+        assert_eq!(
+            codes(Error::Timeout {
+                provider: provider(),
+                seconds: 30,
+            }),
+            (ErrorCode::Network, true)
+        );
+        assert_eq!(
+            codes(Error::OfflineMode(provider())),
+            (ErrorCode::Network, false)
+        );
+        assert_eq!(
+            codes(Error::Redirected("portal.example".to_string())),
+            (ErrorCode::Network, false)
+        );
+        assert_eq!(
+            codes(Error::ResponseParseFailed {
+                provider: provider(),
+                status: 200,
+                body: "{}".to_string(),
+                cause: serde_json::from_str::<u8>("{}").unwrap_err(),
+            }),
+            (ErrorCode::Decode, false)
+        );
+
+        // A failed geocoding request has the code of its cause
+        let geocoding = |source| Error::GeocodingFailed {
+            provider: provider(),
+            query: "London".to_string(),
+            source: Box::new(source),
+        };
+        assert_eq!(
+            codes(geocoding(Error::RateLimited(provider()))),
+            (ErrorCode::RateLimited, true)
+        );
+        assert_eq!(
+            codes(geocoding(Error::InvalidApiKey(provider()))),
+            (ErrorCode::InvalidKey, false)
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_http_codes() {
+        use ::wiremock::{Mock, MockServer, ResponseTemplate, matchers::path};
+
+        let server = MockServer::start().await;
+        for status in [401, 403, 404, 429, 500, 503, 418] {
+            Mock::given(path(format!("/{status}")))
+                .respond_with(ResponseTemplate::new(status))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(path("/html"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>"))
+            .mount(&server)
+            .await;
+
+        let status = async |status: u16| {
+            let response = reqwest::get(format!("{}/{status}", server.uri()))
+                .await
+                .unwrap();
+            codes(response.error_for_status().unwrap_err().into())
+        };
+        assert_eq!(status(401).await, (ErrorCode::InvalidKey, false));
+        assert_eq!(status(403).await, (ErrorCode::InvalidKey, false));
+        assert_eq!(status(404).await, (ErrorCode::NotFound, false));
+        assert_eq!(status(429).await, (ErrorCode::RateLimited, true));
+        assert_eq!(status(500).await, (ErrorCode::Upstream, true));
+        assert_eq!(status(503).await, (ErrorCode::Upstream, true));
+        assert_eq!(status(418).await, (ErrorCode::Upstream, false));
+
+        let response = reqwest::get(format!("{}/html", server.uri()))
+            .await
+            .unwrap();
+        let decode = response.json::<serde_json::Value>().await.unwrap_err();
+        assert_eq!(codes(decode.into()), (ErrorCode::Decode, false));
+
+        let builder = reqwest::Client::new().get("not a url").build().unwrap_err();
+        assert_eq!(codes(builder.into()), (ErrorCode::Other, false));
+
+        // Bind an ephemeral port and release it so nothing is listening there
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|l| l.local_addr())
+            .unwrap()
+            .port();
+        let connect = reqwest::get(format!("http://127.0.0.1:{port}"))
+            .await
+            .unwrap_err();
+        assert_eq!(codes(connect.into()), (ErrorCode::Network, true));
+    }
+
+    #[test]
+    fn test_shared_error() {
+        let shared = SharedError::from(Error::RateLimited("Test".into()));
+        let clone = shared.clone();
+
+        assert_eq!(clone.code(), ErrorCode::RateLimited);
+        assert!(clone.is_retryable());
+        assert_eq!(clone.to_string(), shared.to_string());
+        assert!(Arc::ptr_eq(&shared.0, &clone.0));
+    }
+
+    #[test]
+    fn test_response_parse_failed_display() {
+        let error = |body: String| Error::ResponseParseFailed {
+            provider: "Test".into(),
+            status: 200,
+            cause: serde_json::from_str::<u8>(&body).unwrap_err(),
+            body,
+        };
+
+        assert_eq!(
+            error("<html>Maintenance</html>".to_string()).to_string(),
+            "Failed to parse the response of 'Test' (HTTP 200): expected value at line 1 \
+             column 1. Body: <html>Maintenance</html>"
+        );
+
+        // The parse error is shown once, in the message
+        assert!(std::error::Error::source(&error("{".to_string())).is_none());
+
+        // Long bodies are cut after 200 characters, but kept whole in the error
+        let long = format!("<p>{}</p>", "é".repeat(300));
+        let message = error(long.clone()).to_string();
+        assert!(message.ends_with(&format!("Body: <p>{}…", "é".repeat(197))));
+        assert!(
+            matches!(error(long.clone()), Error::ResponseParseFailed { body, .. } if body == long)
+        );
+    }
+
+    #[test]
+    fn test_error_code_names() {
+        assert_eq!(ErrorCode::RateLimited.to_string(), "rate_limited");
+        assert_eq!(
+            serde_json::to_value(ErrorCode::MissingKey).unwrap(),
+            "missing_key"
+        );
+    }
+}
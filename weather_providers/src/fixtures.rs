@@ -0,0 +1,112 @@
+//! # Fixtures
+//!
+//! Raw provider responses saved under `weather_providers/tests/fixtures/` by
+//! `cargo xtask fetch-fixtures`. Each fixture file is named after an entry of `FIXTURES`
+//! (e.g., `weather_api_current.json`) and must deserialize into the response model of that
+//! entry, or parse with its XML parser for the XML responses. This module is an internal developer tool and not part of the stable API.
+
+use crate::{
+    common::*,
+    models::{
+        aemet::AemetForecast,
+        ambient_weather::AmbientWeatherDevice,
+        bom::BomResponse,
+        dmi::{DmiCollection, DmiObservation, DmiStation},
+        eccc::{EcccCollection, EcccHourly, EcccStation},
+        fmi::FmiObservations,
+        meteo_swiss::MeteoSwissCollection,
+        open_meteo::{OpenMeteoAirQualityResponse, OpenMeteoGeoResponse},
+        open_weather::*,
+        sunrise_sunset::SunriseSunsetResponse,
+        weather_api::WeatherApiResponse,
+        world_weather_online::WorldWeatherOnlineResponse,
+    },
+};
+use ::serde::de::DeserializeOwned;
+
+/// A provider response that has a fixture.
+pub struct Fixture {
+    /// The fixture file name without the `.json` extension.
+    pub name: &'static str,
+    check: fn(&str) -> Result<()>,
+}
+
+impl Fixture {
+    /// Deserializes `body` through the response model of the fixture.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the schema mismatch if `body` does not fit the model.
+    pub fn check(&self, body: &str) -> Result<()> {
+        (self.check)(body).map_err(|e| format!("'{}': {e}", self.name).into())
+    }
+}
+
+const fn fixture<T: DeserializeOwned>(name: &'static str) -> Fixture {
+    Fixture {
+        name,
+        check: |json| {
+            serde_json::from_str::<T>(json)
+                .map(|_| ())
+                .map_err(|e| e.to_string().into())
+        },
+    }
+}
+
+/// All known fixtures.
+pub const FIXTURES: &[Fixture] = &[
+    fixture::<Vec<OpenWeatherGeoResponse>>("open_weather_geocoding"),
+    fixture::<OpenWeatherResponse>("open_weather_day_summary"),
+    fixture::<OpenWeatherOneCallResponse>("open_weather_onecall"),
+    fixture::<WeatherApiResponse>("weather_api_current"),
+    fixture::<Vec<AmbientWeatherDevice>>("ambient_weather_devices"),
+    fixture::<OpenMeteoGeoResponse>("open_meteo_geocoding"),
+    fixture::<SunriseSunsetResponse>("sunrise_sunset"),
+    fixture::<WorldWeatherOnlineResponse>("world_weather_online_current"),
+    fixture::<MeteoSwissCollection>("meteo_swiss_current"),
+    fixture::<OpenMeteoAirQualityResponse>("open_meteo_air_quality"),
+    fixture::<EcccCollection<EcccStation>>("eccc_stations"),
+    fixture::<EcccCollection<EcccHourly>>("eccc_hourly"),
+    fixture::<BomResponse>("bom_observations"),
+    // The data of the second stage, with no xtask source: its URL is issued per request
+    fixture::<Vec<AemetForecast>>("aemet_forecast"),
+    // No xtask source either, as the API key is sent in a header
+    fixture::<DmiCollection<DmiStation>>("dmi_stations"),
+    fixture::<DmiCollection<DmiObservation>>("dmi_observations"),
+    // An XML document (`fmi_observations.xml`); xtask only saves JSON
+    Fixture {
+        name: "fmi_observations",
+        check: |xml| FmiObservations::from_xml(xml).map(|_| ()),
+    },
+];
+
+/// Returns the fixture with the given name.
+pub fn find(name: &str) -> Option<&'static Fixture> {
+    FIXTURES.iter().find(|f| f.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let fixture = find("open_meteo_geocoding").unwrap();
+
+        assert!(fixture.check(r#"{"results": []}"#).is_ok());
+
+        let error = fixture.check(r#"{"results": [{"name": 1}]}"#).unwrap_err();
+        assert!(error.to_string().starts_with("'open_meteo_geocoding': "));
+    }
+
+    #[test]
+    fn test_names_are_unique() {
+        for fixture in FIXTURES {
+            assert_eq!(
+                FIXTURES.iter().filter(|f| f.name == fixture.name).count(),
+                1
+            );
+        }
+        assert!(find("unknown").is_none());
+    }
+}
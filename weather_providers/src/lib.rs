@@ -12,7 +12,7 @@
 //! async fn weather() -> Result<()> {
 //!     // Use the enum variant directly
 //!     let weather = create_provider(Provider::Mock)
-//!         .get_weather(Some("mock-api-key"), "London", None)
+//!         .get_weather(Some("mock-api-key"), "London")
 //!         .await?;
 //!
 //!     println!("{}", weather);
@@ -22,21 +22,72 @@
 //! ```
 
 mod common;
+#[doc(hidden)]
+pub mod fixtures;
 mod models;
 mod providers;
+mod registry;
 mod utils;
 
 use crate::providers::*;
 use ::clap::ValueEnum;
-use ::std::fmt::Display;
+use ::std::time::Duration;
+use ::std::{fmt::Display, sync::LazyLock};
 use async_trait::async_trait;
 
 // Re-export commonly used types for easier access
 pub use self::{
-    common::{Error, Result},
-    models::WeatherInfo,
+    common::{Error, ErrorCode, Result, SharedError},
+    models::{
+        Breach, CacheStatus, CloudCoverage, CloudLayer, DataSource, DayParts, DisplayAnnotations,
+        DisplayOptions, ExpositionFormat, FREEZING_POINT_F, GeoLocation, HourlyWeather, Icon,
+        MetricsSample, OpenWeatherEndpoint, PrecipType, PressureTendency, ProviderDescriptor,
+        ProviderInfo, ProviderMetadata, ProviderOptions, SLEET_MAX_F, SUN_PROTECTION_UV_INDEX,
+        SafetyThresholds, TRACE_PRECIPITATION_MM, Unit, WeatherAlert, WeatherInfo, WeatherMeta,
+        WindUnit,
+        mock::{FailKind, MOCK_FAULTS_ENV, MockFaults},
+        render_metrics,
+    },
+    registry::ProviderRegistry,
+    utils::{
+        clock::{Clock, FixedClock, SystemClock},
+        date::{normalize_date, normalize_date_with, parse_date},
+        http::{OFFLINE_ENV, is_offline, set_offline},
+        observer::{RequestObserver, clear_observer, set_observer},
+        pressure::{PressureTracker, STEADY_THRESHOLD_HPA},
+        relative_date::{parse_relative_date, parse_relative_date_with},
+        signing::{HmacSha256Signer, RequestSigner},
+    },
 };
 
+/// The version of this library.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Returns a descriptor of every provider, in the order of the `Provider` variants.
+///
+/// No provider is constructed, so the list is cheap to get and suits tooling that enumerates
+/// what this build supports.
+///
+/// # Examples
+///
+/// ```rust
+/// use weather_providers::available_providers;
+///
+/// for descriptor in available_providers().iter().filter(|d| d.compiled_in) {
+///     println!("{} ({})", descriptor.name, descriptor.id);
+/// }
+/// ```
+pub fn available_providers() -> &'static [ProviderDescriptor] {
+    static DESCRIPTORS: LazyLock<Vec<ProviderDescriptor>> = LazyLock::new(|| {
+        Provider::value_variants()
+            .iter()
+            .map(Provider::descriptor)
+            .collect()
+    });
+
+    &DESCRIPTORS
+}
+
 /// Creates a new weather provider instance based on the given identifier.
 ///
 /// This factory function takes a `Provider` enum variant and returns a boxed trait object
@@ -57,7 +108,7 @@ pub use self::{
 ///
 /// async fn weather() -> Result<()> {
 ///     let weather_info = create_provider(Provider::Mock)
-///         .get_weather(Some("mock-key"), "UK, London", None)
+///         .get_weather(Some("mock-key"), "UK, London")
 ///         .await?;
 ///
 ///     println!("{weather_info}");
@@ -66,22 +117,305 @@ pub use self::{
 /// }
 /// ```
 pub fn create_provider(provider: Provider) -> Box<dyn WeatherProvider> {
+    create_provider_with(provider, &ProviderOptions::default())
+}
+
+/// Creates a new weather provider instance with provider-specific settings.
+///
+/// # Arguments
+///
+/// * `provider` - The enum variant identifying the provider.
+/// * `options` - The settings to apply; settings of other providers are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use weather_providers::{
+///     OpenWeatherEndpoint, Provider, ProviderOptions, create_provider_with,
+/// };
+///
+/// let options = ProviderOptions {
+///     open_weather_endpoint: OpenWeatherEndpoint::OneCall,
+///     ..ProviderOptions::default()
+/// };
+/// let provider = create_provider_with(Provider::OpenWeather, &options);
+/// ```
+pub fn create_provider_with(
+    provider: Provider,
+    options: &ProviderOptions,
+) -> Box<dyn WeatherProvider> {
     match provider {
-        Provider::Mock => Box::new(MockProvider),
+        Provider::Mock => Box::new(MockProvider::with_options(options)),
         Provider::GrpcMock => Box::new(GrpcMockProvider),
-        Provider::OpenWeather => Box::new(OpenWeatherProvider),
-        Provider::WeatherApi => Box::new(WeatherApiProvider),
+        Provider::OpenWeather => Box::new(OpenWeatherProvider::with_options(options)),
+        Provider::WeatherApi => Box::new(WeatherApiProvider::with_options(options)),
+        Provider::AmbientWeather => Box::new(AmbientWeatherProvider::with_options(options)),
+        Provider::SunriseSunset => Box::new(SunriseSunsetProvider::with_options(options)),
+        Provider::Foreca => Box::new(ForecaProvider::with_options(options)),
+        Provider::PurpleAir => Box::new(PurpleAirProvider::with_options(options)),
+        Provider::Era5 => Box::new(Era5Provider::with_options(options)),
+        Provider::HistoricPirateWeather => {
+            Box::new(HistoricPirateWeatherProvider::with_options(options))
+        }
+        Provider::Synoptic => Box::new(SynopticProvider::with_options(options)),
+        Provider::Windy => Box::new(WindyProvider::with_options(options)),
+        Provider::ClimateServ => Box::new(ClimateServProvider::with_options(options)),
+        Provider::WorldWeatherOnline => Box::new(WorldWeatherOnlineProvider::with_options(options)),
+        Provider::Tempest => Box::new(TempestProvider::with_options(options)),
+        Provider::MeteoSwiss => Box::new(MeteoSwissProvider::with_options(options)),
+        Provider::OpenMeteoAq => Box::new(OpenMeteoAqProvider::with_options(options)),
+        Provider::OpenAq => Box::new(OpenAqProvider::with_options(options)),
+        Provider::Aemet => Box::new(AemetProvider::with_options(options)),
+        Provider::Dmi => Box::new(DmiProvider::with_options(options)),
+        Provider::Fmi => Box::new(FmiProvider::with_options(options)),
+        Provider::Eccc => Box::new(EcccProvider::with_options(options)),
+        Provider::Bom => Box::new(BomProvider::with_options(options)),
+    }
+}
+
+/// Creates a geocoding client for the given provider.
+///
+/// Geocoding resolves a free-form address into normalized city/country names and coordinates
+/// using the provider's own geocoding API, so the result reflects how that provider
+/// recognizes the location.
+///
+/// # Errors
+///
+/// Returns an error if the provider does not offer a geocoding API.
+///
+/// # Examples
+///
+/// ```rust
+/// use weather_providers::{create_geocoder, Result, Provider};
+///
+/// async fn locate() -> Result<()> {
+///     let location = create_geocoder(Provider::Mock)?
+///         .geocode(None, "London")
+///         .await?;
+///
+///     println!("{location}");
+///
+///     Ok(())
+/// }
+/// ```
+pub fn create_geocoder(provider: Provider) -> Result<Box<dyn GeocodingClient>> {
+    create_geocoder_with(provider, &ProviderOptions::default())
+}
+
+/// Creates a geocoding client for the given provider with provider-specific settings.
+///
+/// # Arguments
+///
+/// * `provider` - The enum variant identifying the provider.
+/// * `options` - The settings to apply; settings of other providers are ignored.
+///
+/// # Errors
+///
+/// Returns an error if the provider does not offer a geocoding API.
+pub fn create_geocoder_with(
+    provider: Provider,
+    options: &ProviderOptions,
+) -> Result<Box<dyn GeocodingClient>> {
+    match provider {
+        Provider::Mock => Ok(Box::new(MockProvider::with_options(options))),
+        Provider::OpenWeather => Ok(Box::new(OpenWeatherProvider::with_options(options))),
+        Provider::WeatherApi => Ok(Box::new(WeatherApiProvider::with_options(options))),
+        Provider::SunriseSunset => Ok(Box::new(SunriseSunsetProvider::with_options(options))),
+        Provider::Foreca => Ok(Box::new(ForecaProvider::with_options(options))),
+        Provider::Era5 => Ok(Box::new(Era5Provider::with_options(options))),
+        Provider::HistoricPirateWeather => Ok(Box::new(
+            HistoricPirateWeatherProvider::with_options(options),
+        )),
+        Provider::Windy => Ok(Box::new(WindyProvider::with_options(options))),
+        Provider::ClimateServ => Ok(Box::new(ClimateServProvider::with_options(options))),
+        Provider::MeteoSwiss => Ok(Box::new(MeteoSwissProvider::with_options(options))),
+        Provider::OpenMeteoAq => Ok(Box::new(OpenMeteoAqProvider::with_options(options))),
+        Provider::OpenAq => Ok(Box::new(OpenAqProvider::with_options(options))),
+        Provider::Dmi => Ok(Box::new(DmiProvider::with_options(options))),
+        Provider::Eccc => Ok(Box::new(EcccProvider::with_options(options))),
+        Provider::GrpcMock
+        | Provider::AmbientWeather
+        | Provider::PurpleAir
+        | Provider::Synoptic
+        | Provider::WorldWeatherOnline
+        | Provider::Tempest
+        | Provider::Aemet
+        | Provider::Fmi
+        | Provider::Bom => Err(Error::from(format!(
+            "Provider '{provider}' does not support geocoding."
+        ))),
     }
 }
 
-#[async_trait]
+/// Resolves addresses into coordinates using a provider's geocoding API.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait GeocodingClient: Send + Sync {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation>;
+}
+
+/// A source of weather data.
+///
+/// Every provider reports the weather of an address. What it can do beyond that is offered
+/// through the extension traits, reached with the `as_*` accessors: `ForecastProvider` for
+/// future dates, `HistoricalProvider` for past dates, `AlertsProvider` for severe weather
+/// warnings and `HourlyProvider` for the weather hour by hour. A consumer that only needs the
+/// weather requires nothing but this trait.
+///
+/// On `wasm32` the returned futures are not `Send`, since the browser's `fetch` futures are not;
+/// implementations for that target use `#[async_trait(?Send)]`.
+///
+/// # Examples
+///
+/// ```rust
+/// use weather_providers::{Provider, Result, WeatherProvider, create_provider};
+///
+/// async fn temperature(provider: &dyn WeatherProvider, address: &str) -> Result<f32> {
+///     Ok(provider.get_weather(None, address).await?.temperature)
+/// }
+///
+/// async fn london() -> Result<f32> {
+///     temperature(create_provider(Provider::Mock).as_ref(), "London").await
+/// }
+/// ```
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait WeatherProvider: Send + Sync {
-    async fn get_weather(
+    /// Returns the current weather of `address`, or the latest the provider has.
+    ///
+    /// Other dates are requested through `as_forecast` and `as_historical`.
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo>;
+
+    /// Performs a cheap liveness probe against the provider and returns the round-trip latency.
+    ///
+    /// Providers without a remote endpoint return immediately with a zero duration.
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration>;
+
+    /// Returns getting-started information: where to get a key, its format and free-tier limits.
+    fn info(&self) -> ProviderInfo;
+
+    /// Returns the provider as a `ForecastProvider` if it forecasts future dates.
+    fn as_forecast(&self) -> Option<&dyn ForecastProvider> {
+        None
+    }
+
+    /// Returns the provider as a `HistoricalProvider` if it reports past dates.
+    fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+        None
+    }
+
+    /// Returns the provider as an `AlertsProvider` if it reports severe weather warnings.
+    fn as_alerts(&self) -> Option<&dyn AlertsProvider> {
+        None
+    }
+
+    /// Returns the provider as an `HourlyProvider` if it reports the weather hour by hour.
+    fn as_hourly(&self) -> Option<&dyn HourlyProvider> {
+        None
+    }
+}
+
+/// A provider that forecasts the weather of future dates.
+///
+/// # Examples
+///
+/// ```rust
+/// use weather_providers::{Provider, Result, create_provider};
+///
+/// async fn tomorrow() -> Result<()> {
+///     let provider = create_provider(Provider::Mock);
+///     let Some(forecast) = provider.as_forecast() else {
+///         return Err("The provider does not forecast.".into());
+///     };
+///
+///     println!("{}", forecast.get_forecast(None, "London", "2030-06-01").await?);
+///
+///     Ok(())
+/// }
+/// ```
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait ForecastProvider: WeatherProvider {
+    /// Returns the forecast of `address` on a future `date`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedDate` if the date is beyond the range of the forecast.
+    async fn get_forecast(
         &self,
         provider_key: Option<&str>,
         address: &str,
-        date: Option<&str>,
+        date: &str,
     ) -> Result<WeatherInfo>;
+
+    /// Returns the forecasts of `address` on several future `dates`, in the same order.
+    ///
+    /// By default each date is requested with `get_forecast`; providers that forecast several
+    /// days in one response request it only once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the forecast of any of the dates cannot be fetched.
+    async fn get_forecast_days(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        dates: &[String],
+    ) -> Result<Vec<WeatherInfo>> {
+        ::futures::future::try_join_all(
+            dates
+                .iter()
+                .map(|date| self.get_forecast(provider_key, address, date)),
+        )
+        .await
+    }
+}
+
+/// A provider that reports the weather of past dates.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait HistoricalProvider: WeatherProvider {
+    /// Returns the weather of `address` on a past `date`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedDate` if the date is before the provider's records.
+    async fn get_history(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo>;
+}
+
+/// A provider that reports the severe weather warnings issued for a location.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait AlertsProvider: WeatherProvider {
+    /// Returns the warnings in effect or announced at `address`.
+    async fn get_alerts(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+    ) -> Result<Vec<WeatherAlert>>;
+}
+
+/// A provider that reports the weather hour by hour.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait HourlyProvider: WeatherProvider {
+    /// Returns the hours of `address` on `date` (today if `None`), in the local time of the
+    /// location and in chronological order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provider has no hours of the date, e.g. beyond its forecast.
+    async fn get_hourly(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<Vec<HourlyWeather>>;
 }
 
 /// The type of weather provider.
@@ -92,11 +426,52 @@ pub enum Provider {
     /// A mock provider for testing or offline use.
     Mock,
     /// A mock provider for testing or offline use using gRPC.
+    ///
+    /// Without the `grpc` feature the server is never contacted and static data is returned.
     GrpcMock,
     /// The OpenWeatherMap API provider.
     OpenWeather,
     /// A generic WeatherAPI provider (placeholder).
     WeatherApi,
+    /// The Ambient Weather Network API for personal weather stations.
+    AmbientWeather,
+    /// The Sunrise-Sunset.org API, providing daylight times only.
+    SunriseSunset,
+    /// The Foreca Point Forecast API, authorized with OAuth2 client credentials.
+    Foreca,
+    /// The PurpleAir sensor network API, reporting air quality (PM2.5).
+    PurpleAir,
+    /// ERA5 reanalysis from the Copernicus Climate Data Store, retrieved as queued jobs.
+    Era5,
+    /// Historic weather back to 1979 from the Dark Sky compatible Pirate Weather API.
+    HistoricPirateWeather,
+    /// Surface station observations from the Synoptic Data (Mesonet) API.
+    Synoptic,
+    /// GFS model forecasts from the Windy.com Point Forecast API.
+    Windy,
+    /// Agricultural weather from the NASA/SERVIR ClimateServ API, retrieved as polled jobs.
+    ClimateServ,
+    /// Current conditions from the premium WorldWeatherOnline API.
+    WorldWeatherOnline,
+    /// Real-time observations of WeatherFlow Tempest personal weather stations.
+    Tempest,
+    /// Current measurements of the Swiss weather stations from the MeteoSwiss open data.
+    MeteoSwiss,
+    /// Air quality (PM2.5 and the European AQI) from the Open-Meteo Air Quality API.
+    OpenMeteoAq,
+    /// Air quality (PM2.5) from monitoring stations, aggregated by OpenAQ.
+    OpenAq,
+    /// Daily municipality forecasts of the Spanish State Meteorological Agency, fetched in two
+    /// stages.
+    Aemet,
+    /// Observations of the nearest weather station of the Danish Meteorological Institute.
+    Dmi,
+    /// Weather station observations of the Finnish Meteorological Institute, as WFS XML.
+    Fmi,
+    /// Hourly climate data of the Environment and Climate Change Canada weather stations.
+    Eccc,
+    /// Latest observations of the Australian Bureau of Meteorology weather stations.
+    Bom,
 }
 
 impl Display for Provider {
@@ -110,23 +485,385 @@ impl Provider {
         matches!(self, Provider::Mock)
     }
 
-    pub fn id(&self) -> &'static str {
+    /// Returns structured information about the provider.
+    pub fn metadata(&self) -> ProviderMetadata {
         match self {
-            Provider::Mock => "mock",
-            Provider::GrpcMock => "grpc",
-            Provider::OpenWeather => "ow",
-            Provider::WeatherApi => "wa",
+            Provider::Mock => ProviderMetadata {
+                id: "mock",
+                name: "MockWeather",
+                website: "https://github.com/AnatoliiShliakhto/weather",
+                requires_key: false,
+                supports_history: true,
+                supports_forecast: true,
+                requires_geocoding: false,
+                wasm_compatible: true,
+                notes: None,
+            },
+            Provider::GrpcMock => ProviderMetadata {
+                id: "grpc",
+                name: "GrpcMockWeather",
+                website: "https://github.com/AnatoliiShliakhto/weather",
+                requires_key: false,
+                supports_history: true,
+                supports_forecast: true,
+                requires_geocoding: false,
+                wasm_compatible: false,
+                notes: None,
+            },
+            Provider::OpenWeather => ProviderMetadata {
+                id: "ow",
+                name: "OpenWeather",
+                website: "https://openweathermap.org",
+                requires_key: true,
+                supports_history: true,
+                supports_forecast: true,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: None,
+            },
+            Provider::WeatherApi => ProviderMetadata {
+                id: "wa",
+                name: "WeatherApi",
+                website: "https://www.weatherapi.com",
+                requires_key: true,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: None,
+            },
+            Provider::AmbientWeather => ProviderMetadata {
+                id: "amw",
+                name: "AmbientWeather",
+                website: "https://ambientweather.net",
+                requires_key: true,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: false,
+                wasm_compatible: true,
+                notes: Some(
+                    "The address must be the device MAC address in format XX:XX:XX:XX:XX:XX. \
+                     The key is '<API_KEY>:<APPLICATION_KEY>'.",
+                ),
+            },
+            Provider::SunriseSunset => ProviderMetadata {
+                id: "ss",
+                name: "SunriseSunset",
+                website: "https://sunrise-sunset.org",
+                requires_key: false,
+                supports_history: true,
+                supports_forecast: true,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: Some("Provides sunrise and sunset times only, without weather data."),
+            },
+            Provider::Foreca => ProviderMetadata {
+                id: "fc",
+                name: "Foreca",
+                website: "https://developer.foreca.com",
+                requires_key: true,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: Some("The key is '<CLIENT_ID>:<CLIENT_SECRET>'."),
+            },
+            Provider::PurpleAir => ProviderMetadata {
+                id: "pa",
+                name: "PurpleAir",
+                website: "https://www2.purpleair.com",
+                requires_key: true,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: false,
+                wasm_compatible: true,
+                notes: Some(
+                    "The address must be the numeric sensor index. Reports air quality \
+                     (PM2.5 and AQI), plus the temperature and humidity inside the sensor.",
+                ),
+            },
+            Provider::Era5 => ProviderMetadata {
+                id: "era5",
+                name: "Era5",
+                website: "https://cds.climate.copernicus.eu",
+                requires_key: true,
+                supports_history: true,
+                supports_forecast: false,
+                requires_geocoding: true,
+                wasm_compatible: false,
+                notes: Some(
+                    "The key is '<UID>:<API_KEY>'. Reanalysis is published about 5 days behind \
+                     today, and retrievals are queued, so a request may take minutes.",
+                ),
+            },
+            Provider::HistoricPirateWeather => ProviderMetadata {
+                id: "pwh",
+                name: "HistoricPirateWeather",
+                website: "https://pirateweather.net",
+                requires_key: true,
+                supports_history: true,
+                supports_forecast: false,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: Some(
+                    "Reports the conditions at midnight (UTC) of dates from 1979 to today.",
+                ),
+            },
+            Provider::Synoptic => ProviderMetadata {
+                id: "sy",
+                name: "Synoptic",
+                website: "https://synopticdata.com",
+                requires_key: true,
+                supports_history: true,
+                supports_forecast: false,
+                requires_geocoding: false,
+                wasm_compatible: true,
+                notes: Some(
+                    "The address must be a station ID (e.g., 'KSLC'); stations are mostly in \
+                     North America. Past dates go back as far as the station's record, with \
+                     the observation nearest to noon (UTC).",
+                ),
+            },
+            Provider::Windy => ProviderMetadata {
+                id: "wd",
+                name: "Windy",
+                website: "https://api.windy.com",
+                requires_key: true,
+                supports_history: false,
+                supports_forecast: true,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: Some(
+                    "Reports the GFS model forecast nearest to now for today, and to noon \
+                     (UTC) for later dates, about 10 days ahead. Trial keys return shuffled \
+                     data.",
+                ),
+            },
+            Provider::ClimateServ => ProviderMetadata {
+                id: "cs",
+                name: "ClimateServ",
+                website: "https://climateserv.servirglobal.net",
+                requires_key: false,
+                supports_history: true,
+                supports_forecast: false,
+                requires_geocoding: true,
+                wasm_compatible: false,
+                notes: Some(
+                    "Reports the daily temperature and relative humidity from NASA satellite \
+                     data. Requests are processed as jobs and time out after 30 seconds.",
+                ),
+            },
+            Provider::WorldWeatherOnline => ProviderMetadata {
+                id: "wwo",
+                name: "WorldWeatherOnline",
+                website: "https://www.worldweatheronline.com",
+                requires_key: true,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: false,
+                wasm_compatible: true,
+                notes: Some(
+                    "Reports the current conditions only. The API resolves the address itself \
+                     and reports the nearest area it knows.",
+                ),
+            },
+            Provider::Tempest => ProviderMetadata {
+                id: "tw",
+                name: "Tempest",
+                website: "https://tempestwx.com",
+                requires_key: true,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: false,
+                wasm_compatible: true,
+                notes: Some(
+                    "The address must be the numeric station ID. The key is a personal access \
+                     token, which gives access to the stations of its account.",
+                ),
+            },
+            Provider::MeteoSwiss => ProviderMetadata {
+                id: "msw",
+                name: "MeteoSwiss",
+                website: "https://www.meteoswiss.admin.ch",
+                requires_key: false,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: Some(
+                    "Reports the latest 10-minute measurements of the station nearest to the \
+                     address, for locations in Switzerland only.",
+                ),
+            },
+            Provider::OpenMeteoAq => ProviderMetadata {
+                id: "omaq",
+                name: "OpenMeteoAirQuality",
+                website: "https://open-meteo.com/en/docs/air-quality-api",
+                requires_key: false,
+                supports_history: true,
+                supports_forecast: true,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: Some(
+                    "Reports air quality only (PM2.5, its US AQI and the European AQI band) at \
+                     the first hour of the date, without temperature.",
+                ),
+            },
+            Provider::OpenAq => ProviderMetadata {
+                id: "oaq",
+                name: "OpenAQ",
+                website: "https://openaq.org",
+                requires_key: false,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: Some(
+                    "Reports the latest PM2.5 of the nearest monitoring station within 25 km, \
+                     without temperature. The key is optional, but the v3 API may reject \
+                     requests without one.",
+                ),
+            },
+            Provider::Aemet => ProviderMetadata {
+                id: "ae",
+                name: "AEMET",
+                website: "https://opendata.aemet.es",
+                requires_key: true,
+                supports_history: false,
+                supports_forecast: true,
+                requires_geocoding: false,
+                wasm_compatible: false,
+                notes: Some(
+                    "The address must be the 5-digit INE code of a Spanish municipality (e.g., \
+                     '28079' for Madrid). Reports the maximum temperature and humidity of today \
+                     and the next 6 days; the data is polled, so a request takes a few seconds.",
+                ),
+            },
+            Provider::Dmi => ProviderMetadata {
+                id: "dmi",
+                name: "DMI",
+                website: "https://opendatadocs.dmi.govcloud.dk",
+                requires_key: true,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: Some(
+                    "Reports the latest temperature and humidity of the DMI station nearest to \
+                     the address, in Denmark and Greenland only.",
+                ),
+            },
+            Provider::Fmi => ProviderMetadata {
+                id: "fmi",
+                name: "FMI",
+                website: "https://en.ilmatieteenlaitos.fi/open-data",
+                requires_key: false,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: false,
+                wasm_compatible: true,
+                notes: Some(
+                    "Reports the latest temperature and humidity of the FMI station nearest to \
+                     the address, which FMI resolves itself; Finnish places only.",
+                ),
+            },
+            Provider::Eccc => ProviderMetadata {
+                id: "ec",
+                name: "ECCC",
+                website: "https://api.weather.gc.ca",
+                requires_key: false,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: true,
+                wasm_compatible: true,
+                notes: Some(
+                    "The address is a climate station ID (e.g., '6158355' for Toronto City) or \
+                     a Canadian place, of which the nearest active station is used. Reports the \
+                     latest hourly temperature and humidity of the climate archive.",
+                ),
+            },
+            Provider::Bom => ProviderMetadata {
+                id: "bom",
+                name: "BOM",
+                website: "https://www.bom.gov.au/catalogue/data-feeds.shtml",
+                requires_key: false,
+                supports_history: false,
+                supports_forecast: false,
+                requires_geocoding: false,
+                wasm_compatible: true,
+                notes: Some(
+                    "The address is a BOM station code: the observations product and the WMO \
+                     number of the station (e.g., 'IDN60901.94768' for Sydney - Observatory \
+                     Hill). Reports the latest temperature and humidity of the station.",
+                ),
+            },
         }
     }
 
-    pub fn name(&self) -> &'static str {
+    /// Returns the descriptor of the provider, as listed by `available_providers`.
+    pub fn descriptor(&self) -> ProviderDescriptor {
+        let metadata = self.metadata();
+        let capabilities = [
+            (true, "current"),
+            (metadata.supports_history, "history"),
+            (metadata.supports_forecast, "forecast"),
+            (metadata.requires_geocoding, "geocoding"),
+        ]
+        .into_iter()
+        .filter_map(|(supported, capability)| supported.then_some(capability))
+        .collect();
+
+        ProviderDescriptor {
+            provider: self.clone(),
+            id: metadata.id,
+            name: metadata.name,
+            requires_key: metadata.requires_key,
+            capabilities,
+            compiled_in: self.is_compiled_in(),
+        }
+    }
+
+    /// Returns `true` unless the provider depends on a cargo feature that is disabled.
+    fn is_compiled_in(&self) -> bool {
         match self {
-            Provider::Mock => "MockWeather",
-            Provider::GrpcMock => "GrpcMockWeather",
-            Provider::OpenWeather => "OpenWeather",
-            Provider::WeatherApi => "WeatherApi",
+            Provider::GrpcMock => cfg!(feature = "grpc"),
+            Provider::Mock
+            | Provider::OpenWeather
+            | Provider::WeatherApi
+            | Provider::AmbientWeather
+            | Provider::SunriseSunset
+            | Provider::Foreca
+            | Provider::PurpleAir
+            | Provider::Era5
+            | Provider::HistoricPirateWeather
+            | Provider::Synoptic
+            | Provider::Windy
+            | Provider::ClimateServ
+            | Provider::WorldWeatherOnline
+            | Provider::Tempest
+            | Provider::MeteoSwiss
+            | Provider::OpenMeteoAq
+            | Provider::OpenAq
+            | Provider::Aemet
+            | Provider::Dmi
+            | Provider::Fmi
+            | Provider::Eccc
+            | Provider::Bom => true,
         }
     }
+
+    pub fn id(&self) -> &'static str {
+        self.metadata().id
+    }
+
+    /// Returns `true` if addresses are geocoded before the weather request.
+    pub fn requires_geocoding(&self) -> bool {
+        self.metadata().requires_geocoding
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.metadata().name
+    }
 }
 
 impl TryFrom<&str> for Provider {
@@ -138,6 +875,25 @@ impl TryFrom<&str> for Provider {
             "grpcmockweather" | "grpc" => Ok(Provider::GrpcMock),
             "openweather" | "ow" => Ok(Provider::OpenWeather),
             "weatherapi" | "wa" => Ok(Provider::WeatherApi),
+            "ambientweather" | "amw" => Ok(Provider::AmbientWeather),
+            "sunrisesunset" | "ss" => Ok(Provider::SunriseSunset),
+            "foreca" | "fc" => Ok(Provider::Foreca),
+            "purpleair" | "pa" => Ok(Provider::PurpleAir),
+            "era5" => Ok(Provider::Era5),
+            "historicpirateweather" | "pwh" => Ok(Provider::HistoricPirateWeather),
+            "synoptic" | "sy" => Ok(Provider::Synoptic),
+            "windy" | "wd" => Ok(Provider::Windy),
+            "climateserv" | "cs" => Ok(Provider::ClimateServ),
+            "worldweatheronline" | "wwo" => Ok(Provider::WorldWeatherOnline),
+            "tempest" | "weatherflow" | "tw" => Ok(Provider::Tempest),
+            "meteoswiss" | "msw" => Ok(Provider::MeteoSwiss),
+            "openmeteoairquality" | "omaq" => Ok(Provider::OpenMeteoAq),
+            "openaq" | "oaq" => Ok(Provider::OpenAq),
+            "aemet" | "ae" => Ok(Provider::Aemet),
+            "dmi" => Ok(Provider::Dmi),
+            "fmi" => Ok(Provider::Fmi),
+            "eccc" | "ec" => Ok(Provider::Eccc),
+            "bom" => Ok(Provider::Bom),
             _ => Err(Error::from(format!(
                 "Unknown provider: '{s}'.\nAvailable providers: {}",
                 Provider::value_variants()
@@ -171,15 +927,241 @@ mod tests {
         assert_eq!(Provider::try_from("mockweather").ok(), Some(Provider::Mock));
         assert_eq!(Provider::try_from("mock").ok(), Some(Provider::Mock));
 
+        assert_eq!(
+            Provider::try_from("amw").ok(),
+            Some(Provider::AmbientWeather)
+        );
+        assert_eq!(Provider::try_from("ss").ok(), Some(Provider::SunriseSunset));
+        assert_eq!(Provider::try_from("fc").ok(), Some(Provider::Foreca));
+        assert_eq!(Provider::try_from("pa").ok(), Some(Provider::PurpleAir));
+        assert_eq!(Provider::try_from("ERA5").ok(), Some(Provider::Era5));
+        assert_eq!(
+            Provider::try_from("pwh").ok(),
+            Some(Provider::HistoricPirateWeather)
+        );
+        assert_eq!(Provider::try_from("sy").ok(), Some(Provider::Synoptic));
+        assert_eq!(Provider::try_from("wd").ok(), Some(Provider::Windy));
+        assert_eq!(Provider::try_from("cs").ok(), Some(Provider::ClimateServ));
+        assert_eq!(
+            Provider::try_from("wwo").ok(),
+            Some(Provider::WorldWeatherOnline)
+        );
+        assert_eq!(Provider::try_from("tw").ok(), Some(Provider::Tempest));
+        assert_eq!(
+            Provider::try_from("WeatherFlow").ok(),
+            Some(Provider::Tempest)
+        );
+        assert_eq!(Provider::try_from("msw").ok(), Some(Provider::MeteoSwiss));
+        assert_eq!(
+            Provider::try_from("MeteoSwiss").ok(),
+            Some(Provider::MeteoSwiss)
+        );
+        assert_eq!(Provider::try_from("omaq").ok(), Some(Provider::OpenMeteoAq));
+        assert_eq!(Provider::try_from("oaq").ok(), Some(Provider::OpenAq));
+        assert_eq!(Provider::try_from("OpenAQ").ok(), Some(Provider::OpenAq));
+        assert_eq!(Provider::try_from("ae").ok(), Some(Provider::Aemet));
+        assert_eq!(Provider::try_from("AEMET").ok(), Some(Provider::Aemet));
+        assert_eq!(Provider::try_from("DMI").ok(), Some(Provider::Dmi));
+        assert_eq!(Provider::try_from("fmi").ok(), Some(Provider::Fmi));
+        assert_eq!(Provider::try_from("ec").ok(), Some(Provider::Eccc));
+        assert_eq!(Provider::try_from("ECCC").ok(), Some(Provider::Eccc));
+        assert_eq!(Provider::try_from("BOM").ok(), Some(Provider::Bom));
+        assert_eq!(
+            Provider::try_from("OpenMeteoAirQuality").ok(),
+            Some(Provider::OpenMeteoAq)
+        );
+
         assert!(Provider::try_from("").is_err());
         assert!(Provider::try_from("unknown").is_err());
     }
 
+    #[test]
+    fn test_provider_metadata_complete() {
+        for provider in Provider::value_variants() {
+            let metadata = provider.metadata();
+
+            assert!(!metadata.id.is_empty());
+            assert!(!metadata.name.is_empty());
+            assert!(metadata.website.starts_with("https://"));
+
+            // Both the ID and the name must parse back into the same provider
+            assert_eq!(
+                Provider::try_from(metadata.id).ok().as_ref(),
+                Some(provider)
+            );
+            assert_eq!(
+                Provider::try_from(metadata.name).ok().as_ref(),
+                Some(provider)
+            );
+        }
+    }
+
+    #[test]
+    fn test_available_providers_in_sync() {
+        let descriptors = available_providers();
+
+        // Every variant has exactly one descriptor, in the order of the variants
+        assert_eq!(
+            descriptors
+                .iter()
+                .map(|d| d.provider.clone())
+                .collect::<Vec<_>>(),
+            Provider::value_variants()
+        );
+        for descriptor in descriptors {
+            let metadata = descriptor.provider.metadata();
+
+            assert_eq!(descriptor.id, metadata.id);
+            assert_eq!(descriptor.name, metadata.name);
+            assert_eq!(descriptor.requires_key, metadata.requires_key);
+            assert_eq!(descriptor.capabilities.first(), Some(&"current"));
+            assert_eq!(
+                descriptor.capabilities.contains(&"history"),
+                metadata.supports_history
+            );
+        }
+    }
+
+    #[test]
+    fn test_capability_traits_match_metadata() {
+        for provider in Provider::value_variants() {
+            let metadata = provider.metadata();
+            let weather_provider = create_provider(provider.clone());
+
+            assert_eq!(
+                weather_provider.as_forecast().is_some(),
+                metadata.supports_forecast,
+                "{provider}"
+            );
+            assert_eq!(
+                weather_provider.as_historical().is_some(),
+                metadata.supports_history,
+                "{provider}"
+            );
+        }
+
+        assert!(create_provider(Provider::OpenWeather).as_alerts().is_some());
+        assert!(create_provider(Provider::Mock).as_alerts().is_none());
+
+        assert!(create_provider(Provider::Mock).as_hourly().is_some());
+        assert!(create_provider(Provider::OpenWeather).as_hourly().is_some());
+        assert!(
+            create_provider(Provider::SunriseSunset)
+                .as_hourly()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_compiled_in() {
+        let compiled_in = |provider: Provider| {
+            available_providers()
+                .iter()
+                .find(|d| d.provider == provider)
+                .map(|d| d.compiled_in)
+        };
+
+        assert_eq!(compiled_in(Provider::Mock), Some(true));
+        assert_eq!(
+            compiled_in(Provider::GrpcMock),
+            Some(cfg!(feature = "grpc"))
+        );
+    }
+
+    #[test]
+    fn test_requires_geocoding() {
+        assert!(Provider::OpenWeather.requires_geocoding());
+        assert!(Provider::WeatherApi.requires_geocoding());
+        assert!(Provider::SunriseSunset.requires_geocoding());
+        assert!(Provider::Foreca.requires_geocoding());
+        assert!(Provider::Era5.requires_geocoding());
+        assert!(Provider::HistoricPirateWeather.requires_geocoding());
+        assert!(Provider::Windy.requires_geocoding());
+        assert!(Provider::ClimateServ.requires_geocoding());
+        assert!(Provider::MeteoSwiss.requires_geocoding());
+        assert!(Provider::OpenMeteoAq.requires_geocoding());
+        assert!(Provider::OpenAq.requires_geocoding());
+        assert!(Provider::Dmi.requires_geocoding());
+        assert!(Provider::Eccc.requires_geocoding());
+
+        // Addressed by device MAC address, sensor index, station ID or municipality code, or
+        // not remote at all
+        assert!(!Provider::AmbientWeather.requires_geocoding());
+        assert!(!Provider::PurpleAir.requires_geocoding());
+        assert!(!Provider::Synoptic.requires_geocoding());
+        assert!(!Provider::Tempest.requires_geocoding());
+        assert!(!Provider::Aemet.requires_geocoding());
+        assert!(!Provider::Bom.requires_geocoding());
+        assert!(!Provider::Mock.requires_geocoding());
+        assert!(!Provider::GrpcMock.requires_geocoding());
+        // Resolves the address itself
+        assert!(!Provider::WorldWeatherOnline.requires_geocoding());
+        assert!(!Provider::Fmi.requires_geocoding());
+    }
+
+    #[test]
+    fn test_wasm_compatible() {
+        // Everything goes through reqwest, which uses `fetch` on wasm32, except the gRPC client
+        // and ERA5, ClimateServ and AEMET, which need timers to poll their jobs or data
+        for provider in Provider::value_variants() {
+            assert_eq!(
+                provider.metadata().wasm_compatible,
+                !matches!(
+                    provider,
+                    Provider::GrpcMock | Provider::Era5 | Provider::ClimateServ | Provider::Aemet
+                ),
+                "{provider}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_provider_info_complete() {
+        for provider in Provider::value_variants() {
+            let info = create_provider(provider.clone()).info();
+
+            assert!(
+                reqwest::Url::parse(info.signup_url)
+                    .is_ok_and(|url| url.scheme() == "https" && url.host_str().is_some()),
+                "invalid signup URL for {provider}"
+            );
+            assert!(!info.key_format.is_empty());
+            assert!(!info.free_tier.is_empty());
+            assert!(info.capabilities.contains(&"current"));
+            assert!(info.example.starts_with("weather "));
+        }
+    }
+
     #[test]
     fn test_provider_type_display() {
         assert_eq!(Provider::WeatherApi.to_string(), "WeatherApi");
         assert_eq!(Provider::OpenWeather.to_string(), "OpenWeather");
         assert_eq!(Provider::Mock.to_string(), "MockWeather");
         assert_eq!(Provider::GrpcMock.to_string(), "GrpcMockWeather");
+        assert_eq!(Provider::AmbientWeather.to_string(), "AmbientWeather");
+        assert_eq!(Provider::SunriseSunset.to_string(), "SunriseSunset");
+        assert_eq!(Provider::Foreca.to_string(), "Foreca");
+        assert_eq!(Provider::PurpleAir.to_string(), "PurpleAir");
+        assert_eq!(Provider::Era5.to_string(), "Era5");
+        assert_eq!(
+            Provider::HistoricPirateWeather.to_string(),
+            "HistoricPirateWeather"
+        );
+        assert_eq!(Provider::Synoptic.to_string(), "Synoptic");
+        assert_eq!(Provider::Windy.to_string(), "Windy");
+        assert_eq!(Provider::ClimateServ.to_string(), "ClimateServ");
+        assert_eq!(
+            Provider::WorldWeatherOnline.to_string(),
+            "WorldWeatherOnline"
+        );
+        assert_eq!(Provider::Tempest.to_string(), "Tempest");
+        assert_eq!(Provider::MeteoSwiss.to_string(), "MeteoSwiss");
+        assert_eq!(Provider::OpenMeteoAq.to_string(), "OpenMeteoAirQuality");
+        assert_eq!(Provider::OpenAq.to_string(), "OpenAQ");
+        assert_eq!(Provider::Aemet.to_string(), "AEMET");
+        assert_eq!(Provider::Dmi.to_string(), "DMI");
+        assert_eq!(Provider::Fmi.to_string(), "FMI");
+        assert_eq!(Provider::Eccc.to_string(), "ECCC");
+        assert_eq!(Provider::Bom.to_string(), "BOM");
     }
 }
@@ -0,0 +1,67 @@
+use ::serde::Deserialize;
+
+/// The response of the first stage of an AEMET request: a link to the data, or an error.
+///
+/// Errors are also reported with HTTP 200 and a non-200 `estado`, without `datos`.
+#[derive(Deserialize)]
+pub struct AemetEnvelope {
+    /// A message in Spanish, e.g. "exito" or "API key invalido".
+    pub descripcion: String,
+    /// The status of the request, using the HTTP status codes.
+    pub estado: u16,
+    /// The URL of the data, valid for a few minutes.
+    pub datos: Option<String>,
+}
+
+/// The daily forecast of a municipality, fetched from the `datos` URL.
+#[derive(Deserialize)]
+pub struct AemetForecast {
+    /// The name of the municipality, e.g. "Madrid".
+    pub nombre: String,
+    pub prediccion: AemetPrediction,
+}
+
+#[derive(Deserialize)]
+pub struct AemetPrediction {
+    /// The forecast of each day, starting today.
+    #[serde(default)]
+    pub dia: Vec<AemetDay>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AemetDay {
+    /// The date at local midnight, e.g. "2024-01-15T00:00:00".
+    pub fecha: String,
+    /// The temperature in degrees Celsius.
+    pub temperatura: AemetRange,
+    /// The relative humidity in percent.
+    pub humedad_relativa: AemetRange,
+    /// The sky condition of the day and of its periods (e.g., "00-24", "06-12").
+    #[serde(default)]
+    pub estado_cielo: Vec<AemetSky>,
+    /// The probability of precipitation in percent, of the day and of its periods.
+    #[serde(default)]
+    pub prob_precipitacion: Vec<AemetProbability>,
+}
+
+/// The range of a daily value; only its maximum is reported.
+#[derive(Deserialize)]
+pub struct AemetRange {
+    pub maxima: Option<f32>,
+}
+
+#[derive(Deserialize)]
+pub struct AemetSky {
+    /// The period in local hours, e.g. "00-24"; missing for the last days of the forecast.
+    pub periodo: Option<String>,
+    /// The condition in Spanish, e.g. "Poco nuboso"; empty for periods without a forecast.
+    #[serde(default)]
+    pub descripcion: String,
+}
+
+#[derive(Deserialize)]
+pub struct AemetProbability {
+    pub periodo: Option<String>,
+    pub value: Option<u8>,
+}
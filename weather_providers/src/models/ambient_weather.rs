@@ -0,0 +1,28 @@
+use ::serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct AmbientWeatherDevice {
+    #[serde(rename = "macAddress")]
+    pub mac_address: String,
+    #[serde(default)]
+    pub info: AmbientWeatherDeviceInfo,
+    #[serde(rename = "lastData")]
+    pub last_data: AmbientWeatherData,
+}
+
+#[derive(Deserialize, Default)]
+pub struct AmbientWeatherDeviceInfo {
+    pub name: Option<String>,
+    pub location: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AmbientWeatherData {
+    /// Observation time in milliseconds since the Unix epoch.
+    pub dateutc: i64,
+    pub tempf: f32,
+    pub humidity: u8,
+    #[serde(rename = "feelsLike")]
+    pub feels_like: Option<f32>,
+    pub windspeedmph: Option<f32>,
+}
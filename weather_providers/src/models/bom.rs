@@ -0,0 +1,50 @@
+use ::serde::Deserialize;
+
+/// The observations product of a BOM weather station, e.g. `IDN60901.94768.json`.
+///
+/// Every field of the document sits under a single `observations` object, whose `header` is
+/// an array of one object and whose `data` holds the half-hourly observations, latest first.
+#[derive(Deserialize)]
+pub struct BomResponse {
+    pub observations: BomObservations,
+}
+
+#[derive(Deserialize)]
+pub struct BomObservations {
+    #[serde(default)]
+    pub header: Vec<BomHeader>,
+    #[serde(default)]
+    pub data: Vec<BomObservation>,
+}
+
+#[derive(Deserialize)]
+pub struct BomHeader {
+    /// The name of the station, e.g. "Sydney - Observatory Hill".
+    pub name: String,
+}
+
+/// An observation of the station. Every measurement is `null` when it was not observed.
+#[derive(Deserialize)]
+pub struct BomObservation {
+    /// The WMO number of the station, e.g. `94768`.
+    pub wmo: Option<u32>,
+    pub name: Option<String>,
+    /// The local time of the observation, e.g. `"20240115140000"`.
+    pub local_date_time_full: Option<String>,
+    /// The time of the observation in UTC, e.g. `"20240115030000"`.
+    pub aifstime_utc: Option<String>,
+    /// The air temperature in degrees Celsius.
+    pub air_temp: Option<f32>,
+    /// The apparent temperature in degrees Celsius.
+    pub apparent_t: Option<f32>,
+    /// The relative humidity in percent.
+    pub rel_hum: Option<f32>,
+    /// The mean sea-level pressure in hPa.
+    pub press_msl: Option<f32>,
+    /// The wind speed in km/h.
+    pub wind_spd_kmh: Option<f32>,
+    /// The present weather, e.g. "Showers"; `"-"` if there is nothing to report.
+    pub weather: Option<String>,
+    /// The cloud cover, e.g. "Partly cloudy"; `"-"` if not observed.
+    pub cloud: Option<String>,
+}
@@ -0,0 +1,24 @@
+use ::serde::Deserialize;
+
+/// The data of a completed request: one entry per date of the requested range.
+#[derive(Deserialize)]
+pub struct ClimateServData {
+    #[serde(default)]
+    pub data: Vec<ClimateServEntry>,
+}
+
+#[derive(Deserialize)]
+pub struct ClimateServEntry {
+    pub value: ClimateServValue,
+}
+
+/// The averages over the requested area.
+#[derive(Deserialize)]
+pub struct ClimateServValue {
+    /// The air temperature in degrees Celsius.
+    #[serde(rename = "Temperature", default)]
+    pub temperature: Option<f32>,
+    /// The relative humidity in percent.
+    #[serde(rename = "Relative_Humidity", default)]
+    pub relative_humidity: Option<f32>,
+}
@@ -0,0 +1,695 @@
+use super::{DayParts, PrecipType, WeatherInfo};
+use ::chrono::{DateTime, Utc};
+use ::clap::ValueEnum;
+use ::serde::{Deserialize, Serialize};
+use ::std::fmt::Write;
+
+/// Languages that use a comma as the decimal separator.
+const DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "bg", "cs", "da", "de", "es", "fi", "fr", "it", "nb", "nl", "no", "pl", "pt", "ro", "ru", "sk",
+    "sv", "tr", "uk",
+];
+
+/// The unit used to display temperatures.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    /// Degrees Fahrenheit (the unit returned by all providers).
+    #[default]
+    Fahrenheit,
+    /// Degrees Celsius.
+    Celsius,
+}
+
+impl Unit {
+    /// Returns the display symbol of the unit (e.g., `°F`).
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Fahrenheit => "°F",
+            Unit::Celsius => "°C",
+        }
+    }
+
+    /// Converts a temperature in degrees Fahrenheit into this unit.
+    pub fn from_fahrenheit(&self, fahrenheit: f32) -> f32 {
+        match self {
+            Unit::Fahrenheit => fahrenheit,
+            Unit::Celsius => (fahrenheit - 32.0) * 5.0 / 9.0,
+        }
+    }
+}
+
+/// Miles per hour in a meter per second.
+const MPH_PER_MS: f32 = 2.236_936;
+/// Miles per hour in a kilometer per hour.
+const MPH_PER_KMH: f32 = 0.621_371;
+/// Miles per hour in a knot.
+const MPH_PER_KNOT: f32 = 1.150_779;
+
+/// The unit used to display wind speeds.
+///
+/// Wind speeds are stored in miles per hour (`WeatherInfo::wind_speed_mph`); providers that
+/// report another unit convert it with `to_mph`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize, ValueEnum)]
+pub enum WindUnit {
+    /// Miles per hour.
+    #[default]
+    #[serde(rename = "mph")]
+    #[value(name = "mph")]
+    MilesPerHour,
+    /// Kilometers per hour.
+    #[serde(rename = "kmh", alias = "km/h")]
+    #[value(name = "kmh", alias = "km/h")]
+    KilometersPerHour,
+    /// Meters per second.
+    #[serde(rename = "ms", alias = "m/s")]
+    #[value(name = "ms", alias = "m/s")]
+    MetersPerSecond,
+    /// Knots (nautical miles per hour).
+    #[serde(rename = "knots", alias = "kn")]
+    #[value(name = "knots", alias = "kn")]
+    Knots,
+}
+
+impl WindUnit {
+    /// Returns the display symbol of the unit (e.g., `km/h`).
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            WindUnit::MilesPerHour => "mph",
+            WindUnit::KilometersPerHour => "km/h",
+            WindUnit::MetersPerSecond => "m/s",
+            WindUnit::Knots => "kn",
+        }
+    }
+
+    /// Converts a wind speed in miles per hour into this unit.
+    pub fn from_mph(&self, mph: f32) -> f32 {
+        mph / self.mph_per_unit()
+    }
+
+    /// Converts a wind speed in this unit into miles per hour.
+    pub fn to_mph(&self, value: f32) -> f32 {
+        value * self.mph_per_unit()
+    }
+
+    fn mph_per_unit(&self) -> f32 {
+        match self {
+            WindUnit::MilesPerHour => 1.0,
+            WindUnit::KilometersPerHour => MPH_PER_KMH,
+            WindUnit::MetersPerSecond => MPH_PER_MS,
+            WindUnit::Knots => MPH_PER_KNOT,
+        }
+    }
+}
+
+/// Options controlling how `WeatherInfo` is rendered for humans.
+///
+/// Missing fields fall back to their defaults when deserialized, so partial
+/// configurations are accepted.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DisplayOptions {
+    /// Include the date of the observation, and its time if known.
+    #[serde(alias = "showDate")]
+    pub show_date: bool,
+    /// Include the relative humidity.
+    #[serde(alias = "showHumidity")]
+    pub show_humidity: bool,
+    /// Include the weather description, if the provider returned one.
+    #[serde(alias = "showDescription")]
+    pub show_description: bool,
+    /// The unit used to display temperatures.
+    #[serde(alias = "temperatureUnit")]
+    pub temperature_unit: Unit,
+    /// The locale used for number formatting (e.g., "en-US", "de-DE").
+    pub locale: String,
+    /// Add a second line with the temperatures of the parts of the day, if the provider
+    /// reported them.
+    #[serde(alias = "showPeriods")]
+    pub show_periods: bool,
+    /// Put the emoji of the condition (see `WeatherInfo::condition_emoji`) before the
+    /// description. Only enable it for terminals that can display emoji.
+    #[serde(alias = "showEmoji")]
+    pub show_emoji: bool,
+    /// Include the wind speed, if the provider reported it.
+    #[serde(alias = "showWind")]
+    pub show_wind: bool,
+    /// The unit used to display wind speeds.
+    #[serde(alias = "windUnit")]
+    pub wind_unit: WindUnit,
+    /// Add a line telling where the data came from (see `WeatherMeta`), if known.
+    #[serde(alias = "showMeta")]
+    pub show_meta: bool,
+}
+
+impl Default for DisplayOptions {
+    /// Creates options matching the classic single-line output.
+    fn default() -> Self {
+        Self {
+            show_date: false,
+            show_humidity: true,
+            show_description: true,
+            temperature_unit: Unit::default(),
+            locale: "en-US".to_string(),
+            show_periods: false,
+            show_emoji: false,
+            show_wind: false,
+            wind_unit: WindUnit::default(),
+            show_meta: false,
+        }
+    }
+}
+
+impl DisplayOptions {
+    /// Formats a number with one decimal place using the locale's decimal separator.
+    pub fn format_decimal(&self, value: f32) -> String {
+        let formatted = format!("{value:.1}");
+
+        let language = self
+            .locale
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if DECIMAL_COMMA_LANGUAGES.contains(&language.as_str()) {
+            formatted.replace('.', ",")
+        } else {
+            formatted
+        }
+    }
+}
+
+/// Extra text appended after individual values by `WeatherInfo::format_for_display_with`.
+///
+/// Applications use annotations to decorate values with information the library does not
+/// know about, such as trends compared to earlier observations.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DisplayAnnotations {
+    /// Appended after the temperature (e.g., " ↑ (+2.4 since 08:00)").
+    pub temperature: Option<String>,
+    /// Appended after the humidity.
+    pub humidity: Option<String>,
+}
+
+impl WeatherInfo {
+    /// Formats the weather information as a single human-readable line.
+    ///
+    /// # Arguments
+    ///
+    /// * `opts` - Controls which fields are shown, the temperature unit, and number formatting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use weather_providers::{DisplayOptions, Unit, WeatherInfo};
+    ///
+    /// fn show(info: &WeatherInfo) {
+    ///     let opts = DisplayOptions {
+    ///         temperature_unit: Unit::Celsius,
+    ///         ..DisplayOptions::default()
+    ///     };
+    ///     println!("{}", info.format_for_display(&opts));
+    /// }
+    /// ```
+    pub fn format_for_display(&self, opts: &DisplayOptions) -> String {
+        self.format_for_display_with(opts, &DisplayAnnotations::default())
+    }
+
+    /// Formats the weather information like `format_for_display`, appending the given
+    /// annotations after the corresponding values.
+    pub fn format_for_display_with(
+        &self,
+        opts: &DisplayOptions,
+        annotations: &DisplayAnnotations,
+    ) -> String {
+        // Station and sensor providers do not always report a country
+        let location = [self.country.as_str(), self.city.as_str()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut out = format!("Weather in '{location}'");
+
+        if let Some(station_id) = &self.station_id {
+            let _ = write!(out, " [station: {station_id}]");
+        }
+
+        if opts.show_date {
+            let _ = write!(out, " on {}", self.date);
+            if let Some(time) = self.observation_time {
+                let _ = write!(out, " at {} UTC", time.format("%H:%M"));
+            }
+        }
+
+        // Providers without weather observations (e.g., daylight times only) report NaN
+        let has_weather = !self.temperature.is_nan();
+        let mut separator = ": ";
+
+        if has_weather {
+            let temperature = opts.temperature_unit.from_fahrenheit(self.temperature);
+            let _ = write!(
+                out,
+                ": {}{}",
+                opts.format_decimal(temperature),
+                opts.temperature_unit.symbol()
+            );
+            out.push_str(annotations.temperature.as_deref().unwrap_or_default());
+            separator = ", ";
+        }
+
+        if opts.show_description
+            && let Some(desc) = &self.description
+        {
+            out.push_str(separator);
+            if opts.show_emoji
+                && let Some(emoji) = self.condition_emoji()
+            {
+                let _ = write!(out, "{emoji} ");
+            }
+            out.push_str(desc);
+            separator = ", ";
+        }
+
+        if opts.show_humidity
+            && has_weather
+            && let Some(humidity) = self.humidity
+        {
+            let _ = write!(out, ", Humidity: {humidity}%");
+            out.push_str(annotations.humidity.as_deref().unwrap_or_default());
+        }
+
+        if opts.show_wind
+            && let Some(mph) = self.wind_speed_mph
+        {
+            let _ = write!(
+                out,
+                "{separator}Wind: {} {}",
+                opts.format_decimal(opts.wind_unit.from_mph(mph)),
+                opts.wind_unit.symbol()
+            );
+            separator = ", ";
+        }
+
+        if let Some(aqi) = self.air_quality_index {
+            let _ = write!(out, "{separator}AQI: {aqi}");
+            separator = ", ";
+        }
+
+        for (label, time) in [("Sunrise", &self.sunrise), ("Sunset", &self.sunset)] {
+            if let Some(time) = time {
+                let _ = write!(out, "{separator}{label}: {}", format_time(time));
+                separator = ", ";
+            }
+        }
+
+        if let Some(moon_phase) = self.moon_phase_name() {
+            let _ = write!(out, "{separator}Moon: {moon_phase}");
+        }
+
+        if opts.show_periods
+            && let Some(periods) = &self.periods
+        {
+            out.push('\n');
+            out.push_str(&periods.format_for_display(opts));
+        }
+
+        if self.precip_type == Some(PrecipType::FreezingRain) {
+            out.push_str("\n  ⚠ freezing rain possible");
+        }
+
+        if opts.show_meta
+            && let Some(meta) = &self.meta
+        {
+            let _ = write!(out, "\n  {meta}");
+        }
+
+        out
+    }
+}
+
+impl DayParts {
+    /// Formats the parts of the day as an indented line of four columns, followed by the
+    /// lowest and highest temperature if known.
+    pub fn format_for_display(&self, opts: &DisplayOptions) -> String {
+        let temperature = |fahrenheit| {
+            format!(
+                "{}{}",
+                opts.format_decimal(opts.temperature_unit.from_fahrenheit(fahrenheit)),
+                opts.temperature_unit.symbol()
+            )
+        };
+
+        let mut out = String::from(" ");
+        for (label, value) in [
+            ("Morning", self.morning),
+            ("Afternoon", self.afternoon),
+            ("Evening", self.evening),
+            ("Night", self.night),
+        ] {
+            let _ = write!(out, " {:<17}", format!("{label} {}", temperature(value)));
+        }
+
+        for (label, value) in [("Low", self.low), ("High", self.high)] {
+            if let Some(value) = value {
+                let _ = write!(out, " {label} {}", temperature(value));
+            }
+        }
+
+        out.trim_end().to_string()
+    }
+}
+
+/// Formats an RFC 3339 timestamp as the time of day in UTC, e.g., "05:04 UTC".
+///
+/// Timestamps that cannot be parsed are shown as is.
+fn format_time(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Utc).format("%H:%M UTC").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weather() -> WeatherInfo {
+        WeatherInfo {
+            humidity: Some(81),
+            description: Some("Cloudy".to_string()),
+            ..WeatherInfo::sample()
+        }
+    }
+
+    #[test]
+    fn test_default_matches_display() {
+        let info = weather();
+        let formatted = info.format_for_display(&DisplayOptions::default());
+
+        assert_eq!(
+            formatted,
+            "Weather in 'UK, London': 50.0°F, Cloudy, Humidity: 81%"
+        );
+        assert_eq!(info.to_string(), formatted);
+    }
+
+    #[test]
+    fn test_display_without_country() {
+        let info = WeatherInfo {
+            country: String::new(),
+            city: "Backyard Station".to_string(),
+            ..weather()
+        };
+
+        assert_eq!(
+            info.format_for_display(&DisplayOptions::default()),
+            "Weather in 'Backyard Station': 50.0°F, Cloudy, Humidity: 81%"
+        );
+    }
+
+    #[test]
+    fn test_hidden_fields_and_date() {
+        let opts = DisplayOptions {
+            show_date: true,
+            show_humidity: false,
+            show_description: false,
+            ..DisplayOptions::default()
+        };
+
+        assert_eq!(
+            weather().format_for_display(&opts),
+            "Weather in 'UK, London' on 2024-01-01: 50.0°F"
+        );
+
+        // The time of real-time data follows the date
+        let info = WeatherInfo {
+            observation_time: DateTime::from_timestamp(1704119400, 0),
+            ..weather()
+        };
+        assert_eq!(
+            info.format_for_display(&opts),
+            "Weather in 'UK, London' on 2024-01-01 at 14:30 UTC: 50.0°F"
+        );
+    }
+
+    #[test]
+    fn test_condition_emoji() {
+        let opts = DisplayOptions {
+            show_emoji: true,
+            ..DisplayOptions::default()
+        };
+        let info = WeatherInfo {
+            condition_code: Some(804),
+            ..weather()
+        };
+
+        assert_eq!(
+            info.format_for_display(&opts),
+            "Weather in 'UK, London': 50.0°F, ☁️ Cloudy, Humidity: 81%"
+        );
+        // Off by default, and nothing to show without a known code
+        assert!(!info.to_string().contains('☁'));
+        assert_eq!(
+            weather().format_for_display(&opts),
+            weather().format_for_display(&DisplayOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_celsius_and_locale() {
+        let opts = DisplayOptions {
+            temperature_unit: Unit::Celsius,
+            locale: "de_DE.UTF-8".to_string(),
+            ..DisplayOptions::default()
+        };
+
+        assert_eq!(
+            weather().format_for_display(&opts),
+            "Weather in 'UK, London': 10,0°C, Cloudy, Humidity: 81%"
+        );
+    }
+
+    #[test]
+    fn test_wind_unit_round_trip() {
+        let units = [
+            WindUnit::MilesPerHour,
+            WindUnit::KilometersPerHour,
+            WindUnit::MetersPerSecond,
+            WindUnit::Knots,
+        ];
+
+        for unit in units {
+            for tenths in 0..=2000 {
+                let mph = tenths as f32 / 10.0;
+                let round_trip = unit.to_mph(unit.from_mph(mph));
+                assert!(
+                    (round_trip - mph).abs() < 1e-3,
+                    "{unit:?}: {mph} -> {round_trip}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_wind_unit_conversions() {
+        let close = |a: f32, b: f32| (a - b).abs() < 0.01;
+
+        assert!(close(WindUnit::MetersPerSecond.to_mph(10.0), 22.37));
+        assert!(close(WindUnit::KilometersPerHour.to_mph(100.0), 62.14));
+        assert!(close(WindUnit::Knots.to_mph(10.0), 11.51));
+        assert!(close(WindUnit::KilometersPerHour.from_mph(10.0), 16.09));
+        assert!(close(WindUnit::MetersPerSecond.from_mph(10.0), 4.47));
+        assert!(close(WindUnit::Knots.from_mph(10.0), 8.69));
+        assert_eq!(WindUnit::MilesPerHour.from_mph(12.5), 12.5);
+    }
+
+    #[test]
+    fn test_wind() {
+        let info = WeatherInfo {
+            wind_speed_mph: Some(10.0),
+            ..weather()
+        };
+
+        // Hidden by default
+        assert_eq!(
+            info.to_string(),
+            "Weather in 'UK, London': 50.0°F, Cloudy, Humidity: 81%"
+        );
+
+        let opts = DisplayOptions {
+            show_wind: true,
+            ..DisplayOptions::default()
+        };
+        assert_eq!(
+            info.format_for_display(&opts),
+            "Weather in 'UK, London': 50.0°F, Cloudy, Humidity: 81%, Wind: 10.0 mph"
+        );
+
+        let opts = DisplayOptions {
+            wind_unit: WindUnit::KilometersPerHour,
+            locale: "de-DE".to_string(),
+            ..opts
+        };
+        assert_eq!(
+            info.format_for_display(&opts),
+            "Weather in 'UK, London': 50,0°F, Cloudy, Humidity: 81%, Wind: 16,1 km/h"
+        );
+
+        let opts = DisplayOptions {
+            wind_unit: WindUnit::Knots,
+            locale: "en-US".to_string(),
+            ..opts
+        };
+        assert!(info.format_for_display(&opts).ends_with("Wind: 8.7 kn"));
+
+        // Nothing is shown if the provider reported no wind
+        assert!(!weather().format_for_display(&opts).contains("Wind"));
+    }
+
+    #[test]
+    fn test_station_id() {
+        let info = WeatherInfo {
+            station_id: Some("KCASANFR1".to_string()),
+            ..weather()
+        };
+
+        assert_eq!(
+            info.to_string(),
+            "Weather in 'UK, London' [station: KCASANFR1]: 50.0°F, Cloudy, Humidity: 81%"
+        );
+    }
+
+    #[test]
+    fn test_daylight_only() {
+        let info = WeatherInfo {
+            temperature: f32::NAN,
+            description: Some("Sunrise/Sunset data only".to_string()),
+            sunrise: Some("2024-06-21T01:53:38+00:00".to_string()),
+            sunset: Some("2024-06-21T22:44:11+02:00".to_string()),
+            ..weather()
+        };
+
+        assert_eq!(
+            info.to_string(),
+            "Weather in 'UK, London': Sunrise/Sunset data only, \
+             Sunrise: 01:53 UTC, Sunset: 20:44 UTC"
+        );
+
+        let opts = DisplayOptions {
+            show_description: false,
+            ..DisplayOptions::default()
+        };
+        assert_eq!(
+            info.format_for_display(&opts),
+            "Weather in 'UK, London': Sunrise: 01:53 UTC, Sunset: 20:44 UTC"
+        );
+
+        let info = WeatherInfo {
+            moon_phase: Some(0.5),
+            ..info
+        };
+        assert_eq!(
+            info.format_for_display(&opts),
+            "Weather in 'UK, London': Sunrise: 01:53 UTC, Sunset: 20:44 UTC, Moon: Full Moon"
+        );
+    }
+
+    #[test]
+    fn test_freezing_rain_warning() {
+        let info = WeatherInfo {
+            temperature: 32.0,
+            precip_type: Some(PrecipType::FreezingRain),
+            ..weather()
+        };
+
+        assert_eq!(
+            info.to_string(),
+            "Weather in 'UK, London': 32.0°F, Cloudy, Humidity: 81%\n  ⚠ freezing rain possible"
+        );
+
+        let info = WeatherInfo {
+            precip_type: Some(PrecipType::Rain),
+            ..info
+        };
+        assert!(!info.to_string().contains("freezing rain"));
+    }
+
+    #[test]
+    fn test_day_parts() {
+        let info = WeatherInfo {
+            periods: Some(DayParts {
+                morning: 41.0,
+                afternoon: 50.0,
+                evening: 46.4,
+                night: 35.6,
+                low: Some(33.8),
+                high: Some(51.8),
+            }),
+            ..weather()
+        };
+
+        // Hidden unless requested
+        assert_eq!(
+            info.format_for_display(&DisplayOptions::default()),
+            "Weather in 'UK, London': 50.0°F, Cloudy, Humidity: 81%"
+        );
+
+        let opts = DisplayOptions {
+            show_periods: true,
+            ..DisplayOptions::default()
+        };
+        assert_eq!(
+            info.format_for_display(&opts),
+            "Weather in 'UK, London': 50.0°F, Cloudy, Humidity: 81%\n  \
+             Morning 41.0°F    Afternoon 50.0°F  Evening 46.4°F    Night 35.6°F      \
+             Low 33.8°F High 51.8°F"
+        );
+
+        let opts = DisplayOptions {
+            temperature_unit: Unit::Celsius,
+            locale: "de-DE".to_string(),
+            ..opts
+        };
+        let periods = DayParts {
+            low: None,
+            high: None,
+            ..info.periods.unwrap()
+        };
+        assert_eq!(
+            periods.format_for_display(&opts),
+            "  Morning 5,0°C     Afternoon 10,0°C  Evening 8,0°C     Night 2,0°C"
+        );
+    }
+
+    #[test]
+    fn test_annotations() {
+        let annotations = DisplayAnnotations {
+            temperature: Some(" ↑".to_string()),
+            humidity: Some(" ↓".to_string()),
+        };
+
+        assert_eq!(
+            weather().format_for_display_with(&DisplayOptions::default(), &annotations),
+            "Weather in 'UK, London': 50.0°F ↑, Cloudy, Humidity: 81% ↓"
+        );
+    }
+
+    #[test]
+    fn test_partial_deserialization() {
+        let opts: DisplayOptions =
+            serde_json::from_str(r#"{"temperature_unit":"celsius"}"#).unwrap();
+
+        assert_eq!(opts.temperature_unit, Unit::Celsius);
+        assert!(opts.show_humidity);
+        assert_eq!(opts.locale, "en-US");
+
+        let opts: DisplayOptions =
+            serde_json::from_str(r#"{"show_wind":true,"wind_unit":"m/s"}"#).unwrap();
+        assert!(opts.show_wind);
+        assert_eq!(opts.wind_unit, WindUnit::MetersPerSecond);
+        assert_eq!(
+            serde_json::to_value(WindUnit::KilometersPerHour).unwrap(),
+            "kmh"
+        );
+    }
+}
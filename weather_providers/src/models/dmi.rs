@@ -0,0 +1,59 @@
+use ::serde::Deserialize;
+
+/// A GeoJSON feature collection of the metObs API, of stations or of observations.
+#[derive(Deserialize)]
+pub struct DmiCollection<T> {
+    #[serde(default = "Vec::new")]
+    pub features: Vec<DmiFeature<T>>,
+}
+
+#[derive(Deserialize)]
+pub struct DmiFeature<T> {
+    /// The position of the station; `null` for some observations.
+    pub geometry: Option<DmiGeometry>,
+    pub properties: T,
+}
+
+/// A GeoJSON point.
+#[derive(Deserialize)]
+pub struct DmiGeometry {
+    /// Longitude and latitude in degrees.
+    pub coordinates: Vec<f64>,
+}
+
+impl DmiGeometry {
+    /// Returns the latitude and longitude of the point, if it has both.
+    pub fn lat_lon(&self) -> Option<(f64, f64)> {
+        match self.coordinates.as_slice() {
+            [lon, lat, ..] => Some((*lat, *lon)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DmiStation {
+    /// The WMO-style station ID, e.g. `"06186"`.
+    pub station_id: String,
+    pub name: String,
+    /// The ISO 3166-1 alpha-3 code of the country, e.g. `"DNK"` or `"GRL"`.
+    pub country: Option<String>,
+    /// The parameters measured by the station, e.g. `["temp_dry", "humidity"]`.
+    #[serde(default, rename = "parameterId")]
+    pub parameter_ids: Vec<String>,
+}
+
+impl DmiStation {
+    pub fn measures(&self, parameter: &str) -> bool {
+        self.parameter_ids.iter().any(|p| p == parameter)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DmiObservation {
+    /// The time of the observation, e.g. `"2024-01-15T14:00:00Z"`.
+    pub observed: String,
+    pub value: f32,
+}
@@ -0,0 +1,76 @@
+use ::serde::Deserialize;
+
+/// A GeoJSON feature collection of the MSC GeoMet OGC API, of climate stations or of hourly
+/// climate data.
+#[derive(Deserialize)]
+pub struct EcccCollection<T> {
+    #[serde(default = "Vec::new")]
+    pub features: Vec<EcccFeature<T>>,
+}
+
+#[derive(Deserialize)]
+pub struct EcccFeature<T> {
+    pub geometry: Option<EcccGeometry>,
+    pub properties: T,
+}
+
+/// A GeoJSON point.
+#[derive(Deserialize)]
+pub struct EcccGeometry {
+    /// Longitude and latitude in degrees.
+    pub coordinates: Vec<f64>,
+}
+
+impl EcccGeometry {
+    /// Returns the latitude and longitude of the point, if it has both.
+    pub fn lat_lon(&self) -> Option<(f64, f64)> {
+        match self.coordinates.as_slice() {
+            [lon, lat, ..] => Some((*lat, *lon)),
+            _ => None,
+        }
+    }
+}
+
+/// A station of the `climate-stations` collection.
+#[derive(Deserialize)]
+pub struct EcccStation {
+    /// The climate station ID, e.g. `"6158355"`.
+    #[serde(rename = "CLIMATE_IDENTIFIER")]
+    pub climate_id: String,
+    #[serde(rename = "STATION_NAME")]
+    pub name: String,
+    /// `"Y"` if the station reports hourly data.
+    #[serde(rename = "HAS_HOURLY_DATA", default)]
+    pub has_hourly_data: Option<String>,
+    /// The time of the last hourly data, e.g. `"2024-01-15 00:00:00"`; `null` without hourly
+    /// data.
+    #[serde(rename = "HLY_LAST_DATE", default)]
+    pub hourly_last_date: Option<String>,
+}
+
+impl EcccStation {
+    pub fn reports_hourly(&self) -> bool {
+        self.has_hourly_data.as_deref() == Some("Y")
+    }
+}
+
+/// An hour of the `climate-hourly` collection.
+#[derive(Deserialize)]
+pub struct EcccHourly {
+    #[serde(rename = "CLIMATE_IDENTIFIER")]
+    pub climate_id: String,
+    #[serde(rename = "STATION_NAME")]
+    pub station_name: String,
+    /// The local time of the hour, e.g. `"2024-01-15 09:00:00"`.
+    #[serde(rename = "LOCAL_DATE")]
+    pub local_date: String,
+    /// The time of the hour in UTC, e.g. `"2024-01-15T14:00:00"`.
+    #[serde(rename = "UTC_DATE", default)]
+    pub utc_date: Option<String>,
+    /// The air temperature in degrees Celsius; `null` if not measured that hour.
+    #[serde(rename = "TEMP", default)]
+    pub temperature: Option<f32>,
+    /// The relative humidity in percent.
+    #[serde(rename = "REL_HUM", alias = "RELATIVE_HUMIDITY", default)]
+    pub humidity: Option<f32>,
+}
@@ -0,0 +1,55 @@
+use ::serde::{Deserialize, Serialize};
+use ::std::collections::HashMap;
+
+/// A retrieval request for one variable at one point and hour.
+#[derive(Serialize)]
+pub struct Era5Request<'a> {
+    pub product_type: &'a str,
+    pub variable: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure_level: Option<&'a str>,
+    pub year: String,
+    pub month: String,
+    pub day: String,
+    pub time: &'a str,
+    /// The bounding box as north, west, south, east; a single point here.
+    pub area: [f64; 4],
+    pub format: &'a str,
+}
+
+/// A queued retrieval job.
+#[derive(Deserialize)]
+pub struct Era5Task {
+    pub state: Era5TaskState,
+    pub request_id: String,
+    /// The URL of the result, once the job is completed.
+    pub location: Option<String>,
+    pub error: Option<Era5TaskError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Era5TaskState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Deserialize)]
+pub struct Era5TaskError {
+    pub message: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// A CoverageJSON result; only the value ranges are read.
+#[derive(Deserialize)]
+pub struct Era5Coverage {
+    pub ranges: HashMap<String, Era5Range>,
+}
+
+#[derive(Deserialize)]
+pub struct Era5Range {
+    pub values: Vec<Option<f32>>,
+}
@@ -0,0 +1,121 @@
+use crate::common::*;
+use ::quick_xml::{Reader, events::Event};
+use ::std::collections::BTreeMap;
+
+/// The latest observations of a place, read from the `wfs:member` elements of a WFS feature
+/// collection of the "simple" stored queries. Each member holds a `BsWfs:BsWfsElement`: the
+/// value of one parameter at one time.
+#[derive(Debug, Default, PartialEq)]
+pub struct FmiObservations {
+    /// The time of the latest value, e.g. `"2024-01-15T14:00:00Z"`.
+    pub time: Option<String>,
+    /// The latest value of each parameter, e.g. `"temperature"` in degrees Celsius. Missing
+    /// values (`NaN`) are left out.
+    pub values: BTreeMap<String, f32>,
+}
+
+/// The text fields of a `BsWfs:BsWfsElement`.
+#[derive(Default)]
+struct FmiElement {
+    time: String,
+    name: String,
+    value: String,
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Time,
+    Name,
+    Value,
+}
+
+impl FmiElement {
+    fn field_mut(&mut self, field: Field) -> &mut String {
+        match field {
+            Field::Time => &mut self.time,
+            Field::Name => &mut self.name,
+            Field::Value => &mut self.value,
+        }
+    }
+}
+
+impl FmiObservations {
+    /// Parses a WFS feature collection, keeping the latest value of each parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not well-formed XML.
+    pub fn from_xml(xml: &str) -> Result<Self> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+
+        let mut latest = BTreeMap::<String, (String, f32)>::new();
+        let mut element = None::<FmiElement>;
+        let mut field = None;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| format!("Failed to parse the FMI response: {e}"))?;
+
+            match event {
+                // Elements are matched by their local names, whatever the namespace prefixes
+                Event::Start(tag) => match tag.local_name().as_ref() {
+                    b"BsWfsElement" => element = Some(FmiElement::default()),
+                    b"Time" => field = Some(Field::Time),
+                    b"ParameterName" => field = Some(Field::Name),
+                    b"ParameterValue" => field = Some(Field::Value),
+                    _ => field = None,
+                },
+                Event::Text(text) => {
+                    if let (Some(element), Some(field)) = (element.as_mut(), field) {
+                        let text = text
+                            .decode()
+                            .map_err(|e| format!("Failed to parse the FMI response: {e}"))?;
+                        element.field_mut(field).push_str(&text);
+                    }
+                }
+                Event::End(tag) => {
+                    field = None;
+                    if tag.local_name().as_ref() == b"BsWfsElement"
+                        && let Some(element) = element.take()
+                        && let Ok(value) = element.value.trim().parse::<f32>()
+                        && !value.is_nan()
+                        && latest
+                            .get(&element.name)
+                            .is_none_or(|(time, _)| *time <= element.time)
+                    {
+                        latest.insert(element.name, (element.time, value));
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        // ISO 8601 times in UTC sort as strings
+        let time = latest.values().map(|(time, _)| time.clone()).max();
+        let values = latest
+            .into_iter()
+            .map(|(name, (_, value))| (name, value))
+            .collect();
+
+        Ok(Self { time, values })
+    }
+}
+
+/// Returns the message of an OWS exception report, the body of the FMI errors.
+pub fn exception_text(xml: &str) -> Option<String> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(tag) => in_text = tag.local_name().as_ref() == b"ExceptionText",
+            Event::Text(text) if in_text => return text.decode().ok().map(|t| t.into_owned()),
+            Event::Eof => return None,
+            _ => {}
+        }
+    }
+}
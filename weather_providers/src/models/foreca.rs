@@ -0,0 +1,55 @@
+use ::serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+pub struct ForecaTokenRequest<'a> {
+    pub user: &'a str,
+    pub password: &'a str,
+}
+
+#[derive(Deserialize)]
+pub struct ForecaTokenResponse {
+    pub access_token: String,
+    /// The lifetime of the token, in seconds.
+    pub expires_in: u64,
+}
+
+#[derive(Deserialize)]
+pub struct ForecaLocationResponse {
+    #[serde(default)]
+    pub locations: Vec<ForecaLocation>,
+}
+
+#[derive(Deserialize)]
+pub struct ForecaLocation {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Deserialize)]
+pub struct ForecaCurrentResponse {
+    pub current: ForecaCurrent,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecaCurrent {
+    /// Local observation time, e.g. `2024-01-15T14:00+01:00`.
+    pub time: String,
+    pub symbol_phrase: Option<String>,
+    /// The weather symbol, e.g. "d300": "d" or "n" for day or night, then the cloudiness,
+    /// the precipitation rate and its type.
+    pub symbol: Option<String>,
+    /// Temperature in degrees Celsius.
+    pub temperature: f32,
+    /// Feels-like temperature in degrees Celsius.
+    pub feels_like_temp: Option<f32>,
+    pub rel_humidity: u8,
+    /// Wind speed in meters per second.
+    pub wind_speed: Option<f32>,
+    /// Precipitation intensity in millimeters per hour.
+    pub precip_rate: Option<f32>,
+}
@@ -0,0 +1,265 @@
+use ::serde::{Deserialize, Serialize};
+
+/// A normalized weather icon.
+///
+/// The codes follow the Skycons naming (e.g., "clear-day", "rain"), which the weather-icons
+/// font and most status-bar widgets also understand, plus "thunderstorm".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Icon {
+    ClearDay,
+    ClearNight,
+    PartlyCloudyDay,
+    PartlyCloudyNight,
+    Cloudy,
+    Rain,
+    Sleet,
+    Snow,
+    Wind,
+    Fog,
+    Thunderstorm,
+}
+
+impl Icon {
+    /// Returns the icon code, e.g. "partly-cloudy-night".
+    pub fn code(&self) -> &'static str {
+        match self {
+            Icon::ClearDay => "clear-day",
+            Icon::ClearNight => "clear-night",
+            Icon::PartlyCloudyDay => "partly-cloudy-day",
+            Icon::PartlyCloudyNight => "partly-cloudy-night",
+            Icon::Cloudy => "cloudy",
+            Icon::Rain => "rain",
+            Icon::Sleet => "sleet",
+            Icon::Snow => "snow",
+            Icon::Wind => "wind",
+            Icon::Fog => "fog",
+            Icon::Thunderstorm => "thunderstorm",
+        }
+    }
+
+    /// Parses an icon code, e.g. one of the Dark Sky compatible `icon` of Pirate Weather.
+    pub fn from_code(code: &str) -> Option<Self> {
+        ALL.into_iter().find(|icon| icon.code() == code)
+    }
+
+    /// Maps an OpenWeather icon code (e.g., "10n"): two digits for the condition group and
+    /// "d" or "n" for day or night.
+    ///
+    /// | Code | Condition        | Icon                  |
+    /// |------|------------------|-----------------------|
+    /// | 01   | clear sky        | clear-day/night       |
+    /// | 02   | few clouds       | partly-cloudy-day/night |
+    /// | 03   | scattered clouds | partly-cloudy-day/night |
+    /// | 04   | broken clouds    | cloudy                |
+    /// | 09   | shower rain      | rain                  |
+    /// | 10   | rain             | rain                  |
+    /// | 11   | thunderstorm     | thunderstorm          |
+    /// | 13   | snow             | snow                  |
+    /// | 50   | mist             | fog                   |
+    pub fn from_open_weather(code: &str) -> Option<Self> {
+        let (group, time) = code.split_at_checked(2)?;
+        let is_day = match time {
+            "d" => true,
+            "n" => false,
+            _ => return None,
+        };
+
+        match group {
+            "01" => Some(Self::clear(is_day)),
+            "02" | "03" => Some(Self::partly_cloudy(is_day)),
+            "04" => Some(Icon::Cloudy),
+            "09" | "10" => Some(Icon::Rain),
+            "11" => Some(Icon::Thunderstorm),
+            "13" => Some(Icon::Snow),
+            "50" => Some(Icon::Fog),
+            _ => None,
+        }
+    }
+
+    /// Maps a WeatherAPI condition code (see `weather_conditions.json` of WeatherAPI).
+    ///
+    /// Freezing rain and ice pellets map to "sleet"; showers map to their precipitation.
+    pub fn from_weather_api(code: u16, is_day: bool) -> Option<Self> {
+        match code {
+            1000 => Some(Self::clear(is_day)),
+            1003 => Some(Self::partly_cloudy(is_day)),
+            1006 | 1009 => Some(Icon::Cloudy),
+            1030 | 1135 | 1147 => Some(Icon::Fog),
+            1063 | 1150 | 1153 | 1180..=1195 | 1240..=1246 => Some(Icon::Rain),
+            1066 | 1114 | 1117 | 1210..=1225 | 1255 | 1258 => Some(Icon::Snow),
+            1069 | 1072 | 1168 | 1171 | 1198 | 1201 | 1204 | 1207 | 1237 | 1249 | 1252 | 1261
+            | 1264 => Some(Icon::Sleet),
+            1087 | 1273..=1282 => Some(Icon::Thunderstorm),
+            _ => None,
+        }
+    }
+
+    /// Guesses the icon from a free-text description (e.g., "Light snow showers"), for
+    /// providers without icon codes.
+    ///
+    /// The most significant condition wins: thunder over snow over sleet over rain, and any
+    /// precipitation over fog, wind and clouds.
+    pub fn from_description(description: &str, is_day: bool) -> Option<Self> {
+        let description = description.to_lowercase();
+        let mentions = |words: &[&str]| words.iter().any(|w| description.contains(w));
+
+        let icon = if mentions(&["thunder"]) {
+            Icon::Thunderstorm
+        } else if mentions(&["snow", "blizzard", "flurries"]) {
+            Icon::Snow
+        } else if mentions(&["sleet", "freezing", "ice pellets", "hail"]) {
+            Icon::Sleet
+        } else if mentions(&["rain", "drizzle", "shower"]) {
+            Icon::Rain
+        } else if mentions(&["fog", "mist", "haze", "smoke"]) {
+            Icon::Fog
+        } else if mentions(&["wind", "breez", "gale"]) {
+            Icon::Wind
+        } else if mentions(&["partly", "few clouds", "scattered", "mostly clear"]) {
+            Self::partly_cloudy(is_day)
+        } else if mentions(&["cloud", "overcast"]) {
+            Icon::Cloudy
+        } else if mentions(&["clear", "sunny", "fair"]) {
+            Self::clear(is_day)
+        } else {
+            return None;
+        };
+
+        Some(icon)
+    }
+
+    fn clear(is_day: bool) -> Self {
+        if is_day {
+            Icon::ClearDay
+        } else {
+            Icon::ClearNight
+        }
+    }
+
+    fn partly_cloudy(is_day: bool) -> Self {
+        if is_day {
+            Icon::PartlyCloudyDay
+        } else {
+            Icon::PartlyCloudyNight
+        }
+    }
+}
+
+const ALL: [Icon; 11] = [
+    Icon::ClearDay,
+    Icon::ClearNight,
+    Icon::PartlyCloudyDay,
+    Icon::PartlyCloudyNight,
+    Icon::Cloudy,
+    Icon::Rain,
+    Icon::Sleet,
+    Icon::Snow,
+    Icon::Wind,
+    Icon::Fog,
+    Icon::Thunderstorm,
+];
+
+impl std::fmt::Display for Icon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Icon as I, *};
+
+    #[test]
+    fn test_codes_round_trip() {
+        for icon in ALL {
+            assert_eq!(I::from_code(icon.code()), Some(icon));
+            assert_eq!(
+                serde_json::to_string(&icon).unwrap(),
+                format!("\"{}\"", icon.code())
+            );
+        }
+
+        assert_eq!(I::from_code("tornado"), None);
+    }
+
+    #[test]
+    fn test_open_weather_mapping() {
+        let table = [
+            ("01d", Some(I::ClearDay)),
+            ("01n", Some(I::ClearNight)),
+            ("02d", Some(I::PartlyCloudyDay)),
+            ("03n", Some(I::PartlyCloudyNight)),
+            ("04d", Some(I::Cloudy)),
+            ("09n", Some(I::Rain)),
+            ("10d", Some(I::Rain)),
+            ("11d", Some(I::Thunderstorm)),
+            ("13n", Some(I::Snow)),
+            ("50d", Some(I::Fog)),
+            ("05d", None),
+            ("01x", None),
+            ("1", None),
+            ("", None),
+        ];
+
+        for (code, expected) in table {
+            assert_eq!(I::from_open_weather(code), expected, "{code}");
+        }
+    }
+
+    #[test]
+    fn test_weather_api_mapping() {
+        let table = [
+            (1000, true, Some(I::ClearDay)),
+            (1000, false, Some(I::ClearNight)),
+            (1003, true, Some(I::PartlyCloudyDay)),
+            (1003, false, Some(I::PartlyCloudyNight)),
+            (1009, true, Some(I::Cloudy)),
+            (1135, true, Some(I::Fog)),
+            (1183, true, Some(I::Rain)),
+            (1243, false, Some(I::Rain)),
+            (1198, true, Some(I::Sleet)),
+            (1237, true, Some(I::Sleet)),
+            (1117, true, Some(I::Snow)),
+            (1258, true, Some(I::Snow)),
+            (1087, true, Some(I::Thunderstorm)),
+            (1282, true, Some(I::Thunderstorm)),
+            (999, true, None),
+        ];
+
+        for (code, is_day, expected) in table {
+            assert_eq!(I::from_weather_api(code, is_day), expected, "{code}");
+        }
+    }
+
+    #[test]
+    fn test_description_heuristic() {
+        let table = [
+            ("Sunny", true, Some(I::ClearDay)),
+            ("clear sky", false, Some(I::ClearNight)),
+            ("Partly cloudy", true, Some(I::PartlyCloudyDay)),
+            ("scattered clouds", false, Some(I::PartlyCloudyNight)),
+            ("Overcast", true, Some(I::Cloudy)),
+            ("Light rain shower", true, Some(I::Rain)),
+            ("Freezing drizzle", true, Some(I::Sleet)),
+            ("Heavy snow showers", true, Some(I::Snow)),
+            (
+                "Patchy light snow with thunder",
+                true,
+                Some(I::Thunderstorm),
+            ),
+            ("Mist", true, Some(I::Fog)),
+            ("Windy", true, Some(I::Wind)),
+            ("PM2.5: 12.0 μg/m³", true, None),
+            ("", true, None),
+        ];
+
+        for (description, is_day, expected) in table {
+            assert_eq!(
+                I::from_description(description, is_day),
+                expected,
+                "{description}"
+            );
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use super::WeatherInfo;
+use crate::{
+    Provider,
+    utils::clock::{Clock, SystemClock},
+};
+use ::chrono::{DateTime, NaiveDate, Utc};
+use ::serde::{Deserialize, Serialize};
+
+/// Where the values of a `WeatherInfo` came from, for users who need to trace each number
+/// back to its source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherMeta {
+    /// The ID of the provider that returned the data (e.g., "ow").
+    pub provider: String,
+    /// The upstream endpoint that was called, e.g. "onecall" or "day_summary" for OpenWeather.
+    pub endpoint: String,
+    /// Whether the values were observed, forecast or taken from historical records.
+    pub source: DataSource,
+    /// The fields computed locally rather than reported by the provider, e.g. "precip_type".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derived: Vec<String>,
+    /// When the upstream request was made.
+    pub fetched_at: DateTime<Utc>,
+    /// Whether the data was served from a cache instead of a new upstream request.
+    #[serde(default)]
+    pub cache: CacheStatus,
+}
+
+/// The kind of data a provider reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataSource {
+    /// Current conditions, measured by a station or estimated by a model.
+    Observed,
+    /// A prediction for a date that has not ended yet.
+    Forecast,
+    /// Past conditions, e.g. from an archive or a reanalysis.
+    Historical,
+}
+
+/// Whether data was fetched upstream or served from a cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheStatus {
+    /// The data was fetched by a new upstream request.
+    #[default]
+    Miss,
+    /// The data was served from the result of an earlier request.
+    Hit,
+}
+
+impl WeatherMeta {
+    /// Creates the metadata of a request made now to `endpoint` of `provider`.
+    pub fn new(provider: Provider, endpoint: &str, source: DataSource) -> Self {
+        Self::with_clock(provider, endpoint, source, &SystemClock)
+    }
+
+    /// Like `new`, with the time of the request taken from `clock`.
+    pub fn with_clock(
+        provider: Provider,
+        endpoint: &str,
+        source: DataSource,
+        clock: &dyn Clock,
+    ) -> Self {
+        Self {
+            provider: provider.id().to_string(),
+            endpoint: endpoint.to_string(),
+            source,
+            derived: Vec::new(),
+            fetched_at: clock.now(),
+            cache: CacheStatus::Miss,
+        }
+    }
+}
+
+impl DataSource {
+    /// Returns the kind of data of a date-based request: `Historical` before today, `Forecast`
+    /// after it and `Observed` for today or an unparsable date.
+    pub fn for_date(date: &str) -> Self {
+        Self::for_date_with(date, &SystemClock)
+    }
+
+    /// Like `for_date`, with today taken from `clock`.
+    pub fn for_date_with(date: &str, clock: &dyn Clock) -> Self {
+        let Ok(date) = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") else {
+            return DataSource::Observed;
+        };
+
+        match date.cmp(&clock.today_in(None)) {
+            std::cmp::Ordering::Less => DataSource::Historical,
+            std::cmp::Ordering::Equal => DataSource::Observed,
+            std::cmp::Ordering::Greater => DataSource::Forecast,
+        }
+    }
+}
+
+impl std::fmt::Display for WeatherMeta {
+    /// Formats the metadata as a single line, e.g.
+    /// "Source: ow onecall (forecast), fetched 2024-01-15 12:00:00 UTC, cache miss".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Source: {} {} ({}), fetched {}, cache {}",
+            self.provider,
+            self.endpoint,
+            self.source,
+            self.fetched_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.cache
+        )?;
+
+        if !self.derived.is_empty() {
+            write!(f, ", derived: {}", self.derived.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for DataSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DataSource::Observed => "observed",
+            DataSource::Forecast => "forecast",
+            DataSource::Historical => "historical",
+        })
+    }
+}
+
+impl std::fmt::Display for CacheStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CacheStatus::Miss => "miss",
+            CacheStatus::Hit => "hit",
+        })
+    }
+}
+
+impl WeatherInfo {
+    /// Records `field` as computed locally in the metadata, if any.
+    pub fn mark_derived(&mut self, field: &str) {
+        if let Some(meta) = &mut self.meta
+            && !meta.derived.iter().any(|f| f == field)
+        {
+            meta.derived.push(field.to_string());
+        }
+    }
+
+    /// Marks the data as served from a cache, if it has metadata.
+    pub fn with_cache_hit(mut self) -> Self {
+        if let Some(meta) = &mut self.meta {
+            meta.cache = CacheStatus::Hit;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FixedClock;
+    use ::chrono::TimeZone;
+
+    fn noon() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn test_for_date() {
+        let clock = noon();
+
+        assert_eq!(
+            DataSource::for_date_with("2024-01-14", &clock),
+            DataSource::Historical
+        );
+        assert_eq!(
+            DataSource::for_date_with("2024-01-15", &clock),
+            DataSource::Observed
+        );
+        assert_eq!(
+            DataSource::for_date_with("2024-01-16", &clock),
+            DataSource::Forecast
+        );
+        assert_eq!(
+            DataSource::for_date_with("tomorrow", &clock),
+            DataSource::Observed
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let clock = noon();
+        let mut meta = WeatherMeta::with_clock(
+            Provider::OpenWeather,
+            "onecall",
+            DataSource::Forecast,
+            &clock,
+        );
+
+        assert_eq!(
+            serde_json::to_value(&meta).unwrap(),
+            serde_json::json!({
+                "provider": "ow",
+                "endpoint": "onecall",
+                "source": "forecast",
+                "fetched_at": "2024-01-15T12:00:00Z",
+                "cache": "miss"
+            })
+        );
+
+        meta.derived.push("icon".to_string());
+        meta.cache = CacheStatus::Hit;
+        assert_eq!(
+            meta.to_string(),
+            "Source: ow onecall (forecast), fetched 2024-01-15 12:00:00 UTC, cache hit, \
+             derived: icon"
+        );
+        let json = serde_json::to_string(&meta).unwrap();
+        assert!(json.contains(r#""derived":["icon"]"#), "{json}");
+        assert_eq!(serde_json::from_str::<WeatherMeta>(&json).unwrap(), meta);
+    }
+}
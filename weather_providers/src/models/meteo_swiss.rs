@@ -0,0 +1,46 @@
+use ::serde::Deserialize;
+
+/// The GeoJSON feature collection of the current 10-minute measurements, one feature per
+/// station.
+#[derive(Deserialize)]
+pub struct MeteoSwissCollection {
+    #[serde(default)]
+    pub features: Vec<MeteoSwissFeature>,
+}
+
+#[derive(Deserialize)]
+pub struct MeteoSwissFeature {
+    /// The station abbreviation, e.g. `"BER"`.
+    pub id: Option<String>,
+    pub geometry: MeteoSwissGeometry,
+    pub properties: MeteoSwissProperties,
+}
+
+/// A GeoJSON point.
+#[derive(Deserialize)]
+pub struct MeteoSwissGeometry {
+    /// Longitude and latitude in degrees, optionally followed by the altitude.
+    pub coordinates: Vec<f64>,
+}
+
+impl MeteoSwissGeometry {
+    /// Returns the latitude and longitude of the point, if it has both.
+    pub fn lat_lon(&self) -> Option<(f64, f64)> {
+        match self.coordinates.as_slice() {
+            [lon, lat, ..] => Some((*lat, *lon)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MeteoSwissProperties {
+    pub station_name: String,
+    /// The air temperature 2 m above ground in degrees Celsius. `null` for stations that do
+    /// not measure it.
+    pub tt: Option<f32>,
+    /// The relative humidity 2 m above ground in percent.
+    pub rh: Option<f32>,
+    /// The time of the measurement, e.g. `"2024-01-15T10:20:00Z"`.
+    pub reference_ts: Option<String>,
+}
@@ -0,0 +1,170 @@
+use crate::common::*;
+use ::std::time::Duration;
+use ::tracing::warn;
+
+/// Setting this environment variable configures `MockFaults` for the mock provider, e.g.
+/// `latency_ms=200,fail_rate=0.2,fail_kind=server_error,seed=42`.
+pub const MOCK_FAULTS_ENV: &str = "WEATHER_PROVIDERS_MOCK_FAULTS";
+
+/// Latency and failures injected into the mock provider, to exercise timeouts and error
+/// handling of applications in benchmarks and resilience tests.
+///
+/// Failures are drawn from a generator seeded with `seed`, so a sequence of requests fails
+/// the same way on every run. Requests for the addresses of `fail_on` always fail, to fail
+/// some locations of a multi-location request and not the others.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockFaults {
+    /// The delay added to every request. Ignored on `wasm32`, which has no timers.
+    pub latency: Duration,
+    /// The share of requests that fail, from 0.0 (none) to 1.0 (all).
+    pub fail_rate: f64,
+    /// How the failing requests fail.
+    pub fail_kind: FailKind,
+    /// The seed of the generator deciding which requests fail.
+    pub seed: u64,
+    /// The addresses whose requests always fail with `fail_kind`, compared case-insensitively.
+    pub fail_on: Vec<String>,
+}
+
+impl Default for MockFaults {
+    fn default() -> Self {
+        Self {
+            latency: Duration::ZERO,
+            fail_rate: 0.0,
+            fail_kind: FailKind::ServerError,
+            seed: 0,
+            fail_on: Vec::new(),
+        }
+    }
+}
+
+/// How an injected failure of the mock provider fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailKind {
+    /// The request never completes, so the caller's timeout has to end it.
+    Timeout,
+    /// The request fails like a provider server error (HTTP 5xx).
+    ServerError,
+    /// The request fails with `Error::LocationNotFound`.
+    NotFound,
+}
+
+impl MockFaults {
+    /// Parses comma-separated `key=value` settings: `latency_ms`, `fail_rate`, `fail_kind`
+    /// (`timeout`, `server_error` or `not_found`), `seed` and `fail_on` (addresses separated by
+    /// `|`, e.g. `fail_on=Paris|Tokyo`). Missing settings keep their defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a setting is unknown or its value is invalid.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut faults = Self::default();
+
+        for setting in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (key, value) = setting
+                .split_once('=')
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .ok_or_else(|| {
+                    format!("Invalid mock fault setting '{setting}': expected key=value")
+                })?;
+            let invalid = || format!("Invalid value '{value}' of mock fault setting '{key}'");
+
+            match key {
+                "latency_ms" => {
+                    faults.latency = Duration::from_millis(value.parse().map_err(|_| invalid())?)
+                }
+                "fail_rate" => {
+                    faults.fail_rate = value
+                        .parse()
+                        .ok()
+                        .filter(|rate| (0.0..=1.0).contains(rate))
+                        .ok_or_else(invalid)?
+                }
+                "fail_kind" => {
+                    faults.fail_kind = match value {
+                        "timeout" => FailKind::Timeout,
+                        "server_error" | "5xx" => FailKind::ServerError,
+                        "not_found" => FailKind::NotFound,
+                        _ => Err(invalid())?,
+                    }
+                }
+                "seed" => faults.seed = value.parse().map_err(|_| invalid())?,
+                "fail_on" => {
+                    faults.fail_on = value
+                        .split('|')
+                        .map(str::trim)
+                        .filter(|address| !address.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                }
+                _ => Err(format!(
+                    "Unknown mock fault setting '{key}'. \
+                     Expected latency_ms, fail_rate, fail_kind, seed or fail_on"
+                ))?,
+            }
+        }
+
+        Ok(faults)
+    }
+
+    /// Returns `true` if requests for `address` always fail.
+    pub fn fails_on(&self, address: &str) -> bool {
+        self.fail_on
+            .iter()
+            .any(|failing| failing.eq_ignore_ascii_case(address.trim()))
+    }
+
+    /// Reads the settings of the `WEATHER_PROVIDERS_MOCK_FAULTS` environment variable.
+    ///
+    /// Returns `None` if the variable is not set or invalid; an invalid value is logged.
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var(MOCK_FAULTS_ENV).ok()?;
+
+        Self::parse(&value)
+            .inspect_err(|e| warn!("Ignoring {MOCK_FAULTS_ENV}: {e}"))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            MockFaults::parse("latency_ms=200, fail_rate=0.2, fail_kind=timeout, seed=42").unwrap(),
+            MockFaults {
+                latency: Duration::from_millis(200),
+                fail_rate: 0.2,
+                fail_kind: FailKind::Timeout,
+                seed: 42,
+                fail_on: Vec::new(),
+            }
+        );
+        let faults = MockFaults::parse("fail_on=Paris | tokyo,fail_kind=not_found").unwrap();
+        assert_eq!(faults.fail_on, ["Paris", "tokyo"]);
+        assert!(faults.fails_on("paris"));
+        assert!(faults.fails_on(" Tokyo "));
+        assert!(!faults.fails_on("London"));
+        assert_eq!(
+            MockFaults::parse("fail_kind=5xx").unwrap().fail_kind,
+            FailKind::ServerError
+        );
+        assert_eq!(MockFaults::parse("").unwrap(), MockFaults::default());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [
+            "fail_rate=1.5",
+            "fail_rate=-0.1",
+            "fail_kind=crash",
+            "latency_ms=fast",
+            "seed",
+            "jitter_ms=10",
+        ] {
+            assert!(MockFaults::parse(s).is_err(), "{s}");
+        }
+    }
+}
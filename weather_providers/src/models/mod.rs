@@ -1,30 +1,958 @@
+pub mod aemet;
+pub mod ambient_weather;
+pub mod bom;
+pub mod climate_serv;
+mod display;
+pub mod dmi;
+pub mod eccc;
+pub mod era5;
+pub mod fmi;
+pub mod foreca;
+mod icon;
+mod meta;
+pub mod meteo_swiss;
+pub mod mock;
+pub mod open_meteo;
 pub mod open_weather;
+pub mod openaq;
+mod openmetrics;
+pub mod pirate_weather;
+mod provider;
+pub mod purple_air;
+mod safety;
+pub mod sunrise_sunset;
+pub mod synoptic;
+pub mod tempest;
 pub mod weather_api;
+pub mod windy;
+pub mod world_weather_online;
 
-use ::serde::{Deserialize, Serialize};
+pub use self::{
+    display::{DisplayAnnotations, DisplayOptions, Unit, WindUnit},
+    icon::Icon,
+    meta::{CacheStatus, DataSource, WeatherMeta},
+    openmetrics::{ExpositionFormat, MetricsSample, render_metrics},
+    provider::{
+        OpenWeatherEndpoint, ProviderDescriptor, ProviderInfo, ProviderMetadata, ProviderOptions,
+    },
+    safety::{Breach, SafetyThresholds},
+};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::utils::clock::{Clock, SystemClock};
+use ::chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use ::serde::{Deserialize, Deserializer, Serialize};
+use ::std::ops::RangeInclusive;
+
+/// A geocoded location with its normalized name and coordinates.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeoLocation {
+    pub city: String,
+    /// The state or region, if the geocoder reports one (e.g., "Missouri").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl GeoLocation {
+    /// Returns the name of the location as an address the geocoder resolves again, e.g.
+    /// "Springfield, Missouri, US". The parts that were not reported are left out.
+    pub fn display_name(&self) -> String {
+        [
+            Some(self.city.as_str()),
+            self.region.as_deref(),
+            Some(self.country.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+impl std::fmt::Display for GeoLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}, {}': lat {:.4}, lon {:.4}",
+            self.country, self.city, self.lat, self.lon
+        )
+    }
+}
+
+/// At or below this temperature (°F, i.e. 0 °C), rain freezes on contact with the ground.
+pub const FREEZING_POINT_F: f32 = 32.0;
+
+/// Below this temperature (°F, about 1.7 °C) and above the freezing point, precipitation of an
+/// unreported type is assumed to fall as sleet rather than rain.
+pub const SLEET_MAX_F: f32 = 35.0;
+
+/// Precipitation below this amount (mm) is a trace and is not counted as precipitation.
+pub const TRACE_PRECIPITATION_MM: f32 = 0.1;
+
+/// From this UV index on, the WHO recommends protection against the sun.
+pub const SUN_PROTECTION_UV_INDEX: f32 = 3.0;
+
+/// The type of precipitation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrecipType {
+    Rain,
+    Snow,
+    Sleet,
+    /// Rain falling at or below the freezing point, which freezes on contact.
+    #[serde(rename = "freezing_rain")]
+    FreezingRain,
+    /// A mix of rain and snow.
+    Mixed,
+    /// No precipitation.
+    None,
+}
+
+impl PrecipType {
+    /// Parses a provider precipitation type name such as "rain" or "Snow".
+    ///
+    /// # Returns
+    ///
+    /// `None` if the name is not recognized.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "rain" | "drizzle" => Some(PrecipType::Rain),
+            "freezing rain" | "freezing_rain" | "freezing drizzle" => {
+                Some(PrecipType::FreezingRain)
+            }
+            "snow" => Some(PrecipType::Snow),
+            "sleet" | "ice pellets" => Some(PrecipType::Sleet),
+            "mixed" | "rain and snow" => Some(PrecipType::Mixed),
+            "none" => Some(PrecipType::None),
+            _ => None,
+        }
+    }
+
+    /// Derives the type of precipitation from the temperature, the type reported by the
+    /// provider (`condition`) and the amount of precipitation.
+    ///
+    /// * Rain at or below `FREEZING_POINT_F` is freezing rain.
+    /// * Other reported types are kept.
+    /// * Without a reported type, a measurable amount (at least `TRACE_PRECIPITATION_MM`) is
+    ///   snow at or below `FREEZING_POINT_F`, sleet below `SLEET_MAX_F` and rain above, while
+    ///   a trace counts as no precipitation.
+    ///
+    /// # Returns
+    ///
+    /// `None` if neither the type nor the amount is known, or the temperature is not a number.
+    pub fn classify(
+        temperature_f: f32,
+        condition: Option<PrecipType>,
+        precipitation_mm: Option<f32>,
+    ) -> Option<PrecipType> {
+        if temperature_f.is_nan() {
+            return condition;
+        }
+
+        let freezing = temperature_f <= FREEZING_POINT_F;
+
+        match condition {
+            Some(PrecipType::Rain) if freezing => Some(PrecipType::FreezingRain),
+            Some(condition) => Some(condition),
+            None => precipitation_mm.map(|mm| match mm {
+                mm if mm < TRACE_PRECIPITATION_MM => PrecipType::None,
+                _ if freezing => PrecipType::Snow,
+                _ if temperature_f < SLEET_MAX_F => PrecipType::Sleet,
+                _ => PrecipType::Rain,
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for PrecipType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PrecipType::Rain => "rain",
+            PrecipType::Snow => "snow",
+            PrecipType::Sleet => "sleet",
+            PrecipType::FreezingRain => "freezing rain",
+            PrecipType::Mixed => "mixed",
+            PrecipType::None => "none",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How the air pressure changed since the previous reading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PressureTendency {
+    /// Falling pressure usually brings clouds and precipitation.
+    Falling,
+    Steady,
+    /// Rising pressure usually brings clearing, settled weather.
+    Rising,
+}
+
+impl std::fmt::Display for PressureTendency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PressureTendency::Falling => "falling",
+            PressureTendency::Steady => "steady",
+            PressureTendency::Rising => "rising",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The part of the sky covered by a cloud layer, in eighths (oktas), as coded in METAR reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CloudCoverage {
+    /// 1 to 2 oktas.
+    #[serde(rename = "FEW")]
+    Few,
+    /// 3 to 4 oktas.
+    #[serde(rename = "SCT")]
+    Scattered,
+    /// 5 to 7 oktas.
+    #[serde(rename = "BKN")]
+    Broken,
+    /// 8 oktas.
+    #[serde(rename = "OVC")]
+    Overcast,
+}
+
+impl CloudCoverage {
+    /// Returns `true` for broken and overcast layers, the lowest of which is the ceiling.
+    pub fn is_ceiling(&self) -> bool {
+        matches!(self, CloudCoverage::Broken | CloudCoverage::Overcast)
+    }
+}
+
+impl std::fmt::Display for CloudCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let code = match self {
+            CloudCoverage::Few => "FEW",
+            CloudCoverage::Scattered => "SCT",
+            CloudCoverage::Broken => "BKN",
+            CloudCoverage::Overcast => "OVC",
+        };
+        write!(f, "{code}")
+    }
+}
+
+/// A layer of clouds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloudLayer {
+    /// The height of the cloud base above the ground, in feet.
+    pub altitude_ft: u32,
+    pub coverage: CloudCoverage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherInfo {
     pub country: String,
     pub city: String,
     pub date: String,
+    /// When the data was observed or last updated by the provider, for real-time data. `date`
+    /// has day granularity only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observation_time: Option<DateTime<Utc>>,
+    /// The temperature in degrees Fahrenheit, NaN if the provider reports none (e.g., an air
+    /// quality or sun times provider). JSON has no NaN, so it is written as `null`.
+    #[serde(deserialize_with = "nan_if_null")]
     pub temperature: f32,
-    pub humidity: u8,
+    /// The relative humidity in percent, `None` if the provider reports none. A reading of 0
+    /// is a genuine 0 %, and readings saved without it are read back as `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<u8>,
     pub description: Option<String>,
+    /// The numeric condition code as reported by the provider, e.g. `500` (light rain) for
+    /// OpenWeather or `1063` (patchy rain) for WeatherAPI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition_code: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wind_speed_mph: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precipitation_mm: Option<f32>,
+    /// The probability of precipitation as a fraction (0.0–1.0), unrounded, as reported by
+    /// forecast endpoints. `precip_probability_pct` returns it in percent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precip_probability: Option<f32>,
+    /// The apparent ("feels like") temperature in degrees Fahrenheit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feels_like: Option<f32>,
+    /// The sea-level air pressure in hectopascals (hPa, equal to millibars).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pressure_hpa: Option<f32>,
+    /// Whether the pressure is rising, falling or steady, which indicates how the weather
+    /// develops. Set by `PressureTracker` from consecutive readings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pressure_tendency: Option<PressureTendency>,
+    /// The identifier of the physical station that observed the weather (e.g., "KCASANFR1").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub station_id: Option<String>,
+    /// The time of sunrise as an RFC 3339 timestamp (e.g., "2024-06-21T03:43:00+00:00").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sunrise: Option<String>,
+    /// The time of sunset as an RFC 3339 timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sunset: Option<String>,
+    /// The depth of snow (or of new snowfall, depending on the provider) in millimeters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snow_depth_mm: Option<f32>,
+    /// The type of precipitation, as reported by the provider or derived from the temperature
+    /// and the amount of precipitation (see `PrecipType::classify`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precip_type: Option<PrecipType>,
+    /// The lunar phase from 0.0 to 1.0: 0.0 (and 1.0) is a new moon, 0.5 a full moon.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moon_phase: Option<f32>,
+    /// The temperatures of the parts of the day, if the provider reports them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub periods: Option<DayParts>,
+    /// The US EPA Air Quality Index (0 to 500), if the provider reports air quality.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub air_quality_index: Option<u16>,
+    /// The concentration of fine particulate matter (PM2.5) in μg/m³, if the provider reports
+    /// air quality.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pm25: Option<f32>,
+    /// The highest UV index of the day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uv_index_max: Option<f32>,
+    /// The highest UV index of the day under a clear sky, which clouds can only lower.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uv_index_clear_sky: Option<f32>,
+    /// The cloud layers, lowest first, as reported in aviation weather (METAR).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cloud_layers: Option<Vec<CloudLayer>>,
+    /// A normalized icon code (see `Icon`), e.g. "clear-day".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// The icon code as reported by the provider, e.g. "10d" for OpenWeather.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_icon: Option<String>,
+    /// The severe weather warnings issued for the location, e.g. by a national weather service.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<WeatherAlert>,
+    /// Where the data came from: the provider, endpoint, kind of data and cache status.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<WeatherMeta>,
+}
+
+impl Default for WeatherInfo {
+    /// An empty reading without a temperature (NaN), to be completed with the struct update
+    /// syntax (`..Default::default()`).
+    fn default() -> Self {
+        Self {
+            country: String::new(),
+            city: String::new(),
+            date: String::new(),
+            observation_time: None,
+            temperature: f32::NAN,
+            humidity: None,
+            description: None,
+            condition_code: None,
+            wind_speed_mph: None,
+            precipitation_mm: None,
+            precip_probability: None,
+            feels_like: None,
+            pressure_hpa: None,
+            pressure_tendency: None,
+            station_id: None,
+            sunrise: None,
+            sunset: None,
+            snow_depth_mm: None,
+            precip_type: None,
+            moon_phase: None,
+            periods: None,
+            air_quality_index: None,
+            pm25: None,
+            uv_index_max: None,
+            uv_index_clear_sky: None,
+            cloud_layers: None,
+            icon: None,
+            provider_icon: None,
+            alerts: Vec::new(),
+            meta: None,
+        }
+    }
+}
+
+/// The temperatures of the parts of a day, in degrees Fahrenheit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DayParts {
+    pub morning: f32,
+    pub afternoon: f32,
+    pub evening: f32,
+    pub night: f32,
+    /// The lowest temperature of the day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low: Option<f32>,
+    /// The highest temperature of the day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high: Option<f32>,
+}
+
+/// The weather of an hour, as forecast or observed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HourlyWeather {
+    /// The start of the hour, in the local time of the location.
+    pub time: DateTime<FixedOffset>,
+    /// The temperature in degrees Fahrenheit.
+    pub temperature: f32,
+    pub humidity: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wind_speed_mph: Option<f32>,
+}
+
+/// A severe weather warning, as issued by a national weather service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherAlert {
+    /// The type of the event, e.g. "Heat Advisory".
+    pub event: String,
+    /// When the event starts (UTC).
+    pub start: NaiveDateTime,
+    /// When the event ends (UTC).
+    pub end: NaiveDateTime,
+    pub description: String,
+    /// The issuer of the alert, e.g. "NWS Philadelphia - Mount Holly".
+    pub sender: String,
+}
+
+impl WeatherInfo {
+    /// Creates a reading of the temperature (°F) and humidity with none of the optional data.
+    pub fn new(
+        country: impl Into<String>,
+        city: impl Into<String>,
+        date: impl Into<String>,
+        temperature: f32,
+        humidity: u8,
+    ) -> Self {
+        Self {
+            country: country.into(),
+            city: city.into(),
+            date: date.into(),
+            temperature,
+            humidity: Some(humidity),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if any of the alerts has not ended yet, including events yet to start.
+    pub fn has_active_alerts(&self) -> bool {
+        self.has_active_alerts_with(&SystemClock)
+    }
+
+    /// Like `has_active_alerts`, with the current time taken from `clock`.
+    pub fn has_active_alerts_with(&self, clock: &dyn Clock) -> bool {
+        let now = clock.now().naive_utc();
+        self.alerts.iter().any(|alert| alert.end > now)
+    }
+
+    /// Replaces the reported type of precipitation with the one derived by
+    /// `PrecipType::classify`, e.g. to tell freezing rain from rain.
+    pub fn with_derived_precip_type(mut self) -> Self {
+        let reported = self.precip_type;
+        self.precip_type = PrecipType::classify(self.temperature, reported, self.precipitation_mm);
+        if self.precip_type != reported {
+            self.mark_derived("precip_type");
+        }
+        self
+    }
+
+    /// Derives the icon from the description with `Icon::from_description` if the provider
+    /// reported none. Day icons are used, as the time of day is not known.
+    pub fn with_derived_icon(mut self) -> Self {
+        if self.icon.is_none() {
+            self.icon = self
+                .description
+                .as_deref()
+                .and_then(|d| Icon::from_description(d, true))
+                .map(|icon| icon.code().to_string());
+            if self.icon.is_some() {
+                self.mark_derived("icon");
+            }
+        }
+        self
+    }
+
+    /// Returns `true` if the data was observed by an identified weather station.
+    pub fn is_from_station(&self) -> bool {
+        self.station_id.is_some()
+    }
+
+    /// Sets the probability of precipitation, clamped to 0.0–1.0.
+    pub fn with_precip_probability(mut self, probability: Option<f32>) -> Self {
+        self.precip_probability = probability.map(|p| p.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Returns the probability of precipitation in whole percent (0–100).
+    pub fn precip_probability_pct(&self) -> Option<u8> {
+        self.precip_probability
+            .map(|p| (p.clamp(0.0, 1.0) * 100.0).round() as u8)
+    }
+
+    /// Returns `true` if the highest UV index of the day is 3 or more, from which the WHO
+    /// recommends sun protection. An unknown UV index returns `false`.
+    pub fn sun_protection_needed(&self) -> bool {
+        self.uv_index_max
+            .is_some_and(|uv| uv >= SUN_PROTECTION_UV_INDEX)
+    }
+
+    /// Returns the ceiling: the height in feet of the lowest broken or overcast cloud layer.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the cloud layers are unknown or none of them is broken or overcast.
+    pub fn ceiling_ft(&self) -> Option<u32> {
+        self.cloud_layers
+            .iter()
+            .flatten()
+            .filter(|layer| layer.coverage.is_ceiling())
+            .map(|layer| layer.altitude_ft)
+            .min()
+    }
+
+    /// Returns an emoji for the condition code, e.g. "⛈️" for a thunderstorm.
+    ///
+    /// The lookup table covers the OpenWeather codes (200 to 804). Codes of other providers,
+    /// such as the WeatherAPI codes from 1000, fall outside it and return `None`.
+    pub fn condition_emoji(&self) -> Option<&'static str> {
+        let code = self.condition_code?;
+
+        CONDITION_EMOJI
+            .iter()
+            .find(|(codes, _)| codes.contains(&code))
+            .map(|(_, emoji)| *emoji)
+    }
+
+    /// Returns the name of the lunar phase, e.g. "Waxing Crescent".
+    ///
+    /// Each of the eight phases covers an eighth of the cycle, centered on its principal
+    /// value (e.g., "Full Moon" spans 0.4375 to 0.5625).
+    ///
+    /// # Returns
+    ///
+    /// `None` if the phase is unknown or outside the 0.0 to 1.0 range.
+    pub fn moon_phase_name(&self) -> Option<&'static str> {
+        const NAMES: [&str; 8] = [
+            "New Moon",
+            "Waxing Crescent",
+            "First Quarter",
+            "Waxing Gibbous",
+            "Full Moon",
+            "Waning Gibbous",
+            "Third Quarter",
+            "Waning Crescent",
+        ];
+
+        let phase = self.moon_phase.filter(|p| (0.0..=1.0).contains(p))?;
+        let index = ((phase * 8.0 + 0.5).floor() as usize) % NAMES.len();
+
+        Some(NAMES[index])
+    }
+}
+
+/// Emoji for ranges of OpenWeather condition codes, the first matching range winning.
+///
+/// See <https://openweathermap.org/weather-conditions>.
+const CONDITION_EMOJI: &[(RangeInclusive<u32>, &str)] = &[
+    (200..=232, "⛈️"), // Thunderstorm
+    (300..=321, "🌦️"), // Drizzle
+    (511..=511, "🌨️"), // Freezing rain
+    (500..=531, "🌧️"), // Rain
+    (611..=616, "🌨️"), // Sleet, rain and snow
+    (600..=622, "❄️"), // Snow
+    (781..=781, "🌪️"), // Tornado
+    (701..=771, "🌫️"), // Mist, smoke, haze, dust, fog, squalls
+    (800..=800, "☀️"), // Clear sky
+    (801..=801, "🌤️"), // Few clouds
+    (802..=802, "⛅"), // Scattered clouds
+    (803..=804, "☁️"), // Broken and overcast clouds
+];
+
+#[cfg(test)]
+impl WeatherInfo {
+    /// A reading in London shared by the unit tests, adjusted with the struct update syntax.
+    pub(crate) fn sample() -> Self {
+        Self::new("UK", "London", "2024-01-01", 50.0, 80)
+    }
 }
 
 impl std::fmt::Display for WeatherInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let description = self
-            .description
-            .as_ref()
-            .map(|desc| format!(", {desc}"))
-            .unwrap_or_default();
+        write!(f, "{}", self.format_for_display(&DisplayOptions::default()))
+    }
+}
 
-        write!(
-            f,
-            "Weather in '{}, {}': {:.1}°F{}, Humidity: {}%",
-            self.country, self.city, self.temperature, description, self.humidity
-        )
+/// Reads a number written by `serde_json`, which writes NaN as `null`, back as NaN.
+fn nan_if_null<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    Ok(Option::<f32>::deserialize(deserializer)?.unwrap_or(f32::NAN))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_moon_phase(moon_phase: Option<f32>) -> WeatherInfo {
+        WeatherInfo {
+            moon_phase,
+            ..WeatherInfo::sample()
+        }
+    }
+
+    #[test]
+    fn test_geo_location_display_name() {
+        let mut location = GeoLocation {
+            city: "Springfield".to_string(),
+            region: Some("Missouri".to_string()),
+            country: "US".to_string(),
+            ..GeoLocation::default()
+        };
+        assert_eq!(location.display_name(), "Springfield, Missouri, US");
+
+        location.region = None;
+        location.country = String::new();
+        assert_eq!(location.display_name(), "Springfield");
+    }
+
+    #[test]
+    fn test_has_active_alerts() {
+        use crate::utils::clock::FixedClock;
+        use ::chrono::{TimeZone, Utc};
+
+        let at = |hour| {
+            Utc.with_ymd_and_hms(2024, 1, 15, hour, 0, 0)
+                .unwrap()
+                .naive_utc()
+        };
+        let info = WeatherInfo {
+            alerts: vec![WeatherAlert {
+                event: "Wind Advisory".to_string(),
+                start: at(6),
+                end: at(18),
+                description: "Gusts up to 55 mph.".to_string(),
+                sender: "Met Office".to_string(),
+            }],
+            ..WeatherInfo::sample()
+        };
+        let clock = |hour| FixedClock(Utc.with_ymd_and_hms(2024, 1, 15, hour, 0, 0).unwrap());
+
+        assert!(info.has_active_alerts_with(&clock(3)));
+        assert!(info.has_active_alerts_with(&clock(12)));
+        assert!(!info.has_active_alerts_with(&clock(18)));
+        assert!(!WeatherInfo::sample().has_active_alerts_with(&clock(12)));
+    }
+
+    #[test]
+    fn test_alerts_round_trip() {
+        let json = r#"{"country":"UK","city":"London","date":"2024-01-15","temperature":50.0,
+            "humidity":80,"description":null,"alerts":[{"event":"Wind Advisory",
+            "start":"2024-01-15T06:00:00","end":"2024-01-15T18:00:00",
+            "description":"Gusts up to 55 mph.","sender":"Met Office"}]}"#;
+
+        let info: WeatherInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.alerts[0].event, "Wind Advisory");
+        assert_eq!(info.alerts[0].end.to_string(), "2024-01-15 18:00:00");
+
+        let serialized = serde_json::to_value(&info).unwrap();
+        assert_eq!(serialized["alerts"][0]["start"], "2024-01-15T06:00:00");
+        // No alerts are omitted
+        let serialized = serde_json::to_value(WeatherInfo::sample()).unwrap();
+        assert!(serialized.get("alerts").is_none());
+    }
+
+    #[test]
+    fn test_missing_humidity_round_trip() {
+        let info = WeatherInfo {
+            humidity: None,
+            ..WeatherInfo::sample()
+        };
+        let serialized = serde_json::to_value(&info).unwrap();
+        assert!(serialized.get("humidity").is_none());
+        let info: WeatherInfo = serde_json::from_value(serialized).unwrap();
+        assert_eq!(info.humidity, None);
+
+        // A reading of 0 % is kept, both as saved by older versions and as a new value
+        let json = r#"{"country":"UK","city":"London","date":"2024-01-15","temperature":50.0,
+            "humidity":0,"description":null}"#;
+        let info: WeatherInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.humidity, Some(0));
+        let serialized = serde_json::to_value(&info).unwrap();
+        assert_eq!(serialized["humidity"], 0);
+
+        // A null humidity is a missing one
+        let json = r#"{"country":"UK","city":"London","date":"2024-01-15","temperature":50.0,
+            "humidity":null,"description":null}"#;
+        let info: WeatherInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.humidity, None);
+    }
+
+    #[test]
+    fn test_sun_protection_needed() {
+        let needed = |uv_index_max| {
+            WeatherInfo {
+                uv_index_max,
+                ..WeatherInfo::sample()
+            }
+            .sun_protection_needed()
+        };
+
+        assert!(!needed(Some(2.9)));
+        assert!(needed(Some(3.0)));
+        assert!(needed(Some(3.1)));
+        assert!(!needed(None));
+    }
+
+    #[test]
+    fn test_precip_probability_pct() {
+        let pct = |precip_probability| {
+            WeatherInfo {
+                precip_probability,
+                ..WeatherInfo::sample()
+            }
+            .precip_probability_pct()
+        };
+
+        assert_eq!(pct(Some(0.0)), Some(0));
+        assert_eq!(pct(Some(0.5)), Some(50));
+        assert_eq!(pct(Some(1.0)), Some(100));
+        assert_eq!(pct(Some(0.29)), Some(29));
+        assert_eq!(pct(None), None);
+    }
+
+    #[test]
+    fn test_with_precip_probability() {
+        let with = |probability| WeatherInfo::sample().with_precip_probability(probability);
+
+        for (probability, expected, pct) in [
+            (0.0, 0.0, 0),
+            (0.5, 0.5, 50),
+            (1.0, 1.0, 100),
+            (-0.2, 0.0, 0),
+            (1.3, 1.0, 100),
+        ] {
+            let info = with(Some(probability));
+            assert_eq!(info.precip_probability, Some(expected), "{probability}");
+            assert_eq!(info.precip_probability_pct(), Some(pct), "{probability}");
+        }
+
+        let info = with(None);
+        assert_eq!(info.precip_probability, None);
+        assert_eq!(info.precip_probability_pct(), None);
+    }
+
+    #[test]
+    fn test_ceiling_ft() {
+        let layer = |altitude_ft, coverage| CloudLayer {
+            altitude_ft,
+            coverage,
+        };
+        let ceiling = |cloud_layers| {
+            WeatherInfo {
+                cloud_layers,
+                ..WeatherInfo::sample()
+            }
+            .ceiling_ft()
+        };
+
+        assert_eq!(
+            ceiling(Some(vec![
+                layer(1200, CloudCoverage::Few),
+                layer(2500, CloudCoverage::Scattered),
+                layer(9000, CloudCoverage::Overcast),
+                layer(4000, CloudCoverage::Broken),
+            ])),
+            Some(4000)
+        );
+        // Few and scattered layers do not form a ceiling
+        assert_eq!(
+            ceiling(Some(vec![
+                layer(1200, CloudCoverage::Few),
+                layer(2500, CloudCoverage::Scattered),
+            ])),
+            None
+        );
+        assert_eq!(ceiling(Some(Vec::new())), None);
+        assert_eq!(ceiling(None), None);
+    }
+
+    #[test]
+    fn test_cloud_layer_serialization() {
+        let layer = CloudLayer {
+            altitude_ft: 2500,
+            coverage: CloudCoverage::Broken,
+        };
+
+        let json = serde_json::to_value(layer).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "altitude_ft": 2500, "coverage": "BKN" })
+        );
+        assert_eq!(serde_json::from_value::<CloudLayer>(json).unwrap(), layer);
+        assert_eq!(CloudCoverage::Overcast.to_string(), "OVC");
+    }
+
+    #[test]
+    fn test_moon_phase_name_boundaries() {
+        let name = |phase| with_moon_phase(Some(phase)).moon_phase_name();
+
+        // Each phase starts at its lower boundary
+        for (phase, expected) in [
+            (0.0, "New Moon"),
+            (0.0625, "Waxing Crescent"),
+            (0.1875, "First Quarter"),
+            (0.3125, "Waxing Gibbous"),
+            (0.4375, "Full Moon"),
+            (0.5625, "Waning Gibbous"),
+            (0.6875, "Third Quarter"),
+            (0.8125, "Waning Crescent"),
+        ] {
+            assert_eq!(name(phase), Some(expected), "phase {phase}");
+        }
+
+        assert_eq!(name(0.0624), Some("New Moon"));
+        assert_eq!(name(0.5), Some("Full Moon"));
+        assert_eq!(name(0.9374), Some("Waning Crescent"));
+        assert_eq!(name(0.9375), Some("New Moon"));
+        assert_eq!(name(1.0), Some("New Moon"));
+    }
+
+    #[test]
+    fn test_condition_emoji() {
+        let emoji = |code| {
+            WeatherInfo {
+                condition_code: code,
+                ..WeatherInfo::sample()
+            }
+            .condition_emoji()
+        };
+
+        for (code, expected) in [
+            (200, "⛈️"),
+            (232, "⛈️"),
+            (301, "🌦️"),
+            (500, "🌧️"),
+            (511, "🌨️"),
+            (522, "🌧️"),
+            (601, "❄️"),
+            (611, "🌨️"),
+            (741, "🌫️"),
+            (781, "🌪️"),
+            (800, "☀️"),
+            (801, "🌤️"),
+            (802, "⛅"),
+            (804, "☁️"),
+        ] {
+            assert_eq!(emoji(Some(code)), Some(expected), "code {code}");
+        }
+
+        assert_eq!(emoji(Some(100)), None);
+        assert_eq!(emoji(Some(1063)), None);
+        assert_eq!(emoji(None), None);
+    }
+
+    #[test]
+    fn test_moon_phase_name_out_of_range() {
+        assert_eq!(with_moon_phase(None).moon_phase_name(), None);
+        assert_eq!(with_moon_phase(Some(-0.1)).moon_phase_name(), None);
+        assert_eq!(with_moon_phase(Some(1.5)).moon_phase_name(), None);
+        assert_eq!(with_moon_phase(Some(f32::NAN)).moon_phase_name(), None);
+    }
+
+    #[test]
+    fn test_precip_type_names() {
+        assert_eq!(PrecipType::from_name("Rain"), Some(PrecipType::Rain));
+        assert_eq!(PrecipType::from_name(" snow "), Some(PrecipType::Snow));
+        assert_eq!(PrecipType::from_name("sleet"), Some(PrecipType::Sleet));
+        assert_eq!(PrecipType::from_name("mixed"), Some(PrecipType::Mixed));
+        assert_eq!(PrecipType::from_name("none"), Some(PrecipType::None));
+        assert_eq!(
+            PrecipType::from_name("Freezing rain"),
+            Some(PrecipType::FreezingRain)
+        );
+        assert_eq!(PrecipType::from_name("hail"), None);
+
+        assert_eq!(PrecipType::Sleet.to_string(), "sleet");
+        assert_eq!(
+            serde_json::to_string(&PrecipType::Mixed).unwrap(),
+            r#""mixed""#
+        );
+        assert_eq!(
+            serde_json::to_string(&PrecipType::FreezingRain).unwrap(),
+            r#""freezing_rain""#
+        );
+    }
+
+    #[test]
+    fn test_classify_freezing_point() {
+        use PrecipType as P;
+
+        // 0 °C is exactly the freezing point
+        let zero_celsius = 0.0 * 9.0 / 5.0 + 32.0;
+        assert_eq!(zero_celsius, FREEZING_POINT_F);
+
+        assert_eq!(
+            PrecipType::classify(zero_celsius, Some(P::Rain), None),
+            Some(P::FreezingRain)
+        );
+        assert_eq!(
+            PrecipType::classify(31.9, Some(P::Rain), Some(2.0)),
+            Some(P::FreezingRain)
+        );
+        assert_eq!(
+            PrecipType::classify(32.1, Some(P::Rain), None),
+            Some(P::Rain)
+        );
+
+        // Other reported types are kept on both sides of the freezing point
+        for temperature in [31.9, 32.0, 32.1] {
+            for condition in [P::Snow, P::Sleet, P::Mixed, P::None, P::FreezingRain] {
+                assert_eq!(
+                    PrecipType::classify(temperature, Some(condition), Some(1.0)),
+                    Some(condition)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_classify_unreported_type() {
+        use PrecipType as P;
+
+        let classify = |temperature, mm| PrecipType::classify(temperature, None, mm);
+
+        assert_eq!(classify(FREEZING_POINT_F, Some(1.0)), Some(P::Snow));
+        assert_eq!(classify(32.1, Some(1.0)), Some(P::Sleet));
+        assert_eq!(classify(34.9, Some(1.0)), Some(P::Sleet));
+        assert_eq!(classify(SLEET_MAX_F, Some(1.0)), Some(P::Rain));
+
+        // Trace precipitation
+        assert_eq!(classify(20.0, Some(TRACE_PRECIPITATION_MM)), Some(P::Snow));
+        assert_eq!(classify(20.0, Some(0.09)), Some(P::None));
+        assert_eq!(classify(50.0, Some(0.0)), Some(P::None));
+
+        // Unknown amount or temperature
+        assert_eq!(classify(20.0, None), None);
+        assert_eq!(
+            PrecipType::classify(f32::NAN, Some(P::Rain), Some(1.0)),
+            Some(P::Rain)
+        );
+    }
+
+    #[test]
+    fn test_with_derived_precip_type() {
+        let info = WeatherInfo {
+            temperature: 30.0,
+            precip_type: Some(PrecipType::Rain),
+            ..WeatherInfo::sample()
+        };
+
+        assert_eq!(
+            info.with_derived_precip_type().precip_type,
+            Some(PrecipType::FreezingRain)
+        );
     }
 }
@@ -0,0 +1,44 @@
+use ::serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct OpenMeteoGeoResponse {
+    /// Missing if no location matches the query.
+    #[serde(default)]
+    pub results: Vec<OpenMeteoGeoResult>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenMeteoGeoResult {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub country: Option<String>,
+    /// The first-level administrative area, e.g. a state.
+    #[serde(default)]
+    pub admin1: Option<String>,
+}
+
+/// The hourly air quality of the Open-Meteo Air Quality API.
+#[derive(Deserialize)]
+pub struct OpenMeteoAirQualityResponse {
+    pub hourly: OpenMeteoAirQualityHourly,
+}
+
+/// Parallel arrays with one value per hour, starting at local midnight. Hours without a
+/// value are `null`.
+#[derive(Deserialize)]
+pub struct OpenMeteoAirQualityHourly {
+    /// The concentration of fine particulate matter in μg/m³.
+    #[serde(default)]
+    pub pm2_5: Vec<Option<f32>>,
+    /// The European Air Quality Index (0 to 100 and above).
+    #[serde(default)]
+    pub european_aqi: Vec<Option<f32>>,
+    /// The UV index, considering clouds.
+    #[serde(default)]
+    pub uv_index: Vec<Option<f32>>,
+    /// The UV index under a clear sky.
+    #[serde(default)]
+    pub uv_index_clear_sky: Vec<Option<f32>>,
+}
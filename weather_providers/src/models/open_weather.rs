@@ -31,20 +31,181 @@ pub struct OpenWeatherGeoResponse {
     pub lat: f64,
     pub lon: f64,
     pub country: String,
+    /// Reported for some countries only (e.g., "Missouri").
+    #[serde(default)]
+    pub state: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub struct OpenWeatherResponse {
     pub temperature: OpenWeatherTemperature,
     pub humidity: OpenWeatherHumidity,
+    pub precipitation: Option<OpenWeatherPrecipitation>,
+    pub wind: Option<OpenWeatherWind>,
+    /// Not part of every response (e.g., "rain", "snow").
+    pub precipitation_type: Option<String>,
+    pub snow: Option<OpenWeatherSnow>,
+    pub pressure: Option<OpenWeatherPressure>,
 }
 
+/// `day_summary` reports the pressure of the afternoon only, in hPa.
+#[derive(Deserialize)]
+pub struct OpenWeatherPressure {
+    pub afternoon: f32,
+}
+
+#[derive(Deserialize)]
+pub struct OpenWeatherSnow {
+    /// Snow volume for the last hour, in millimeters.
+    #[serde(rename = "1h")]
+    pub one_hour: f32,
+}
+
+/// `day_summary` reports the humidity of the afternoon only.
 #[derive(Deserialize)]
 pub struct OpenWeatherHumidity {
     pub afternoon: u8,
 }
 
+/// The temperatures of the day: its extremes and the parts of the day (12:00, 06:00, 18:00 and
+/// 00:00 local time).
 #[derive(Deserialize)]
 pub struct OpenWeatherTemperature {
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub morning: Option<f32>,
     pub afternoon: f32,
+    pub evening: Option<f32>,
+    pub night: Option<f32>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenWeatherPrecipitation {
+    pub total: f32,
+}
+
+#[derive(Deserialize)]
+pub struct OpenWeatherWind {
+    pub max: OpenWeatherWindMax,
+}
+
+#[derive(Deserialize)]
+pub struct OpenWeatherWindMax {
+    pub speed: f32,
+}
+
+/// The response of the One Call 3.0 endpoint (`/data/3.0/onecall`).
+#[derive(Clone, Deserialize)]
+pub struct OpenWeatherOneCallResponse {
+    /// The shift of the location's local time from UTC, in seconds.
+    pub timezone_offset: i64,
+    pub current: Option<OpenWeatherOneCallCurrent>,
+    #[serde(default)]
+    pub hourly: Vec<OpenWeatherOneCallHourly>,
+    #[serde(default)]
+    pub daily: Vec<OpenWeatherOneCallDaily>,
+    #[serde(default)]
+    pub alerts: Vec<OpenWeatherOneCallAlert>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct OpenWeatherOneCallCurrent {
+    /// Unix time of the observation.
+    pub dt: i64,
+    pub sunrise: Option<i64>,
+    pub sunset: Option<i64>,
+    pub temp: f32,
+    pub feels_like: f32,
+    /// The sea-level pressure in hPa.
+    pub pressure: Option<f32>,
+    pub humidity: u8,
+    pub wind_speed: Option<f32>,
+    #[serde(default)]
+    pub weather: Vec<OpenWeatherCondition>,
+    pub rain: Option<OpenWeatherLastHour>,
+    pub snow: Option<OpenWeatherLastHour>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct OpenWeatherLastHour {
+    /// Precipitation for the last hour, in millimeters.
+    #[serde(rename = "1h")]
+    pub one_hour: f32,
+}
+
+/// An hour of the forecast of the next 48 hours.
+#[derive(Clone, Deserialize)]
+pub struct OpenWeatherOneCallHourly {
+    /// Unix time of the start of the hour.
+    pub dt: i64,
+    pub temp: f32,
+    pub humidity: u8,
+    pub wind_speed: Option<f32>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct OpenWeatherOneCallDaily {
+    /// Unix time of noon at the location.
+    pub dt: i64,
+    pub sunrise: Option<i64>,
+    pub sunset: Option<i64>,
+    pub temp: OpenWeatherDailyTemperature,
+    pub feels_like: OpenWeatherDailyFeelsLike,
+    /// The sea-level pressure in hPa.
+    pub pressure: Option<f32>,
+    pub humidity: u8,
+    pub wind_speed: Option<f32>,
+    #[serde(default)]
+    pub weather: Vec<OpenWeatherCondition>,
+    /// Rain volume, in millimeters.
+    pub rain: Option<f32>,
+    /// Snow volume, in millimeters.
+    pub snow: Option<f32>,
+    /// The probability of precipitation from 0.0 to 1.0.
+    pub pop: Option<f32>,
+    /// The lunar phase from 0.0 (new moon) to 1.0, 0.5 being a full moon.
+    pub moon_phase: Option<f32>,
+    /// The highest UV index of the day.
+    pub uvi: Option<f32>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct OpenWeatherDailyTemperature {
+    pub day: f32,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct OpenWeatherDailyFeelsLike {
+    pub day: f32,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct OpenWeatherCondition {
+    /// The condition code, e.g. `500` for light rain.
+    #[serde(default)]
+    pub id: Option<u32>,
+    pub description: String,
+    /// The icon code, e.g. "10d".
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct OpenWeatherOneCallAlert {
+    pub sender_name: String,
+    pub event: String,
+    /// Unix time of the start of the event.
+    pub start: i64,
+    /// Unix time of the end of the event.
+    pub end: i64,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// The error body of OpenWeather, e.g. `{"cod": 401, "message": "Invalid API key..."}`.
+#[derive(Deserialize)]
+pub struct OpenWeatherErrorResponse {
+    /// The error code, sent as a number or as a string depending on the endpoint.
+    pub cod: serde_json::Value,
+    pub message: String,
 }
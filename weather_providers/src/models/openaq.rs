@@ -0,0 +1,75 @@
+use ::serde::Deserialize;
+
+/// A page of results of the OpenAQ v3 API.
+#[derive(Deserialize)]
+pub struct OpenAqResponse<T> {
+    pub meta: OpenAqMeta,
+    pub results: Vec<T>,
+}
+
+/// The pagination of a response.
+#[derive(Deserialize)]
+pub struct OpenAqMeta {
+    /// The page number, starting at 1.
+    pub page: u32,
+    /// The maximum number of results per page.
+    pub limit: u32,
+    /// The number of results across all pages.
+    pub found: OpenAqFound,
+}
+
+impl<T> OpenAqResponse<T> {
+    /// Returns `true` if more results follow on the next page.
+    pub fn has_next_page(&self) -> bool {
+        match &self.meta.found {
+            OpenAqFound::Exact(found) => u64::from(self.meta.page * self.meta.limit) < *found,
+            OpenAqFound::AtLeast(_) => self.results.len() == self.meta.limit as usize,
+        }
+    }
+}
+
+/// The number of results of a query: exact, or a lower bound such as ">1000" for large ones.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum OpenAqFound {
+    Exact(u64),
+    AtLeast(String),
+}
+
+/// A monitoring location (station), with the sensors installed there.
+#[derive(Deserialize)]
+pub struct OpenAqLocation {
+    pub id: u64,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub sensors: Vec<OpenAqSensor>,
+    /// The distance in meters from the searched coordinates.
+    pub distance: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAqSensor {
+    pub id: u64,
+    pub parameter: OpenAqParameter,
+}
+
+/// A measured quantity, e.g. "pm25".
+#[derive(Deserialize)]
+pub struct OpenAqParameter {
+    pub name: String,
+}
+
+/// The latest measurement of a sensor of a location.
+#[derive(Deserialize)]
+pub struct OpenAqLatest {
+    pub datetime: OpenAqDateTime,
+    pub value: f32,
+    #[serde(rename = "sensorsId")]
+    pub sensors_id: u64,
+}
+
+#[derive(Deserialize)]
+pub struct OpenAqDateTime {
+    /// An RFC 3339 timestamp in UTC, e.g. "2024-01-15T12:00:00Z".
+    pub utc: String,
+}
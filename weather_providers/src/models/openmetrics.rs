@@ -0,0 +1,380 @@
+//! # Metrics Exposition
+//!
+//! Renders weather data as gauges in the Prometheus or OpenMetrics text exposition format,
+//! e.g. for the node_exporter textfile collector.
+
+use super::WeatherInfo;
+use ::std::fmt::Write;
+
+/// The text format of a metrics exposition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpositionFormat {
+    /// The Prometheus text exposition format.
+    Prometheus,
+    /// The OpenMetrics text format, terminated by the mandatory `# EOF` line.
+    OpenMetrics,
+}
+
+/// The weather of one location, with the labels of its samples besides `city` and `country`
+/// (e.g., `("provider", "ow")`).
+#[derive(Debug, Clone)]
+pub struct MetricsSample<'a> {
+    /// The weather data to expose.
+    pub info: &'a WeatherInfo,
+    /// Additional labels, in the order they are written.
+    pub labels: Vec<(&'a str, &'a str)>,
+}
+
+/// A gauge exported for every numeric field: name, help text and value accessor.
+type Gauge = (&'static str, &'static str, fn(&WeatherInfo) -> Option<f32>);
+
+/// The gauges of the numeric fields of `WeatherInfo`, in the order they are written.
+const GAUGES: &[Gauge] = &[
+    (
+        "weather_temperature_fahrenheit",
+        "Temperature in degrees Fahrenheit.",
+        |w| Some(w.temperature).filter(|t| !t.is_nan()),
+    ),
+    (
+        "weather_feels_like_fahrenheit",
+        "Apparent temperature in degrees Fahrenheit.",
+        |w| w.feels_like,
+    ),
+    (
+        "weather_humidity_percent",
+        "Relative humidity in percent.",
+        |w| w.humidity.map(f32::from),
+    ),
+    (
+        "weather_condition_code",
+        "Condition code as reported by the provider.",
+        |w| w.condition_code.map(|code| code as f32),
+    ),
+    (
+        "weather_wind_speed_mph",
+        "Wind speed in miles per hour.",
+        |w| w.wind_speed_mph,
+    ),
+    (
+        "weather_precipitation_millimeters",
+        "Precipitation amount in millimeters.",
+        |w| w.precipitation_mm,
+    ),
+    (
+        "weather_precipitation_probability_ratio",
+        "Probability of precipitation from 0 to 1.",
+        |w| w.precip_probability,
+    ),
+    (
+        "weather_pressure_hectopascals",
+        "Sea-level air pressure in hectopascals.",
+        |w| w.pressure_hpa,
+    ),
+    (
+        "weather_snow_depth_millimeters",
+        "Snow depth in millimeters.",
+        |w| w.snow_depth_mm,
+    ),
+    (
+        "weather_moon_phase_ratio",
+        "Lunar phase from 0 (new moon) to 1, with 0.5 the full moon.",
+        |w| w.moon_phase,
+    ),
+    (
+        "weather_air_quality_index",
+        "US EPA Air Quality Index.",
+        |w| w.air_quality_index.map(f32::from),
+    ),
+    (
+        "weather_pm25_micrograms_per_cubic_meter",
+        "Concentration of fine particulate matter (PM2.5) in micrograms per cubic meter.",
+        |w| w.pm25,
+    ),
+    (
+        "weather_uv_index_max",
+        "Highest UV index of the day.",
+        |w| w.uv_index_max,
+    ),
+    (
+        "weather_uv_index_clear_sky_max",
+        "Highest UV index of the day under a clear sky.",
+        |w| w.uv_index_clear_sky,
+    ),
+];
+
+/// Renders the samples as gauges in the given exposition format.
+///
+/// Each metric is emitted once with its `# HELP` and `# TYPE` header, followed by one sample
+/// per location labelled with `city`, `country` and the labels of the sample. Values that are
+/// not reported are skipped for that metric only, and a metric without any value is omitted.
+/// The weather description is exposed as a `weather_condition_info` gauge with the value `1`,
+/// carrying the description as a label.
+///
+/// # Examples
+///
+/// ```rust
+/// use weather_providers::{ExpositionFormat, MetricsSample, WeatherInfo, render_metrics};
+///
+/// fn expose(info: &WeatherInfo) {
+///     let sample = MetricsSample {
+///         info,
+///         labels: vec![("provider", "ow")],
+///     };
+///     print!("{}", render_metrics(&[sample], ExpositionFormat::Prometheus));
+/// }
+/// ```
+pub fn render_metrics(samples: &[MetricsSample], format: ExpositionFormat) -> String {
+    let mut out = String::new();
+
+    for (name, help, value) in GAUGES {
+        let lines = samples
+            .iter()
+            .filter_map(|s| value(s.info).map(|v| (labels(s, None), v)))
+            .collect::<Vec<_>>();
+
+        write_metric(&mut out, name, help, &lines);
+    }
+
+    let conditions = samples
+        .iter()
+        .filter_map(|s| {
+            s.info
+                .description
+                .as_deref()
+                .map(|desc| (labels(s, Some(desc)), 1.0))
+        })
+        .collect::<Vec<_>>();
+
+    write_metric(
+        &mut out,
+        "weather_condition_info",
+        "Weather condition description.",
+        &conditions,
+    );
+
+    if format == ExpositionFormat::OpenMetrics {
+        out.push_str("# EOF\n");
+    }
+
+    out
+}
+
+impl WeatherInfo {
+    /// Renders the numeric fields as OpenMetrics text exposition format (see `render_metrics`).
+    ///
+    /// Every metric is a gauge labelled with `city` and `country`. The output is terminated by
+    /// the mandatory `# EOF` line and a newline.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use weather_providers::WeatherInfo;
+    ///
+    /// fn expose(info: &WeatherInfo) {
+    ///     print!("{}", info.to_openmetrics());
+    /// }
+    /// ```
+    pub fn to_openmetrics(&self) -> String {
+        let sample = MetricsSample {
+            info: self,
+            labels: Vec::new(),
+        };
+
+        render_metrics(&[sample], ExpositionFormat::OpenMetrics)
+    }
+}
+
+fn write_metric(out: &mut String, name: &str, help: &str, lines: &[(String, f32)]) {
+    if lines.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+
+    for (labels, value) in lines {
+        let _ = writeln!(out, "{name}{{{labels}}} {value}");
+    }
+}
+
+fn labels(sample: &MetricsSample, description: Option<&str>) -> String {
+    let mut labels = format!(
+        "city=\"{}\",country=\"{}\"",
+        escape_label_value(&sample.info.city),
+        escape_label_value(&sample.info.country),
+    );
+
+    for (name, value) in &sample.labels {
+        let _ = write!(labels, ",{name}=\"{}\"", escape_label_value(value));
+    }
+    if let Some(desc) = description {
+        let _ = write!(labels, ",description=\"{}\"", escape_label_value(desc));
+    }
+
+    labels
+}
+
+/// Escapes a label value: backslash, double quote and line feed must be escaped.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weather(city: &str) -> WeatherInfo {
+        WeatherInfo {
+            city: city.to_string(),
+            temperature: 72.3,
+            humidity: Some(40),
+            precipitation_mm: Some(1.5),
+            ..WeatherInfo::sample()
+        }
+    }
+
+    fn prometheus(info: &WeatherInfo, provider: &str) -> String {
+        let sample = MetricsSample {
+            info,
+            labels: vec![("provider", provider)],
+        };
+
+        render_metrics(&[sample], ExpositionFormat::Prometheus)
+    }
+
+    #[test]
+    fn test_openmetrics_format() {
+        let output = weather("London").to_openmetrics();
+
+        assert!(output.starts_with(
+            "# HELP weather_temperature_fahrenheit Temperature in degrees Fahrenheit.\n\
+             # TYPE weather_temperature_fahrenheit gauge\n\
+             weather_temperature_fahrenheit{city=\"London\",country=\"UK\"} 72.3\n"
+        ));
+        assert!(output.contains("weather_humidity_percent{city=\"London\",country=\"UK\"} 40\n"));
+        assert!(
+            output.contains(
+                "weather_precipitation_millimeters{city=\"London\",country=\"UK\"} 1.5\n"
+            )
+        );
+        // Missing optional fields are omitted entirely
+        assert!(!output.contains("weather_wind_speed_mph"));
+        // The exposition must be terminated by "# EOF" and a newline
+        assert!(output.ends_with("\n# EOF\n"));
+    }
+
+    #[test]
+    fn test_every_numeric_field() {
+        let info = WeatherInfo {
+            feels_like: Some(70.0),
+            condition_code: Some(500),
+            wind_speed_mph: Some(8.5),
+            precip_probability: Some(0.35),
+            pressure_hpa: Some(1013.2),
+            snow_depth_mm: Some(20.0),
+            moon_phase: Some(0.5),
+            air_quality_index: Some(42),
+            pm25: Some(10.1),
+            uv_index_max: Some(6.2),
+            uv_index_clear_sky: Some(7.0),
+            ..weather("London")
+        };
+        let output = prometheus(&info, "ow");
+
+        for (name, ..) in GAUGES {
+            assert!(output.contains(&format!("# TYPE {name} gauge\n")), "{name}");
+        }
+        assert!(output.contains(
+            "weather_precipitation_probability_ratio{city=\"London\",country=\"UK\",\
+             provider=\"ow\"} 0.35\n"
+        ));
+        // A temperature that is not reported is not a sample
+        let info = WeatherInfo {
+            temperature: f32::NAN,
+            ..info
+        };
+        assert!(!prometheus(&info, "ow").contains("weather_temperature_fahrenheit"));
+    }
+
+    #[test]
+    fn test_prometheus_format() {
+        let info = WeatherInfo {
+            description: None,
+            ..weather("London")
+        };
+        let output = prometheus(&info, "ow");
+
+        assert!(output.contains(
+            "weather_temperature_fahrenheit{city=\"London\",country=\"UK\",provider=\"ow\"} 72.3\n"
+        ));
+        // Missing descriptions produce no metric at all, and only OpenMetrics ends with EOF
+        assert!(!output.contains("weather_condition_info"));
+        assert!(!output.contains("# EOF"));
+        assert!(output.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("plain"), "plain");
+        assert_eq!(escape_label_value("say \"hi\""), "say \\\"hi\\\"");
+        assert_eq!(escape_label_value("C:\\temp"), "C:\\\\temp");
+        assert_eq!(escape_label_value("line1\nline2"), "line1\\nline2");
+    }
+
+    #[test]
+    fn test_label_escaping() {
+        let info = WeatherInfo {
+            description: Some("Heavy \"rain\"\nwith \\ wind".to_string()),
+            ..weather("Saint \"John's\"\\Town\nEast")
+        };
+        let output = prometheus(&info, "wa");
+
+        assert!(output.contains(
+            "weather_temperature_fahrenheit{city=\"Saint \\\"John's\\\"\\\\Town\\nEast\",\
+             country=\"UK\",provider=\"wa\"} 72.3\n"
+        ));
+        assert!(
+            output.contains(
+                ",provider=\"wa\",description=\"Heavy \\\"rain\\\"\\nwith \\\\ wind\"} 1\n"
+            )
+        );
+        // Each sample stays on a single line
+        assert!(
+            output
+                .lines()
+                .all(|l| l.starts_with('#') || l.starts_with("weather_"))
+        );
+    }
+
+    #[test]
+    fn test_multiple_locations() {
+        let london = weather("London");
+        let paris = WeatherInfo {
+            temperature: 65.0,
+            ..weather("Paris")
+        };
+        let samples = [&london, &paris].map(|info| MetricsSample {
+            info,
+            labels: vec![("provider", "ow")],
+        });
+        let output = render_metrics(&samples, ExpositionFormat::Prometheus);
+
+        assert_eq!(
+            output
+                .matches("# TYPE weather_temperature_fahrenheit gauge")
+                .count(),
+            1
+        );
+        assert_eq!(
+            output
+                .lines()
+                .filter(|l| l.starts_with("weather_temperature_fahrenheit{"))
+                .count(),
+            2
+        );
+        assert!(output.contains("city=\"Paris\",country=\"UK\",provider=\"ow\"} 65\n"));
+    }
+}
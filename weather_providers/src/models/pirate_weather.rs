@@ -0,0 +1,38 @@
+use ::serde::Deserialize;
+
+/// A response in the Dark Sky format, requested in US units.
+#[derive(Deserialize)]
+pub struct PirateWeatherResponse {
+    pub currently: PirateWeatherDataPoint,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PirateWeatherDataPoint {
+    /// The Unix timestamp of the data point.
+    pub time: i64,
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// The icon code in the Skycons naming, e.g. "partly-cloudy-night".
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// The temperature in degrees Fahrenheit.
+    pub temperature: f32,
+    #[serde(default)]
+    pub apparent_temperature: Option<f32>,
+    /// The relative humidity, from 0 to 1.
+    #[serde(default)]
+    pub humidity: Option<f32>,
+    /// The sea-level pressure in hectopascals.
+    #[serde(default)]
+    pub pressure: Option<f32>,
+    /// The wind speed in miles per hour.
+    #[serde(default)]
+    pub wind_speed: Option<f32>,
+    /// The precipitation intensity in inches per hour.
+    #[serde(default)]
+    pub precip_intensity: Option<f32>,
+    /// "rain", "snow" or "sleet"; "none" without precipitation.
+    #[serde(default)]
+    pub precip_type: Option<String>,
+}
@@ -0,0 +1,108 @@
+use crate::{Provider, models::mock::MockFaults, utils::signing::RequestSigner};
+use ::serde::{Deserialize, Serialize};
+use ::std::sync::Arc;
+
+/// Structured, static information about a weather provider.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ProviderMetadata {
+    /// The short identifier used on the command line and in the configuration (e.g., "ow").
+    pub id: &'static str,
+    /// The human-readable provider name (e.g., "OpenWeather").
+    pub name: &'static str,
+    /// The provider's website, where API keys can be obtained.
+    pub website: &'static str,
+    /// Whether an API key must be configured to use the provider.
+    pub requires_key: bool,
+    /// Whether weather for past dates can be retrieved.
+    pub supports_history: bool,
+    /// Whether weather for future dates can be retrieved.
+    pub supports_forecast: bool,
+    /// Whether addresses are resolved by a geocoding step before the weather request.
+    ///
+    /// Providers addressed by station or device identifiers do not need geocoding.
+    pub requires_geocoding: bool,
+    /// Whether the provider works in a `wasm32` build, where requests go through the browser's
+    /// `fetch` API (and are therefore subject to the provider's CORS policy).
+    pub wasm_compatible: bool,
+    /// Provider-specific usage notes, e.g. the expected address or API key format.
+    pub notes: Option<&'static str>,
+}
+
+/// A provider supported by this build of the library, listed by `available_providers`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderDescriptor {
+    /// The provider variant.
+    pub provider: Provider,
+    /// The short identifier (e.g., "ow"), as in `ProviderMetadata::id`.
+    pub id: &'static str,
+    /// The human-readable provider name (e.g., "OpenWeather").
+    pub name: &'static str,
+    /// Whether an API key must be configured to use the provider.
+    pub requires_key: bool,
+    /// The supported capabilities: "current", plus "history", "forecast" and "geocoding"
+    /// as reported by `ProviderMetadata`.
+    pub capabilities: Vec<&'static str>,
+    /// Whether the implementation is compiled in. Providers behind a disabled cargo feature
+    /// (e.g., `grpc`) are listed, but cannot reach their service.
+    pub compiled_in: bool,
+}
+
+/// Getting-started information about a weather provider, shown by `weather provider <id> --info`.
+///
+/// Each provider implementation maintains its own info, so it stays accurate as the
+/// implementation changes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ProviderInfo {
+    /// Where to sign up for an API key.
+    pub signup_url: &'static str,
+    /// What a valid API key looks like.
+    pub key_format: &'static str,
+    /// The limits of the free tier.
+    pub free_tier: &'static str,
+    /// The supported capabilities (e.g., "current", "history", "forecast", "geocoding").
+    pub capabilities: &'static [&'static str],
+    /// An example command using the provider.
+    pub example: &'static str,
+}
+
+/// The OpenWeather endpoint used to retrieve weather.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OpenWeatherEndpoint {
+    /// One Call 3.0 `day_summary`: one aggregated request per date.
+    #[default]
+    #[serde(rename = "day_summary")]
+    DaySummary,
+    /// One Call 3.0 full endpoint: current conditions, an 8-day daily forecast and alerts.
+    #[serde(rename = "onecall")]
+    OneCall,
+}
+
+/// Provider-specific settings applied when creating a provider with `create_provider_with`.
+///
+/// Settings that do not apply to the selected provider are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct ProviderOptions {
+    /// The endpoint used by the OpenWeather provider.
+    pub open_weather_endpoint: OpenWeatherEndpoint,
+    /// Accept invalid TLS certificates, e.g. of a local HTTPS proxy capturing provider traffic.
+    ///
+    /// Only available with the `danger` feature. Never enable it outside of development: it
+    /// exposes API keys and responses to anyone able to intercept the connection.
+    #[cfg(feature = "danger")]
+    pub danger_accept_invalid_certs: bool,
+    /// Signs every request of the provider, e.g. for a gateway that requires an HMAC of each
+    /// request (see `HmacSha256Signer`).
+    pub signer: Option<Arc<dyn RequestSigner>>,
+    /// Follow redirects to other hosts. By default only redirects within the requested host are
+    /// followed, and a redirect elsewhere, e.g. by a captive portal, fails with
+    /// `Error::Redirected`.
+    pub allow_cross_host_redirects: bool,
+    /// Latency and failures injected by the mock provider. If `None`, the mock provider reads
+    /// them from the `WEATHER_PROVIDERS_MOCK_FAULTS` environment variable, if set.
+    pub mock_faults: Option<MockFaults>,
+    /// The media types accepted in responses, most preferred first (e.g., `application/xml`),
+    /// sent in the `Accept` header by providers able to answer in several formats.
+    ///
+    /// Providers that only answer in JSON, currently all of them, ignore it.
+    pub accepted_formats: Vec<String>,
+}
@@ -0,0 +1,23 @@
+use ::serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct PurpleAirSensorResponse {
+    /// The time the sensor data was last updated, in seconds since the Unix epoch.
+    pub data_time_stamp: i64,
+    pub sensor: PurpleAirSensor,
+}
+
+#[derive(Deserialize)]
+pub struct PurpleAirSensor {
+    pub sensor_index: u64,
+    pub name: Option<String>,
+    /// The time the sensor last reported, in seconds since the Unix epoch.
+    pub last_seen: Option<i64>,
+    /// Temperature inside the sensor housing in degrees Fahrenheit.
+    pub temperature: Option<f32>,
+    /// Relative humidity inside the sensor housing in percent.
+    pub humidity: Option<f32>,
+    /// PM2.5 mass concentration in micrograms per cubic meter.
+    #[serde(rename = "pm2.5")]
+    pub pm2_5: Option<f32>,
+}
@@ -0,0 +1,284 @@
+use super::{DisplayOptions, HourlyWeather, WeatherInfo};
+use ::serde::{Deserialize, Serialize};
+
+/// Limits beyond which the weather is considered unsafe for outdoor activities.
+///
+/// Temperatures are in Fahrenheit and wind speeds in miles per hour, like in `WeatherInfo`.
+/// A limit that is not set is not checked.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SafetyThresholds {
+    /// The highest safe temperature (°F).
+    #[serde(alias = "maxTemperatureF", skip_serializing_if = "Option::is_none")]
+    pub max_temperature_f: Option<f32>,
+    /// The lowest safe temperature (°F).
+    #[serde(alias = "minTemperatureF", skip_serializing_if = "Option::is_none")]
+    pub min_temperature_f: Option<f32>,
+    /// The highest safe relative humidity (%).
+    #[serde(alias = "maxHumidity", skip_serializing_if = "Option::is_none")]
+    pub max_humidity: Option<u8>,
+    /// The highest safe wind speed (mph).
+    #[serde(alias = "maxWindSpeedMph", skip_serializing_if = "Option::is_none")]
+    pub max_wind_speed_mph: Option<f32>,
+}
+
+impl Default for SafetyThresholds {
+    /// Warns above 95 °F or 90% relative humidity.
+    fn default() -> Self {
+        Self {
+            max_temperature_f: Some(95.0),
+            min_temperature_f: None,
+            max_humidity: Some(90),
+            max_wind_speed_mph: None,
+        }
+    }
+}
+
+/// A safety threshold exceeded by the weather.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Breach {
+    /// The temperature is above `max_temperature_f`.
+    TooHot { temperature: f32, limit: f32 },
+    /// The temperature is below `min_temperature_f`.
+    TooCold { temperature: f32, limit: f32 },
+    /// The relative humidity is above `max_humidity`.
+    TooHumid { humidity: u8, limit: u8 },
+    /// The wind speed is above `max_wind_speed_mph`.
+    TooWindy { wind_speed_mph: f32, limit: f32 },
+}
+
+impl Breach {
+    /// Describes the breach with temperatures in the unit of the display options, e.g.
+    /// "Temperature 36.1°C is above the limit of 35.0°C".
+    pub fn describe(&self, opts: &DisplayOptions) -> String {
+        let temperature = |fahrenheit: f32| {
+            format!(
+                "{}{}",
+                opts.format_decimal(opts.temperature_unit.from_fahrenheit(fahrenheit)),
+                opts.temperature_unit.symbol()
+            )
+        };
+
+        match *self {
+            Self::TooHot {
+                temperature: t,
+                limit,
+            } => format!(
+                "Temperature {} is above the limit of {}",
+                temperature(t),
+                temperature(limit)
+            ),
+            Self::TooCold {
+                temperature: t,
+                limit,
+            } => format!(
+                "Temperature {} is below the limit of {}",
+                temperature(t),
+                temperature(limit)
+            ),
+            Self::TooHumid { humidity, limit } => {
+                format!("Humidity {humidity}% is above the limit of {limit}%")
+            }
+            Self::TooWindy {
+                wind_speed_mph,
+                limit,
+            } => format!(
+                "Wind speed {} mph is above the limit of {} mph",
+                opts.format_decimal(wind_speed_mph),
+                opts.format_decimal(limit)
+            ),
+        }
+    }
+}
+
+impl SafetyThresholds {
+    /// Returns the thresholds exceeded by the readings, in the order of the fields. A missing
+    /// humidity or wind speed never exceeds its limit.
+    fn breaches_of(
+        &self,
+        temperature: f32,
+        humidity: Option<u8>,
+        wind_speed_mph: Option<f32>,
+    ) -> Vec<Breach> {
+        let mut breaches = Vec::new();
+
+        if let Some(limit) = self.max_temperature_f
+            && temperature > limit
+        {
+            breaches.push(Breach::TooHot { temperature, limit });
+        }
+        if let Some(limit) = self.min_temperature_f
+            && temperature < limit
+        {
+            breaches.push(Breach::TooCold { temperature, limit });
+        }
+        if let (Some(limit), Some(humidity)) = (self.max_humidity, humidity)
+            && humidity > limit
+        {
+            breaches.push(Breach::TooHumid { humidity, limit });
+        }
+        if let (Some(limit), Some(wind_speed_mph)) = (self.max_wind_speed_mph, wind_speed_mph)
+            && wind_speed_mph > limit
+        {
+            breaches.push(Breach::TooWindy {
+                wind_speed_mph,
+                limit,
+            });
+        }
+
+        breaches
+    }
+}
+
+impl HourlyWeather {
+    /// Returns the safety thresholds exceeded in the hour, like `WeatherInfo::breaches`.
+    pub fn breaches(&self, thresholds: &SafetyThresholds) -> Vec<Breach> {
+        thresholds.breaches_of(self.temperature, Some(self.humidity), self.wind_speed_mph)
+    }
+}
+
+impl WeatherInfo {
+    /// Returns the safety thresholds exceeded by the weather, in the order of the fields of
+    /// `SafetyThresholds`. A missing humidity or wind speed never exceeds its limit.
+    pub fn breaches(&self, thresholds: &SafetyThresholds) -> Vec<Breach> {
+        thresholds.breaches_of(self.temperature, self.humidity, self.wind_speed_mph)
+    }
+
+    /// Returns whether the weather is within all safety thresholds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use weather_providers::{SafetyThresholds, WeatherInfo};
+    ///
+    /// fn check(info: &WeatherInfo) {
+    ///     if !info.is_outdoor_safe(&SafetyThresholds::default()) {
+    ///         println!("Stay inside!");
+    ///     }
+    /// }
+    /// ```
+    pub fn is_outdoor_safe(&self, thresholds: &SafetyThresholds) -> bool {
+        self.breaches(thresholds).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Unit;
+
+    fn weather(temperature: f32, humidity: u8, wind_speed_mph: Option<f32>) -> WeatherInfo {
+        WeatherInfo {
+            temperature,
+            humidity: Some(humidity),
+            wind_speed_mph,
+            ..WeatherInfo::sample()
+        }
+    }
+
+    #[test]
+    fn test_default_thresholds() {
+        let thresholds = SafetyThresholds::default();
+
+        assert!(weather(95.0, 90, Some(40.0)).is_outdoor_safe(&thresholds));
+        assert_eq!(
+            weather(104.0, 91, None).breaches(&thresholds),
+            [
+                Breach::TooHot {
+                    temperature: 104.0,
+                    limit: 95.0
+                },
+                Breach::TooHumid {
+                    humidity: 91,
+                    limit: 90
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_thresholds() {
+        let thresholds = SafetyThresholds {
+            max_temperature_f: None,
+            min_temperature_f: Some(14.0),
+            max_humidity: None,
+            max_wind_speed_mph: Some(30.0),
+        };
+
+        assert!(weather(120.0, 100, None).is_outdoor_safe(&thresholds));
+        assert_eq!(
+            weather(10.0, 50, Some(35.0)).breaches(&thresholds),
+            [
+                Breach::TooCold {
+                    temperature: 10.0,
+                    limit: 14.0
+                },
+                Breach::TooWindy {
+                    wind_speed_mph: 35.0,
+                    limit: 30.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let celsius = DisplayOptions {
+            temperature_unit: Unit::Celsius,
+            ..DisplayOptions::default()
+        };
+        let hot = Breach::TooHot {
+            temperature: 104.0,
+            limit: 95.0,
+        };
+
+        assert_eq!(
+            hot.describe(&celsius),
+            "Temperature 40.0°C is above the limit of 35.0°C"
+        );
+        assert_eq!(
+            Breach::TooHumid {
+                humidity: 95,
+                limit: 90
+            }
+            .describe(&celsius),
+            "Humidity 95% is above the limit of 90%"
+        );
+    }
+
+    #[test]
+    fn test_hourly_breaches() {
+        let hour = HourlyWeather {
+            time: "2024-07-15T06:00:00-07:00".parse().unwrap(),
+            temperature: 30.0,
+            humidity: 95,
+            wind_speed_mph: None,
+        };
+        let thresholds = SafetyThresholds {
+            min_temperature_f: Some(35.6),
+            ..SafetyThresholds::default()
+        };
+
+        assert_eq!(
+            hour.breaches(&thresholds),
+            [
+                Breach::TooCold {
+                    temperature: 30.0,
+                    limit: 35.6
+                },
+                Breach::TooHumid {
+                    humidity: 95,
+                    limit: 90
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_partial() {
+        let thresholds: SafetyThresholds = serde_json::from_str(r#"{"max_humidity": 80}"#).unwrap();
+
+        assert_eq!(thresholds.max_humidity, Some(80));
+        assert_eq!(thresholds.max_temperature_f, Some(95.0));
+    }
+}
@@ -0,0 +1,36 @@
+use ::serde::{Deserialize, Deserializer, de::IgnoredAny};
+
+#[derive(Deserialize)]
+pub struct SunriseSunsetResponse {
+    /// An empty string instead of an object if the request failed.
+    #[serde(default, deserialize_with = "object_or_none")]
+    pub results: Option<SunriseSunsetResults>,
+    /// "OK" on success, otherwise an error code such as "INVALID_DATE".
+    pub status: String,
+}
+
+#[derive(Deserialize)]
+pub struct SunriseSunsetResults {
+    /// RFC 3339 timestamp in UTC (requires `formatted=0`).
+    pub sunrise: String,
+    /// RFC 3339 timestamp in UTC (requires `formatted=0`).
+    pub sunset: String,
+}
+
+fn object_or_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ObjectOrNone<T> {
+        Object(T),
+        None(IgnoredAny),
+    }
+
+    Ok(match ObjectOrNone::deserialize(deserializer)? {
+        ObjectOrNone::Object(value) => Some(value),
+        ObjectOrNone::None(_) => None,
+    })
+}
@@ -0,0 +1,45 @@
+use ::serde::Deserialize;
+
+/// A response of the `stations/latest` and `stations/nearesttime` endpoints.
+///
+/// Errors are reported with HTTP 200 and a non-success `SUMMARY`, without stations.
+#[derive(Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct SynopticResponse {
+    #[serde(default)]
+    pub station: Vec<SynopticStation>,
+    pub summary: SynopticSummary,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct SynopticSummary {
+    /// 1 on success, 2 if no station matched, 200 if the token was rejected.
+    pub response_code: i32,
+    pub response_message: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct SynopticStation {
+    pub stid: String,
+    pub name: Option<String>,
+    pub country: Option<String>,
+    #[serde(default)]
+    pub observations: SynopticObservations,
+}
+
+#[derive(Deserialize, Default)]
+pub struct SynopticObservations {
+    /// Air temperature in degrees Celsius.
+    pub air_temp_value_1: Option<SynopticValue>,
+    /// Relative humidity in percent.
+    pub relative_humidity_value_1: Option<SynopticValue>,
+}
+
+#[derive(Deserialize)]
+pub struct SynopticValue {
+    pub value: Option<f32>,
+    /// The observation time in ISO 8601 format (e.g., "2024-03-20T11:56:00Z").
+    pub date_time: String,
+}
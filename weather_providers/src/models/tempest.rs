@@ -0,0 +1,35 @@
+use ::serde::Deserialize;
+
+/// A response of the `observations/station/{station_id}` endpoint.
+#[derive(Deserialize)]
+pub struct TempestStationResponse {
+    pub station_id: Option<u64>,
+    pub station_name: Option<String>,
+    /// The name shown on the public map, which may differ from `station_name`.
+    pub public_name: Option<String>,
+    /// The latest observations, most recent first. `null` while the station is offline.
+    #[serde(default)]
+    pub obs: Option<Vec<TempestObservation>>,
+}
+
+/// An observation in the array-indexed format (v2): the values of the `obs_st` message of
+/// the station, in a fixed order, with `null` for sensors that reported nothing.
+#[derive(Deserialize)]
+#[serde(transparent)]
+pub struct TempestObservation(pub Vec<Option<f64>>);
+
+impl TempestObservation {
+    /// The observation time in seconds since the Unix epoch.
+    pub const TIMESTAMP: usize = 0;
+    /// The average wind speed in meters per second.
+    pub const WIND_AVG: usize = 2;
+    /// The air temperature in degrees Celsius.
+    pub const AIR_TEMPERATURE: usize = 7;
+    /// The relative humidity in percent.
+    pub const RELATIVE_HUMIDITY: usize = 8;
+
+    /// Returns the value at `index`, or `None` if the station reported nothing there.
+    pub fn value(&self, index: usize) -> Option<f64> {
+        self.0.get(index).copied().flatten()
+    }
+}
@@ -4,6 +4,8 @@ use ::serde::Deserialize;
 pub struct WeatherApiResponse {
     pub location: WeatherApiLocation,
     pub current: WeatherApiCurrent,
+    /// The daily forecasts, returned by `forecast.json` only.
+    pub forecast: Option<WeatherApiForecast>,
 }
 
 #[derive(Deserialize)]
@@ -14,12 +16,67 @@ pub struct WeatherApiLocation {
 
 #[derive(Deserialize)]
 pub struct WeatherApiCurrent {
+    /// Unix time of the last update of the station data.
+    pub last_updated_epoch: Option<i64>,
     pub temp_f: f32,
     pub humidity: u8,
+    pub wind_mph: Option<f32>,
+    pub precip_mm: Option<f32>,
+    pub pressure_mb: Option<f32>,
+    /// The dew point in degrees Fahrenheit.
+    pub dewpoint_f: Option<f32>,
+    /// The cloud cover in percent.
+    pub cloud: Option<u8>,
+    pub snow_cm: Option<f32>,
+    /// `1` during a blizzard.
+    pub is_blizzard: Option<u8>,
+    /// `1` during daylight, `0` at night.
+    pub is_day: Option<u8>,
     pub condition: WeatherApiCondition,
 }
 
 #[derive(Deserialize)]
 pub struct WeatherApiCondition {
     pub text: String,
+    /// The condition code, e.g. `1183` for light rain.
+    pub code: Option<u16>,
+}
+
+#[derive(Deserialize)]
+pub struct WeatherApiForecast {
+    pub forecastday: Vec<WeatherApiForecastDay>,
+}
+
+#[derive(Deserialize)]
+pub struct WeatherApiForecastDay {
+    pub day: WeatherApiDay,
+}
+
+#[derive(Deserialize)]
+pub struct WeatherApiDay {
+    /// The chance of rain in percent (0–100).
+    pub daily_chance_of_rain: Option<f32>,
+}
+
+#[derive(Deserialize)]
+pub struct WeatherApiSearchResult {
+    pub name: String,
+    /// Empty if the location has no region.
+    #[serde(default)]
+    pub region: String,
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// The error body of WeatherAPI, e.g. `{"error": {"code": 1006, "message": "..."}}`.
+#[derive(Deserialize)]
+pub struct WeatherApiErrorResponse {
+    pub error: WeatherApiError,
+}
+
+#[derive(Deserialize)]
+pub struct WeatherApiError {
+    pub code: u32,
+    pub message: String,
 }
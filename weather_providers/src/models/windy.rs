@@ -0,0 +1,31 @@
+use ::serde::{Deserialize, Serialize};
+
+/// A Point Forecast request.
+#[derive(Serialize)]
+pub struct WindyRequest<'a> {
+    pub lat: f64,
+    pub lon: f64,
+    pub model: &'a str,
+    pub parameters: &'a [&'a str],
+    pub levels: &'a [&'a str],
+    pub key: &'a str,
+}
+
+/// The forecast series of the surface level, aligned with `ts`.
+#[derive(Deserialize)]
+pub struct WindyResponse {
+    /// The Unix timestamps of the forecast steps, in milliseconds.
+    pub ts: Vec<i64>,
+    /// The temperature in Kelvin.
+    #[serde(rename = "temp-surface")]
+    pub temp: Vec<Option<f32>>,
+    /// The relative humidity in percent.
+    #[serde(rename = "rh-surface", default)]
+    pub rh: Vec<Option<f32>>,
+    /// The eastward wind component in meters per second.
+    #[serde(rename = "wind_u-surface", default)]
+    pub wind_u: Vec<Option<f32>>,
+    /// The northward wind component in meters per second.
+    #[serde(rename = "wind_v-surface", default)]
+    pub wind_v: Vec<Option<f32>>,
+}
@@ -0,0 +1,56 @@
+use ::serde::Deserialize;
+
+/// The response of the `weather.ashx` endpoint. Numbers are sent as strings.
+#[derive(Deserialize)]
+pub struct WorldWeatherOnlineResponse {
+    pub data: WorldWeatherOnlineData,
+}
+
+#[derive(Deserialize)]
+pub struct WorldWeatherOnlineData {
+    #[serde(default)]
+    pub current_condition: Vec<WorldWeatherOnlineCurrent>,
+    /// The area the query resolved to (requires `includelocation=yes`).
+    #[serde(default)]
+    pub nearest_area: Vec<WorldWeatherOnlineArea>,
+    /// Set instead of the weather if the request failed, even with a successful status.
+    #[serde(default)]
+    pub error: Vec<WorldWeatherOnlineError>,
+}
+
+#[derive(Deserialize)]
+pub struct WorldWeatherOnlineCurrent {
+    #[serde(rename = "temp_F")]
+    pub temp_f: String,
+    pub humidity: String,
+    #[serde(rename = "FeelsLikeF")]
+    pub feels_like_f: Option<String>,
+    #[serde(rename = "windspeedMiles")]
+    pub windspeed_miles: Option<String>,
+    #[serde(rename = "precipMM")]
+    pub precip_mm: Option<String>,
+    /// Pressure in millibars (hPa).
+    pub pressure: Option<String>,
+    #[serde(rename = "weatherDesc", default)]
+    pub weather_desc: Vec<WorldWeatherOnlineValue>,
+}
+
+#[derive(Deserialize)]
+pub struct WorldWeatherOnlineArea {
+    #[serde(rename = "areaName", default)]
+    pub area_name: Vec<WorldWeatherOnlineValue>,
+    #[serde(default)]
+    pub country: Vec<WorldWeatherOnlineValue>,
+}
+
+/// A text wrapped in an object, e.g. `{"value": "Partly cloudy"}`.
+#[derive(Deserialize)]
+pub struct WorldWeatherOnlineValue {
+    pub value: String,
+}
+
+/// An error of the request, e.g. `{"msg": "API key is invalid"}`.
+#[derive(Deserialize)]
+pub struct WorldWeatherOnlineError {
+    pub msg: String,
+}
@@ -0,0 +1,467 @@
+use crate::{
+    ForecastProvider, Provider, WeatherProvider,
+    common::*,
+    models::{DataSource, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, aemet::*},
+    utils::{
+        date::normalize_date,
+        http::{HttpClient, check_status, check_status_with, decode_body, parse_json},
+        poll::{Polling, poll},
+        units::{celsius_to_fahrenheit, humidity_percent},
+    },
+};
+use ::async_trait::async_trait;
+use ::reqwest::{StatusCode, Url};
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const BASE_URL: &str = "https://opendata.aemet.es/opendata/api";
+
+/// The whole day, as opposed to its 6 or 12-hour periods.
+const WHOLE_DAY: &str = "00-24";
+
+/// How the data of a request is polled: it is published about 2 seconds after the link, and
+/// abandoned after 20 seconds.
+const POLLING: Polling = Polling::fixed(10, Duration::from_secs(2));
+
+/// Daily municipality forecasts from the AEMET OpenData API of the Spanish State
+/// Meteorological Agency.
+///
+/// The address is the 5-digit INE code of a Spanish municipality (e.g., "28079" for Madrid),
+/// so no geocoding is needed. Each request takes two stages: the API first returns a link to
+/// the data, which is then polled until it is published. The maximum temperature and relative
+/// humidity of the requested day are reported, for today and the next 6 days.
+#[derive(Debug)]
+pub struct AemetProvider {
+    base_url: String,
+    http: HttpClient,
+    polling: Polling,
+}
+
+impl Default for AemetProvider {
+    fn default() -> Self {
+        Self::with_options(&ProviderOptions::default())
+    }
+}
+
+impl AemetProvider {
+    /// Creates a provider with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self::with_base_url(BASE_URL, HttpClient::new(options))
+    }
+
+    fn with_base_url(base_url: &str, http: HttpClient) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http,
+            polling: POLLING,
+        }
+    }
+
+    /// Requests the forecast of a municipality and returns the URL of its data.
+    async fn request_data(&self, api_key: &str, municipality: &str) -> Result<Url> {
+        let response = check_status_with(
+            "AEMET",
+            self.http
+                .get(
+                    "AEMET",
+                    "prediccion/especifica/municipio/diaria",
+                    forecast_url(&self.base_url, api_key, municipality)?,
+                )
+                .await?,
+            decode_error,
+        )
+        .await?;
+        let envelope = parse_json::<AemetEnvelope>("AEMET", response).await?;
+
+        data_url(envelope)
+    }
+
+    /// Waits for the data to be published and returns it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if the data is not published after the attempts of `polling`.
+    async fn wait_for_data(&self, url: &Url) -> Result<Vec<AemetForecast>> {
+        poll("AEMET", self.polling, || self.fetch_data(url)).await
+    }
+
+    /// Fetches the data, or `None` if it is not published yet.
+    async fn fetch_data(&self, url: &Url) -> Result<Option<Vec<AemetForecast>>> {
+        let response = self.http.get("AEMET", "datos", url.clone()).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let bytes = check_status("AEMET", response).await?.bytes().await?;
+        parse_data(&decode_body(&bytes))
+    }
+
+    /// Returns the weather of `address` on `date` (today if `None`).
+    #[instrument(skip(self, provider_key))]
+    async fn weather_on(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<WeatherInfo> {
+        let api_key = require_key(provider_key)?;
+        let municipality = parse_municipality_code(address)?;
+        let date = normalize_date(date);
+
+        let url = self.request_data(api_key, &municipality).await?;
+        let forecast = self
+            .wait_for_data(&url)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::LocationNotFound("AEMET".into()))?;
+
+        to_weather_info(date, forecast)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for AemetProvider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let url = forecast_url(&self.base_url, require_key(provider_key)?, "28079")?;
+
+        self.http
+            .probe("AEMET", "prediccion/especifica/municipio/diaria", url)
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://opendata.aemet.es/centrodedescargas/altaUsuario",
+            key_format: "A JSON Web Token sent by email (starts with 'eyJ')",
+            free_tier: "Free open data, attribution to AEMET required",
+            capabilities: &["current", "forecast"],
+            example: "weather get 28079 --provider ae",
+        }
+    }
+
+    fn as_forecast(&self) -> Option<&dyn ForecastProvider> {
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ForecastProvider for AemetProvider {
+    async fn get_forecast(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+fn require_key(provider_key: Option<&str>) -> Result<&str> {
+    provider_key.ok_or_else(|| {
+        Error::MissingApiKey(
+            "'AEMET' API key not set. Please set it using: 'weather provider ae --key <API_KEY>'"
+                .into(),
+        )
+    })
+}
+
+/// Validates the INE code of a municipality: 5 digits, e.g. "28079" for Madrid.
+fn parse_municipality_code(address: &str) -> Result<String> {
+    let address = address.trim();
+
+    if address.len() != 5 || !address.chars().all(|c| c.is_ascii_digit()) {
+        Err(format!(
+            "Invalid municipality code: '{address}'. \
+             AEMET expects the 5-digit INE code of a Spanish municipality (e.g., 28079 for Madrid)"
+        ))?;
+    }
+
+    Ok(address.to_string())
+}
+
+/// Builds the URL of the first stage of the daily forecast of a municipality.
+fn forecast_url(base_url: &str, api_key: &str, municipality: &str) -> Result<Url> {
+    Ok(Url::parse_with_params(
+        &format!("{base_url}/prediccion/especifica/municipio/diaria/{municipality}"),
+        &[("api_key", api_key)],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+/// Returns the URL of the data linked by a first-stage response.
+///
+/// # Errors
+///
+/// Returns the error reported by the envelope if it has no link.
+fn data_url(envelope: AemetEnvelope) -> Result<Url> {
+    let datos = envelope
+        .datos
+        .filter(|_| envelope.estado == 200)
+        .ok_or_else(|| error_from_status(envelope.estado, envelope.descripcion))?;
+
+    Url::parse(&datos).map_err(|e| format!("AEMET returned an invalid data URL: {e}").into())
+}
+
+/// Parses the data, or returns `None` if AEMET reports that it is not published yet.
+///
+/// Unpublished data is answered with an envelope like the first stage, with the status 404.
+fn parse_data(body: &str) -> Result<Option<Vec<AemetForecast>>> {
+    if let Ok(envelope) = serde_json::from_str::<AemetEnvelope>(body) {
+        return match envelope.estado {
+            404 => Ok(None),
+            estado => Err(error_from_status(estado, envelope.descripcion)),
+        };
+    }
+
+    serde_json::from_str(body)
+        .map(Some)
+        .map_err(|e| format!("AEMET returned invalid data: {e}").into())
+}
+
+/// Maps an AEMET error body to an error.
+fn decode_error(body: &str) -> Option<Error> {
+    let envelope = serde_json::from_str::<AemetEnvelope>(body).ok()?;
+
+    Some(error_from_status(envelope.estado, envelope.descripcion))
+}
+
+/// Maps the status of an envelope, which follows the HTTP status codes, to an error.
+fn error_from_status(estado: u16, descripcion: String) -> Error {
+    match estado {
+        401 => Error::InvalidApiKey("AEMET".into()),
+        404 => Error::LocationNotFound("AEMET".into()),
+        429 => Error::RateLimited("AEMET".into()),
+        500..=599 => Error::ServerError("AEMET".into()),
+        _ => format!("AEMET error ({estado}): {descripcion}").into(),
+    }
+}
+
+/// Returns the value of the whole day of a daily series, or of its first period otherwise.
+fn whole_day<T>(values: &[T], period: impl Fn(&T) -> Option<&str>) -> Option<&T> {
+    values
+        .iter()
+        .find(|value| period(value) == Some(WHOLE_DAY))
+        .or_else(|| values.first())
+}
+
+fn to_weather_info(date: String, forecast: AemetForecast) -> Result<WeatherInfo> {
+    let day = forecast
+        .prediccion
+        .dia
+        .into_iter()
+        .find(|day| day.fecha.starts_with(&date))
+        .ok_or_else(|| Error::UnsupportedDate {
+            provider: "AEMET".into(),
+            date: date.clone(),
+            reason: "forecasts cover today and the next 6 days".into(),
+        })?;
+
+    let maximum = day
+        .temperatura
+        .maxima
+        .ok_or_else(|| format!("AEMET returned no temperature for {date}"))?;
+    let description = whole_day(&day.estado_cielo, |sky| sky.periodo.as_deref())
+        .map(|sky| sky.descripcion.clone())
+        .filter(|description| !description.is_empty());
+    let precip_probability = whole_day(&day.prob_precipitacion, |p| p.periodo.as_deref())
+        .and_then(|probability| probability.value)
+        .map(|percent| f32::from(percent) / 100.0);
+
+    Ok(WeatherInfo {
+        country: "ES".to_string(),
+        city: forecast.nombre,
+        date,
+        temperature: celsius_to_fahrenheit(maximum),
+        humidity: day.humedad_relativa.maxima.map(humidity_percent),
+        description,
+        meta: Some(WeatherMeta::new(
+            Provider::Aemet,
+            "prediccion/especifica/municipio/diaria",
+            DataSource::Forecast,
+        )),
+        ..Default::default()
+    }
+    .with_precip_probability(precip_probability))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use ::wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path, query_param},
+    };
+
+    const DATA_PATH: &str = "/opendata/sh/a1b2c3d4";
+
+    fn fixture() -> Vec<AemetForecast> {
+        serde_json::from_str(include_str!("../../tests/fixtures/aemet_forecast.json")).unwrap()
+    }
+
+    fn provider(server: &MockServer, attempts: u32) -> AemetProvider {
+        AemetProvider {
+            polling: Polling::fixed(attempts, Duration::from_millis(10)),
+            ..AemetProvider::with_base_url(&server.uri(), HttpClient::default())
+        }
+    }
+
+    fn envelope(server: &MockServer) -> serde_json::Value {
+        serde_json::json!({
+            "descripcion": "exito",
+            "estado": 200,
+            "datos": format!("{}{DATA_PATH}", server.uri()),
+            "metadatos": format!("{}/opendata/sh/metadatos", server.uri())
+        })
+    }
+
+    async fn mount_envelope(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/prediccion/especifica/municipio/diaria/28079"))
+            .and(query_param("api_key", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(envelope(server)))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_fixture_deserializes() {
+        let forecast = fixture();
+        let days = &forecast[0].prediccion.dia;
+
+        assert_eq!(forecast[0].nombre, "Madrid");
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].temperatura.maxima, Some(12.0));
+        assert_eq!(days[0].humedad_relativa.maxima, Some(85.0));
+        assert_eq!(days[0].estado_cielo[0].descripcion, "Poco nuboso");
+    }
+
+    #[tokio::test]
+    async fn test_two_stage_request() {
+        let server = MockServer::start().await;
+        mount_envelope(&server).await;
+        // The data is not published at the first attempt
+        Mock::given(method("GET"))
+            .and(path(DATA_PATH))
+            .respond_with(ResponseTemplate::new(404))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(DATA_PATH))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../../tests/fixtures/aemet_forecast.json")),
+            )
+            .mount(&server)
+            .await;
+
+        let info = provider(&server, 500)
+            .get_forecast(Some("key"), "28079", "2024-01-16")
+            .await
+            .unwrap();
+
+        assert_eq!(info.city, "Madrid");
+        assert_eq!(info.temperature, 50.0);
+        assert_eq!(info.humidity, Some(95));
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_polling_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(DATA_PATH))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "descripcion": "No hay datos que satisfagan esos criterios",
+                "estado": 404
+            })))
+            .mount(&server)
+            .await;
+        let url = Url::parse(&format!("{}{DATA_PATH}", server.uri())).unwrap();
+
+        let Err(error) = provider(&server, 5).wait_for_data(&url).await else {
+            panic!("the data is never published");
+        };
+
+        assert!(matches!(error, Error::Timeout { .. }), "{error}");
+    }
+
+    #[test]
+    fn test_latin1_data() {
+        // The data is encoded in ISO-8859-15, e.g. "Logroño" with a single byte for "ñ"
+        let body = b"[{\"nombre\": \"Logro\xf1o\", \"prediccion\": {\"dia\": []}}]";
+        let data = parse_data(&decode_body(body)).unwrap().unwrap();
+
+        assert_eq!(data[0].nombre, "Logroño");
+    }
+
+    #[test]
+    fn test_errors() {
+        let envelope = |estado: u16, datos: Option<&str>| AemetEnvelope {
+            descripcion: "API key invalido".to_string(),
+            estado,
+            datos: datos.map(str::to_string),
+        };
+
+        assert!(matches!(
+            data_url(envelope(401, None)),
+            Err(Error::InvalidApiKey(_))
+        ));
+        assert!(matches!(
+            data_url(envelope(429, None)),
+            Err(Error::RateLimited(_))
+        ));
+        assert_eq!(
+            data_url(envelope(
+                200,
+                Some("https://opendata.aemet.es/opendata/sh/1")
+            ))
+            .unwrap()
+            .path(),
+            "/opendata/sh/1"
+        );
+        assert!(matches!(
+            decode_error(r#"{"descripcion": "Not found", "estado": 404}"#),
+            Some(Error::LocationNotFound(_))
+        ));
+        assert!(decode_error("<html>Bad Gateway</html>").is_none());
+    }
+
+    #[test]
+    fn test_parse_municipality_code() {
+        assert_eq!(parse_municipality_code(" 28079 ").unwrap(), "28079");
+        for address in ["Madrid", "2807", "280790", "28O79"] {
+            assert!(parse_municipality_code(address).is_err(), "{address}");
+        }
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let forecast = fixture().into_iter().next().unwrap();
+        let info = to_weather_info("2024-01-15".to_string(), forecast).unwrap();
+
+        assert_eq!(info.city, "Madrid");
+        assert_eq!(info.country, "ES");
+        assert_eq!(info.date, "2024-01-15");
+        assert!((info.temperature - 53.6).abs() < 0.01);
+        assert_eq!(info.humidity, Some(85));
+        assert_eq!(info.description.as_deref(), Some("Poco nuboso"));
+        assert_eq!(info.precip_probability_pct(), Some(0));
+        assert_eq!(info.meta.unwrap().source, DataSource::Forecast);
+
+        let forecast = fixture().into_iter().next().unwrap();
+        assert!(matches!(
+            to_weather_info("2024-01-20".to_string(), forecast),
+            Err(Error::UnsupportedDate { .. })
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, ambient_weather::*,
+    },
+    utils::http::{HttpClient, check_status, parse_json},
+};
+use ::async_trait::async_trait;
+use ::chrono::DateTime;
+use ::reqwest::Url;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const DEVICES_URL: &str = "https://api.ambientweather.net/v1/devices";
+
+/// Personal weather stations of the Ambient Weather Network.
+///
+/// The address is the MAC address of one of the account's devices, so no geocoding is needed.
+#[derive(Debug, Default)]
+pub struct AmbientWeatherProvider {
+    http: HttpClient,
+}
+
+impl AmbientWeatherProvider {
+    /// Creates a provider with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for AmbientWeatherProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let mac_address = parse_mac_address(address)?;
+        let url = devices_url(provider_key)?;
+
+        let response = check_status(
+            "AmbientWeather",
+            self.http.get("AmbientWeather", "devices", url).await?,
+        )
+        .await?;
+        let devices = parse_json::<Vec<AmbientWeatherDevice>>("AmbientWeather", response).await?;
+
+        let device = devices
+            .into_iter()
+            .find(|device| device.mac_address.eq_ignore_ascii_case(&mac_address))
+            .ok_or_else(|| format!("Device '{mac_address}' not found in the account"))?;
+
+        Ok(to_weather_info(device).with_derived_precip_type())
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        self.http
+            .probe("AmbientWeather", "devices", devices_url(provider_key)?)
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://ambientweather.net/account",
+            key_format: "<API_KEY>:<APPLICATION_KEY>, both created in the account settings",
+            free_tier: "1 request per second per API key",
+            capabilities: &["current"],
+            example: "weather get 00:0E:C6:20:0F:7B --provider amw",
+        }
+    }
+}
+
+/// Builds the device list URL from a key in the `<API_KEY>:<APPLICATION_KEY>` format.
+fn devices_url(provider_key: Option<&str>) -> Result<Url> {
+    let (api_key, application_key) = provider_key
+        .and_then(|key| key.split_once(':'))
+        .ok_or_else(|| {
+            Error::MissingApiKey(
+                "'AmbientWeather' API key not set. Please set it using: \
+                 'weather provider amw --key <API_KEY>:<APPLICATION_KEY>'"
+                    .into(),
+            )
+        })?;
+
+    Url::parse_with_params(
+        DEVICES_URL,
+        &[("apiKey", api_key), ("applicationKey", application_key)],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}").into())
+}
+
+/// Validates a MAC address in the `XX:XX:XX:XX:XX:XX` format and returns it in upper case.
+fn parse_mac_address(address: &str) -> Result<String> {
+    let address = address.trim();
+    let octets = address.split(':').collect::<Vec<_>>();
+
+    let valid = octets.len() == 6
+        && octets
+            .iter()
+            .all(|o| o.len() == 2 && o.chars().all(|c| c.is_ascii_hexdigit()));
+
+    if !valid {
+        Err(format!(
+            "Invalid device address: '{address}'. \
+             Ambient Weather expects a MAC address in format XX:XX:XX:XX:XX:XX"
+        ))?;
+    }
+
+    Ok(address.to_uppercase())
+}
+
+fn to_weather_info(device: AmbientWeatherDevice) -> WeatherInfo {
+    let data = device.last_data;
+    let date = DateTime::from_timestamp_millis(data.dateutc)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    WeatherInfo {
+        country: device.info.location.unwrap_or_default(),
+        city: device
+            .info
+            .name
+            .unwrap_or_else(|| device.mac_address.clone()),
+        date,
+        temperature: data.tempf,
+        humidity: Some(data.humidity),
+        wind_speed_mph: data.windspeedmph,
+        feels_like: data.feels_like,
+        station_id: Some(device.mac_address),
+        meta: Some(WeatherMeta::new(
+            Provider::AmbientWeather,
+            "devices",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEVICES: &str = r#"[
+        {
+            "macAddress": "00:0E:C6:20:0F:7B",
+            "info": { "name": "Backyard", "location": "Home" },
+            "lastData": {
+                "dateutc": 1704110400000,
+                "tempf": 38.7,
+                "humidity": 86,
+                "feelsLike": 35.2,
+                "windspeedmph": 3.1,
+                "baromrelin": 29.9
+            }
+        }
+    ]"#;
+
+    #[test]
+    fn test_parse_mac_address() {
+        assert_eq!(
+            parse_mac_address(" 00:0e:c6:20:0f:7b ").unwrap(),
+            "00:0E:C6:20:0F:7B"
+        );
+
+        assert!(parse_mac_address("London").is_err());
+        assert!(parse_mac_address("00-0E-C6-20-0F-7B").is_err());
+        assert!(parse_mac_address("00:0E:C6:20:0F").is_err());
+        assert!(parse_mac_address("00:0E:C6:20:0F:ZZ").is_err());
+    }
+
+    #[test]
+    fn test_devices_url_requires_both_keys() {
+        let url = devices_url(Some("api:app")).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://api.ambientweather.net/v1/devices?apiKey=api&applicationKey=app"
+        );
+
+        assert!(devices_url(Some("api")).is_err());
+        assert!(devices_url(None).is_err());
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let device = serde_json::from_str::<Vec<AmbientWeatherDevice>>(DEVICES)
+            .unwrap()
+            .remove(0);
+
+        let info = to_weather_info(device);
+
+        assert_eq!(info.city, "Backyard");
+        assert_eq!(info.country, "Home");
+        assert_eq!(info.date, "2024-01-01");
+        assert_eq!(info.temperature, 38.7);
+        assert_eq!(info.humidity, Some(86));
+        assert_eq!(info.feels_like, Some(35.2));
+        assert_eq!(info.wind_speed_mph, Some(3.1));
+        assert_eq!(info.station_id.as_deref(), Some("00:0E:C6:20:0F:7B"));
+        assert!(info.is_from_station());
+    }
+}
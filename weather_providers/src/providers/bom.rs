@@ -0,0 +1,336 @@
+use crate::{
+    Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, WindUnit, bom::*,
+    },
+    utils::{
+        date::normalize_date,
+        http::{HttpClient, check_status, parse_json},
+        units::{celsius_to_fahrenheit, humidity_percent},
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::{NaiveDate, NaiveDateTime};
+use ::reqwest::Url;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const BASE_URL: &str = "https://www.bom.gov.au/fwo";
+
+/// The station probed by the health check: Sydney - Observatory Hill.
+const HEALTH_CHECK_STATION: &str = "IDN60901.94768";
+
+/// Latest observations of the weather stations of the Australian Bureau of Meteorology.
+///
+/// The address is the BOM station code: the ID of the observations product of the state and
+/// the WMO number of the station, as in the URLs of the BOM observation pages (e.g.,
+/// "IDN60901.94768" for Sydney - Observatory Hill). No geocoding is needed, nor an API key.
+/// The latest observation with a temperature is reported, whatever the date.
+#[derive(Debug)]
+pub struct BomProvider {
+    base_url: String,
+    http: HttpClient,
+}
+
+impl Default for BomProvider {
+    fn default() -> Self {
+        Self::with_options(&ProviderOptions::default())
+    }
+}
+
+impl BomProvider {
+    /// Creates a provider with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self::with_base_url(BASE_URL, HttpClient::new(options))
+    }
+
+    fn with_base_url(base_url: &str, http: HttpClient) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http,
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for BomProvider {
+    #[instrument(skip(self, _provider_key))]
+    async fn get_weather(&self, _provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let station = StationCode::parse(address)?;
+
+        let response = check_status(
+            "BOM",
+            self.http
+                .get(
+                    "BOM",
+                    "observations",
+                    observations_url(&self.base_url, &station)?,
+                )
+                .await?,
+        )
+        .await?;
+        let body = parse_json::<BomResponse>("BOM", response).await?;
+
+        to_weather_info(&station, body)
+    }
+
+    #[instrument(skip(self, _provider_key))]
+    async fn health_check(&self, _provider_key: Option<&str>) -> Result<Duration> {
+        let station = StationCode::parse(HEALTH_CHECK_STATION)?;
+
+        self.http
+            .probe(
+                "BOM",
+                "observations",
+                observations_url(&self.base_url, &station)?,
+            )
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://www.bom.gov.au/catalogue/data-feeds.shtml",
+            key_format: "Not required",
+            free_tier: "Free public data feeds, subject to the BOM copyright notice",
+            capabilities: &["current"],
+            example: "weather get IDN60901.94768 --provider bom",
+        }
+    }
+}
+
+/// A BOM station code, e.g. "IDN60901.94768".
+#[derive(Debug, PartialEq)]
+struct StationCode {
+    /// The ID of the observations product of the state, e.g. "IDN60901" for New South Wales.
+    product: String,
+    /// The WMO number of the station, e.g. "94768".
+    wmo: String,
+}
+
+impl StationCode {
+    /// Parses a station code, ignoring the case and surrounding whitespace.
+    fn parse(address: &str) -> Result<Self> {
+        let address = address.trim().to_ascii_uppercase();
+        let invalid = || {
+            Error::from(format!(
+                "Invalid station code: '{address}'. BOM expects the product ID and the WMO \
+                 number of a station (e.g., IDN60901.94768 for Sydney - Observatory Hill)"
+            ))
+        };
+
+        // Byte offsets below are only character boundaries in ASCII
+        if !address.is_ascii() {
+            return Err(invalid());
+        }
+
+        let (product, wmo) = address.split_once('.').ok_or_else(invalid)?;
+        let digits = |s: &str, len: usize| s.len() == len && s.chars().all(|c| c.is_ascii_digit());
+
+        // "ID", the letter of the state, then the number of the product
+        let is_product = product.len() == 8
+            && product.starts_with("ID")
+            && product[2..3].chars().all(|c| c.is_ascii_uppercase())
+            && digits(&product[3..], 5);
+        if !is_product || !digits(wmo, 5) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            product: product.to_string(),
+            wmo: wmo.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for StationCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.product, self.wmo)
+    }
+}
+
+/// Builds the URL of the observations of a station, e.g. `/IDN60901/IDN60901.94768.json`.
+fn observations_url(base_url: &str, station: &StationCode) -> Result<Url> {
+    Ok(
+        Url::parse(&format!("{base_url}/{}/{station}.json", station.product))
+            .map_err(|e| format!("Failed to build URL: {e}"))?,
+    )
+}
+
+fn to_weather_info(station: &StationCode, body: BomResponse) -> Result<WeatherInfo> {
+    let observations = body.observations;
+    let header_name = observations.header.into_iter().next().map(|h| h.name);
+    let (temperature, observation) = observations
+        .data
+        .into_iter()
+        .find_map(|o| o.air_temp.map(|temperature| (temperature, o)))
+        .ok_or_else(|| format!("BOM reported no temperature for station {station}"))?;
+
+    let date = observation
+        .local_date_time_full
+        .as_deref()
+        .and_then(|time| NaiveDate::parse_from_str(time.get(..8)?, "%Y%m%d").ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| normalize_date(None::<&str>));
+    let observation_time = observation
+        .aifstime_utc
+        .as_deref()
+        .and_then(|time| NaiveDateTime::parse_from_str(time, "%Y%m%d%H%M%S").ok())
+        .map(|time| time.and_utc());
+
+    // "-" stands for nothing to report
+    let text = |text: Option<String>| text.filter(|t| !t.is_empty() && t != "-");
+    let description = text(observation.weather).or_else(|| text(observation.cloud));
+
+    Ok(WeatherInfo {
+        country: "AU".to_string(),
+        city: observation
+            .name
+            .or(header_name)
+            .unwrap_or_else(|| station.to_string()),
+        date,
+        observation_time,
+        temperature: celsius_to_fahrenheit(temperature),
+        humidity: observation.rel_hum.map(humidity_percent),
+        description,
+        wind_speed_mph: observation
+            .wind_spd_kmh
+            .map(|kmh| WindUnit::KilometersPerHour.to_mph(kmh)),
+        feels_like: observation.apparent_t.map(celsius_to_fahrenheit),
+        pressure_hpa: observation.press_msl,
+        station_id: Some(
+            observation
+                .wmo
+                .map_or_else(|| station.wmo.clone(), |wmo| wmo.to_string()),
+        ),
+        meta: Some(WeatherMeta::new(
+            Provider::Bom,
+            "observations",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/bom_observations.json");
+
+    fn sydney() -> StationCode {
+        StationCode::parse("IDN60901.94768").unwrap()
+    }
+
+    #[test]
+    fn test_fixture_deserializes() {
+        let body: BomResponse = serde_json::from_str(FIXTURE).unwrap();
+        let observations = body.observations;
+
+        assert_eq!(observations.header[0].name, "Sydney - Observatory Hill");
+        assert_eq!(observations.data.len(), 3);
+        // The latest observation comes first, without a temperature
+        assert_eq!(observations.data[0].air_temp, None);
+        assert_eq!(observations.data[1].air_temp, Some(26.1));
+    }
+
+    #[test]
+    fn test_station_code() {
+        assert_eq!(
+            StationCode::parse(" idn60901.94768 ").unwrap(),
+            StationCode {
+                product: "IDN60901".to_string(),
+                wmo: "94768".to_string(),
+            }
+        );
+        assert_eq!(sydney().to_string(), "IDN60901.94768");
+
+        for address in [
+            "",
+            "Sydney",
+            "94768",
+            "IDN60901",
+            "IDN60901.9476",
+            "IDN6090.94768",
+            "XXN60901.94768",
+            "ID160901.94768",
+            "IDN60901.94768.1",
+            // Non-ASCII input whose byte length matches
+            "IDé6090.94768",
+            "IDN6090é.94768",
+        ] {
+            let error = StationCode::parse(address).unwrap_err().to_string();
+            assert!(error.contains("Invalid station code"), "{address}: {error}");
+        }
+    }
+
+    #[test]
+    fn test_observations_url() {
+        let url = observations_url(BASE_URL, &sydney()).unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "https://www.bom.gov.au/fwo/IDN60901/IDN60901.94768.json"
+        );
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let info = to_weather_info(&sydney(), serde_json::from_str(FIXTURE).unwrap()).unwrap();
+
+        assert_eq!(info.country, "AU");
+        assert_eq!(info.city, "Sydney - Observatory Hill");
+        assert_eq!(info.date, "2024-01-15");
+        assert_eq!(
+            info.observation_time.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2024-01-15T03:00:00+00:00")
+        );
+        assert!(
+            (info.temperature - 78.98).abs() < 0.01,
+            "{}",
+            info.temperature
+        );
+        assert_eq!(info.humidity, Some(59));
+        assert_eq!(info.description.as_deref(), Some("Partly cloudy"));
+        assert_eq!(info.station_id.as_deref(), Some("94768"));
+        assert_eq!(info.pressure_hpa, Some(1012.4));
+        assert!((info.wind_speed_mph.unwrap() - 13.67).abs() < 0.01);
+
+        let empty = r#"{"observations": {"header": [], "data": []}}"#;
+        let error = to_weather_info(&sydney(), serde_json::from_str(empty).unwrap())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("no temperature for station IDN60901.94768"),
+            "{error}"
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_get_weather() {
+        use ::wiremock::{
+            Mock, MockServer, ResponseTemplate,
+            matchers::{method, path},
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/IDN60901/IDN60901.94768.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(FIXTURE))
+            .mount(&server)
+            .await;
+        let provider = BomProvider::with_base_url(&server.uri(), HttpClient::default());
+
+        let info = provider.get_weather(None, "IDN60901.94768").await.unwrap();
+        assert_eq!(info.humidity, Some(59));
+
+        // An unknown station is a missing document
+        let error = provider
+            .get_weather(None, "IDN60901.99999")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::LocationNotFound(_)), "{error}");
+    }
+}
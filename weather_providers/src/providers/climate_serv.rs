@@ -0,0 +1,434 @@
+use crate::{
+    GeocodingClient, HistoricalProvider, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta,
+        climate_serv::*,
+    },
+    providers::OpenMeteoGeocoder,
+    utils::{
+        clock::{Clock, SystemClock},
+        date::normalize_date,
+        http::{HttpClient, check_status, parse_json},
+        poll::{Polling, poll},
+        units::{celsius_to_fahrenheit, humidity_percent},
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::NaiveDate;
+use ::reqwest::Url;
+use ::serde::de::DeserializeOwned;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const BASE_URL: &str = "https://climateserv.servirglobal.net/api";
+
+/// The ID of the agricultural weather dataset, reporting `Temperature` and
+/// `Relative_Humidity`.
+const DATATYPE: &str = "37";
+
+/// The average over the area is requested (`operationtype`).
+const AVERAGE_OPERATION: &str = "5";
+
+/// Half the side (degrees) of the square around the location; requests take a polygon.
+const AREA_HALF_SIDE: f64 = 0.05;
+
+/// How a submitted request is polled: every second, for 30 seconds.
+const POLLING: Polling = Polling::fixed(30, Duration::from_secs(1));
+
+/// Agricultural weather from the ClimateServ API of NASA and SERVIR.
+///
+/// ClimateServ processes each request as a job: the request is submitted, its progress is
+/// polled until it is complete, and the resulting time series is downloaded. The averages of
+/// the temperature and the relative humidity over a small square around the location are
+/// reported. Addresses are resolved with Open-Meteo geocoding. No API key is required.
+#[derive(Debug)]
+pub struct ClimateServProvider {
+    base_url: String,
+    http: HttpClient,
+    geocoder: OpenMeteoGeocoder,
+    polling: Polling,
+}
+
+impl Default for ClimateServProvider {
+    fn default() -> Self {
+        Self::with_options(&ProviderOptions::default())
+    }
+}
+
+impl ClimateServProvider {
+    /// Creates a provider with the connection settings of `options`, also used for geocoding.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            geocoder: OpenMeteoGeocoder::with_options(options),
+            ..Self::with_base_url(BASE_URL, HttpClient::new(options))
+        }
+    }
+
+    fn with_base_url(base_url: &str, http: HttpClient) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http,
+            geocoder: OpenMeteoGeocoder::default(),
+            polling: POLLING,
+        }
+    }
+
+    /// Submits a request for the dataset around the location and returns its ID.
+    async fn submit(&self, location: &GeoLocation, date: NaiveDate) -> Result<String> {
+        let ids: Vec<String> = self
+            .get(
+                "submitDataRequest",
+                &submit_params(location.lat, location.lon, date),
+            )
+            .await?;
+
+        ids.into_iter()
+            .next()
+            .ok_or_else(|| "ClimateServ returned no request ID".into())
+    }
+
+    /// Waits for a request to complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if the request is not complete after the attempts of `polling`.
+    async fn wait(&self, id: &str) -> Result<()> {
+        poll("ClimateServ", self.polling, || async {
+            Ok(self.is_complete(id).await?.then_some(()))
+        })
+        .await
+    }
+
+    /// Returns whether a request is complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if ClimateServ reports that the request failed.
+    async fn is_complete(&self, id: &str) -> Result<bool> {
+        let progress: Vec<f32> = self.get("getDataRequestProgress", &[("id", id)]).await?;
+
+        match progress.first() {
+            Some(p) if *p < 0.0 => Err(format!("ClimateServ request {id} failed"))?,
+            Some(p) => Ok(*p >= 100.0),
+            None => Ok(false),
+        }
+    }
+
+    /// Sends a GET request to an API endpoint and parses the JSON response.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        endpoint: &'static str,
+        params: &[(&str, impl AsRef<str>)],
+    ) -> Result<T> {
+        let params = params.iter().map(|(name, value)| (name, value.as_ref()));
+        let url = Url::parse_with_params(&format!("{}/{endpoint}/", self.base_url), params)
+            .map_err(|e| format!("Failed to build URL: {e}"))?;
+        let response = check_status(
+            "ClimateServ",
+            self.http.get("ClimateServ", endpoint, url).await?,
+        )
+        .await?;
+
+        parse_json("ClimateServ", response).await
+    }
+
+    /// Returns the weather of `address` on `date` (today if `None`).
+    #[instrument(skip(self, _provider_key))]
+    async fn weather_on(
+        &self,
+        _provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<WeatherInfo> {
+        let date = past_date(date, &SystemClock)?;
+        let location = self.geocode(None, address).await?;
+
+        let id = self.submit(&location, date).await?;
+        self.wait(&id).await?;
+        let data: ClimateServData = self.get("getDataFromRequest", &[("id", &id)]).await?;
+
+        to_weather_info(location, date, data)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for ClimateServProvider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
+
+    #[instrument(skip(self, _provider_key))]
+    async fn health_check(&self, _provider_key: Option<&str>) -> Result<Duration> {
+        let url = Url::parse(&format!("{}/getParameterTypes/", self.base_url))
+            .map_err(|e| format!("Failed to build URL: {e}"))?;
+
+        self.http
+            .probe("ClimateServ", "getParameterTypes", url)
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://climateserv.servirglobal.net",
+            key_format: "Not required",
+            free_tier: "Free; requests are processed as jobs and may take seconds",
+            capabilities: &["current", "history", "geocoding"],
+            example: "weather get Nairobi --date 2024-03-01 --provider cs",
+        }
+    }
+
+    fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HistoricalProvider for ClimateServProvider {
+    async fn get_history(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for ClimateServProvider {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        self.geocoder.geocode(provider_key, address).await
+    }
+}
+
+/// Resolves the date to request: the given date, or today if `None`.
+///
+/// # Errors
+///
+/// Returns `Error::UnsupportedDate` if the date is in the future.
+fn past_date(date: Option<&str>, clock: &dyn Clock) -> Result<NaiveDate> {
+    let today = clock.today_in(None);
+    let Some(date) = date else {
+        return Ok(today);
+    };
+
+    let date = NaiveDate::parse_from_str(&normalize_date(Some(date)), "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{date}': {e}"))?;
+    if date > today {
+        return Err(Error::UnsupportedDate {
+            provider: "ClimateServ".into(),
+            date: date.to_string(),
+            reason: "the date is in the future".into(),
+        });
+    }
+
+    Ok(date)
+}
+
+/// Returns the query of a request for the dataset on `date`, averaged over a square around
+/// the coordinates.
+fn submit_params(lat: f64, lon: f64, date: NaiveDate) -> Vec<(&'static str, String)> {
+    let (south, north) = (lat - AREA_HALF_SIDE, lat + AREA_HALF_SIDE);
+    let (west, east) = (lon - AREA_HALF_SIDE, lon + AREA_HALF_SIDE);
+    let geometry = serde_json::json!({
+        "type": "Polygon",
+        "coordinates": [[
+            [west, south], [east, south], [east, north], [west, north], [west, south]
+        ]]
+    });
+    let date = date.format("%m/%d/%Y").to_string();
+
+    vec![
+        ("datatype", DATATYPE.to_string()),
+        ("begintime", date.clone()),
+        ("endtime", date),
+        ("intervaltype", "0".to_string()),
+        ("operationtype", AVERAGE_OPERATION.to_string()),
+        ("geometry", geometry.to_string()),
+    ]
+}
+
+fn to_weather_info(
+    location: GeoLocation,
+    date: NaiveDate,
+    data: ClimateServData,
+) -> Result<WeatherInfo> {
+    let value = data
+        .data
+        .into_iter()
+        .map(|entry| entry.value)
+        .find(|value| value.temperature.is_some())
+        .ok_or_else(|| format!("ClimateServ returned no temperature for {date}"))?;
+
+    Ok(WeatherInfo {
+        country: location.country,
+        city: location.city,
+        date: date.format("%Y-%m-%d").to_string(),
+        temperature: value.temperature.map_or(f32::NAN, celsius_to_fahrenheit),
+        humidity: value.relative_humidity.map(humidity_percent),
+        description: Some("ClimateServ agricultural weather".to_string()),
+        meta: Some(WeatherMeta::new(
+            Provider::ClimateServ,
+            "data_request",
+            DataSource::Historical,
+        )),
+        ..Default::default()
+    })
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::utils::clock::FixedClock;
+    use ::chrono::{TimeZone, Utc};
+    use ::wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path, query_param},
+    };
+
+    fn provider(server: &MockServer, attempts: u32) -> ClimateServProvider {
+        ClimateServProvider {
+            polling: Polling::fixed(attempts, Duration::from_millis(10)),
+            ..ClimateServProvider::with_base_url(&server.uri(), HttpClient::default())
+        }
+    }
+
+    fn location() -> GeoLocation {
+        GeoLocation {
+            city: "Nairobi".to_string(),
+            country: "Kenya".to_string(),
+            lat: -1.28,
+            lon: 36.82,
+            ..GeoLocation::default()
+        }
+    }
+
+    async fn progress(server: &MockServer, progress: f32) {
+        Mock::given(method("GET"))
+            .and(path("/getDataRequestProgress/"))
+            .and(query_param("id", "job-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json([progress]))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_request_is_polled_until_complete() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/getDataRequestProgress/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json([40.0]))
+            .up_to_n_times(2)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        progress(&server, 100.0).await;
+
+        provider(&server, 500).wait("job-1").await.unwrap();
+
+        let polls = server.received_requests().await.unwrap().len();
+        assert_eq!(polls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_polling_timeout() {
+        let server = MockServer::start().await;
+        progress(&server, 10.0).await;
+
+        let error = provider(&server, 5).wait("job-1").await.unwrap_err();
+
+        assert!(matches!(error, Error::Timeout { .. }), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_failed_request() {
+        let server = MockServer::start().await;
+        progress(&server, -1.0).await;
+
+        let error = provider(&server, 500).wait("job-1").await.unwrap_err();
+
+        assert_eq!(error.to_string(), "ClimateServ request job-1 failed");
+    }
+
+    #[tokio::test]
+    async fn test_submit() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/submitDataRequest/"))
+            .and(query_param("begintime", "03/01/2024"))
+            .and(query_param("operationtype", "5"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(["job-1"]))
+            .mount(&server)
+            .await;
+
+        let id = provider(&server, 500)
+            .submit(&location(), NaiveDate::from_ymd_opt(2024, 3, 1).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(id, "job-1");
+    }
+
+    #[test]
+    fn test_submit_params() {
+        let params = submit_params(-1.28, 36.82, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        let param = |name| {
+            params
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| value.as_str())
+        };
+
+        assert_eq!(param("datatype"), Some("37"));
+        assert_eq!(param("endtime"), Some("03/01/2024"));
+        let geometry: serde_json::Value = serde_json::from_str(param("geometry").unwrap()).unwrap();
+        assert_eq!(geometry["type"], "Polygon");
+        assert_eq!(geometry["coordinates"][0].as_array().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_past_date() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap());
+        let date = |d| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+
+        assert_eq!(past_date(None, &clock).unwrap(), date("2024-03-20"));
+        assert_eq!(
+            past_date(Some("2024-03-01"), &clock).unwrap(),
+            date("2024-03-01")
+        );
+        assert!(matches!(
+            past_date(Some("2024-03-21"), &clock),
+            Err(Error::UnsupportedDate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let data = serde_json::from_str::<ClimateServData>(
+            r#"{
+                "data": [{
+                    "date": "03/01/2024",
+                    "epochTime": "1709251200",
+                    "workid": "f1c2",
+                    "value": { "Temperature": 20.0, "Relative_Humidity": 64.6 }
+                }]
+            }"#,
+        )
+        .unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        let info = to_weather_info(location(), date, data).unwrap();
+
+        assert_eq!(info.city, "Nairobi");
+        assert_eq!(info.date, "2024-03-01");
+        assert!((info.temperature - 68.0).abs() < 0.01);
+        assert_eq!(info.humidity, Some(65));
+
+        let empty = ClimateServData { data: Vec::new() };
+        assert!(to_weather_info(location(), date, empty).is_err());
+    }
+}
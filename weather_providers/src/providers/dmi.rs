@@ -0,0 +1,452 @@
+use crate::{
+    GeocodingClient, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, dmi::*,
+    },
+    providers::OpenMeteoGeocoder,
+    utils::{
+        geo,
+        http::{HttpClient, check_status, parse_json},
+        units::{celsius_to_fahrenheit, humidity_percent},
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::{DateTime, Utc};
+use ::reqwest::Url;
+use ::serde::de::DeserializeOwned;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const BASE_URL: &str = "https://dmigw.govcloud.dk/v2/metObs";
+
+/// The API gateway reads the key from this header, not from the query.
+const API_KEY_HEADER: &str = "X-Gravitee-Api-Key";
+
+/// The air temperature 2 m above ground, in degrees Celsius.
+const TEMPERATURE: &str = "temp_dry";
+
+/// The relative humidity 2 m above ground, in percent.
+const HUMIDITY: &str = "humidity";
+
+/// How far (in degrees) around the location stations are searched. Stations are a few tens of
+/// kilometers apart, so anything farther is outside the DMI network.
+const SEARCH_RADIUS_DEG: f64 = 0.5;
+
+/// Observations of the weather stations of the Danish Meteorological Institute, from the DMI
+/// Open Data metObs API.
+///
+/// Addresses are resolved with Open-Meteo geocoding, as the API has no place search. The
+/// active station nearest to them that measures the temperature is looked up with a bounding
+/// box query, then its latest temperature and humidity of the past hour are reported, whatever
+/// the date.
+#[derive(Debug)]
+pub struct DmiProvider {
+    base_url: String,
+    http: HttpClient,
+    geocoder: OpenMeteoGeocoder,
+}
+
+impl Default for DmiProvider {
+    fn default() -> Self {
+        Self::with_options(&ProviderOptions::default())
+    }
+}
+
+impl DmiProvider {
+    /// Creates a provider with the connection settings of `options`, also used for geocoding.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self::with_base_url(BASE_URL, options)
+    }
+
+    fn with_base_url(base_url: &str, options: &ProviderOptions) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http: HttpClient::new(options),
+            geocoder: OpenMeteoGeocoder::with_options(options),
+        }
+    }
+
+    /// Sends a GET request to `endpoint` with the API key header and decodes the JSON response.
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        endpoint: &'static str,
+        url: Url,
+        api_key: &str,
+    ) -> Result<T> {
+        let request = self
+            .http
+            .client("DMI")?
+            .get(url)
+            .header(API_KEY_HEADER, api_key);
+        let response = check_status("DMI", self.http.send("DMI", endpoint, request).await?).await?;
+
+        parse_json("DMI", response).await
+    }
+
+    /// Returns the latest observation of a parameter at a station in the past hour, if any.
+    async fn latest_observation(
+        &self,
+        api_key: &str,
+        station_id: &str,
+        parameter: &str,
+    ) -> Result<Option<DmiObservation>> {
+        let observations = self
+            .get_json::<DmiCollection<DmiObservation>>(
+                "observation",
+                observations_url(&self.base_url, station_id, parameter)?,
+                api_key,
+            )
+            .await?;
+
+        Ok(observations
+            .features
+            .into_iter()
+            .next()
+            .map(|feature| feature.properties))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for DmiProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let api_key = require_key(provider_key)?;
+        let location = self.geocode(None, address).await?;
+
+        let stations = self
+            .get_json::<DmiCollection<DmiStation>>(
+                "station",
+                stations_url(&self.base_url, location.lat, location.lon)?,
+                api_key,
+            )
+            .await?;
+        let station =
+            nearest_station(stations.features, location.lat, location.lon).ok_or_else(|| {
+                format!(
+                    "No DMI station near '{}, {}'. DMI only covers Denmark and Greenland.",
+                    location.city, location.country
+                )
+            })?;
+
+        let temperature = self
+            .latest_observation(api_key, &station.station_id, TEMPERATURE)
+            .await?
+            .ok_or_else(|| {
+                format!(
+                    "DMI station '{}' reported no temperature in the past hour",
+                    station.name
+                )
+            })?;
+        let humidity = if station.measures(HUMIDITY) {
+            self.latest_observation(api_key, &station.station_id, HUMIDITY)
+                .await?
+        } else {
+            None
+        };
+
+        Ok(to_weather_info(station, temperature, humidity))
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let request = self
+            .http
+            .client("DMI")?
+            .get(stations_url(&self.base_url, 55.6761, 12.5683)?)
+            .header(API_KEY_HEADER, require_key(provider_key)?);
+
+        self.http.probe_request("DMI", "station", request).await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://opendatadocs.dmi.govcloud.dk/Authentication",
+            key_format: "A UUID API key of the metObs API from the DMI Developer Portal",
+            free_tier: "Free open data, attribution to DMI required",
+            capabilities: &["current", "geocoding"],
+            example: "weather get Copenhagen --provider dmi",
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for DmiProvider {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        self.geocoder.geocode(provider_key, address).await
+    }
+}
+
+fn require_key(provider_key: Option<&str>) -> Result<&str> {
+    provider_key.ok_or_else(|| {
+        Error::MissingApiKey(
+            "'DMI' API key not set. Please set it using: 'weather provider dmi --key <API_KEY>'"
+                .into(),
+        )
+    })
+}
+
+/// Builds the URL of the active stations in a bounding box around the coordinates.
+fn stations_url(base_url: &str, lat: f64, lon: f64) -> Result<Url> {
+    // The box is given as "min lon,min lat,max lon,max lat"
+    let bbox = format!(
+        "{},{},{},{}",
+        lon - SEARCH_RADIUS_DEG,
+        lat - SEARCH_RADIUS_DEG,
+        lon + SEARCH_RADIUS_DEG,
+        lat + SEARCH_RADIUS_DEG
+    );
+
+    Ok(Url::parse_with_params(
+        &format!("{base_url}/collections/station/items"),
+        &[
+            ("bbox", bbox.as_str()),
+            ("status", "Active"),
+            ("limit", "1000"),
+        ],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+/// Builds the URL of the latest observation of a parameter at a station in the past hour.
+fn observations_url(base_url: &str, station_id: &str, parameter: &str) -> Result<Url> {
+    Ok(Url::parse_with_params(
+        &format!("{base_url}/collections/observation/items"),
+        &[
+            ("stationId", station_id),
+            ("parameterId", parameter),
+            ("period", "latest-hour"),
+            ("sortorder", "observed,DESC"),
+            ("limit", "1"),
+        ],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+/// Returns the station nearest to the coordinates that measures the temperature.
+fn nearest_station(
+    features: Vec<DmiFeature<DmiStation>>,
+    lat: f64,
+    lon: f64,
+) -> Option<DmiStation> {
+    let stations = features
+        .into_iter()
+        .filter(|feature| feature.properties.measures(TEMPERATURE))
+        .filter_map(|feature| Some((feature.properties, feature.geometry?.lat_lon()?)));
+
+    geo::nearest(stations, lat, lon).map(|(station, _)| station)
+}
+
+/// Maps the ISO 3166-1 alpha-3 country of a station to the alpha-2 code of the other
+/// providers. Stations without a country are Danish.
+fn country_code(country: Option<&str>) -> &'static str {
+    match country {
+        Some("GRL") => "GL",
+        Some("FRO") => "FO",
+        _ => "DK",
+    }
+}
+
+fn to_weather_info(
+    station: DmiStation,
+    temperature: DmiObservation,
+    humidity: Option<DmiObservation>,
+) -> WeatherInfo {
+    let observed = DateTime::parse_from_rfc3339(&temperature.observed)
+        .ok()
+        .map(|time| time.with_timezone(&Utc));
+
+    WeatherInfo {
+        country: country_code(station.country.as_deref()).to_string(),
+        city: station.name,
+        date: temperature.observed.chars().take(10).collect(),
+        observation_time: observed,
+        temperature: celsius_to_fahrenheit(temperature.value),
+        humidity: humidity.map(|h| humidity_percent(h.value)),
+        station_id: Some(station.station_id),
+        meta: Some(WeatherMeta::new(
+            Provider::Dmi,
+            "observation",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use ::wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{header, method, path, query_param},
+    };
+
+    fn stations() -> DmiCollection<DmiStation> {
+        serde_json::from_str(include_str!("../../tests/fixtures/dmi_stations.json")).unwrap()
+    }
+
+    fn observations() -> DmiCollection<DmiObservation> {
+        serde_json::from_str(include_str!("../../tests/fixtures/dmi_observations.json")).unwrap()
+    }
+
+    fn station(id: &str) -> DmiStation {
+        stations()
+            .features
+            .into_iter()
+            .map(|feature| feature.properties)
+            .find(|station| station.station_id == id)
+            .unwrap()
+    }
+
+    fn observation(observed: &str, value: f32) -> DmiObservation {
+        DmiObservation {
+            observed: observed.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_fixtures_deserialize() {
+        let stations = stations();
+        assert_eq!(stations.features.len(), 4);
+        let copenhagen = &stations.features[0];
+        assert_eq!(copenhagen.properties.station_id, "06186");
+        assert_eq!(copenhagen.properties.name, "København - Landbohøjskolen");
+        assert!(copenhagen.properties.measures(TEMPERATURE));
+        assert_eq!(
+            copenhagen.geometry.as_ref().unwrap().lat_lon(),
+            Some((55.6867, 12.5381))
+        );
+
+        let observations = observations();
+        assert_eq!(observations.features.len(), 1);
+        assert_eq!(
+            observations.features[0].properties.observed,
+            "2024-01-15T14:00:00Z"
+        );
+        assert_eq!(observations.features[0].properties.value, 4.5);
+    }
+
+    #[test]
+    fn test_nearest_station() {
+        let nearest = |lat, lon| nearest_station(stations().features, lat, lon);
+
+        // The harbour rain gauge is nearer to the center, but does not measure the temperature
+        assert_eq!(
+            nearest(55.6761, 12.5683).map(|s| s.station_id).as_deref(),
+            Some("06186")
+        );
+        assert_eq!(
+            nearest(55.6181, 12.6561).map(|s| s.station_id).as_deref(),
+            Some("06180")
+        );
+        assert!(nearest_station(Vec::new(), 55.6761, 12.5683).is_none());
+    }
+
+    #[test]
+    fn test_urls() {
+        let url = stations_url(BASE_URL, 55.5, 12.5).unwrap();
+        assert_eq!(url.path(), "/v2/metObs/collections/station/items");
+        assert!(
+            url.query().unwrap().contains("bbox=12%2C55%2C13%2C56"),
+            "{url}"
+        );
+
+        let url = observations_url(BASE_URL, "06186", TEMPERATURE).unwrap();
+        assert_eq!(url.path(), "/v2/metObs/collections/observation/items");
+        assert_eq!(
+            url.query(),
+            Some(
+                "stationId=06186&parameterId=temp_dry&period=latest-hour\
+                 &sortorder=observed%2CDESC&limit=1"
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let temperature = observations().features.remove(0).properties;
+        let info = to_weather_info(
+            station("06186"),
+            temperature,
+            Some(observation("2024-01-15T14:00:00Z", 86.6)),
+        );
+
+        assert_eq!(info.city, "København - Landbohøjskolen");
+        assert_eq!(info.country, "DK");
+        assert_eq!(info.date, "2024-01-15");
+        assert_eq!(
+            info.observation_time.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2024-01-15T14:00:00+00:00")
+        );
+        assert_eq!(info.temperature, 40.1);
+        assert_eq!(info.humidity, Some(87));
+        assert_eq!(info.station_id.as_deref(), Some("06186"));
+        assert!(info.is_from_station());
+    }
+
+    #[test]
+    fn test_country_code() {
+        assert_eq!(country_code(Some("DNK")), "DK");
+        assert_eq!(country_code(Some("GRL")), "GL");
+        assert_eq!(country_code(Some("FRO")), "FO");
+        assert_eq!(country_code(None), "DK");
+    }
+
+    #[tokio::test]
+    async fn test_missing_key() {
+        let error = DmiProvider::default()
+            .get_weather(None, "Copenhagen")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, Error::MissingApiKey(_)), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_latest_observation_sends_key_header() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/collections/observation/items"))
+            .and(header(API_KEY_HEADER, "key"))
+            .and(query_param("stationId", "06186"))
+            .and(query_param("parameterId", HUMIDITY))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "type": "FeatureCollection",
+                "features": [{
+                    "type": "Feature",
+                    "geometry": null,
+                    "properties": {
+                        "observed": "2024-01-15T14:00:00Z",
+                        "parameterId": "humidity",
+                        "stationId": "06186",
+                        "value": 86.6
+                    }
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        let provider = DmiProvider::with_base_url(&server.uri(), &ProviderOptions::default());
+
+        let humidity = provider
+            .latest_observation("key", "06186", HUMIDITY)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(humidity.value, 86.6);
+
+        // The gateway rejects other keys
+        let error = provider
+            .latest_observation("wrong", "06186", HUMIDITY)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::InvalidApiKey(_)), "{error}");
+    }
+}
@@ -0,0 +1,408 @@
+use crate::{
+    GeocodingClient, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, eccc::*,
+    },
+    providers::OpenMeteoGeocoder,
+    utils::{
+        geo,
+        http::{HttpClient, check_status, parse_json},
+        units::{celsius_to_fahrenheit, humidity_percent},
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::{Days, NaiveDate, NaiveDateTime};
+use ::reqwest::Url;
+use ::serde::de::DeserializeOwned;
+use ::std::time::Duration;
+use ::tracing::{debug, instrument};
+
+const BASE_URL: &str = "https://api.weather.gc.ca";
+
+/// How far (in degrees) around the location stations are searched.
+const SEARCH_RADIUS_DEG: f64 = 0.5;
+
+/// How many of the latest hours are requested; an hour may lack the temperature.
+const HOURS: &str = "24";
+
+/// How many days the hourly data of a station may lag behind the freshest station of the
+/// search and still count as active. Closed stations keep their archives, decades old.
+const ACTIVE_LAG_DAYS: u64 = 7;
+
+/// Hourly climate data of the weather stations of Environment and Climate Change Canada, from
+/// the MSC GeoMet OGC API.
+///
+/// The address is either a climate station ID (e.g., "6158355" for Toronto City), or a place
+/// resolved with Open-Meteo geocoding, of which the nearest active station reporting hourly
+/// data is looked up with a bounding box query. The latest hour with a temperature is
+/// reported, whatever the date; the climate archive lags the observations by a few hours.
+#[derive(Debug)]
+pub struct EcccProvider {
+    base_url: String,
+    http: HttpClient,
+    geocoder: OpenMeteoGeocoder,
+}
+
+impl Default for EcccProvider {
+    fn default() -> Self {
+        Self::with_options(&ProviderOptions::default())
+    }
+}
+
+impl EcccProvider {
+    /// Creates a provider with the connection settings of `options`, also used for geocoding.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self::with_base_url(BASE_URL, options)
+    }
+
+    fn with_base_url(base_url: &str, options: &ProviderOptions) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http: HttpClient::new(options),
+            geocoder: OpenMeteoGeocoder::with_options(options),
+        }
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, endpoint: &'static str, url: Url) -> Result<T> {
+        let response = check_status("ECCC", self.http.get("ECCC", endpoint, url).await?).await?;
+
+        parse_json("ECCC", response).await
+    }
+
+    /// Returns the climate ID of the active station nearest to the address.
+    async fn nearest_station_id(&self, address: &str) -> Result<String> {
+        let location = self.geocode(None, address).await?;
+        let stations = self
+            .get_json::<EcccCollection<EcccStation>>(
+                "climate-stations",
+                stations_url(&self.base_url, location.lat, location.lon)?,
+            )
+            .await?;
+
+        let station =
+            nearest_station(stations.features, location.lat, location.lon).ok_or_else(|| {
+                format!(
+                    "No ECCC climate station near '{}, {}'. ECCC only covers Canada.",
+                    location.city, location.country
+                )
+            })?;
+        debug!(
+            "Nearest ECCC station: {} ({})",
+            station.name, station.climate_id
+        );
+
+        Ok(station.climate_id)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for EcccProvider {
+    #[instrument(skip(self, _provider_key))]
+    async fn get_weather(&self, _provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let climate_id = match climate_id(address) {
+            Some(climate_id) => climate_id,
+            None => self.nearest_station_id(address).await?,
+        };
+
+        let hours = self
+            .get_json::<EcccCollection<EcccHourly>>(
+                "climate-hourly",
+                hourly_url(&self.base_url, &climate_id)?,
+            )
+            .await?;
+        // An unknown station ID matches no data
+        if hours.features.is_empty() {
+            return Err(Error::LocationNotFound("ECCC".into()));
+        }
+
+        let hour = latest_hour(hours.features).ok_or_else(|| {
+            format!("ECCC station '{climate_id}' reported no temperature in its latest hours")
+        })?;
+
+        Ok(to_weather_info(hour))
+    }
+
+    #[instrument(skip(self, _provider_key))]
+    async fn health_check(&self, _provider_key: Option<&str>) -> Result<Duration> {
+        self.http
+            .probe(
+                "ECCC",
+                "climate-hourly",
+                hourly_url(&self.base_url, "6158355")?,
+            )
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://api.weather.gc.ca",
+            key_format: "Not required",
+            free_tier: "Free open data under the Environment and Climate Change Canada Data \
+                        Servers End-use Licence",
+            capabilities: &["current", "geocoding"],
+            example: "weather get 6158355 --provider ec",
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for EcccProvider {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        self.geocoder.geocode(provider_key, address).await
+    }
+}
+
+/// Returns the address as a climate station ID, if it is one: 7 letters or digits starting
+/// with the digit of the province, e.g. "6158355" or "702S006".
+fn climate_id(address: &str) -> Option<String> {
+    let address = address.trim();
+
+    (address.len() == 7
+        && address.starts_with(|c: char| c.is_ascii_digit())
+        && address.chars().all(|c| c.is_ascii_alphanumeric()))
+    .then(|| address.to_ascii_uppercase())
+}
+
+/// Builds the URL of the stations reporting hourly data in a bounding box around the
+/// coordinates.
+fn stations_url(base_url: &str, lat: f64, lon: f64) -> Result<Url> {
+    // The box is given as "min lon,min lat,max lon,max lat"
+    let bbox = format!(
+        "{},{},{},{}",
+        lon - SEARCH_RADIUS_DEG,
+        lat - SEARCH_RADIUS_DEG,
+        lon + SEARCH_RADIUS_DEG,
+        lat + SEARCH_RADIUS_DEG
+    );
+
+    Ok(Url::parse_with_params(
+        &format!("{base_url}/collections/climate-stations/items"),
+        &[
+            ("bbox", bbox.as_str()),
+            ("HAS_HOURLY_DATA", "Y"),
+            ("limit", "500"),
+            ("f", "json"),
+        ],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+/// Builds the URL of the latest hourly data of a station, newest first.
+fn hourly_url(base_url: &str, climate_id: &str) -> Result<Url> {
+    Ok(Url::parse_with_params(
+        &format!("{base_url}/collections/climate-hourly/items"),
+        &[
+            ("CLIMATE_IDENTIFIER", climate_id),
+            ("sortby", "-LOCAL_DATE"),
+            ("limit", HOURS),
+            ("f", "json"),
+        ],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+/// Returns the active station nearest to the coordinates that reports hourly data.
+fn nearest_station(
+    features: Vec<EcccFeature<EcccStation>>,
+    lat: f64,
+    lon: f64,
+) -> Option<EcccStation> {
+    let last_day = |station: &EcccStation| {
+        let date = station.hourly_last_date.as_deref()?.get(..10)?;
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+    };
+
+    let freshest = features
+        .iter()
+        .filter(|feature| feature.properties.reports_hourly())
+        .filter_map(|feature| last_day(&feature.properties))
+        .max()?;
+    let active_since = freshest.checked_sub_days(Days::new(ACTIVE_LAG_DAYS))?;
+
+    let stations = features
+        .into_iter()
+        .filter(|feature| feature.properties.reports_hourly())
+        .filter(|feature| last_day(&feature.properties).is_some_and(|day| day >= active_since))
+        .filter_map(|feature| Some((feature.properties, feature.geometry?.lat_lon()?)));
+
+    geo::nearest(stations, lat, lon).map(|(station, _)| station)
+}
+
+/// Returns the latest hour with a temperature, of hours sorted newest first.
+fn latest_hour(features: Vec<EcccFeature<EcccHourly>>) -> Option<EcccHourly> {
+    features
+        .into_iter()
+        .map(|feature| feature.properties)
+        .find(|hour| hour.temperature.is_some_and(|t| !t.is_nan()))
+}
+
+fn to_weather_info(hour: EcccHourly) -> WeatherInfo {
+    let observation_time = hour
+        .utc_date
+        .as_deref()
+        .and_then(|time| NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M:%S").ok())
+        .map(|time| time.and_utc());
+
+    WeatherInfo {
+        country: "CA".to_string(),
+        city: hour.station_name,
+        date: hour.local_date.chars().take(10).collect(),
+        observation_time,
+        temperature: celsius_to_fahrenheit(hour.temperature.unwrap_or(f32::NAN)),
+        humidity: hour.humidity.map(humidity_percent),
+        station_id: Some(hour.climate_id),
+        meta: Some(WeatherMeta::new(
+            Provider::Eccc,
+            "climate-hourly",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use ::wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path, query_param},
+    };
+
+    const HOURLY: &str = include_str!("../../tests/fixtures/eccc_hourly.json");
+
+    fn stations() -> EcccCollection<EcccStation> {
+        serde_json::from_str(include_str!("../../tests/fixtures/eccc_stations.json")).unwrap()
+    }
+
+    fn hours() -> EcccCollection<EcccHourly> {
+        serde_json::from_str(HOURLY).unwrap()
+    }
+
+    #[test]
+    fn test_fixtures_deserialize() {
+        let stations = stations();
+        assert_eq!(stations.features.len(), 4);
+        let toronto = &stations.features[0];
+        assert_eq!(toronto.properties.climate_id, "6158355");
+        assert_eq!(toronto.properties.name, "TORONTO CITY");
+        assert!(toronto.properties.reports_hourly());
+        assert_eq!(
+            toronto.geometry.as_ref().unwrap().lat_lon(),
+            Some((43.666667, -79.4))
+        );
+        assert!(!stations.features[2].properties.reports_hourly());
+
+        let hours = hours();
+        assert_eq!(hours.features.len(), 2);
+        assert_eq!(hours.features[0].properties.temperature, None);
+        assert_eq!(hours.features[1].properties.temperature, Some(-5.2));
+        assert_eq!(hours.features[1].properties.humidity, Some(77.6));
+    }
+
+    #[test]
+    fn test_humidity_field_names() {
+        let hour = |field: &str| {
+            serde_json::from_value::<EcccHourly>(serde_json::json!({
+                "CLIMATE_IDENTIFIER": "6158355",
+                "STATION_NAME": "TORONTO CITY",
+                "LOCAL_DATE": "2024-01-15 08:00:00",
+                "TEMP": -5.2,
+                field: 77.6
+            }))
+            .unwrap()
+        };
+
+        assert_eq!(hour("REL_HUM").humidity, Some(77.6));
+        assert_eq!(hour("RELATIVE_HUMIDITY").humidity, Some(77.6));
+    }
+
+    #[test]
+    fn test_climate_id() {
+        assert_eq!(climate_id(" 6158355 ").as_deref(), Some("6158355"));
+        assert_eq!(climate_id("702s006").as_deref(), Some("702S006"));
+        assert_eq!(climate_id("Toronto"), None);
+        assert_eq!(climate_id("615835"), None);
+        assert_eq!(climate_id("Ottawa, CA"), None);
+    }
+
+    #[test]
+    fn test_nearest_station() {
+        let nearest = |lat, lon| {
+            nearest_station(stations().features, lat, lon).map(|station| station.climate_id)
+        };
+
+        // Toronto Island is nearer, but closed; the Harbourfront has no hourly data
+        assert_eq!(nearest(43.6532, -79.3832).as_deref(), Some("6158355"));
+        // Toronto City Centre lags a day behind Toronto City, and is still active
+        assert_eq!(nearest(43.6275, -79.3961).as_deref(), Some("6158359"));
+        assert!(nearest_station(Vec::new(), 43.6532, -79.3832).is_none());
+    }
+
+    #[test]
+    fn test_urls() {
+        let url = stations_url(BASE_URL, 43.5, -79.5).unwrap();
+        assert_eq!(url.path(), "/collections/climate-stations/items");
+        assert!(
+            url.query()
+                .unwrap()
+                .starts_with("bbox=-80%2C43%2C-79%2C44&HAS_HOURLY_DATA=Y"),
+            "{url}"
+        );
+
+        assert_eq!(
+            hourly_url(BASE_URL, "6158355").unwrap().as_str(),
+            "https://api.weather.gc.ca/collections/climate-hourly/items\
+             ?CLIMATE_IDENTIFIER=6158355&sortby=-LOCAL_DATE&limit=24&f=json"
+        );
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let info = to_weather_info(latest_hour(hours().features).unwrap());
+
+        assert_eq!(info.city, "TORONTO CITY");
+        assert_eq!(info.country, "CA");
+        assert_eq!(info.date, "2024-01-15");
+        assert_eq!(
+            info.observation_time.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2024-01-15T13:00:00+00:00")
+        );
+        assert_eq!(info.temperature, 22.64);
+        assert_eq!(info.humidity, Some(78));
+        assert_eq!(info.station_id.as_deref(), Some("6158355"));
+        assert!(info.is_from_station());
+
+        assert!(latest_hour(hours().features.into_iter().take(1).collect()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_station_id_address() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/collections/climate-hourly/items"))
+            .and(query_param("CLIMATE_IDENTIFIER", "6158355"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(HOURLY))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/collections/climate-hourly/items"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "type": "FeatureCollection",
+                "features": [],
+                "numberMatched": 0,
+                "numberReturned": 0
+            })))
+            .mount(&server)
+            .await;
+        let provider = EcccProvider::with_base_url(&server.uri(), &ProviderOptions::default());
+
+        let info = provider.get_weather(None, "6158355").await.unwrap();
+        assert_eq!(info.city, "TORONTO CITY");
+
+        let error = provider.get_weather(None, "1234567").await.unwrap_err();
+        assert!(matches!(error, Error::LocationNotFound(_)), "{error}");
+    }
+}
@@ -0,0 +1,552 @@
+use crate::{
+    GeocodingClient, HistoricalProvider, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, era5::*,
+    },
+    providers::OpenMeteoGeocoder,
+    utils::{
+        clock::{Clock, SystemClock},
+        date::normalize_date,
+        http::{HttpClient, check_status, parse_json},
+        poll::{Polling, poll},
+        units::{humidity_percent, kelvin_to_fahrenheit},
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::{Datelike, NaiveDate};
+use ::reqwest::{RequestBuilder, Url};
+use ::serde::de::DeserializeOwned;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const BASE_URL: &str = "https://cds.climate.copernicus.eu/api/v2";
+
+const SINGLE_LEVELS: &str = "reanalysis-era5-single-levels";
+const PRESSURE_LEVELS: &str = "reanalysis-era5-pressure-levels";
+
+/// ERA5 is published about five days behind real time.
+const PUBLICATION_LAG_DAYS: u64 = 5;
+
+/// The hour (UTC) of the reanalysis reported for a date.
+const HOUR: &str = "12:00";
+
+/// The pressure level (hPa) of the relative humidity; the closest to the surface.
+const HUMIDITY_PRESSURE_LEVEL: &str = "1000";
+
+/// How a queued job is polled: with delays doubling from 1 to 30 seconds, for about 10
+/// minutes.
+const POLLING: Polling = Polling {
+    attempts: 24,
+    interval: Duration::from_secs(1),
+    max_interval: Duration::from_secs(30),
+};
+
+/// ERA5 reanalysis from the Copernicus Climate Data Store (CDS).
+///
+/// The CDS queues each retrieval as a job, so the 2 m temperature and the relative humidity
+/// are requested as two jobs, polled with exponential back-off until their results can be
+/// downloaded. Results are requested as CoverageJSON. Addresses are resolved with Open-Meteo
+/// geocoding.
+#[derive(Debug)]
+pub struct Era5Provider {
+    base_url: String,
+    http: HttpClient,
+    geocoder: OpenMeteoGeocoder,
+    polling: Polling,
+}
+
+impl Default for Era5Provider {
+    fn default() -> Self {
+        Self::with_options(&ProviderOptions::default())
+    }
+}
+
+impl Era5Provider {
+    /// Creates a provider with the connection settings of `options`, also used for geocoding.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            geocoder: OpenMeteoGeocoder::with_options(options),
+            ..Self::with_base_url(BASE_URL, HttpClient::new(options))
+        }
+    }
+
+    fn with_base_url(base_url: &str, http: HttpClient) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http,
+            geocoder: OpenMeteoGeocoder::default(),
+            polling: POLLING,
+        }
+    }
+
+    /// Submits a retrieval job for `request` to `dataset`.
+    async fn submit(
+        &self,
+        credentials: (&str, &str),
+        dataset: &str,
+        request: &Era5Request<'_>,
+    ) -> Result<Era5Task> {
+        let url = format!("{}/resources/{dataset}", self.base_url);
+
+        self.send(
+            credentials,
+            "resources",
+            self.http.client("ERA5")?.post(url).json(request),
+        )
+        .await
+    }
+
+    /// Waits for a job and downloads the values of `variable` from its result.
+    async fn values(
+        &self,
+        credentials: (&str, &str),
+        task: Era5Task,
+        variable: &str,
+    ) -> Result<Vec<Option<f32>>> {
+        let location = self.wait(credentials, task).await?;
+
+        let url = Url::parse(&self.base_url)
+            .and_then(|base| base.join(&location))
+            .map_err(|e| format!("Invalid ERA5 result location '{location}': {e}"))?;
+        let mut coverage = self
+            .send::<Era5Coverage>(credentials, "result", self.http.client("ERA5")?.get(url))
+            .await?;
+
+        coverage
+            .ranges
+            .remove(variable)
+            .map(|range| range.values)
+            .ok_or_else(|| format!("ERA5 result has no '{variable}' values").into())
+    }
+
+    /// Waits for a job to complete and returns the URL of its result.
+    ///
+    /// A job that did not complete on submission is polled with the delays of `polling`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the job fails, and `Error::Timeout` if it is not complete after the
+    /// attempts of `polling`.
+    async fn wait(&self, credentials: (&str, &str), task: Era5Task) -> Result<String> {
+        if let Some(location) = job_result(&task)? {
+            return Ok(location);
+        }
+
+        let url = format!("{}/tasks/{}", self.base_url, task.request_id);
+        poll("ERA5", self.polling, || async {
+            let task = self
+                .send::<Era5Task>(credentials, "tasks", self.http.client("ERA5")?.get(&url))
+                .await?;
+
+            job_result(&task)
+        })
+        .await
+    }
+
+    /// Sends a request to `endpoint` authorized with the CDS credentials and parses the JSON
+    /// response.
+    async fn send<T: DeserializeOwned>(
+        &self,
+        credentials: (&str, &str),
+        endpoint: &'static str,
+        request: RequestBuilder,
+    ) -> Result<T> {
+        let (uid, api_key) = credentials;
+        let response = self
+            .http
+            .send("ERA5", endpoint, request.basic_auth(uid, Some(api_key)))
+            .await?;
+
+        parse_json("ERA5", check_status("ERA5", response).await?).await
+    }
+
+    /// Returns the weather of `address` on `date` (today if `None`).
+    #[instrument(skip(self, provider_key))]
+    async fn weather_on(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<WeatherInfo> {
+        let credentials = parse_credentials(provider_key)?;
+        let date = reanalysis_date(date, &SystemClock)?;
+        let location = self.geocode(None, address).await?;
+
+        // Both jobs are queued before either is awaited, so they are processed concurrently
+        let temperature = era5_request("2m_temperature", None, date, &location);
+        let temperature = self
+            .submit(credentials, SINGLE_LEVELS, &temperature)
+            .await?;
+        let humidity = era5_request(
+            "relative_humidity",
+            Some(HUMIDITY_PRESSURE_LEVEL),
+            date,
+            &location,
+        );
+        let humidity = self.submit(credentials, PRESSURE_LEVELS, &humidity).await?;
+
+        let t2m = self.values(credentials, temperature, "t2m").await?;
+        let r = self.values(credentials, humidity, "r").await?;
+
+        to_weather_info(location, date, &t2m, &r)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for Era5Provider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let (uid, api_key) = parse_credentials(provider_key)?;
+        let request = self
+            .http
+            .client("ERA5")?
+            .get(format!("{}/resources", self.base_url))
+            .basic_auth(uid, Some(api_key));
+
+        self.http.probe_request("ERA5", "resources", request).await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://cds.climate.copernicus.eu/user/register",
+            key_format: "<UID>:<API_KEY> from the CDS user profile",
+            free_tier: "Free; retrievals are queued and may take minutes",
+            capabilities: &["current", "history", "geocoding"],
+            example: "weather get Vienna --date 2020-06-01 --provider era5",
+        }
+    }
+
+    fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HistoricalProvider for Era5Provider {
+    async fn get_history(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for Era5Provider {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        self.geocoder.geocode(provider_key, address).await
+    }
+}
+
+/// Splits a key in the `<UID>:<API_KEY>` format.
+fn parse_credentials(provider_key: Option<&str>) -> Result<(&str, &str)> {
+    provider_key
+        .and_then(|key| key.split_once(':'))
+        .ok_or_else(|| {
+            Error::MissingApiKey(
+                "'ERA5' credentials not set. Please set them using: \
+                 'weather provider era5 --key <UID>:<API_KEY>'"
+                    .into(),
+            )
+        })
+}
+
+/// Resolves the date to retrieve: the given date, or the latest published one if `None`.
+///
+/// # Errors
+///
+/// Returns an error if the date is within the publication lag of ERA5.
+fn reanalysis_date(date: Option<&str>, clock: &dyn Clock) -> Result<NaiveDate> {
+    let latest = clock.today_in(None) - chrono::Days::new(PUBLICATION_LAG_DAYS);
+
+    let Some(date) = date else {
+        return Ok(latest);
+    };
+
+    let date = NaiveDate::parse_from_str(&normalize_date(Some(date)), "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{date}': {e}"))?;
+    if date > latest {
+        Err(format!(
+            "ERA5 data for {date} is not published yet. \
+             Reanalysis is available up to {latest}, {PUBLICATION_LAG_DAYS} days behind today"
+        ))?;
+    }
+
+    Ok(date)
+}
+
+fn era5_request<'a>(
+    variable: &'a str,
+    pressure_level: Option<&'a str>,
+    date: NaiveDate,
+    location: &GeoLocation,
+) -> Era5Request<'a> {
+    Era5Request {
+        product_type: "reanalysis",
+        variable,
+        pressure_level,
+        year: date.year().to_string(),
+        month: format!("{:02}", date.month()),
+        day: format!("{:02}", date.day()),
+        time: HOUR,
+        area: [location.lat, location.lon, location.lat, location.lon],
+        format: "covjson",
+    }
+}
+
+/// Returns the result URL of a completed job, or `None` if the job is still pending.
+///
+/// # Errors
+///
+/// Returns the reason of a failed job, or an error if a completed job has no result.
+fn job_result(task: &Era5Task) -> Result<Option<String>> {
+    match task.state {
+        Era5TaskState::Queued | Era5TaskState::Running => Ok(None),
+        Era5TaskState::Completed => task.location.clone().map(Some).ok_or_else(|| {
+            format!("ERA5 job {} completed without a result", task.request_id).into()
+        }),
+        Era5TaskState::Failed => {
+            let reason = task
+                .error
+                .as_ref()
+                .map(|e| format!("{} {}", e.message, e.reason).trim().to_string())
+                .unwrap_or_else(|| "unknown error".to_string());
+
+            Err(format!("ERA5 job {} failed: {reason}", task.request_id).into())
+        }
+    }
+}
+
+fn to_weather_info(
+    location: GeoLocation,
+    date: NaiveDate,
+    t2m: &[Option<f32>],
+    r: &[Option<f32>],
+) -> Result<WeatherInfo> {
+    let temperature = t2m
+        .iter()
+        .flatten()
+        .next()
+        .copied()
+        .ok_or("ERA5 returned no temperature for the location")?;
+    let humidity = r.iter().flatten().next().copied();
+
+    Ok(WeatherInfo {
+        country: location.country,
+        city: location.city,
+        date: date.format("%Y-%m-%d").to_string(),
+        temperature: kelvin_to_fahrenheit(temperature),
+        humidity: humidity.map(humidity_percent),
+        description: Some(format!("ERA5 reanalysis at {HOUR} UTC")),
+        meta: Some(WeatherMeta::new(
+            Provider::Era5,
+            "reanalysis",
+            DataSource::Historical,
+        )),
+        ..Default::default()
+    })
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::utils::clock::FixedClock;
+    use ::chrono::{TimeZone, Utc};
+    use ::wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{header, method, path},
+    };
+
+    const CREDENTIALS: (&str, &str) = ("12345", "secret");
+
+    fn provider(server: &MockServer) -> Era5Provider {
+        Era5Provider {
+            polling: Polling {
+                attempts: 100,
+                interval: Duration::from_millis(10),
+                max_interval: Duration::from_millis(40),
+            },
+            ..Era5Provider::with_base_url(&server.uri(), HttpClient::default())
+        }
+    }
+
+    fn location() -> GeoLocation {
+        GeoLocation {
+            city: "Vienna".to_string(),
+            country: "Austria".to_string(),
+            lat: 48.21,
+            lon: 16.37,
+            ..GeoLocation::default()
+        }
+    }
+
+    fn task(state: &str, location: Option<&str>) -> serde_json::Value {
+        serde_json::json!({ "state": state, "request_id": "job-1", "location": location })
+    }
+
+    #[tokio::test]
+    async fn test_job_is_polled_until_completed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/resources/reanalysis-era5-single-levels"))
+            // "12345:secret" in base64
+            .and(header("authorization", "Basic MTIzNDU6c2VjcmV0"))
+            .respond_with(ResponseTemplate::new(202).set_body_json(task("queued", None)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/tasks/job-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(task("running", None)))
+            .up_to_n_times(2)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/tasks/job-1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(task("completed", Some("/download/job-1.covjson"))),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/download/job-1.covjson"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "type": "Coverage",
+                "ranges": { "t2m": { "type": "NdArray", "values": [293.15] } }
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = provider(&server);
+        let request = era5_request(
+            "2m_temperature",
+            None,
+            NaiveDate::from_ymd_opt(2020, 6, 1).unwrap(),
+            &location(),
+        );
+        let task = provider
+            .submit(CREDENTIALS, SINGLE_LEVELS, &request)
+            .await
+            .unwrap();
+        let values = provider.values(CREDENTIALS, task, "t2m").await.unwrap();
+
+        assert_eq!(values, [Some(293.15)]);
+        let polls = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|r| r.url.path() == "/tasks/job-1")
+            .count();
+        assert_eq!(polls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_job() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/tasks/job-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "state": "failed",
+                "request_id": "job-1",
+                "error": { "message": "the request you have submitted is not valid" }
+            })))
+            .mount(&server)
+            .await;
+
+        let task = serde_json::from_value::<Era5Task>(task("queued", None)).unwrap();
+        let error = provider(&server)
+            .values(CREDENTIALS, task, "t2m")
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "ERA5 job job-1 failed: the request you have submitted is not valid"
+        );
+    }
+
+    #[test]
+    fn test_era5_request() {
+        let request = era5_request(
+            "relative_humidity",
+            Some(HUMIDITY_PRESSURE_LEVEL),
+            NaiveDate::from_ymd_opt(2020, 6, 1).unwrap(),
+            &location(),
+        );
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "product_type": "reanalysis",
+                "variable": "relative_humidity",
+                "pressure_level": "1000",
+                "year": "2020",
+                "month": "06",
+                "day": "01",
+                "time": "12:00",
+                "area": [48.21, 16.37, 48.21, 16.37],
+                "format": "covjson"
+            })
+        );
+    }
+
+    #[test]
+    fn test_reanalysis_date() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap());
+        let date = |d| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+
+        assert_eq!(reanalysis_date(None, &clock).unwrap(), date("2024-03-15"));
+        assert_eq!(
+            reanalysis_date(Some("2020-06-01"), &clock).unwrap(),
+            date("2020-06-01")
+        );
+        assert_eq!(
+            reanalysis_date(Some("2024-03-15"), &clock).unwrap(),
+            date("2024-03-15")
+        );
+
+        let error = reanalysis_date(Some("2024-03-16"), &clock).unwrap_err();
+        assert!(error.to_string().contains("not published yet"), "{error}");
+    }
+
+    #[test]
+    fn test_parse_credentials() {
+        assert_eq!(
+            parse_credentials(Some("12345:abc-def")).unwrap(),
+            ("12345", "abc-def")
+        );
+        assert!(parse_credentials(Some("abc-def")).is_err());
+        assert!(parse_credentials(None).is_err());
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let date = NaiveDate::from_ymd_opt(2020, 6, 1).unwrap();
+
+        let info = to_weather_info(location(), date, &[None, Some(293.15)], &[Some(64.6)]).unwrap();
+
+        assert_eq!(info.city, "Vienna");
+        assert_eq!(info.date, "2020-06-01");
+        assert!((info.temperature - 68.0).abs() < 0.01);
+        assert_eq!(info.humidity, Some(65));
+        assert_eq!(
+            info.description.as_deref(),
+            Some("ERA5 reanalysis at 12:00 UTC")
+        );
+
+        assert!(to_weather_info(location(), date, &[None], &[]).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{
+    Provider, WeatherProvider,
+    common::*,
+    models::{DataSource, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, fmi::*},
+    utils::{
+        date::normalize_date,
+        http::{HttpClient, check_status_with},
+        observer,
+        units::{celsius_to_fahrenheit, humidity_percent},
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::{DateTime, Utc};
+use ::reqwest::Url;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const BASE_URL: &str = "https://opendata.fmi.fi/wfs";
+
+/// The stored query of the weather station observations, one value per WFS member.
+const STORED_QUERY: &str = "fmi::observations::weather::simple";
+
+/// The observed parameters: the air temperature in degrees Celsius and the relative humidity.
+const PARAMETERS: &str = "temperature,humidity";
+
+/// Weather station observations from the open data WFS service of the Finnish Meteorological
+/// Institute.
+///
+/// FMI resolves the address itself (e.g., "Helsinki" or "Oulu") to its nearest station, so no
+/// geocoding is needed, nor an API key. The service answers with a WFS feature collection in
+/// XML, of which the latest temperature and humidity of the past hours are reported, whatever
+/// the date.
+#[derive(Debug)]
+pub struct FmiProvider {
+    base_url: String,
+    http: HttpClient,
+}
+
+impl Default for FmiProvider {
+    fn default() -> Self {
+        Self::with_options(&ProviderOptions::default())
+    }
+}
+
+impl FmiProvider {
+    /// Creates a provider with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self::with_base_url(BASE_URL, HttpClient::new(options))
+    }
+
+    fn with_base_url(base_url: &str, http: HttpClient) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http,
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for FmiProvider {
+    #[instrument(skip(self, _provider_key))]
+    async fn get_weather(&self, _provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let response = check_status_with(
+            "FMI",
+            self.http
+                .get(
+                    "FMI",
+                    "observations",
+                    observations_url(&self.base_url, address)?,
+                )
+                .await?,
+            decode_error,
+        )
+        .await?;
+        let observations = FmiObservations::from_xml(&response.text().await?)
+            .inspect_err(|e| observer::request_failed("FMI", e))?;
+
+        to_weather_info(address, observations)
+    }
+
+    #[instrument(skip(self, _provider_key))]
+    async fn health_check(&self, _provider_key: Option<&str>) -> Result<Duration> {
+        self.http
+            .probe(
+                "FMI",
+                "observations",
+                observations_url(&self.base_url, "Helsinki")?,
+            )
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://en.ilmatieteenlaitos.fi/open-data",
+            key_format: "Not required",
+            free_tier: "Free open data (CC BY 4.0), attribution to FMI required",
+            capabilities: &["current"],
+            example: "weather get Helsinki --provider fmi",
+        }
+    }
+}
+
+/// Builds the URL of the observations of the station nearest to a place.
+fn observations_url(base_url: &str, place: &str) -> Result<Url> {
+    Ok(Url::parse_with_params(
+        base_url,
+        &[
+            ("service", "WFS"),
+            ("version", "2.0.0"),
+            ("request", "getFeature"),
+            ("storedquery_id", STORED_QUERY),
+            ("place", place.trim()),
+            ("parameters", PARAMETERS),
+        ],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+/// Decodes the OWS exception report of an FMI error.
+fn decode_error(body: &str) -> Option<Error> {
+    let text = exception_text(body)?;
+
+    // The message of a place FMI cannot resolve
+    if text.starts_with("No locations found") {
+        return Some(Error::LocationNotFound("FMI".into()));
+    }
+
+    Some(format!("FMI error: {text}").into())
+}
+
+fn to_weather_info(address: &str, observations: FmiObservations) -> Result<WeatherInfo> {
+    let temperature = observations
+        .values
+        .get("temperature")
+        .ok_or_else(|| format!("FMI reported no temperature for '{}'", address.trim()))?;
+    let humidity = observations.values.get("humidity");
+
+    let (date, observation_time) = match &observations.time {
+        Some(time) => (
+            time.chars().take(10).collect(),
+            DateTime::parse_from_rfc3339(time)
+                .ok()
+                .map(|time| time.with_timezone(&Utc)),
+        ),
+        None => (normalize_date(None::<&str>), None),
+    };
+
+    Ok(WeatherInfo {
+        country: "FI".to_string(),
+        city: address.trim().to_string(),
+        date,
+        observation_time,
+        temperature: celsius_to_fahrenheit(*temperature),
+        humidity: humidity.map(|&h| humidity_percent(h)),
+        meta: Some(WeatherMeta::new(
+            Provider::Fmi,
+            "observations",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/fmi_observations.xml");
+
+    const EXCEPTION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ExceptionReport xmlns="http://www.opengis.net/ows/1.1" version="2.0.0" xml:lang="eng">
+  <Exception exceptionCode="OperationParsingFailed">
+    <ExceptionText>No locations found for the place with the requested language!</ExceptionText>
+    <ExceptionText>URI: /wfs?place=Atlantis</ExceptionText>
+  </Exception>
+</ExceptionReport>"#;
+
+    #[test]
+    fn test_fixture_parses() {
+        let observations = FmiObservations::from_xml(FIXTURE).unwrap();
+
+        assert_eq!(observations.time.as_deref(), Some("2024-01-15T14:00:00Z"));
+        // The latest humidity is missing (NaN), so the one before it is kept
+        assert_eq!(
+            observations.values.into_iter().collect::<Vec<_>>(),
+            [
+                ("humidity".to_string(), 89.0),
+                ("temperature".to_string(), -5.5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_members_without_values() {
+        let empty = r#"<wfs:FeatureCollection xmlns:wfs="http://www.opengis.net/wfs/2.0"
+            numberMatched="0" numberReturned="0"/>"#;
+        assert_eq!(
+            FmiObservations::from_xml(empty).unwrap(),
+            FmiObservations::default()
+        );
+
+        assert!(FmiObservations::from_xml("<a></b>").is_err());
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let info =
+            to_weather_info(" Helsinki ", FmiObservations::from_xml(FIXTURE).unwrap()).unwrap();
+
+        assert_eq!(info.city, "Helsinki");
+        assert_eq!(info.country, "FI");
+        assert_eq!(info.date, "2024-01-15");
+        assert_eq!(
+            info.observation_time.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2024-01-15T14:00:00+00:00")
+        );
+        assert_eq!(info.temperature, 22.1);
+        assert_eq!(info.humidity, Some(89));
+
+        let error = to_weather_info("Helsinki", FmiObservations::default()).unwrap_err();
+        assert!(error.to_string().contains("no temperature"), "{error}");
+    }
+
+    #[test]
+    fn test_decode_error() {
+        assert!(matches!(
+            decode_error(EXCEPTION),
+            Some(Error::LocationNotFound(_))
+        ));
+
+        let other = EXCEPTION.replace("No locations found", "Invalid parameter");
+        assert!(
+            decode_error(&other)
+                .unwrap()
+                .to_string()
+                .contains("Invalid parameter for the place")
+        );
+
+        assert!(decode_error("Service Unavailable").is_none());
+    }
+
+    #[test]
+    fn test_observations_url() {
+        let url = observations_url(BASE_URL, " Helsinki ").unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "https://opendata.fmi.fi/wfs?service=WFS&version=2.0.0&request=getFeature\
+             &storedquery_id=fmi%3A%3Aobservations%3A%3Aweather%3A%3Asimple&place=Helsinki\
+             &parameters=temperature%2Chumidity"
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_unknown_place() {
+        use ::wiremock::{
+            Mock, MockServer, ResponseTemplate,
+            matchers::{method, query_param},
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("place", "Atlantis"))
+            .respond_with(ResponseTemplate::new(400).set_body_string(EXCEPTION))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("place", "Helsinki"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(FIXTURE))
+            .mount(&server)
+            .await;
+        let provider = FmiProvider::with_base_url(&server.uri(), HttpClient::default());
+
+        let error = provider.get_weather(None, "Atlantis").await.unwrap_err();
+        assert!(matches!(error, Error::LocationNotFound(_)), "{error}");
+
+        let info = provider.get_weather(None, "Helsinki").await.unwrap();
+        assert_eq!(info.temperature, 22.1);
+    }
+}
@@ -0,0 +1,431 @@
+use crate::{
+    GeocodingClient, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, Icon, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta,
+        WindUnit, foreca::*,
+    },
+    utils::{
+        clock::Instant,
+        http::{HttpClient, check_status, parse_json},
+        units::celsius_to_fahrenheit,
+    },
+};
+use ::async_trait::async_trait;
+use ::reqwest::{StatusCode, Url};
+use ::serde::de::DeserializeOwned;
+use ::std::time::Duration;
+use ::tokio::sync::Mutex;
+use ::tracing::instrument;
+
+const BASE_URL: &str = "https://pfa.foreca.com";
+
+/// Tokens are refreshed this long before they expire, so a request never races the expiry.
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// The Foreca Point Forecast API.
+///
+/// Requests are authorized with a Bearer token obtained from the client credentials. The token
+/// is cached until shortly before it expires and refreshed once if the API rejects it.
+#[derive(Debug)]
+pub struct ForecaProvider {
+    base_url: String,
+    http: HttpClient,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl Default for ForecaProvider {
+    fn default() -> Self {
+        Self::with_options(&ProviderOptions::default())
+    }
+}
+
+impl ForecaProvider {
+    /// Creates a provider with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self::with_base_url(BASE_URL, HttpClient::new(options))
+    }
+
+    fn with_base_url(base_url: &str, http: HttpClient) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached token, or requests a new one if it is missing, expired or `refresh` is
+    /// set.
+    async fn token(&self, credentials: (&str, &str), refresh: bool) -> Result<String> {
+        let mut cache = self.token.lock().await;
+
+        if !refresh
+            && let Some((token, expires_at)) = cache.as_ref()
+            && Instant::now() < *expires_at
+        {
+            return Ok(token.clone());
+        }
+
+        let (user, password) = credentials;
+        let request = self
+            .http
+            .client("Foreca")?
+            .post(format!("{}/authorize/token", self.base_url))
+            .json(&ForecaTokenRequest { user, password });
+        let response = self.http.send("Foreca", "authorize/token", request).await?;
+        let body =
+            parse_json::<ForecaTokenResponse>("Foreca", check_status("Foreca", response).await?)
+                .await?;
+
+        let lifetime = Duration::from_secs(body.expires_in).saturating_sub(TOKEN_EXPIRY_MARGIN);
+        *cache = Some((body.access_token.clone(), Instant::now() + lifetime));
+
+        Ok(body.access_token)
+    }
+
+    /// Sends an authorized GET request to `endpoint`, refreshing the token once if it is
+    /// rejected.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        credentials: (&str, &str),
+        endpoint: &'static str,
+        url: Url,
+    ) -> Result<T> {
+        let client = self.http.client("Foreca")?;
+
+        let token = self.token(credentials, false).await?;
+        let mut response = self
+            .http
+            .send(
+                "Foreca",
+                endpoint,
+                client.get(url.clone()).bearer_auth(token),
+            )
+            .await?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            let token = self.token(credentials, true).await?;
+            response = self
+                .http
+                .send("Foreca", endpoint, client.get(url).bearer_auth(token))
+                .await?;
+        }
+
+        parse_json("Foreca", check_status("Foreca", response).await?).await
+    }
+
+    /// Searches for the location matching `address`.
+    async fn find_location(
+        &self,
+        credentials: (&str, &str),
+        address: &str,
+    ) -> Result<ForecaLocation> {
+        let url = self.url(&["api", "v1", "location", "search", address])?;
+        let body = self
+            .get::<ForecaLocationResponse>(credentials, "location/search", url)
+            .await?;
+
+        body.locations
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::UnknownLocation {
+                provider: "Foreca".into(),
+                query: address.to_string(),
+            })
+    }
+
+    fn url(&self, segments: &[&str]) -> Result<Url> {
+        let mut url =
+            Url::parse(&self.base_url).map_err(|e| format!("Failed to build URL: {e}"))?;
+        url.path_segments_mut()
+            .map_err(|_| "Failed to build URL: invalid base URL")?
+            .extend(segments);
+
+        Ok(url)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for ForecaProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let credentials = parse_credentials(provider_key)?;
+
+        let location = self.find_location(credentials, address).await?;
+        let url = self.url(&["api", "v1", "current", &location.id.to_string()])?;
+        let body = self
+            .get::<ForecaCurrentResponse>(credentials, "current", url)
+            .await?;
+
+        Ok(to_weather_info(location, body.current).with_derived_precip_type())
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let credentials = parse_credentials(provider_key)?;
+
+        let started = Instant::now();
+        self.token(credentials, true).await?;
+
+        Ok(started.elapsed())
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://developer.foreca.com",
+            key_format: "<CLIENT_ID>:<CLIENT_SECRET> of the API account",
+            free_tier: "1,000 requests per day on the trial plan",
+            capabilities: &["current", "geocoding"],
+            example: "weather provider fc --key <CLIENT_ID>:<CLIENT_SECRET>",
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for ForecaProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        let location = self
+            .find_location(parse_credentials(provider_key)?, address)
+            .await?;
+
+        Ok(GeoLocation {
+            city: location.name,
+            region: None,
+            country: location.country,
+            lat: location.lat,
+            lon: location.lon,
+        })
+    }
+}
+
+/// Splits a key in the `<CLIENT_ID>:<CLIENT_SECRET>` format.
+fn parse_credentials(provider_key: Option<&str>) -> Result<(&str, &str)> {
+    provider_key
+        .and_then(|key| key.split_once(':'))
+        .ok_or_else(|| {
+            Error::MissingApiKey(
+                "'Foreca' credentials not set. Please set them using: \
+                 'weather provider fc --key <CLIENT_ID>:<CLIENT_SECRET>'"
+                    .into(),
+            )
+        })
+}
+
+fn to_weather_info(location: ForecaLocation, current: ForecaCurrent) -> WeatherInfo {
+    WeatherInfo {
+        country: location.country,
+        city: location.name,
+        date: current.time.chars().take(10).collect(),
+        temperature: celsius_to_fahrenheit(current.temperature),
+        humidity: Some(current.rel_humidity),
+        description: current.symbol_phrase.clone(),
+        wind_speed_mph: current
+            .wind_speed
+            .map(|ms| WindUnit::MetersPerSecond.to_mph(ms)),
+        precipitation_mm: current.precip_rate,
+        feels_like: current.feels_like_temp.map(celsius_to_fahrenheit),
+        // The symbols are not documented publicly, so the icon is derived from the phrase
+        icon: current
+            .symbol_phrase
+            .as_deref()
+            .and_then(|phrase| {
+                let is_night = current
+                    .symbol
+                    .as_deref()
+                    .is_some_and(|s| s.starts_with('n'));
+                Icon::from_description(phrase, !is_night)
+            })
+            .map(|icon| icon.code().to_string()),
+        provider_icon: current.symbol,
+        meta: Some(WeatherMeta::new(
+            Provider::Foreca,
+            "current",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::utils::signing::HmacSha256Signer;
+    use ::wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{header, method, path},
+    };
+
+    const CREDENTIALS: Option<&str> = Some("client:secret");
+
+    async fn mount_token(server: &MockServer, token: &str, expected_calls: u64) {
+        Mock::given(method("POST"))
+            .and(path("/authorize/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": token,
+                "expires_in": 3600,
+                "token_type": "bearer"
+            })))
+            .expect(expected_calls)
+            .mount(server)
+            .await;
+    }
+
+    async fn mount_location(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/location/search/Helsinki"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "locations": [{
+                    "id": 100658225,
+                    "name": "Helsinki",
+                    "country": "Finland",
+                    "timezone": "Europe/Helsinki",
+                    "lon": 24.93545,
+                    "lat": 60.16952
+                }]
+            })))
+            .mount(server)
+            .await;
+    }
+
+    fn current() -> serde_json::Value {
+        serde_json::json!({
+            "current": {
+                "time": "2024-01-15T14:00+02:00",
+                "symbol": "d300",
+                "symbolPhrase": "cloudy",
+                "temperature": -5,
+                "feelsLikeTemp": -10,
+                "relHumidity": 84,
+                "windSpeed": 4,
+                "precipRate": 0.2
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_current_weather_and_token_cache() {
+        let server = MockServer::start().await;
+        mount_token(&server, "token-1", 1).await;
+        mount_location(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/current/100658225"))
+            .and(header("authorization", "Bearer token-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(current()))
+            .mount(&server)
+            .await;
+
+        let provider = ForecaProvider::with_base_url(&server.uri(), HttpClient::default());
+        let info = provider.get_weather(CREDENTIALS, "Helsinki").await.unwrap();
+
+        assert_eq!(info.city, "Helsinki");
+        assert_eq!(info.country, "Finland");
+        assert_eq!(info.date, "2024-01-15");
+        assert_eq!(info.temperature, 23.0);
+        assert_eq!(info.feels_like, Some(14.0));
+        assert_eq!(info.humidity, Some(84));
+        assert_eq!(info.description.as_deref(), Some("cloudy"));
+        // 4 m/s
+        assert!((info.wind_speed_mph.unwrap() - 8.948).abs() < 0.01);
+
+        // The second request reuses the cached token
+        provider.get_weather(CREDENTIALS, "Helsinki").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_signed() {
+        let server = MockServer::start().await;
+        mount_token(&server, "token-1", 1).await;
+        mount_location(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/current/100658225"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(current()))
+            .mount(&server)
+            .await;
+
+        let signer = HmacSha256Signer::new("X-Gateway-Signature", "secret").unwrap();
+        let http = HttpClient::new(&ProviderOptions {
+            signer: Some(std::sync::Arc::new(signer)),
+            ..ProviderOptions::default()
+        });
+        ForecaProvider::with_base_url(&server.uri(), http)
+            .get_weather(CREDENTIALS, "Helsinki")
+            .await
+            .unwrap();
+
+        // The token, location and weather requests all carry a signature
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 3);
+        for request in requests {
+            let signature = request.headers["x-gateway-signature"].to_str().unwrap();
+            assert!(signature.starts_with("t="), "{signature}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_is_refreshed_on_unauthorized() {
+        let server = MockServer::start().await;
+        mount_token(&server, "fresh", 1).await;
+        mount_location(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/current/100658225"))
+            .and(header("authorization", "Bearer fresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(current()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(header("authorization", "Bearer stale"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let provider = ForecaProvider::with_base_url(&server.uri(), HttpClient::default());
+        *provider.token.lock().await = Some((
+            "stale".to_string(),
+            Instant::now() + Duration::from_secs(3600),
+        ));
+
+        let info = provider.get_weather(CREDENTIALS, "Helsinki").await.unwrap();
+
+        assert_eq!(info.city, "Helsinki");
+        assert_eq!(
+            provider
+                .token
+                .lock()
+                .await
+                .as_ref()
+                .map(|(t, _)| t.as_str()),
+            Some("fresh")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_credentials() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/authorize/token"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let provider = ForecaProvider::with_base_url(&server.uri(), HttpClient::default());
+        let error = provider
+            .get_weather(CREDENTIALS, "Helsinki")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, Error::InvalidApiKey(_)));
+    }
+
+    #[test]
+    fn test_parse_credentials() {
+        assert_eq!(
+            parse_credentials(Some("id:se:cret")).unwrap(),
+            ("id", "se:cret")
+        );
+        assert!(parse_credentials(Some("no-separator")).is_err());
+        assert!(parse_credentials(None).is_err());
+    }
+}
@@ -1,19 +1,38 @@
+#[cfg(feature = "grpc")]
 pub mod weather_proto {
     tonic::include_proto!("weather");
 }
 
-use crate::{WeatherProvider, common::*, models::WeatherInfo, utils::date::*};
+use crate::{
+    ForecastProvider, HistoricalProvider, Provider, WeatherProvider,
+    common::*,
+    models::{DataSource, ProviderInfo, WeatherInfo, WeatherMeta},
+    utils::{date::*, http::ensure_online, units::humidity_percent},
+};
 use ::async_trait::async_trait;
-use weather_proto::{WeatherRequest, weather_service_client::WeatherServiceClient};
+use ::std::{fmt::Display, time::Duration};
+#[cfg(feature = "grpc")]
+use {
+    crate::utils::{clock::Instant, observer, units::celsius_to_fahrenheit},
+    ::chrono::DateTime,
+    weather_proto::{
+        Units, WeatherRequest, WeatherResponse, weather_service_client::WeatherServiceClient,
+    },
+};
 
 /// Mock provider address for weather data using gRPC
+#[cfg(feature = "grpc")]
 const MOCK_SERVER: &str = "http://[::1]:54583";
 
+/// Why the server is never contacted in builds without the `grpc` feature.
+#[cfg(not(feature = "grpc"))]
+const GRPC_DISABLED: &str = "gRPC support is not compiled in (enable the 'grpc' feature)";
+
 pub struct GrpcMockProvider;
 
-#[async_trait]
-impl WeatherProvider for GrpcMockProvider {
-    async fn get_weather(
+impl GrpcMockProvider {
+    /// Returns the weather of `address` on `date` (today if `None`).
+    async fn weather_on(
         &self,
         _provider_key: Option<&str>,
         address: &str,
@@ -21,44 +40,325 @@ impl WeatherProvider for GrpcMockProvider {
     ) -> Result<WeatherInfo> {
         let date_normalized = normalize_date(date);
 
-        let client_result = WeatherServiceClient::connect(MOCK_SERVER).await;
-
-        match client_result {
-            Ok(mut client) => {
-                let request = tonic::Request::new(WeatherRequest {
-                    location: address.to_string(),
-                    date: date_normalized.clone(),
-                });
-
-                let response = client
-                    .get_weather(request)
-                    .await
-                    .map_err(|e| format!("gRPC error: {e}"))?
-                    .into_inner();
-
-                Ok(WeatherInfo {
-                    country: response.country,
-                    city: response.city,
-                    date: response.date,
-                    temperature: response.temperature,
-                    humidity: response.humidity as u8,
-                    description: Some(response.description),
-                })
-            }
-            Err(_) => {
-                eprintln!(
-                    "(gRPC Mock: Server not found at '{MOCK_SERVER}', returning static data)"
-                );
-
-                Ok(WeatherInfo {
-                    country: "gRPC Mock Country".to_string(),
-                    city: "gRPC Mock City".to_string(),
-                    date: date_normalized,
-                    temperature: 42.0,
-                    humidity: 88,
-                    description: Some("Rain (Mock)".to_string()),
-                })
-            }
+        // Offline, the server is not contacted and the static data is returned
+        match ensure_online("GrpcMockWeather") {
+            Ok(()) => request_weather(address, date_normalized).await,
+            Err(e) => Ok(static_weather(e, date_normalized)),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for GrpcMockProvider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
+
+    async fn health_check(&self, _provider_key: Option<&str>) -> Result<Duration> {
+        ensure_online("GrpcMockWeather")?;
+
+        connect_latency().await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://github.com/AnatoliiShliakhto/weather",
+            key_format: "Not required",
+            free_tier: "Unlimited (requires the local gRPC mock server)",
+            capabilities: &["current", "history", "forecast"],
+            example: "weather get London --provider grpc",
+        }
+    }
+
+    fn as_forecast(&self) -> Option<&dyn ForecastProvider> {
+        Some(self)
+    }
+
+    fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ForecastProvider for GrpcMockProvider {
+    async fn get_forecast(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HistoricalProvider for GrpcMockProvider {
+    async fn get_history(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+/// Requests the weather from the mock server, or returns the static data if it is not running.
+#[cfg(feature = "grpc")]
+async fn request_weather(address: &str, date: String) -> Result<WeatherInfo> {
+    request_weather_from(MOCK_SERVER, address, date).await
+}
+
+#[cfg(feature = "grpc")]
+async fn request_weather_from(server: &str, address: &str, date: String) -> Result<WeatherInfo> {
+    let Ok(mut client) = WeatherServiceClient::connect(server.to_string()).await else {
+        return Ok(static_weather(
+            format!("Server not found at '{server}'"),
+            date,
+        ));
+    };
+
+    let request = tonic::Request::new(WeatherRequest {
+        location: address.to_string(),
+        date,
+    });
+
+    observer::request_started("GrpcMockWeather", "GetWeather");
+    let started = Instant::now();
+    let response = client.get_weather(request).await;
+    observer::request_ended("GrpcMockWeather", "GetWeather", None, started.elapsed());
+
+    response
+        .map_err(|e| Error::from(format!("gRPC error: {e}")))
+        .and_then(|response| to_weather_info(response.into_inner()))
+        .inspect_err(|e| observer::request_failed("GrpcMockWeather", e))
+}
+
+/// Validates a response of the server and converts it to Fahrenheit.
+///
+/// # Errors
+///
+/// Returns a decode error with the offending value if the temperature is not finite or the
+/// humidity is outside `0..=100`.
+#[cfg(feature = "grpc")]
+fn to_weather_info(response: WeatherResponse) -> Result<WeatherInfo> {
+    if !response.temperature.is_finite() {
+        Err(format!(
+            "Failed to decode gRPC response: temperature {} is not a finite number",
+            response.temperature
+        ))?;
+    }
+
+    // Servers predating `relative_humidity` send the legacy integer field
+    #[allow(deprecated)]
+    let humidity = response
+        .relative_humidity
+        .unwrap_or(response.humidity as f32);
+    if !(0.0..=100.0).contains(&humidity) {
+        Err(format!(
+            "Failed to decode gRPC response: humidity {humidity} is outside 0..=100"
+        ))?;
+    }
+
+    let temperature = match response.units() {
+        Units::Metric => celsius_to_fahrenheit(response.temperature),
+        Units::Imperial | Units::Unspecified => response.temperature,
+    };
+
+    Ok(WeatherInfo {
+        country: response.country,
+        city: response.city,
+        date: response.date,
+        observation_time: response
+            .observation_time
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0)),
+        temperature,
+        humidity: Some(humidity_percent(humidity)),
+        description: Some(response.description),
+        meta: Some(WeatherMeta::new(
+            Provider::GrpcMock,
+            "GetWeather",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    })
+}
+
+#[cfg(not(feature = "grpc"))]
+async fn request_weather(_address: &str, date: String) -> Result<WeatherInfo> {
+    Ok(static_weather(GRPC_DISABLED, date))
+}
+
+#[cfg(feature = "grpc")]
+async fn connect_latency() -> Result<Duration> {
+    let started = Instant::now();
+
+    WeatherServiceClient::connect(MOCK_SERVER)
+        .await
+        .map_err(|e| format!("gRPC server not reachable at '{MOCK_SERVER}': {e}"))?;
+
+    Ok(started.elapsed())
+}
+
+#[cfg(not(feature = "grpc"))]
+async fn connect_latency() -> Result<Duration> {
+    Err(GRPC_DISABLED.into())
+}
+
+/// The data returned when the mock server cannot be used.
+fn static_weather(reason: impl Display, date: String) -> WeatherInfo {
+    eprintln!("(gRPC Mock: {reason}, returning static data)");
+
+    WeatherInfo {
+        country: "gRPC Mock Country".to_string(),
+        city: "gRPC Mock City".to_string(),
+        date,
+        temperature: 42.0,
+        humidity: Some(88),
+        description: Some("Rain (Mock)".to_string()),
+        meta: Some(WeatherMeta::new(
+            Provider::GrpcMock,
+            "static",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    }
+}
+
+#[cfg(all(test, feature = "grpc", not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use ::tonic::{
+        Request, Response, Status,
+        transport::{Server, server::TcpIncoming},
+    };
+    use weather_proto::weather_service_server::{WeatherService, WeatherServiceServer};
+
+    /// A server answering every request with the same response.
+    struct FixedServer(WeatherResponse);
+
+    #[tonic::async_trait]
+    impl WeatherService for FixedServer {
+        async fn get_weather(
+            &self,
+            request: Request<WeatherRequest>,
+        ) -> std::result::Result<Response<WeatherResponse>, Status> {
+            let request = request.into_inner();
+
+            Ok(Response::new(WeatherResponse {
+                city: request.location,
+                date: request.date,
+                ..self.0.clone()
+            }))
+        }
+    }
+
+    /// Starts an in-process server and returns its address.
+    async fn serve(response: WeatherResponse) -> String {
+        let incoming = TcpIncoming::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let address = incoming.local_addr().unwrap();
+
+        tokio::spawn(
+            Server::builder()
+                .add_service(WeatherServiceServer::new(FixedServer(response)))
+                .serve_with_incoming(incoming),
+        );
+
+        format!("http://{address}")
+    }
+
+    fn response(temperature: f32, relative_humidity: Option<f32>, units: Units) -> WeatherResponse {
+        WeatherResponse {
+            country: "Testland".to_string(),
+            temperature,
+            description: "Clear".to_string(),
+            relative_humidity,
+            units: units.into(),
+            ..Default::default()
+        }
+    }
+
+    async fn round_trip(response: WeatherResponse) -> Result<WeatherInfo> {
+        let server = serve(response).await;
+
+        request_weather_from(&server, "Oslo", "2024-01-15".to_string()).await
+    }
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let info = round_trip(WeatherResponse {
+            observation_time: Some(1705327200),
+            ..response(68.0, Some(45.6), Units::Imperial)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(info.city, "Oslo");
+        assert_eq!(info.country, "Testland");
+        assert_eq!(info.date, "2024-01-15");
+        assert_eq!(info.temperature, 68.0);
+        assert_eq!(info.humidity, Some(46));
+        assert_eq!(info.description.as_deref(), Some("Clear"));
+        assert_eq!(
+            info.observation_time.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2024-01-15T14:00:00+00:00")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metric_temperature_is_converted() {
+        let info = round_trip(response(20.0, Some(50.0), Units::Metric))
+            .await
+            .unwrap();
+
+        assert_eq!(info.temperature, 68.0);
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_legacy_response() {
+        let legacy = WeatherResponse {
+            humidity: 73,
+            ..response(42.0, None, Units::Unspecified)
+        };
+
+        let info = round_trip(legacy).await.unwrap();
+
+        assert_eq!(info.temperature, 42.0);
+        assert_eq!(info.humidity, Some(73));
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_invalid_values_are_rejected() {
+        let cases = [
+            (response(68.0, Some(150.0), Units::Imperial), "humidity 150"),
+            (response(68.0, Some(-1.0), Units::Imperial), "humidity -1"),
+            (
+                WeatherResponse {
+                    humidity: 300,
+                    ..response(68.0, None, Units::Imperial)
+                },
+                "humidity 300",
+            ),
+            (
+                response(f32::NAN, Some(50.0), Units::Imperial),
+                "temperature NaN",
+            ),
+            (
+                response(f32::INFINITY, Some(50.0), Units::Metric),
+                "temperature inf",
+            ),
+        ];
+
+        for (response, expected) in cases {
+            let error = round_trip(response).await.unwrap_err().to_string();
+
+            assert!(error.contains("Failed to decode gRPC response"), "{error}");
+            assert!(error.contains(expected), "{error}");
         }
     }
 }
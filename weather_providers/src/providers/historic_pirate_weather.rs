@@ -0,0 +1,312 @@
+use crate::{
+    GeocodingClient, HistoricalProvider, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, Icon, PrecipType, ProviderInfo, ProviderOptions, WeatherInfo,
+        WeatherMeta, pirate_weather::*,
+    },
+    providers::OpenMeteoGeocoder,
+    utils::{
+        clock::{Clock, SystemClock},
+        date::normalize_date,
+        http::{HttpClient, check_status, parse_json},
+        units::humidity_percent,
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::{DateTime, NaiveDate};
+use ::reqwest::Url;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const FORECAST_URL: &str = "https://api.pirateweather.net/forecast";
+
+/// The blocks left out of the response; only `currently` is read.
+const EXCLUDED_BLOCKS: &str = "minutely,hourly,daily,alerts,flags";
+
+/// The first date of the reanalysis behind the historic data.
+const FIRST_DATE: NaiveDate = NaiveDate::from_ymd_opt(1979, 1, 1).unwrap();
+
+/// Historic weather from the Dark Sky compatible Time Machine endpoint of Pirate Weather.
+///
+/// The conditions at midnight (UTC) of the requested date are reported, for any date from
+/// 1979 up to today. Addresses are resolved with Open-Meteo geocoding.
+#[derive(Debug, Default)]
+pub struct HistoricPirateWeatherProvider {
+    http: HttpClient,
+    geocoder: OpenMeteoGeocoder,
+}
+
+impl HistoricPirateWeatherProvider {
+    /// Creates a provider with the connection settings of `options`, also used for geocoding.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+            geocoder: OpenMeteoGeocoder::with_options(options),
+        }
+    }
+
+    /// Returns the weather of `address` on `date` (today if `None`).
+    #[instrument(skip(self, provider_key))]
+    async fn weather_on(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<WeatherInfo> {
+        let api_key = require_key(provider_key)?;
+        let timestamp = midnight_timestamp(date, &SystemClock)?;
+        let location = self.geocode(None, address).await?;
+
+        let url = time_machine_url(api_key, location.lat, location.lon, timestamp)?;
+        let response = check_status(
+            "HistoricPirateWeather",
+            self.http
+                .get("HistoricPirateWeather", "time_machine", url)
+                .await?,
+        )
+        .await?;
+        let body = parse_json::<PirateWeatherResponse>("HistoricPirateWeather", response).await?;
+
+        Ok(to_weather_info(location, body.currently)
+            .with_derived_precip_type()
+            .with_derived_icon())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for HistoricPirateWeatherProvider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let timestamp = midnight_timestamp(None, &SystemClock)?;
+
+        self.http
+            .probe(
+                "HistoricPirateWeather",
+                "time_machine",
+                time_machine_url(require_key(provider_key)?, 51.5072, -0.1276, timestamp)?,
+            )
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://pirate-weather.apiable.io",
+            key_format: "The API key from the Pirate Weather dashboard",
+            free_tier: "10,000 requests per month",
+            capabilities: &["current", "history", "geocoding"],
+            example: "weather get Boston --date 1995-07-14 --provider pwh",
+        }
+    }
+
+    fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HistoricalProvider for HistoricPirateWeatherProvider {
+    async fn get_history(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for HistoricPirateWeatherProvider {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        self.geocoder.geocode(provider_key, address).await
+    }
+}
+
+fn require_key(provider_key: Option<&str>) -> Result<&str> {
+    provider_key.ok_or_else(|| {
+        Error::MissingApiKey(
+            "'HistoricPirateWeather' API key not set. Please set it using: \
+             'weather provider pwh --key <API_KEY>'"
+                .into(),
+        )
+    })
+}
+
+/// Returns the Unix timestamp of midnight (UTC) of the date, or of today if `None`.
+///
+/// # Errors
+///
+/// Returns `Error::UnsupportedDate` if the date is in the future or before 1979.
+fn midnight_timestamp(date: Option<&str>, clock: &dyn Clock) -> Result<i64> {
+    let today = clock.today_in(None);
+    let date = match date {
+        Some(date) => NaiveDate::parse_from_str(&normalize_date(Some(date)), "%Y-%m-%d")
+            .map_err(|e| format!("Invalid date '{date}': {e}"))?,
+        None => today,
+    };
+
+    let reason = if date > today {
+        "the date is in the future"
+    } else if date < FIRST_DATE {
+        "historic data starts on 1979-01-01"
+    } else {
+        return Ok(date.and_time(Default::default()).and_utc().timestamp());
+    };
+
+    Err(Error::UnsupportedDate {
+        provider: "HistoricPirateWeather".into(),
+        date: date.to_string(),
+        reason: reason.into(),
+    })
+}
+
+fn time_machine_url(api_key: &str, lat: f64, lon: f64, timestamp: i64) -> Result<Url> {
+    let mut url = Url::parse(FORECAST_URL).map_err(|e| format!("Failed to build URL: {e}"))?;
+    url.path_segments_mut()
+        .map_err(|_| "Failed to build URL: invalid base URL")?
+        .extend([api_key, &format!("{lat},{lon},{timestamp}")]);
+    url.query_pairs_mut()
+        .append_pair("units", "us")
+        .append_pair("exclude", EXCLUDED_BLOCKS);
+
+    Ok(url)
+}
+
+fn to_weather_info(location: GeoLocation, currently: PirateWeatherDataPoint) -> WeatherInfo {
+    WeatherInfo {
+        country: location.country,
+        city: location.city,
+        date: DateTime::from_timestamp(currently.time, 0)
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+        temperature: currently.temperature,
+        humidity: currently.humidity.map(|h| humidity_percent(h * 100.0)),
+        description: currently.summary,
+        wind_speed_mph: currently.wind_speed,
+        precipitation_mm: currently.precip_intensity.map(|inches| inches * 25.4),
+        feels_like: currently.apparent_temperature,
+        pressure_hpa: currently.pressure,
+        precip_type: currently
+            .precip_type
+            .as_deref()
+            .and_then(PrecipType::from_name),
+        icon: currently
+            .icon
+            .as_deref()
+            .and_then(Icon::from_code)
+            .map(|icon| icon.code().to_string()),
+        provider_icon: currently.icon,
+        meta: Some(WeatherMeta::new(
+            Provider::HistoricPirateWeather,
+            "time_machine",
+            DataSource::Historical,
+        )),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::FixedClock;
+    use ::chrono::{TimeZone, Utc};
+
+    const CURRENTLY: &str = r#"{
+        "latitude": 42.36,
+        "longitude": -71.06,
+        "timezone": "America/New_York",
+        "currently": {
+            "time": 805680000,
+            "summary": "Light Rain",
+            "icon": "rain",
+            "precipIntensity": 0.05,
+            "precipType": "rain",
+            "temperature": 71.3,
+            "apparentTemperature": 72.1,
+            "humidity": 0.88,
+            "pressure": 1009.4,
+            "windSpeed": 6.2
+        }
+    }"#;
+
+    fn clock() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn test_midnight_timestamp() {
+        assert_eq!(
+            midnight_timestamp(Some("1995-07-14"), &clock()).unwrap(),
+            805680000
+        );
+        assert_eq!(
+            midnight_timestamp(Some("1979-01-01"), &clock()).unwrap(),
+            283996800
+        );
+        assert_eq!(midnight_timestamp(None, &clock()).unwrap(), 1710892800);
+    }
+
+    #[test]
+    fn test_unsupported_dates() {
+        for date in ["2024-03-21", "1978-12-31"] {
+            let error = midnight_timestamp(Some(date), &clock()).unwrap_err();
+
+            assert!(
+                matches!(&error, Error::UnsupportedDate { date: d, .. } if d == date),
+                "{error}"
+            );
+        }
+
+        let error = midnight_timestamp(Some("2030-01-01"), &clock()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "'HistoricPirateWeather' has no data for 2030-01-01: the date is in the future"
+        );
+    }
+
+    #[test]
+    fn test_time_machine_url() {
+        let url = time_machine_url("my-key", 42.36, -71.06, 805680000).unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "https://api.pirateweather.net/forecast/my-key/42.36,-71.06,805680000\
+             ?units=us&exclude=minutely%2Chourly%2Cdaily%2Calerts%2Cflags"
+        );
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let body = serde_json::from_str::<PirateWeatherResponse>(CURRENTLY).unwrap();
+        let location = GeoLocation {
+            city: "Boston".to_string(),
+            country: "United States".to_string(),
+            lat: 42.36,
+            lon: -71.06,
+            ..GeoLocation::default()
+        };
+
+        let info = to_weather_info(location, body.currently);
+
+        assert_eq!(info.city, "Boston");
+        assert_eq!(info.date, "1995-07-14");
+        assert_eq!(info.temperature, 71.3);
+        assert_eq!(info.feels_like, Some(72.1));
+        assert_eq!(info.humidity, Some(88));
+        assert_eq!(info.wind_speed_mph, Some(6.2));
+        assert_eq!(info.pressure_hpa, Some(1009.4));
+        assert_eq!(info.precipitation_mm, Some(1.27));
+        assert_eq!(info.precip_type, Some(PrecipType::Rain));
+        assert_eq!(info.description.as_deref(), Some("Light Rain"));
+        assert_eq!(info.icon.as_deref(), Some("rain"));
+        assert_eq!(info.provider_icon.as_deref(), Some("rain"));
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    GeocodingClient, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta,
+        meteo_swiss::*,
+    },
+    providers::OpenMeteoGeocoder,
+    utils::{
+        date::normalize_date,
+        geo,
+        http::{HttpClient, check_status, parse_json},
+        units::{celsius_to_fahrenheit, humidity_percent},
+    },
+};
+use ::async_trait::async_trait;
+use ::reqwest::Url;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const MEASUREMENTS_URL: &str = "https://data.geo.admin.ch/ch.meteoschweiz.messwerte-aktuell-10min/ch.meteoschweiz.messwerte-aktuell-10min_en.json";
+
+/// How far (in kilometers) the nearest station may be from the location. Stations are a few
+/// kilometers apart, so anything farther is outside Switzerland.
+const MAX_STATION_DISTANCE_KM: f64 = 50.0;
+
+/// Current 10-minute measurements of the MeteoSwiss SwissMetNet stations, from the open data
+/// of the Swiss Federal Office of Meteorology and Climatology.
+///
+/// Addresses are resolved with Open-Meteo geocoding, and the station nearest to them that
+/// measures the temperature is reported. The latest measurements are reported, whatever the
+/// date.
+#[derive(Debug, Default)]
+pub struct MeteoSwissProvider {
+    http: HttpClient,
+    geocoder: OpenMeteoGeocoder,
+}
+
+impl MeteoSwissProvider {
+    /// Creates a provider with the connection settings of `options`, also used for geocoding.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+            geocoder: OpenMeteoGeocoder::with_options(options),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for MeteoSwissProvider {
+    #[instrument(skip(self, _provider_key))]
+    async fn get_weather(&self, _provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let location = self.geocode(None, address).await?;
+
+        let response = check_status(
+            "MeteoSwiss",
+            self.http
+                .get("MeteoSwiss", "measurements", measurements_url()?)
+                .await?,
+        )
+        .await?;
+        let body = parse_json::<MeteoSwissCollection>("MeteoSwiss", response).await?;
+
+        to_weather_info(&location, body)
+    }
+
+    #[instrument(skip(self, _provider_key))]
+    async fn health_check(&self, _provider_key: Option<&str>) -> Result<Duration> {
+        self.http
+            .probe("MeteoSwiss", "measurements", measurements_url()?)
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://opendatadocs.meteoswiss.ch",
+            key_format: "Not required",
+            free_tier: "Free open data, attribution to MeteoSwiss required",
+            capabilities: &["current", "geocoding"],
+            example: "weather get Bern --provider msw",
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for MeteoSwissProvider {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        self.geocoder.geocode(provider_key, address).await
+    }
+}
+
+fn measurements_url() -> Result<Url> {
+    Url::parse(MEASUREMENTS_URL).map_err(|e| format!("Failed to build URL: {e}").into())
+}
+
+/// Returns the station nearest to the coordinates that measures the temperature, along with
+/// its distance in kilometers.
+fn nearest_station(
+    features: &[MeteoSwissFeature],
+    lat: f64,
+    lon: f64,
+) -> Option<(&MeteoSwissFeature, f64)> {
+    let stations = features
+        .iter()
+        .filter(|feature| feature.properties.tt.is_some())
+        .filter_map(|feature| Some((feature, feature.geometry.lat_lon()?)));
+
+    geo::nearest(stations, lat, lon)
+}
+
+fn to_weather_info(location: &GeoLocation, body: MeteoSwissCollection) -> Result<WeatherInfo> {
+    let (station, distance) = nearest_station(&body.features, location.lat, location.lon)
+        .ok_or("'MeteoSwiss' returned no station measuring the temperature")?;
+
+    if distance > MAX_STATION_DISTANCE_KM {
+        Err(format!(
+            "No MeteoSwiss station near '{}, {}'. MeteoSwiss only covers Switzerland.",
+            location.city, location.country
+        ))?;
+    }
+
+    let properties = &station.properties;
+    let date = match &properties.reference_ts {
+        Some(timestamp) => timestamp.chars().take(10).collect(),
+        None => normalize_date(None::<&str>),
+    };
+
+    Ok(WeatherInfo {
+        country: "CH".to_string(),
+        city: properties.station_name.clone(),
+        date,
+        temperature: properties.tt.map(celsius_to_fahrenheit).unwrap_or_default(),
+        humidity: properties.rh.map(humidity_percent),
+        station_id: station.id.clone(),
+        meta: Some(WeatherMeta::new(
+            Provider::MeteoSwiss,
+            "measurements",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> MeteoSwissCollection {
+        serde_json::from_str(include_str!(
+            "../../tests/fixtures/meteo_swiss_current.json"
+        ))
+        .unwrap()
+    }
+
+    fn location(city: &str, lat: f64, lon: f64) -> GeoLocation {
+        GeoLocation {
+            city: city.to_string(),
+            country: "Switzerland".to_string(),
+            lat,
+            lon,
+            ..GeoLocation::default()
+        }
+    }
+
+    #[test]
+    fn test_fixture_deserializes() {
+        let body = fixture();
+
+        assert_eq!(body.features.len(), 4);
+        assert_eq!(body.features[0].id.as_deref(), Some("BER"));
+        assert_eq!(body.features[0].geometry.lat_lon(), Some((46.9907, 7.4641)));
+        assert_eq!(
+            body.features[0].properties.station_name,
+            "Bern / Zollikofen"
+        );
+        assert_eq!(body.features[0].properties.tt, Some(2.5));
+        assert_eq!(body.features[2].properties.tt, None);
+    }
+
+    #[test]
+    fn test_nearest_station() {
+        let body = fixture();
+        let nearest = |lat, lon| {
+            nearest_station(&body.features, lat, lon).map(|(feature, _)| feature.id.as_deref())
+        };
+
+        assert_eq!(nearest(46.948, 7.4474), Some(Some("BER")));
+        assert_eq!(nearest(46.2044, 6.1432), Some(Some("GVE")));
+        // The Uetliberg station is nearer to Zurich, but does not measure the temperature
+        assert_eq!(nearest(47.3769, 8.5417), Some(Some("SMA")));
+
+        assert!(nearest_station(&[], 46.948, 7.4474).is_none());
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let info = to_weather_info(&location("Bern", 46.948, 7.4474), fixture()).unwrap();
+
+        assert_eq!(info.city, "Bern / Zollikofen");
+        assert_eq!(info.country, "CH");
+        assert_eq!(info.date, "2024-01-15");
+        assert_eq!(info.temperature, 36.5);
+        assert_eq!(info.humidity, Some(87));
+        assert_eq!(info.station_id.as_deref(), Some("BER"));
+        assert!(info.is_from_station());
+    }
+
+    #[test]
+    fn test_outside_switzerland() {
+        let error = to_weather_info(&location("Paris", 48.8566, 2.3522), fixture())
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("only covers Switzerland"), "{error}");
+    }
+}
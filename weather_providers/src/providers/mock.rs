@@ -1,25 +1,268 @@
-use crate::{WeatherProvider, common::*, models::WeatherInfo, utils::date::*};
+use crate::{
+    ForecastProvider, GeocodingClient, HistoricalProvider, HourlyProvider, Provider,
+    WeatherProvider,
+    common::*,
+    models::{
+        DataSource, DayParts, GeoLocation, HourlyWeather, PrecipType, ProviderInfo,
+        ProviderOptions, WeatherInfo, WeatherMeta,
+        mock::{FailKind, MockFaults},
+    },
+    utils::{
+        clock::{Clock, SystemClock},
+        date::*,
+    },
+};
 use ::async_trait::async_trait;
+use ::chrono::{NaiveDate, TimeDelta};
+use ::std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-pub struct MockProvider;
+pub struct MockProvider {
+    clock: Arc<dyn Clock>,
+    faults: Option<FaultInjector>,
+}
 
-#[async_trait]
-impl WeatherProvider for MockProvider {
-    async fn get_weather(
+impl MockProvider {
+    /// Creates a mock provider that resolves missing dates using the given clock.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            faults: None,
+        }
+    }
+
+    /// Creates a mock provider injecting the faults of `options.mock_faults`, or else of the
+    /// `WEATHER_PROVIDERS_MOCK_FAULTS` environment variable.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self::default().with_faults(options.mock_faults.clone().or_else(MockFaults::from_env))
+    }
+
+    /// Injects the faults into every weather request; `None` keeps the provider fault-free.
+    pub fn with_faults(mut self, faults: Option<MockFaults>) -> Self {
+        self.faults = faults.map(FaultInjector::new);
+        self
+    }
+
+    /// Returns the weather of `address` on `date` (today if `None`).
+    async fn weather_on(
         &self,
         _provider_key: Option<&str>,
-        _address: &str,
+        address: &str,
         date: Option<&str>,
     ) -> Result<WeatherInfo> {
-        let date = normalize_date(date);
+        if let Some(faults) = &self.faults {
+            faults.inject(address).await?;
+        }
+
+        let date = normalize_date_with(date, self.clock.as_ref());
+        let meta = WeatherMeta::with_clock(
+            Provider::Mock,
+            "mock",
+            DataSource::for_date_with(&date, self.clock.as_ref()),
+            self.clock.as_ref(),
+        );
 
         Ok(WeatherInfo {
             country: "Mock Country".to_string(),
             city: "Mock City".to_string(),
             date,
+            observation_time: Some(self.clock.now()),
             temperature: 20.0,
-            humidity: 50,
+            humidity: Some(50),
             description: Some("Sunny (Mock)".to_string()),
+            wind_speed_mph: Some(5.0),
+            precipitation_mm: Some(0.0),
+            precip_type: Some(PrecipType::None),
+            periods: Some(DayParts {
+                morning: 14.0,
+                afternoon: 20.0,
+                evening: 17.0,
+                night: 11.0,
+                low: Some(9.0),
+                high: Some(22.0),
+            }),
+            icon: Some("clear-day".to_string()),
+            provider_icon: Some("sunny".to_string()),
+            meta: Some(meta),
+            ..Default::default()
+        }
+        .with_precip_probability(Some(0.1)))
+    }
+}
+
+/// Decides which requests fail, with a SplitMix64 generator so a seed always gives the same
+/// sequence of failures.
+struct FaultInjector {
+    faults: MockFaults,
+    state: Mutex<u64>,
+}
+
+impl FaultInjector {
+    fn new(faults: MockFaults) -> Self {
+        Self {
+            state: Mutex::new(faults.seed),
+            faults,
+        }
+    }
+
+    /// Returns the next number of the sequence, uniformly distributed in `[0, 1)`.
+    fn next_unit(&self) -> f64 {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        // The top 53 bits fill the mantissa of an `f64`
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Waits for the configured latency, then fails if this request is drawn to fail or its
+    /// address is one of `fail_on`.
+    async fn inject(&self, address: &str) -> Result<()> {
+        // Drawn before waiting, so concurrent requests keep the order of the sequence
+        let fail = self.next_unit() < self.faults.fail_rate || self.faults.fails_on(address);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if !self.faults.latency.is_zero() {
+            tokio::time::sleep(self.faults.latency).await;
+        }
+
+        if !fail {
+            return Ok(());
+        }
+
+        match self.faults.fail_kind {
+            FailKind::Timeout => std::future::pending().await,
+            FailKind::ServerError => Err(Error::ServerError("MockWeather".into())),
+            FailKind::NotFound => Err(Error::LocationNotFound("MockWeather".into())),
+        }
+    }
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for MockProvider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
+
+    async fn health_check(&self, _provider_key: Option<&str>) -> Result<Duration> {
+        Ok(Duration::ZERO)
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://github.com/AnatoliiShliakhto/weather",
+            key_format: "Not required",
+            free_tier: "Unlimited (static offline data)",
+            capabilities: &["current", "history", "forecast", "geocoding"],
+            example: "weather get London --provider mock",
+        }
+    }
+
+    fn as_forecast(&self) -> Option<&dyn ForecastProvider> {
+        Some(self)
+    }
+
+    fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+        Some(self)
+    }
+
+    fn as_hourly(&self) -> Option<&dyn HourlyProvider> {
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ForecastProvider for MockProvider {
+    async fn get_forecast(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HistoricalProvider for MockProvider {
+    async fn get_history(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HourlyProvider for MockProvider {
+    /// Returns the 24 hours of the date in UTC, each at the temperature of its part of the day.
+    async fn get_hourly(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<Vec<HourlyWeather>> {
+        let weather = self.weather_on(provider_key, address, date).await?;
+        let midnight = NaiveDate::parse_from_str(&weather.date, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid date: {e}"))?
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc()
+            .fixed_offset();
+        let periods = weather.periods.unwrap_or(DayParts {
+            morning: weather.temperature,
+            afternoon: weather.temperature,
+            evening: weather.temperature,
+            night: weather.temperature,
+            low: None,
+            high: None,
+        });
+
+        Ok((0..24)
+            .map(|hour| HourlyWeather {
+                time: midnight + TimeDelta::hours(hour),
+                temperature: match hour {
+                    0..6 => periods.night,
+                    6..12 => periods.morning,
+                    12..18 => periods.afternoon,
+                    _ => periods.evening,
+                },
+                // The mock weather always reports a humidity
+                humidity: weather.humidity.unwrap_or_default(),
+                wind_speed_mph: weather.wind_speed_mph,
+            })
+            .collect())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for MockProvider {
+    async fn geocode(&self, _provider_key: Option<&str>, _address: &str) -> Result<GeoLocation> {
+        Ok(GeoLocation {
+            city: "Mock City".to_string(),
+            region: None,
+            country: "Mock Country".to_string(),
+            lat: 51.5072,
+            lon: -0.1276,
         })
     }
 }
@@ -28,10 +271,11 @@ impl WeatherProvider for MockProvider {
 mod tests {
     use super::*;
 
+    #[cfg(not(target_arch = "wasm32"))]
     #[tokio::test]
     async fn test_mock_provider_returns_data() {
-        let provider = MockProvider;
-        let result = provider.get_weather(None, "Nowhere", None).await;
+        let provider = MockProvider::default();
+        let result = provider.get_weather(None, "Nowhere").await;
 
         assert!(result.is_ok());
         let info = result.unwrap();
@@ -39,18 +283,21 @@ mod tests {
         assert_eq!(info.city, "Mock City");
         assert_eq!(info.country, "Mock Country");
         assert_eq!(info.temperature, 20.0);
-        assert_eq!(info.humidity, 50);
+        assert_eq!(info.humidity, Some(50));
         assert_eq!(info.description, Some("Sunny (Mock)".to_string()));
+        assert_eq!(info.precip_type, Some(PrecipType::None));
+        assert_eq!(info.snow_depth_mm, None);
+        assert_eq!(info.precip_probability, Some(0.1));
+        assert_eq!(info.precip_probability_pct(), Some(10));
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     #[tokio::test]
     async fn test_mock_provider_handles_date() {
-        let provider = MockProvider;
+        let provider = MockProvider::default();
         let specific_date = "10/5/2023";
 
-        let result = provider
-            .get_weather(None, "Nowhere", Some(specific_date))
-            .await;
+        let result = provider.get_history(None, "Nowhere", specific_date).await;
 
         assert!(result.is_ok());
         let info = result.unwrap();
@@ -58,18 +305,190 @@ mod tests {
         assert_eq!(info.date, "2023-10-05");
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_mock_provider_hourly() {
+        let hours = MockProvider::default()
+            .get_hourly(None, "Nowhere", Some("2024-06-21"))
+            .await
+            .unwrap();
+
+        assert_eq!(hours.len(), 24);
+        assert_eq!(hours[0].time.to_rfc3339(), "2024-06-21T00:00:00+00:00");
+        assert_eq!(hours[23].time.to_rfc3339(), "2024-06-21T23:00:00+00:00");
+        let temperatures = [0, 5, 6, 11, 12, 17, 18, 23].map(|hour| hours[hour].temperature);
+        assert_eq!(
+            temperatures,
+            [11.0, 11.0, 14.0, 14.0, 20.0, 20.0, 17.0, 17.0]
+        );
+        assert!(hours.iter().all(|h| h.humidity == 50));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_mock_provider_geocode() {
+        let location = MockProvider::default()
+            .geocode(None, "Nowhere")
+            .await
+            .unwrap();
+
+        assert_eq!(location.city, "Mock City");
+        assert_eq!(location.country, "Mock Country");
+        assert!((-90.0..=90.0).contains(&location.lat));
+        assert!((-180.0..=180.0).contains(&location.lon));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_mock_provider_health_check() {
+        let latency = MockProvider::default().health_check(None).await.unwrap();
+        assert_eq!(latency, Duration::ZERO);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
     #[tokio::test]
     async fn test_mock_provider_defaults_to_today() {
-        use ::chrono::Utc;
+        use crate::{CacheStatus, utils::clock::FixedClock};
+        use ::chrono::{TimeZone, Utc};
 
-        let provider = MockProvider;
-        let result = provider.get_weather(None, "Nowhere", None).await;
+        let now = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock(now));
+        let provider = MockProvider::with_clock(clock.clone());
+        let result = provider.get_weather(None, "Nowhere").await;
 
         assert!(result.is_ok());
         let info = result.unwrap();
 
-        // Check that the date is today's date
-        let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+        // Check that the date is today's date in the local timezone
+        let today = clock.today_in(None).format("%Y-%m-%d").to_string();
         assert_eq!(info.date, today);
+        assert_eq!(info.observation_time, Some(now));
+
+        let meta = info.meta.unwrap();
+        assert_eq!(meta.provider, "mock");
+        assert_eq!(meta.endpoint, "mock");
+        assert_eq!(meta.source, DataSource::Observed);
+        assert_eq!(meta.fetched_at, now);
+        assert_eq!(meta.cache, CacheStatus::Miss);
+        assert!(meta.derived.is_empty());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn failing(fail_rate: f64, fail_kind: FailKind, seed: u64) -> MockProvider {
+        MockProvider::default().with_faults(Some(MockFaults {
+            fail_rate,
+            fail_kind,
+            seed,
+            ..MockFaults::default()
+        }))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn outcomes(provider: &MockProvider, requests: usize) -> Vec<bool> {
+        let mut outcomes = Vec::with_capacity(requests);
+        for _ in 0..requests {
+            outcomes.push(provider.get_weather(None, "Nowhere").await.is_ok());
+        }
+        outcomes
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_faults_are_deterministic() {
+        let first = outcomes(&failing(0.2, FailKind::ServerError, 42), 100).await;
+        let second = outcomes(&failing(0.2, FailKind::ServerError, 42), 100).await;
+        let other_seed = outcomes(&failing(0.2, FailKind::ServerError, 7), 100).await;
+
+        assert_eq!(first, second);
+        assert_ne!(first, other_seed);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_fail_rate() {
+        let failures = |outcomes: Vec<bool>| outcomes.iter().filter(|ok| !**ok).count();
+
+        let injected = failures(outcomes(&failing(0.2, FailKind::ServerError, 42), 1000).await);
+        assert!((150..=250).contains(&injected), "{injected}");
+
+        assert_eq!(
+            failures(outcomes(&failing(0.0, FailKind::ServerError, 42), 100).await),
+            0
+        );
+        assert_eq!(
+            failures(outcomes(&failing(1.0, FailKind::ServerError, 42), 100).await),
+            100
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_fail_on() {
+        let provider = MockProvider::default().with_faults(Some(MockFaults {
+            fail_kind: FailKind::NotFound,
+            fail_on: vec!["Paris".to_string()],
+            ..MockFaults::default()
+        }));
+
+        assert!(provider.get_weather(None, "London").await.is_ok());
+        assert!(matches!(
+            provider.get_weather(None, "paris").await,
+            Err(Error::LocationNotFound(_))
+        ));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_fail_kinds() {
+        let error = |kind| async move {
+            failing(1.0, kind, 0)
+                .get_weather(None, "Nowhere")
+                .await
+                .unwrap_err()
+        };
+
+        assert!(matches!(
+            error(FailKind::NotFound).await,
+            Error::LocationNotFound(_)
+        ));
+        assert!(
+            error(FailKind::ServerError)
+                .await
+                .to_string()
+                .contains("server error")
+        );
+
+        let provider = failing(1.0, FailKind::Timeout, 0);
+        let request = provider.get_weather(None, "Nowhere");
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), request)
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_latency() {
+        let provider = MockProvider::default().with_faults(Some(MockFaults {
+            latency: Duration::from_millis(50),
+            ..MockFaults::default()
+        }));
+        let started = std::time::Instant::now();
+
+        assert!(provider.get_weather(None, "Nowhere").await.is_ok());
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_unconfigured_provider_has_no_injector() {
+        let options = ProviderOptions {
+            mock_faults: None,
+            ..ProviderOptions::default()
+        };
+
+        // The environment variable is not set in tests
+        assert!(MockProvider::with_options(&options).faults.is_none());
+        assert!(MockProvider::default().faults.is_none());
     }
 }
@@ -1,9 +1,36 @@
+mod aemet;
+mod ambient_weather;
+mod bom;
+mod climate_serv;
+mod dmi;
+mod eccc;
+mod era5;
+mod fmi;
+mod foreca;
 mod grpc_mock;
+mod historic_pirate_weather;
+mod meteo_swiss;
 mod mock;
+mod open_meteo;
+mod open_meteo_air_quality;
 mod open_weather;
+mod openaq;
+pub(crate) mod purple_air;
+mod sunrise_sunset;
+mod synoptic;
+mod tempest;
 mod weather_api;
+mod windy;
+mod world_weather_online;
 
 pub use self::{
-    grpc_mock::GrpcMockProvider, mock::MockProvider, open_weather::OpenWeatherProvider,
-    weather_api::WeatherApiProvider,
+    aemet::AemetProvider, ambient_weather::AmbientWeatherProvider, bom::BomProvider,
+    climate_serv::ClimateServProvider, dmi::DmiProvider, eccc::EcccProvider, era5::Era5Provider,
+    fmi::FmiProvider, foreca::ForecaProvider, grpc_mock::GrpcMockProvider,
+    historic_pirate_weather::HistoricPirateWeatherProvider, meteo_swiss::MeteoSwissProvider,
+    mock::MockProvider, open_meteo::OpenMeteoGeocoder, open_meteo_air_quality::OpenMeteoAqProvider,
+    open_weather::OpenWeatherProvider, openaq::OpenAqProvider, purple_air::PurpleAirProvider,
+    sunrise_sunset::SunriseSunsetProvider, synoptic::SynopticProvider, tempest::TempestProvider,
+    weather_api::WeatherApiProvider, windy::WindyProvider,
+    world_weather_online::WorldWeatherOnlineProvider,
 };
@@ -0,0 +1,102 @@
+use crate::{
+    GeocodingClient,
+    common::*,
+    models::{GeoLocation, ProviderOptions, open_meteo::*},
+    utils::http::{HttpClient, check_status, parse_json},
+};
+use ::async_trait::async_trait;
+use ::reqwest::Url;
+use ::tracing::instrument;
+
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+
+/// The free, key-less Open-Meteo geocoding API.
+///
+/// Used by providers whose own API only accepts coordinates.
+#[derive(Debug, Default)]
+pub struct OpenMeteoGeocoder {
+    http: HttpClient,
+}
+
+impl OpenMeteoGeocoder {
+    /// Creates a geocoder with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for OpenMeteoGeocoder {
+    #[instrument(skip(self, _provider_key))]
+    async fn geocode(&self, _provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        let url = Url::parse_with_params(
+            GEOCODING_URL,
+            &[("name", address), ("count", "1"), ("format", "json")],
+        )
+        .map_err(|e| format!("Failed to build URL: {e}"))?;
+
+        let response = check_status(
+            "OpenMeteo",
+            self.http.get("OpenMeteo", "search", url).await?,
+        )
+        .await?;
+        let body = parse_json::<OpenMeteoGeoResponse>("OpenMeteo", response).await?;
+
+        to_geo_location(body).ok_or_else(|| format!("Location not found: '{address}'").into())
+    }
+}
+
+fn to_geo_location(body: OpenMeteoGeoResponse) -> Option<GeoLocation> {
+    let location = body.results.into_iter().next()?;
+
+    Some(GeoLocation {
+        city: location.name,
+        region: location.admin1,
+        country: location.country.unwrap_or_default(),
+        lat: location.latitude,
+        lon: location.longitude,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_geo_location() {
+        let body = serde_json::from_str::<OpenMeteoGeoResponse>(
+            r#"{
+                "results": [{
+                    "id": 2950159,
+                    "name": "Berlin",
+                    "latitude": 52.52437,
+                    "longitude": 13.41053,
+                    "country_code": "DE",
+                    "country": "Germany",
+                    "admin1": "Land Berlin"
+                }],
+                "generationtime_ms": 0.5
+            }"#,
+        )
+        .unwrap();
+
+        let location = to_geo_location(body).unwrap();
+
+        assert_eq!(location.city, "Berlin");
+        assert_eq!(location.region.as_deref(), Some("Land Berlin"));
+        assert_eq!(location.country, "Germany");
+        assert_eq!(location.lat, 52.52437);
+        assert_eq!(location.lon, 13.41053);
+    }
+
+    #[test]
+    fn test_no_results() {
+        let body =
+            serde_json::from_str::<OpenMeteoGeoResponse>(r#"{"generationtime_ms": 0.5}"#).unwrap();
+
+        assert!(to_geo_location(body).is_none());
+    }
+}
@@ -0,0 +1,309 @@
+use crate::{
+    ForecastProvider, GeocodingClient, HistoricalProvider, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta,
+        open_meteo::*,
+    },
+    providers::OpenMeteoGeocoder,
+    utils::{
+        aqi::pm25_aqi,
+        date::normalize_date,
+        http::{HttpClient, check_status, parse_json},
+    },
+};
+use ::async_trait::async_trait;
+use ::reqwest::Url;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const AIR_QUALITY_URL: &str = "https://air-quality-api.open-meteo.com/v1/air-quality";
+
+/// The upper bounds of the European Air Quality Index bands, with their names.
+const EUROPEAN_AQI_BANDS: [(f32, &str); 5] = [
+    (20.0, "Good"),
+    (40.0, "Fair"),
+    (60.0, "Moderate"),
+    (80.0, "Poor"),
+    (100.0, "Very poor"),
+];
+
+/// Air quality from the free, key-less Open-Meteo Air Quality API, without weather.
+///
+/// Addresses are resolved with Open-Meteo geocoding. The first hour of the date (local
+/// midnight) is reported: the PM2.5 concentration, its US EPA Air Quality Index, and the band
+/// of the European Air Quality Index as the description. The highest UV indexes of the day,
+/// with and without clouds, are reported too. The temperature is `NaN`, as no weather is
+/// observed.
+#[derive(Debug, Default)]
+pub struct OpenMeteoAqProvider {
+    http: HttpClient,
+    geocoder: OpenMeteoGeocoder,
+}
+
+impl OpenMeteoAqProvider {
+    /// Creates a provider with the connection settings of `options`, also used for geocoding.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+            geocoder: OpenMeteoGeocoder::with_options(options),
+        }
+    }
+
+    /// Returns the weather of `address` on `date` (today if `None`).
+    #[instrument(skip(self, _provider_key))]
+    async fn weather_on(
+        &self,
+        _provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<WeatherInfo> {
+        let location = self.geocode(None, address).await?;
+        let date = normalize_date(date);
+
+        let url = air_quality_url(location.lat, location.lon, &date)?;
+        let response = check_status(
+            "OpenMeteoAirQuality",
+            self.http
+                .get("OpenMeteoAirQuality", "air-quality", url)
+                .await?,
+        )
+        .await?;
+        let body =
+            parse_json::<OpenMeteoAirQualityResponse>("OpenMeteoAirQuality", response).await?;
+
+        Ok(to_weather_info(location, date, body))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for OpenMeteoAqProvider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
+
+    #[instrument(skip(self, _provider_key))]
+    async fn health_check(&self, _provider_key: Option<&str>) -> Result<Duration> {
+        let url = air_quality_url(51.5074, -0.1278, &normalize_date(None::<&str>))?;
+
+        self.http
+            .probe("OpenMeteoAirQuality", "air-quality", url)
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://open-meteo.com/en/docs/air-quality-api",
+            key_format: "Not required",
+            free_tier: "Free for non-commercial use, up to 10,000 calls per day",
+            capabilities: &["current", "history", "forecast", "geocoding"],
+            example: "weather get Berlin --provider omaq",
+        }
+    }
+
+    fn as_forecast(&self) -> Option<&dyn ForecastProvider> {
+        Some(self)
+    }
+
+    fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ForecastProvider for OpenMeteoAqProvider {
+    async fn get_forecast(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HistoricalProvider for OpenMeteoAqProvider {
+    async fn get_history(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for OpenMeteoAqProvider {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        self.geocoder.geocode(provider_key, address).await
+    }
+}
+
+/// Builds the URL of the hourly PM2.5, European AQI and UV indexes of `date` at the
+/// coordinates.
+fn air_quality_url(lat: f64, lon: f64, date: &str) -> Result<Url> {
+    Ok(Url::parse_with_params(
+        AIR_QUALITY_URL,
+        &[
+            ("latitude", lat.to_string().as_str()),
+            ("longitude", lon.to_string().as_str()),
+            ("hourly", "pm2_5,european_aqi,uv_index,uv_index_clear_sky"),
+            ("start_date", date),
+            ("end_date", date),
+            ("timezone", "auto"),
+        ],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+/// Returns the name of the European Air Quality Index band of `index`, e.g. "Fair".
+fn european_aqi_band(index: f32) -> &'static str {
+    EUROPEAN_AQI_BANDS
+        .iter()
+        .find(|(upper, _)| index <= *upper)
+        .map_or("Extremely poor", |(_, name)| name)
+}
+
+/// Returns the highest of the hourly values, or `None` if none was reported.
+fn daily_max(hourly: &[Option<f32>]) -> Option<f32> {
+    hourly.iter().flatten().copied().reduce(f32::max)
+}
+
+fn to_weather_info(
+    location: GeoLocation,
+    date: String,
+    body: OpenMeteoAirQualityResponse,
+) -> WeatherInfo {
+    let source = DataSource::for_date(&date);
+    let hourly = body.hourly;
+    let pm25 = hourly.pm2_5.first().copied().flatten();
+    let european_aqi = hourly.european_aqi.first().copied().flatten();
+
+    let mut info = WeatherInfo {
+        country: location.country,
+        city: location.city,
+        date,
+        temperature: f32::NAN,
+        description: european_aqi.map(|index| {
+            format!(
+                "{} air quality (European AQI {index:.0})",
+                european_aqi_band(index)
+            )
+        }),
+        air_quality_index: pm25.and_then(pm25_aqi),
+        pm25,
+        uv_index_max: daily_max(&hourly.uv_index),
+        uv_index_clear_sky: daily_max(&hourly.uv_index_clear_sky),
+        meta: Some(WeatherMeta::new(
+            Provider::OpenMeteoAq,
+            "air-quality",
+            source,
+        )),
+        ..Default::default()
+    };
+    // The US AQI is computed from the PM2.5 concentration
+    if info.air_quality_index.is_some() {
+        info.mark_derived("air_quality_index");
+    }
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> OpenMeteoAirQualityResponse {
+        serde_json::from_str(include_str!(
+            "../../tests/fixtures/open_meteo_air_quality.json"
+        ))
+        .unwrap()
+    }
+
+    fn london() -> GeoLocation {
+        GeoLocation {
+            city: "London".to_string(),
+            country: "United Kingdom".to_string(),
+            lat: 51.5074,
+            lon: -0.1278,
+            ..GeoLocation::default()
+        }
+    }
+
+    #[test]
+    fn test_fixture_deserializes() {
+        let hourly = fixture().hourly;
+
+        assert_eq!(hourly.pm2_5.len(), 24);
+        assert_eq!(hourly.pm2_5[0], Some(8.4));
+        assert_eq!(hourly.european_aqi[0], Some(32.0));
+    }
+
+    #[test]
+    fn test_air_quality_url() {
+        let url = air_quality_url(51.5074, -0.1278, "2024-01-15").unwrap();
+
+        assert_eq!(url.host_str(), Some("air-quality-api.open-meteo.com"));
+        assert_eq!(
+            url.query(),
+            Some(
+                "latitude=51.5074&longitude=-0.1278\
+                 &hourly=pm2_5%2Ceuropean_aqi%2Cuv_index%2Cuv_index_clear_sky\
+                 &start_date=2024-01-15&end_date=2024-01-15&timezone=auto"
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let info = to_weather_info(london(), "2024-01-15".to_string(), fixture());
+
+        assert_eq!(info.city, "London");
+        assert_eq!(info.date, "2024-01-15");
+        assert!(info.temperature.is_nan());
+        assert_eq!(info.pm25, Some(8.4));
+        assert_eq!(info.air_quality_index, Some(47));
+        assert_eq!(info.uv_index_max, Some(0.8));
+        assert_eq!(info.uv_index_clear_sky, Some(1.05));
+        assert!(!info.sun_protection_needed());
+        assert_eq!(
+            info.description.as_deref(),
+            Some("Fair air quality (European AQI 32)")
+        );
+    }
+
+    #[test]
+    fn test_missing_values() {
+        let body = serde_json::from_str(r#"{"hourly": {"pm2_5": [null], "european_aqi": [null]}}"#)
+            .unwrap();
+        let info = to_weather_info(london(), "2024-01-15".to_string(), body);
+
+        assert_eq!(info.pm25, None);
+        assert_eq!(info.air_quality_index, None);
+        assert_eq!(info.description, None);
+        assert_eq!(info.uv_index_max, None);
+        assert_eq!(info.uv_index_clear_sky, None);
+
+        let body = serde_json::from_str(r#"{"hourly": {}}"#).unwrap();
+        assert_eq!(
+            to_weather_info(london(), "2024-01-15".to_string(), body).pm25,
+            None
+        );
+    }
+
+    #[test]
+    fn test_european_aqi_band() {
+        assert_eq!(european_aqi_band(0.0), "Good");
+        assert_eq!(european_aqi_band(20.0), "Good");
+        assert_eq!(european_aqi_band(20.5), "Fair");
+        assert_eq!(european_aqi_band(75.0), "Poor");
+        assert_eq!(european_aqi_band(100.0), "Very poor");
+        assert_eq!(european_aqi_band(140.0), "Extremely poor");
+    }
+}
@@ -1,67 +1,1138 @@
 use crate::{
-    WeatherProvider,
+    AlertsProvider, ForecastProvider, GeocodingClient, HistoricalProvider, HourlyProvider,
+    Provider, WeatherProvider,
     common::*,
-    models::{WeatherInfo, open_weather::*},
-    utils::date::*,
+    models::{
+        DataSource, DayParts, GeoLocation, HourlyWeather, Icon, OpenWeatherEndpoint, PrecipType,
+        ProviderInfo, ProviderOptions, Unit, WeatherAlert, WeatherInfo, WeatherMeta,
+        open_weather::*,
+    },
+    utils::{
+        date::*,
+        http::{HttpClient, check_status_with, parse_json},
+    },
 };
+use ::chrono::{DateTime, FixedOffset, NaiveDate};
 use ::reqwest::Url;
-use ::tracing::instrument;
+use ::std::time::Duration;
+use ::tracing::{debug, instrument};
 
-#[derive(Debug)]
-pub struct OpenWeatherProvider;
+const GEOCODING_URL: &str = "https://api.openweathermap.org/geo/1.0/direct";
+const DAY_SUMMARY_URL: &str = "https://api.openweathermap.org/data/3.0/onecall/day_summary";
+const ONE_CALL_URL: &str = "https://api.openweathermap.org/data/3.0/onecall";
 
-#[async_trait::async_trait]
-impl WeatherProvider for OpenWeatherProvider {
+#[derive(Debug, Default)]
+pub struct OpenWeatherProvider {
+    endpoint: OpenWeatherEndpoint,
+    http: HttpClient,
+}
+
+impl OpenWeatherProvider {
+    /// Creates a provider requesting weather from the configured endpoint, with the connection
+    /// settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            endpoint: options.open_weather_endpoint,
+            http: HttpClient::new(options),
+        }
+    }
+
+    /// Returns the weather of `address` on `date` (today if `None`).
     #[instrument(fields(provider_key, address, date))]
-    async fn get_weather(
+    async fn weather_on(
         &self,
         provider_key: Option<&str>,
         address: &str,
         date: Option<&str>,
     ) -> Result<WeatherInfo> {
-        let provider_key = provider_key.ok_or_else(|| {
-            Error::from("'OpenWeather' API key not set. Please set it using: 'weather provider ow --key <API_KEY>'")
-        })?;
+        let provider_key = require_key(provider_key)?;
 
         // --- Geocoding API ---
-        let geo_url = Url::parse_with_params(
-            "https://api.openweathermap.org/geo/1.0/direct",
-            &[("appid", provider_key), ("q", address), ("limit", "1")],
+        let location = self.geocode(Some(provider_key), address).await?;
+
+        // --- Weather API ---
+        let date = normalize_date(date);
+
+        match self.endpoint {
+            OpenWeatherEndpoint::DaySummary => {
+                let url = OpenWeatherRequests::day_summary(
+                    provider_key,
+                    location.lat,
+                    location.lon,
+                    &date,
+                    Unit::Fahrenheit,
+                )?;
+
+                let response = check_status_with(
+                    "OpenWeather",
+                    self.http.get("OpenWeather", "day_summary", url).await?,
+                    decode_error,
+                )
+                .await?;
+                let body = parse_json::<OpenWeatherResponse>("OpenWeather", response).await?;
+
+                Ok(to_weather_info(location, date, body).with_derived_precip_type())
+            }
+            OpenWeatherEndpoint::OneCall => {
+                let body = self.one_call(provider_key, &location).await?;
+
+                one_call_to_weather_info(location, date, body)
+                    .map(WeatherInfo::with_derived_precip_type)
+            }
+        }
+    }
+
+    /// Requests the current weather and the daily forecast of `location` from One Call.
+    async fn one_call(
+        &self,
+        provider_key: &str,
+        location: &GeoLocation,
+    ) -> Result<OpenWeatherOneCallResponse> {
+        let url = OpenWeatherRequests::one_call(
+            provider_key,
+            location.lat,
+            location.lon,
+            Unit::Fahrenheit,
+        )?;
+
+        let response = check_status_with(
+            "OpenWeather",
+            self.http.get("OpenWeather", "onecall", url).await?,
+            decode_error,
         )
-        .map_err(|e| format!("Failed to build URL: {e}"))?;
+        .await?;
 
-        let geo_response = reqwest::get(geo_url).await?.error_for_status()?;
-        let geo_body = geo_response.json::<Vec<OpenWeatherGeoResponse>>().await?;
+        parse_json::<OpenWeatherOneCallResponse>("OpenWeather", response).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl WeatherProvider for OpenWeatherProvider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
 
-        let location = geo_body
-            .first()
-            .ok_or_else(|| format!("Location not found: '{address}'"))?;
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let url = OpenWeatherRequests::geocode(require_key(provider_key)?, "London")?;
 
-        // --- Weather API ---
-        let date = normalize_date(date);
+        self.http.probe("OpenWeather", "direct", url).await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://home.openweathermap.org/users/sign_up",
+            key_format: "32-character hexadecimal API key",
+            free_tier: "Requires a One Call API 3.0 subscription (\"One Call by Call\"); \
+                        the first 1,000 calls per day are free",
+            capabilities: &["current", "history", "forecast", "geocoding"],
+            example: "weather provider ow --key <API_KEY>",
+        }
+    }
+
+    fn as_forecast(&self) -> Option<&dyn ForecastProvider> {
+        Some(self)
+    }
+
+    fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+        Some(self)
+    }
+
+    fn as_alerts(&self) -> Option<&dyn AlertsProvider> {
+        // The alerts come from the One Call endpoint, whichever endpoint is configured
+        Some(self)
+    }
+
+    fn as_hourly(&self) -> Option<&dyn HourlyProvider> {
+        // The hours come from the One Call endpoint, whichever endpoint is configured
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl ForecastProvider for OpenWeatherProvider {
+    async fn get_forecast(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn get_forecast_days(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        dates: &[String],
+    ) -> Result<Vec<WeatherInfo>> {
+        if self.endpoint == OpenWeatherEndpoint::DaySummary {
+            return ::futures::future::try_join_all(
+                dates
+                    .iter()
+                    .map(|date| self.weather_on(provider_key, address, Some(date))),
+            )
+            .await;
+        }
+
+        // One Call reports the next 7 days at once, so every date is sliced from one response
+        let provider_key = require_key(provider_key)?;
+        let location = self.geocode(Some(provider_key), address).await?;
+        let body = self.one_call(provider_key, &location).await?;
+
+        one_call_days(location, dates, body)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl HistoricalProvider for OpenWeatherProvider {
+    async fn get_history(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl AlertsProvider for OpenWeatherProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn get_alerts(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+    ) -> Result<Vec<WeatherAlert>> {
+        let provider_key = require_key(provider_key)?;
+        let location = self.geocode(Some(provider_key), address).await?;
+        let url = OpenWeatherRequests::one_call_alerts(provider_key, location.lat, location.lon)?;
+
+        let response = check_status_with(
+            "OpenWeather",
+            self.http.get("OpenWeather", "onecall", url).await?,
+            decode_error,
+        )
+        .await?;
+        let body = parse_json::<OpenWeatherOneCallResponse>("OpenWeather", response).await?;
+
+        Ok(alerts_on(body.alerts, |_, _| true))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl HourlyProvider for OpenWeatherProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn get_hourly(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<Vec<HourlyWeather>> {
+        let provider_key = require_key(provider_key)?;
+        let location = self.geocode(Some(provider_key), address).await?;
+        let url = OpenWeatherRequests::one_call_hourly(
+            provider_key,
+            location.lat,
+            location.lon,
+            Unit::Fahrenheit,
+        )?;
+
+        let response = check_status_with(
+            "OpenWeather",
+            self.http.get("OpenWeather", "onecall", url).await?,
+            decode_error,
+        )
+        .await?;
+        let body = parse_json::<OpenWeatherOneCallResponse>("OpenWeather", response).await?;
+
+        hours_on(&normalize_date(date), body)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl GeocodingClient for OpenWeatherProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        let url = OpenWeatherRequests::geocode(require_key(provider_key)?, address)?;
+
+        let locations = fetch_locations(&self.http, url)
+            .await
+            .map_err(|e| match e {
+                Error::InvalidApiKey(_) | Error::OfflineMode(_) => e,
+                e => Error::GeocodingFailed {
+                    provider: "OpenWeather".into(),
+                    query: address.to_string(),
+                    source: Box::new(e),
+                },
+            })?;
 
-        let url = Url::parse_with_params(
-            "https://api.openweathermap.org/data/3.0/onecall/day_summary",
+        select_location(address, locations)
+    }
+}
+
+/// Builds the URLs of the OpenWeather requests.
+struct OpenWeatherRequests;
+
+impl OpenWeatherRequests {
+    /// The URL of the first location matching `query`.
+    fn geocode(api_key: &str, query: &str) -> Result<Url> {
+        build_url(
+            GEOCODING_URL,
+            &[("appid", api_key), ("q", query), ("limit", "1")],
+        )
+    }
+
+    /// The URL of the aggregated weather of `date` at the coordinates, in `units`.
+    fn day_summary(api_key: &str, lat: f64, lon: f64, date: &str, units: Unit) -> Result<Url> {
+        build_url(
+            DAY_SUMMARY_URL,
             &[
-                ("appid", provider_key),
-                ("lat", &location.lat.to_string()),
-                ("lon", &location.lon.to_string()),
-                ("date", &date),
-                ("units", "imperial"),
+                ("appid", api_key),
+                ("lat", &lat.to_string()),
+                ("lon", &lon.to_string()),
+                ("date", date),
+                ("units", units_param(units)),
             ],
         )
-        .map_err(|e| format!("Failed to build URL: {e}"))?;
+    }
 
-        let response = reqwest::get(url).await?.error_for_status()?;
-        let body = response.json::<OpenWeatherResponse>().await?;
+    /// The URL of the current conditions, daily forecast and alerts at the coordinates, in
+    /// `units`.
+    fn one_call(api_key: &str, lat: f64, lon: f64, units: Unit) -> Result<Url> {
+        build_url(
+            ONE_CALL_URL,
+            &[
+                ("appid", api_key),
+                ("lat", &lat.to_string()),
+                ("lon", &lon.to_string()),
+                ("exclude", "minutely,hourly"),
+                ("units", units_param(units)),
+            ],
+        )
+    }
 
-        Ok(WeatherInfo {
-            country: location.country.clone(),
-            city: location.name.clone(),
+    /// The URL of the alerts in effect or announced at the coordinates.
+    fn one_call_alerts(api_key: &str, lat: f64, lon: f64) -> Result<Url> {
+        build_url(
+            ONE_CALL_URL,
+            &[
+                ("appid", api_key),
+                ("lat", &lat.to_string()),
+                ("lon", &lon.to_string()),
+                ("exclude", "current,minutely,hourly,daily"),
+            ],
+        )
+    }
+
+    /// The URL of the hourly forecast of the next 48 hours at the coordinates, in `units`.
+    fn one_call_hourly(api_key: &str, lat: f64, lon: f64, units: Unit) -> Result<Url> {
+        build_url(
+            ONE_CALL_URL,
+            &[
+                ("appid", api_key),
+                ("lat", &lat.to_string()),
+                ("lon", &lon.to_string()),
+                ("exclude", "current,minutely,daily,alerts"),
+                ("units", units_param(units)),
+            ],
+        )
+    }
+}
+
+fn build_url(base: &str, params: &[(&str, &str)]) -> Result<Url> {
+    Ok(Url::parse_with_params(base, params).map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+/// Returns the `units` parameter giving temperatures in `unit`. Wind speeds follow: mph for
+/// imperial, m/s for metric.
+fn units_param(unit: Unit) -> &'static str {
+    match unit {
+        Unit::Fahrenheit => "imperial",
+        Unit::Celsius => "metric",
+    }
+}
+
+fn to_weather_info(location: GeoLocation, date: String, body: OpenWeatherResponse) -> WeatherInfo {
+    let source = DataSource::for_date(&date);
+    let snow_depth_mm = body.snow.map(|s| s.one_hour);
+    let precip_type = body
+        .precipitation_type
+        .as_deref()
+        .and_then(PrecipType::from_name)
+        .or_else(|| {
+            snow_depth_mm
+                .filter(|&mm| mm > 0.0)
+                .map(|_| PrecipType::Snow)
+        });
+
+    WeatherInfo {
+        country: location.country,
+        city: location.city,
+        date,
+        temperature: body.temperature.afternoon,
+        humidity: Some(body.humidity.afternoon),
+        wind_speed_mph: body.wind.map(|w| w.max.speed),
+        precipitation_mm: body.precipitation.map(|p| p.total),
+        pressure_hpa: body.pressure.map(|p| p.afternoon),
+        snow_depth_mm,
+        precip_type,
+        periods: day_parts(&body.temperature),
+        meta: Some(WeatherMeta::new(
+            Provider::OpenWeather,
+            "day_summary",
+            source,
+        )),
+        ..Default::default()
+    }
+}
+
+/// Returns the parts of the day, or `None` unless all four are reported.
+fn day_parts(temperature: &OpenWeatherTemperature) -> Option<DayParts> {
+    Some(DayParts {
+        morning: temperature.morning?,
+        afternoon: temperature.afternoon,
+        evening: temperature.evening?,
+        night: temperature.night?,
+        low: temperature.min,
+        high: temperature.max,
+    })
+}
+
+/// Maps a One Call response to the weather of `date`.
+///
+/// The current conditions are used if `date` is today at the location; otherwise the daily
+/// entry falling on `date` in the location's local time is used.
+///
+/// # Errors
+///
+/// Returns an error if `date` is invalid or outside the range covered by the response.
+fn one_call_to_weather_info(
+    location: GeoLocation,
+    date: String,
+    body: OpenWeatherOneCallResponse,
+) -> Result<WeatherInfo> {
+    let requested =
+        NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| format!("Invalid date: {e}"))?;
+    let offset = body.timezone_offset;
+    let local_date = |dt: i64| DateTime::from_timestamp(dt + offset, 0).map(|t| t.date_naive());
+    let alerts = alerts_on(body.alerts, |start, end| {
+        local_date(start) <= Some(requested) && local_date(end) >= Some(requested)
+    });
+
+    let day = body
+        .daily
+        .into_iter()
+        .find(|d| local_date(d.dt) == Some(requested));
+    let moon_phase = day.as_ref().and_then(|d| d.moon_phase);
+    let uv_index_max = day.as_ref().and_then(|d| d.uvi);
+    let precip_probability = day.as_ref().and_then(|d| d.pop);
+
+    if let Some(current) = body.current.filter(|c| local_date(c.dt) == Some(requested)) {
+        let rain = current.rain.map(|r| r.one_hour);
+        let snow = current.snow.map(|s| s.one_hour);
+        let condition = current.weather.into_iter().next();
+
+        return Ok(WeatherInfo {
+            country: location.country,
+            city: location.city,
             date,
-            temperature: body.temperature.afternoon,
-            humidity: body.humidity.afternoon,
-            description: None,
+            observation_time: DateTime::from_timestamp(current.dt, 0),
+            temperature: current.temp,
+            humidity: Some(current.humidity),
+            description: condition.as_ref().map(|c| c.description.clone()),
+            condition_code: condition.as_ref().and_then(|c| c.id),
+            wind_speed_mph: current.wind_speed,
+            precipitation_mm: total_precipitation(rain, snow),
+            feels_like: Some(current.feels_like),
+            pressure_hpa: current.pressure,
+            sunrise: current.sunrise.and_then(to_rfc3339),
+            sunset: current.sunset.and_then(to_rfc3339),
+            snow_depth_mm: snow,
+            precip_type: Some(classify_precipitation(rain, snow)),
+            moon_phase,
+            uv_index_max,
+            icon: open_weather_icon(condition.as_ref()),
+            provider_icon: condition.and_then(|c| c.icon),
+            alerts,
+            meta: Some(WeatherMeta::new(
+                Provider::OpenWeather,
+                "onecall",
+                DataSource::Observed,
+            )),
+            ..Default::default()
+        }
+        .with_precip_probability(precip_probability));
+    }
+
+    let day = day.ok_or_else(|| {
+        Error::from(format!(
+            "OpenWeather One Call has no data for {date}: only today and the next 7 days are available"
+        ))
+    })?;
+    let condition = day.weather.into_iter().next();
+
+    Ok(WeatherInfo {
+        country: location.country,
+        city: location.city,
+        date,
+        temperature: day.temp.day,
+        humidity: Some(day.humidity),
+        description: condition.as_ref().map(|c| c.description.clone()),
+        condition_code: condition.as_ref().and_then(|c| c.id),
+        wind_speed_mph: day.wind_speed,
+        precipitation_mm: total_precipitation(day.rain, day.snow),
+        feels_like: Some(day.feels_like.day),
+        pressure_hpa: day.pressure,
+        sunrise: day.sunrise.and_then(to_rfc3339),
+        sunset: day.sunset.and_then(to_rfc3339),
+        snow_depth_mm: day.snow,
+        precip_type: Some(classify_precipitation(day.rain, day.snow)),
+        moon_phase,
+        uv_index_max,
+        icon: open_weather_icon(condition.as_ref()),
+        provider_icon: condition.and_then(|c| c.icon),
+        alerts,
+        meta: Some(WeatherMeta::new(
+            Provider::OpenWeather,
+            "onecall",
+            DataSource::Forecast,
+        )),
+        ..Default::default()
+    }
+    .with_precip_probability(precip_probability))
+}
+
+/// Maps the weather of each of `dates` from one One Call response.
+fn one_call_days(
+    location: GeoLocation,
+    dates: &[String],
+    body: OpenWeatherOneCallResponse,
+) -> Result<Vec<WeatherInfo>> {
+    dates
+        .iter()
+        .map(|date| {
+            one_call_to_weather_info(location.clone(), normalize_date(Some(date)), body.clone())
+                .map(WeatherInfo::with_derived_precip_type)
+        })
+        .collect()
+}
+
+/// Maps the alerts for which `overlaps(start, end)` holds, dropping alerts with invalid times.
+fn alerts_on(
+    alerts: Vec<OpenWeatherOneCallAlert>,
+    overlaps: impl Fn(i64, i64) -> bool,
+) -> Vec<WeatherAlert> {
+    alerts
+        .into_iter()
+        .filter(|alert| overlaps(alert.start, alert.end))
+        .filter_map(|alert| {
+            debug!("{}: {}", alert.sender_name, alert.event);
+            Some(WeatherAlert {
+                start: DateTime::from_timestamp(alert.start, 0)?.naive_utc(),
+                end: DateTime::from_timestamp(alert.end, 0)?.naive_utc(),
+                event: alert.event,
+                description: alert.description,
+                sender: alert.sender_name,
+            })
+        })
+        .collect()
+}
+
+/// Maps the icon code of a condition with `Icon::from_open_weather`.
+fn open_weather_icon(condition: Option<&OpenWeatherCondition>) -> Option<String> {
+    condition
+        .and_then(|c| c.icon.as_deref())
+        .and_then(Icon::from_open_weather)
+        .map(|icon| icon.code().to_string())
+}
+
+/// Sums rain and snow; `None` if neither is reported.
+fn total_precipitation(rain: Option<f32>, snow: Option<f32>) -> Option<f32> {
+    match (rain, snow) {
+        (None, None) => None,
+        (rain, snow) => Some(rain.unwrap_or(0.0) + snow.unwrap_or(0.0)),
+    }
+}
+
+/// One Call omits `rain` and `snow` when there is none, so missing amounts mean no precipitation.
+fn classify_precipitation(rain: Option<f32>, snow: Option<f32>) -> PrecipType {
+    let rain = rain.is_some_and(|mm| mm > 0.0);
+    let snow = snow.is_some_and(|mm| mm > 0.0);
+
+    match (rain, snow) {
+        (true, true) => PrecipType::Mixed,
+        (false, true) => PrecipType::Snow,
+        (true, false) => PrecipType::Rain,
+        (false, false) => PrecipType::None,
+    }
+}
+
+/// Returns the hours of the local `date`, in the local time of the location.
+fn hours_on(date: &str, body: OpenWeatherOneCallResponse) -> Result<Vec<HourlyWeather>> {
+    let requested =
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| format!("Invalid date: {e}"))?;
+    let offset = i32::try_from(body.timezone_offset)
+        .ok()
+        .and_then(FixedOffset::east_opt)
+        .ok_or_else(|| format!("Invalid timezone offset: {}", body.timezone_offset))?;
+
+    let hours = body
+        .hourly
+        .into_iter()
+        .filter_map(|hour| {
+            let time = DateTime::from_timestamp(hour.dt, 0)?.with_timezone(&offset);
+            (time.date_naive() == requested).then_some(HourlyWeather {
+                time,
+                temperature: hour.temp,
+                humidity: hour.humidity,
+                wind_speed_mph: hour.wind_speed,
+            })
         })
+        .collect::<Vec<_>>();
+
+    if hours.is_empty() {
+        Err(format!(
+            "OpenWeather One Call has no hourly data for {date}: only the next 48 hours are available"
+        ))?;
+    }
+
+    Ok(hours)
+}
+
+/// Converts a Unix timestamp to an RFC 3339 UTC time.
+fn to_rfc3339(timestamp: i64) -> Option<String> {
+    DateTime::from_timestamp(timestamp, 0).map(|t| t.to_rfc3339())
+}
+
+/// Requests the geocoding candidates for a query.
+async fn fetch_locations(http: &HttpClient, url: Url) -> Result<Vec<OpenWeatherGeoResponse>> {
+    let response = check_status_with(
+        "OpenWeather",
+        http.get("OpenWeather", "direct", url).await?,
+        decode_error,
+    )
+    .await?;
+    parse_json("OpenWeather", response).await
+}
+
+/// Picks the first plausible geocoding candidate.
+///
+/// OpenWeather may answer garbage queries with entries that have an empty name or zeroed
+/// coordinates, so such entries are treated as no match.
+fn select_location(query: &str, locations: Vec<OpenWeatherGeoResponse>) -> Result<GeoLocation> {
+    locations
+        .into_iter()
+        .find(|l| {
+            !l.name.trim().is_empty()
+                && (-90.0..=90.0).contains(&l.lat)
+                && (-180.0..=180.0).contains(&l.lon)
+                && (l.lat, l.lon) != (0.0, 0.0)
+        })
+        .map(|l| GeoLocation {
+            city: l.name,
+            region: l.state,
+            country: l.country,
+            lat: l.lat,
+            lon: l.lon,
+        })
+        .ok_or_else(|| Error::UnknownLocation {
+            provider: "OpenWeather".into(),
+            query: query.to_string(),
+        })
+}
+
+/// Maps an OpenWeather error body to an error, using the documented error codes.
+fn decode_error(body: &str) -> Option<Error> {
+    let error = serde_json::from_str::<OpenWeatherErrorResponse>(body).ok()?;
+    let code = error
+        .cod
+        .as_u64()
+        .or_else(|| error.cod.as_str()?.parse().ok())?;
+
+    Some(match code {
+        401 => Error::InvalidApiKey("OpenWeather".into()),
+        404 => Error::LocationNotFound("OpenWeather".into()),
+        429 => Error::RateLimited("OpenWeather".into()),
+        code => format!("OpenWeather error {code}: {}", error.message).into(),
+    })
+}
+
+fn require_key(provider_key: Option<&str>) -> Result<&str> {
+    provider_key.ok_or_else(|| {
+        Error::MissingApiKey("'OpenWeather' API key not set. Please set it using: 'weather provider ow --key <API_KEY>'".into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use ::wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path},
+    };
+
+    fn query(url: &Url) -> Vec<(String, String)> {
+        url.query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect()
+    }
+
+    fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_geocode_url() {
+        let url = OpenWeatherRequests::geocode("key", "London, UK").unwrap();
+
+        assert_eq!(url.path(), "/geo/1.0/direct");
+        assert_eq!(
+            query(&url),
+            pairs(&[("appid", "key"), ("q", "London, UK"), ("limit", "1")])
+        );
+        assert_eq!(url.query(), Some("appid=key&q=London%2C+UK&limit=1"));
+    }
+
+    #[test]
+    fn test_geocode_url_encoding() {
+        for address in [
+            "Tom & Jerry St",
+            "Rio de Janeiro",
+            "Київ",
+            "☔ Manchester 🌧️",
+        ] {
+            let url = OpenWeatherRequests::geocode("key", address).unwrap();
+            assert_eq!(query(&url)[1], ("q".to_string(), address.to_string()));
+        }
+
+        // An ampersand cannot start another parameter
+        let url = OpenWeatherRequests::geocode("key", "A&limit=5").unwrap();
+        assert_eq!(url.query(), Some("appid=key&q=A%26limit%3D5&limit=1"));
+
+        let url = OpenWeatherRequests::geocode("key", "Київ").unwrap();
+        assert_eq!(
+            url.query(),
+            Some("appid=key&q=%D0%9A%D0%B8%D1%97%D0%B2&limit=1")
+        );
+    }
+
+    #[test]
+    fn test_day_summary_url() {
+        let url = OpenWeatherRequests::day_summary(
+            "key",
+            51.5073,
+            -0.1276,
+            "2024-01-15",
+            Unit::Fahrenheit,
+        )
+        .unwrap();
+
+        assert_eq!(url.path(), "/data/3.0/onecall/day_summary");
+        assert_eq!(
+            query(&url),
+            pairs(&[
+                ("appid", "key"),
+                ("lat", "51.5073"),
+                ("lon", "-0.1276"),
+                ("date", "2024-01-15"),
+                ("units", "imperial"),
+            ])
+        );
+
+        let url =
+            OpenWeatherRequests::day_summary("key", 51.5073, -0.1276, "2024-01-15", Unit::Celsius)
+                .unwrap();
+        assert_eq!(query(&url)[4], ("units".to_string(), "metric".to_string()));
+    }
+
+    #[test]
+    fn test_one_call_url() {
+        let url = OpenWeatherRequests::one_call("key", 50.45, 30.5234, Unit::Fahrenheit).unwrap();
+
+        assert_eq!(url.path(), "/data/3.0/onecall");
+        assert_eq!(
+            query(&url),
+            pairs(&[
+                ("appid", "key"),
+                ("lat", "50.45"),
+                ("lon", "30.5234"),
+                ("exclude", "minutely,hourly"),
+                ("units", "imperial"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_one_call_alerts_url() {
+        let url = OpenWeatherRequests::one_call_alerts("key", 50.45, 30.5234).unwrap();
+
+        assert_eq!(url.path(), "/data/3.0/onecall");
+        assert_eq!(
+            query(&url)[3],
+            (
+                "exclude".to_string(),
+                "current,minutely,hourly,daily".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_one_call_hourly_url() {
+        let url =
+            OpenWeatherRequests::one_call_hourly("key", 50.45, 30.5234, Unit::Fahrenheit).unwrap();
+
+        assert_eq!(url.path(), "/data/3.0/onecall");
+        assert_eq!(
+            query(&url)[3],
+            (
+                "exclude".to_string(),
+                "current,minutely,daily,alerts".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_hours_on_local_date() {
+        // 21:00 and 23:00 UTC on Jan 15, then 00:00 UTC; at UTC+2 only the first is still Jan 15
+        let body: OpenWeatherOneCallResponse = serde_json::from_str(
+            r#"{"timezone_offset": 7200, "hourly": [
+                {"dt": 1705352400, "temp": 30.2, "humidity": 91, "wind_speed": 4.5},
+                {"dt": 1705359600, "temp": 29.8, "humidity": 93},
+                {"dt": 1705363200, "temp": 29.1, "humidity": 94, "wind_speed": 3.9}
+            ]}"#,
+        )
+        .unwrap();
+
+        let hours = hours_on("2024-01-16", body).unwrap();
+        assert_eq!(hours.len(), 2);
+        assert_eq!(hours[0].time.to_rfc3339(), "2024-01-16T01:00:00+02:00");
+        assert_eq!(hours[0].temperature, 29.8);
+        assert_eq!(hours[0].wind_speed_mph, None);
+        assert_eq!(hours[1].humidity, 94);
+
+        let error = hours_on("2024-01-20", one_call()).unwrap_err().to_string();
+        assert!(error.contains("no hourly data for 2024-01-20"), "{error}");
+    }
+
+    fn locations(json: &str) -> Vec<OpenWeatherGeoResponse> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_select_location() {
+        let location = select_location(
+            "London",
+            locations(r#"[{"name": "London", "lat": 51.5073, "lon": -0.1276, "country": "GB"}]"#),
+        )
+        .unwrap();
+
+        assert_eq!(location.city, "London");
+        assert_eq!(location.country, "GB");
+    }
+
+    #[test]
+    fn test_empty_result_is_unknown_location() {
+        let error = select_location("xyz", locations("[]")).unwrap_err();
+
+        assert!(matches!(
+            &error,
+            Error::UnknownLocation { provider, query } if provider == "OpenWeather" && query == "xyz"
+        ));
+        assert!(error.to_string().starts_with("Location not found: 'xyz'"));
+    }
+
+    #[test]
+    fn test_implausible_entries_are_rejected() {
+        for json in [
+            r#"[{"name": "", "lat": 51.5, "lon": -0.1, "country": "GB"}]"#,
+            r#"[{"name": "Nowhere", "lat": 0.0, "lon": 0.0, "country": ""}]"#,
+            r#"[{"name": "Nowhere", "lat": 91.0, "lon": 0.1, "country": ""}]"#,
+            r#"[{"name": "Nowhere", "lat": 10.0, "lon": -180.5, "country": ""}]"#,
+        ] {
+            let result = select_location("garbage", locations(json));
+            assert!(
+                matches!(result, Err(Error::UnknownLocation { .. })),
+                "{json} should be rejected"
+            );
+        }
+    }
+
+    fn london() -> GeoLocation {
+        GeoLocation {
+            city: "London".to_string(),
+            country: "GB".to_string(),
+            lat: 51.5073,
+            lon: -0.1276,
+            ..GeoLocation::default()
+        }
+    }
+
+    fn day_summary(extra: &str) -> OpenWeatherResponse {
+        serde_json::from_str(&format!(
+            r#"{{
+                "temperature": {{"afternoon": 33.8}},
+                "humidity": {{"afternoon": 90}},
+                "precipitation": {{"total": 4.2}}
+                {extra}
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_precipitation_type() {
+        let info = to_weather_info(
+            london(),
+            "2024-01-15".to_string(),
+            day_summary(r#", "precipitation_type": "Sleet", "snow": {"1h": 1.5}"#),
+        );
+        assert_eq!(info.precip_type, Some(PrecipType::Sleet));
+        assert_eq!(info.snow_depth_mm, Some(1.5));
+
+        // Without a type, snowfall implies snow
+        let info = to_weather_info(
+            london(),
+            "2024-01-15".to_string(),
+            day_summary(r#", "snow": {"1h": 2.0}"#),
+        );
+        assert_eq!(info.precip_type, Some(PrecipType::Snow));
+
+        let info = to_weather_info(london(), "2024-01-15".to_string(), day_summary(""));
+        assert_eq!(info.precip_type, None);
+        assert_eq!(info.snow_depth_mm, None);
+        assert_eq!(info.precipitation_mm, Some(4.2));
+    }
+
+    #[test]
+    fn test_day_summary_periods() {
+        let body = serde_json::from_str(include_str!(
+            "../../tests/fixtures/open_weather_day_summary.json"
+        ))
+        .unwrap();
+        let info = to_weather_info(london(), "2024-01-15".to_string(), body);
+
+        assert_eq!(info.temperature, 36.5);
+        assert_eq!(info.humidity, Some(87));
+        assert_eq!(info.wind_speed_mph, Some(12.5));
+        assert_eq!(info.pressure_hpa, Some(1012.0));
+        assert_eq!(
+            info.periods,
+            Some(DayParts {
+                morning: 32.4,
+                afternoon: 36.5,
+                evening: 34.3,
+                night: 31.8,
+                low: Some(30.2),
+                high: Some(38.1),
+            })
+        );
+
+        // Without all four parts there is nothing to show
+        let info = to_weather_info(london(), "2024-01-15".to_string(), day_summary(""));
+        assert_eq!(info.periods, None);
+    }
+
+    fn one_call() -> OpenWeatherOneCallResponse {
+        serde_json::from_str(include_str!(
+            "../../tests/fixtures/open_weather_onecall.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_one_call_today_uses_current_conditions() {
+        let info =
+            one_call_to_weather_info(london(), "2024-01-15".to_string(), one_call()).unwrap();
+
+        assert_eq!(info.temperature, 35.6);
+        assert_eq!(info.humidity, Some(87));
+        assert_eq!(info.feels_like, Some(29.3));
+        assert_eq!(info.description.as_deref(), Some("light snow"));
+        assert_eq!(info.precipitation_mm, Some(0.3));
+        assert_eq!(info.precip_type, Some(PrecipType::Snow));
+        assert_eq!(info.sunrise.as_deref(), Some("2024-01-15T07:58:13+00:00"));
+        assert_eq!(
+            info.observation_time.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2024-01-15T14:00:00+00:00")
+        );
+        assert_eq!(info.moon_phase, Some(0.14));
+        assert_eq!(info.moon_phase_name(), Some("Waxing Crescent"));
+        assert_eq!(info.icon.as_deref(), Some("snow"));
+        assert_eq!(info.provider_icon.as_deref(), Some("13d"));
+        assert_eq!(info.condition_code, Some(600));
+        // The probability and highest UV index of today's daily entry
+        assert_eq!(info.precip_probability, Some(0.62));
+        assert_eq!(info.precip_probability_pct(), Some(62));
+        assert_eq!(info.uv_index_max, Some(0.6));
+        assert!(!info.sun_protection_needed());
+        assert_eq!(info.pressure_hpa, Some(1024.0));
+        assert_eq!(info.alerts.len(), 1);
+        assert_eq!(info.alerts[0].event, "Yellow snow and ice warning");
+        assert_eq!(info.alerts[0].sender, "Met Office");
+        assert_eq!(info.alerts[0].start.to_string(), "2024-01-15 11:00:00");
+        assert_eq!(info.alerts[0].end.to_string(), "2024-01-15 20:00:00");
+
+        let meta = info.meta.unwrap();
+        assert_eq!(
+            (meta.provider.as_str(), meta.endpoint.as_str()),
+            ("ow", "onecall")
+        );
+        assert_eq!(meta.source, DataSource::Observed);
+    }
+
+    #[test]
+    fn test_one_call_future_date_uses_daily_entry() {
+        let info =
+            one_call_to_weather_info(london(), "2024-01-16".to_string(), one_call()).unwrap();
+
+        assert_eq!(info.date, "2024-01-16");
+        assert_eq!(info.temperature, 41.5);
+        assert_eq!(info.humidity, Some(78));
+        assert_eq!(info.wind_speed_mph, Some(11.4));
+        assert_eq!(info.precipitation_mm, Some(2.7));
+        assert_eq!(info.precip_type, Some(PrecipType::Rain));
+        assert_eq!(info.snow_depth_mm, None);
+        assert_eq!(info.moon_phase, Some(0.18));
+        assert_eq!(info.icon.as_deref(), Some("rain"));
+        assert_eq!(info.provider_icon.as_deref(), Some("10d"));
+        assert_eq!(info.condition_code, Some(500));
+        assert_eq!(info.precip_probability, Some(0.8));
+        assert_eq!(info.precip_probability_pct(), Some(80));
+        assert_eq!(info.uv_index_max, Some(0.8));
+        assert_eq!(info.pressure_hpa, Some(1018.0));
+        // The warning ends the day before
+        assert!(info.alerts.is_empty());
+        assert_eq!(info.meta.unwrap().source, DataSource::Forecast);
+
+        let dry = one_call_to_weather_info(london(), "2024-01-17".to_string(), one_call()).unwrap();
+        assert_eq!(dry.precipitation_mm, None);
+        assert_eq!(dry.precip_type, Some(PrecipType::None));
+    }
+
+    #[test]
+    fn test_one_call_uses_local_dates() {
+        // 23:30 UTC on Jan 15 is already Jan 16 at UTC+2, so the current conditions are Jan 16's
+        let mut body = one_call();
+        body.timezone_offset = 7200;
+        body.current.as_mut().unwrap().dt = 1705361400;
+
+        let info = one_call_to_weather_info(london(), "2024-01-16".to_string(), body).unwrap();
+        assert_eq!(info.temperature, 35.6);
+    }
+
+    #[test]
+    fn test_one_call_days() {
+        let dates = ["2024-01-15", "2024-01-16", "2024-01-17"].map(String::from);
+
+        let days = one_call_days(london(), &dates, one_call()).unwrap();
+
+        let dates = days.iter().map(|d| d.date.as_str()).collect::<Vec<_>>();
+        assert_eq!(dates, ["2024-01-15", "2024-01-16", "2024-01-17"]);
+        assert_eq!(days[0].temperature, 35.6);
+        assert!(days[0].observation_time.is_some());
+        assert!(days[1..].iter().all(|d| d.observation_time.is_none()));
+
+        let dates = ["2024-01-16", "2024-02-01"].map(String::from);
+        assert!(one_call_days(london(), &dates, one_call()).is_err());
+    }
+
+    #[test]
+    fn test_one_call_date_out_of_range() {
+        let error = one_call_to_weather_info(london(), "2024-02-01".to_string(), one_call())
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("no data for 2024-02-01"));
+    }
+
+    #[test]
+    fn test_decode_error_codes() {
+        assert!(matches!(
+            decode_error(r#"{"cod": 401, "message": "Invalid API key."}"#),
+            Some(Error::InvalidApiKey(p)) if p == "OpenWeather"
+        ));
+        assert!(matches!(
+            decode_error(r#"{"cod": "404", "message": "city not found"}"#),
+            Some(Error::LocationNotFound(_))
+        ));
+        assert!(matches!(
+            decode_error(r#"{"cod": 429, "message": "Your account is temporary blocked"}"#),
+            Some(Error::RateLimited(_))
+        ));
+
+        // Unknown codes keep the upstream message
+        assert_eq!(
+            decode_error(r#"{"cod": "400", "message": "wrong latitude"}"#)
+                .unwrap()
+                .to_string(),
+            "OpenWeather error 400: wrong latitude"
+        );
+    }
+
+    #[test]
+    fn test_decode_error_unknown_shape() {
+        assert!(decode_error("<html>502 Bad Gateway</html>").is_none());
+        assert!(decode_error(r#"{"cod": "abc", "message": "odd"}"#).is_none());
+        assert!(decode_error(r#"{"error": {"code": 1006, "message": "x"}}"#).is_none());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_server_error_is_not_unknown_location() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/geo/1.0/direct"))
+            .respond_with(
+                ResponseTemplate::new(502).set_body_string("<html>502 Bad Gateway</html>"),
+            )
+            .mount(&server)
+            .await;
+        let url = Url::parse(&format!("{}/geo/1.0/direct?q=London", server.uri())).unwrap();
+
+        let error = fetch_locations(&HttpClient::default(), url)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "Provider server error: try again later");
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_unexpected_body_is_kept_in_error() {
+        // A day summary where the geocoding candidates are expected
+        let fixture = include_str!("../../tests/fixtures/open_weather_day_summary.json");
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/geo/1.0/direct"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(fixture))
+            .mount(&server)
+            .await;
+        let url = Url::parse(&format!("{}/geo/1.0/direct?q=London", server.uri())).unwrap();
+
+        let Err(error) = fetch_locations(&HttpClient::default(), url).await else {
+            panic!("a day summary is not a list of locations");
+        };
+
+        assert_eq!(error.code(), ErrorCode::Decode);
+        assert!(matches!(
+            &error,
+            Error::ResponseParseFailed { provider, status: 200, body, .. }
+                if provider == "OpenWeather" && body == fixture
+        ));
+        assert!(error.to_string().starts_with(
+            "Failed to parse the response of 'OpenWeather' (HTTP 200): invalid type: map"
+        ));
     }
 }
@@ -0,0 +1,416 @@
+use crate::{
+    GeocodingClient, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, openaq::*,
+    },
+    providers::OpenMeteoGeocoder,
+    utils::{
+        aqi::pm25_aqi,
+        http::{HttpClient, check_status, parse_json},
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::DateTime;
+use ::reqwest::{RequestBuilder, Url};
+use ::serde::de::DeserializeOwned;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const LOCATIONS_URL: &str = "https://api.openaq.org/v3/locations";
+
+/// The OpenAQ ID of the PM2.5 parameter.
+const PM25_PARAMETER_ID: &str = "2";
+
+/// How far from the address stations are searched, in meters (the API maximum).
+const SEARCH_RADIUS_M: &str = "25000";
+
+/// The most pages of latest measurements read while looking for the PM2.5 sensor.
+const MAX_LATEST_PAGES: u32 = 5;
+
+/// Air quality measured by government and research monitoring stations, aggregated by OpenAQ.
+///
+/// Addresses are resolved with Open-Meteo geocoding, as the OpenAQ v3 API has no city search.
+/// The nearest station measuring PM2.5 within 25 km is used, and its latest measurement is
+/// mapped to the US EPA Air Quality Index. The temperature is `NaN`, as no weather is observed.
+#[derive(Debug, Default)]
+pub struct OpenAqProvider {
+    http: HttpClient,
+    geocoder: OpenMeteoGeocoder,
+}
+
+impl OpenAqProvider {
+    /// Creates a provider with the connection settings of `options`, also used for geocoding.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+            geocoder: OpenMeteoGeocoder::with_options(options),
+        }
+    }
+
+    /// Builds a GET request of `url`, with the API key if one is set.
+    fn request(&self, url: Url, api_key: Option<&str>) -> Result<RequestBuilder> {
+        let request = self.http.client("OpenAQ")?.get(url);
+
+        Ok(match api_key {
+            Some(api_key) => request.header("X-API-Key", api_key),
+            None => request,
+        })
+    }
+
+    /// Sends a GET request to `endpoint`, with the API key if one is set, and decodes the JSON response.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MissingApiKey` if the API rejects a request without a key.
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        endpoint: &'static str,
+        url: Url,
+        api_key: Option<&str>,
+    ) -> Result<T> {
+        let request = self.request(url, api_key)?;
+        let response = check_status("OpenAQ", self.http.send("OpenAQ", endpoint, request).await?)
+            .await
+            .map_err(|e| match (e, api_key) {
+                (Error::InvalidApiKey(_), None) => missing_key(),
+                (e, _) => e,
+            })?;
+
+        parse_json("OpenAQ", response).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for OpenAqProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let location = self.geocode(None, address).await?;
+
+        let stations = self
+            .get_json::<OpenAqResponse<OpenAqLocation>>(
+                "locations",
+                locations_url(location.lat, location.lon)?,
+                provider_key,
+            )
+            .await?;
+        let (station, sensor_id) = nearest_station(stations.results).ok_or_else(|| {
+            format!(
+                "No OpenAQ station measures PM2.5 within 25 km of '{}'",
+                location.city
+            )
+        })?;
+
+        // The latest measurements of every sensor of the station, possibly over several pages
+        let mut measurement = None;
+        for page in 1..=MAX_LATEST_PAGES {
+            let latest = self
+                .get_json::<OpenAqResponse<OpenAqLatest>>(
+                    "latest",
+                    latest_url(station.id, page)?,
+                    provider_key,
+                )
+                .await?;
+            let has_next_page = latest.has_next_page();
+
+            measurement = latest
+                .results
+                .into_iter()
+                .find(|m| m.sensors_id == sensor_id);
+            if measurement.is_some() || !has_next_page {
+                break;
+            }
+        }
+
+        let name = station_name(&station);
+        let measurement = measurement
+            .ok_or_else(|| format!("OpenAQ station '{name}' reported no PM2.5 measurement"))?;
+
+        to_weather_info(location, &station, measurement)
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let request = self.request(locations_url(51.5074, -0.1278)?, provider_key)?;
+
+        self.http
+            .probe_request("OpenAQ", "locations", request)
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://explore.openaq.org/register",
+            key_format: "Optional; an API key from the OpenAQ Explorer account settings",
+            free_tier: "Free; keyed requests are limited to 60 per minute and 2,000 per hour",
+            capabilities: &["current", "air-quality", "geocoding"],
+            example: "weather get Delhi --provider oaq",
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for OpenAqProvider {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        self.geocoder.geocode(provider_key, address).await
+    }
+}
+
+fn missing_key() -> Error {
+    Error::MissingApiKey(
+        "'OpenAQ' requires an API key for this request. Please set it using: \
+         'weather provider oaq --key <API_KEY>'"
+            .into(),
+    )
+}
+
+/// Builds the URL of the stations measuring PM2.5 around the coordinates.
+fn locations_url(lat: f64, lon: f64) -> Result<Url> {
+    Ok(Url::parse_with_params(
+        LOCATIONS_URL,
+        &[
+            ("coordinates", format!("{lat},{lon}").as_str()),
+            ("radius", SEARCH_RADIUS_M),
+            ("parameters_id", PM25_PARAMETER_ID),
+            ("limit", "10"),
+        ],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+/// Builds the URL of a page of the latest measurements of a station.
+fn latest_url(location_id: u64, page: u32) -> Result<Url> {
+    Ok(Url::parse_with_params(
+        &format!("{LOCATIONS_URL}/{location_id}/latest"),
+        &[("page", page.to_string().as_str()), ("limit", "100")],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+/// Returns the closest of the stations with a PM2.5 sensor, with the ID of that sensor.
+fn nearest_station(stations: Vec<OpenAqLocation>) -> Option<(OpenAqLocation, u64)> {
+    stations
+        .into_iter()
+        .filter_map(|station| pm25_sensor(&station).map(|sensor_id| (station, sensor_id)))
+        .min_by(|(a, _), (b, _)| {
+            let distance = |s: &OpenAqLocation| s.distance.unwrap_or(f64::INFINITY);
+            distance(a).total_cmp(&distance(b))
+        })
+}
+
+fn station_name(station: &OpenAqLocation) -> String {
+    station
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("Station {}", station.id))
+}
+
+/// Returns the ID of the PM2.5 sensor of a station.
+fn pm25_sensor(station: &OpenAqLocation) -> Option<u64> {
+    station
+        .sensors
+        .iter()
+        .find(|sensor| sensor.parameter.name == "pm25")
+        .map(|sensor| sensor.id)
+}
+
+fn to_weather_info(
+    location: GeoLocation,
+    station: &OpenAqLocation,
+    measurement: OpenAqLatest,
+) -> Result<WeatherInfo> {
+    let name = station_name(station);
+    let pm25 = measurement.value;
+    let observed = DateTime::parse_from_rfc3339(&measurement.datetime.utc).map_err(|_| {
+        format!(
+            "'OpenAQ' returned an invalid measurement time: '{}'",
+            measurement.datetime.utc
+        )
+    })?;
+    let date = observed.format("%Y-%m-%d").to_string();
+
+    let mut info = WeatherInfo {
+        country: location.country,
+        city: location.city,
+        date,
+        temperature: f32::NAN,
+        description: Some(format!("PM2.5: {pm25:.1} μg/m³ at {name}")),
+        station_id: Some(station.id.to_string()),
+        air_quality_index: pm25_aqi(pm25),
+        pm25: Some(pm25),
+        meta: Some(WeatherMeta::new(
+            Provider::OpenAq,
+            "latest",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    };
+    // The US AQI is computed from the PM2.5 concentration
+    if info.air_quality_index.is_some() {
+        info.mark_derived("air_quality_index");
+    }
+
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCATIONS: &str = r#"{
+        "meta": {"name": "openaq-api", "website": "/", "page": 1, "limit": 10, "found": 2},
+        "results": [
+            {
+                "id": 2178,
+                "name": "Del Norte",
+                "locality": "Albuquerque",
+                "country": {"id": 155, "code": "US", "name": "United States"},
+                "sensors": [
+                    {"id": 3917, "name": "o3 ppm", "parameter": {"id": 10, "name": "o3", "units": "ppm"}},
+                    {"id": 3920, "name": "pm25 µg/m³", "parameter": {"id": 2, "name": "pm25", "units": "µg/m³"}}
+                ],
+                "distance": 5230.7
+            },
+            {
+                "id": 2183,
+                "name": "South Valley",
+                "locality": "Albuquerque",
+                "country": {"id": 155, "code": "US", "name": "United States"},
+                "sensors": [
+                    {"id": 3935, "name": "pm25 µg/m³", "parameter": {"id": 2, "name": "pm25", "units": "µg/m³"}}
+                ],
+                "distance": 1805.2
+            }
+        ]
+    }"#;
+
+    const LATEST: &str = r#"{
+        "meta": {"name": "openaq-api", "website": "/", "page": 1, "limit": 100, "found": ">100"},
+        "results": [
+            {
+                "datetime": {"utc": "2024-01-15T12:00:00Z", "local": "2024-01-15T05:00:00-07:00"},
+                "value": 0.031,
+                "coordinates": {"latitude": 35.06, "longitude": -106.67},
+                "sensorsId": 3934,
+                "locationsId": 2183
+            },
+            {
+                "datetime": {"utc": "2024-01-15T12:00:00Z", "local": "2024-01-15T05:00:00-07:00"},
+                "value": 12.04,
+                "coordinates": {"latitude": 35.06, "longitude": -106.67},
+                "sensorsId": 3935,
+                "locationsId": 2183
+            }
+        ]
+    }"#;
+
+    fn albuquerque() -> GeoLocation {
+        GeoLocation {
+            city: "Albuquerque".to_string(),
+            country: "United States".to_string(),
+            lat: 35.0844,
+            lon: -106.6504,
+            ..GeoLocation::default()
+        }
+    }
+
+    fn stations() -> Vec<OpenAqLocation> {
+        serde_json::from_str::<OpenAqResponse<OpenAqLocation>>(LOCATIONS)
+            .unwrap()
+            .results
+    }
+
+    fn latest() -> OpenAqResponse<OpenAqLatest> {
+        serde_json::from_str(LATEST).unwrap()
+    }
+
+    #[test]
+    fn test_paginated_response() {
+        let body = serde_json::from_str::<OpenAqResponse<OpenAqLocation>>(LOCATIONS).unwrap();
+        assert_eq!((body.meta.page, body.meta.limit), (1, 10));
+        assert_eq!(body.meta.found, OpenAqFound::Exact(2));
+        assert_eq!(body.results.len(), 2);
+        assert!(!body.has_next_page());
+
+        // Large result sets only report a lower bound, and a partial page is the last one
+        let body = latest();
+        assert_eq!(body.meta.found, OpenAqFound::AtLeast(">100".to_string()));
+        assert_eq!(body.results[1].sensors_id, 3935);
+        assert!(!body.has_next_page());
+
+        let body = serde_json::from_str::<OpenAqResponse<OpenAqLatest>>(
+            r#"{"meta": {"page": 1, "limit": 1, "found": 3}, "results": [
+                {"datetime": {"utc": "2024-01-15T12:00:00Z"}, "value": 1.0, "sensorsId": 1}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(body.has_next_page());
+    }
+
+    #[test]
+    fn test_nearest_station() {
+        let (station, sensor_id) = nearest_station(stations()).unwrap();
+
+        assert_eq!(station.id, 2183);
+        assert_eq!(sensor_id, 3935);
+
+        // Stations without a PM2.5 sensor are skipped
+        let mut stations = stations();
+        stations[1].sensors.clear();
+        assert_eq!(
+            nearest_station(stations).map(|(station, sensor_id)| (station.id, sensor_id)),
+            Some((2178, 3920))
+        );
+        assert!(nearest_station(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_locations_url() {
+        let url = locations_url(35.0844, -106.6504).unwrap();
+
+        assert_eq!(
+            url.query(),
+            Some("coordinates=35.0844%2C-106.6504&radius=25000&parameters_id=2&limit=10")
+        );
+        assert_eq!(
+            latest_url(2183, 2).unwrap().as_str(),
+            "https://api.openaq.org/v3/locations/2183/latest?page=2&limit=100"
+        );
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let (station, _) = nearest_station(stations()).unwrap();
+        let measurement = latest().results.remove(1);
+        let info = to_weather_info(albuquerque(), &station, measurement).unwrap();
+
+        assert_eq!(info.city, "Albuquerque");
+        assert_eq!(info.date, "2024-01-15");
+        assert!(info.temperature.is_nan());
+        assert_eq!(info.pm25, Some(12.04));
+        assert_eq!(info.air_quality_index, Some(56));
+        assert_eq!(
+            info.description.as_deref(),
+            Some("PM2.5: 12.0 μg/m³ at South Valley")
+        );
+        assert_eq!(info.station_id.as_deref(), Some("2183"));
+
+        let meta = info.meta.unwrap();
+        assert_eq!(meta.provider, "oaq");
+        assert_eq!(meta.derived, ["air_quality_index"]);
+    }
+
+    #[test]
+    fn test_invalid_measurement_time() {
+        let (station, _) = nearest_station(stations()).unwrap();
+        let measurement = serde_json::from_str::<OpenAqLatest>(
+            r#"{"datetime": {"utc": "yesterday"}, "value": 1.0, "sensorsId": 1}"#,
+        )
+        .unwrap();
+
+        let error = to_weather_info(albuquerque(), &station, measurement).unwrap_err();
+        assert!(error.to_string().contains("'yesterday'"), "{error}");
+    }
+}
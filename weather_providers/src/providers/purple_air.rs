@@ -0,0 +1,209 @@
+use crate::{
+    Provider, WeatherProvider,
+    common::*,
+    models::{DataSource, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, purple_air::*},
+    utils::{
+        aqi::pm25_aqi,
+        http::{HttpClient, check_status, parse_json},
+        units::humidity_percent,
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::DateTime;
+use ::reqwest::Url;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const SENSORS_URL: &str = "https://api.purpleair.com/v1/sensors";
+const KEYS_URL: &str = "https://api.purpleair.com/v1/keys";
+
+/// The sensor fields requested from the API.
+const SENSOR_FIELDS: &str = "name,last_seen,temperature,humidity,pm2.5";
+
+/// Air quality sensors of the PurpleAir network.
+///
+/// The address is the index of a sensor, so no geocoding is needed. Sensors report PM2.5
+/// concentrations, which are mapped to a description and the US EPA Air Quality Index.
+#[derive(Debug, Default)]
+pub struct PurpleAirProvider {
+    http: HttpClient,
+}
+
+impl PurpleAirProvider {
+    /// Creates a provider with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for PurpleAirProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let sensor_index = parse_sensor_index(address)?;
+        let api_key = require_key(provider_key)?;
+
+        let url = Url::parse_with_params(
+            &format!("{SENSORS_URL}/{sensor_index}"),
+            &[("fields", SENSOR_FIELDS)],
+        )
+        .map_err(|e| format!("Failed to build URL: {e}"))?;
+
+        let request = self
+            .http
+            .client("PurpleAir")?
+            .get(url)
+            .header("X-API-Key", api_key);
+        let response = self.http.send("PurpleAir", "sensors", request).await?;
+        let body = parse_json::<PurpleAirSensorResponse>(
+            "PurpleAir",
+            check_status("PurpleAir", response).await?,
+        )
+        .await?;
+
+        Ok(to_weather_info(body))
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let request = self
+            .http
+            .client("PurpleAir")?
+            .get(KEYS_URL)
+            .header("X-API-Key", require_key(provider_key)?);
+
+        self.http.probe_request("PurpleAir", "keys", request).await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://develop.purpleair.com",
+            key_format: "A READ API key created in the developer dashboard",
+            free_tier: "Initial points grant; each request costs points per returned field",
+            capabilities: &["current", "air-quality"],
+            example: "weather get 131075 --provider pa",
+        }
+    }
+}
+
+fn require_key(provider_key: Option<&str>) -> Result<&str> {
+    provider_key.ok_or_else(|| {
+        Error::MissingApiKey(
+            "'PurpleAir' API key not set. Please set it using: \
+             'weather provider pa --key <READ_API_KEY>'"
+                .into(),
+        )
+    })
+}
+
+/// Validates a sensor index, the integer identifying a PurpleAir sensor.
+fn parse_sensor_index(address: &str) -> Result<u64> {
+    let address = address.trim();
+
+    address.parse::<u64>().map_err(|_| {
+        format!(
+            "Invalid sensor index: '{address}'. \
+             PurpleAir expects the numeric index of a sensor (e.g., 131075)"
+        )
+        .into()
+    })
+}
+
+fn to_weather_info(body: PurpleAirSensorResponse) -> WeatherInfo {
+    let sensor = body.sensor;
+    let date = DateTime::from_timestamp(sensor.last_seen.unwrap_or(body.data_time_stamp), 0)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    let mut info = WeatherInfo {
+        country: String::new(),
+        city: sensor
+            .name
+            .unwrap_or_else(|| format!("Sensor {}", sensor.sensor_index)),
+        date,
+        // Sensors without a temperature reading report air quality only
+        temperature: sensor.temperature.unwrap_or(f32::NAN),
+        humidity: sensor.humidity.map(humidity_percent),
+        description: sensor.pm2_5.map(|pm| format!("PM2.5: {pm:.1} μg/m³")),
+        station_id: Some(sensor.sensor_index.to_string()),
+        air_quality_index: sensor.pm2_5.and_then(pm25_aqi),
+        pm25: sensor.pm2_5,
+        meta: Some(WeatherMeta::new(
+            Provider::PurpleAir,
+            "sensors",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    };
+    // The US AQI is computed from the PM2.5 concentration
+    if info.air_quality_index.is_some() {
+        info.mark_derived("air_quality_index");
+    }
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENSOR: &str = r#"{
+        "api_version": "V1.0.11-0.0.58",
+        "time_stamp": 1704110460,
+        "data_time_stamp": 1704110400,
+        "sensor": {
+            "sensor_index": 131075,
+            "name": "Mariners Bluff",
+            "last_seen": 1704110350,
+            "humidity": 36,
+            "temperature": 81,
+            "pm2.5": 12.04
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_sensor_index() {
+        assert_eq!(parse_sensor_index(" 131075 ").unwrap(), 131075);
+
+        assert!(parse_sensor_index("London").is_err());
+        assert!(parse_sensor_index("-1").is_err());
+        assert!(parse_sensor_index("").is_err());
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let body = serde_json::from_str::<PurpleAirSensorResponse>(SENSOR).unwrap();
+
+        let info = to_weather_info(body);
+
+        assert_eq!(info.city, "Mariners Bluff");
+        assert_eq!(info.date, "2024-01-01");
+        assert_eq!(info.temperature, 81.0);
+        assert_eq!(info.humidity, Some(36));
+        assert_eq!(info.description.as_deref(), Some("PM2.5: 12.0 μg/m³"));
+        assert_eq!(info.air_quality_index, Some(56));
+        assert_eq!(info.pm25, Some(12.04));
+        assert_eq!(info.station_id.as_deref(), Some("131075"));
+    }
+
+    #[test]
+    fn test_to_weather_info_air_quality_only() {
+        let body = serde_json::from_str::<PurpleAirSensorResponse>(
+            r#"{"data_time_stamp": 1704110400, "sensor": {"sensor_index": 7, "pm2.5": 40.2}}"#,
+        )
+        .unwrap();
+
+        let info = to_weather_info(body);
+
+        assert_eq!(info.city, "Sensor 7");
+        assert!(info.temperature.is_nan());
+        assert_eq!(info.air_quality_index, Some(113));
+        assert_eq!(
+            info.to_string(),
+            "Weather in 'Sensor 7' [station: 7]: PM2.5: 40.2 μg/m³, AQI: 113"
+        );
+    }
+}
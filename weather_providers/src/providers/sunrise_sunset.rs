@@ -0,0 +1,238 @@
+use crate::{
+    ForecastProvider, GeocodingClient, HistoricalProvider, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta,
+        sunrise_sunset::*,
+    },
+    providers::OpenMeteoGeocoder,
+    utils::{
+        date::*,
+        http::{HttpClient, check_status, parse_json},
+    },
+};
+use ::async_trait::async_trait;
+use ::reqwest::Url;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const SUNRISE_SUNSET_URL: &str = "https://api.sunrise-sunset.org/json";
+
+/// Sunrise and sunset times from Sunrise-Sunset.org, without any weather data.
+///
+/// The API only accepts coordinates, so addresses are resolved with Open-Meteo geocoding.
+/// The temperature is `NaN`, as no weather is observed.
+#[derive(Debug, Default)]
+pub struct SunriseSunsetProvider {
+    http: HttpClient,
+    geocoder: OpenMeteoGeocoder,
+}
+
+impl SunriseSunsetProvider {
+    /// Creates a provider with the connection settings of `options`, also used for geocoding.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+            geocoder: OpenMeteoGeocoder::with_options(options),
+        }
+    }
+
+    /// Returns the weather of `address` on `date` (today if `None`).
+    #[instrument(skip(self, _provider_key))]
+    async fn weather_on(
+        &self,
+        _provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<WeatherInfo> {
+        let location = self.geocode(None, address).await?;
+        let date = normalize_date(date);
+
+        let url = sunrise_sunset_url(location.lat, location.lon, &date)?;
+        let response = check_status(
+            "SunriseSunset",
+            self.http.get("SunriseSunset", "json", url).await?,
+        )
+        .await?;
+        let body = parse_json::<SunriseSunsetResponse>("SunriseSunset", response).await?;
+
+        to_weather_info(location, date, body)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for SunriseSunsetProvider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
+
+    #[instrument(skip(self, _provider_key))]
+    async fn health_check(&self, _provider_key: Option<&str>) -> Result<Duration> {
+        self.http
+            .probe(
+                "SunriseSunset",
+                "json",
+                sunrise_sunset_url(51.5072, -0.1276, "today")?,
+            )
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://sunrise-sunset.org/api",
+            key_format: "Not required",
+            free_tier: "Free, attribution to sunrise-sunset.org required",
+            capabilities: &["current", "history", "forecast", "geocoding"],
+            example: "weather get Oslo --provider ss",
+        }
+    }
+
+    fn as_forecast(&self) -> Option<&dyn ForecastProvider> {
+        Some(self)
+    }
+
+    fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ForecastProvider for SunriseSunsetProvider {
+    async fn get_forecast(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HistoricalProvider for SunriseSunsetProvider {
+    async fn get_history(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for SunriseSunsetProvider {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        self.geocoder.geocode(provider_key, address).await
+    }
+}
+
+fn sunrise_sunset_url(lat: f64, lon: f64, date: &str) -> Result<Url> {
+    Url::parse_with_params(
+        SUNRISE_SUNSET_URL,
+        &[
+            ("lat", lat.to_string().as_str()),
+            ("lng", lon.to_string().as_str()),
+            ("date", date),
+            ("formatted", "0"),
+        ],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}").into())
+}
+
+fn to_weather_info(
+    location: GeoLocation,
+    date: String,
+    body: SunriseSunsetResponse,
+) -> Result<WeatherInfo> {
+    let results = body
+        .results
+        .filter(|_| body.status == "OK")
+        .ok_or_else(|| format!("Sunrise-Sunset request failed: {}", body.status))?;
+    let source = DataSource::for_date(&date);
+
+    Ok(WeatherInfo {
+        country: location.country,
+        city: location.city,
+        date,
+        temperature: f32::NAN,
+        description: Some("Sunrise/Sunset data only".to_string()),
+        sunrise: Some(results.sunrise),
+        sunset: Some(results.sunset),
+        meta: Some(WeatherMeta::new(Provider::SunriseSunset, "json", source)),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oslo() -> GeoLocation {
+        GeoLocation {
+            city: "Oslo".to_string(),
+            country: "Norway".to_string(),
+            lat: 59.91273,
+            lon: 10.74609,
+            ..GeoLocation::default()
+        }
+    }
+
+    #[test]
+    fn test_sunrise_sunset_url() {
+        let url = sunrise_sunset_url(59.91273, 10.74609, "2024-06-21").unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "https://api.sunrise-sunset.org/json?lat=59.91273&lng=10.74609&date=2024-06-21&formatted=0"
+        );
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let body = serde_json::from_str::<SunriseSunsetResponse>(
+            r#"{
+                "results": {
+                    "sunrise": "2024-06-21T01:53:38+00:00",
+                    "sunset": "2024-06-21T20:44:11+00:00",
+                    "solar_noon": "2024-06-21T11:18:54+00:00",
+                    "day_length": 67833
+                },
+                "status": "OK",
+                "tzid": "UTC"
+            }"#,
+        )
+        .unwrap();
+
+        let info = to_weather_info(oslo(), "2024-06-21".to_string(), body).unwrap();
+
+        assert_eq!(info.city, "Oslo");
+        assert_eq!(info.country, "Norway");
+        assert!(info.temperature.is_nan());
+        assert_eq!(info.sunrise.as_deref(), Some("2024-06-21T01:53:38+00:00"));
+        assert_eq!(info.sunset.as_deref(), Some("2024-06-21T20:44:11+00:00"));
+        assert_eq!(
+            info.description.as_deref(),
+            Some("Sunrise/Sunset data only")
+        );
+    }
+
+    #[test]
+    fn test_failed_status() {
+        let body = serde_json::from_str::<SunriseSunsetResponse>(
+            r#"{"results": "", "status": "INVALID_DATE"}"#,
+        )
+        .unwrap();
+
+        let error = to_weather_info(oslo(), String::new(), body).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Sunrise-Sunset request failed: INVALID_DATE"
+        );
+    }
+}
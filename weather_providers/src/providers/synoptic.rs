@@ -0,0 +1,377 @@
+use crate::{
+    HistoricalProvider, Provider, WeatherProvider,
+    common::*,
+    models::{DataSource, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, synoptic::*},
+    utils::{
+        clock::{Clock, SystemClock},
+        date::normalize_date,
+        http::{HttpClient, check_status, parse_json},
+        units::{celsius_to_fahrenheit, humidity_percent},
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::{DateTime, NaiveDate};
+use ::reqwest::Url;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const STATIONS_URL: &str = "https://api.synopticdata.com/v2/stations";
+
+/// The variables requested; the others a station reports are left out of the response.
+const VARIABLES: &str = "air_temp,relative_humidity";
+
+/// How far from noon (UTC) the observation of a past date may be, in minutes.
+const WITHIN_MINUTES: &str = "60";
+
+/// Surface station observations aggregated by Synoptic Data (Mesonet), mostly in North America.
+///
+/// The address is a station ID (e.g., "KSLC"), so no geocoding is needed. The latest
+/// observation is reported for today, and the one nearest to noon (UTC) for past dates, as far
+/// back as the station's own record goes.
+#[derive(Debug, Default)]
+pub struct SynopticProvider {
+    http: HttpClient,
+}
+
+impl SynopticProvider {
+    /// Creates a provider with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+        }
+    }
+
+    /// Returns the weather of `address` on `date` (today if `None`).
+    #[instrument(skip(self, provider_key))]
+    async fn weather_on(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<WeatherInfo> {
+        let token = require_key(provider_key)?;
+        let station_id = parse_station_id(address)?;
+        let date = past_date(date, &SystemClock)?;
+
+        let url = stations_url(token, &station_id, date)?;
+        let response = check_status(
+            "Synoptic",
+            self.http
+                .get("Synoptic", stations_endpoint(date), url)
+                .await?,
+        )
+        .await?;
+        let body = parse_json::<SynopticResponse>("Synoptic", response).await?;
+        check_summary(&body.summary)?;
+
+        let station = body
+            .station
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::LocationNotFound("Synoptic".into()))?;
+
+        to_weather_info(station)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for SynopticProvider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        self.http
+            .probe(
+                "Synoptic",
+                stations_endpoint(None),
+                stations_url(require_key(provider_key)?, "KSLC", None)?,
+            )
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://customer.synopticdata.com",
+            key_format: "A public token, generated for an API key in the customer console",
+            free_tier: "5,000 requests per month for non-commercial use",
+            capabilities: &["current", "history"],
+            example: "weather get KSLC --provider sy",
+        }
+    }
+
+    fn as_historical(&self) -> Option<&dyn HistoricalProvider> {
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HistoricalProvider for SynopticProvider {
+    async fn get_history(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+fn require_key(provider_key: Option<&str>) -> Result<&str> {
+    provider_key.ok_or_else(|| {
+        Error::MissingApiKey(
+            "'Synoptic' API key not set. Please set it using: \
+             'weather provider sy --key <TOKEN>'"
+                .into(),
+        )
+    })
+}
+
+/// Validates a station ID (e.g., "KSLC") and returns it in upper case.
+fn parse_station_id(address: &str) -> Result<String> {
+    let address = address.trim();
+
+    if address.is_empty() || !address.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Err(format!(
+            "Invalid station ID: '{address}'. Synoptic expects a station ID such as 'KSLC'"
+        ))?;
+    }
+
+    Ok(address.to_uppercase())
+}
+
+/// Returns the requested date if it is in the past, or `None` for today and no date at all.
+///
+/// # Errors
+///
+/// Returns `Error::UnsupportedDate` if the date is in the future.
+fn past_date(date: Option<&str>, clock: &dyn Clock) -> Result<Option<NaiveDate>> {
+    let Some(date) = date else {
+        return Ok(None);
+    };
+
+    let today = clock.today_in(None);
+    let date = NaiveDate::parse_from_str(&normalize_date(Some(date)), "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{date}': {e}"))?;
+
+    if date > today {
+        return Err(Error::UnsupportedDate {
+            provider: "Synoptic".into(),
+            date: date.to_string(),
+            reason: "stations report observations, not forecasts".into(),
+        });
+    }
+
+    Ok((date < today).then_some(date))
+}
+
+/// Returns the endpoint of the latest observation, or of the one nearest to a time of `date`.
+fn stations_endpoint(date: Option<NaiveDate>) -> &'static str {
+    if date.is_some() {
+        "nearesttime"
+    } else {
+        "latest"
+    }
+}
+
+/// Builds the URL of the latest observation, or of the one nearest to noon (UTC) of `date`.
+fn stations_url(token: &str, station_id: &str, date: Option<NaiveDate>) -> Result<Url> {
+    let mut url = Url::parse(STATIONS_URL).map_err(|e| format!("Failed to build URL: {e}"))?;
+    url.path_segments_mut()
+        .map_err(|_| "Failed to build URL: invalid base URL")?
+        .push(stations_endpoint(date));
+
+    let mut query = url.query_pairs_mut();
+    query
+        .append_pair("token", token)
+        .append_pair("stid", station_id)
+        .append_pair("vars", VARIABLES);
+    if let Some(date) = date {
+        query
+            .append_pair("attime", &date.format("%Y%m%d1200").to_string())
+            .append_pair("within", WITHIN_MINUTES);
+    }
+    drop(query);
+
+    Ok(url)
+}
+
+/// Maps an unsuccessful response summary to an error.
+///
+/// Synoptic reports errors with HTTP 200, so they are only visible in the body.
+fn check_summary(summary: &SynopticSummary) -> Result<()> {
+    let message = summary.response_message.as_deref().unwrap_or_default();
+
+    match summary.response_code {
+        1 => Ok(()),
+        2 => Err(Error::LocationNotFound("Synoptic".into())),
+        200 => Err(Error::InvalidApiKey("Synoptic".into())),
+        code => Err(format!("Synoptic error {code}: {message}").into()),
+    }
+}
+
+fn to_weather_info(station: SynopticStation) -> Result<WeatherInfo> {
+    let observations = station.observations;
+    let (temperature, observed_at) = observations
+        .air_temp_value_1
+        .and_then(|t| Some((t.value?, t.date_time)))
+        .ok_or_else(|| format!("Station '{}' reported no air temperature", station.stid))?;
+
+    Ok(WeatherInfo {
+        country: station.country.unwrap_or_default(),
+        city: station.name.unwrap_or_else(|| station.stid.clone()),
+        date: DateTime::parse_from_rfc3339(&observed_at)
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+        temperature: celsius_to_fahrenheit(temperature),
+        humidity: observations
+            .relative_humidity_value_1
+            .and_then(|h| h.value)
+            .map(humidity_percent),
+        station_id: Some(station.stid),
+        meta: Some(WeatherMeta::new(
+            Provider::Synoptic,
+            "latest",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::FixedClock;
+    use ::chrono::{TimeZone, Utc};
+
+    const LATEST: &str = r#"{
+        "STATION": [{
+            "STID": "KSLC",
+            "NAME": "Salt Lake City, Salt Lake City International Airport",
+            "STATE": "UT",
+            "COUNTRY": "US",
+            "OBSERVATIONS": {
+                "air_temp_value_1": {"value": 20.0, "date_time": "2024-03-20T11:56:00Z"},
+                "relative_humidity_value_1": {"value": 45.6, "date_time": "2024-03-20T11:56:00Z"}
+            }
+        }],
+        "SUMMARY": {"RESPONSE_CODE": 1, "RESPONSE_MESSAGE": "OK", "NUMBER_OF_OBJECTS": 1}
+    }"#;
+
+    fn clock() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn test_parse_station_id() {
+        assert_eq!(parse_station_id(" kslc ").unwrap(), "KSLC");
+        assert_eq!(parse_station_id("WBB").unwrap(), "WBB");
+
+        assert!(parse_station_id("").is_err());
+        assert!(parse_station_id("Salt Lake City").is_err());
+    }
+
+    #[test]
+    fn test_past_date() {
+        assert_eq!(past_date(None, &clock()).unwrap(), None);
+        assert_eq!(past_date(Some("2024-03-20"), &clock()).unwrap(), None);
+        // No lower bound: the depth depends on the station's record
+        assert_eq!(
+            past_date(Some("1950-01-01"), &clock()).unwrap(),
+            NaiveDate::from_ymd_opt(1950, 1, 1)
+        );
+
+        let error = past_date(Some("2024-03-21"), &clock()).unwrap_err();
+        assert!(matches!(error, Error::UnsupportedDate { .. }), "{error}");
+    }
+
+    #[test]
+    fn test_stations_url() {
+        assert_eq!(
+            stations_url("tok", "KSLC", None).unwrap().as_str(),
+            "https://api.synopticdata.com/v2/stations/latest\
+             ?token=tok&stid=KSLC&vars=air_temp%2Crelative_humidity"
+        );
+        assert_eq!(
+            stations_url("tok", "KSLC", NaiveDate::from_ymd_opt(2001, 7, 4))
+                .unwrap()
+                .as_str(),
+            "https://api.synopticdata.com/v2/stations/nearesttime\
+             ?token=tok&stid=KSLC&vars=air_temp%2Crelative_humidity&attime=200107041200&within=60"
+        );
+    }
+
+    #[test]
+    fn test_check_summary() {
+        let summary = |code| SynopticSummary {
+            response_code: code,
+            response_message: Some("message".to_string()),
+        };
+
+        assert!(check_summary(&summary(1)).is_ok());
+        assert!(matches!(
+            check_summary(&summary(2)),
+            Err(Error::LocationNotFound(_))
+        ));
+        assert!(matches!(
+            check_summary(&summary(200)),
+            Err(Error::InvalidApiKey(_))
+        ));
+        assert_eq!(
+            check_summary(&summary(400)).unwrap_err().to_string(),
+            "Synoptic error 400: message"
+        );
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let body = serde_json::from_str::<SynopticResponse>(LATEST).unwrap();
+        let station = body.station.into_iter().next().unwrap();
+
+        let info = to_weather_info(station).unwrap();
+
+        assert_eq!(
+            info.city,
+            "Salt Lake City, Salt Lake City International Airport"
+        );
+        assert_eq!(info.country, "US");
+        assert_eq!(info.date, "2024-03-20");
+        assert_eq!(info.temperature, 68.0);
+        assert_eq!(info.humidity, Some(46));
+        assert_eq!(info.station_id.as_deref(), Some("KSLC"));
+    }
+
+    #[test]
+    fn test_missing_temperature() {
+        let body = serde_json::from_str::<SynopticResponse>(
+            r#"{
+                "STATION": [{"STID": "WBB", "OBSERVATIONS": {}}],
+                "SUMMARY": {"RESPONSE_CODE": 1}
+            }"#,
+        )
+        .unwrap();
+        let station = body.station.into_iter().next().unwrap();
+
+        assert_eq!(
+            to_weather_info(station).unwrap_err().to_string(),
+            "Station 'WBB' reported no air temperature"
+        );
+    }
+
+    #[test]
+    fn test_error_response() {
+        let body = serde_json::from_str::<SynopticResponse>(
+            r#"{"SUMMARY": {"RESPONSE_CODE": 200, "RESPONSE_MESSAGE": "Invalid token."}}"#,
+        )
+        .unwrap();
+
+        assert!(body.station.is_empty());
+        assert!(matches!(
+            check_summary(&body.summary),
+            Err(Error::InvalidApiKey(_))
+        ));
+    }
+}
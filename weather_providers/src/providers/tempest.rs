@@ -0,0 +1,222 @@
+use crate::{
+    Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, WindUnit, tempest::*,
+    },
+    utils::{
+        http::{HttpClient, check_status, parse_json},
+        units::{celsius_to_fahrenheit, humidity_percent},
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::DateTime;
+use ::reqwest::Url;
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const OBSERVATIONS_URL: &str = "https://swd.weatherflow.com/swd/rest/observations/station";
+const STATIONS_URL: &str = "https://swd.weatherflow.com/swd/rest/stations";
+
+/// Real-time observations of WeatherFlow Tempest personal weather stations.
+///
+/// The address is the numeric ID of a station, so no geocoding is needed. The latest
+/// observation of the station is reported, whatever the date.
+#[derive(Debug, Default)]
+pub struct TempestProvider {
+    http: HttpClient,
+}
+
+impl TempestProvider {
+    /// Creates a provider with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for TempestProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let station_id = parse_station_id(address)?;
+        let token = require_key(provider_key)?;
+
+        let url = Url::parse_with_params(
+            &format!("{OBSERVATIONS_URL}/{station_id}"),
+            &[("token", token)],
+        )
+        .map_err(|e| format!("Failed to build URL: {e}"))?;
+
+        let response = check_status(
+            "Tempest",
+            self.http.get("Tempest", "observations", url).await?,
+        )
+        .await?;
+        let body = parse_json::<TempestStationResponse>("Tempest", response).await?;
+
+        to_weather_info(station_id, body)
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let url = Url::parse_with_params(STATIONS_URL, &[("token", require_key(provider_key)?)])
+            .map_err(|e| format!("Failed to build URL: {e}"))?;
+
+        self.http.probe("Tempest", "stations", url).await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://tempestwx.com/settings/tokens",
+            key_format: "A personal access token created in the Tempest web app settings",
+            free_tier: "Free for the stations of your own account",
+            capabilities: &["current"],
+            example: "weather get 12345 --provider tw",
+        }
+    }
+}
+
+fn require_key(provider_key: Option<&str>) -> Result<&str> {
+    provider_key.ok_or_else(|| {
+        Error::MissingApiKey(
+            "'Tempest' API key not set. Please set it using: \
+             'weather provider tw --key <TOKEN>'"
+                .into(),
+        )
+    })
+}
+
+/// Validates a station ID, the integer identifying a Tempest station.
+fn parse_station_id(address: &str) -> Result<u64> {
+    let address = address.trim();
+
+    address.parse::<u64>().map_err(|_| {
+        format!(
+            "Invalid station ID: '{address}'. \
+             Tempest expects the numeric ID of a station (e.g., 12345)"
+        )
+        .into()
+    })
+}
+
+fn to_weather_info(station_id: u64, body: TempestStationResponse) -> Result<WeatherInfo> {
+    let observation = body
+        .obs
+        .and_then(|obs| obs.into_iter().next())
+        .ok_or_else(|| format!("Station '{station_id}' reported no observation. Is it online?"))?;
+    let temperature = observation
+        .value(TempestObservation::AIR_TEMPERATURE)
+        .ok_or_else(|| format!("Station '{station_id}' reported no air temperature"))?;
+
+    let date = observation
+        .value(TempestObservation::TIMESTAMP)
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp as i64, 0))
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    Ok(WeatherInfo {
+        country: String::new(),
+        city: body
+            .station_name
+            .or(body.public_name)
+            .unwrap_or_else(|| format!("Station {station_id}")),
+        date,
+        temperature: celsius_to_fahrenheit(temperature as f32),
+        humidity: observation
+            .value(TempestObservation::RELATIVE_HUMIDITY)
+            .map(|h| humidity_percent(h as f32)),
+        wind_speed_mph: observation
+            .value(TempestObservation::WIND_AVG)
+            .map(|ms| WindUnit::MetersPerSecond.to_mph(ms as f32)),
+        station_id: Some(body.station_id.unwrap_or(station_id).to_string()),
+        meta: Some(WeatherMeta::new(
+            Provider::Tempest,
+            "observations",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATION: &str = r#"{
+        "station_id": 12345,
+        "station_name": "Backyard",
+        "public_name": "Elm Street",
+        "latitude": 35.2,
+        "longitude": -106.6,
+        "obs": [[1704110400, 0.4, 1.5, 2.9, 241, 3, 838.9, 20.0, 45.6, 12345, 1.2, 103, 0.0, 0, 0, 0, 2.61, 1, null, null, 0, 0]],
+        "status": {"status_code": 0, "status_message": "SUCCESS"}
+    }"#;
+
+    fn station(json: &str) -> TempestStationResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_station_id() {
+        assert_eq!(parse_station_id(" 12345 ").unwrap(), 12345);
+
+        assert!(parse_station_id("").is_err());
+        assert!(parse_station_id("Backyard").is_err());
+        assert!(parse_station_id("-1").is_err());
+    }
+
+    #[test]
+    fn test_observation_format() {
+        let body = station(STATION);
+        let observation = &body.obs.as_ref().unwrap()[0];
+
+        assert_eq!(
+            observation.value(TempestObservation::TIMESTAMP),
+            Some(1704110400.0)
+        );
+        assert_eq!(
+            observation.value(TempestObservation::AIR_TEMPERATURE),
+            Some(20.0)
+        );
+        assert_eq!(
+            observation.value(TempestObservation::RELATIVE_HUMIDITY),
+            Some(45.6)
+        );
+        // Sensors without a reading are `null`, and older formats may have fewer values
+        assert_eq!(observation.value(18), None);
+        assert_eq!(observation.value(100), None);
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let info = to_weather_info(12345, station(STATION)).unwrap();
+
+        assert_eq!(info.city, "Backyard");
+        assert_eq!(info.date, "2024-01-01");
+        assert_eq!(info.temperature, 68.0);
+        assert_eq!(info.humidity, Some(46));
+        // 1.5 m/s
+        assert!((info.wind_speed_mph.unwrap() - 3.355).abs() < 0.01);
+        assert_eq!(info.station_id.as_deref(), Some("12345"));
+        assert!(info.is_from_station());
+    }
+
+    #[test]
+    fn test_missing_observation() {
+        let error = to_weather_info(12345, station(r#"{"station_id": 12345, "obs": null}"#))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("no observation"), "{error}");
+
+        let error = to_weather_info(
+            12345,
+            station(r#"{"obs": [[1704110400, 0.4, 1.5, 2.9, 241, 3, 838.9, null, 45.6]]}"#),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("no air temperature"), "{error}");
+    }
+}
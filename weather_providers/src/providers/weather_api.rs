@@ -1,53 +1,516 @@
 use crate::{
-    WeatherProvider,
+    GeocodingClient, Provider, WeatherProvider,
     common::*,
-    models::{WeatherInfo, weather_api::*},
-    utils::date::*,
+    models::{
+        CloudCoverage, CloudLayer, DataSource, GeoLocation, Icon, PrecipType, ProviderInfo,
+        ProviderOptions, WeatherInfo, WeatherMeta, weather_api::*,
+    },
+    utils::{
+        date::*,
+        http::{FORBIDDEN, HttpClient, check_status_with, parse_json},
+    },
 };
 use ::async_trait::async_trait;
+use ::chrono::DateTime;
 use ::reqwest::Url;
+use ::std::time::Duration;
 use ::tracing::instrument;
 
-#[derive(Debug)]
-pub struct WeatherApiProvider;
+const CURRENT_URL: &str = "https://api.weatherapi.com/v1/current.json";
+const FORECAST_URL: &str = "https://api.weatherapi.com/v1/forecast.json";
+const SEARCH_URL: &str = "https://api.weatherapi.com/v1/search.json";
 
-#[async_trait]
+/// Above this cloud cover (percent), the clouds are reported as a broken layer.
+const BROKEN_CLOUD_COVER: u8 = 50;
+
+/// The spread between the temperature and the dew point (°F) per 1000 ft of cloud base height.
+const DEW_POINT_SPREAD_PER_1000_FT: f32 = 4.4;
+
+#[derive(Debug, Default)]
+pub struct WeatherApiProvider {
+    http: HttpClient,
+}
+
+impl WeatherApiProvider {
+    /// Creates a provider with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl WeatherProvider for WeatherApiProvider {
-    #[instrument(fields(provider_key, address, date))]
-    async fn get_weather(
-        &self,
-        provider_key: Option<&str>,
-        address: &str,
-        date: Option<&str>,
-    ) -> Result<WeatherInfo> {
-        let provider_key = provider_key.ok_or_else(|| {
-            Error::from("'WeatherApi' API key not set. Please set it using: 'weather provider wa --key <API_KEY>'")
-        })?;
-
-        let date = normalize_date(date);
-
-        let url = Url::parse_with_params(
-            "https://api.weatherapi.com/v1/current.json",
+    #[instrument(fields(provider_key, address))]
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let provider_key = require_key(provider_key)?;
+
+        let date = normalize_date(None::<&str>);
+
+        let url = WeatherApiRequests::forecast(provider_key, address, &date)?;
+
+        let response = check_status_with(
+            "WeatherApi",
+            self.http.get("WeatherApi", "forecast", url).await?,
+            decode_error,
+        )
+        .await?;
+        let body = parse_json::<WeatherApiResponse>("WeatherApi", response).await?;
+
+        Ok(to_weather_info(date, body)
+            .with_derived_precip_type()
+            .with_derived_icon())
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let url = WeatherApiRequests::current(require_key(provider_key)?, "London")?;
+
+        self.http.probe("WeatherApi", "current", url).await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://www.weatherapi.com/signup.aspx",
+            key_format: "32-character hexadecimal API key",
+            free_tier: "1,000,000 calls per month",
+            capabilities: &["current", "geocoding"],
+            example: "weather provider wa --key <API_KEY>",
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for WeatherApiProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        let url = WeatherApiRequests::search(require_key(provider_key)?, address)?;
+
+        let response = check_status_with(
+            "WeatherApi",
+            self.http.get("WeatherApi", "search", url).await?,
+            decode_error,
+        )
+        .await?;
+        let body = parse_json::<Vec<WeatherApiSearchResult>>("WeatherApi", response).await?;
+
+        let location = body
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("Location not found: '{address}'"))?;
+
+        Ok(GeoLocation {
+            city: location.name,
+            region: Some(location.region).filter(|region| !region.is_empty()),
+            country: location.country,
+            lat: location.lat,
+            lon: location.lon,
+        })
+    }
+}
+
+/// Builds the URLs of the WeatherAPI requests. Responses carry both imperial and metric
+/// values, so there is no unit to select.
+struct WeatherApiRequests;
+
+impl WeatherApiRequests {
+    /// The URL of the current conditions at `address`.
+    fn current(api_key: &str, address: &str) -> Result<Url> {
+        Ok(Url::parse_with_params(
+            CURRENT_URL,
+            &[("key", api_key), ("q", address), ("aqi", "no")],
+        )
+        .map_err(|e| format!("Failed to build URL: {e}"))?)
+    }
+
+    /// The URL of the current conditions at `address` together with the forecast of `date`.
+    fn forecast(api_key: &str, address: &str, date: &str) -> Result<Url> {
+        Ok(Url::parse_with_params(
+            FORECAST_URL,
             &[
-                ("key", provider_key),
+                ("key", api_key),
                 ("q", address),
-                ("dt", &date),
-                ("aqi", "no"),
+                ("dt", date),
                 ("days", "1"),
+                ("aqi", "no"),
+                ("alerts", "no"),
             ],
         )
-        .map_err(|e| format!("Failed to build URL: {e}"))?;
-
-        let response = reqwest::get(url).await?.error_for_status()?;
-        let body = response.json::<WeatherApiResponse>().await?;
-
-        Ok(WeatherInfo {
-            country: body.location.country,
-            city: body.location.name,
-            date,
-            temperature: body.current.temp_f,
-            humidity: body.current.humidity,
-            description: Some(body.current.condition.text),
+        .map_err(|e| format!("Failed to build URL: {e}"))?)
+    }
+
+    /// The URL of the locations matching `query`.
+    fn search(api_key: &str, query: &str) -> Result<Url> {
+        Ok(
+            Url::parse_with_params(SEARCH_URL, &[("key", api_key), ("q", query)])
+                .map_err(|e| format!("Failed to build URL: {e}"))?,
+        )
+    }
+}
+
+fn to_weather_info(date: String, body: WeatherApiResponse) -> WeatherInfo {
+    let source = DataSource::for_date(&date);
+    let current = body.current;
+    let cloud_layers = cloud_layers(&current);
+    let precip_probability = body
+        .forecast
+        .and_then(|forecast| forecast.forecastday.into_iter().next())
+        .and_then(|forecast_day| forecast_day.day.daily_chance_of_rain)
+        .map(|percent| percent / 100.0);
+    let snow_depth_mm = current.snow_cm.map(|cm| cm * 10.0);
+
+    let precip_type = if current.is_blizzard == Some(1) || snow_depth_mm.is_some_and(|mm| mm > 0.0)
+    {
+        Some(PrecipType::Snow)
+    } else {
+        current.precip_mm.map(|mm| {
+            if mm > 0.0 {
+                PrecipType::Rain
+            } else {
+                PrecipType::None
+            }
         })
+    };
+
+    let mut info = WeatherInfo {
+        country: body.location.country,
+        city: body.location.name,
+        date,
+        observation_time: current
+            .last_updated_epoch
+            .and_then(|epoch| DateTime::from_timestamp(epoch, 0)),
+        temperature: current.temp_f,
+        humidity: Some(current.humidity),
+        description: Some(current.condition.text.clone()),
+        condition_code: current.condition.code.map(u32::from),
+        wind_speed_mph: current.wind_mph,
+        precipitation_mm: current.precip_mm,
+        pressure_hpa: current.pressure_mb,
+        snow_depth_mm,
+        precip_type,
+        cloud_layers,
+        icon: current
+            .condition
+            .code
+            .and_then(|code| Icon::from_weather_api(code, current.is_day != Some(0)))
+            .map(|icon| icon.code().to_string()),
+        provider_icon: current.condition.code.map(|code| code.to_string()),
+        meta: Some(WeatherMeta::new(Provider::WeatherApi, "forecast", source)),
+        ..Default::default()
+    }
+    .with_precip_probability(precip_probability);
+
+    // The altitude of the layer is estimated, not reported
+    if info.cloud_layers.is_some() {
+        info.mark_derived("cloud_layers");
+    }
+    info
+}
+
+/// Derives the cloud layers from the cloud cover, as WeatherAPI reports no layers.
+///
+/// A cover above `BROKEN_CLOUD_COVER` is a single broken layer, whose base is estimated from
+/// the spread between the temperature and the dew point, rounded to hundreds of feet as in
+/// METAR reports.
+///
+/// # Returns
+///
+/// `None` if the cover is not broken, or the cover or the dew point is unknown.
+fn cloud_layers(current: &WeatherApiCurrent) -> Option<Vec<CloudLayer>> {
+    let dew_point = current.dewpoint_f?;
+    if current.cloud? <= BROKEN_CLOUD_COVER {
+        return None;
+    }
+
+    let spread = (current.temp_f - dew_point).max(0.0);
+    let altitude_ft = (spread / DEW_POINT_SPREAD_PER_1000_FT * 10.0).round() as u32 * 100;
+
+    Some(vec![CloudLayer {
+        altitude_ft,
+        coverage: CloudCoverage::Broken,
+    }])
+}
+
+/// Maps a WeatherAPI error body to an error, using the documented error codes.
+fn decode_error(body: &str) -> Option<Error> {
+    let error = serde_json::from_str::<WeatherApiErrorResponse>(body)
+        .ok()?
+        .error;
+
+    Some(match error.code {
+        // Key not provided, invalid or disabled
+        1002 | 2006 | 2008 => Error::InvalidApiKey("WeatherApi".into()),
+        1006 => Error::LocationNotFound("WeatherApi".into()),
+        // Monthly quota exceeded
+        2007 => Error::RateLimited("WeatherApi".into()),
+        2009 => FORBIDDEN.into(),
+        code => format!("WeatherApi error {code}: {}", error.message).into(),
+    })
+}
+
+fn require_key(provider_key: Option<&str>) -> Result<&str> {
+    provider_key.ok_or_else(|| {
+        Error::MissingApiKey("'WeatherApi' API key not set. Please set it using: 'weather provider wa --key <API_KEY>'".into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(url: &Url) -> Vec<(String, String)> {
+        url.query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_current_url() {
+        let url = WeatherApiRequests::current("key", "London").unwrap();
+
+        assert_eq!(url.path(), "/v1/current.json");
+        assert_eq!(url.query(), Some("key=key&q=London&aqi=no"));
+    }
+
+    #[test]
+    fn test_forecast_url() {
+        let url = WeatherApiRequests::forecast("key", "London", "2024-01-15").unwrap();
+
+        assert_eq!(url.path(), "/v1/forecast.json");
+        assert_eq!(
+            url.query(),
+            Some("key=key&q=London&dt=2024-01-15&days=1&aqi=no&alerts=no")
+        );
+    }
+
+    #[test]
+    fn test_url_encoding() {
+        for address in ["Tom & Jerry St", "New York", "Київ", "☔ Manchester 🌧️"] {
+            let current = WeatherApiRequests::current("key", address).unwrap();
+            let search = WeatherApiRequests::search("key", address).unwrap();
+
+            assert_eq!(query(&current)[1], ("q".to_string(), address.to_string()));
+            assert_eq!(query(&search)[1], ("q".to_string(), address.to_string()));
+        }
+
+        let url = WeatherApiRequests::search("key", "Tom & Jerry St").unwrap();
+        assert_eq!(url.path(), "/v1/search.json");
+        assert_eq!(url.query(), Some("key=key&q=Tom+%26+Jerry+St"));
+
+        let url = WeatherApiRequests::search("key", "🌧").unwrap();
+        assert_eq!(url.query(), Some("key=key&q=%F0%9F%8C%A7"));
+    }
+
+    fn error(code: u32, message: &str) -> Option<Error> {
+        decode_error(&format!(
+            r#"{{"error": {{"code": {code}, "message": "{message}"}}}}"#
+        ))
+    }
+
+    #[test]
+    fn test_decode_error_codes() {
+        for code in [1002, 2006, 2008] {
+            assert!(matches!(error(code, "key"), Some(Error::InvalidApiKey(_))));
+        }
+        assert!(matches!(
+            error(1006, "No matching location found."),
+            Some(Error::LocationNotFound(p)) if p == "WeatherApi"
+        ));
+        assert!(matches!(error(2007, "quota"), Some(Error::RateLimited(_))));
+        assert!(matches!(error(2009, "plan"), Some(Error::Any(m)) if m == FORBIDDEN));
+
+        // Unknown codes keep the upstream message
+        assert_eq!(
+            error(9999, "Internal application error.")
+                .unwrap()
+                .to_string(),
+            "WeatherApi error 9999: Internal application error."
+        );
+    }
+
+    #[test]
+    fn test_decode_error_unknown_shape() {
+        assert!(decode_error("<html>Bad Gateway</html>").is_none());
+        assert!(decode_error(r#"{"message": "no envelope"}"#).is_none());
+        assert!(decode_error("").is_none());
+    }
+
+    fn response(current: &str) -> WeatherApiResponse {
+        serde_json::from_str(&format!(
+            r#"{{
+                "location": {{"name": "Oslo", "country": "Norway"}},
+                "current": {{
+                    "temp_f": 28.4,
+                    "humidity": 93,
+                    "condition": {{"text": "Light snow"}}
+                    {current}
+                }}
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    fn precip(current: &str) -> (Option<PrecipType>, Option<f32>) {
+        let info = to_weather_info("2024-01-15".to_string(), response(current));
+        (info.precip_type, info.snow_depth_mm)
+    }
+
+    #[test]
+    fn test_snow_and_precipitation_type() {
+        assert_eq!(
+            precip(r#", "precip_mm": 0.8, "snow_cm": 1.2"#),
+            (Some(PrecipType::Snow), Some(12.0))
+        );
+        assert_eq!(
+            precip(r#", "precip_mm": 0.0, "is_blizzard": 1"#),
+            (Some(PrecipType::Snow), None)
+        );
+        assert_eq!(
+            precip(r#", "precip_mm": 2.5, "snow_cm": 0.0"#),
+            (Some(PrecipType::Rain), Some(0.0))
+        );
+        assert_eq!(
+            precip(r#", "precip_mm": 0.0"#),
+            (Some(PrecipType::None), None)
+        );
+        assert_eq!(precip(""), (None, None));
+    }
+
+    #[test]
+    fn test_precip_probability() {
+        let body = |chance: serde_json::Value| {
+            serde_json::from_value(serde_json::json!({
+                "location": {"name": "Oslo", "country": "Norway"},
+                "current": {"temp_f": 28.4, "humidity": 93, "condition": {"text": "Light snow"}},
+                "forecast": {"forecastday": [{"day": {"daily_chance_of_rain": chance}}]}
+            }))
+            .unwrap()
+        };
+        let probability = |body| {
+            let info = to_weather_info("2024-01-15".to_string(), body);
+            (info.precip_probability, info.precip_probability_pct())
+        };
+
+        assert_eq!(probability(body(0.into())), (Some(0.0), Some(0)));
+        assert_eq!(probability(body(50.into())), (Some(0.5), Some(50)));
+        assert_eq!(probability(body(100.into())), (Some(1.0), Some(100)));
+        // current.json has no forecast
+        assert_eq!(probability(response("")), (None, None));
+    }
+
+    #[test]
+    fn test_pressure() {
+        let info = to_weather_info(
+            "2024-01-15".to_string(),
+            response(r#", "pressure_mb": 1016.0"#),
+        );
+
+        assert_eq!(info.pressure_hpa, Some(1016.0));
+        assert_eq!(info.pressure_tendency, None);
+    }
+
+    #[test]
+    fn test_observation_time() {
+        let info = to_weather_info(
+            "2024-01-15".to_string(),
+            response(r#", "last_updated_epoch": 1705327200"#),
+        );
+        assert_eq!(
+            info.observation_time.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2024-01-15T14:00:00+00:00")
+        );
+
+        let info = to_weather_info("2024-01-15".to_string(), response(""));
+        assert_eq!(info.observation_time, None);
+    }
+
+    #[test]
+    fn test_wind_speed() {
+        let info = to_weather_info(
+            "2024-01-15".to_string(),
+            response(r#", "wind_mph": 7.4, "wind_kph": 11.9"#),
+        );
+
+        assert_eq!(info.wind_speed_mph, Some(7.4));
+    }
+
+    #[test]
+    fn test_cloud_layers() {
+        let clouds = |current: &str| {
+            let info = to_weather_info("2024-01-15".to_string(), response(current));
+            (info.ceiling_ft(), info.cloud_layers)
+        };
+
+        // 28.4 °F with a dew point of 19.6 °F: a base at 2000 ft
+        assert_eq!(
+            clouds(r#", "cloud": 75, "dewpoint_f": 19.6"#),
+            (
+                Some(2000),
+                Some(vec![CloudLayer {
+                    altitude_ft: 2000,
+                    coverage: CloudCoverage::Broken
+                }])
+            )
+        );
+        // Saturated air: the clouds reach the ground
+        assert_eq!(clouds(r#", "cloud": 100, "dewpoint_f": 28.4"#).0, Some(0));
+        assert_eq!(clouds(r#", "cloud": 50, "dewpoint_f": 19.6"#), (None, None));
+        assert_eq!(clouds(r#", "cloud": 75"#), (None, None));
+        assert_eq!(clouds(""), (None, None));
+    }
+
+    #[test]
+    fn test_cloud_layers_are_derived() {
+        let derived = |current: &str| {
+            to_weather_info("2024-01-15".to_string(), response(current))
+                .meta
+                .unwrap()
+                .derived
+        };
+
+        assert_eq!(
+            derived(r#", "cloud": 75, "dewpoint_f": 19.6"#),
+            ["cloud_layers"]
+        );
+        assert!(derived(r#", "cloud": 20, "dewpoint_f": 19.6"#).is_empty());
+    }
+
+    #[test]
+    fn test_icon() {
+        let icons = |condition: serde_json::Value, is_day: u8| {
+            let body = serde_json::json!({
+                "location": {"name": "Oslo", "country": "Norway"},
+                "current": {"temp_f": 28.4, "humidity": 93, "is_day": is_day, "condition": condition}
+            });
+            let info = to_weather_info(
+                "2024-01-15".to_string(),
+                serde_json::from_value(body).unwrap(),
+            )
+            .with_derived_icon();
+
+            assert_eq!(
+                info.condition_code.map(|c| c.to_string()),
+                info.provider_icon
+            );
+            (info.icon, info.provider_icon)
+        };
+
+        assert_eq!(
+            icons(serde_json::json!({"text": "Light snow", "code": 1213}), 0),
+            (Some("snow".to_string()), Some("1213".to_string()))
+        );
+        assert_eq!(
+            icons(serde_json::json!({"text": "Clear", "code": 1000}), 0),
+            (Some("clear-night".to_string()), Some("1000".to_string()))
+        );
+
+        // Without a known code, the icon is derived from the description
+        assert_eq!(
+            icons(serde_json::json!({"text": "Patchy rain nearby"}), 1),
+            (Some("rain".to_string()), None)
+        );
     }
 }
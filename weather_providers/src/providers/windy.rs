@@ -0,0 +1,358 @@
+use crate::{
+    ForecastProvider, GeocodingClient, Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, GeoLocation, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta, WindUnit,
+        windy::*,
+    },
+    providers::OpenMeteoGeocoder,
+    utils::{
+        clock::{Clock, SystemClock},
+        date::normalize_date,
+        http::{HttpClient, check_status, parse_json},
+        units::{humidity_percent, kelvin_to_fahrenheit},
+    },
+};
+use ::async_trait::async_trait;
+use ::chrono::{DateTime, NaiveDate, NaiveTime};
+use ::std::time::Duration;
+use ::tracing::instrument;
+
+const POINT_FORECAST_URL: &str = "https://api.windy.com/api/point-forecast/v2";
+
+/// The numerical weather prediction model of the forecast.
+const MODEL: &str = "gfs";
+const PARAMETERS: &[&str] = &["temp", "rh", "wind"];
+const LEVELS: &[&str] = &["surface"];
+
+/// How far (ms) the requested time may lie outside the forecast steps, which are 3 hours apart.
+const MAX_STEP_DISTANCE_MS: i64 = 3 * 60 * 60 * 1000;
+
+/// Forecasts of the GFS model from the Windy.com Point Forecast API.
+///
+/// The forecast step nearest to the current time is reported for today, and the one nearest
+/// to noon (UTC) for later dates, up to the end of the forecast (about 10 days). Addresses
+/// are resolved with Open-Meteo geocoding.
+#[derive(Debug, Default)]
+pub struct WindyProvider {
+    http: HttpClient,
+    geocoder: OpenMeteoGeocoder,
+}
+
+impl WindyProvider {
+    /// Creates a provider with the connection settings of `options`, also used for geocoding.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+            geocoder: OpenMeteoGeocoder::with_options(options),
+        }
+    }
+
+    /// Requests the forecast series at the coordinates.
+    async fn forecast(&self, api_key: &str, lat: f64, lon: f64) -> Result<WindyResponse> {
+        let request = self
+            .http
+            .client("Windy")?
+            .post(POINT_FORECAST_URL)
+            .json(&forecast_request(api_key, lat, lon));
+        let response = check_status(
+            "Windy",
+            self.http.send("Windy", "point_forecast", request).await?,
+        )
+        .await?;
+
+        parse_json("Windy", response).await
+    }
+
+    /// Returns the weather of `address` on `date` (today if `None`).
+    #[instrument(skip(self, provider_key))]
+    async fn weather_on(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: Option<&str>,
+    ) -> Result<WeatherInfo> {
+        let api_key = require_key(provider_key)?;
+        let target = target_timestamp(date, &SystemClock)?;
+        let location = self.geocode(None, address).await?;
+
+        let forecast = self.forecast(api_key, location.lat, location.lon).await?;
+
+        to_weather_info(location, &forecast, target)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for WindyProvider {
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, None).await
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let request = self
+            .http
+            .client("Windy")?
+            .post(POINT_FORECAST_URL)
+            .json(&forecast_request(
+                require_key(provider_key)?,
+                51.5072,
+                -0.1276,
+            ));
+
+        self.http
+            .probe_request("Windy", "point_forecast", request)
+            .await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://api.windy.com/keys",
+            key_format: "The Point Forecast API key from the Windy API console",
+            free_tier: "Trial keys for development only, returning shuffled data",
+            capabilities: &["current", "forecast", "geocoding"],
+            example: "weather get Lisbon --date-relative +3d --provider wd",
+        }
+    }
+
+    fn as_forecast(&self) -> Option<&dyn ForecastProvider> {
+        Some(self)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ForecastProvider for WindyProvider {
+    async fn get_forecast(
+        &self,
+        provider_key: Option<&str>,
+        address: &str,
+        date: &str,
+    ) -> Result<WeatherInfo> {
+        self.weather_on(provider_key, address, Some(date)).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GeocodingClient for WindyProvider {
+    async fn geocode(&self, provider_key: Option<&str>, address: &str) -> Result<GeoLocation> {
+        self.geocoder.geocode(provider_key, address).await
+    }
+}
+
+fn require_key(provider_key: Option<&str>) -> Result<&str> {
+    provider_key.ok_or_else(|| {
+        Error::MissingApiKey(
+            "'Windy' API key not set. Please set it using: 'weather provider wd --key <API_KEY>'"
+                .into(),
+        )
+    })
+}
+
+fn forecast_request(api_key: &str, lat: f64, lon: f64) -> WindyRequest<'_> {
+    WindyRequest {
+        lat,
+        lon,
+        model: MODEL,
+        parameters: PARAMETERS,
+        levels: LEVELS,
+        key: api_key,
+    }
+}
+
+/// Returns the time (Unix timestamp in ms) to report: now for today or `None`, otherwise
+/// noon (UTC) of the date.
+///
+/// # Errors
+///
+/// Returns `Error::UnsupportedDate` for past dates, which a forecast does not cover.
+fn target_timestamp(date: Option<&str>, clock: &dyn Clock) -> Result<i64> {
+    let today = clock.today_in(None);
+    let date = match date {
+        Some(date) => NaiveDate::parse_from_str(&normalize_date(Some(date)), "%Y-%m-%d")
+            .map_err(|e| format!("Invalid date '{date}': {e}"))?,
+        None => today,
+    };
+
+    if date < today {
+        return Err(Error::UnsupportedDate {
+            provider: "Windy".into(),
+            date: date.to_string(),
+            reason: "forecasts start today".into(),
+        });
+    }
+
+    if date == today {
+        return Ok(clock.now().timestamp_millis());
+    }
+
+    Ok(date
+        .and_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap_or_default())
+        .and_utc()
+        .timestamp_millis())
+}
+
+/// Returns the index of the forecast step nearest to `target`.
+///
+/// # Errors
+///
+/// Returns `Error::UnsupportedDate` if `target` is beyond the end of the forecast.
+fn nearest_step(ts: &[i64], target: i64) -> Result<usize> {
+    ts.iter()
+        .enumerate()
+        .min_by_key(|(_, t)| (*t - target).abs())
+        .filter(|(_, t)| (*t - target).abs() <= MAX_STEP_DISTANCE_MS)
+        .map(|(index, _)| index)
+        .ok_or_else(|| Error::UnsupportedDate {
+            provider: "Windy".into(),
+            date: DateTime::from_timestamp_millis(target)
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            reason: "the date is beyond the end of the forecast".into(),
+        })
+}
+
+fn to_weather_info(
+    location: GeoLocation,
+    forecast: &WindyResponse,
+    target: i64,
+) -> Result<WeatherInfo> {
+    let step = nearest_step(&forecast.ts, target)?;
+    let value = |series: &[Option<f32>]| series.get(step).copied().flatten();
+
+    let temperature = value(&forecast.temp)
+        .ok_or_else(|| Error::from("Windy forecast has no temperature for the date"))?;
+    let wind_speed_mph = value(&forecast.wind_u)
+        .zip(value(&forecast.wind_v))
+        .map(|(u, v)| WindUnit::MetersPerSecond.to_mph(u.hypot(v)));
+
+    Ok(WeatherInfo {
+        country: location.country,
+        city: location.city,
+        date: DateTime::from_timestamp_millis(forecast.ts[step])
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+        temperature: kelvin_to_fahrenheit(temperature),
+        humidity: value(&forecast.rh).map(humidity_percent),
+        description: Some(format!("{} model forecast", MODEL.to_uppercase())),
+        wind_speed_mph,
+        meta: Some(WeatherMeta::new(
+            Provider::Windy,
+            "point_forecast",
+            DataSource::Forecast,
+        )),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::FixedClock;
+    use ::chrono::{TimeZone, Utc};
+
+    /// Three steps from 2024-03-20 12:00 UTC, 3 hours apart.
+    const FORECAST: &str = r#"{
+        "ts": [1710936000000, 1710946800000, 1710957600000],
+        "units": {
+            "temp-surface": "K",
+            "rh-surface": "%",
+            "wind_u-surface": "m*s-1",
+            "wind_v-surface": "m*s-1"
+        },
+        "temp-surface": [288.15, 285.5, null],
+        "rh-surface": [62.4, 71.0, 80.0],
+        "wind_u-surface": [3.0, -1.2, 0.5],
+        "wind_v-surface": [4.0, 2.2, 0.1],
+        "warning": "The trial API version is for development purposes only."
+    }"#;
+
+    fn clock() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2024, 3, 20, 13, 0, 0).unwrap())
+    }
+
+    fn location() -> GeoLocation {
+        GeoLocation {
+            city: "Lisbon".to_string(),
+            country: "Portugal".to_string(),
+            lat: 38.7167,
+            lon: -9.1333,
+            ..GeoLocation::default()
+        }
+    }
+
+    #[test]
+    fn test_forecast_request() {
+        let body = serde_json::to_value(forecast_request("my-key", 38.7167, -9.1333)).unwrap();
+
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "lat": 38.7167,
+                "lon": -9.1333,
+                "model": "gfs",
+                "parameters": ["temp", "rh", "wind"],
+                "levels": ["surface"],
+                "key": "my-key"
+            })
+        );
+    }
+
+    #[test]
+    fn test_target_timestamp() {
+        assert_eq!(target_timestamp(None, &clock()).unwrap(), 1710939600000);
+        assert_eq!(
+            target_timestamp(Some("2024-03-22"), &clock()).unwrap(),
+            1711108800000
+        );
+
+        let error = target_timestamp(Some("2024-03-19"), &clock()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "'Windy' has no data for 2024-03-19: forecasts start today"
+        );
+    }
+
+    #[test]
+    fn test_nearest_step() {
+        let ts = [1710936000000, 1710946800000, 1710957600000];
+
+        assert_eq!(nearest_step(&ts, 1710939600000).unwrap(), 0);
+        assert_eq!(nearest_step(&ts, 1710945000000).unwrap(), 1);
+        assert_eq!(nearest_step(&ts, 1710960000000).unwrap(), 2);
+        assert!(matches!(
+            nearest_step(&ts, 1711108800000),
+            Err(Error::UnsupportedDate { .. })
+        ));
+        assert!(nearest_step(&[], 1710936000000).is_err());
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let forecast = serde_json::from_str::<WindyResponse>(FORECAST).unwrap();
+
+        let info = to_weather_info(location(), &forecast, 1710939600000).unwrap();
+
+        assert_eq!(info.city, "Lisbon");
+        assert_eq!(info.country, "Portugal");
+        assert_eq!(info.date, "2024-03-20");
+        assert!(
+            (info.temperature - 59.0).abs() < 0.01,
+            "{}",
+            info.temperature
+        );
+        assert_eq!(info.humidity, Some(62));
+        // A 3-4-5 triangle: 5 m/s
+        assert!((info.wind_speed_mph.unwrap() - 11.18).abs() < 0.01);
+        assert_eq!(info.description.as_deref(), Some("GFS model forecast"));
+    }
+
+    #[test]
+    fn test_missing_temperature() {
+        let forecast = serde_json::from_str::<WindyResponse>(FORECAST).unwrap();
+
+        assert!(to_weather_info(location(), &forecast, 1710957600000).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{
+    Provider, WeatherProvider,
+    common::*,
+    models::{
+        DataSource, ProviderInfo, ProviderOptions, WeatherInfo, WeatherMeta,
+        world_weather_online::*,
+    },
+    utils::{
+        date::normalize_date,
+        http::{HttpClient, check_status_with, parse_json},
+    },
+};
+use ::async_trait::async_trait;
+use ::reqwest::Url;
+use ::std::{str::FromStr, time::Duration};
+use ::tracing::instrument;
+
+const WEATHER_URL: &str = "https://api.worldweatheronline.com/premium/v1/weather.ashx";
+
+/// Current conditions from the premium WorldWeatherOnline API.
+///
+/// The API resolves the address itself and reports the area it matched, so no geocoding is
+/// needed. Only the current conditions are requested, whatever the date.
+#[derive(Debug, Default)]
+pub struct WorldWeatherOnlineProvider {
+    http: HttpClient,
+}
+
+impl WorldWeatherOnlineProvider {
+    /// Creates a provider with the connection settings of `options`.
+    pub fn with_options(options: &ProviderOptions) -> Self {
+        Self {
+            http: HttpClient::new(options),
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl WeatherProvider for WorldWeatherOnlineProvider {
+    #[instrument(skip(self, provider_key))]
+    async fn get_weather(&self, provider_key: Option<&str>, address: &str) -> Result<WeatherInfo> {
+        let url = weather_url(require_key(provider_key)?, address)?;
+
+        let response = check_status_with(
+            "WorldWeatherOnline",
+            self.http.get("WorldWeatherOnline", "weather", url).await?,
+            decode_error,
+        )
+        .await?;
+        let body = parse_json::<WorldWeatherOnlineResponse>("WorldWeatherOnline", response).await?;
+
+        Ok(to_weather_info(normalize_date(None::<&str>), body)?
+            .with_derived_precip_type()
+            .with_derived_icon())
+    }
+
+    #[instrument(skip(self, provider_key))]
+    async fn health_check(&self, provider_key: Option<&str>) -> Result<Duration> {
+        let url = weather_url(require_key(provider_key)?, "London")?;
+
+        self.http.probe("WorldWeatherOnline", "weather", url).await
+    }
+
+    fn info(&self) -> ProviderInfo {
+        ProviderInfo {
+            signup_url: "https://www.worldweatheronline.com/weather-api/signup.aspx",
+            key_format: "31-character hexadecimal API key",
+            free_tier: "A 30-day trial with 500 calls per day",
+            capabilities: &["current"],
+            example: "weather get Madrid --provider wwo",
+        }
+    }
+}
+
+fn require_key(provider_key: Option<&str>) -> Result<&str> {
+    provider_key.ok_or_else(|| {
+        Error::MissingApiKey(
+            "'WorldWeatherOnline' API key not set. \
+             Please set it using: 'weather provider wwo --key <API_KEY>'"
+                .into(),
+        )
+    })
+}
+
+/// Builds the URL of the current conditions, without the forecast, at `address`.
+fn weather_url(api_key: &str, address: &str) -> Result<Url> {
+    Ok(Url::parse_with_params(
+        WEATHER_URL,
+        &[
+            ("key", api_key),
+            ("q", address),
+            ("format", "json"),
+            ("fx", "no"),
+            ("mca", "no"),
+            ("includelocation", "yes"),
+        ],
+    )
+    .map_err(|e| format!("Failed to build URL: {e}"))?)
+}
+
+fn to_weather_info(date: String, body: WorldWeatherOnlineResponse) -> Result<WeatherInfo> {
+    let data = body.data;
+    if let Some(error) = data.error.into_iter().next() {
+        return Err(error_from_message(error.msg));
+    }
+
+    let current = data
+        .current_condition
+        .into_iter()
+        .next()
+        .ok_or("'WorldWeatherOnline' returned no current conditions")?;
+    let (city, country) = data
+        .nearest_area
+        .into_iter()
+        .next()
+        .map(|area| (first_value(area.area_name), first_value(area.country)))
+        .unwrap_or_default();
+
+    Ok(WeatherInfo {
+        country: country.unwrap_or_default(),
+        city: city.unwrap_or_default(),
+        date,
+        temperature: parse("temperature", &current.temp_f)?,
+        humidity: Some(parse("humidity", &current.humidity)?),
+        description: first_value(current.weather_desc),
+        // The WWO codes overlap the OpenWeather codes `condition_emoji` looks up (e.g. 200 is
+        // "Thundery outbreaks" here, not a thunderstorm with light rain), so they are left out
+        condition_code: None,
+        wind_speed_mph: parse_optional(current.windspeed_miles),
+        precipitation_mm: parse_optional(current.precip_mm),
+        feels_like: parse_optional(current.feels_like_f),
+        pressure_hpa: parse_optional(current.pressure),
+        meta: Some(WeatherMeta::new(
+            Provider::WorldWeatherOnline,
+            "weather",
+            DataSource::Observed,
+        )),
+        ..Default::default()
+    })
+}
+
+fn first_value(values: Vec<WorldWeatherOnlineValue>) -> Option<String> {
+    values.into_iter().next().map(|v| v.value)
+}
+
+/// Parses a number sent as a string.
+///
+/// # Errors
+///
+/// Returns an error naming the `field` if `value` is not a number.
+fn parse<T: FromStr>(field: &str, value: &str) -> Result<T> {
+    value.trim().parse().map_err(|_| {
+        Error::from(format!(
+            "'WorldWeatherOnline' returned an invalid {field}: '{value}'"
+        ))
+    })
+}
+
+fn parse_optional<T: FromStr>(value: Option<String>) -> Option<T> {
+    value?.trim().parse().ok()
+}
+
+/// Maps a WorldWeatherOnline error body to an error.
+fn decode_error(body: &str) -> Option<Error> {
+    let error = serde_json::from_str::<WorldWeatherOnlineResponse>(body)
+        .ok()?
+        .data
+        .error
+        .into_iter()
+        .next()?;
+
+    Some(error_from_message(error.msg))
+}
+
+/// Maps an error message to an error. The API has no error codes, so the documented
+/// messages are matched instead.
+fn error_from_message(message: String) -> Error {
+    let lowercase = message.to_lowercase();
+
+    if lowercase.contains("api key") && lowercase.contains("limit") {
+        Error::RateLimited("WorldWeatherOnline".into())
+    } else if lowercase.contains("api key") {
+        Error::InvalidApiKey("WorldWeatherOnline".into())
+    } else if lowercase.contains("unable to find any matching weather location") {
+        Error::LocationNotFound("WorldWeatherOnline".into())
+    } else {
+        format!("WorldWeatherOnline error: {message}").into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> WorldWeatherOnlineResponse {
+        serde_json::from_str(include_str!(
+            "../../tests/fixtures/world_weather_online_current.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fixture_deserializes() {
+        let data = fixture().data;
+
+        assert!(data.error.is_empty());
+        assert_eq!(data.current_condition.len(), 1);
+        assert_eq!(data.current_condition[0].temp_f, "59");
+        assert_eq!(
+            data.current_condition[0].weather_desc[0].value,
+            "Partly cloudy"
+        );
+        assert_eq!(data.nearest_area[0].area_name[0].value, "London");
+        assert_eq!(data.nearest_area[0].country[0].value, "United Kingdom");
+    }
+
+    #[test]
+    fn test_to_weather_info() {
+        let info = to_weather_info("2024-01-15".to_string(), fixture()).unwrap();
+
+        assert_eq!(info.city, "London");
+        assert_eq!(info.country, "United Kingdom");
+        assert_eq!(info.date, "2024-01-15");
+        assert_eq!(info.temperature, 59.0);
+        assert_eq!(info.humidity, Some(72));
+        assert_eq!(info.description.as_deref(), Some("Partly cloudy"));
+        assert_eq!(info.condition_code, None);
+        assert_eq!(info.feels_like, Some(57.0));
+        assert_eq!(info.wind_speed_mph, Some(7.0));
+        assert_eq!(info.precipitation_mm, Some(0.1));
+        assert_eq!(info.pressure_hpa, Some(1015.0));
+
+        let meta = info.meta.as_ref().unwrap();
+        assert_eq!(meta.provider, "wwo");
+        assert_eq!(meta.endpoint, "weather");
+        assert_eq!(meta.source, DataSource::Observed);
+        assert!(meta.derived.is_empty());
+
+        // The icon is derived from the description, as the API reports none
+        let info = info.with_derived_icon();
+        assert_eq!(info.icon.as_deref(), Some("partly-cloudy-day"));
+        assert_eq!(info.meta.unwrap().derived, ["icon"]);
+    }
+
+    #[test]
+    fn test_minimal_response() {
+        // Only the mapped fields are required; the area is missing without `includelocation`
+        let body = serde_json::from_str(
+            r#"{"data": {"current_condition": [{
+                "temp_F": "28", "humidity": "93", "weatherDesc": [{"value": "Light snow"}]
+            }]}}"#,
+        )
+        .unwrap();
+        let info = to_weather_info("2024-01-15".to_string(), body).unwrap();
+
+        assert_eq!(info.temperature, 28.0);
+        assert_eq!(info.humidity, Some(93));
+        assert_eq!(info.description.as_deref(), Some("Light snow"));
+        assert_eq!(info.city, "");
+        assert_eq!(info.wind_speed_mph, None);
+
+        let body = serde_json::from_str(
+            r#"{"data": {"current_condition": [{"temp_F": "n/a", "humidity": "93"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            to_weather_info("2024-01-15".to_string(), body)
+                .unwrap_err()
+                .to_string(),
+            "'WorldWeatherOnline' returned an invalid temperature: 'n/a'"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let error =
+            |msg: &str| decode_error(&format!(r#"{{"data": {{"error": [{{"msg": "{msg}"}}]}}}}"#));
+
+        assert!(matches!(
+            error("Unable to find any matching weather location to the query submitted!"),
+            Some(Error::LocationNotFound(p)) if p == "WorldWeatherOnline"
+        ));
+        assert!(matches!(
+            error("API key is invalid"),
+            Some(Error::InvalidApiKey(_))
+        ));
+        assert!(matches!(
+            error("API key has reached calls per day allowed limit."),
+            Some(Error::RateLimited(_))
+        ));
+        assert_eq!(
+            error("Internal error").unwrap().to_string(),
+            "WorldWeatherOnline error: Internal error"
+        );
+        assert!(decode_error("<html>Bad Gateway</html>").is_none());
+        assert!(decode_error(r#"{"data": {}}"#).is_none());
+
+        // The API also reports errors with a successful status
+        let body = serde_json::from_str(r#"{"data": {"error": [{"msg": "API key is invalid"}]}}"#)
+            .unwrap();
+        assert!(matches!(
+            to_weather_info("2024-01-15".to_string(), body),
+            Err(Error::InvalidApiKey(_))
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+//! # Provider Registry
+//!
+//! Maps provider IDs to shared provider instances, so applications can select built-in and
+//! their own `WeatherProvider` implementations by ID at runtime.
+
+use crate::{Provider, WeatherProvider, create_provider};
+use ::clap::ValueEnum;
+use ::std::{collections::BTreeMap, sync::Arc};
+
+/// A set of weather providers addressable by ID.
+///
+/// Providers are stored as `Arc`, so a provider obtained from the registry can be shared
+/// between tasks.
+///
+/// # Examples
+///
+/// ```rust
+/// use weather_providers::{ProviderRegistry, Result};
+///
+/// async fn weather() -> Result<()> {
+///     let registry = ProviderRegistry::with_builtin();
+///     let provider = registry.get("mock").expect("the mock provider is built in");
+///
+///     println!("{}", provider.get_weather(None, "London").await?);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Default, Clone)]
+pub struct ProviderRegistry {
+    providers: BTreeMap<String, Arc<dyn WeatherProvider>>,
+}
+
+impl ProviderRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry containing every built-in provider under its ID (e.g., "ow").
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        for provider in Provider::value_variants() {
+            registry.register(provider.id(), Arc::from(create_provider(provider.clone())));
+        }
+        registry
+    }
+
+    /// Registers a provider under the given ID.
+    ///
+    /// # Returns
+    ///
+    /// The provider previously registered under the ID, if any.
+    pub fn register(
+        &mut self,
+        id: impl Into<String>,
+        provider: Arc<dyn WeatherProvider>,
+    ) -> Option<Arc<dyn WeatherProvider>> {
+        self.providers.insert(id.into(), provider)
+    }
+
+    /// Returns the provider registered under the given ID.
+    pub fn get(&self, id: &str) -> Option<Arc<dyn WeatherProvider>> {
+        self.providers.get(id).cloned()
+    }
+
+    /// Returns the IDs of all registered providers in alphabetical order.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.providers.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_builtin() {
+        let registry = ProviderRegistry::with_builtin();
+
+        for provider in Provider::value_variants() {
+            assert!(registry.get(provider.id()).is_some());
+        }
+        assert!(registry.get("unknown").is_none());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn test_register_replaces() {
+        use crate::providers::MockProvider;
+
+        let mut registry = ProviderRegistry::new();
+
+        assert!(
+            registry
+                .register("custom", Arc::new(MockProvider::default()))
+                .is_none()
+        );
+        assert!(
+            registry
+                .register("custom", Arc::new(MockProvider::default()))
+                .is_some()
+        );
+        assert_eq!(registry.ids().collect::<Vec<_>>(), ["custom"]);
+
+        let provider = registry.get("custom").unwrap();
+        let info = provider.get_weather(None, "London").await.unwrap();
+        assert_eq!(info.city, "Mock City");
+    }
+}
@@ -0,0 +1,63 @@
+//! # Air Quality Index
+//!
+//! Air quality providers reporting PM2.5 concentrations (e.g., PurpleAir, OpenAQ, Open-Meteo)
+//! map them to the US EPA Air Quality Index with `pm25_aqi`.
+
+/// The US EPA PM2.5 breakpoints (2024 revision): concentration range in μg/m³ and the
+/// corresponding index range.
+const PM25_BREAKPOINTS: [(f32, f32, u16, u16); 6] = [
+    (0.0, 9.0, 0, 50),
+    (9.1, 35.4, 51, 100),
+    (35.5, 55.4, 101, 150),
+    (55.5, 125.4, 151, 200),
+    (125.5, 225.4, 201, 300),
+    (225.5, 325.4, 301, 500),
+];
+
+/// Computes the US EPA Air Quality Index of a 24-hour PM2.5 concentration in μg/m³.
+///
+/// The concentration is truncated to one decimal place, as the EPA specifies. Concentrations
+/// above the highest breakpoint map to the top of the scale (500).
+///
+/// # Returns
+///
+/// `None` if the concentration is negative or not a number.
+pub fn pm25_aqi(concentration: f32) -> Option<u16> {
+    if concentration.is_nan() || concentration < 0.0 {
+        return None;
+    }
+
+    let concentration = (concentration * 10.0).floor() / 10.0;
+    let Some(&(c_low, c_high, i_low, i_high)) = PM25_BREAKPOINTS
+        .iter()
+        .find(|(_, c_high, _, _)| concentration <= *c_high)
+    else {
+        return Some(500);
+    };
+
+    let index =
+        f32::from(i_high - i_low) / (c_high - c_low) * (concentration - c_low) + f32::from(i_low);
+
+    Some(index.round() as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pm25_aqi_breakpoints() {
+        assert_eq!(pm25_aqi(0.0), Some(0));
+        assert_eq!(pm25_aqi(9.0), Some(50));
+        assert_eq!(pm25_aqi(9.1), Some(51));
+        assert_eq!(pm25_aqi(12.04), Some(56));
+        assert_eq!(pm25_aqi(35.49), Some(100));
+        assert_eq!(pm25_aqi(55.5), Some(151));
+        assert_eq!(pm25_aqi(225.5), Some(301));
+        assert_eq!(pm25_aqi(325.4), Some(500));
+        assert_eq!(pm25_aqi(600.0), Some(500));
+
+        assert_eq!(pm25_aqi(-1.0), None);
+        assert_eq!(pm25_aqi(f32::NAN), None);
+    }
+}
@@ -0,0 +1,93 @@
+//! # Clock
+//!
+//! Provides the current time to the date utilities. Calendar dates such as "today" are
+//! resolved in the user's timezone (the system local timezone by default), not in UTC, so
+//! users far from UTC get the expected day around midnight. Tests inject a `FixedClock`.
+
+use ::chrono::{DateTime, Local, NaiveDate, Utc};
+use ::chrono_tz::Tz;
+
+/// A monotonic instant for measuring latencies and expiries.
+///
+/// `std::time::Instant` panics on `wasm32-unknown-unknown`, so the browser's clock is used there.
+#[cfg(not(target_arch = "wasm32"))]
+pub use ::std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub use ::web_time::Instant;
+
+/// A source of the current time.
+pub trait Clock: Send + Sync {
+    /// Returns the current instant.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Returns the current calendar date in `tz`, or in the system local timezone if `None`.
+    fn today_in(&self, tz: Option<Tz>) -> NaiveDate {
+        let now = self.now();
+
+        match tz {
+            Some(tz) => now.with_timezone(&tz).date_naive(),
+            None => now.with_timezone(&Local).date_naive(),
+        }
+    }
+}
+
+/// The system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock frozen at a fixed instant, for tests and reproducible runs.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::chrono::TimeZone;
+
+    #[test]
+    fn test_today_across_the_date_line() {
+        // 11:30 UTC is already the next day in UTC+13 and still the same day in UTC-11
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 1, 15, 11, 30, 0).unwrap());
+
+        assert_eq!(
+            clock.today_in(Some(Tz::Pacific__Tongatapu)),
+            NaiveDate::from_ymd_opt(2024, 1, 16).unwrap()
+        );
+        assert_eq!(
+            clock.today_in(Some(Tz::Pacific__Pago_Pago)),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+        assert_eq!(
+            clock.today_in(Some(Tz::UTC)),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_midnight_boundary() {
+        // One second before and at local midnight in UTC+13 (10:59:59 / 11:00:00 UTC)
+        let before = FixedClock(Utc.with_ymd_and_hms(2024, 1, 15, 10, 59, 59).unwrap());
+        let after = FixedClock(Utc.with_ymd_and_hms(2024, 1, 15, 11, 0, 0).unwrap());
+
+        assert_eq!(
+            before.today_in(Some(Tz::Pacific__Tongatapu)),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+        assert_eq!(
+            after.today_in(Some(Tz::Pacific__Tongatapu)),
+            NaiveDate::from_ymd_opt(2024, 1, 16).unwrap()
+        );
+    }
+}
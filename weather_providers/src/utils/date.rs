@@ -3,7 +3,9 @@
 //! This module contains general utility functions used across the application,
 //! primarily focusing on date parsing and formatting helpers.
 
-use ::chrono::{NaiveDate, Utc};
+use super::clock::{Clock, SystemClock};
+use crate::common::*;
+use ::chrono::NaiveDate;
 
 /// A list of supported date formats used when attempting to parse a date string.
 ///
@@ -38,10 +40,28 @@ fn parse_date_with_unknown_format(date_str: impl AsRef<str>) -> Option<NaiveDate
         .find_map(|&format| NaiveDate::parse_from_str(date_str, format).ok())
 }
 
+/// Parses a date string in any of the supported formats (see `POSSIBLE_FORMATS`).
+///
+/// Unlike `normalize_date`, an unparsable date is an error rather than today, so a mistyped
+/// date is never mistaken for a request of today's weather.
+///
+/// # Errors
+///
+/// Returns an error listing the supported formats if `date_str` matches none of them.
+pub fn parse_date(date_str: &str) -> Result<NaiveDate> {
+    parse_date_with_unknown_format(date_str).ok_or_else(|| {
+        format!(
+            "Invalid date: '{date_str}'. Supported formats: YYYY-MM-DD, DD.MM.YYYY, \
+             MM/DD/YYYY, DD-MM-YYYY, DD Mon YYYY, YYYY/MM/DD"
+        )
+        .into()
+    })
+}
+
 /// Normalizes a date string to the ISO 8601 format (`YYYY-MM-DD`).
 ///
 /// If the input string is `None` or cannot be parsed using any of the supported formats,
-/// the current UTC date is returned.
+/// the current date in the system local timezone is returned.
 ///
 /// # Arguments
 ///
@@ -51,10 +71,15 @@ fn parse_date_with_unknown_format(date_str: impl AsRef<str>) -> Option<NaiveDate
 ///
 /// * `String` - The date formatted as `YYYY-MM-DD`.
 pub fn normalize_date(date_str: Option<impl AsRef<str>>) -> String {
+    normalize_date_with(date_str, &SystemClock)
+}
+
+/// Normalizes a date string like `normalize_date`, taking "today" from the given clock.
+pub fn normalize_date_with(date_str: Option<impl AsRef<str>>, clock: &dyn Clock) -> String {
     date_str
         .as_ref()
         .and_then(parse_date_with_unknown_format)
-        .unwrap_or_else(|| Utc::now().date_naive())
+        .unwrap_or_else(|| clock.today_in(None))
         .format("%Y-%m-%d")
         .to_string()
 }
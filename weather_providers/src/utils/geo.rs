@@ -0,0 +1,58 @@
+//! # Geographic Distances
+//!
+//! Providers reporting the observations of weather stations (e.g., DMI, ECCC, MeteoSwiss)
+//! look up the station nearest to the geocoded address.
+
+/// The mean radius of the Earth in kilometers.
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Returns the great-circle distance in kilometers between two coordinates in degrees
+/// (`(latitude, longitude)`), with the haversine formula.
+pub fn distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+
+    let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// Returns the place nearest to the coordinates `(lat, lon)`, of places paired with their
+/// coordinates, along with its distance in kilometers.
+pub fn nearest<T>(
+    places: impl IntoIterator<Item = (T, (f64, f64))>,
+    lat: f64,
+    lon: f64,
+) -> Option<(T, f64)> {
+    places
+        .into_iter()
+        .map(|(place, coordinates)| (place, distance_km((lat, lon), coordinates)))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_km() {
+        // Paris to London
+        let distance = distance_km((48.8566, 2.3522), (51.5074, -0.1278));
+        assert!((distance - 343.5).abs() < 1.0, "{distance}");
+
+        assert_eq!(distance_km((46.948, 7.4474), (46.948, 7.4474)), 0.0);
+    }
+
+    #[test]
+    fn test_nearest() {
+        // A degree of longitude is half as long as a degree of latitude at 60° N, so the place
+        // farther in degrees is the nearer one
+        let places = [("north", (61.0, 10.0)), ("east", (60.0, 11.8))];
+        let (place, distance) = nearest(places, 60.0, 10.0).unwrap();
+        assert_eq!(place, "east");
+        assert!((distance - 100.0).abs() < 1.0, "{distance}");
+
+        assert!(nearest(Vec::<((), (f64, f64))>::new(), 60.0, 10.0).is_none());
+    }
+}
@@ -0,0 +1,889 @@
+//! # HTTP Helpers
+//!
+//! Shared helpers for the HTTP-based weather providers. Every outbound request goes through
+//! an `HttpClient`, which fails with `Error::OfflineMode` before opening a socket while network
+//! access is disabled, and signs the request if the provider options set a `RequestSigner`.
+//! Redirects to another host than the requested one are not followed unless allowed by the
+//! options, so a captive portal fails with `Error::Redirected` instead of a confusing parse error.
+//! Providers able to answer in several formats negotiate it with `get_negotiated`.
+//!
+//! Each request names its `endpoint`, a fixed name such as "onecall" (the one recorded in
+//! `WeatherMeta::endpoint`), which is what the `RequestObserver` receives. The URL path is never
+//! reported: it may hold an API key, as for Pirate Weather, or an unbounded number of IDs.
+
+use crate::{
+    ProviderOptions,
+    common::*,
+    utils::{clock::Instant, observer, signing::RequestSigner},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use ::reqwest::redirect::Policy;
+use ::reqwest::{
+    Client, RequestBuilder, Response, StatusCode, Url,
+    header::{ACCEPT, HeaderValue, LOCATION},
+};
+use ::serde::de::DeserializeOwned;
+use ::std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+/// The maximum number of idle connections kept open per host by an `HttpClient`.
+#[cfg(not(target_arch = "wasm32"))]
+const POOL_MAX_IDLE_PER_HOST: usize = 5;
+
+/// The maximum number of redirects followed by an `HttpClient`, as by default in `reqwest`.
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_REDIRECTS: usize = 10;
+
+/// Setting this environment variable to `1` disables network access, like `set_offline(true)`.
+pub const OFFLINE_ENV: &str = "WEATHER_PROVIDERS_OFFLINE";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Maximum time a liveness probe may take before it is considered failed.
+#[cfg(not(target_arch = "wasm32"))]
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Enables or disables network access for all providers of the process.
+///
+/// While offline, every request to a remote provider fails immediately with
+/// `Error::OfflineMode`; the mock providers keep working.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Returns `true` if network access is disabled by `set_offline` or the `WEATHER_PROVIDERS_OFFLINE`
+/// environment variable.
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed) || std::env::var(OFFLINE_ENV).is_ok_and(|v| v == "1")
+}
+
+/// Fails with `Error::OfflineMode` if network access is disabled.
+pub fn ensure_online(provider: &'static str) -> Result<()> {
+    if is_offline() {
+        return Err(Error::OfflineMode(provider.into()));
+    }
+
+    Ok(())
+}
+
+/// The HTTP client of a provider.
+///
+/// Each provider holds one client for all its requests, so connections to the provider are
+/// pooled and reused instead of being opened for every call. On `wasm32` requests go through
+/// the browser's `fetch` API, which manages connections itself.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: Client,
+    signer: Option<Arc<dyn RequestSigner>>,
+    allow_cross_host_redirects: bool,
+    accept: Option<HeaderValue>,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new(&ProviderOptions::default())
+    }
+}
+
+impl HttpClient {
+    /// Creates a client with the connection settings of `options`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(options: &ProviderOptions) -> Self {
+        let builder = Client::builder()
+            .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+            .redirect(redirect_policy(options.allow_cross_host_redirects));
+
+        #[cfg(feature = "danger")]
+        let builder = builder.danger_accept_invalid_certs(options.danger_accept_invalid_certs);
+
+        Self {
+            client: builder.build().unwrap_or_default(),
+            signer: options.signer.clone(),
+            allow_cross_host_redirects: options.allow_cross_host_redirects,
+            accept: accept_header(&options.accepted_formats),
+        }
+    }
+
+    /// Creates a client with the connection settings of `options`.
+    ///
+    /// The browser manages connections, certificates and redirects itself, so only the signer
+    /// applies on wasm32. Redirects to another host are still detected after the fact.
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(options: &ProviderOptions) -> Self {
+        Self {
+            client: Client::new(),
+            signer: options.signer.clone(),
+            allow_cross_host_redirects: options.allow_cross_host_redirects,
+            accept: accept_header(&options.accepted_formats),
+        }
+    }
+
+    /// Sends a GET request to the `endpoint` of a provider.
+    ///
+    /// # Errors
+    ///
+    /// * `Error::OfflineMode` - Network access is disabled.
+    /// * `Error::Reqwest` - The request failed.
+    pub async fn get(
+        &self,
+        provider: &'static str,
+        endpoint: &'static str,
+        url: Url,
+    ) -> Result<Response> {
+        self.send(provider, endpoint, self.client(provider)?.get(url))
+            .await
+    }
+
+    /// Sends a GET request to a provider that supports content negotiation, with an `Accept`
+    /// header listing the `accepted_formats` of the options. Without accepted formats, it is
+    /// the same as `get`.
+    ///
+    /// # Errors
+    ///
+    /// See `get`.
+    // Every current provider answers in JSON only, so none negotiates the format yet
+    #[allow(dead_code)]
+    pub async fn get_negotiated(
+        &self,
+        provider: &'static str,
+        endpoint: &'static str,
+        url: Url,
+    ) -> Result<Response> {
+        let request = self.client(provider)?.get(url);
+
+        match &self.accept {
+            Some(accept) => {
+                self.send(provider, endpoint, request.header(ACCEPT, accept.clone()))
+                    .await
+            }
+            None => self.send(provider, endpoint, request).await,
+        }
+    }
+
+    /// Returns the underlying client for building requests that need more than `get`, e.g. a
+    /// body or headers. Send them with `send`, which signs them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::OfflineMode` if network access is disabled.
+    pub fn client(&self, provider: &'static str) -> Result<&Client> {
+        ensure_online(provider)?;
+        Ok(&self.client)
+    }
+
+    /// Signs a request built from `client`, if a signer is set, and sends it to the `endpoint`
+    /// of `provider`. The request and its failure are reported to the `RequestObserver`, if one
+    /// is set.
+    ///
+    /// # Errors
+    ///
+    /// * `Error::Redirected` - The request was redirected to another host, unless allowed.
+    /// * `Error::Reqwest` - The request cannot be built or fails.
+    pub async fn send(
+        &self,
+        provider: &'static str,
+        endpoint: &'static str,
+        request: RequestBuilder,
+    ) -> Result<Response> {
+        self.execute(provider, endpoint, request)
+            .await
+            .inspect_err(|e| observer::request_failed(provider, e))
+    }
+
+    async fn execute(
+        &self,
+        provider: &'static str,
+        endpoint: &'static str,
+        request: RequestBuilder,
+    ) -> Result<Response> {
+        let mut request = request.build()?;
+        if let Some(signer) = &self.signer {
+            signer.sign(&mut request);
+        }
+
+        let host = request.url().host_str().map(str::to_string);
+
+        observer::request_started(provider, endpoint);
+        let started = Instant::now();
+        let response = self.client.execute(request).await;
+        observer::request_ended(
+            provider,
+            endpoint,
+            response.as_ref().ok().map(|r| r.status().as_u16()),
+            started.elapsed(),
+        );
+        let response = response?;
+
+        if !self.allow_cross_host_redirects
+            && let Some(target) = foreign_host(host.as_deref(), &response)
+        {
+            return Err(Error::Redirected(target));
+        }
+
+        Ok(response)
+    }
+
+    /// Sends a cheap liveness request to a provider endpoint and measures the round-trip latency.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The provider name used in error messages (e.g., "OpenWeather").
+    /// * `endpoint` - The name of the probed endpoint, reported to the `RequestObserver`.
+    /// * `url` - The fully built request URL, including the API key.
+    ///
+    /// # Returns
+    ///
+    /// The round-trip time of the request if the provider answered with a success status.
+    ///
+    /// # Errors
+    ///
+    /// * `Error::OfflineMode` - Network access is disabled.
+    /// * `Error::InvalidApiKey` - The provider rejected the key (HTTP 401 or 403).
+    /// * `Error::Reqwest` - A network failure, a timeout, or any other non-success status.
+    pub async fn probe(
+        &self,
+        provider: &'static str,
+        endpoint: &'static str,
+        url: Url,
+    ) -> Result<Duration> {
+        self.probe_request(provider, endpoint, self.client(provider)?.get(url))
+            .await
+    }
+
+    /// Sends a liveness request like `probe`, for endpoints that need more than a URL, e.g.
+    /// a key in a header. The request is built from `client`, which checks the offline mode.
+    ///
+    /// # Errors
+    ///
+    /// See `probe`.
+    pub async fn probe_request(
+        &self,
+        provider: &'static str,
+        endpoint: &'static str,
+        request: RequestBuilder,
+    ) -> Result<Duration> {
+        // The wasm client has no timeouts; the browser aborts stalled requests itself
+        #[cfg(not(target_arch = "wasm32"))]
+        let request = request.timeout(PROBE_TIMEOUT);
+
+        let started = Instant::now();
+        let response = self.send(provider, endpoint, request).await?;
+        let elapsed = started.elapsed();
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err(Error::InvalidApiKey(provider.into()))
+            }
+            _ => {
+                response.error_for_status()?;
+                Ok(elapsed)
+            }
+        }
+    }
+}
+
+/// Builds the `Accept` header value of `formats`, most preferred first, or `None` if there is
+/// no valid media type.
+fn accept_header(formats: &[String]) -> Option<HeaderValue> {
+    let formats = formats
+        .iter()
+        .map(|format| format.trim())
+        .filter(|format| !format.is_empty())
+        .collect::<Vec<_>>();
+
+    if formats.is_empty() {
+        return None;
+    }
+
+    HeaderValue::from_str(&formats.join(", ")).ok()
+}
+
+/// Follows redirects within the requested host only, unless `allow_cross_host` is set.
+///
+/// A redirect to another host is not followed, so its response reaches `HttpClient::send`,
+/// which reports it.
+#[cfg(not(target_arch = "wasm32"))]
+fn redirect_policy(allow_cross_host: bool) -> Policy {
+    if allow_cross_host {
+        return Policy::limited(MAX_REDIRECTS);
+    }
+
+    Policy::custom(|attempt| {
+        let requested = attempt.previous().first().and_then(Url::host_str);
+        if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if attempt.url().host_str() == requested {
+            attempt.follow()
+        } else {
+            attempt.stop()
+        }
+    })
+}
+
+/// Returns the host a response leads to if it is not the `requested` host: the target of a
+/// redirect that was not followed, or the host of the final URL after followed redirects.
+fn foreign_host(requested: Option<&str>, response: &Response) -> Option<String> {
+    let location = response
+        .status()
+        .is_redirection()
+        .then(|| response.headers().get(LOCATION))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|location| response.url().join(location).ok());
+    let target = location.as_ref().unwrap_or(response.url());
+
+    target
+        .host_str()
+        .filter(|&host| Some(host) != requested)
+        .map(str::to_string)
+}
+
+/// Decodes a response body without failing.
+///
+/// Bodies that are not valid UTF-8, e.g. the error page of a proxy or the ISO-8859-15 data of
+/// AEMET, are decoded as Latin-1, which maps every byte to a character.
+pub fn decode_body(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().copied().map(char::from).collect(),
+    }
+}
+
+/// Decodes a provider's documented error body into an error.
+///
+/// Returns `None` if the body does not have the provider's error shape.
+pub type ErrorDecoder = fn(&str) -> Option<Error>;
+
+/// Maps an unsuccessful provider response to a descriptive error.
+///
+/// Equivalent to `check_status_with` with a decoder that recognizes no error bodies.
+///
+/// # Errors
+///
+/// See `check_status_with`.
+pub async fn check_status(provider: &'static str, response: Response) -> Result<Response> {
+    check_status_with(provider, response, |_| None).await
+}
+
+/// Maps an unsuccessful provider response to a descriptive error.
+///
+/// The body of an unsuccessful response is decoded with `decode` first, so documented
+/// provider error codes map to typed errors and other upstream messages are kept verbatim.
+/// Bodies that are not valid UTF-8 are passed to `decode` as Latin-1 (see `decode_body`).
+/// Bodies that cannot be decoded fall back to a mapping of the HTTP status.
+///
+/// # Arguments
+///
+/// * `provider` - The provider name used in error messages (e.g., "OpenWeather").
+/// * `response` - The response received from the provider.
+/// * `decode` - Decodes the provider's error body.
+///
+/// # Returns
+///
+/// The response itself if its status indicates success.
+///
+/// # Errors
+///
+/// * The error decoded from the body, if any.
+/// * `Error::Any` - A bad request (400) or a forbidden feature (403).
+/// * `Error::InvalidApiKey` - The provider rejected the key (401).
+/// * `Error::LocationNotFound` - The provider does not know the location (404).
+/// * `Error::RateLimited` - The provider rate limit was exceeded (429).
+/// * `Error::ServerError` - The provider failed (5xx).
+/// * `Error::Reqwest` - Any other non-success status.
+pub async fn check_status_with(
+    provider: &'static str,
+    response: Response,
+    decode: ErrorDecoder,
+) -> Result<Response> {
+    status_error(provider, response, decode)
+        .await
+        .inspect_err(|e| observer::request_failed(provider, e))
+}
+
+async fn status_error(
+    provider: &'static str,
+    response: Response,
+    decode: ErrorDecoder,
+) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    // Keep the status error before the body consumes the response
+    let status_error = response.error_for_status_ref().err();
+    let body = response
+        .bytes()
+        .await
+        .map(|bytes| decode_body(&bytes))
+        .unwrap_or_default();
+
+    if let Some(error) = decode(&body) {
+        return Err(error);
+    }
+
+    match status {
+        StatusCode::BAD_REQUEST => Err("Bad request: check address format".into()),
+        StatusCode::UNAUTHORIZED => Err(Error::InvalidApiKey(provider.into())),
+        StatusCode::FORBIDDEN => Err(FORBIDDEN.into()),
+        StatusCode::NOT_FOUND => Err(Error::LocationNotFound(provider.into())),
+        StatusCode::TOO_MANY_REQUESTS => Err(Error::RateLimited(provider.into())),
+        status if status.is_server_error() => Err(Error::ServerError(provider.into())),
+        _ => Err(status_error.map_or_else(|| format!("HTTP error: {status}").into(), Error::from)),
+    }
+}
+
+/// Parses the JSON body of a successful provider response.
+///
+/// The body is read as text first (see `decode_body`), so a payload that does not match `T` is
+/// kept in the error for debugging instead of being lost in the parse error.
+///
+/// # Errors
+///
+/// * `Error::Reqwest` - The body could not be read.
+/// * `Error::ResponseParseFailed` - The body is not valid JSON or does not match `T`.
+pub async fn parse_json<T: DeserializeOwned>(
+    provider: &'static str,
+    response: Response,
+) -> Result<T> {
+    let status = response.status().as_u16();
+    let body = decode_body(&response.bytes().await?);
+
+    serde_json::from_str(&body)
+        .map_err(|cause| Error::ResponseParseFailed {
+            provider: provider.into(),
+            status,
+            body,
+            cause,
+        })
+        .inspect_err(|e| observer::request_failed(provider, e))
+}
+
+/// The message of a request the provider plan does not allow.
+pub const FORBIDDEN: &str = "Forbidden: plan does not include this feature";
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::utils::signing::HmacSha256Signer;
+    use ::wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path},
+    };
+
+    async fn server_with(status: u16, delay: Duration) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ping"))
+            .respond_with(ResponseTemplate::new(status).set_delay(delay))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn ping_url(server: &MockServer) -> Url {
+        Url::parse(&format!("{}/ping", server.uri())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_probe_measures_latency() {
+        let server = server_with(200, Duration::from_millis(50)).await;
+
+        let latency = HttpClient::default()
+            .probe("Test", "ping", ping_url(&server))
+            .await
+            .unwrap();
+
+        assert!(latency >= Duration::from_millis(50));
+        assert!(latency < PROBE_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_probe_classifies_auth_failure() {
+        for status in [401, 403] {
+            let server = server_with(status, Duration::ZERO).await;
+
+            let result = HttpClient::default()
+                .probe("Test", "ping", ping_url(&server))
+                .await;
+
+            assert!(matches!(result, Err(Error::InvalidApiKey(name)) if name == "Test"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_classifies_server_failure() {
+        let server = server_with(503, Duration::ZERO).await;
+
+        let result = HttpClient::default()
+            .probe("Test", "ping", ping_url(&server))
+            .await;
+
+        assert!(matches!(result, Err(Error::Reqwest(e)) if e.is_status()));
+    }
+
+    #[tokio::test]
+    async fn test_check_status() {
+        let check = |status: u16| async move {
+            let server = server_with(status, Duration::ZERO).await;
+            let response = reqwest::get(ping_url(&server)).await.unwrap();
+            check_status("Test", response).await
+        };
+
+        assert!(check(200).await.is_ok());
+        assert!(
+            matches!(check(400).await, Err(Error::Any(msg)) if msg == "Bad request: check address format")
+        );
+        assert!(matches!(check(401).await, Err(Error::InvalidApiKey(name)) if name == "Test"));
+        assert!(matches!(check(403).await, Err(Error::Any(msg)) if msg.starts_with("Forbidden")));
+        assert!(matches!(check(404).await, Err(Error::LocationNotFound(name)) if name == "Test"));
+        assert!(matches!(check(429).await, Err(Error::RateLimited(name)) if name == "Test"));
+        for status in [500, 502, 503, 599] {
+            assert!(matches!(check(status).await, Err(Error::ServerError(name)) if name == "Test"));
+        }
+        assert!(matches!(check(418).await, Err(Error::Reqwest(e)) if e.is_status()));
+    }
+
+    #[tokio::test]
+    async fn test_check_status_with_decoder() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ping"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("known"))
+            .mount(&server)
+            .await;
+        let decode: ErrorDecoder = |body| (body == "known").then(|| "decoded".into());
+
+        let response = reqwest::get(ping_url(&server)).await.unwrap();
+        let result = check_status_with("Test", response, decode).await;
+        assert!(matches!(result, Err(Error::Any(msg)) if msg == "decoded"));
+
+        // Bodies the decoder does not recognize fall back to the status mapping
+        let server = server_with(404, Duration::ZERO).await;
+        let response = reqwest::get(ping_url(&server)).await.unwrap();
+        let result = check_status_with("Test", response, decode).await;
+        assert!(matches!(result, Err(Error::LocationNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_check_status_decodes_latin1_body() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ping"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .set_body_raw(b"Dienst nicht verf\xfcgbar".to_vec(), "text/plain"),
+            )
+            .mount(&server)
+            .await;
+        let decode: ErrorDecoder = |body| Some(body.to_string().into());
+
+        let response = reqwest::get(ping_url(&server)).await.unwrap();
+        let result = check_status_with("Test", response, decode).await;
+
+        assert!(matches!(result, Err(Error::Any(msg)) if msg == "Dienst nicht verfügbar"));
+    }
+
+    #[test]
+    fn test_decode_body() {
+        assert_eq!(decode_body("Grüße".as_bytes()), "Grüße");
+        assert_eq!(decode_body(b"Gr\xfc\xdfe"), "Grüße");
+        assert_eq!(decode_body(b""), "");
+    }
+
+    #[tokio::test]
+    async fn test_parse_json() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ping"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"temp": 59.5}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/portal"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>Sign in</html>"))
+            .mount(&server)
+            .await;
+
+        let response = reqwest::get(ping_url(&server)).await.unwrap();
+        let value = parse_json::<serde_json::Value>("Test", response)
+            .await
+            .unwrap();
+        assert_eq!(value["temp"], 59.5);
+
+        let response = reqwest::get(format!("{}/portal", server.uri()))
+            .await
+            .unwrap();
+        let result = parse_json::<serde_json::Value>("Test", response).await;
+        assert!(matches!(
+            result,
+            Err(Error::ResponseParseFailed { provider, status: 200, body, .. })
+                if provider == "Test" && body == "<html>Sign in</html>"
+        ));
+
+        // Bodies that are not valid UTF-8 are read as Latin-1, like the data of AEMET
+        Mock::given(method("GET"))
+            .and(path("/latin1"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"{\"city\": \"M\xe9rida\"}"))
+            .mount(&server)
+            .await;
+        let response = reqwest::get(format!("{}/latin1", server.uri()))
+            .await
+            .unwrap();
+        let value = parse_json::<serde_json::Value>("Test", response)
+            .await
+            .unwrap();
+        assert_eq!(value["city"], "Mérida");
+    }
+
+    /// Mounts a redirect from `/old` to `location` on `server`.
+    async fn redirect(server: &MockServer, location: &str) {
+        Mock::given(method("GET"))
+            .and(path("/old"))
+            .respond_with(ResponseTemplate::new(302).insert_header("Location", location))
+            .mount(server)
+            .await;
+    }
+
+    fn old_url(server: &MockServer) -> Url {
+        Url::parse(&format!("{}/old", server.uri())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_same_host_redirect_is_followed() {
+        let server = server_with(200, Duration::ZERO).await;
+        redirect(&server, "/ping").await;
+
+        let response = HttpClient::default()
+            .get("Test", "ping", old_url(&server))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.url().path(), "/ping");
+    }
+
+    #[tokio::test]
+    async fn test_cross_host_redirect_is_reported() {
+        // A captive portal on another host: `localhost` instead of `127.0.0.1`
+        let portal = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/login"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>Sign in</html>"))
+            .mount(&portal)
+            .await;
+        let server = MockServer::start().await;
+        let login = format!("http://localhost:{}/login", portal.address().port());
+        redirect(&server, &login).await;
+
+        let result = HttpClient::default()
+            .get("Test", "ping", old_url(&server))
+            .await;
+
+        assert!(matches!(&result, Err(Error::Redirected(host)) if host == "localhost"));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Request was redirected to localhost — are you behind a captive portal?"
+        );
+        assert!(portal.received_requests().await.unwrap().is_empty());
+
+        // Cross-host redirects are followed when allowed
+        let http = HttpClient::new(&ProviderOptions {
+            allow_cross_host_redirects: true,
+            ..ProviderOptions::default()
+        });
+        let response = http.get("Test", "ping", old_url(&server)).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.url().as_str(), login);
+    }
+
+    #[test]
+    fn test_accept_header() {
+        let formats = |formats: &[&str]| {
+            let formats = formats.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+            accept_header(&formats)
+        };
+
+        assert_eq!(
+            formats(&["application/xml", " application/json "]).unwrap(),
+            "application/xml, application/json"
+        );
+        assert!(formats(&[]).is_none());
+        assert!(formats(&["", " "]).is_none());
+        assert!(formats(&["application/\nxml"]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_negotiated_sends_accept_header() {
+        let server = server_with(200, Duration::ZERO).await;
+        let http = HttpClient::new(&ProviderOptions {
+            accepted_formats: vec!["application/xml".into(), "application/json".into()],
+            ..ProviderOptions::default()
+        });
+
+        http.get_negotiated("Test", "ping", ping_url(&server))
+            .await
+            .unwrap();
+        // JSON-only providers use `get`, which ignores the accepted formats and keeps the
+        // default of `reqwest`
+        http.get("Test", "ping", ping_url(&server)).await.unwrap();
+        HttpClient::default()
+            .get_negotiated("Test", "ping", ping_url(&server))
+            .await
+            .unwrap();
+
+        let accept = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| {
+                r.headers
+                    .get("accept")
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(accept, ["application/xml, application/json", "*/*", "*/*"]);
+    }
+
+    #[tokio::test]
+    async fn test_probe_classifies_network_failure() {
+        // Bind an ephemeral port and release it so nothing is listening there
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|l| l.local_addr())
+            .unwrap()
+            .port();
+        let url = Url::parse(&format!("http://127.0.0.1:{port}/ping")).unwrap();
+
+        let result = HttpClient::default().probe("Test", "ping", url).await;
+
+        assert!(matches!(result, Err(Error::Reqwest(e)) if e.is_connect()));
+    }
+
+    /// Accepts requests signed with the secret, checking them the way a gateway would.
+    struct ValidSignature(&'static str);
+
+    impl wiremock::Match for ValidSignature {
+        fn matches(&self, request: &wiremock::Request) -> bool {
+            use ::hmac::{Hmac, Mac};
+
+            let Some((timestamp, signature)) = request
+                .headers
+                .get("x-signature")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("t="))
+                .and_then(|v| v.split_once(",sig="))
+            else {
+                return false;
+            };
+
+            let fresh = timestamp
+                .parse::<i64>()
+                .is_ok_and(|t| (chrono::Utc::now().timestamp() - t).abs() <= 300);
+            let Ok(signature) = (0..signature.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(signature.get(i..i + 2).unwrap_or("x"), 16))
+                .collect::<std::result::Result<Vec<_>, _>>()
+            else {
+                return false;
+            };
+
+            let url = &request.url;
+            let query = url.query().map(|q| format!("?{q}")).unwrap_or_default();
+            let message = format!("{timestamp}\n{}\n{}{query}\n", request.method, url.path());
+
+            let mut mac = Hmac::<sha2::Sha256>::new_from_slice(self.0.as_bytes()).unwrap();
+            mac.update(message.as_bytes());
+            mac.update(&request.body);
+
+            fresh && mac.verify_slice(&signature).is_ok()
+        }
+    }
+
+    async fn gateway() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/weather"))
+            .and(ValidSignature("shared-secret"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn signed_client(secret: &str) -> HttpClient {
+        let signer = HmacSha256Signer::new("X-Signature", secret).unwrap();
+
+        HttpClient::new(&ProviderOptions {
+            signer: Some(Arc::new(signer)),
+            ..ProviderOptions::default()
+        })
+    }
+
+    async fn post(http: &HttpClient, server: &MockServer) -> StatusCode {
+        let request = http
+            .client("Test")
+            .unwrap()
+            .post(format!("{}/v1/weather?q=London", server.uri()))
+            .body(r#"{"units":"us"}"#);
+
+        http.send("Test", "ping", request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_signed_requests_are_accepted() {
+        let server = gateway().await;
+
+        assert_eq!(post(&signed_client("shared-secret"), &server).await, 200);
+
+        // Requests without a signature or signed with another secret are rejected
+        assert_eq!(post(&HttpClient::default(), &server).await, 404);
+        assert_eq!(post(&signed_client("wrong-secret"), &server).await, 404);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_body_is_rejected() {
+        let server = gateway().await;
+        let client = Client::new();
+        let mut request = client
+            .post(format!("{}/v1/weather", server.uri()))
+            .body("original")
+            .build()
+            .unwrap();
+        HmacSha256Signer::new("X-Signature", "shared-secret")
+            .unwrap()
+            .sign(&mut request);
+
+        let mut tampered = request.try_clone().unwrap();
+        *tampered.body_mut() = Some("tampered".into());
+
+        assert_eq!(client.execute(tampered).await.unwrap().status(), 404);
+        assert_eq!(client.execute(request).await.unwrap().status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_probes_are_signed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ping"))
+            .and(ValidSignature("shared-secret"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let result = signed_client("shared-secret")
+            .probe("Test", "ping", ping_url(&server))
+            .await;
+
+        assert!(result.is_ok(), "{result:?}");
+    }
+}
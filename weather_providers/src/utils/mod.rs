@@ -1 +1,11 @@
+pub mod aqi;
+pub mod clock;
 pub mod date;
+pub mod geo;
+pub mod http;
+pub mod observer;
+pub mod poll;
+pub mod pressure;
+pub mod relative_date;
+pub mod signing;
+pub mod units;
@@ -0,0 +1,228 @@
+//! # Request Observation
+//!
+//! Reports the outbound provider requests to an application-defined `RequestObserver`, e.g.
+//! to feed its own metrics registry, without the library depending on a metrics crate. The
+//! observer is set for the whole process with `set_observer`; without one, nothing is reported.
+//!
+//! The shared HTTP helpers report every request sent by an `HttpClient`, the errors of
+//! `check_status_with` and `parse_json`, and the gRPC provider reports its calls.
+
+use crate::common::*;
+use ::std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+/// Receives the requests made by the providers. Every callback has a no-op default, so an
+/// observer implements only the ones it needs.
+///
+/// The callbacks run on the task making the request, so they should return quickly.
+pub trait RequestObserver: Send + Sync {
+    /// A request to the `endpoint` of `provider` is sent. The endpoint is a fixed name, such as
+    /// "onecall" or the gRPC method, never the URL path, which may hold an API key or IDs.
+    fn on_request_start(&self, _provider: &str, _endpoint: &str) {}
+
+    /// A request has finished after `duration`, with the HTTP `status` of its response, or
+    /// `None` if no HTTP response was received (a network failure, or a gRPC call).
+    fn on_request_end(
+        &self,
+        _provider: &str,
+        _endpoint: &str,
+        _status: Option<u16>,
+        _duration: Duration,
+    ) {
+    }
+
+    /// A request to `provider` has failed: no response, an error status or an undecodable
+    /// body.
+    fn on_error(&self, _provider: &str, _code: ErrorCode) {}
+}
+
+static OBSERVER: RwLock<Option<Arc<dyn RequestObserver>>> = RwLock::new(None);
+
+/// Sets the observer of the requests of all providers of the process, replacing any
+/// previous one.
+pub fn set_observer(observer: Box<dyn RequestObserver>) {
+    *OBSERVER.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::from(observer));
+}
+
+/// Removes the observer set by `set_observer`, if any.
+pub fn clear_observer() {
+    *OBSERVER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Returns the current observer. It is cloned out of the lock, so callbacks never run while
+/// the lock is held.
+fn observer() -> Option<Arc<dyn RequestObserver>> {
+    OBSERVER.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub(crate) fn request_started(provider: &str, endpoint: &str) {
+    if let Some(observer) = observer() {
+        observer.on_request_start(provider, endpoint);
+    }
+}
+
+pub(crate) fn request_ended(
+    provider: &str,
+    endpoint: &str,
+    status: Option<u16>,
+    duration: Duration,
+) {
+    if let Some(observer) = observer() {
+        observer.on_request_end(provider, endpoint, status, duration);
+    }
+}
+
+/// Reports a failed request, for use with `Result::inspect_err`.
+pub(crate) fn request_failed(provider: &str, error: &Error) {
+    if let Some(observer) = observer() {
+        observer.on_error(provider, error.code());
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::utils::http::{HttpClient, check_status, parse_json};
+    use ::reqwest::Url;
+    use ::std::sync::Mutex;
+    use ::wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path},
+    };
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Start(String, String),
+        End(String, String, Option<u16>),
+        Error(String, ErrorCode),
+    }
+
+    /// Records the events of the providers named "Observed…", ignoring the requests of
+    /// other tests running in parallel.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Event>>>);
+
+    impl Recorder {
+        fn record(&self, provider: &str, event: Event) {
+            if provider.starts_with("Observed") {
+                self.0.lock().unwrap().push(event);
+            }
+        }
+
+        fn events(&self, provider: &str) -> Vec<Event> {
+            self.0
+                .lock()
+                .unwrap()
+                .drain(..)
+                .filter(|event| match event {
+                    Event::Start(p, _) | Event::End(p, _, _) | Event::Error(p, _) => p == provider,
+                })
+                .collect()
+        }
+    }
+
+    impl RequestObserver for Recorder {
+        fn on_request_start(&self, provider: &str, endpoint: &str) {
+            self.record(provider, Event::Start(provider.into(), endpoint.into()));
+        }
+
+        fn on_request_end(
+            &self,
+            provider: &str,
+            endpoint: &str,
+            status: Option<u16>,
+            _duration: Duration,
+        ) {
+            self.record(
+                provider,
+                Event::End(provider.into(), endpoint.into(), status),
+            );
+        }
+
+        fn on_error(&self, provider: &str, code: ErrorCode) {
+            self.record(provider, Event::Error(provider.into(), code));
+        }
+    }
+
+    /// Sends a GET request to `route` of `server`, as the "value" endpoint, and parses the JSON
+    /// body.
+    async fn fetch(provider: &'static str, server: &MockServer, route: &str) -> Result<u8> {
+        let url = Url::parse(&format!("{}{route}", server.uri())).unwrap();
+        let response = HttpClient::default().get(provider, "value", url).await?;
+
+        parse_json(provider, check_status(provider, response).await?).await
+    }
+
+    // A single test, as the observer is global to the process
+    #[tokio::test]
+    async fn test_observer_callbacks() {
+        let server = MockServer::start().await;
+        for (route, status, body) in [("/ok", 200, "42"), ("/down", 503, ""), ("/odd", 200, "{")] {
+            Mock::given(method("GET"))
+                .and(path(route))
+                .respond_with(ResponseTemplate::new(status).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+        let recorder = Recorder::default();
+        set_observer(Box::new(recorder.clone()));
+
+        let start = |provider: &str, endpoint: &str| Event::Start(provider.into(), endpoint.into());
+        let end = |provider: &str, endpoint: &str, status| {
+            Event::End(provider.into(), endpoint.into(), Some(status))
+        };
+
+        assert_eq!(fetch("ObservedOk", &server, "/ok").await.unwrap(), 42);
+        assert_eq!(
+            recorder.events("ObservedOk"),
+            [
+                start("ObservedOk", "value"),
+                end("ObservedOk", "value", 200)
+            ]
+        );
+
+        assert!(fetch("ObservedDown", &server, "/down").await.is_err());
+        assert_eq!(
+            recorder.events("ObservedDown"),
+            [
+                start("ObservedDown", "value"),
+                end("ObservedDown", "value", 503),
+                Event::Error("ObservedDown".into(), ErrorCode::Upstream),
+            ]
+        );
+
+        assert!(fetch("ObservedOdd", &server, "/odd").await.is_err());
+        assert_eq!(
+            recorder.events("ObservedOdd"),
+            [
+                start("ObservedOdd", "value"),
+                end("ObservedOdd", "value", 200),
+                Event::Error("ObservedOdd".into(), ErrorCode::Decode),
+            ]
+        );
+
+        // A key in the URL path, as sent to Pirate Weather, never reaches the observer
+        let route = "/forecast/secret-api-key/51.5072,-0.1276,0";
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200).set_body_string("7"))
+            .mount(&server)
+            .await;
+        assert_eq!(fetch("ObservedKey", &server, route).await.unwrap(), 7);
+        let events = recorder.events("ObservedKey");
+        assert_eq!(
+            events,
+            [
+                start("ObservedKey", "value"),
+                end("ObservedKey", "value", 200)
+            ]
+        );
+        assert!(!format!("{events:?}").contains("secret-api-key"));
+
+        clear_observer();
+        assert!(fetch("ObservedOk", &server, "/ok").await.is_ok());
+        assert!(recorder.events("ObservedOk").is_empty());
+    }
+}
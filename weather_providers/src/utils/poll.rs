@@ -0,0 +1,155 @@
+//! # Polling
+//!
+//! Providers that process requests as jobs (e.g., ClimateServ, ERA5), or that publish the data
+//! of a request after a delay (AEMET), are probed until the result is ready.
+
+use crate::common::*;
+use ::std::{future::Future, time::Duration};
+
+/// How a job is polled.
+#[derive(Debug, Clone, Copy)]
+pub struct Polling {
+    /// The most probes before the job is abandoned.
+    pub attempts: u32,
+    /// The delay before the first probe.
+    pub interval: Duration,
+    /// The longest delay before a probe. Each delay doubles the previous one up to it, so a
+    /// `max_interval` equal to `interval` polls on a fixed interval.
+    pub max_interval: Duration,
+}
+
+impl Polling {
+    /// Polls at most `attempts` times, waiting `interval` before each probe.
+    pub const fn fixed(attempts: u32, interval: Duration) -> Self {
+        Self {
+            attempts,
+            interval,
+            max_interval: interval,
+        }
+    }
+
+    /// Returns the delays before the probes, in order.
+    fn delays(&self) -> impl Iterator<Item = Duration> {
+        let max = self.max_interval.max(self.interval);
+
+        ::std::iter::successors(Some(self.interval), move |delay| {
+            Some((*delay * 2).min(max))
+        })
+        .take(self.attempts as usize)
+    }
+
+    /// Returns how long a job is waited for before it is abandoned, without the time taken by
+    /// the probes.
+    pub fn timeout(&self) -> Duration {
+        self.delays().sum()
+    }
+}
+
+/// Probes a job of `provider` until `probe` returns its result, waiting the delays of
+/// `polling` before each probe.
+///
+/// # Errors
+///
+/// Returns the error of a probe, and `Error::Timeout` if the job is not ready after
+/// `Polling::attempts` probes.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn poll<T, F, Fut>(provider: &'static str, polling: Polling, mut probe: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    for delay in polling.delays() {
+        ::tokio::time::sleep(delay).await;
+        if let Some(result) = probe().await? {
+            return Ok(result);
+        }
+    }
+
+    Err(Error::Timeout {
+        provider: provider.into(),
+        seconds: polling.timeout().as_secs(),
+    })
+}
+
+/// Probes a job of `provider` once, without waiting.
+///
+/// Timers are not available in wasm32 builds, so a job that is not ready cannot be polled.
+#[cfg(target_arch = "wasm32")]
+pub async fn poll<T, F, Fut>(provider: &'static str, _polling: Polling, mut probe: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    probe().await?.ok_or_else(|| {
+        format!(
+            "'{provider}' is still processing the request, which cannot be polled in wasm32 builds"
+        )
+        .into()
+    })
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use ::std::cell::Cell;
+
+    #[test]
+    fn test_delays() {
+        let backoff = Polling {
+            attempts: 6,
+            interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(10),
+        };
+        let delays = backoff.delays().map(|d| d.as_secs()).collect::<Vec<_>>();
+        assert_eq!(delays, [1, 2, 4, 8, 10, 10]);
+        assert_eq!(backoff.timeout(), Duration::from_secs(35));
+
+        let fixed = Polling::fixed(3, Duration::from_secs(2));
+        assert_eq!(fixed.timeout(), Duration::from_secs(6));
+    }
+
+    #[tokio::test]
+    async fn test_poll_until_ready() {
+        let probes = Cell::new(0);
+
+        let result = poll(
+            "Test",
+            Polling::fixed(5, Duration::from_millis(1)),
+            || async {
+                probes.set(probes.get() + 1);
+                Ok((probes.get() == 3).then_some("done"))
+            },
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(probes.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_poll_gives_up() {
+        let probes = Cell::new(0);
+
+        let result = poll(
+            "Test",
+            Polling::fixed(4, Duration::from_millis(1)),
+            || async {
+                probes.set(probes.get() + 1);
+                Ok(None::<()>)
+            },
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::Timeout { .. })));
+        assert_eq!(probes.get(), 4);
+
+        // A failed probe ends the polling
+        let result = poll(
+            "Test",
+            Polling::fixed(4, Duration::from_millis(1)),
+            || async { Err::<Option<()>, _>(Error::from("job failed")) },
+        )
+        .await;
+        assert_eq!(result.unwrap_err().to_string(), "job failed");
+    }
+}
@@ -0,0 +1,155 @@
+//! # Pressure Tendency
+//!
+//! Few providers report whether the air pressure is rising or falling, but most report the
+//! pressure itself. `PressureTracker` remembers the last reading of every location and derives
+//! the tendency from the next one, so applications that poll a location (e.g., a watch loop)
+//! can show it for any provider.
+
+use crate::models::{PressureTendency, WeatherInfo};
+use ::std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+/// Changes of the pressure up to this amount (hPa) between two readings count as steady.
+pub const STEADY_THRESHOLD_HPA: f32 = 1.0;
+
+/// Derives the pressure tendency of locations from consecutive readings.
+///
+/// The tracker is shared by reference; readings are kept in memory for the tracker's lifetime.
+#[derive(Debug)]
+pub struct PressureTracker {
+    readings: Mutex<HashMap<String, f32>>,
+    threshold_hpa: f32,
+}
+
+impl Default for PressureTracker {
+    fn default() -> Self {
+        Self::with_threshold(STEADY_THRESHOLD_HPA)
+    }
+}
+
+impl PressureTracker {
+    /// Creates a tracker treating changes up to `threshold_hpa` as steady.
+    pub fn with_threshold(threshold_hpa: f32) -> Self {
+        Self {
+            readings: Mutex::new(HashMap::new()),
+            threshold_hpa: threshold_hpa.abs(),
+        }
+    }
+
+    /// Records a reading of `location` and returns the tendency compared with the previous one.
+    ///
+    /// # Returns
+    ///
+    /// `None` for the first reading of the location.
+    pub fn record(&self, location: &str, pressure_hpa: f32) -> Option<PressureTendency> {
+        let previous = self
+            .readings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(location.to_string(), pressure_hpa)?;
+
+        let change = pressure_hpa - previous;
+        Some(if change > self.threshold_hpa {
+            PressureTendency::Rising
+        } else if change < -self.threshold_hpa {
+            PressureTendency::Falling
+        } else {
+            PressureTendency::Steady
+        })
+    }
+
+    /// Records the pressure of `info` and sets its tendency, unless the provider reported one.
+    ///
+    /// Weather without a pressure is returned unchanged.
+    pub fn apply(&self, location: &str, mut info: WeatherInfo) -> WeatherInfo {
+        if let Some(pressure_hpa) = info.pressure_hpa {
+            let tendency = self.record(location, pressure_hpa);
+            info.pressure_tendency = info.pressure_tendency.or(tendency);
+        }
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let tracker = PressureTracker::default();
+
+        assert_eq!(tracker.record("London", 1012.0), None);
+        assert_eq!(
+            tracker.record("London", 1013.0),
+            Some(PressureTendency::Steady)
+        );
+        assert_eq!(
+            tracker.record("London", 1014.5),
+            Some(PressureTendency::Rising)
+        );
+        assert_eq!(
+            tracker.record("London", 1009.0),
+            Some(PressureTendency::Falling)
+        );
+
+        // Locations are tracked separately
+        assert_eq!(tracker.record("Paris", 1009.0), None);
+    }
+
+    #[test]
+    fn test_threshold() {
+        let tracker = PressureTracker::with_threshold(0.1);
+
+        tracker.record("London", 1012.0);
+        assert_eq!(
+            tracker.record("London", 1012.5),
+            Some(PressureTendency::Rising)
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let tracker = PressureTracker::default();
+        let reading = |pressure_hpa| WeatherInfo {
+            pressure_hpa,
+            ..WeatherInfo::sample()
+        };
+
+        assert_eq!(
+            tracker
+                .apply("London", reading(Some(1020.0)))
+                .pressure_tendency,
+            None
+        );
+        assert_eq!(
+            tracker
+                .apply("London", reading(Some(1016.0)))
+                .pressure_tendency,
+            Some(PressureTendency::Falling)
+        );
+
+        // A missing reading keeps the previous one for the next comparison
+        assert_eq!(
+            tracker.apply("London", reading(None)).pressure_tendency,
+            None
+        );
+        assert_eq!(
+            tracker
+                .apply("London", reading(Some(1016.5)))
+                .pressure_tendency,
+            Some(PressureTendency::Steady)
+        );
+
+        // A reported tendency is kept
+        let reported = WeatherInfo {
+            pressure_tendency: Some(PressureTendency::Rising),
+            ..reading(Some(1010.0))
+        };
+        assert_eq!(
+            tracker.apply("London", reported).pressure_tendency,
+            Some(PressureTendency::Rising)
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! # Relative Dates
+//!
+//! Parses relative date expressions such as `"yesterday"`, `"2 days ago"` or `"-7d"` into
+//! calendar dates.
+
+use super::clock::{Clock, SystemClock};
+use crate::common::*;
+use ::chrono::{Days, NaiveDate};
+use ::chrono_tz::Tz;
+
+/// Resolves a relative date expression to a date relative to today in the system local timezone.
+///
+/// Supported expressions (case-insensitive, surrounding whitespace ignored):
+/// - `today`, `yesterday`, `tomorrow`
+/// - `N days ago` (or `1 day ago`)
+/// - `-Nd` for past dates and `+Nd` for future dates
+///
+/// # Errors
+///
+/// Returns an error describing the supported expressions if `expr` cannot be parsed,
+/// or if the resulting date is out of range.
+///
+/// # Examples
+///
+/// ```rust
+/// use weather_providers::parse_relative_date;
+///
+/// let yesterday = parse_relative_date("yesterday").unwrap();
+/// println!("{}", yesterday.format("%Y-%m-%d"));
+/// ```
+pub fn parse_relative_date(expr: &str) -> Result<NaiveDate> {
+    parse_relative_date_with(expr, &SystemClock, None)
+}
+
+/// Resolves a relative date expression like `parse_relative_date`, relative to today
+/// according to `clock` in the timezone `tz` (the system local timezone if `None`).
+///
+/// # Errors
+///
+/// Returns an error if `expr` cannot be parsed or the resulting date is out of range.
+pub fn parse_relative_date_with(
+    expr: &str,
+    clock: &dyn Clock,
+    tz: Option<Tz>,
+) -> Result<NaiveDate> {
+    parse_relative_date_from(expr, clock.today_in(tz))
+}
+
+/// Resolves a relative date expression against the given `today`.
+fn parse_relative_date_from(expr: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let offset = parse_offset(expr).ok_or_else(|| {
+        Error::from(format!(
+            "Invalid relative date: '{expr}'. Supported expressions: \
+             'today', 'yesterday', 'tomorrow', 'N days ago', '-Nd', '+Nd'"
+        ))
+    })?;
+
+    let days = Days::new(offset.unsigned_abs());
+    let date = if offset < 0 {
+        today.checked_sub_days(days)
+    } else {
+        today.checked_add_days(days)
+    };
+
+    date.ok_or_else(|| format!("Relative date out of range: '{expr}'").into())
+}
+
+/// Parses an expression into an offset in days (negative for the past).
+fn parse_offset(expr: &str) -> Option<i64> {
+    let expr = expr.trim().to_lowercase();
+
+    match expr.as_str() {
+        "today" => return Some(0),
+        "yesterday" => return Some(-1),
+        "tomorrow" => return Some(1),
+        _ => {}
+    }
+
+    if let Some(days) = expr
+        .strip_suffix("days ago")
+        .or_else(|| expr.strip_suffix("day ago"))
+    {
+        return parse_count(days.trim_end()).map(|n| -n);
+    }
+
+    let (sign, rest) = match expr.chars().next()? {
+        '-' => (-1, &expr[1..]),
+        '+' => (1, &expr[1..]),
+        _ => return None,
+    };
+
+    rest.strip_suffix('d')
+        .and_then(parse_count)
+        .map(|n| sign * n)
+}
+
+/// Parses a non-negative day count such as `"7"`.
+fn parse_count(s: &str) -> Option<i64> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    }
+
+    fn resolve(expr: &str) -> Option<String> {
+        parse_relative_date_from(expr, today())
+            .ok()
+            .map(|d| d.format("%Y-%m-%d").to_string())
+    }
+
+    #[test]
+    fn test_keywords() {
+        assert_eq!(resolve("today").as_deref(), Some("2024-03-01"));
+        assert_eq!(resolve("Yesterday").as_deref(), Some("2024-02-29"));
+        assert_eq!(resolve(" tomorrow ").as_deref(), Some("2024-03-02"));
+    }
+
+    #[test]
+    fn test_days_ago() {
+        assert_eq!(resolve("2 days ago").as_deref(), Some("2024-02-28"));
+        assert_eq!(resolve("1 day ago").as_deref(), Some("2024-02-29"));
+        assert_eq!(resolve("0 days ago").as_deref(), Some("2024-03-01"));
+        assert_eq!(resolve("30 DAYS AGO").as_deref(), Some("2024-01-31"));
+    }
+
+    #[test]
+    fn test_signed_offsets() {
+        assert_eq!(resolve("-7d").as_deref(), Some("2024-02-23"));
+        assert_eq!(resolve("+7d").as_deref(), Some("2024-03-08"));
+        assert_eq!(resolve("-0d").as_deref(), Some("2024-03-01"));
+        assert_eq!(resolve("-365d").as_deref(), Some("2023-03-02"));
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expr in [
+            "",
+            "now",
+            "7d",
+            "-d",
+            "--7d",
+            "+-7d",
+            "-7",
+            "-7days",
+            "two days ago",
+            "-1 d",
+            "days ago",
+            "-7.5d",
+        ] {
+            assert!(resolve(expr).is_none(), "'{expr}' should be rejected");
+        }
+
+        let error = parse_relative_date_from("now", today())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'N days ago', '-Nd', '+Nd'"));
+    }
+
+    #[test]
+    fn test_tomorrow_near_midnight() {
+        use crate::utils::clock::FixedClock;
+        use ::chrono::{TimeZone, Utc};
+
+        // 10:30 UTC on Jan 15 is 23:30 on Jan 15 in UTC+13 and 23:30 on Jan 14 in UTC-11
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap());
+        let tomorrow = |tz| {
+            parse_relative_date_with("tomorrow", &clock, Some(tz))
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(tomorrow(Tz::Pacific__Tongatapu), "2024-01-16");
+        assert_eq!(tomorrow(Tz::Pacific__Pago_Pago), "2024-01-15");
+        assert_eq!(tomorrow(Tz::UTC), "2024-01-16");
+
+        // One hour later it is already Jan 16 in UTC+13, so "tomorrow" is Jan 17 there
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 1, 15, 11, 30, 0).unwrap());
+        assert_eq!(
+            parse_relative_date_with("tomorrow", &clock, Some(Tz::Pacific__Tongatapu))
+                .unwrap()
+                .to_string(),
+            "2024-01-17"
+        );
+        assert_eq!(
+            parse_relative_date_with("yesterday", &clock, Some(Tz::Pacific__Pago_Pago))
+                .unwrap()
+                .to_string(),
+            "2024-01-14"
+        );
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert!(resolve("+99999999999d").is_none());
+        assert!(resolve("-99999999999999999999d").is_none());
+    }
+}
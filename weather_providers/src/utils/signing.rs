@@ -0,0 +1,220 @@
+//! # Request Signing
+//!
+//! Signs outbound provider requests for gateways that authenticate callers by a signature
+//! instead of (or in addition to) an API key. A signer set in `ProviderOptions::signer` is
+//! applied by the provider's `HttpClient` to every request, right before it is sent.
+
+use crate::{
+    common::*,
+    utils::clock::{Clock, SystemClock},
+};
+use ::hmac::{Hmac, Mac};
+use ::reqwest::{
+    Request,
+    header::{HeaderName, HeaderValue},
+};
+use ::sha2::Sha256;
+use ::std::fmt::{self, Debug, Write};
+
+/// Signs a request before it is sent, typically by adding a header.
+pub trait RequestSigner: Debug + Send + Sync {
+    fn sign(&self, request: &mut Request);
+}
+
+/// Signs requests with an HMAC-SHA256 of the timestamp, the method, the path and the body.
+///
+/// The signed message is the Unix timestamp, the method, and the path with the query, each
+/// followed by a line feed, then the raw body:
+///
+/// ```text
+/// 1718000000\nPOST\n/v1/weather?q=London\n{"units":"us"}
+/// ```
+///
+/// The header is set to `t=<TIMESTAMP>,sig=<HEX>`, with the lowercase hex digest of the
+/// message, so the gateway can recompute it and reject stale timestamps.
+pub struct HmacSha256Signer {
+    header: HeaderName,
+    secret: Vec<u8>,
+    clock_skew: i64,
+}
+
+impl HmacSha256Signer {
+    /// Creates a signer writing the signature to the `header` (e.g., "X-Signature").
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `header` is not a valid header name or the secret is empty.
+    pub fn new(header: &str, secret: impl Into<Vec<u8>>) -> Result<Self> {
+        let header = HeaderName::try_from(header)
+            .map_err(|e| format!("Invalid signature header name '{header}': {e}"))?;
+        let secret = secret.into();
+
+        if secret.is_empty() {
+            Err("The request signing secret is empty.")?;
+        }
+
+        Ok(Self {
+            header,
+            secret,
+            clock_skew: 0,
+        })
+    }
+
+    /// Adds `seconds` to the timestamps, to match a gateway whose clock differs from ours.
+    pub fn with_clock_skew(mut self, seconds: i64) -> Self {
+        self.clock_skew = seconds;
+        self
+    }
+
+    /// Signs the request as if the current time were `clock.now()`.
+    pub fn sign_with(&self, request: &mut Request, clock: &dyn Clock) {
+        let timestamp = clock.now().timestamp() + self.clock_skew;
+        let signature = self.signature(timestamp, request);
+
+        // Digits, letters, ',' and '=' always make a valid header value
+        if let Ok(value) = HeaderValue::try_from(format!("t={timestamp},sig={signature}")) {
+            request.headers_mut().insert(self.header.clone(), value);
+        }
+    }
+
+    /// Returns the hex digest of the signed message of the request.
+    fn signature(&self, timestamp: i64, request: &Request) -> String {
+        let url = request.url();
+        let mut message = format!("{timestamp}\n{}\n{}", request.method(), url.path());
+        if let Some(query) = url.query() {
+            let _ = write!(message, "?{query}");
+        }
+        message.push('\n');
+
+        // The key length is not restricted for HMAC
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("any key length");
+        mac.update(message.as_bytes());
+        if let Some(body) = request.body().and_then(|b| b.as_bytes()) {
+            mac.update(body);
+        }
+
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .fold(String::with_capacity(64), |mut hex, byte| {
+                let _ = write!(hex, "{byte:02x}");
+                hex
+            })
+    }
+}
+
+impl RequestSigner for HmacSha256Signer {
+    fn sign(&self, request: &mut Request) {
+        self.sign_with(request, &SystemClock);
+    }
+}
+
+impl Debug for HmacSha256Signer {
+    /// Shows the configuration without the secret, so it never ends up in logs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HmacSha256Signer")
+            .field("header", &self.header)
+            .field("secret", &"<redacted>")
+            .field("clock_skew", &self.clock_skew)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::FixedClock;
+    use ::chrono::DateTime;
+    use ::reqwest::{Client, Method, Url};
+
+    fn clock() -> FixedClock {
+        FixedClock(DateTime::from_timestamp(1718000000, 0).unwrap())
+    }
+
+    fn request(body: &'static str) -> Request {
+        Client::new()
+            .post("https://gateway.example/v1/weather?q=London")
+            .body(body)
+            .build()
+            .unwrap()
+    }
+
+    fn header(request: &Request) -> &str {
+        request.headers()["x-signature"].to_str().unwrap()
+    }
+
+    #[test]
+    fn test_known_signature() {
+        // Computed independently of this crate, e.g. with Python's `hmac` module
+        let signer = HmacSha256Signer::new("X-Signature", "secret").unwrap();
+        let mut signed = request(r#"{"units":"us"}"#);
+        signer.sign_with(&mut signed, &clock());
+
+        assert_eq!(
+            header(&signed),
+            "t=1718000000,sig=45502acc8333c298c8db2e7e408ebf60eb773adcc845ae296ba702617528f01a"
+        );
+    }
+
+    #[test]
+    fn test_signature_header() {
+        let signer = HmacSha256Signer::new("X-Signature", "secret").unwrap();
+        let mut signed = request(r#"{"units":"us"}"#);
+        signer.sign_with(&mut signed, &clock());
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(b"1718000000\nPOST\n/v1/weather?q=London\n{\"units\":\"us\"}");
+        let expected = format!("t=1718000000,sig={:x}", mac.finalize().into_bytes());
+
+        assert_eq!(header(&signed), expected);
+    }
+
+    #[test]
+    fn test_signature_covers_body_method_and_path() {
+        let signer = HmacSha256Signer::new("X-Signature", "secret").unwrap();
+        let sign = |mut request: Request| {
+            signer.sign_with(&mut request, &clock());
+            header(&request).to_string()
+        };
+
+        let original = sign(request("a"));
+        assert_ne!(sign(request("b")), original);
+
+        let mut other_method = request("a");
+        *other_method.method_mut() = Method::PUT;
+        assert_ne!(sign(other_method), original);
+
+        let mut other_path = request("a");
+        *other_path.url_mut() = Url::parse("https://gateway.example/v1/weather?q=Paris").unwrap();
+        assert_ne!(sign(other_path), original);
+
+        // Only the path is signed, so a gateway behind another host name accepts it
+        let mut other_host = request("a");
+        *other_host.url_mut() = Url::parse("https://other.example/v1/weather?q=London").unwrap();
+        assert_eq!(sign(other_host), original);
+    }
+
+    #[test]
+    fn test_clock_skew() {
+        let signer = HmacSha256Signer::new("X-Signature", "secret")
+            .unwrap()
+            .with_clock_skew(-30);
+        let mut signed = request("");
+        signer.sign_with(&mut signed, &clock());
+
+        assert!(header(&signed).starts_with("t=1717999970,sig="));
+    }
+
+    #[test]
+    fn test_invalid_configuration() {
+        assert!(HmacSha256Signer::new("X Signature", "secret").is_err());
+        assert!(HmacSha256Signer::new("X-Signature", "").is_err());
+    }
+
+    #[test]
+    fn test_debug_hides_secret() {
+        let signer = HmacSha256Signer::new("X-Signature", "top-secret").unwrap();
+
+        assert!(!format!("{signer:?}").contains("top-secret"));
+    }
+}
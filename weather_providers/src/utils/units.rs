@@ -0,0 +1,44 @@
+//! # Unit Conversions
+//!
+//! `WeatherInfo` reports temperatures in degrees Fahrenheit and the relative humidity in whole
+//! percent, so providers reporting them otherwise convert them with these functions.
+
+/// Converts a temperature from degrees Celsius to degrees Fahrenheit.
+pub fn celsius_to_fahrenheit(celsius: f32) -> f32 {
+    celsius * 9.0 / 5.0 + 32.0
+}
+
+/// Converts a temperature from kelvins to degrees Fahrenheit.
+pub fn kelvin_to_fahrenheit(kelvin: f32) -> f32 {
+    celsius_to_fahrenheit(kelvin - 273.15)
+}
+
+/// Rounds a relative humidity in percent to a whole percent within 0–100.
+pub fn humidity_percent(humidity: f32) -> u8 {
+    humidity.round().clamp(0.0, 100.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_celsius_to_fahrenheit() {
+        assert_eq!(celsius_to_fahrenheit(0.0), 32.0);
+        assert_eq!(celsius_to_fahrenheit(100.0), 212.0);
+        assert_eq!(celsius_to_fahrenheit(-40.0), -40.0);
+    }
+
+    #[test]
+    fn test_kelvin_to_fahrenheit() {
+        assert!((kelvin_to_fahrenheit(273.15) - 32.0).abs() < 1e-4);
+        assert!((kelvin_to_fahrenheit(373.15) - 212.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_humidity_percent() {
+        assert_eq!(humidity_percent(77.6), 78);
+        assert_eq!(humidity_percent(100.4), 100);
+        assert_eq!(humidity_percent(-0.2), 0);
+    }
+}
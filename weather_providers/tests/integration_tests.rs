@@ -0,0 +1,189 @@
+//! Tests against the real provider APIs, enabled by the `integration` feature.
+//!
+//! Keys are read from the same environment variables as `cargo xtask fetch-fixtures`; a test
+//! whose key is missing passes without calling the API. See CONTRIBUTING.md.
+#![cfg(feature = "integration")]
+
+use ::weather_providers::{Provider, create_provider};
+
+/// Returns the environment variable, or `None` after noting that the test is skipped.
+fn key(var: &str) -> Option<String> {
+    let key = std::env::var(var).ok().filter(|key| !key.is_empty());
+    if key.is_none() {
+        eprintln!("skipped: {var} is not set");
+    }
+    key
+}
+
+async fn assert_weather(provider: Provider, key: Option<&str>) {
+    let weather = create_provider(provider.clone())
+        .get_weather(key, "London")
+        .await
+        .unwrap_or_else(|e| panic!("{provider}: {e}"));
+
+    assert!(!weather.city.is_empty(), "{provider}: {weather:?}");
+    assert!(!weather.date.is_empty(), "{provider}: {weather:?}");
+}
+
+#[tokio::test]
+async fn test_sunrise_sunset() {
+    assert_weather(Provider::SunriseSunset, None).await;
+}
+
+#[tokio::test]
+async fn test_meteo_swiss() {
+    // Only covers Switzerland
+    let weather = create_provider(Provider::MeteoSwiss)
+        .get_weather(None, "Zurich")
+        .await
+        .unwrap_or_else(|e| panic!("MeteoSwiss: {e}"));
+
+    assert!(!weather.city.is_empty(), "{weather:?}");
+    assert_eq!(weather.country, "CH");
+}
+
+#[tokio::test]
+async fn test_fmi() {
+    let weather = create_provider(Provider::Fmi)
+        .get_weather(None, "Helsinki")
+        .await
+        .unwrap_or_else(|e| panic!("FMI: {e}"));
+
+    assert_eq!(weather.country, "FI");
+    assert!(!weather.temperature.is_nan());
+}
+
+#[tokio::test]
+async fn test_eccc() {
+    // Both by climate station ID (Toronto City) and by place
+    for address in ["6158355", "Toronto"] {
+        let weather = create_provider(Provider::Eccc)
+            .get_weather(None, address)
+            .await
+            .unwrap_or_else(|e| panic!("ECCC ({address}): {e}"));
+
+        assert_eq!(weather.country, "CA");
+        assert!(weather.station_id.is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_bom() {
+    // Sydney - Observatory Hill
+    let weather = create_provider(Provider::Bom)
+        .get_weather(None, "IDN60901.94768")
+        .await
+        .unwrap_or_else(|e| panic!("BOM: {e}"));
+
+    assert_eq!(weather.country, "AU");
+    assert_eq!(weather.station_id.as_deref(), Some("94768"));
+}
+
+#[tokio::test]
+async fn test_open_meteo_air_quality() {
+    // Air quality only, without temperature
+    let weather = create_provider(Provider::OpenMeteoAq)
+        .get_weather(None, "Berlin")
+        .await
+        .unwrap_or_else(|e| panic!("OpenMeteoAirQuality: {e}"));
+
+    assert!(weather.pm25.is_some(), "{weather:?}");
+    assert!(weather.temperature.is_nan());
+}
+
+#[tokio::test]
+async fn test_aemet() {
+    let Some(key) = key("AEMET_API_KEY") else {
+        return;
+    };
+
+    // Addressed by the INE code of the municipality (Madrid)
+    let weather = create_provider(Provider::Aemet)
+        .get_weather(Some(&key), "28079")
+        .await
+        .unwrap_or_else(|e| panic!("AEMET: {e}"));
+
+    assert_eq!(weather.city, "Madrid");
+    assert_eq!(weather.country, "ES");
+}
+
+#[tokio::test]
+async fn test_dmi() {
+    let Some(key) = key("DMI_API_KEY") else {
+        return;
+    };
+
+    let weather = create_provider(Provider::Dmi)
+        .get_weather(Some(&key), "Copenhagen")
+        .await
+        .unwrap_or_else(|e| panic!("DMI: {e}"));
+
+    assert_eq!(weather.country, "DK");
+    assert!(weather.station_id.is_some());
+    assert!(!weather.temperature.is_nan());
+}
+
+#[tokio::test]
+async fn test_openaq() {
+    // The key is optional for the provider, but the v3 API rejects most requests without one
+    let Some(key) = key("OPENAQ_API_KEY") else {
+        return;
+    };
+
+    let weather = create_provider(Provider::OpenAq)
+        .get_weather(Some(&key), "London")
+        .await
+        .unwrap_or_else(|e| panic!("OpenAQ: {e}"));
+
+    assert!(weather.pm25.is_some(), "{weather:?}");
+    assert!(weather.station_id.is_some());
+}
+
+#[tokio::test]
+async fn test_open_weather() {
+    let Some(key) = key("OPENWEATHER_API_KEY") else {
+        return;
+    };
+
+    assert_weather(Provider::OpenWeather, Some(&key)).await;
+}
+
+#[tokio::test]
+async fn test_weather_api() {
+    let Some(key) = key("WEATHERAPI_API_KEY") else {
+        return;
+    };
+
+    assert_weather(Provider::WeatherApi, Some(&key)).await;
+}
+
+#[tokio::test]
+async fn test_historic_pirate_weather() {
+    let Some(key) = key("PIRATE_WEATHER_API_KEY") else {
+        return;
+    };
+
+    assert_weather(Provider::HistoricPirateWeather, Some(&key)).await;
+}
+
+#[tokio::test]
+async fn test_world_weather_online() {
+    let Some(key) = key("WWO_API_KEY") else {
+        return;
+    };
+
+    assert_weather(Provider::WorldWeatherOnline, Some(&key)).await;
+}
+
+#[tokio::test]
+async fn test_invalid_key_is_rejected() {
+    let error = create_provider(Provider::OpenWeather)
+        .get_weather(Some("invalid-key"), "London")
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(error, weather_providers::Error::InvalidApiKey(_)),
+        "{error}"
+    );
+}
@@ -1,3 +1,5 @@
+#![cfg(not(target_arch = "wasm32"))]
+
 use ::weather_providers::{Provider, create_provider};
 
 #[tokio::test]
@@ -8,7 +10,9 @@ async fn test_mock_provider_via_trait() {
     let provider = create_provider(Provider::Mock);
 
     let response = provider
-        .get_weather(None, "New York", Some("2024-01-01"))
+        .as_historical()
+        .expect("the mock provider reports past dates")
+        .get_history(None, "New York", "2024-01-01")
         .await;
 
     assert!(response.is_ok());
@@ -28,3 +32,24 @@ fn test_provider_enum_parsing() {
     assert_eq!(Provider::try_from("mock").unwrap(), Provider::Mock);
     assert!(Provider::try_from("invalid").is_err());
 }
+
+#[test]
+fn test_fixtures_match_models() {
+    // Fixtures are refreshed with `cargo xtask fetch-fixtures`; there may be none yet.
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for path in entries.map(|e| e.unwrap().path()) {
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let fixture = weather_providers::fixtures::find(name)
+            .unwrap_or_else(|| panic!("no response model for fixture '{name}'"));
+
+        fixture
+            .check(&std::fs::read_to_string(&path).unwrap())
+            .unwrap();
+    }
+}
@@ -0,0 +1,48 @@
+//! The offline switch is process-wide, so these tests live in their own test binary.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use ::std::time::{Duration, Instant};
+use ::weather_providers::{Error, Provider, create_provider, is_offline, set_offline};
+
+#[tokio::test]
+async fn test_offline_mode() {
+    set_offline(true);
+    assert!(is_offline());
+
+    for (provider, key) in [
+        (Provider::OpenWeather, "key"),
+        (Provider::WeatherApi, "key"),
+        (Provider::SunriseSunset, ""),
+        (Provider::Foreca, "client:secret"),
+    ] {
+        let weather_provider = create_provider(provider.clone());
+
+        let started = Instant::now();
+        let result = weather_provider.get_weather(Some(key), "London").await;
+
+        assert!(
+            matches!(result, Err(Error::OfflineMode(_))),
+            "{provider} should fail in offline mode"
+        );
+        assert!(started.elapsed() < Duration::from_millis(100));
+    }
+
+    let health = create_provider(Provider::GrpcMock).health_check(None).await;
+    assert!(matches!(health, Err(Error::OfflineMode(_))));
+
+    // Mocks keep working
+    let weather = create_provider(Provider::Mock)
+        .get_weather(None, "London")
+        .await
+        .unwrap();
+    assert_eq!(weather.city, "Mock City");
+    assert!(
+        create_provider(Provider::GrpcMock)
+            .get_weather(None, "London")
+            .await
+            .is_ok()
+    );
+
+    set_offline(false);
+}
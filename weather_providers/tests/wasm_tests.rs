@@ -0,0 +1,31 @@
+//! Smoke tests for the `wasm32-unknown-unknown` build, run in a headless browser with
+//! `wasm-pack test --headless --chrome weather_providers -- --no-default-features`.
+
+#![cfg(target_arch = "wasm32")]
+
+use ::wasm_bindgen_test::*;
+use ::weather_providers::{Provider, create_provider, normalize_date, parse_relative_date};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn test_mock_provider() {
+    let info = create_provider(Provider::Mock)
+        .as_historical()
+        .unwrap()
+        .get_history(None, "London", "2024-01-15")
+        .await
+        .unwrap();
+
+    assert_eq!(info.date, "2024-01-15");
+}
+
+#[wasm_bindgen_test]
+fn test_date_utilities() {
+    assert_eq!(normalize_date(Some("2024-01-15")), "2024-01-15");
+    assert_eq!(normalize_date(None::<&str>).len(), 10);
+
+    let yesterday = parse_relative_date("yesterday").unwrap();
+    let today = parse_relative_date("today").unwrap();
+    assert_eq!(today.signed_duration_since(yesterday).num_days(), 1);
+}
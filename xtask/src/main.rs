@@ -0,0 +1,379 @@
+//! # Developer Tasks
+//!
+//! Run with `cargo xtask <COMMAND>`.
+//!
+//! - `fetch-fixtures`: Calls the real provider APIs and saves the raw JSON responses into
+//!   `weather_providers/tests/fixtures/`. Keys are read from environment variables; providers
+//!   whose keys are missing are skipped.
+//! - `check-fixtures`: Deserializes every saved fixture through the current response models
+//!   and reports schema mismatches.
+//! - `test-integration`: Runs the workspace tests with the `integration` feature, which adds the
+//!   tests against the real provider APIs. Keys are read from the same environment variables.
+
+use ::clap::{Parser, Subcommand};
+use ::reqwest::Url;
+use ::std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+use ::weather_providers::{Error, Result, fixtures};
+
+/// The coordinates used for coordinate-based requests (London).
+const LAT: &str = "51.5072";
+const LON: &str = "-0.1276";
+
+#[derive(Parser)]
+#[command(name = "xtask", about = "Developer tasks for the weather workspace")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Fetch fresh fixture JSON from the real provider APIs.
+    FetchFixtures {
+        /// Fetch only the fixture with this name (e.g., "weather_api_current").
+        #[arg(long, value_name = "NAME")]
+        only: Option<String>,
+    },
+    /// Check that every fixture deserializes through the current response models.
+    CheckFixtures,
+    /// Run the tests, including those against the real provider APIs.
+    TestIntegration,
+}
+
+/// A request whose response is saved as a fixture.
+struct FixtureSource {
+    /// The fixture name, matching an entry of `weather_providers::fixtures::FIXTURES`.
+    name: &'static str,
+    /// The environment variables holding the required keys.
+    keys: &'static [&'static str],
+    /// Builds the request URL from the key values, in the order of `keys`.
+    url: fn(&[String]) -> Result<Url>,
+}
+
+const SOURCES: &[FixtureSource] = &[
+    FixtureSource {
+        name: "open_weather_geocoding",
+        keys: &["OPENWEATHER_API_KEY"],
+        url: |keys| {
+            url(
+                "https://api.openweathermap.org/geo/1.0/direct",
+                &[("appid", &keys[0]), ("q", "London"), ("limit", "1")],
+            )
+        },
+    },
+    FixtureSource {
+        name: "open_weather_day_summary",
+        keys: &["OPENWEATHER_API_KEY"],
+        url: |keys| {
+            url(
+                "https://api.openweathermap.org/data/3.0/onecall/day_summary",
+                &[
+                    ("appid", &keys[0]),
+                    ("lat", LAT),
+                    ("lon", LON),
+                    ("date", "2024-01-01"),
+                    ("units", "imperial"),
+                ],
+            )
+        },
+    },
+    FixtureSource {
+        name: "open_weather_onecall",
+        keys: &["OPENWEATHER_API_KEY"],
+        url: |keys| {
+            url(
+                "https://api.openweathermap.org/data/3.0/onecall",
+                &[
+                    ("appid", &keys[0]),
+                    ("lat", LAT),
+                    ("lon", LON),
+                    ("exclude", "minutely,hourly"),
+                    ("units", "imperial"),
+                ],
+            )
+        },
+    },
+    FixtureSource {
+        name: "weather_api_current",
+        keys: &["WEATHERAPI_API_KEY"],
+        url: |keys| {
+            url(
+                "https://api.weatherapi.com/v1/current.json",
+                &[("key", &keys[0]), ("q", "London"), ("aqi", "no")],
+            )
+        },
+    },
+    FixtureSource {
+        name: "ambient_weather_devices",
+        keys: &["AMBIENT_API_KEY", "AMBIENT_APPLICATION_KEY"],
+        url: |keys| {
+            url(
+                "https://api.ambientweather.net/v1/devices",
+                &[("apiKey", &keys[0]), ("applicationKey", &keys[1])],
+            )
+        },
+    },
+    FixtureSource {
+        name: "open_meteo_geocoding",
+        keys: &[],
+        url: |_| {
+            url(
+                "https://geocoding-api.open-meteo.com/v1/search",
+                &[("name", "London"), ("count", "1"), ("format", "json")],
+            )
+        },
+    },
+    FixtureSource {
+        name: "sunrise_sunset",
+        keys: &[],
+        url: |_| {
+            url(
+                "https://api.sunrise-sunset.org/json",
+                &[
+                    ("lat", LAT),
+                    ("lng", LON),
+                    ("date", "2024-06-21"),
+                    ("formatted", "0"),
+                ],
+            )
+        },
+    },
+    FixtureSource {
+        name: "world_weather_online_current",
+        keys: &["WWO_API_KEY"],
+        url: |keys| {
+            url(
+                "https://api.worldweatheronline.com/premium/v1/weather.ashx",
+                &[
+                    ("key", &keys[0]),
+                    ("q", "London"),
+                    ("format", "json"),
+                    ("fx", "no"),
+                    ("mca", "no"),
+                    ("includelocation", "yes"),
+                ],
+            )
+        },
+    },
+    FixtureSource {
+        name: "meteo_swiss_current",
+        keys: &[],
+        url: |_| {
+            url(
+                "https://data.geo.admin.ch/ch.meteoschweiz.messwerte-aktuell-10min/\
+                 ch.meteoschweiz.messwerte-aktuell-10min_en.json",
+                &[],
+            )
+        },
+    },
+    FixtureSource {
+        name: "open_meteo_air_quality",
+        keys: &[],
+        url: |_| {
+            url(
+                "https://air-quality-api.open-meteo.com/v1/air-quality",
+                &[
+                    ("latitude", LAT),
+                    ("longitude", LON),
+                    ("hourly", "pm2_5,european_aqi"),
+                    ("start_date", "2024-01-15"),
+                    ("end_date", "2024-01-15"),
+                    ("timezone", "auto"),
+                ],
+            )
+        },
+    },
+    // ECCC covers Canada only, so these use Toronto instead of London
+    FixtureSource {
+        name: "eccc_stations",
+        keys: &[],
+        url: |_| {
+            url(
+                "https://api.weather.gc.ca/collections/climate-stations/items",
+                &[
+                    ("bbox", "-79.9,43.2,-78.9,44.2"),
+                    ("HAS_HOURLY_DATA", "Y"),
+                    ("limit", "10"),
+                    ("f", "json"),
+                ],
+            )
+        },
+    },
+    FixtureSource {
+        name: "eccc_hourly",
+        keys: &[],
+        url: |_| {
+            url(
+                "https://api.weather.gc.ca/collections/climate-hourly/items",
+                &[
+                    ("CLIMATE_IDENTIFIER", "6158355"),
+                    ("sortby", "-LOCAL_DATE"),
+                    ("limit", "2"),
+                    ("f", "json"),
+                ],
+            )
+        },
+    },
+    // BOM covers Australia only: Sydney - Observatory Hill
+    FixtureSource {
+        name: "bom_observations",
+        keys: &[],
+        url: |_| {
+            url(
+                "https://www.bom.gov.au/fwo/IDN60901/IDN60901.94768.json",
+                &[],
+            )
+        },
+    },
+];
+
+#[tokio::main]
+async fn main() {
+    let result = match Cli::parse().command {
+        Command::FetchFixtures { only } => fetch_fixtures(only.as_deref()).await,
+        Command::CheckFixtures => check_fixtures(),
+        Command::TestIntegration => test_integration(),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../weather_providers/tests/fixtures")
+}
+
+fn url(base: &str, params: &[(&str, &str)]) -> Result<Url> {
+    Url::parse_with_params(base, params).map_err(|e| format!("Failed to build URL: {e}").into())
+}
+
+async fn fetch_fixtures(only: Option<&str>) -> Result<()> {
+    let sources = SOURCES
+        .iter()
+        .filter(|s| only.is_none_or(|name| s.name == name))
+        .collect::<Vec<_>>();
+
+    if sources.is_empty() {
+        Err(format!(
+            "Unknown fixture: '{}'. Available fixtures: {}",
+            only.unwrap_or_default(),
+            SOURCES
+                .iter()
+                .map(|s| s.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))?;
+    }
+
+    let dir = fixtures_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+
+    let mut failed = 0;
+    for source in sources {
+        let keys = source
+            .keys
+            .iter()
+            .map(|var| std::env::var(var).map_err(|_| *var))
+            .collect::<std::result::Result<Vec<_>, _>>();
+
+        let keys = match keys {
+            Ok(keys) => keys,
+            Err(var) => {
+                println!("SKIP  {} ({var} is not set)", source.name);
+                continue;
+            }
+        };
+
+        match fetch(source, &keys).await {
+            Ok(body) => {
+                let path = dir.join(format!("{}.json", source.name));
+                fs::write(&path, body).map_err(|e| format!("Failed to write {path:?}: {e}"))?;
+                println!("SAVED {}", source.name);
+            }
+            Err(e) => {
+                failed += 1;
+                println!("FAIL  {}: {e}", source.name);
+            }
+        }
+    }
+
+    if failed > 0 {
+        Err(format!("Failed to fetch {failed} fixture(s)."))?;
+    }
+
+    Ok(())
+}
+
+async fn fetch(source: &FixtureSource, keys: &[String]) -> Result<String> {
+    let response = reqwest::get((source.url)(keys)?)
+        .await?
+        .error_for_status()?;
+
+    Ok(response.text().await?)
+}
+
+fn check_fixtures() -> Result<()> {
+    let dir = fixtures_dir();
+    let mut paths = fs::read_dir(&dir)
+        .map_err(|e| {
+            format!("Failed to read {dir:?}: {e}. Run 'cargo xtask fetch-fixtures' first.")
+        })?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut mismatches = 0;
+    for path in &paths {
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+
+        let result = fixtures::find(name)
+            .ok_or_else(|| Error::from(format!("'{name}': no response model for this fixture")))
+            .and_then(|fixture| {
+                let json = fs::read_to_string(path)
+                    .map_err(|e| format!("Failed to read {path:?}: {e}"))?;
+                fixture.check(&json)
+            });
+
+        match result {
+            Ok(()) => println!("OK    {name}"),
+            Err(e) => {
+                mismatches += 1;
+                println!("FAIL  {e}");
+            }
+        }
+    }
+
+    if mismatches > 0 {
+        Err(format!(
+            "{mismatches} of {} fixture(s) do not match the models.",
+            paths.len()
+        ))?;
+    }
+
+    println!("{} fixture(s) checked.", paths.len());
+    Ok(())
+}
+
+fn test_integration() -> Result<()> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let status = process::Command::new(cargo)
+        .args(["test", "--workspace", "--features", "integration"])
+        .status()
+        .map_err(|e| format!("Failed to run cargo: {e}"))?;
+
+    if !status.success() {
+        Err(format!("Integration tests failed ({status})."))?;
+    }
+
+    Ok(())
+}